@@ -0,0 +1,211 @@
+//! Calling other agents over A2A (JSON-RPC over HTTP, paid with x402 when asked)
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::header;
+use serde_json::{json, Value};
+
+use super::types::{
+    AgentCard, JsonRpcRequest, JsonRpcResponse, Message, Task, TaskStatus,
+};
+use crate::models::A2aTaskState;
+use crate::wallet::WalletProvider;
+use crate::x402::X402PaymentInfo;
+
+/// Remote agents can take a while to finish a blocking message/send
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Result of a call that may have been paid for
+pub struct A2aCallResult<T> {
+    pub value: T,
+    pub payment: Option<X402PaymentInfo>,
+}
+
+/// A2A JSON-RPC client
+pub struct A2aClient {
+    http: reqwest::Client,
+    wallet_provider: Option<Arc<dyn WalletProvider>>,
+    bearer_token: Option<String>,
+}
+
+impl A2aClient {
+    pub fn new(http: reqwest::Client, wallet_provider: Option<Arc<dyn WalletProvider>>) -> Self {
+        Self {
+            http,
+            wallet_provider,
+            bearer_token: None,
+        }
+    }
+
+    /// Authenticate with the peer's API token instead of paying
+    pub fn with_bearer_token(mut self, token: Option<String>) -> Self {
+        self.bearer_token = token.filter(|t| !t.is_empty());
+        self
+    }
+
+    /// Fetch an agent card from its URL
+    pub async fn fetch_card(&self, card_url: &str) -> Result<AgentCard, String> {
+        let response = self
+            .http
+            .get(card_url)
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch agent card: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Agent card request returned HTTP {}", response.status()));
+        }
+        response
+            .json::<AgentCard>()
+            .await
+            .map_err(|e| format!("Invalid agent card: {}", e))
+    }
+
+    /// Turn whatever a peer advertises (agent card URL, site root or JSON-RPC URL)
+    /// into its JSON-RPC endpoint, along with the card when one was found.
+    pub async fn resolve_endpoint(&self, endpoint: &str) -> Result<(String, Option<AgentCard>), String> {
+        let endpoint = endpoint.trim().trim_end_matches('/');
+        let url = reqwest::Url::parse(endpoint).map_err(|e| format!("Invalid agent URL '{}': {}", endpoint, e))?;
+
+        if url.path().ends_with(".json") {
+            let card = self.fetch_card(endpoint).await?;
+            return Ok((card.url.clone(), Some(card)));
+        }
+
+        let origin = url.origin().ascii_serialization();
+        for path in ["/.well-known/agent-card.json", "/.well-known/agent.json"] {
+            if let Ok(card) = self.fetch_card(&format!("{}{}", origin, path)).await {
+                return Ok((card.url.clone(), Some(card)));
+            }
+        }
+
+        // No card: assume the URL is the JSON-RPC endpoint itself
+        Ok((endpoint.to_string(), None))
+    }
+
+    /// Send a text message (`message/send`). With `blocking`, the peer answers when the task is done.
+    pub async fn send_message(
+        &self,
+        rpc_url: &str,
+        text: &str,
+        context_id: Option<String>,
+        blocking: bool,
+    ) -> Result<A2aCallResult<Task>, String> {
+        let params = json!({
+            "message": Message::user_text(text, context_id),
+            "configuration": { "blocking": blocking },
+        });
+        let result = self.call(rpc_url, "message/send", params).await?;
+        Ok(A2aCallResult {
+            value: task_from_send_result(result.value)?,
+            payment: result.payment,
+        })
+    }
+
+    /// Poll a task (`tasks/get`)
+    pub async fn get_task(&self, rpc_url: &str, task_id: &str) -> Result<Task, String> {
+        let result = self.call(rpc_url, "tasks/get", json!({ "id": task_id })).await?;
+        serde_json::from_value(result.value).map_err(|e| format!("Invalid task in response: {}", e))
+    }
+
+    /// Cancel a task (`tasks/cancel`)
+    pub async fn cancel_task(&self, rpc_url: &str, task_id: &str) -> Result<Task, String> {
+        let result = self.call(rpc_url, "tasks/cancel", json!({ "id": task_id })).await?;
+        serde_json::from_value(result.value).map_err(|e| format!("Invalid task in response: {}", e))
+    }
+
+    /// Make one JSON-RPC call, answering a 402 challenge with a signed x402 payment
+    async fn call(&self, rpc_url: &str, method: &str, params: Value) -> Result<A2aCallResult<Value>, String> {
+        let request = JsonRpcRequest::new(uuid::Uuid::new_v4().to_string(), method, params);
+        let build = || {
+            let mut builder = self
+                .http
+                .post(rpc_url)
+                .timeout(REQUEST_TIMEOUT)
+                .header(header::CONTENT_TYPE, "application/json")
+                .json(&request);
+            if let Some(ref token) = self.bearer_token {
+                builder = builder.bearer_auth(token);
+            }
+            builder
+        };
+
+        let response = build()
+            .send()
+            .await
+            .map_err(|e| format!("A2A request to {} failed: {}", rpc_url, e))?;
+
+        let (response, payment) = if response.status().as_u16() == 402 {
+            let wallet_provider = self
+                .wallet_provider
+                .as_ref()
+                .ok_or("Peer requires x402 payment but no wallet is configured")?;
+            log::info!("[A2A] {} requires payment for {}", rpc_url, method);
+            let paid = crate::x402::retry_with_x402_payment(response, wallet_provider, build).await?;
+            (paid.response, paid.payment)
+        } else {
+            (response, None)
+        };
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(format!("A2A {} returned HTTP {}: {}", method, status, truncate(&body)));
+        }
+
+        let rpc: JsonRpcResponse = serde_json::from_str(&body)
+            .map_err(|e| format!("Invalid JSON-RPC response: {} ({})", e, truncate(&body)))?;
+        if let Some(error) = rpc.error {
+            return Err(format!("A2A {} error {}: {}", method, error.code, error.message));
+        }
+        Ok(A2aCallResult {
+            value: rpc.result.unwrap_or(Value::Null),
+            payment,
+        })
+    }
+}
+
+/// `message/send` answers with a Task, or with a bare Message for instant replies
+fn task_from_send_result(value: Value) -> Result<Task, String> {
+    if value.get("kind").and_then(|k| k.as_str()) == Some("message") {
+        let message: Message = serde_json::from_value(value).map_err(|e| format!("Invalid message in response: {}", e))?;
+        return Ok(Task {
+            kind: "task".to_string(),
+            id: message.task_id.clone().unwrap_or_else(|| message.message_id.clone()),
+            context_id: message.context_id.clone().unwrap_or_default(),
+            status: TaskStatus {
+                state: A2aTaskState::Completed,
+                message: Some(message),
+                timestamp: None,
+            },
+            artifacts: Vec::new(),
+        });
+    }
+    serde_json::from_value(value).map_err(|e| format!("Invalid task in response: {}", e))
+}
+
+fn truncate(text: &str) -> String {
+    text.chars().take(500).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_reply_becomes_completed_task() {
+        let task = task_from_send_result(json!({
+            "kind": "message",
+            "role": "agent",
+            "messageId": "m-1",
+            "contextId": "ctx-1",
+            "parts": [{"kind": "text", "text": "pong"}]
+        }))
+        .unwrap();
+        assert_eq!(task.id, "m-1");
+        assert_eq!(task.context_id, "ctx-1");
+        assert_eq!(task.status.state, A2aTaskState::Completed);
+        assert_eq!(task.output_text(), "pong");
+    }
+}
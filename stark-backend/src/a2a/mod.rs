@@ -0,0 +1,19 @@
+//! Agent-to-agent (A2A) protocol
+//!
+//! Serving side: an external channel with A2A enabled publishes an agent card at
+//! `/.well-known/agent-card.json` and answers JSON-RPC at `POST /api/a2a`
+//! (`message/send`, `message/stream`, `tasks/get`, `tasks/cancel`). Each task runs
+//! through the dispatcher in a per-context session on that channel. Peers holding
+//! the channel's API token get its normal access; everyone else runs in safe mode
+//! and, when a price is set, pays per message with x402.
+//!
+//! Client side: the `a2a_agent` tool finds peers through EIP-8004 discovery (or a
+//! URL), pays their 402 challenges and tracks the tasks it delegated.
+//!
+//! Both directions are recorded in the `a2a_tasks` table.
+
+pub mod client;
+pub mod server;
+pub mod types;
+
+pub use client::A2aClient;
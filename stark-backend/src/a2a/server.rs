@@ -0,0 +1,348 @@
+//! Serving A2A tasks through an external channel
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+
+use super::types::{AgentCapabilities, AgentCard, AgentExtension, AgentSkill, Artifact, Message, Part, Task, TaskStatus, PROTOCOL_VERSION};
+use crate::channels::types::NormalizedMessage;
+use crate::channels::MessageDispatcher;
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::models::{A2aDirection, A2aTask, A2aTaskState};
+use crate::x402::{PaymentExtra, PaymentRequirements, VerifiedPayment, USDC_ADDRESS};
+
+/// A2A is served through external (gateway) channels
+pub const CHANNEL_TYPE: &str = "external_channel";
+
+/// x402 extension URI advertised in the agent card when messages are paid
+const X402_EXTENSION_URI: &str = "https://github.com/google-a2a/a2a-x402/v0.1";
+
+/// Artifact ID used for the agent's reply
+const RESPONSE_ARTIFACT_ID: &str = "response";
+
+/// How the sender of an A2A request was let in
+#[derive(Debug, Clone)]
+pub enum Caller {
+    /// Presented the channel's API token
+    Token,
+    /// Paid for the message with a verified x402 authorization
+    Paid(Box<VerifiedPayment>),
+    /// Free public access
+    Public,
+}
+
+impl Caller {
+    /// Stored on the task; a context can only be continued by the same caller
+    pub fn label(&self) -> String {
+        match self {
+            Caller::Token => "token".to_string(),
+            Caller::Paid(payment) => payment.payer.to_lowercase(),
+            Caller::Public => "public".to_string(),
+        }
+    }
+
+    /// Only token holders get the channel's own safe mode setting
+    pub fn safe_mode(&self, channel_safe_mode: bool) -> bool {
+        match self {
+            Caller::Token => channel_safe_mode,
+            Caller::Paid(_) | Caller::Public => true,
+        }
+    }
+}
+
+/// Parse the per-message price setting ("0.01") into micro-USDC. Empty means free.
+pub fn parse_price_usdc(price: &str) -> Result<Option<u64>, String> {
+    let price = price.trim();
+    if price.is_empty() {
+        return Ok(None);
+    }
+    let (whole, frac) = price.split_once('.').unwrap_or((price, ""));
+    if frac.len() > 6 || !(whole.chars().chain(frac.chars()).all(|c| c.is_ascii_digit())) || (whole.is_empty() && frac.is_empty()) {
+        return Err(format!("Invalid USDC price '{}'", price));
+    }
+    let whole: u64 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| format!("Invalid USDC price '{}'", price))? };
+    let frac: u64 = format!("{:0<6}", frac).parse().map_err(|_| format!("Invalid USDC price '{}'", price))?;
+    let amount = whole
+        .checked_mul(1_000_000)
+        .and_then(|w| w.checked_add(frac))
+        .ok_or_else(|| format!("USDC price '{}' is too large", price))?;
+    Ok(if amount == 0 { None } else { Some(amount) })
+}
+
+/// What a peer must pay per message (USDC on Base, EIP-3009 "exact" scheme)
+pub fn payment_requirements(pay_to: &str, amount: u64, resource: &str) -> PaymentRequirements {
+    PaymentRequirements {
+        scheme: "exact".to_string(),
+        network: "base".to_string(),
+        max_amount_required: amount.to_string(),
+        pay_to_address: pay_to.to_string(),
+        asset: USDC_ADDRESS.to_string(),
+        max_timeout_seconds: 300,
+        resource: Some(resource.to_string()),
+        description: Some("A2A message".to_string()),
+        extra: Some(PaymentExtra {
+            token: Some("USDC".to_string()),
+            address: Some(USDC_ADDRESS.to_string()),
+            decimals: Some(6),
+            name: Some("USD Coin".to_string()),
+            version: Some("2".to_string()),
+            facilitator_signer: None,
+        }),
+    }
+}
+
+/// Body of a 402 response (also sent base64-encoded in the `payment-required` header)
+pub fn payment_required_body(requirements: &PaymentRequirements, error: &str) -> Value {
+    json!({
+        "x402Version": 1,
+        "error": error,
+        "accepts": [{
+            "scheme": requirements.scheme,
+            "network": requirements.network,
+            "maxAmountRequired": requirements.max_amount_required,
+            "payTo": requirements.pay_to_address,
+            "asset": requirements.asset,
+            "maxTimeoutSeconds": requirements.max_timeout_seconds,
+            "resource": requirements.resource,
+            "description": requirements.description,
+            "mimeType": "application/json",
+            "extra": requirements.extra.as_ref().map(|e| json!({
+                "token": e.token,
+                "address": e.address,
+                "decimals": e.decimals,
+                "name": e.name,
+                "version": e.version,
+            })),
+        }],
+    })
+}
+
+/// Build our agent card. `rpc_url` is the public /api/a2a URL the peer reached us on.
+pub fn build_agent_card(db: &Database, rpc_url: &str, price: Option<u64>) -> AgentCard {
+    let identity = db.get_agent_identity_full();
+    let name = identity
+        .as_ref()
+        .and_then(|i| i.name.clone())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| "StarkBot".to_string());
+    let description = identity
+        .as_ref()
+        .and_then(|i| i.description.clone())
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| "Autonomous crypto agent".to_string());
+
+    let extensions = price
+        .map(|amount| {
+            vec![AgentExtension {
+                uri: X402_EXTENSION_URI.to_string(),
+                description: "Messages from callers without an API token are paid per message with x402".to_string(),
+                required: true,
+                params: Some(json!({
+                    "network": "base",
+                    "asset": USDC_ADDRESS,
+                    "maxAmountRequired": amount.to_string(),
+                })),
+            }]
+        })
+        .unwrap_or_default();
+
+    AgentCard {
+        protocol_version: PROTOCOL_VERSION.to_string(),
+        name,
+        description,
+        url: rpc_url.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        capabilities: AgentCapabilities {
+            streaming: true,
+            push_notifications: false,
+            extensions,
+        },
+        default_input_modes: vec!["text/plain".to_string()],
+        default_output_modes: vec!["text/plain".to_string()],
+        skills: vec![AgentSkill {
+            id: "chat".to_string(),
+            name: "Chat".to_string(),
+            description: "Free-form requests handled by the agent with its available tools".to_string(),
+            tags: vec!["chat".to_string(), "crypto".to_string()],
+        }],
+        security_schemes: Some(json!({
+            "bearer": { "type": "http", "scheme": "bearer" }
+        })),
+    }
+}
+
+/// Session key suffix for an A2A conversation
+pub fn chat_id_for_context(context_id: &str) -> String {
+    format!("a2a:{}", context_id)
+}
+
+/// Wire view of a stored inbound task
+pub fn task_from_row(row: &A2aTask) -> Task {
+    let state = row.state_enum();
+    let artifacts = match row.result.as_deref() {
+        Some(result) if !result.is_empty() => vec![Artifact {
+            artifact_id: RESPONSE_ARTIFACT_ID.to_string(),
+            parts: vec![Part::text(result)],
+        }],
+        _ => Vec::new(),
+    };
+    let message = row
+        .error
+        .as_deref()
+        .map(|e| Message::agent_text(e, &row.context_id, &row.task_id));
+
+    Task {
+        kind: "task".to_string(),
+        id: row.task_id.clone(),
+        context_id: row.context_id.clone(),
+        status: TaskStatus {
+            state,
+            message,
+            timestamp: Some(row.updated_at.clone()),
+        },
+        artifacts,
+    }
+}
+
+/// One inbound task to run through the dispatcher
+pub struct InboundRun {
+    pub channel_id: i64,
+    pub session_id: i64,
+    pub task_id: String,
+    pub context_id: String,
+    pub caller: String,
+    pub text: String,
+    pub safe_mode: bool,
+}
+
+/// Run a task to completion and store its outcome. Text the agent sends with
+/// say_to_user is forwarded to `output` as it arrives (used by message/stream).
+/// Returns the task's final state, which stays canceled if it was canceled meanwhile.
+pub async fn run_inbound_task(
+    db: Arc<Database>,
+    dispatcher: Arc<MessageDispatcher>,
+    broadcaster: Arc<EventBroadcaster>,
+    run: InboundRun,
+    output: Option<mpsc::UnboundedSender<String>>,
+) -> A2aTaskState {
+    let chat_id = chat_id_for_context(&run.context_id);
+    let (client_id, mut rx) = broadcaster.subscribe();
+
+    let collected: Arc<tokio::sync::Mutex<Vec<String>>> = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+    let collected_clone = collected.clone();
+    let cid = run.channel_id;
+    let listen_chat_id = chat_id.clone();
+    let listener = tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            if event.event != "tool.result" {
+                continue;
+            }
+            let ev_channel = event.data.get("channel_id").and_then(|v| v.as_i64());
+            let ev_chat = event.data.get("chat_id").and_then(|v| v.as_str());
+            let tool_name = event.data.get("tool_name").and_then(|v| v.as_str()).unwrap_or("");
+            let success = event.data.get("success").and_then(|v| v.as_bool()).unwrap_or(false);
+            let content = event.data.get("content").and_then(|v| v.as_str()).unwrap_or("");
+
+            if ev_channel == Some(cid)
+                && ev_chat.is_none_or(|c| c == listen_chat_id)
+                && success
+                && !content.is_empty()
+                && (tool_name == "say_to_user" || tool_name == "task_fully_completed")
+            {
+                collected_clone.lock().await.push(content.to_string());
+                if let Some(ref output) = output {
+                    let _ = output.send(content.to_string());
+                }
+            }
+        }
+    });
+
+    let normalized = NormalizedMessage {
+        channel_id: run.channel_id,
+        channel_type: CHANNEL_TYPE.to_string(),
+        chat_id,
+        chat_name: Some(format!("A2A {}", run.context_id)),
+        user_id: run.caller.clone(),
+        user_name: format!("a2a:{}", run.caller),
+        text: run.text,
+        message_id: Some(run.task_id.clone()),
+        session_mode: None,
+        selected_network: None,
+        force_safe_mode: run.safe_mode,
+        safe_mode_fallback: false,
+        endpoint_override: None,
+        target_session_id: Some(run.session_id),
+        attachments: Vec::new(),
+        tool_profile: None,
+        thinking_level: None,
+        agent_subtype: None,
+        max_tool_iterations: None,
+        max_tx_value_wei: None,
+    };
+
+    let result = dispatcher.dispatch(normalized).await;
+
+    broadcaster.unsubscribe(&client_id);
+    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+    listener.abort();
+
+    let said = collected.lock().await;
+    let response = if !said.is_empty() { said.join("\n\n") } else { result.response.clone() };
+
+    let update = match result.error {
+        Some(ref error) => {
+            log::error!("[A2A] Task {} failed: {}", run.task_id, error);
+            db.update_a2a_task_state(A2aDirection::Inbound, &run.task_id, A2aTaskState::Failed, None, Some(error))
+        }
+        None => db.update_a2a_task_state(
+            A2aDirection::Inbound,
+            &run.task_id,
+            A2aTaskState::Completed,
+            Some(&response),
+            None,
+        ),
+    };
+    if let Err(e) = update {
+        log::error!("[A2A] Failed to store outcome of task {}: {}", run.task_id, e);
+    }
+
+    db.get_a2a_task(A2aDirection::Inbound, &run.task_id)
+        .ok()
+        .flatten()
+        .map(|t| t.state_enum())
+        .unwrap_or(A2aTaskState::Unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_price_usdc() {
+        assert_eq!(parse_price_usdc("").unwrap(), None);
+        assert_eq!(parse_price_usdc("0").unwrap(), None);
+        assert_eq!(parse_price_usdc("0.01").unwrap(), Some(10_000));
+        assert_eq!(parse_price_usdc(" 2 ").unwrap(), Some(2_000_000));
+        assert_eq!(parse_price_usdc(".5").unwrap(), Some(500_000));
+        assert_eq!(parse_price_usdc("1.000001").unwrap(), Some(1_000_001));
+        assert!(parse_price_usdc("0.0000001").is_err());
+        assert!(parse_price_usdc("1e3").is_err());
+        assert!(parse_price_usdc("-1").is_err());
+        assert!(parse_price_usdc(".").is_err());
+    }
+
+    #[test]
+    fn test_caller_access() {
+        assert!(!Caller::Token.safe_mode(false));
+        assert!(Caller::Public.safe_mode(false));
+        let paid = Caller::Paid(Box::new(VerifiedPayment::unsettled(
+            "0xF39Fd6e51aad88F6F4ce6aB8827279cffFb92266",
+            "10000",
+            "0x01",
+        )));
+        assert!(paid.safe_mode(false));
+        assert_eq!(paid.label(), "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+    }
+}
@@ -0,0 +1,339 @@
+//! A2A wire types (JSON-RPC 2.0 envelope, agent card, tasks and messages)
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::A2aTaskState;
+
+/// A2A protocol version we implement
+pub const PROTOCOL_VERSION: &str = "0.3.0";
+
+// JSON-RPC / A2A error codes
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+pub const TASK_NOT_FOUND: i64 = -32001;
+pub const TASK_NOT_CANCELABLE: i64 = -32002;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcRequest {
+    pub jsonrpc: String,
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+impl JsonRpcRequest {
+    pub fn new(id: impl Into<Value>, method: &str, params: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: id.into(),
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcResponse {
+    pub jsonrpc: String,
+    #[serde(default)]
+    pub id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+impl JsonRpcResponse {
+    pub fn result(id: Value, result: impl Serialize) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(serde_json::to_value(result).unwrap_or(Value::Null)),
+            error: None,
+        }
+    }
+
+    pub fn error(id: Value, code: i64, message: impl Into<String>) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(JsonRpcError {
+                code,
+                message: message.into(),
+                data: None,
+            }),
+        }
+    }
+}
+
+/// One piece of message/artifact content. Only text is produced; other kinds are
+/// accepted and carried through as raw JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Part {
+    Text {
+        /// "text" (older peers send `type` instead of `kind`)
+        #[serde(alias = "type")]
+        kind: String,
+        text: String,
+    },
+    Other(Value),
+}
+
+impl Part {
+    pub fn text(text: impl Into<String>) -> Self {
+        Part::Text {
+            kind: "text".to_string(),
+            text: text.into(),
+        }
+    }
+}
+
+/// Join the text parts of a message or artifact
+pub fn parts_text(parts: &[Part]) -> String {
+    parts
+        .iter()
+        .filter_map(|p| match p {
+            Part::Text { kind, text } if kind == "text" => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    #[serde(default = "message_kind")]
+    pub kind: String,
+    /// "user" or "agent"
+    pub role: String,
+    pub parts: Vec<Part>,
+    #[serde(default)]
+    pub message_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+}
+
+fn message_kind() -> String {
+    "message".to_string()
+}
+
+impl Message {
+    pub fn user_text(text: &str, context_id: Option<String>) -> Self {
+        Self {
+            kind: message_kind(),
+            role: "user".to_string(),
+            parts: vec![Part::text(text)],
+            message_id: uuid::Uuid::new_v4().to_string(),
+            context_id,
+            task_id: None,
+        }
+    }
+
+    pub fn agent_text(text: &str, context_id: &str, task_id: &str) -> Self {
+        Self {
+            kind: message_kind(),
+            role: "agent".to_string(),
+            parts: vec![Part::text(text)],
+            message_id: uuid::Uuid::new_v4().to_string(),
+            context_id: Some(context_id.to_string()),
+            task_id: Some(task_id.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskStatus {
+    pub state: A2aTaskState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<Message>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
+    pub artifact_id: String,
+    pub parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    #[serde(default = "task_kind")]
+    pub kind: String,
+    pub id: String,
+    #[serde(default)]
+    pub context_id: String,
+    pub status: TaskStatus,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<Artifact>,
+}
+
+fn task_kind() -> String {
+    "task".to_string()
+}
+
+impl Task {
+    /// Text the agent produced: artifacts first, then the status message
+    pub fn output_text(&self) -> String {
+        let from_artifacts = self
+            .artifacts
+            .iter()
+            .map(|a| parts_text(&a.parts))
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        if !from_artifacts.is_empty() {
+            return from_artifacts;
+        }
+        self.status
+            .message
+            .as_ref()
+            .map(|m| parts_text(&m.parts))
+            .unwrap_or_default()
+    }
+}
+
+/// Streaming update: the task's state changed (`final` marks the last event)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskStatusUpdateEvent {
+    pub kind: String,
+    pub task_id: String,
+    pub context_id: String,
+    pub status: TaskStatus,
+    #[serde(rename = "final")]
+    pub is_final: bool,
+}
+
+/// Streaming update: more output for the task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskArtifactUpdateEvent {
+    pub kind: String,
+    pub task_id: String,
+    pub context_id: String,
+    pub artifact: Artifact,
+    pub append: bool,
+}
+
+/// Params of `message/send` and `message/stream`
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageSendParams {
+    pub message: Message,
+    #[serde(default)]
+    pub configuration: Option<MessageSendConfiguration>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MessageSendConfiguration {
+    /// Wait for the task to finish before answering (default true)
+    #[serde(default)]
+    pub blocking: Option<bool>,
+}
+
+/// Params of `tasks/get` and `tasks/cancel`
+#[derive(Debug, Clone, Deserialize)]
+pub struct TaskIdParams {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSkill {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentCapabilities {
+    #[serde(default)]
+    pub streaming: bool,
+    #[serde(default)]
+    pub push_notifications: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<AgentExtension>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentExtension {
+    pub uri: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+/// Self-description served at /.well-known/agent-card.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentCard {
+    #[serde(default)]
+    pub protocol_version: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// JSON-RPC endpoint
+    pub url: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub capabilities: AgentCapabilities,
+    #[serde(default)]
+    pub default_input_modes: Vec<String>,
+    #[serde(default)]
+    pub default_output_modes: Vec<String>,
+    #[serde(default)]
+    pub skills: Vec<AgentSkill>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security_schemes: Option<Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_wire_format() {
+        let task: Task = serde_json::from_value(serde_json::json!({
+            "kind": "task",
+            "id": "t-1",
+            "contextId": "ctx-1",
+            "status": {"state": "input-required", "timestamp": "2026-01-01T00:00:00Z"},
+            "artifacts": [{"artifactId": "a", "parts": [{"type": "text", "text": "hello"}, {"kind": "file", "file": {}}]}]
+        }))
+        .unwrap();
+        assert_eq!(task.status.state, A2aTaskState::InputRequired);
+        assert_eq!(task.output_text(), "hello");
+
+        let json = serde_json::to_value(&task).unwrap();
+        assert_eq!(json["contextId"], "ctx-1");
+        assert_eq!(json["status"]["state"], "input-required");
+    }
+}
@@ -30,6 +30,10 @@ impl ModelArchetype for ClaudeArchetype {
         true
     }
 
+    fn supports_vision(&self, _endpoint: &str) -> bool {
+        true
+    }
+
     fn default_model(&self) -> &'static str {
         "claude-sonnet-4-20250514"
     }
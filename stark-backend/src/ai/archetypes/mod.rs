@@ -110,6 +110,12 @@ pub trait ModelArchetype: Send + Sync {
         false
     }
 
+    /// Whether the model served at `endpoint` accepts images as multimodal content blocks.
+    /// Default: false (attachments are described in text only).
+    fn supports_vision(&self, _endpoint: &str) -> bool {
+        false
+    }
+
     /// Format the follow-up message after a tool execution
     fn format_tool_followup(&self, tool_name: &str, tool_result: &str, success: bool) -> String;
 }
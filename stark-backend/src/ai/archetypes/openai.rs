@@ -29,6 +29,12 @@ impl ModelArchetype for OpenAIArchetype {
         true
     }
 
+    /// Only the OpenAI API itself, where the client defaults to gpt-4o. Relays and other
+    /// OpenAI-compatible servers pick their own model, which may be text-only.
+    fn supports_vision(&self, endpoint: &str) -> bool {
+        endpoint.contains("api.openai.com")
+    }
+
     fn default_model(&self) -> &'static str {
         // Relay endpoints handle model selection server-side.
         // For direct OpenAI API, the client constructor falls back to "gpt-4o".
@@ -50,3 +56,35 @@ impl ModelArchetype for OpenAIArchetype {
         String::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::AiClient;
+    use crate::models::AgentSettings;
+
+    fn settings(endpoint: &str, supports_vision: Option<bool>) -> AgentSettings {
+        AgentSettings {
+            endpoint: endpoint.to_string(),
+            model_archetype: "openai".to_string(),
+            supports_vision,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_vision_only_for_openai_api() {
+        let archetype = OpenAIArchetype::new();
+        assert!(archetype.supports_vision("https://api.openai.com/v1/chat/completions"));
+        assert!(!archetype.supports_vision("https://openai-gpt5-mini.defirelay.com/api/v1/chat/completions"));
+        assert!(!archetype.supports_vision("http://localhost:8080/v1/chat/completions"));
+    }
+
+    #[test]
+    fn test_vision_setting_overrides_endpoint_default() {
+        let relay = "https://openai-gpt5-mini.defirelay.com/api/v1/chat/completions";
+        assert!(!AiClient::supports_vision(&settings(relay, None)));
+        assert!(AiClient::supports_vision(&settings(relay, Some(true))));
+        assert!(!AiClient::supports_vision(&settings("https://api.openai.com/v1/chat/completions", Some(false))));
+    }
+}
@@ -366,12 +366,22 @@ impl ClaudeClient {
             })
             .collect();
 
-        // Convert regular messages to typed messages
+        // Convert regular messages to typed messages (images become image blocks before the text)
         let mut api_messages: Vec<TypedClaudeMessage> = filtered_messages
             .into_iter()
-            .map(|m| TypedClaudeMessage {
-                role: m.role.to_string(),
-                content: ClaudeMessageContent::Text(m.content),
+            .map(|m| {
+                let content = if m.images.is_empty() {
+                    ClaudeMessageContent::Text(m.content)
+                } else {
+                    let mut blocks: Vec<ClaudeContentBlock> =
+                        m.images.iter().map(ClaudeContentBlock::image).collect();
+                    blocks.push(ClaudeContentBlock::text(m.content));
+                    ClaudeMessageContent::Blocks(blocks)
+                };
+                TypedClaudeMessage {
+                    role: m.role.to_string(),
+                    content,
+                }
             })
             .collect();
 
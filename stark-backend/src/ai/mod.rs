@@ -4,12 +4,14 @@ pub mod llama;
 pub mod multi_agent;
 pub mod openai;
 pub mod streaming;
+pub mod tool_retention;
 pub mod types;
 
 pub use claude::ClaudeClient;
 pub use llama::{LlamaClient, LlamaMessage};
 pub use openai::OpenAIClient;
 pub use archetypes::{ArchetypeId, ArchetypeRegistry, ModelArchetype};
+pub use tool_retention::{RetentionReport, ToolHistoryRetention, ToolLedger};
 pub use types::{
     AiError, AiResponse, ClaudeMessage as TypedClaudeMessage, ThinkingLevel, ToolCall,
     ToolHistoryEntry, ToolResponse,
@@ -46,6 +48,25 @@ impl ToString for MessageRole {
 pub struct Message {
     pub role: MessageRole,
     pub content: String,
+    /// Images sent alongside the text to vision-capable models
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<MessageImage>,
+}
+
+/// An inline image for multimodal input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageImage {
+    /// MIME type, e.g. "image/png"
+    pub media_type: String,
+    /// Base64-encoded image bytes
+    pub data: String,
+}
+
+impl MessageImage {
+    /// Data URL form used by OpenAI-compatible APIs
+    pub fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.media_type, self.data)
+    }
 }
 
 /// A single iteration's INPUT (what was sent to the AI) and OUTPUT (what came back).
@@ -227,6 +248,19 @@ impl AiClient {
         ArchetypeId::from_str(&settings.model_archetype).unwrap_or(ArchetypeId::Kimi)
     }
 
+    /// Whether images may be sent to the endpoint in `settings`: the settings' own override
+    /// wins, then the matching ai_endpoints.ron preset, then the archetype's per-endpoint default
+    pub fn supports_vision(settings: &AgentSettings) -> bool {
+        if let Some(enabled) = settings.supports_vision.or_else(|| {
+            crate::ai_endpoint_config::find_ai_endpoint_by_url(&settings.endpoint).and_then(|p| p.supports_vision)
+        }) {
+            return enabled;
+        }
+        ArchetypeRegistry::new()
+            .get(Self::infer_archetype(settings))
+            .is_some_and(|a| a.supports_vision(&settings.endpoint))
+    }
+
     /// Generate text using the configured provider
     pub async fn generate_text(&self, messages: Vec<Message>) -> Result<String, String> {
         match self {
@@ -17,6 +17,7 @@
 
 pub mod orchestrator;
 pub mod subagent_manager;
+pub mod subtype_config;
 pub mod tools;
 pub mod types;
 
@@ -257,6 +257,12 @@ impl Orchestrator {
             prompt.push_str(&Self::code_engineer_guidelines());
         }
 
+        // Prompt fragment from the subtype's definition (config/agent_subtypes/)
+        if let Some(fragment) = self.context.subtype.definition().and_then(|d| d.prompt.as_deref()) {
+            prompt.push_str("\n\n");
+            prompt.push_str(fragment.trim());
+        }
+
         prompt.push_str("\n\n---\n\n");
         prompt.push_str(&self.format_context_summary());
 
@@ -5,17 +5,21 @@
 //! - Database persistence for sub-agent state
 //! - Isolated session creation for sub-agents
 //! - Real-time event broadcasting for sub-agent lifecycle
+//! - Fan-out groups with per-child caps, sibling cancellation and fan-in waiting
 
-use crate::ai::multi_agent::types::{SubAgentConfig, SubAgentContext, SubAgentStatus};
+use crate::ai::multi_agent::types::{
+    SubAgentConfig, SubAgentContext, SubAgentStatus, DEFAULT_SUBAGENT_MAX_ITERATIONS,
+};
 use crate::ai::{AiClient, Message, MessageRole, ToolHistoryEntry};
 use crate::db::Database;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
 use crate::models::{AgentSettings, SessionScope};
 use crate::tools::{ToolContext, ToolDefinition, ToolRegistry};
+use crate::x402::payment_limits::SpendBudget;
 use dashmap::DashMap;
 use serde_json::json;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::{oneshot, Semaphore};
 use tokio::time::{timeout, Duration};
@@ -23,21 +27,52 @@ use tokio::time::{timeout, Duration};
 /// Counter for generating unique sub-agent IDs
 static SUBAGENT_COUNTER: AtomicU64 = AtomicU64::new(1);
 
+/// Counter for generating unique fan-out group IDs
+static GROUP_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+/// Columns read back into a SubAgentContext (see `map_subagent_row`)
+const SUBAGENT_COLUMNS: &str = "subagent_id, parent_session_id, parent_channel_id, session_id,
+    label, task, status, model_override, thinking_level, timeout_secs,
+    context, result, error, started_at, completed_at,
+    group_id, cancel_siblings_on_failure, max_iterations, max_cost_usdc, iterations, cost_usdc, read_only";
+
 /// Handle for a running sub-agent task
 pub struct SubAgentHandle {
-    /// Cancel signal sender
-    cancel_tx: Option<oneshot::Sender<()>>,
+    /// Cancel signal sender (carries the reason)
+    cancel_tx: Option<oneshot::Sender<String>>,
 }
 
 impl SubAgentHandle {
     /// Cancel the sub-agent execution
-    pub fn cancel(mut self) {
+    pub fn cancel(self) {
+        self.cancel_with_reason("Cancelled by user or system".to_string());
+    }
+
+    /// Cancel the sub-agent execution, recording why
+    pub fn cancel_with_reason(mut self, reason: String) {
         if let Some(tx) = self.cancel_tx.take() {
-            let _ = tx.send(());
+            let _ = tx.send(reason);
         }
     }
 }
 
+/// Usage counters shared with a running sub-agent so they survive timeouts and cancellation
+#[derive(Default)]
+struct SubAgentUsage {
+    iterations: AtomicU32,
+    /// x402 spend (AI calls and paid tools), charged before each payment is signed
+    spend: SpendBudget,
+}
+
+/// Outcome of waiting on a set of sub-agents
+#[derive(Debug, Clone)]
+pub struct SubAgentWaitResult {
+    /// Latest state of every sub-agent waited on
+    pub agents: Vec<SubAgentContext>,
+    /// True if the wait ended because the timeout elapsed
+    pub timed_out: bool,
+}
+
 /// Manager for coordinating sub-agent execution
 pub struct SubAgentManager {
     db: Arc<Database>,
@@ -135,7 +170,7 @@ impl SubAgentManager {
         );
 
         // Create cancel channel
-        let (cancel_tx, cancel_rx) = oneshot::channel();
+        let (cancel_tx, cancel_rx) = oneshot::channel::<String>();
 
         // Store the handle
         self.active_agents.insert(
@@ -154,6 +189,10 @@ impl SubAgentManager {
         let wallet_provider = self.wallet_provider.clone();
         let active_agents = self.active_agents.clone();
         let subagent_id_for_cleanup = subagent_id.clone();
+        let usage = Arc::new(SubAgentUsage {
+            iterations: AtomicU32::new(0),
+            spend: SpendBudget::new(context.max_cost_usdc.map(|cap| (cap * 1_000_000.0).round() as u64)),
+        });
 
         // Spawn the execution task
         tokio::spawn(async move {
@@ -173,16 +212,19 @@ impl SubAgentManager {
                 }
             };
 
-            // Execute with timeout and cancel handling
-            let execution = Self::execute_subagent(
+            // Execute with timeout and cancel handling; every x402 payment made inside
+            // is charged against the cost cap before it is signed
+            let execution = usage.spend.clone().scope(Self::execute_subagent(
                 db.clone(),
                 broadcaster.clone(),
                 tool_registry.clone(),
                 context.clone(),
                 wallet_provider,
-            );
+                usage.clone(),
+            ));
 
             let timeout_duration = Duration::from_secs(context.timeout_secs);
+            let mut cancel_reason = None;
             let result = tokio::select! {
                 result = timeout(timeout_duration, execution) => {
                     match result {
@@ -193,14 +235,17 @@ impl SubAgentManager {
                         }
                     }
                 }
-                _ = cancel_rx => {
+                reason = cancel_rx => {
                     log::info!("[SUBAGENT] {} was cancelled", context.id);
+                    cancel_reason = reason.ok();
                     Err("Cancelled".to_string())
                 }
             };
 
-            // Update the context with result
+            // Update the context with result and usage
             let mut final_context = context;
+            final_context.iterations = usage.iterations.load(Ordering::SeqCst);
+            final_context.cost_usdc = usage.spend.spent_micro_usdc() as f64 / 1_000_000.0;
             match result {
                 Ok(response) => {
                     final_context.mark_completed(response.clone());
@@ -213,7 +258,10 @@ impl SubAgentManager {
                 }
                 Err(error) => {
                     if error == "Cancelled" {
-                        final_context.mark_cancelled();
+                        match cancel_reason.take() {
+                            Some(reason) => final_context.mark_cancelled_because(reason),
+                            None => final_context.mark_cancelled(),
+                        }
                     } else if error.contains("timed out") {
                         final_context.mark_timed_out();
                     } else {
@@ -239,6 +287,32 @@ impl SubAgentManager {
                 final_context.status
             );
 
+            // Fail-fast groups: one failure cancels the siblings still running
+            if final_context.cancel_siblings_on_failure
+                && matches!(final_context.status, SubAgentStatus::Failed | SubAgentStatus::TimedOut)
+                && let Some(ref group_id) = final_context.group_id
+            {
+                let reason = format!(
+                    "Cancelled because sibling '{}' {}",
+                    final_context.label, final_context.status
+                );
+                let cancelled = Self::cancel_group_direct(
+                    &db,
+                    &active_agents,
+                    group_id,
+                    Some(&final_context.id),
+                    &reason,
+                );
+                if cancelled > 0 {
+                    log::info!(
+                        "[SUBAGENT] {} failed — cancelled {} sibling(s) in group {}",
+                        final_context.id,
+                        cancelled,
+                        group_id
+                    );
+                }
+            }
+
             // Clean up from active_agents DashMap
             if active_agents.remove(&subagent_id_for_cleanup).is_some() {
                 log::debug!(
@@ -258,6 +332,7 @@ impl SubAgentManager {
         tool_registry: Arc<ToolRegistry>,
         mut context: SubAgentContext,
         wallet_provider: Option<Arc<dyn crate::wallet::WalletProvider>>,
+        usage: Arc<SubAgentUsage>,
     ) -> Result<String, String> {
         log::info!("[SUBAGENT] Starting execution for {}", context.id);
 
@@ -318,10 +393,12 @@ impl SubAgentManager {
             Message {
                 role: MessageRole::System,
                 content: system_prompt,
+                images: Vec::new(),
             },
             Message {
                 role: MessageRole::User,
                 content: task_prompt,
+                images: Vec::new(),
             },
         ];
 
@@ -349,10 +426,28 @@ impl SubAgentManager {
             );
         }
 
-        // Get tool configuration — enforce safe mode and read_only restrictions
-        let mut tool_config = db
-            .get_effective_tool_config(Some(context.parent_channel_id))
-            .unwrap_or_default();
+        // Role-limited callers pass their role's config and spending cap down
+        // (nested sub-agents inherit them again through the same extras)
+        if let Some(ref role_config) = context.tool_config {
+            tool_context.extra.insert(
+                "role_tool_config".to_string(),
+                serde_json::to_value(role_config).unwrap_or_default(),
+            );
+        }
+        if let Some(ref cap) = context.max_tx_value_wei {
+            tool_context.extra.insert("max_tx_value_wei".to_string(), serde_json::json!(cap));
+            if let Some(ref source) = context.max_tx_value_source {
+                tool_context.extra.insert("max_tx_value_source".to_string(), serde_json::json!(source));
+            }
+        }
+
+        // Get tool configuration — enforce role, safe mode and read_only restrictions
+        let mut tool_config = match context.tool_config {
+            Some(ref role_config) => role_config.clone(),
+            None => db
+                .get_effective_tool_config(Some(context.parent_channel_id))
+                .unwrap_or_default(),
+        };
 
         // SECURITY: If parent channel is in safe mode, override to safe mode config.
         // Defense-in-depth — the subagent tool shouldn't be callable in safe mode,
@@ -385,7 +480,7 @@ impl SubAgentManager {
         };
 
         // Execute the AI with tool loop
-        let max_iterations = 15; // Sub-agents get fewer iterations
+        let max_iterations = context.max_iterations.unwrap_or(DEFAULT_SUBAGENT_MAX_ITERATIONS);
         let mut tool_history: Vec<ToolHistoryEntry> = Vec::new();
         let mut final_response = String::new();
         let mut client_error_retries = 0;
@@ -397,6 +492,7 @@ impl SubAgentManager {
                 context.id,
                 iteration + 1
             );
+            usage.iterations.store(iteration + 1, Ordering::SeqCst);
 
             // Generate response
             let response = match client
@@ -532,14 +628,18 @@ impl SubAgentManager {
                     status = ?2,
                     result = ?3,
                     error = ?4,
-                    completed_at = ?5
-                 WHERE subagent_id = ?6",
+                    completed_at = ?5,
+                    iterations = ?6,
+                    cost_usdc = ?7
+                 WHERE subagent_id = ?8",
                 rusqlite::params![
                     context.session_id,
                     context.status.to_string(),
                     context.result,
                     context.error,
                     context.completed_at.map(|t| t.to_rfc3339()),
+                    context.iterations,
+                    context.cost_usdc,
                     context.id,
                 ],
             )
@@ -550,8 +650,10 @@ impl SubAgentManager {
                 "INSERT INTO sub_agents (
                     subagent_id, parent_session_id, parent_channel_id, session_id,
                     label, task, status, model_override, thinking_level, timeout_secs,
-                    context, result, error, started_at, completed_at
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                    context, result, error, started_at, completed_at,
+                    group_id, cancel_siblings_on_failure, max_iterations, max_cost_usdc, read_only
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15,
+                          ?16, ?17, ?18, ?19, ?20)",
                 rusqlite::params![
                     context.id,
                     context.parent_session_id,
@@ -568,6 +670,11 @@ impl SubAgentManager {
                     context.error,
                     context.started_at.to_rfc3339(),
                     context.completed_at.map(|t| t.to_rfc3339()),
+                    context.group_id,
+                    context.cancel_siblings_on_failure as i32,
+                    context.max_iterations,
+                    context.max_cost_usdc,
+                    context.read_only as i32,
                 ],
             )
             .map_err(|e| format!("Failed to insert sub-agent: {}", e))?;
@@ -581,37 +688,9 @@ impl SubAgentManager {
         let conn = self.db.conn();
 
         let result = conn.query_row(
-            "SELECT
-                subagent_id, parent_session_id, parent_channel_id, session_id,
-                label, task, status, model_override, thinking_level, timeout_secs,
-                context, result, error, started_at, completed_at
-             FROM sub_agents WHERE subagent_id = ?1",
+            &format!("SELECT {} FROM sub_agents WHERE subagent_id = ?1", SUBAGENT_COLUMNS),
             [subagent_id],
-            |row| {
-                Ok(SubAgentContext {
-                    id: row.get(0)?,
-                    parent_session_id: row.get(1)?,
-                    parent_channel_id: row.get(2)?,
-                    session_id: row.get(3)?,
-                    label: row.get(4)?,
-                    task: row.get(5)?,
-                    status: SubAgentStatus::from_str(&row.get::<_, String>(6)?).unwrap_or_default(),
-                    model_override: row.get(7)?,
-                    thinking_level: row.get(8)?,
-                    timeout_secs: row.get::<_, i64>(9)? as u64,
-                    context: row.get(10)?,
-                    result: row.get(11)?,
-                    error: row.get(12)?,
-                    started_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(13)?)
-                        .map(|dt| dt.with_timezone(&chrono::Utc))
-                        .unwrap_or_else(|_| chrono::Utc::now()),
-                    completed_at: row
-                        .get::<_, Option<String>>(14)?
-                        .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
-                        .map(|dt| dt.with_timezone(&chrono::Utc)),
-                    read_only: false,
-                })
-            },
+            Self::map_subagent_row,
         );
 
         match result {
@@ -626,43 +705,14 @@ impl SubAgentManager {
         let conn = self.db.conn();
 
         let mut stmt = conn
-            .prepare(
-                "SELECT
-                    subagent_id, parent_session_id, parent_channel_id, session_id,
-                    label, task, status, model_override, thinking_level, timeout_secs,
-                    context, result, error, started_at, completed_at
-                 FROM sub_agents
-                 WHERE parent_channel_id = ?1
-                 ORDER BY started_at DESC",
-            )
+            .prepare(&format!(
+                "SELECT {} FROM sub_agents WHERE parent_channel_id = ?1 ORDER BY started_at DESC",
+                SUBAGENT_COLUMNS
+            ))
             .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
         let rows = stmt
-            .query_map([channel_id], |row| {
-                Ok(SubAgentContext {
-                    id: row.get(0)?,
-                    parent_session_id: row.get(1)?,
-                    parent_channel_id: row.get(2)?,
-                    session_id: row.get(3)?,
-                    label: row.get(4)?,
-                    task: row.get(5)?,
-                    status: SubAgentStatus::from_str(&row.get::<_, String>(6)?).unwrap_or_default(),
-                    model_override: row.get(7)?,
-                    thinking_level: row.get(8)?,
-                    timeout_secs: row.get::<_, i64>(9)? as u64,
-                    context: row.get(10)?,
-                    result: row.get(11)?,
-                    error: row.get(12)?,
-                    started_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(13)?)
-                        .map(|dt| dt.with_timezone(&chrono::Utc))
-                        .unwrap_or_else(|_| chrono::Utc::now()),
-                    completed_at: row
-                        .get::<_, Option<String>>(14)?
-                        .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
-                        .map(|dt| dt.with_timezone(&chrono::Utc)),
-                    read_only: false,
-                })
-            })
+            .query_map([channel_id], Self::map_subagent_row)
             .map_err(|e| format!("Failed to execute query: {}", e))?;
 
         let mut agents = Vec::new();
@@ -673,6 +723,170 @@ impl SubAgentManager {
         Ok(agents)
     }
 
+    /// List the members of a fan-out group in spawn order
+    pub fn list_by_group(&self, group_id: &str) -> Result<Vec<SubAgentContext>, String> {
+        Self::list_by_group_direct(&self.db, group_id)
+    }
+
+    fn list_by_group_direct(db: &Database, group_id: &str) -> Result<Vec<SubAgentContext>, String> {
+        let conn = db.conn();
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM sub_agents WHERE group_id = ?1 ORDER BY id ASC",
+                SUBAGENT_COLUMNS
+            ))
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+        let rows = stmt
+            .query_map([group_id], Self::map_subagent_row)
+            .map_err(|e| format!("Failed to execute query: {}", e))?;
+
+        let mut agents = Vec::new();
+        for row in rows {
+            agents.push(row.map_err(|e| format!("Failed to read row: {}", e))?);
+        }
+
+        Ok(agents)
+    }
+
+    fn map_subagent_row(row: &rusqlite::Row) -> rusqlite::Result<SubAgentContext> {
+        Ok(SubAgentContext {
+            id: row.get(0)?,
+            parent_session_id: row.get(1)?,
+            parent_channel_id: row.get(2)?,
+            session_id: row.get(3)?,
+            label: row.get(4)?,
+            task: row.get(5)?,
+            status: SubAgentStatus::from_str(&row.get::<_, String>(6)?).unwrap_or_default(),
+            model_override: row.get(7)?,
+            thinking_level: row.get(8)?,
+            timeout_secs: row.get::<_, i64>(9)? as u64,
+            context: row.get(10)?,
+            result: row.get(11)?,
+            error: row.get(12)?,
+            started_at: chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(13)?)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .unwrap_or_else(|_| chrono::Utc::now()),
+            completed_at: row
+                .get::<_, Option<String>>(14)?
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&chrono::Utc)),
+            group_id: row.get(15)?,
+            cancel_siblings_on_failure: row.get::<_, i32>(16)? != 0,
+            max_iterations: row.get(17)?,
+            max_cost_usdc: row.get(18)?,
+            iterations: row.get(19)?,
+            cost_usdc: row.get(20)?,
+            read_only: row.get::<_, i32>(21)? != 0,
+            // Inherited limits only matter to the running task, which holds its own copy
+            tool_config: None,
+            max_tx_value_wei: None,
+            max_tx_value_source: None,
+        })
+    }
+
+    /// Spawn several sub-agents as one fan-out group.
+    ///
+    /// Every context joins the same group; if any spawn fails the ones already
+    /// started are cancelled. Returns the group ID and the member IDs in order.
+    pub async fn spawn_group(
+        &self,
+        contexts: Vec<SubAgentContext>,
+        cancel_siblings_on_failure: bool,
+    ) -> Result<(String, Vec<String>), String> {
+        if contexts.is_empty() {
+            return Err("A fan-out group needs at least one sub-agent".to_string());
+        }
+
+        let group_id = format!("group-{}", GROUP_COUNTER.fetch_add(1, Ordering::SeqCst));
+        let mut ids = Vec::with_capacity(contexts.len());
+
+        for context in contexts {
+            let context = context.with_group(group_id.clone(), cancel_siblings_on_failure);
+            match self.spawn(context).await {
+                Ok(id) => ids.push(id),
+                Err(e) => {
+                    self.cancel_group(&group_id, "Fan-out aborted: a sibling failed to spawn");
+                    return Err(e);
+                }
+            }
+        }
+
+        log::info!("[SUBAGENT] Spawned fan-out group {} with {} sub-agents", group_id, ids.len());
+        Ok((group_id, ids))
+    }
+
+    /// Wait for sub-agents to finish.
+    ///
+    /// With `any` set, returns as soon as one of them reaches a terminal state;
+    /// otherwise waits for all of them. Returns whatever state they are in when
+    /// the timeout elapses.
+    pub async fn wait_for(
+        &self,
+        ids: &[String],
+        wait_timeout: Duration,
+        any: bool,
+    ) -> Result<SubAgentWaitResult, String> {
+        let start = tokio::time::Instant::now();
+
+        loop {
+            let mut agents = Vec::with_capacity(ids.len());
+            for id in ids {
+                match self.get_status(id)? {
+                    Some(agent) => agents.push(agent),
+                    None => return Err(format!("Subagent '{}' not found", id)),
+                }
+            }
+
+            let finished = agents.iter().filter(|a| a.status.is_terminal()).count();
+            let done = if any { finished > 0 } else { finished == agents.len() };
+            if done {
+                return Ok(SubAgentWaitResult { agents, timed_out: false });
+            }
+            if start.elapsed() >= wait_timeout {
+                return Ok(SubAgentWaitResult { agents, timed_out: true });
+            }
+
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    }
+
+    /// Cancel every running member of a fan-out group
+    /// Returns the number of agents cancelled
+    pub fn cancel_group(&self, group_id: &str, reason: &str) -> usize {
+        Self::cancel_group_direct(&self.db, &self.active_agents, group_id, None, reason)
+    }
+
+    fn cancel_group_direct(
+        db: &Database,
+        active_agents: &DashMap<String, SubAgentHandle>,
+        group_id: &str,
+        except: Option<&str>,
+        reason: &str,
+    ) -> usize {
+        let members = match Self::list_by_group_direct(db, group_id) {
+            Ok(members) => members,
+            Err(e) => {
+                log::error!("[SUBAGENT_MANAGER] Failed to list group {}: {}", group_id, e);
+                return 0;
+            }
+        };
+
+        let mut count = 0;
+        for member in members {
+            if Some(member.id.as_str()) == except || member.status.is_terminal() {
+                continue;
+            }
+            if let Some((_, handle)) = active_agents.remove(&member.id) {
+                log::info!("[SUBAGENT_MANAGER] Cancelling subagent {} in group {}", member.id, group_id);
+                handle.cancel_with_reason(reason.to_string());
+                count += 1;
+            }
+        }
+        count
+    }
+
     /// Cancel a running sub-agent
     pub fn cancel(&self, subagent_id: &str) -> Result<bool, String> {
         if let Some((_, handle)) = self.active_agents.remove(subagent_id) {
@@ -0,0 +1,331 @@
+//! Agent subtype definitions
+//!
+//! The built-in toolboxes (finance, code_engineer, secretary) are defined here and can be
+//! extended or overridden by RON files in `config/agent_subtypes/`, one subtype per file.
+//! Definitions are validated once at startup; invalid ones are logged and skipped.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::tools::ToolGroup;
+
+static AGENT_SUBTYPES: OnceLock<Vec<SubtypeDefinition>> = OnceLock::new();
+
+/// Subdirectory of the config dir holding subtype definitions
+const SUBTYPES_DIR: &str = "agent_subtypes";
+
+/// A selectable agent subtype ("toolbox")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtypeDefinition {
+    /// Key used by `set_agent_subtype`, cron jobs and kanban tasks (snake_case)
+    pub key: String,
+    pub label: String,
+    #[serde(default = "default_emoji")]
+    pub emoji: String,
+    pub description: String,
+    /// Other names accepted for this subtype
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Tool groups unlocked in addition to system/web/filesystem
+    #[serde(default)]
+    pub tool_groups: Vec<ToolGroup>,
+    /// Tools always included while this subtype is active, regardless of group
+    #[serde(default)]
+    pub required_tools: Vec<String>,
+    /// Skill tags allowed in addition to the universal ones
+    #[serde(default)]
+    pub skill_tags: Vec<String>,
+    /// Skills recommended to the agent when the subtype is selected
+    #[serde(default)]
+    pub default_skills: Vec<String>,
+    /// Extra system prompt text injected while the subtype is active
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// AI endpoint preset (ai_endpoints.ron key) used for runs that start in this subtype
+    #[serde(default)]
+    pub preferred_endpoint: Option<String>,
+}
+
+fn default_emoji() -> String {
+    "🧩".to_string()
+}
+
+/// Load built-in subtypes plus any definitions from `config_dir/agent_subtypes/*.ron`.
+/// Must run after `ai_endpoint_config::load_ai_endpoints` so endpoint preferences can be checked.
+pub fn load_agent_subtypes(config_dir: &Path) {
+    let dir = config_dir.join(SUBTYPES_DIR);
+    let mut configured = Vec::new();
+
+    if dir.is_dir() {
+        let mut paths: Vec<_> = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().map(|ext| ext == "ron").unwrap_or(false))
+                .collect(),
+            Err(e) => {
+                log::error!("[SUBTYPES] Failed to read {:?}: {}", dir, e);
+                Vec::new()
+            }
+        };
+        paths.sort();
+
+        for path in paths {
+            match std::fs::read_to_string(&path) {
+                Ok(content) => match ron::from_str::<SubtypeDefinition>(&content) {
+                    Ok(def) => configured.push(def),
+                    Err(e) => log::error!("[SUBTYPES] Failed to parse {:?}: {}", path, e),
+                },
+                Err(e) => log::error!("[SUBTYPES] Failed to read {:?}: {}", path, e),
+            }
+        }
+    } else {
+        log::info!("[SUBTYPES] No {} directory found, using built-in subtypes", SUBTYPES_DIR);
+    }
+
+    let definitions = merge_definitions(builtin_definitions(), configured);
+    log::info!(
+        "[SUBTYPES] Loaded {} agent subtypes: {:?}",
+        definitions.len(),
+        definitions.iter().map(|d| d.key.as_str()).collect::<Vec<_>>()
+    );
+
+    if AGENT_SUBTYPES.set(definitions).is_err() {
+        log::warn!("[SUBTYPES] Agent subtypes already initialized");
+    }
+}
+
+/// Validate configured definitions and merge them over the built-ins.
+/// A definition whose key matches a built-in replaces it (keeping the built-in aliases).
+fn merge_definitions(
+    mut definitions: Vec<SubtypeDefinition>,
+    configured: Vec<SubtypeDefinition>,
+) -> Vec<SubtypeDefinition> {
+    let builtin_keys: HashSet<String> = definitions.iter().map(|d| d.key.clone()).collect();
+    let mut seen_keys = HashSet::new();
+
+    for mut def in configured {
+        def.key = def.key.trim().to_lowercase();
+        def.aliases = def.aliases.iter().map(|a| a.trim().to_lowercase()).collect();
+
+        if let Err(e) = validate_definition(&def) {
+            log::error!("[SUBTYPES] Skipping subtype '{}': {}", def.key, e);
+            continue;
+        }
+        if !seen_keys.insert(def.key.clone()) {
+            log::error!("[SUBTYPES] Skipping subtype '{}': defined more than once", def.key);
+            continue;
+        }
+
+        let overriding = builtin_keys.contains(&def.key);
+        if overriding
+            && let Some(builtin) = definitions.iter().find(|d| d.key == def.key)
+        {
+            for alias in &builtin.aliases {
+                if !def.aliases.contains(alias) {
+                    def.aliases.push(alias.clone());
+                }
+            }
+        }
+
+        // Names must resolve to exactly one subtype
+        let clash = definitions
+            .iter()
+            .filter(|d| d.key != def.key)
+            .flat_map(|d| std::iter::once(&d.key).chain(d.aliases.iter()))
+            .find(|name| **name == def.key || def.aliases.contains(name))
+            .cloned();
+        if let Some(name) = clash {
+            log::error!("[SUBTYPES] Skipping subtype '{}': name '{}' is already taken", def.key, name);
+            continue;
+        }
+
+        if overriding {
+            log::info!("[SUBTYPES] Overriding built-in subtype '{}'", def.key);
+            definitions.retain(|d| d.key != def.key);
+        }
+        definitions.push(def);
+    }
+
+    definitions
+}
+
+/// Check a single definition for problems that would make it unusable
+fn validate_definition(def: &SubtypeDefinition) -> Result<(), String> {
+    let valid_name = |s: &str| {
+        !s.is_empty() && s.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    };
+    if !valid_name(&def.key) {
+        return Err("key must be non-empty lowercase letters, digits, '_' or '-'".to_string());
+    }
+    if def.key == "none" || def.aliases.iter().any(|a| a == "none") {
+        return Err("'none' is reserved".to_string());
+    }
+    if let Some(alias) = def.aliases.iter().find(|a| !valid_name(a)) {
+        return Err(format!("invalid alias '{}'", alias));
+    }
+    if def.label.trim().is_empty() {
+        return Err("label is required".to_string());
+    }
+    if let Some(ref key) = def.preferred_endpoint
+        && crate::ai_endpoint_config::get_ai_endpoint(key).is_none()
+    {
+        return Err(format!("unknown preferred_endpoint '{}'", key));
+    }
+    Ok(())
+}
+
+/// Warn about required tools that no registered tool provides
+pub fn check_required_tools(registry: &crate::tools::ToolRegistry) {
+    for def in definitions() {
+        for tool in &def.required_tools {
+            if registry.get(tool).is_none() {
+                log::warn!("[SUBTYPES] Subtype '{}' requires unknown tool '{}'", def.key, tool);
+            }
+        }
+    }
+}
+
+/// All subtype definitions (built-ins only if nothing was loaded)
+pub fn definitions() -> &'static [SubtypeDefinition] {
+    AGENT_SUBTYPES.get_or_init(builtin_definitions)
+}
+
+/// Find a definition by key or alias (case-insensitive)
+pub fn find_definition(name: &str) -> Option<&'static SubtypeDefinition> {
+    let name = name.trim().to_lowercase();
+    definitions()
+        .iter()
+        .find(|d| d.key == name)
+        .or_else(|| definitions().iter().find(|d| d.aliases.contains(&name)))
+}
+
+fn builtin_definitions() -> Vec<SubtypeDefinition> {
+    let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    vec![
+        SubtypeDefinition {
+            key: "finance".to_string(),
+            label: "Finance".to_string(),
+            emoji: "💰".to_string(),
+            description: "Crypto swaps, transfers, DeFi operations, token lookups".to_string(),
+            aliases: strings(&["defi", "crypto", "swap", "transfer"]),
+            tool_groups: vec![ToolGroup::Finance], // web3_tx, token_lookup, x402_*, etc.
+            required_tools: vec![],
+            skill_tags: strings(&[
+                "crypto", "defi", "transfer", "swap", "finance", "wallet", "token",
+                "bridge", "lending", "yield", "dex", "payments", "x402", "transaction",
+                "polymarket", "prediction-markets", "trading", "price", "discord", "tipping",
+            ]),
+            default_skills: vec![],
+            prompt: None,
+            preferred_endpoint: None,
+        },
+        SubtypeDefinition {
+            key: "code_engineer".to_string(),
+            label: "CodeEngineer".to_string(),
+            emoji: "🛠️".to_string(),
+            description: "Code editing, git operations, testing, debugging".to_string(),
+            aliases: strings(&["codeengineer", "code", "dev", "developer", "git"]),
+            tool_groups: vec![
+                ToolGroup::Development, // edit_file, grep, glob, git, etc.
+                ToolGroup::Exec,        // exec command
+            ],
+            required_tools: vec![],
+            skill_tags: strings(&[
+                "development", "git", "testing", "debugging", "review", "code", "github",
+                "devops", "deployment", "infrastructure", "workflow", "discussions", "ci-cd",
+                "skills", "project", "scaffold",
+            ]),
+            default_skills: vec![],
+            prompt: None,
+            preferred_endpoint: None,
+        },
+        SubtypeDefinition {
+            key: "secretary".to_string(),
+            label: "Secretary".to_string(),
+            emoji: "📱".to_string(),
+            description: "Social media, messaging, scheduling, marketing".to_string(),
+            aliases: strings(&["social", "marketing", "messaging", "moltx"]),
+            tool_groups: vec![
+                ToolGroup::Messaging, // agent_send
+                ToolGroup::Social,    // moltx, scheduling tools
+                ToolGroup::Memory,    // memory_search, memory_read
+                ToolGroup::Exec,      // exec command (curl, etc.)
+            ],
+            required_tools: vec![],
+            skill_tags: strings(&[
+                "social", "marketing", "messaging", "moltx", "scheduling", "communication",
+                "social-media", "secretary", "journal", "discord", "telegram", "twitter", "4claw",
+                "x402", "cron", "moltbook", "publishing", "content",
+            ]),
+            default_skills: vec![],
+            prompt: None,
+            preferred_endpoint: None,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(key: &str, aliases: &[&str]) -> SubtypeDefinition {
+        SubtypeDefinition {
+            key: key.to_string(),
+            label: "Custom".to_string(),
+            emoji: default_emoji(),
+            description: String::new(),
+            aliases: aliases.iter().map(|s| s.to_string()).collect(),
+            tool_groups: vec![ToolGroup::Memory],
+            required_tools: vec![],
+            skill_tags: vec![],
+            default_skills: vec![],
+            prompt: None,
+            preferred_endpoint: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_adds_overrides_and_rejects_clashes() {
+        let merged = merge_definitions(
+            builtin_definitions(),
+            vec![
+                custom("Researcher", &["research"]),
+                custom("finance", &["treasury"]),
+                custom("ops", &["social"]), // alias already used by secretary
+                custom("researcher", &[]),  // duplicate key
+                custom("none", &[]),
+            ],
+        );
+
+        let keys: Vec<&str> = merged.iter().map(|d| d.key.as_str()).collect();
+        assert_eq!(keys, vec!["code_engineer", "secretary", "researcher", "finance"]);
+
+        let finance = merged.iter().find(|d| d.key == "finance").unwrap();
+        assert_eq!(finance.tool_groups, vec![ToolGroup::Memory]);
+        assert!(finance.aliases.contains(&"treasury".to_string()));
+        assert!(finance.aliases.contains(&"defi".to_string()));
+    }
+
+    #[test]
+    fn test_parse_ron_definition() {
+        let def: SubtypeDefinition = ron::from_str(
+            r#"(
+                key: "treasury_ops",
+                label: "Treasury Ops",
+                description: "Treasury rebalancing and reporting",
+                tool_groups: [finance, memory],
+                required_tools: ["token_lookup"],
+                default_skills: ["swap"],
+                prompt: Some("Never move more than 10% of the treasury in one transaction."),
+            )"#,
+        )
+        .unwrap();
+
+        assert_eq!(def.tool_groups, vec![ToolGroup::Finance, ToolGroup::Memory]);
+        assert_eq!(def.emoji, "🧩");
+        assert!(def.preferred_endpoint.is_none());
+        assert!(validate_definition(&def).is_ok());
+    }
+}
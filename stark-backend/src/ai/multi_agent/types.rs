@@ -2,7 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::tools::types::ToolGroup;
+use super::subtype_config::{self, SubtypeDefinition};
+use crate::tools::types::{ToolConfig, ToolContext, ToolGroup};
 
 // =====================================================
 // Task Planner Types
@@ -184,6 +185,8 @@ impl TaskQueue {
         "ask_user",
         "subagent",
         "subagent_status",
+        "subagent_fanout",
+        "subagent_wait",
         "use_skill",
         "manage_skills",
     ];
@@ -417,8 +420,8 @@ mod task_queue_tests {
 
 /// The specialized mode/persona of the agent
 /// Controls which tools and skills are available (acts as a "toolbox")
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
-#[serde(rename_all = "snake_case")]
+/// Metadata for each subtype lives in `subtype_config` (built-ins plus config/agent_subtypes/)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum AgentSubtype {
     /// No subtype selected yet - agent MUST choose one before using other tools
     #[default]
@@ -429,16 +432,27 @@ pub enum AgentSubtype {
     CodeEngineer,
     /// Secretary - social media, marketing, messaging, scheduling
     Secretary,
+    /// User-defined subtype loaded from config (holds its key)
+    Custom(&'static str),
 }
 
 impl AgentSubtype {
     /// Get all selectable subtypes (excludes None)
     pub fn all() -> Vec<AgentSubtype> {
-        vec![
-            AgentSubtype::Finance,
-            AgentSubtype::CodeEngineer,
-            AgentSubtype::Secretary,
-        ]
+        subtype_config::definitions()
+            .iter()
+            .map(|d| Self::from_key(d.key.as_str()))
+            .collect()
+    }
+
+    /// Map a definition key to its variant
+    fn from_key(key: &'static str) -> Self {
+        match key {
+            "finance" => AgentSubtype::Finance,
+            "code_engineer" => AgentSubtype::CodeEngineer,
+            "secretary" => AgentSubtype::Secretary,
+            _ => AgentSubtype::Custom(key),
+        }
     }
 
     /// Check if a subtype has been selected
@@ -446,101 +460,83 @@ impl AgentSubtype {
         !matches!(self, AgentSubtype::None)
     }
 
+    /// The definition backing this subtype (None when no subtype is selected)
+    pub fn definition(&self) -> Option<&'static SubtypeDefinition> {
+        if !self.is_selected() {
+            return None;
+        }
+        subtype_config::find_definition(self.as_str())
+    }
+
     /// Get the tool groups allowed for this subtype
     /// Note: When None, only System tools are available (to allow set_agent_subtype)
     pub fn allowed_tool_groups(&self) -> Vec<ToolGroup> {
-        match self {
-            AgentSubtype::None => {
-                // Only system tools when no subtype selected
-                // This forces the agent to call set_agent_subtype first
-                vec![ToolGroup::System]
-            }
-            _ => {
-                // Core groups available to all selected subtypes
-                let mut groups = vec![
-                    ToolGroup::System,     // set_agent_subtype, subagent
-                    ToolGroup::Web,        // web_fetch
-                    ToolGroup::Filesystem, // read_file, list_files
-                ];
-
-                // Add subtype-specific groups
-                match self {
-                    AgentSubtype::Finance => {
-                        groups.push(ToolGroup::Finance); // web3_tx, token_lookup, x402_*, etc.
-                    }
-                    AgentSubtype::CodeEngineer => {
-                        groups.push(ToolGroup::Development); // edit_file, grep, glob, git, etc.
-                        groups.push(ToolGroup::Exec);        // exec command
-                    }
-                    AgentSubtype::Secretary => {
-                        groups.push(ToolGroup::Messaging); // agent_send
-                        groups.push(ToolGroup::Social);    // moltx, scheduling tools
-                        groups.push(ToolGroup::Memory);    // memory_search, memory_read
-                        groups.push(ToolGroup::Exec);      // exec command (curl, etc.)
-                    }
-                    AgentSubtype::None => unreachable!(), // Handled above
-                }
+        if !self.is_selected() {
+            // Only system tools when no subtype selected
+            // This forces the agent to call set_agent_subtype first
+            return vec![ToolGroup::System];
+        }
 
-                groups
+        // Core groups available to all selected subtypes
+        let mut groups = vec![
+            ToolGroup::System,     // set_agent_subtype, subagent
+            ToolGroup::Web,        // web_fetch
+            ToolGroup::Filesystem, // read_file, list_files
+        ];
+
+        // Add subtype-specific groups
+        if let Some(def) = self.definition() {
+            for group in &def.tool_groups {
+                if !groups.contains(group) {
+                    groups.push(*group);
+                }
             }
         }
+
+        groups
+    }
+
+    /// Tools always available while this subtype is active
+    pub fn required_tools(&self) -> Vec<String> {
+        self.definition()
+            .map(|d| d.required_tools.clone())
+            .unwrap_or_default()
     }
 
     /// Get the skill tags allowed for this subtype
     /// Note: "general" and "all" tags are available to ALL subtypes
     /// When None, no skills are available (must select subtype first)
     pub fn allowed_skill_tags(&self) -> Vec<&'static str> {
-        match self {
-            AgentSubtype::None => {
-                // No skills available until subtype is selected
-                vec![]
-            }
-            _ => {
-                // Universal tags available to all selected subtypes
-                let mut tags = vec!["general", "all", "identity", "eip8004", "registration"];
-
-                // Add subtype-specific tags
-                match self {
-                    AgentSubtype::Finance => tags.extend([
-                        "crypto", "defi", "transfer", "swap", "finance", "wallet", "token",
-                        "bridge", "lending", "yield", "dex", "payments", "x402", "transaction",
-                        "polymarket", "prediction-markets", "trading", "price", "discord", "tipping",
-                    ]),
-                    AgentSubtype::CodeEngineer => tags.extend([
-                        "development", "git", "testing", "debugging", "review", "code", "github",
-                        "devops", "deployment", "infrastructure", "workflow", "discussions", "ci-cd",
-                        "skills", "project", "scaffold",
-                    ]),
-                    AgentSubtype::Secretary => tags.extend([
-                        "social", "marketing", "messaging", "moltx", "scheduling", "communication",
-                        "social-media", "secretary", "journal", "discord", "telegram", "twitter", "4claw",
-                        "x402", "cron", "moltbook", "publishing", "content",
-                    ]),
-                    AgentSubtype::None => unreachable!(),
-                }
+        if !self.is_selected() {
+            // No skills available until subtype is selected
+            return vec![];
+        }
 
-                tags
-            }
+        // Universal tags available to all selected subtypes
+        let mut tags = vec!["general", "all", "identity", "eip8004", "registration"];
+
+        // Add subtype-specific tags
+        if let Some(def) = self.definition() {
+            tags.extend(def.skill_tags.iter().map(|t| t.as_str()));
         }
+
+        tags
     }
 
     /// Human-readable label for UI display
     pub fn label(&self) -> &'static str {
-        match self {
-            AgentSubtype::None => "Selecting...",
-            AgentSubtype::Finance => "Finance",
-            AgentSubtype::CodeEngineer => "CodeEngineer",
-            AgentSubtype::Secretary => "Secretary",
+        match self.definition() {
+            Some(def) => def.label.as_str(),
+            None if self.is_selected() => self.as_str(),
+            None => "Selecting...",
         }
     }
 
     /// Get description of what this subtype does
     pub fn description(&self) -> &'static str {
-        match self {
-            AgentSubtype::None => "No toolbox selected - must choose one first",
-            AgentSubtype::Finance => "Crypto swaps, transfers, DeFi operations, token lookups",
-            AgentSubtype::CodeEngineer => "Code editing, git operations, testing, debugging",
-            AgentSubtype::Secretary => "Social media, messaging, scheduling, marketing",
+        match self.definition() {
+            Some(def) => def.description.as_str(),
+            None => "No toolbox selected - must choose one first",
         }
     }
 
@@ -551,30 +547,20 @@ impl AgentSubtype {
             AgentSubtype::Finance => "finance",
             AgentSubtype::CodeEngineer => "code_engineer",
             AgentSubtype::Secretary => "secretary",
+            AgentSubtype::Custom(key) => key,
         }
     }
 
-    /// Parse from string (does not parse "none" - use None variant directly)
+    /// Parse from a subtype key or alias (does not parse "none" - use None variant directly)
     pub fn from_str(s: &str) -> Option<Self> {
-        match s.to_lowercase().as_str() {
-            "finance" | "defi" | "crypto" | "swap" | "transfer" => Some(AgentSubtype::Finance),
-            "code_engineer" | "codeengineer" | "code" | "dev" | "developer" | "git" => {
-                Some(AgentSubtype::CodeEngineer)
-            }
-            "secretary" | "social" | "marketing" | "messaging" | "moltx" => {
-                Some(AgentSubtype::Secretary)
-            }
-            _ => None,
-        }
+        subtype_config::find_definition(s).map(|d| Self::from_key(d.key.as_str()))
     }
 
     /// Get emoji for this subtype
     pub fn emoji(&self) -> &'static str {
-        match self {
-            AgentSubtype::None => "❓",
-            AgentSubtype::Finance => "💰",
-            AgentSubtype::CodeEngineer => "🛠️",
-            AgentSubtype::Secretary => "📱",
+        match self.definition() {
+            Some(def) => def.emoji.as_str(),
+            None => "❓",
         }
     }
 }
@@ -585,6 +571,20 @@ impl std::fmt::Display for AgentSubtype {
     }
 }
 
+impl Serialize for AgentSubtype {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AgentSubtype {
+    /// Unknown keys (e.g. a subtype removed from config) deserialize as None
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(Self::from_str(&s).unwrap_or_default())
+    }
+}
+
 /// The current mode of the agent
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -817,8 +817,38 @@ pub struct SubAgentContext {
     /// If true, restrict to read-only tools (for safe parallel research)
     #[serde(default)]
     pub read_only: bool,
+    /// Fan-out group this sub-agent was spawned in
+    #[serde(default)]
+    pub group_id: Option<String>,
+    /// Cancel the rest of the group if this sub-agent fails
+    #[serde(default)]
+    pub cancel_siblings_on_failure: bool,
+    /// Maximum AI iterations (defaults to DEFAULT_SUBAGENT_MAX_ITERATIONS)
+    #[serde(default)]
+    pub max_iterations: Option<u32>,
+    /// Maximum x402 spend in USDC before the sub-agent is stopped
+    #[serde(default)]
+    pub max_cost_usdc: Option<f64>,
+    /// AI iterations used so far
+    #[serde(default)]
+    pub iterations: u32,
+    /// x402 spend in USDC so far
+    #[serde(default)]
+    pub cost_usdc: f64,
+    /// Tool config of a role-limited caller; replaces the channel's config for the sub-agent
+    #[serde(default)]
+    pub tool_config: Option<ToolConfig>,
+    /// Per-transaction spending cap (wei) inherited from the caller
+    #[serde(default)]
+    pub max_tx_value_wei: Option<String>,
+    /// Where the inherited cap comes from (shown in cap errors)
+    #[serde(default)]
+    pub max_tx_value_source: Option<String>,
 }
 
+/// AI iterations a sub-agent gets when no cap is given
+pub const DEFAULT_SUBAGENT_MAX_ITERATIONS: u32 = 15;
+
 impl SubAgentContext {
     /// Create a new sub-agent context
     pub fn new(
@@ -846,6 +876,15 @@ impl SubAgentContext {
             context: None,
             thinking_level: None,
             read_only: false,
+            group_id: None,
+            cancel_siblings_on_failure: false,
+            max_iterations: None,
+            max_cost_usdc: None,
+            iterations: 0,
+            cost_usdc: 0.0,
+            tool_config: None,
+            max_tx_value_wei: None,
+            max_tx_value_source: None,
         }
     }
 
@@ -873,6 +912,33 @@ impl SubAgentContext {
         self
     }
 
+    /// Set per-child iteration and spend caps
+    pub fn with_caps(mut self, max_iterations: Option<u32>, max_cost_usdc: Option<f64>) -> Self {
+        self.max_iterations = max_iterations;
+        self.max_cost_usdc = max_cost_usdc;
+        self
+    }
+
+    /// Inherit the caller's role tool config and spending cap from its tool context,
+    /// so spawning a sub-agent never escapes the caller's limits
+    pub fn with_caller_limits(mut self, caller: &ToolContext) -> Self {
+        // An unreadable role config fails closed
+        self.tool_config = caller
+            .extra
+            .get("role_tool_config")
+            .map(|v| serde_json::from_value(v.clone()).unwrap_or_else(|_| ToolConfig::safe_mode()));
+        self.max_tx_value_wei = caller.extra.get("max_tx_value_wei").and_then(|v| v.as_str()).map(String::from);
+        self.max_tx_value_source = caller.extra.get("max_tx_value_source").and_then(|v| v.as_str()).map(String::from);
+        self
+    }
+
+    /// Join a fan-out group
+    pub fn with_group(mut self, group_id: String, cancel_siblings_on_failure: bool) -> Self {
+        self.group_id = Some(group_id);
+        self.cancel_siblings_on_failure = cancel_siblings_on_failure;
+        self
+    }
+
     /// Mark the sub-agent as running
     pub fn mark_running(&mut self, session_id: i64) {
         self.status = SubAgentStatus::Running;
@@ -902,8 +968,13 @@ impl SubAgentContext {
 
     /// Mark the sub-agent as cancelled
     pub fn mark_cancelled(&mut self) {
+        self.mark_cancelled_because("Cancelled by user or system".to_string());
+    }
+
+    /// Mark the sub-agent as cancelled with a specific reason (e.g. a sibling failed)
+    pub fn mark_cancelled_because(&mut self, reason: String) {
         self.status = SubAgentStatus::Cancelled;
-        self.error = Some("Cancelled by user or system".to_string());
+        self.error = Some(reason);
         self.completed_at = Some(chrono::Utc::now());
     }
 
@@ -87,13 +87,47 @@ struct OpenAIStreamUsage {
 pub struct OpenAIMessage {
     pub role: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub content: Option<String>,
+    pub content: Option<OpenAIContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<OpenAIToolCall>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_call_id: Option<String>,
 }
 
+/// Message content: plain text, or content parts when images are attached
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum OpenAIContent {
+    Text(String),
+    Parts(Vec<OpenAIContentPart>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OpenAIContentPart {
+    Text { text: String },
+    ImageUrl { image_url: OpenAIImageUrl },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OpenAIImageUrl {
+    pub url: String,
+}
+
+impl OpenAIContent {
+    /// Build content for a conversation message (text first, then any images as data URLs)
+    fn from_message(message: Message) -> Self {
+        if message.images.is_empty() {
+            return OpenAIContent::Text(message.content);
+        }
+        let mut parts = vec![OpenAIContentPart::Text { text: message.content }];
+        parts.extend(message.images.iter().map(|image| OpenAIContentPart::ImageUrl {
+            image_url: OpenAIImageUrl { url: image.data_url() },
+        }));
+        OpenAIContent::Parts(parts)
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 struct OpenAITool {
     #[serde(rename = "type")]
@@ -378,7 +412,7 @@ impl OpenAIClient {
             .into_iter()
             .map(|m| OpenAIMessage {
                 role: m.role.to_string(),
-                content: Some(m.content),
+                content: Some(OpenAIContent::from_message(m)),
                 tool_calls: None,
                 tool_call_id: None,
             })
@@ -678,7 +712,7 @@ impl OpenAIClient {
         for response in tool_responses {
             messages.push(OpenAIMessage {
                 role: "tool".to_string(),
-                content: Some(response.content.clone()),
+                content: Some(OpenAIContent::Text(response.content.clone())),
                 tool_calls: None,
                 tool_call_id: Some(response.tool_call_id.clone()),
             });
@@ -703,7 +737,7 @@ impl OpenAIClient {
             .into_iter()
             .map(|m| OpenAIMessage {
                 role: m.role.to_string(),
-                content: Some(m.content),
+                content: Some(OpenAIContent::from_message(m)),
                 tool_calls: None,
                 tool_call_id: None,
             })
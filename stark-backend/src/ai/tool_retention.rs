@@ -0,0 +1,599 @@
+//! Tool history retention policy.
+//!
+//! Replaces the old "keep the last N entries" trimming of the tool loops with a
+//! policy that:
+//! - summarizes evicted tool results into a compact ledger the model still sees
+//!   (in the system prompt, outside the history being trimmed),
+//! - pins results that were cached into registers (they are referenced later),
+//! - evicts noisy discovery output (list_files, glob, ...) before anything else,
+//! - truncates large, already-seen outputs by relevance instead of dropping them.
+//!
+//! Every pass returns a `RetentionReport` so the dispatcher can emit telemetry
+//! about what was dropped.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+use super::types::{ToolCall, ToolHistoryEntry, ToolResponse};
+use super::{Message, MessageRole};
+
+/// Start of the ledger section appended to the system prompt
+const LEDGER_SECTION_HEADER: &str =
+    "\n\n---\n\n## Tool Ledger\nSummary of earlier tool results that were removed from context:";
+
+/// Tools whose output is bulky and rarely needed once acted upon.
+/// These are evicted first when the history is over budget.
+const LOW_VALUE_TOOLS: &[&str] = &[
+    "list_files",
+    "glob",
+    "grep",
+    "read_file",
+    "read_symbol",
+    "web_fetch",
+    "task_fully_completed",
+    "say_to_user",
+];
+
+/// Retention configuration for a single tool loop
+#[derive(Debug, Clone)]
+pub struct ToolHistoryRetention {
+    /// Maximum number of unpinned entries kept verbatim
+    pub max_entries: usize,
+    /// Maximum number of pinned (register-backed) entries kept verbatim
+    pub max_pinned: usize,
+    /// Number of most recent entries that are never evicted or truncated
+    pub keep_recent: usize,
+    /// Results longer than this are truncated by relevance once they are no longer recent
+    pub max_result_chars: usize,
+    /// Maximum number of ledger lines kept (oldest lines are dropped first)
+    pub max_ledger_lines: usize,
+    /// Maximum characters of a result preview in a ledger line
+    pub ledger_preview_chars: usize,
+}
+
+impl Default for ToolHistoryRetention {
+    fn default() -> Self {
+        Self {
+            max_entries: 10,
+            max_pinned: 6,
+            keep_recent: 2,
+            max_result_chars: 4000,
+            max_ledger_lines: 40,
+            ledger_preview_chars: 160,
+        }
+    }
+}
+
+/// What a retention pass did, for logging and telemetry
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RetentionReport {
+    /// Tool names of evicted entries (one per evicted call), or message roles
+    /// for text-tool conversations
+    pub evicted_tools: Vec<String>,
+    /// Number of tool results truncated by relevance
+    pub truncated_results: usize,
+    /// Characters removed by truncation and eviction
+    pub dropped_chars: usize,
+    /// Number of entries currently pinned by registers
+    pub pinned_entries: usize,
+    /// Number of lines currently in the ledger
+    pub ledger_lines: usize,
+}
+
+impl RetentionReport {
+    /// True if the pass changed the history
+    pub fn is_noop(&self) -> bool {
+        self.evicted_tools.is_empty() && self.truncated_results == 0
+    }
+
+    /// JSON form used for telemetry annotations
+    pub fn to_json(&self) -> Value {
+        json!({
+            "evicted_tools": self.evicted_tools,
+            "evicted_count": self.evicted_tools.len(),
+            "truncated_results": self.truncated_results,
+            "dropped_chars": self.dropped_chars,
+            "pinned_entries": self.pinned_entries,
+            "ledger_lines": self.ledger_lines,
+        })
+    }
+}
+
+/// Summary lines for tool results (or messages) evicted from context.
+///
+/// Owned by the tool loop and rendered into the system prompt before each
+/// request, so it is sent once and is never itself counted or trimmed as
+/// conversation history.
+#[derive(Debug, Clone, Default)]
+pub struct ToolLedger {
+    lines: Vec<String>,
+}
+
+impl ToolLedger {
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Append lines, dropping the oldest beyond `max_lines`
+    fn extend(&mut self, lines: Vec<String>, max_lines: usize) {
+        self.lines.extend(lines);
+        if self.lines.len() > max_lines {
+            let overflow = self.lines.len() - max_lines;
+            self.lines.drain(0..overflow);
+        }
+    }
+
+    /// Write the ledger into the last leading system message, replacing any
+    /// earlier copy. Call before each request: the loops rewrite the system
+    /// prompt as modes change.
+    pub fn render_into(&self, conversation: &mut Vec<Message>) {
+        let system_count = conversation
+            .iter()
+            .take_while(|m| m.role == MessageRole::System)
+            .count();
+        if system_count == 0 {
+            if !self.is_empty() {
+                conversation.insert(0, Message {
+                    role: MessageRole::System,
+                    content: self.section().trim_start().to_string(),
+                    images: Vec::new(),
+                });
+            }
+            return;
+        }
+        let system = &mut conversation[system_count - 1];
+        if let Some(pos) = system.content.find(LEDGER_SECTION_HEADER) {
+            system.content.truncate(pos);
+        }
+        if !self.is_empty() {
+            system.content.push_str(&self.section());
+        }
+    }
+
+    fn section(&self) -> String {
+        format!("{}\n{}", LEDGER_SECTION_HEADER, self.lines.join("\n"))
+    }
+}
+
+impl ToolHistoryRetention {
+    /// Apply the retention policy to a native tool history in place.
+    ///
+    /// Evicted entries are summarized into `ledger`, so no tool call is ever
+    /// fabricated. `register_sources` holds the names of tools that currently
+    /// own a register entry (see `RegisterStore::source_tools`); their results
+    /// are pinned.
+    pub fn apply(
+        &self,
+        history: &mut Vec<ToolHistoryEntry>,
+        ledger: &mut ToolLedger,
+        register_sources: &HashSet<String>,
+    ) -> RetentionReport {
+        let mut report = RetentionReport::default();
+
+        let total = history.len();
+        let recent_start = total.saturating_sub(self.keep_recent);
+
+        // Truncate large results the model has already seen
+        for entry in history.iter_mut().take(recent_start) {
+            for response in entry.tool_responses.iter_mut() {
+                if response.content.len() > self.max_result_chars {
+                    let before = response.content.len();
+                    response.content = truncate_by_relevance(&response.content, self.max_result_chars);
+                    report.truncated_results += 1;
+                    report.dropped_chars += before.saturating_sub(response.content.len());
+                }
+            }
+        }
+
+        let pinned: Vec<bool> = history.iter().map(|e| is_pinned(e, register_sources)).collect();
+        let pinned_count = pinned.iter().filter(|p| **p).count();
+        let unpinned_count = total - pinned_count;
+
+        // Pick eviction victims among non-recent entries:
+        // low-value tools first, then oldest first.
+        let mut candidates: Vec<usize> = (0..recent_start).collect();
+        candidates.sort_by_key(|&i| (!is_low_value_entry(&history[i]), i));
+
+        let mut unpinned_excess = unpinned_count.saturating_sub(self.max_entries);
+        let mut pinned_excess = pinned_count.saturating_sub(self.max_pinned);
+        let mut evict: Vec<usize> = Vec::new();
+        for i in candidates {
+            if pinned[i] {
+                if pinned_excess > 0 {
+                    pinned_excess -= 1;
+                    evict.push(i);
+                }
+            } else if unpinned_excess > 0 {
+                unpinned_excess -= 1;
+                evict.push(i);
+            }
+        }
+        evict.sort_unstable();
+        for &i in &evict {
+            report.evicted_tools.extend(history[i].tool_calls.iter().map(|c| c.name.clone()));
+        }
+
+        // Remove victims back to front, summarizing them into the ledger in order
+        let mut evicted_lines: Vec<String> = Vec::new();
+        for &i in evict.iter().rev() {
+            let entry = history.remove(i);
+            let mut lines = Vec::new();
+            for call in &entry.tool_calls {
+                let response = entry.tool_responses.iter().find(|r| r.tool_call_id == call.id);
+                lines.push(self.ledger_line(call, response));
+            }
+            report.dropped_chars += entry.tool_responses.iter().map(|r| r.content.len()).sum::<usize>();
+            lines.extend(evicted_lines);
+            evicted_lines = lines;
+        }
+        ledger.extend(evicted_lines, self.max_ledger_lines);
+
+        report.pinned_entries = history
+            .iter()
+            .filter(|e| is_pinned(e, register_sources))
+            .count();
+        report.ledger_lines = ledger.len();
+
+        report
+    }
+
+    /// Apply the retention policy to a text-tool conversation in place.
+    ///
+    /// Keeps the leading system message(s), summarizes evicted messages into
+    /// `ledger`, and truncates large older tool follow-ups by relevance.
+    /// `max_messages` excludes system messages.
+    pub fn apply_to_conversation(
+        &self,
+        conversation: &mut Vec<Message>,
+        ledger: &mut ToolLedger,
+        max_messages: usize,
+    ) -> RetentionReport {
+        let mut report = RetentionReport::default();
+
+        let system_count = conversation
+            .iter()
+            .take_while(|m| m.role == MessageRole::System)
+            .count();
+
+        let body_len = conversation.len() - system_count;
+        let recent_start = system_count + body_len.saturating_sub(self.keep_recent * 2);
+
+        for msg in conversation[system_count..recent_start].iter_mut() {
+            if msg.role == MessageRole::User && msg.content.len() > self.max_result_chars {
+                let before = msg.content.len();
+                msg.content = truncate_by_relevance(&msg.content, self.max_result_chars);
+                report.truncated_results += 1;
+                report.dropped_chars += before.saturating_sub(msg.content.len());
+            }
+        }
+
+        if body_len > max_messages {
+            let mut remove_count = body_len - max_messages;
+            // The first kept message must be from the user to preserve role alternation
+            while system_count + remove_count < conversation.len()
+                && conversation[system_count + remove_count].role != MessageRole::User
+            {
+                remove_count += 1;
+            }
+            let mut evicted_lines = Vec::new();
+            for msg in conversation.drain(system_count..system_count + remove_count) {
+                report.dropped_chars += msg.content.len();
+                report.evicted_tools.push(msg.role.to_string());
+                evicted_lines.push(format!(
+                    "- {}: {}",
+                    msg.role.to_string(),
+                    preview(&msg.content, self.ledger_preview_chars)
+                ));
+            }
+            ledger.extend(evicted_lines, self.max_ledger_lines);
+        }
+        report.ledger_lines = ledger.len();
+
+        report
+    }
+
+    fn ledger_line(&self, call: &ToolCall, response: Option<&ToolResponse>) -> String {
+        let args = preview(&call.arguments.to_string(), 80);
+        match response {
+            Some(r) => format!(
+                "- {}({}) → {}: {}",
+                call.name,
+                args,
+                if r.is_error { "error" } else { "ok" },
+                preview(&r.content, self.ledger_preview_chars)
+            ),
+            None => format!("- {}({}) → no result", call.name, args),
+        }
+    }
+}
+
+/// An entry is pinned if one of its calls cached into a register, either
+/// explicitly via `cache_as` or because its tool currently owns a register.
+fn is_pinned(entry: &ToolHistoryEntry, register_sources: &HashSet<String>) -> bool {
+    entry.tool_calls.iter().any(|call| {
+        register_sources.contains(&call.name)
+            || call
+                .arguments
+                .get("cache_as")
+                .and_then(|v| v.as_str())
+                .map(|s| !s.is_empty())
+                .unwrap_or(false)
+    })
+}
+
+fn is_low_value_entry(entry: &ToolHistoryEntry) -> bool {
+    !entry.tool_calls.is_empty()
+        && entry
+            .tool_calls
+            .iter()
+            .all(|c| LOW_VALUE_TOOLS.contains(&c.name.as_str()))
+}
+
+/// Single-line preview of content, cut at a char boundary
+fn preview(content: &str, max_chars: usize) -> String {
+    let flat: String = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() <= max_chars {
+        flat
+    } else {
+        let cut: String = flat.chars().take(max_chars).collect();
+        format!("{}…", cut)
+    }
+}
+
+/// Score a line by how likely it is to carry facts the agent needs later
+/// (addresses, hashes, amounts, errors).
+fn line_relevance(line: &str) -> u32 {
+    let lower = line.to_lowercase();
+    let mut score = 0;
+    if lower.contains("0x") {
+        score += 3;
+    }
+    if lower.contains("error") || lower.contains("fail") || lower.contains("revert") {
+        score += 3;
+    }
+    for key in ["address", "amount", "price", "symbol", "hash", "balance", "token", "quote", "status"] {
+        if lower.contains(key) {
+            score += 2;
+            break;
+        }
+    }
+    if line.chars().any(|c| c.is_ascii_digit()) {
+        score += 1;
+    }
+    score
+}
+
+/// Cut a line to at most `max_chars` characters, noting how much was dropped
+fn clip_line(line: &str, max_chars: usize) -> String {
+    let total = line.chars().count();
+    if total <= max_chars {
+        return line.to_string();
+    }
+    let cut: String = line.chars().take(max_chars).collect();
+    format!("{}… [truncated {} chars]", cut, total - max_chars)
+}
+
+/// Truncate content to roughly `max_chars`, keeping the first and last lines
+/// (each clipped to a quarter of the budget) plus the most relevant lines in
+/// between, in their original order.
+pub fn truncate_by_relevance(content: &str, max_chars: usize) -> String {
+    if content.len() <= max_chars {
+        return content.to_string();
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.len() <= 2 {
+        return clip_line(content, max_chars);
+    }
+
+    let last = lines.len() - 1;
+    let first_line = clip_line(lines[0], max_chars / 4);
+    let last_line = clip_line(lines[last], max_chars / 4);
+    let mut keep = vec![false; lines.len()];
+    keep[0] = true;
+    keep[last] = true;
+    let mut budget = max_chars.saturating_sub(first_line.len() + last_line.len());
+
+    let mut ranked: Vec<usize> = (1..last).collect();
+    ranked.sort_by_key(|&i| (std::cmp::Reverse(line_relevance(lines[i])), i));
+    for i in ranked {
+        let cost = lines[i].len() + 1;
+        if cost <= budget {
+            budget -= cost;
+            keep[i] = true;
+        }
+    }
+
+    let mut out: Vec<String> = Vec::new();
+    let mut skipped = 0usize;
+    for (i, line) in lines.iter().enumerate() {
+        if keep[i] {
+            if skipped > 0 {
+                out.push(format!("… [{} lines omitted]", skipped));
+                skipped = 0;
+            }
+            out.push(match i {
+                0 => first_line.clone(),
+                i if i == last => last_line.clone(),
+                _ => line.to_string(),
+            });
+        } else {
+            skipped += 1;
+        }
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(content: &str) -> Message {
+        Message { role: MessageRole::User, content: content.to_string(), images: Vec::new() }
+    }
+
+    fn system(content: &str) -> Message {
+        Message { role: MessageRole::System, content: content.to_string(), images: Vec::new() }
+    }
+
+    fn entry(name: &str, args: Value, content: &str) -> ToolHistoryEntry {
+        let id = format!("call_{}", name);
+        ToolHistoryEntry::new(
+            vec![ToolCall { id: id.clone(), name: name.to_string(), arguments: args }],
+            vec![ToolResponse::success(id, content.to_string())],
+        )
+    }
+
+    #[test]
+    fn test_evicted_entries_are_summarized_into_ledger() {
+        let policy = ToolHistoryRetention { max_entries: 2, keep_recent: 1, ..Default::default() };
+        let mut history = vec![
+            entry("token_lookup", json!({"symbol": "USDC"}), "address: 0xabc"),
+            entry("web_search", json!({}), "results"),
+            entry("dexscreener", json!({}), "price 1.0"),
+        ];
+
+        let mut ledger = ToolLedger::default();
+
+        let report = policy.apply(&mut history, &mut ledger, &HashSet::new());
+
+        assert_eq!(report.evicted_tools, vec!["token_lookup".to_string()]);
+        assert_eq!(report.ledger_lines, 1);
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().all(|e| e.tool_calls[0].name != "token_lookup"));
+
+        // The ledger goes into the system prompt, not the message list
+        let mut conversation = vec![system("sys"), user("swap")];
+        ledger.render_into(&mut conversation);
+        assert_eq!(conversation.len(), 2);
+        assert!(conversation[0].content.starts_with("sys"));
+        assert!(conversation[0].content.contains("0xabc"));
+        assert_eq!(conversation[1].content, "swap");
+    }
+
+    #[test]
+    fn test_low_value_tools_evicted_first() {
+        let policy = ToolHistoryRetention { max_entries: 2, keep_recent: 1, ..Default::default() };
+        let mut history = vec![
+            entry("token_lookup", json!({}), "address: 0xabc"),
+            entry("list_files", json!({}), "a\nb\nc"),
+            entry("dexscreener", json!({}), "price 1.0"),
+        ];
+
+        let report = policy.apply(&mut history, &mut ToolLedger::default(), &HashSet::new());
+
+        assert_eq!(report.evicted_tools, vec!["list_files".to_string()]);
+        assert_eq!(history[0].tool_calls[0].name, "token_lookup");
+    }
+
+    #[test]
+    fn test_register_backed_entries_are_pinned() {
+        let policy = ToolHistoryRetention { max_entries: 1, keep_recent: 1, ..Default::default() };
+        let mut history = vec![
+            entry("to_raw_amount", json!({"cache_as": "amount"}), "1000000"),
+            entry("x402_fetch", json!({}), "{\"to\": \"0x1\"}"),
+            entry("web_search", json!({}), "results"),
+            entry("dexscreener", json!({}), "price"),
+        ];
+        let mut sources = HashSet::new();
+        sources.insert("x402_fetch".to_string());
+
+        let report = policy.apply(&mut history, &mut ToolLedger::default(), &sources);
+
+        assert_eq!(report.pinned_entries, 2);
+        assert_eq!(report.evicted_tools, vec!["web_search".to_string()]);
+        let names: Vec<&str> = history.iter().map(|e| e.tool_calls[0].name.as_str()).collect();
+        assert_eq!(names, vec!["to_raw_amount", "x402_fetch", "dexscreener"]);
+    }
+
+    #[test]
+    fn test_ledger_survives_multiple_passes() {
+        let policy = ToolHistoryRetention { max_entries: 1, keep_recent: 1, ..Default::default() };
+        let mut history = vec![entry("a", json!({}), "first"), entry("b", json!({}), "second")];
+        let mut ledger = ToolLedger::default();
+        let mut conversation = vec![system("sys"), user("request")];
+        policy.apply(&mut history, &mut ledger, &HashSet::new());
+        ledger.render_into(&mut conversation);
+        // The loop rewrites the system prompt and adds notes in between
+        conversation[0].content = "new sys".to_string();
+        conversation.push(user("[SYSTEM] keep going"));
+        history.push(entry("c", json!({}), "third"));
+        policy.apply(&mut history, &mut ledger, &HashSet::new());
+        ledger.render_into(&mut conversation);
+        ledger.render_into(&mut conversation);
+
+        assert_eq!(conversation.len(), 3);
+        let prompt = &conversation[0].content;
+        assert!(prompt.starts_with("new sys"));
+        assert_eq!(prompt.matches(LEDGER_SECTION_HEADER).count(), 1);
+        assert!(prompt.contains("first"));
+        assert!(prompt.contains("second"));
+        assert_eq!(ledger.len(), 2);
+        assert_eq!(history.len(), 1);
+    }
+
+    #[test]
+    fn test_truncate_by_relevance_keeps_important_lines() {
+        let mut content = String::from("header\n");
+        for i in 0..200 {
+            content.push_str(&format!("noise line number filler text {}\n", i));
+        }
+        content.push_str("token address: 0xdeadbeef\n");
+        content.push_str("footer");
+
+        let truncated = truncate_by_relevance(&content, 200);
+
+        assert!(truncated.len() < content.len());
+        assert!(truncated.starts_with("header"));
+        assert!(truncated.ends_with("footer"));
+        assert!(truncated.contains("0xdeadbeef"));
+        assert!(truncated.contains("lines omitted"));
+    }
+
+    #[test]
+    fn test_truncate_by_relevance_bounds_long_lines() {
+        // A single huge line (e.g. minified JSON) is cut to the budget
+        let single = format!("{{\"data\": \"{}\"}}", "a".repeat(50_000));
+        let truncated = truncate_by_relevance(&single, 4000);
+        assert!(truncated.len() < 4100);
+        assert!(truncated.starts_with("{\"data\""));
+        assert!(truncated.contains("truncated"));
+
+        // Huge first and last lines don't blow the budget either
+        let content = format!("{}\nstatus: ok\n{}", "h".repeat(50_000), "f".repeat(50_000));
+        let truncated = truncate_by_relevance(&content, 4000);
+        assert!(truncated.len() < 4100);
+        assert!(truncated.contains("status: ok"));
+    }
+
+    #[test]
+    fn test_conversation_retention_keeps_alternation() {
+        let policy = ToolHistoryRetention::default();
+        let mut conversation = vec![Message { role: MessageRole::System, content: "sys".into(), images: Vec::new() }];
+        conversation.push(Message { role: MessageRole::User, content: "request".into(), images: Vec::new() });
+        for i in 0..6 {
+            conversation.push(Message { role: MessageRole::Assistant, content: format!("call {}", i), images: Vec::new() });
+            conversation.push(Message { role: MessageRole::User, content: format!("result {}", i), images: Vec::new() });
+        }
+
+        let mut ledger = ToolLedger::default();
+        let report = policy.apply_to_conversation(&mut conversation, &mut ledger, 4);
+
+        assert!(!report.evicted_tools.is_empty());
+        assert_eq!(conversation[0].role, MessageRole::System);
+        assert_eq!(conversation[1].role, MessageRole::User);
+        assert_eq!(conversation.len(), 4);
+
+        // A later pass only counts the messages, never the ledger
+        let report = policy.apply_to_conversation(&mut conversation, &mut ledger, 4);
+        assert!(report.is_noop());
+        ledger.render_into(&mut conversation);
+        assert_eq!(conversation.len(), 4);
+        assert!(conversation[0].content.contains("request"));
+    }
+}
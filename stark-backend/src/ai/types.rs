@@ -347,6 +347,17 @@ pub enum ClaudeContentBlock {
         #[serde(skip_serializing_if = "Option::is_none")]
         is_error: Option<bool>,
     },
+    #[serde(rename = "image")]
+    Image { source: ClaudeImageSource },
+}
+
+/// Inline image source for Claude image blocks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaudeImageSource {
+    #[serde(rename = "type")]
+    pub source_type: String,
+    pub media_type: String,
+    pub data: String,
 }
 
 impl ClaudeContentBlock {
@@ -354,6 +365,16 @@ impl ClaudeContentBlock {
         ClaudeContentBlock::Text { text: text.into() }
     }
 
+    pub fn image(image: &crate::ai::MessageImage) -> Self {
+        ClaudeContentBlock::Image {
+            source: ClaudeImageSource {
+                source_type: "base64".to_string(),
+                media_type: image.media_type.clone(),
+                data: image.data.clone(),
+            },
+        }
+    }
+
     pub fn tool_result(tool_use_id: String, content: String, is_error: bool) -> Self {
         ClaudeContentBlock::ToolResult {
             tool_use_id,
@@ -12,6 +12,9 @@ pub struct AiEndpointPreset {
     pub model_archetype: String,
     #[serde(default)]
     pub x402_cost: Option<u64>,
+    /// Whether the model behind this endpoint accepts images (unset = archetype default)
+    #[serde(default)]
+    pub supports_vision: Option<bool>,
 }
 
 pub fn load_ai_endpoints(config_dir: &Path) {
@@ -57,6 +60,7 @@ fn default_endpoints() -> HashMap<String, AiEndpointPreset> {
             endpoint: "https://kimi.defirelay.com/api/v1/chat/completions".to_string(),
             model_archetype: "kimi".to_string(),
             x402_cost: None,
+            supports_vision: None,
         },
     );
     endpoints.insert(
@@ -66,6 +70,7 @@ fn default_endpoints() -> HashMap<String, AiEndpointPreset> {
             endpoint: "https://llama.defirelay.com/api/v1/chat/completions".to_string(),
             model_archetype: "llama".to_string(),
             x402_cost: None,
+            supports_vision: None,
         },
     );
     endpoints
@@ -84,3 +89,15 @@ pub fn list_ai_endpoints() -> Vec<(String, AiEndpointPreset)> {
         })
         .unwrap_or_default()
 }
+
+/// Find the preset whose URL is `endpoint`, if the endpoint came from ai_endpoints.ron
+pub fn find_ai_endpoint_by_url(endpoint: &str) -> Option<AiEndpointPreset> {
+    AI_ENDPOINTS
+        .get()
+        .and_then(|endpoints| endpoints.values().find(|p| p.endpoint == endpoint).cloned())
+}
+
+/// Look up a single endpoint preset by its ai_endpoints.ron key
+pub fn get_ai_endpoint(key: &str) -> Option<AiEndpointPreset> {
+    AI_ENDPOINTS.get().and_then(|endpoints| endpoints.get(key).cloned())
+}
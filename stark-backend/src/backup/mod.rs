@@ -170,6 +170,9 @@ pub struct MindNodeEntry {
 pub struct MindConnectionEntry {
     pub parent_id: i64,
     pub child_id: i64,
+    /// Edge type (empty in older backups, restored as part_of)
+    pub edge_type: String,
+    pub weight: Option<f64>,
 }
 
 /// Cron job entry in backup
@@ -292,6 +295,9 @@ pub struct SkillEntry {
     /// requires_api_keys serialized as JSON string
     #[serde(default)]
     pub requires_api_keys: String,
+    /// Workflow RON source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<String>,
     pub scripts: Vec<SkillScriptEntry>,
 }
 
@@ -316,6 +322,8 @@ pub struct AgentSettingsEntry {
     /// Secret key is included so the user doesn't have to re-enter API keys after restore.
     /// The entire backup payload is already encrypted with ECIES — this is not stored in plaintext.
     pub secret_key: Option<String>,
+    /// Vision override (None = decide from the endpoint)
+    pub supports_vision: Option<bool>,
 }
 
 /// On-chain agent identity registration entry in backup (full metadata — DB is single source of truth)
@@ -359,6 +367,11 @@ pub struct KanbanItemEntry {
     pub result: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    /// Blocking item IDs (as they were in the backed-up board)
+    pub blocked_by: Vec<i64>,
+    pub max_retries: i32,
+    pub timeout_seconds: Option<i64>,
+    pub agent_subtype: Option<String>,
 }
 
 /// Options for what to include in a backup
@@ -436,6 +449,8 @@ pub async fn collect_backup_data(
             .map(|c| MindConnectionEntry {
                 parent_id: c.parent_id,
                 child_id: c.child_id,
+                edge_type: c.edge_type.as_str().to_string(),
+                weight: c.weight,
             })
             .collect();
     }
@@ -595,6 +610,7 @@ pub async fn collect_backup_data(
                 subagent_type: skill.subagent_type,
                 requires_api_keys: serde_json::to_string(&skill.requires_api_keys)
                     .unwrap_or_default(),
+                workflow: skill.workflow,
                 scripts,
             });
         }
@@ -611,6 +627,7 @@ pub async fn collect_backup_data(
                 max_context_tokens: s.max_context_tokens,
                 enabled: s.enabled,
                 secret_key: s.secret_key.clone(),
+                supports_vision: s.supports_vision,
             })
             .collect();
     }
@@ -643,6 +660,10 @@ pub async fn collect_backup_data(
                 result: i.result.clone(),
                 created_at: i.created_at.to_rfc3339(),
                 updated_at: i.updated_at.to_rfc3339(),
+                blocked_by: i.blocked_by.clone(),
+                max_retries: i.max_retries,
+                timeout_seconds: i.timeout_seconds,
+                agent_subtype: i.agent_subtype.clone(),
             })
             .collect();
     }
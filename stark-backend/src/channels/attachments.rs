@@ -0,0 +1,549 @@
+//! Inbound file attachments (photos, documents, uploads)
+//!
+//! Channel adapters describe platform files as `Attachment`s and download them
+//! into `<workspace>/attachments/<channel_id>/`, size-limited and under the disk
+//! quota. The dispatcher lists every attachment in the stored user message — so
+//! the agent can open cached files with `read_file` — and passes images as
+//! multimodal content to archetypes that support vision.
+//!
+//! Outbound, `say_to_user` queues workspace files on the tool context's
+//! `OutboundFiles`; the dispatcher returns them in `DispatchResult::attachments`
+//! and each adapter uploads them natively within its platform's size limit.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::ai::MessageImage;
+use crate::channels::types::ChannelType;
+use crate::disk_quota::{format_bytes, DiskQuotaManager};
+use crate::tools::types::ChannelOutputType;
+
+/// Workspace subdirectory for cached attachments
+pub const ATTACHMENTS_DIR: &str = "attachments";
+
+/// Largest file that will be downloaded (20 MB)
+pub const MAX_ATTACHMENT_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Largest image passed to a model as a content block (5 MB, the Anthropic API limit)
+pub const MAX_VISION_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Maximum attachments processed per message; extras are listed but not downloaded
+pub const MAX_ATTACHMENTS_PER_MESSAGE: usize = 10;
+
+/// Largest image Telegram accepts through sendPhoto; bigger images go as documents
+pub const TELEGRAM_MAX_PHOTO_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Image types accepted by both the Anthropic and OpenAI vision APIs
+const VISION_MIME_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// A file attached to an inbound message
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Attachment {
+    /// Original file name (or a generated one for unnamed photos)
+    pub file_name: String,
+    /// MIME type, e.g. "image/jpeg" (falls back to "application/octet-stream")
+    pub mime_type: String,
+    /// Size in bytes (as reported by the platform until downloaded)
+    pub size_bytes: u64,
+    /// Platform file identifier (Telegram file_id, Discord attachment ID, Slack file ID)
+    #[serde(default)]
+    pub platform_file_id: Option<String>,
+    /// Cached copy, relative to the workspace directory
+    #[serde(default)]
+    pub local_path: Option<String>,
+    /// Why the file was not cached (too large, quota, download failure)
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl Attachment {
+    pub fn new(
+        file_name: impl Into<String>,
+        mime_type: Option<String>,
+        size_bytes: u64,
+        platform_file_id: Option<String>,
+    ) -> Self {
+        let file_name = file_name.into();
+        let mime_type = mime_type
+            .filter(|m| !m.trim().is_empty())
+            .unwrap_or_else(|| guess_mime_type(&file_name).to_string());
+        Self {
+            file_name,
+            mime_type,
+            size_bytes,
+            platform_file_id,
+            local_path: None,
+            error: None,
+        }
+    }
+
+    pub fn is_image(&self) -> bool {
+        self.mime_type.starts_with("image/")
+    }
+
+    /// Describe an existing workspace file for outbound delivery.
+    /// The path must resolve inside the workspace; `local_path` is stored relative to it.
+    pub fn from_workspace_file(workspace_dir: &str, requested: &str) -> Result<Self, String> {
+        let workspace = Path::new(workspace_dir)
+            .canonicalize()
+            .map_err(|e| format!("Cannot resolve workspace directory: {}", e))?;
+        let requested_path = Path::new(requested);
+        let full_path = if requested_path.is_absolute() {
+            requested_path.to_path_buf()
+        } else {
+            workspace.join(requested_path)
+        };
+        let canonical = full_path
+            .canonicalize()
+            .map_err(|_| format!("File not found: {}", requested))?;
+        let relative = canonical
+            .strip_prefix(&workspace)
+            .map_err(|_| format!("Access denied: '{}' is outside the workspace directory", requested))?;
+        let metadata = std::fs::metadata(&canonical).map_err(|e| format!("Cannot read '{}': {}", requested, e))?;
+        if !metadata.is_file() {
+            return Err(format!("Path is not a file: {}", requested));
+        }
+
+        let file_name = canonical
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("file")
+            .to_string();
+        let mut attachment = Attachment::new(file_name, None, metadata.len(), None);
+        attachment.local_path = Some(relative.to_string_lossy().to_string());
+        Ok(attachment)
+    }
+
+    /// Absolute path of the cached/workspace copy
+    pub fn absolute_path(&self) -> Option<PathBuf> {
+        self.local_path
+            .as_ref()
+            .map(|p| Path::new(&crate::config::workspace_dir()).join(p))
+    }
+
+    /// Image Telegram can show inline via sendPhoto
+    pub fn is_telegram_photo(&self) -> bool {
+        matches!(self.mime_type.as_str(), "image/png" | "image/jpeg" | "image/webp")
+            && self.size_bytes <= TELEGRAM_MAX_PHOTO_BYTES
+    }
+
+    /// Cached image small enough, and of a type, that vision models accept
+    pub fn is_vision_image(&self) -> bool {
+        self.local_path.is_some()
+            && VISION_MIME_TYPES.contains(&self.mime_type.as_str())
+            && self.size_bytes <= MAX_VISION_IMAGE_BYTES
+    }
+}
+
+/// Guess a MIME type from the file extension
+fn guess_mime_type(file_name: &str) -> &'static str {
+    let ext = Path::new(file_name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "pdf" => "application/pdf",
+        "txt" | "log" => "text/plain",
+        "md" => "text/markdown",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Reduce a platform file name to a safe single path component
+pub fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    let cleaned = cleaned.trim_matches('.').chars().take(100).collect::<String>();
+    if cleaned.is_empty() {
+        "file".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// Download an attachment into the workspace cache.
+/// Failures are recorded on the attachment instead of returned, so one bad file
+/// never drops the message it came with.
+pub async fn download(
+    mut attachment: Attachment,
+    url: &str,
+    bearer_token: Option<&str>,
+    channel_id: i64,
+    disk_quota: Option<&DiskQuotaManager>,
+) -> Attachment {
+    if attachment.size_bytes > MAX_ATTACHMENT_BYTES {
+        attachment.error = Some(format!("exceeds the {} limit", format_bytes(MAX_ATTACHMENT_BYTES)));
+        return attachment;
+    }
+    if let Some(dq) = disk_quota
+        && let Err(e) = dq.check_quota(attachment.size_bytes)
+    {
+        attachment.error = Some(e.to_string());
+        return attachment;
+    }
+
+    let bytes = match fetch(url, bearer_token).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log::warn!("[ATTACHMENTS] Failed to download {}: {}", attachment.file_name, e);
+            attachment.error = Some(e);
+            return attachment;
+        }
+    };
+    if let Some(dq) = disk_quota
+        && let Err(e) = dq.check_quota(bytes.len() as u64)
+    {
+        attachment.error = Some(e.to_string());
+        return attachment;
+    }
+
+    let relative = format!(
+        "{}/{}/{}-{}",
+        ATTACHMENTS_DIR,
+        channel_id,
+        &uuid::Uuid::new_v4().simple().to_string()[..8],
+        sanitize_file_name(&attachment.file_name)
+    );
+    let full_path = Path::new(&crate::config::workspace_dir()).join(&relative);
+    if let Some(parent) = full_path.parent()
+        && let Err(e) = tokio::fs::create_dir_all(parent).await
+    {
+        attachment.error = Some(format!("Failed to create attachments directory: {}", e));
+        return attachment;
+    }
+    if let Err(e) = tokio::fs::write(&full_path, &bytes).await {
+        attachment.error = Some(format!("Failed to save file: {}", e));
+        return attachment;
+    }
+    if let Some(dq) = disk_quota {
+        dq.record_write(bytes.len() as u64);
+    }
+
+    log::info!(
+        "[ATTACHMENTS] Cached {} ({}, {}) at {}",
+        attachment.file_name,
+        attachment.mime_type,
+        format_bytes(bytes.len() as u64),
+        relative
+    );
+    attachment.size_bytes = bytes.len() as u64;
+    attachment.local_path = Some(relative);
+    attachment
+}
+
+/// Fetch a file, aborting once it grows past the size limit
+async fn fetch(url: &str, bearer_token: Option<&str>) -> Result<Vec<u8>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(60))
+        .build()
+        .map_err(|e| format!("HTTP client error: {}", e))?;
+    let mut request = client.get(url);
+    if let Some(token) = bearer_token {
+        request = request.bearer_auth(token);
+    }
+    // Strip URLs from errors: some platforms (Telegram) embed the bot token in file URLs
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| format!("Download failed: {}", e.without_url()))?;
+    if !response.status().is_success() {
+        return Err(format!("Download failed: HTTP {}", response.status()));
+    }
+    if response.content_length().unwrap_or(0) > MAX_ATTACHMENT_BYTES {
+        return Err(format!("exceeds the {} limit", format_bytes(MAX_ATTACHMENT_BYTES)));
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Download failed: {}", e.without_url()))?
+    {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() as u64 > MAX_ATTACHMENT_BYTES {
+            return Err(format!("exceeds the {} limit", format_bytes(MAX_ATTACHMENT_BYTES)));
+        }
+    }
+    Ok(bytes)
+}
+
+/// Attachment listing appended to the user message text
+pub fn describe_attachments(attachments: &[Attachment]) -> String {
+    let mut out = String::from("[ATTACHMENTS]\n");
+    for a in attachments {
+        let meta = format!("{} ({}, {})", a.file_name, a.mime_type, format_bytes(a.size_bytes));
+        match (&a.local_path, &a.error) {
+            (Some(path), _) => out.push_str(&format!("- {} saved at {}\n", meta, path)),
+            (None, Some(err)) => out.push_str(&format!("- {} — not downloaded: {}\n", meta, err)),
+            (None, None) => out.push_str(&format!("- {} — not downloaded\n", meta)),
+        }
+    }
+    out.push_str("Saved files are in the workspace; use read_file with the path to open text-based files.");
+    out
+}
+
+/// Append the attachment listing to a message (unchanged if there are none)
+pub fn append_to_text(text: &str, attachments: &[Attachment]) -> String {
+    if attachments.is_empty() {
+        text.to_string()
+    } else if text.trim().is_empty() {
+        describe_attachments(attachments)
+    } else {
+        format!("{}\n\n{}", text, describe_attachments(attachments))
+    }
+}
+
+/// Files queued by tools during one dispatch, delivered with the final response.
+/// Clones share the same queue, so tools can push through `&ToolContext`.
+#[derive(Debug, Clone, Default)]
+pub struct OutboundFiles {
+    inner: Arc<Mutex<Vec<Attachment>>>,
+}
+
+impl OutboundFiles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&self, attachment: Attachment) {
+        if let Ok(mut files) = self.inner.lock() {
+            files.push(attachment);
+        }
+    }
+
+    /// Remove and return everything queued so far
+    pub fn take(&self) -> Vec<Attachment> {
+        self.inner
+            .lock()
+            .map(|mut files| std::mem::take(&mut *files))
+            .unwrap_or_default()
+    }
+}
+
+/// Split outbound files into those the channel can deliver and notes for the rest.
+/// Rich (web) channels return files with the chat response rather than uploading
+/// them, so only the per-message count applies; text-only channels upload natively
+/// within the platform's size limit, or cannot receive files at all.
+pub fn split_for_upload(
+    files: Vec<Attachment>,
+    channel_type: &str,
+    output_type: ChannelOutputType,
+) -> (Vec<Attachment>, Vec<String>) {
+    let files = files.into_iter().take(MAX_ATTACHMENTS_PER_MESSAGE);
+    if output_type == ChannelOutputType::RichHtml {
+        return (files.collect(), Vec::new());
+    }
+    let platform = ChannelType::from_str(channel_type);
+    let display_name = platform.map(|c| c.display_name()).unwrap_or(channel_type);
+    let mut uploadable = Vec::new();
+    let mut skipped = Vec::new();
+    for file in files {
+        match platform.and_then(|c| c.max_upload_bytes()) {
+            Some(limit) if file.size_bytes <= limit => uploadable.push(file),
+            Some(limit) => skipped.push(format!(
+                "📎 {} ({}) exceeds the {} upload limit of {}",
+                file.file_name,
+                format_bytes(file.size_bytes),
+                display_name,
+                format_bytes(limit)
+            )),
+            None => skipped.push(format!(
+                "📎 {} could not be sent: {} does not support file uploads",
+                file.file_name, display_name
+            )),
+        }
+    }
+    (uploadable, skipped)
+}
+
+/// Upload a workspace file to a Slack channel with the files.upload v2 flow
+/// (files.getUploadURLExternal → upload → files.completeUploadExternal).
+pub async fn upload_slack_file(
+    client: &reqwest::Client,
+    bot_token: &str,
+    channel: &str,
+    thread_ts: Option<&str>,
+    attachment: &Attachment,
+) -> Result<(), String> {
+    let path = attachment
+        .absolute_path()
+        .ok_or_else(|| format!("{} has no local copy", attachment.file_name))?;
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", attachment.file_name, e))?;
+
+    let length = bytes.len().to_string();
+    let ticket: serde_json::Value = client
+        .get("https://slack.com/api/files.getUploadURLExternal")
+        .bearer_auth(bot_token)
+        .query(&[("filename", attachment.file_name.as_str()), ("length", length.as_str())])
+        .send()
+        .await
+        .map_err(|e| format!("Slack upload request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid Slack response: {}", e))?;
+    if !ticket.get("ok").and_then(|v| v.as_bool()).unwrap_or(false) {
+        return Err(format!(
+            "Slack API error: {}",
+            ticket.get("error").and_then(|v| v.as_str()).unwrap_or("unknown error")
+        ));
+    }
+    let (Some(upload_url), Some(file_id)) = (
+        ticket.get("upload_url").and_then(|v| v.as_str()),
+        ticket.get("file_id").and_then(|v| v.as_str()),
+    ) else {
+        return Err("Slack API error: missing upload_url".to_string());
+    };
+
+    let upload = client
+        .post(upload_url)
+        .body(bytes)
+        .send()
+        .await
+        .map_err(|e| format!("Slack upload failed: {}", e.without_url()))?;
+    if !upload.status().is_success() {
+        return Err(format!("Slack upload failed: HTTP {}", upload.status()));
+    }
+
+    let mut body = serde_json::json!({
+        "files": [{ "id": file_id, "title": attachment.file_name }],
+        "channel_id": channel,
+    });
+    if let Some(ts) = thread_ts {
+        body["thread_ts"] = serde_json::json!(ts);
+    }
+    let completed: serde_json::Value = client
+        .post("https://slack.com/api/files.completeUploadExternal")
+        .bearer_auth(bot_token)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Slack upload request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid Slack response: {}", e))?;
+    if !completed.get("ok").and_then(|v| v.as_bool()).unwrap_or(false) {
+        return Err(format!(
+            "Slack API error: {}",
+            completed.get("error").and_then(|v| v.as_str()).unwrap_or("unknown error")
+        ));
+    }
+    Ok(())
+}
+
+/// Load cached images as base64 content for vision-capable models
+pub async fn load_vision_images(attachments: &[Attachment]) -> Vec<MessageImage> {
+    let workspace = crate::config::workspace_dir();
+    let mut images = Vec::new();
+    for a in attachments.iter().filter(|a| a.is_vision_image()) {
+        let Some(ref path) = a.local_path else { continue };
+        match tokio::fs::read(Path::new(&workspace).join(path)).await {
+            Ok(bytes) => images.push(MessageImage {
+                media_type: a.mime_type.clone(),
+                data: base64::engine::general_purpose::STANDARD.encode(bytes),
+            }),
+            Err(e) => log::warn!("[ATTACHMENTS] Failed to load image {}: {}", path, e),
+        }
+    }
+    images
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("report 2024.pdf"), "report_2024.pdf");
+        assert_eq!(sanitize_file_name("../../etc/passwd"), "_.._etc_passwd");
+        assert_eq!(sanitize_file_name("..."), "file");
+    }
+
+    #[test]
+    fn test_mime_fallback_and_vision_eligibility() {
+        let mut photo = Attachment::new("photo.JPG", None, 1024, Some("abc".to_string()));
+        assert_eq!(photo.mime_type, "image/jpeg");
+        assert!(photo.is_image());
+        assert!(!photo.is_vision_image(), "not cached yet");
+
+        photo.local_path = Some("attachments/1/abcd1234-photo.JPG".to_string());
+        assert!(photo.is_vision_image());
+
+        photo.size_bytes = MAX_VISION_IMAGE_BYTES + 1;
+        assert!(!photo.is_vision_image());
+
+        let svg = Attachment::new("logo.svg", Some("image/svg+xml".to_string()), 10, None);
+        assert!(svg.is_image());
+        assert!(!svg.is_vision_image());
+    }
+
+    #[test]
+    fn test_append_to_text_lists_paths_and_errors() {
+        let mut saved = Attachment::new("notes.txt", None, 2048, None);
+        saved.local_path = Some("attachments/3/0a1b2c3d-notes.txt".to_string());
+        let mut skipped = Attachment::new("dump.zip", Some("application/zip".to_string()), 0, None);
+        skipped.error = Some("exceeds the 20.0MB limit".to_string());
+
+        let text = append_to_text("summarize this", &[saved, skipped]);
+        assert!(text.starts_with("summarize this\n\n[ATTACHMENTS]"));
+        assert!(text.contains("notes.txt (text/plain, 2.0KB) saved at attachments/3/0a1b2c3d-notes.txt"));
+        assert!(text.contains("dump.zip (application/zip, 0B) — not downloaded: exceeds the 20.0MB limit"));
+        assert_eq!(append_to_text("hi", &[]), "hi");
+    }
+
+    #[test]
+    fn test_from_workspace_file_stays_in_workspace() {
+        let workspace = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(workspace.path().join("charts")).unwrap();
+        std::fs::write(workspace.path().join("charts/price.png"), b"png").unwrap();
+        let ws = workspace.path().to_str().unwrap();
+
+        let file = Attachment::from_workspace_file(ws, "charts/price.png").unwrap();
+        assert_eq!(file.file_name, "price.png");
+        assert_eq!(file.mime_type, "image/png");
+        assert_eq!(file.size_bytes, 3);
+        assert_eq!(file.local_path.as_deref(), Some("charts/price.png"));
+
+        assert!(Attachment::from_workspace_file(ws, "missing.csv").is_err());
+        assert!(Attachment::from_workspace_file(ws, "charts").is_err());
+        assert!(Attachment::from_workspace_file(ws, "../../../etc/hostname").is_err());
+    }
+
+    #[test]
+    fn test_split_for_upload_respects_platform_limits() {
+        let small = Attachment::new("a.csv", None, 1024, None);
+        let big = Attachment::new("b.zip", None, 30 * 1024 * 1024, None);
+        let text = ChannelOutputType::TextOnly;
+
+        let (ok, skipped) = split_for_upload(vec![small.clone(), big.clone()], "discord", text);
+        assert_eq!(ok.len(), 1);
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].contains("b.zip"));
+
+        let (ok, skipped) = split_for_upload(vec![small.clone(), big.clone()], "telegram", text);
+        assert_eq!(ok.len(), 2);
+        assert!(skipped.is_empty());
+
+        let (ok, skipped) = split_for_upload(vec![small.clone()], "twitter", text);
+        assert!(ok.is_empty());
+        assert_eq!(skipped.len(), 1);
+
+        // The web UI gets files with the response, not through a platform upload
+        let (ok, skipped) = split_for_upload(vec![small, big], "web", ChannelOutputType::for_channel("web"));
+        assert_eq!(ok.len(), 2);
+        assert!(skipped.is_empty());
+    }
+}
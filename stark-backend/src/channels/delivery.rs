@@ -0,0 +1,322 @@
+//! Outbound delivery of agent output to a configured channel
+//!
+//! Triggers that run outside a chat (inbound webhooks, cron jobs) post the
+//! agent's response into a Telegram chat, Discord channel or DM, Slack channel,
+//! an external channel's callback URL, or an email address. Chat platforms get
+//! the text split to their message length limit (and truncated past
+//! `MAX_CHUNKS` messages); each send is retried on network errors, rate limits
+//! and server errors.
+
+use std::future::Future;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::channels::email::{self, EmailConfig};
+use crate::channels::types::ChannelType;
+use crate::channels::util;
+use crate::db::Database;
+use crate::models::ChannelSettingKey;
+
+/// Attempts per message before giving up
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry (doubles each attempt)
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+
+/// Most chat messages sent for one delivery; longer output is truncated
+const MAX_CHUNKS: usize = 10;
+
+/// A failed send, and whether trying again could help
+struct SendError {
+    message: String,
+    retryable: bool,
+}
+
+impl SendError {
+    fn retryable(message: String) -> Self {
+        Self { message, retryable: true }
+    }
+
+    fn fatal(message: String) -> Self {
+        Self { message, retryable: false }
+    }
+
+    /// Rate limits and server errors are worth retrying; other 4xx are not
+    fn from_status(status: reqwest::StatusCode, message: String) -> Self {
+        Self {
+            message,
+            retryable: status.as_u16() == 429 || status.is_server_error(),
+        }
+    }
+}
+
+/// Send text to a chat on a configured channel.
+///
+/// `deliver_to` is the chat/channel/address (Telegram chat ID, Discord channel ID
+/// or `dm:<user id>`, Slack channel, email address); external channels may leave
+/// it empty. `subject` titles emails and labels callbacks.
+pub async fn deliver_text(
+    db: &Database,
+    channel_id: i64,
+    deliver_to: &str,
+    subject: &str,
+    text: &str,
+) -> Result<(), String> {
+    let channel = db
+        .get_channel(channel_id)
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| format!("Channel {} not found", channel_id))?;
+    let channel_type = ChannelType::from_str(&channel.channel_type)
+        .ok_or_else(|| format!("Unknown channel type '{}'", channel.channel_type))?;
+    let deliver_to = deliver_to.trim();
+    if deliver_to.is_empty() && channel_type != ChannelType::ExternalChannel {
+        return Err(format!("A recipient is required for {} delivery", channel_type.display_name()));
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("HTTP client error: {}", e))?;
+
+    match channel_type {
+        ChannelType::Telegram => {
+            for chunk in chunk_for_platform(text, 4096) {
+                with_retries("Telegram", || send_telegram(&client, &channel.bot_token, deliver_to, &chunk)).await?;
+            }
+        }
+        ChannelType::Discord => {
+            let target = match deliver_to.strip_prefix("dm:") {
+                Some(user_id) => {
+                    with_retries("Discord", || open_discord_dm(&client, &channel.bot_token, user_id)).await?
+                }
+                None => deliver_to.to_string(),
+            };
+            for chunk in chunk_for_platform(text, 2000) {
+                with_retries("Discord", || send_discord(&client, &channel.bot_token, &target, &chunk)).await?;
+            }
+        }
+        ChannelType::Slack => {
+            for chunk in chunk_for_platform(text, 4000) {
+                with_retries("Slack", || send_slack(&client, &channel.bot_token, deliver_to, &chunk)).await?;
+            }
+        }
+        ChannelType::ExternalChannel => {
+            let setting = |key: ChannelSettingKey| {
+                db.get_channel_setting(channel_id, key.as_ref())
+                    .ok()
+                    .flatten()
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+            };
+            let url = setting(ChannelSettingKey::ExternalChannelCallbackUrl)
+                .ok_or_else(|| "External channel has no callback URL configured".to_string())?;
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                return Err(format!("Invalid callback URL '{}'", url));
+            }
+            let token = setting(ChannelSettingKey::ExternalChannelApiToken);
+            let body = json!({
+                "channel_id": channel_id,
+                "deliver_to": deliver_to,
+                "source": subject,
+                "text": text,
+            });
+            with_retries("Callback", || send_callback(&client, &url, token.as_deref(), &body)).await?;
+        }
+        ChannelType::Email => {
+            let config = EmailConfig::from_channel(&channel, db)?;
+            with_retries("Email", || send_mail(&config, deliver_to, subject, text)).await?;
+        }
+        other => return Err(format!("Delivery to {} channels is not supported", other.display_name())),
+    }
+
+    log::info!(
+        "[DELIVERY] Delivered {} chars to {} channel {} ({})",
+        text.len(),
+        channel_type,
+        channel_id,
+        deliver_to
+    );
+    Ok(())
+}
+
+/// Split text to a platform's message limit, truncating after `MAX_CHUNKS` messages
+fn chunk_for_platform(text: &str, max_len: usize) -> Vec<String> {
+    let mut chunks = util::split_message(text, max_len);
+    if chunks.len() > MAX_CHUNKS {
+        let omitted: usize = chunks[MAX_CHUNKS..].iter().map(|c| c.chars().count()).sum();
+        chunks.truncate(MAX_CHUNKS);
+        let note = format!("\n… (truncated, {} more characters)", omitted);
+        if let Some(last) = chunks.last_mut() {
+            while !last.is_empty() && last.len() + note.len() > max_len {
+                last.pop();
+            }
+            last.push_str(&note);
+        }
+    }
+    chunks
+}
+
+/// Run a send, retrying retryable failures with exponential backoff
+async fn with_retries<T, F, Fut>(platform: &str, mut send: F) -> Result<T, String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, SendError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match send().await {
+            Ok(value) => return Ok(value),
+            Err(e) if e.retryable && attempt < MAX_ATTEMPTS => {
+                let delay = RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+                log::warn!(
+                    "[DELIVERY] {} send failed (attempt {}/{}), retrying in {:?}: {}",
+                    platform, attempt, MAX_ATTEMPTS, delay, e.message
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e.message),
+        }
+    }
+}
+
+async fn send_telegram(client: &reqwest::Client, token: &str, chat_id: &str, text: &str) -> Result<(), SendError> {
+    let response = client
+        .post(format!("https://api.telegram.org/bot{}/sendMessage", token))
+        .json(&json!({ "chat_id": chat_id, "text": text }))
+        .send()
+        .await
+        // The URL contains the bot token
+        .map_err(|e| SendError::retryable(format!("Telegram request failed: {}", e.without_url())))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        return Err(SendError::from_status(
+            status,
+            format!("Telegram API error ({}): {}", status, response.text().await.unwrap_or_default()),
+        ));
+    }
+    Ok(())
+}
+
+/// Open (or fetch) the DM channel with a Discord user
+async fn open_discord_dm(client: &reqwest::Client, token: &str, user_id: &str) -> Result<String, SendError> {
+    let response = client
+        .post("https://discord.com/api/v10/users/@me/channels")
+        .header("Authorization", format!("Bot {}", token))
+        .json(&json!({ "recipient_id": user_id }))
+        .send()
+        .await
+        .map_err(|e| SendError::retryable(format!("Discord request failed: {}", e)))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(SendError::from_status(
+            status,
+            format!("Discord DM error ({}): {}", status, response.text().await.unwrap_or_default()),
+        ));
+    }
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| SendError::retryable(format!("Invalid Discord response: {}", e)))?;
+    body.get("id")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .ok_or_else(|| SendError::fatal("Discord DM response has no channel id".to_string()))
+}
+
+async fn send_discord(client: &reqwest::Client, token: &str, channel: &str, text: &str) -> Result<(), SendError> {
+    let response = client
+        .post(format!("https://discord.com/api/v10/channels/{}/messages", channel))
+        .header("Authorization", format!("Bot {}", token))
+        .json(&json!({ "content": text }))
+        .send()
+        .await
+        .map_err(|e| SendError::retryable(format!("Discord request failed: {}", e)))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        return Err(SendError::from_status(
+            status,
+            format!("Discord API error ({}): {}", status, response.text().await.unwrap_or_default()),
+        ));
+    }
+    Ok(())
+}
+
+async fn send_slack(client: &reqwest::Client, token: &str, channel: &str, text: &str) -> Result<(), SendError> {
+    let body: Value = client
+        .post("https://slack.com/api/chat.postMessage")
+        .bearer_auth(token)
+        .json(&json!({ "channel": channel, "text": text }))
+        .send()
+        .await
+        .map_err(|e| SendError::retryable(format!("Slack request failed: {}", e)))?
+        .json()
+        .await
+        .map_err(|e| SendError::retryable(format!("Invalid Slack response: {}", e)))?;
+    // Slack returns 200 even on errors
+    if !body.get("ok").and_then(|v| v.as_bool()).unwrap_or(false) {
+        let error = body.get("error").and_then(|v| v.as_str()).unwrap_or("unknown error");
+        let message = format!("Slack API error: {}", error);
+        return Err(if error == "ratelimited" {
+            SendError::retryable(message)
+        } else {
+            SendError::fatal(message)
+        });
+    }
+    Ok(())
+}
+
+async fn send_callback(
+    client: &reqwest::Client,
+    url: &str,
+    token: Option<&str>,
+    body: &Value,
+) -> Result<(), SendError> {
+    let mut request = client.post(url).json(body);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| SendError::retryable(format!("Callback request failed: {}", e)))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        return Err(SendError::from_status(status, format!("Callback returned {}", status)));
+    }
+    Ok(())
+}
+
+async fn send_mail(config: &EmailConfig, to: &str, subject: &str, text: &str) -> Result<(), SendError> {
+    email::send_email(config, to, subject, text, None, &[])
+        .await
+        .map(|_| ())
+        // Bad addresses won't get better; SMTP failures might
+        .map_err(|e| {
+            if e.starts_with("Invalid") {
+                SendError::fatal(e)
+            } else {
+                SendError::retryable(e)
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_for_platform_truncates() {
+        let short = chunk_for_platform("hello", 100);
+        assert_eq!(short, vec!["hello".to_string()]);
+
+        let line = "x".repeat(90);
+        let text = vec![line.as_str(); 30].join("\n");
+        let chunks = chunk_for_platform(&text, 100);
+        assert_eq!(chunks.len(), MAX_CHUNKS);
+        let last = chunks.last().unwrap();
+        assert!(last.len() <= 100);
+        assert!(last.contains("truncated, 1800 more characters"));
+    }
+}
@@ -1,17 +1,25 @@
+use crate::channels::attachments::{self, Attachment};
 use crate::channels::dispatcher::MessageDispatcher;
 use crate::channels::safe_mode_rate_limiter::SafeModeChannelRateLimiter;
+use crate::channels::tx_approval::{self, TxApprovalAction};
 use crate::channels::types::{ChannelType, NormalizedMessage};
 use crate::channels::util;
 use crate::db::Database;
-use crate::discord_hooks;
+use crate::discord_hooks::{self, slash::SlashCommand};
+use crate::disk_quota::DiskQuotaManager;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
-use crate::models::{Channel, ToolOutputVerbosity};
+use crate::models::{Channel, ChannelSettingKey, ToolOutputVerbosity};
+use crate::tx_queue::{QueuedTransaction, TxQueueManager};
+use dashmap::DashMap;
 use serenity::all::{
-    Client, Context, EditMessage, EventHandler, GatewayIntents, GetMessages, Message, MessageId,
-    Ready,
+    AutoArchiveDuration, ButtonStyle, ChannelId, Client, CommandInteraction, ComponentInteraction,
+    Context, CreateActionRow, CreateAttachment, CreateButton, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, CreateThread, EditInteractionResponse,
+    EditMessage, EventHandler, GatewayIntents, GetMessages, Http, Interaction, Message, MessageId,
+    Ready, UserId,
 };
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use tokio::sync::oneshot;
 
 /// Format a tool call event for Discord display based on verbosity
@@ -83,7 +91,135 @@ fn format_mode_change_for_discord(mode: &str, label: &str, reason: Option<&str>)
     }
 }
 
-/// Check if a tool terminates the chat loop and sets the final response.
+/// Download the files attached to a Discord message into the workspace attachment cache
+async fn download_discord_attachments(
+    msg: &Message,
+    channel_id: i64,
+    disk_quota: Option<Arc<DiskQuotaManager>>,
+) -> Vec<Attachment> {
+    let mut downloaded = Vec::new();
+    for (i, a) in msg.attachments.iter().enumerate() {
+        let mut attachment = Attachment::new(
+            a.filename.clone(),
+            a.content_type.clone(),
+            a.size as u64,
+            Some(a.id.to_string()),
+        );
+        if i >= attachments::MAX_ATTACHMENTS_PER_MESSAGE {
+            attachment.error = Some("too many attachments in one message".to_string());
+            downloaded.push(attachment);
+            continue;
+        }
+        downloaded.push(
+            attachments::download(attachment, &a.url, None, channel_id, disk_quota.as_deref()).await,
+        );
+    }
+    downloaded
+}
+
+/// Upload outbound files (already within the size limit), one message per file
+async fn send_discord_files(http: &Http, channel_id: ChannelId, files: Vec<Attachment>) {
+    for file in files {
+        let Some(path) = file.absolute_path() else { continue };
+        let sent = match CreateAttachment::path(&path).await {
+            Ok(upload) => channel_id
+                .send_message(http, CreateMessage::new().add_file(upload))
+                .await
+                .map(|_| ()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = sent {
+            log::error!("Discord: Failed to upload {}: {}", file.file_name, e);
+            let _ = channel_id
+                .say(http, format!("📎 Failed to send {}: {}", file.file_name, e))
+                .await;
+        }
+    }
+}
+
+/// Post a transaction approval prompt with Approve/Deny buttons.
+/// The buttons are removed if the approval window passes without a decision.
+async fn send_tx_approval_prompt(
+    http: &Arc<Http>,
+    discord_channel_id: ChannelId,
+    tx: &QueuedTransaction,
+    tx_queue: Arc<TxQueueManager>,
+) {
+    let button = |action: TxApprovalAction, style: ButtonStyle| {
+        CreateButton::new(action.callback_id(&tx.uuid))
+            .label(action.label())
+            .style(style)
+    };
+    let message = CreateMessage::new()
+        .content(tx_approval::format_prompt(tx))
+        .components(vec![CreateActionRow::Buttons(vec![
+            button(TxApprovalAction::Approve, ButtonStyle::Success),
+            button(TxApprovalAction::Deny, ButtonStyle::Danger),
+        ])]);
+
+    match discord_channel_id.send_message(http, message).await {
+        Ok(sent) => {
+            log::info!("Discord: Posted approval prompt for transaction {}", tx.uuid);
+            let http = http.clone();
+            let uuid = tx.uuid.clone();
+            tokio::spawn(async move {
+                if tx_approval::wait_for_expiry(tx_queue, uuid.clone()).await {
+                    let edit = EditMessage::new()
+                        .content(tx_approval::format_expired(&uuid))
+                        .components(vec![]);
+                    let _ = discord_channel_id.edit_message(&http, sent.id, edit).await;
+                }
+            });
+        }
+        Err(e) => log::error!("Discord: Failed to send approval prompt: {}", e),
+    }
+}
+
+/// Thread title for a conversation: the first line of the prompt, within Discord's 100-char limit
+fn thread_name(text: &str, user_name: &str) -> String {
+    let first_line = text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("");
+    if first_line.is_empty() {
+        return format!("Conversation with {}", user_name);
+    }
+    if first_line.chars().count() > 90 {
+        format!("{}…", first_line.chars().take(90).collect::<String>())
+    } else {
+        first_line.to_string()
+    }
+}
+
+/// Open a thread on a message for a new conversation. Returns None if Discord refuses
+/// (e.g. the message is already in a thread or the bot lacks Create Public Threads).
+async fn open_conversation_thread(
+    http: &Http,
+    channel_id: ChannelId,
+    message_id: MessageId,
+    name: String,
+) -> Option<ChannelId> {
+    let builder = CreateThread::new(name).auto_archive_duration(AutoArchiveDuration::OneDay);
+    match channel_id.create_thread_from_message(http, message_id, builder).await {
+        Ok(thread) => {
+            log::info!("Discord: Opened conversation thread {}", thread.id);
+            Some(thread.id)
+        }
+        Err(e) => {
+            log::warn!("Discord: Could not open thread, replying in channel: {}", e);
+            None
+        }
+    }
+}
+
+/// Send an ephemeral reply to a slash command (only the invoking user sees it)
+async fn respond_ephemeral(ctx: &Context, command: &CommandInteraction, text: &str) {
+    let content = util::split_message(text, 2000).into_iter().next().unwrap_or_default();
+    let reply = CreateInteractionResponseMessage::new().content(content).ephemeral(true);
+    if let Err(e) = command
+        .create_response(&ctx.http, CreateInteractionResponse::Message(reply))
+        .await
+    {
+        log::warn!("Discord: Failed to respond to /{}: {}", command.data.name, e);
+    }
+}
 
 struct DiscordHandler {
     channel_id: i64,
@@ -91,6 +227,10 @@ struct DiscordHandler {
     broadcaster: Arc<EventBroadcaster>,
     db: Arc<Database>,
     safe_mode_rate_limiter: SafeModeChannelRateLimiter,
+    /// Bot user ID, set once connected
+    bot_user_id: OnceLock<UserId>,
+    /// Whether a channel is a thread owned by the bot (looked up once per channel)
+    bot_threads: DashMap<ChannelId, bool>,
 }
 
 #[serenity::async_trait]
@@ -116,13 +256,23 @@ impl EventHandler for DiscordHandler {
         }
 
         let text = msg.content.clone();
-        if text.is_empty() {
+        if text.is_empty() && msg.attachments.is_empty() {
             return;
         }
 
+        // Messages in the bot's conversation threads don't need a mention
+        let auto_thread = self
+            .db
+            .get_channel_setting(self.channel_id, ChannelSettingKey::DiscordAutoThread.as_ref())
+            .ok()
+            .flatten()
+            .as_deref()
+            == Some("true");
+        let in_bot_thread = msg.guild_id.is_some() && self.is_bot_thread(&ctx, msg.channel_id).await;
+
         // ===== Discord Hooks Integration =====
         // Process through discord_hooks module first (config reloaded from DB each time)
-        match discord_hooks::process(&msg, &ctx, &self.db, self.channel_id).await {
+        match discord_hooks::process(&msg, &ctx, &self.db, self.channel_id, in_bot_thread).await {
             Ok(result) => {
                 // If module handled it with a direct response, send it and return
                 if let Some(response) = result.response {
@@ -226,10 +376,38 @@ impl EventHandler for DiscordHandler {
                         ch.guild().map(|gc| gc.name().to_string())
                     });
 
+                    // Download any attachments into the workspace
+                    let attachments = download_discord_attachments(
+                        &msg,
+                        self.channel_id,
+                        self.dispatcher.disk_quota(),
+                    )
+                    .await;
+
+                    // New server-channel conversations move into their own thread (and session)
+                    let reply_channel = if auto_thread && msg.guild_id.is_some() && !in_bot_thread {
+                        match open_conversation_thread(
+                            &ctx.http,
+                            msg.channel_id,
+                            msg.id,
+                            thread_name(&forward.text, &user_name),
+                        )
+                        .await
+                        {
+                            Some(thread_id) => {
+                                self.bot_threads.insert(thread_id, true);
+                                thread_id
+                            }
+                            None => msg.channel_id,
+                        }
+                    } else {
+                        msg.channel_id
+                    };
+
                     let normalized = NormalizedMessage {
                         channel_id: self.channel_id,
                         channel_type: ChannelType::Discord.to_string(),
-                        chat_id: msg.channel_id.to_string(),
+                        chat_id: reply_channel.to_string(),
                         chat_name: channel_name,
                         user_id,
                         user_name: user_name.clone(),
@@ -237,10 +415,20 @@ impl EventHandler for DiscordHandler {
                         message_id: Some(msg.id.to_string()),
                         session_mode: None,
                         selected_network: None,
-                        force_safe_mode: forward.force_safe_mode,
+                        // Non-admins fall back to safe mode unless a role is assigned to them
+                        force_safe_mode: false,
+                        safe_mode_fallback: forward.force_safe_mode,
+                        endpoint_override: None,
+                        target_session_id: None,
+                        attachments,
+                        tool_profile: None,
+                        thinking_level: None,
+                        agent_subtype: None,
+                        max_tool_iterations: None,
+                        max_tx_value_wei: None,
                     };
 
-                    self.dispatch_and_respond(&ctx, &msg, normalized, &user_name).await;
+                    self.dispatch_and_respond(&ctx, reply_channel, normalized, &user_name).await;
                     return;
                 }
 
@@ -267,17 +455,280 @@ impl EventHandler for DiscordHandler {
         // ===== End Discord Hooks Integration =====
     }
 
-    async fn ready(&self, _ctx: Context, ready: Ready) {
+    async fn ready(&self, ctx: Context, ready: Ready) {
         log::info!("Discord: Bot connected as {}", ready.user.name);
+        let _ = self.bot_user_id.set(ready.user.id);
+
+        match serenity::all::Command::set_global_commands(&ctx.http, discord_hooks::slash::definitions()).await {
+            Ok(commands) => log::info!("Discord: Registered {} slash commands", commands.len()),
+            Err(e) => log::error!("Discord: Failed to register slash commands: {}", e),
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        match interaction {
+            Interaction::Command(command) => self.handle_slash_command(&ctx, command).await,
+            Interaction::Component(component) => self.handle_approval_button(&ctx, component).await,
+            _ => {}
+        }
     }
 }
 
 impl DiscordHandler {
+    /// Whether a channel is a thread the bot opened for a conversation
+    async fn is_bot_thread(&self, ctx: &Context, channel_id: ChannelId) -> bool {
+        if let Some(known) = self.bot_threads.get(&channel_id) {
+            return *known;
+        }
+        let Some(bot_id) = self.bot_user_id.get().copied() else {
+            return false;
+        };
+        let is_bot_thread = match ctx.http.get_channel(channel_id).await {
+            Ok(serenity::all::Channel::Guild(channel)) => {
+                channel.thread_metadata.is_some() && channel.owner_id == Some(bot_id)
+            }
+            Ok(_) => false,
+            Err(e) => {
+                // Don't cache failures — the next message retries the lookup
+                log::warn!("Discord: Failed to look up channel {}: {}", channel_id, e);
+                return false;
+            }
+        };
+        self.bot_threads.insert(channel_id, is_bot_thread);
+        is_bot_thread
+    }
+
+    /// Approve/Deny button presses on transaction approval prompts
+    async fn handle_approval_button(&self, ctx: &Context, component: ComponentInteraction) {
+        let Some((action, uuid)) = tx_approval::parse_callback_id(&component.data.custom_id) else {
+            return;
+        };
+
+        // Only explicitly configured admin IDs may approve (no Administrator-permission fallback)
+        let user_id = component.user.id.to_string();
+        let config = discord_hooks::DiscordHooksConfig::from_channel_settings(&self.db, self.channel_id);
+        if !config.is_admin_by_id(&user_id) {
+            log::warn!(
+                "Discord: Non-admin user {} tried to {} transaction {}",
+                user_id, action.as_str(), uuid
+            );
+            let reply = CreateInteractionResponseMessage::new()
+                .content("Only admins can approve or deny transactions.")
+                .ephemeral(true);
+            let _ = component
+                .create_response(&ctx.http, CreateInteractionResponse::Message(reply))
+                .await;
+            return;
+        }
+
+        // Acknowledge immediately — broadcasting can take longer than the interaction deadline
+        if let Err(e) = component
+            .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
+            .await
+        {
+            log::warn!("Discord: Failed to acknowledge approval interaction: {}", e);
+        }
+
+        let outcome = tx_approval::handle_action(
+            &self.dispatcher,
+            self.channel_id,
+            action,
+            &uuid,
+            &component.user.name,
+        )
+        .await;
+
+        let edit = EditInteractionResponse::new().content(outcome).components(vec![]);
+        if let Err(e) = component.edit_response(&ctx.http, edit).await {
+            log::warn!("Discord: Failed to update approval prompt: {}", e);
+        }
+    }
+
+    /// Handle an application (slash) command
+    async fn handle_slash_command(&self, ctx: &Context, command: CommandInteraction) {
+        let Some(slash_command) = discord_hooks::slash::parse(&command.data) else {
+            respond_ephemeral(ctx, &command, "Unknown command.").await;
+            return;
+        };
+
+        let user_id = command.user.id.to_string();
+        let user_name = command.user.name.clone();
+        let config = discord_hooks::DiscordHooksConfig::from_channel_settings(&self.db, self.channel_id);
+        let is_admin = config.is_admin_for_interaction(
+            &user_id,
+            command.guild_id,
+            command.member.as_ref().and_then(|m| m.permissions),
+        );
+
+        if slash_command.requires_admin() && !is_admin {
+            log::warn!("Discord: Non-admin user {} tried /{}", user_id, command.data.name);
+            respond_ephemeral(ctx, &command, "Only admins can use this command.").await;
+            return;
+        }
+
+        match slash_command {
+            SlashCommand::Status => {
+                let mut text = format!(
+                    "**Access:** {}",
+                    if is_admin { "Admin (full agent access)" } else { "Safe mode (restricted tools)" }
+                );
+                if self.db.is_module_installed("discord_tipping").unwrap_or(false) {
+                    match discord_hooks::commands::execute(
+                        discord_hooks::commands::Command::Status,
+                        &user_id,
+                        &self.db,
+                    )
+                    .await
+                    {
+                        Ok(status) => text = format!("{}\n\n{}", text, status),
+                        Err(e) => log::error!("Discord: Failed to get profile status: {}", e),
+                    }
+                }
+                respond_ephemeral(ctx, &command, &text).await;
+            }
+            SlashCommand::TxList => {
+                let text = match self.dispatcher.tx_queue() {
+                    Some(tx_queue) => discord_hooks::slash::format_tx_list(
+                        tx_queue.list_all_for_channel(self.channel_id),
+                    ),
+                    None => "The transaction queue is not available.".to_string(),
+                };
+                respond_ephemeral(ctx, &command, &text).await;
+            }
+            SlashCommand::Reset | SlashCommand::Thinking(_) => {
+                // Same directives the dispatcher handles for text messages
+                let directive = match &slash_command {
+                    SlashCommand::Thinking(level) => format!("/think:{}", level),
+                    _ => "/reset".to_string(),
+                };
+                if let Err(e) = command
+                    .create_response(&ctx.http, CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new()))
+                    .await
+                {
+                    log::warn!("Discord: Failed to defer /{}: {}", command.data.name, e);
+                    return;
+                }
+
+                let normalized = NormalizedMessage {
+                    channel_id: self.channel_id,
+                    channel_type: ChannelType::Discord.to_string(),
+                    chat_id: command.channel_id.to_string(),
+                    chat_name: None,
+                    user_id,
+                    user_name,
+                    text: directive,
+                    message_id: Some(command.id.to_string()),
+                    session_mode: None,
+                    selected_network: None,
+                    force_safe_mode: false,
+                    safe_mode_fallback: false,
+                    endpoint_override: None,
+                    target_session_id: None,
+                    attachments: Vec::new(),
+                    tool_profile: None,
+                    thinking_level: None,
+                    agent_subtype: None,
+                    max_tool_iterations: None,
+                    max_tx_value_wei: None,
+                };
+                let result = self.dispatcher.dispatch(normalized).await;
+                let text = match result.error {
+                    Some(error) => format!("Sorry, I encountered an error: {}", error),
+                    None => result.response,
+                };
+                let content = util::split_message(&text, 2000).into_iter().next().unwrap_or_default();
+                if let Err(e) = command.edit_response(&ctx.http, EditInteractionResponse::new().content(content)).await {
+                    log::warn!("Discord: Failed to answer /{}: {}", command.data.name, e);
+                }
+            }
+            SlashCommand::Ask(prompt) => {
+                if !is_admin
+                    && let Err(rate_limit_msg) = self.safe_mode_rate_limiter.check_and_record_query(&user_id, "discord")
+                {
+                    log::info!("Discord: Rate limiting user {} - {}", user_id, rate_limit_msg);
+                    respond_ephemeral(ctx, &command, &format!("⏳ {}", rate_limit_msg)).await;
+                    return;
+                }
+
+                if let Err(e) = command
+                    .create_response(&ctx.http, CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new()))
+                    .await
+                {
+                    log::warn!("Discord: Failed to defer /ask: {}", e);
+                    return;
+                }
+
+                // Echo the question so the channel (or the thread opened on it) shows what was asked
+                let question = format!("**{}** asked: {}", user_name, prompt);
+                let question = util::split_message(&question, 2000).into_iter().next().unwrap_or_default();
+                let echoed = command
+                    .edit_response(&ctx.http, EditInteractionResponse::new().content(question))
+                    .await;
+
+                let reply_channel = match echoed {
+                    Ok(message) if config.auto_thread && command.guild_id.is_some() => {
+                        match open_conversation_thread(
+                            &ctx.http,
+                            command.channel_id,
+                            message.id,
+                            thread_name(&prompt, &user_name),
+                        )
+                        .await
+                        {
+                            Some(thread_id) => {
+                                self.bot_threads.insert(thread_id, true);
+                                thread_id
+                            }
+                            None => command.channel_id,
+                        }
+                    }
+                    Ok(_) => command.channel_id,
+                    Err(e) => {
+                        log::warn!("Discord: Failed to echo /ask prompt: {}", e);
+                        command.channel_id
+                    }
+                };
+
+                log::info!(
+                    "Discord: /ask {} from {} ({})",
+                    if is_admin { "admin command" } else { "safe mode query" },
+                    user_name,
+                    user_id
+                );
+
+                let normalized = NormalizedMessage {
+                    channel_id: self.channel_id,
+                    channel_type: ChannelType::Discord.to_string(),
+                    chat_id: reply_channel.to_string(),
+                    chat_name: None,
+                    user_id,
+                    user_name: user_name.clone(),
+                    text: format!("[DISCORD MESSAGE - Use discord_tipping skill for tips.]\n\n{}", prompt),
+                    message_id: Some(command.id.to_string()),
+                    session_mode: None,
+                    selected_network: None,
+                    force_safe_mode: false,
+                    safe_mode_fallback: !is_admin,
+                    endpoint_override: None,
+                    target_session_id: None,
+                    attachments: Vec::new(),
+                    tool_profile: None,
+                    thinking_level: None,
+                    agent_subtype: None,
+                    max_tool_iterations: None,
+                    max_tx_value_wei: None,
+                };
+
+                self.dispatch_and_respond(ctx, reply_channel, normalized, &user_name).await;
+            }
+        }
+    }
+
     /// Dispatch a message to the AI and send the response
     async fn dispatch_and_respond(
         &self,
         ctx: &Context,
-        msg: &Message,
+        reply_channel: ChannelId,
         normalized: NormalizedMessage,
         user_name: &str,
     ) {
@@ -289,10 +740,10 @@ impl DiscordHandler {
 
         // Clone context and channel info for the event forwarder task
         let http = ctx.http.clone();
-        let discord_channel_id = msg.channel_id;
+        let dispatcher_for_events = self.dispatcher.clone();
+        let discord_channel_id = reply_channel;
         let channel_id_for_events = self.channel_id;
-        // Convert Discord channel ID to string for event filtering
-        let chat_id_for_events = discord_channel_id.to_string();
+        let chat_id_for_events = normalized.chat_id.clone();
 
         // Spawn task to forward events to Discord in real-time
         // Uses a single "status message" that gets edited for each update to reduce spam
@@ -309,6 +760,17 @@ impl DiscordHandler {
                     continue;
                 }
 
+                // Partner mode: post the tx with Approve/Deny buttons
+                if event.event == "tx_queue.confirmation_required" {
+                    if let (Some(tx), Some(tx_queue)) = (
+                        tx_approval::pending_tx_from_event(&dispatcher_for_events, &event.data),
+                        dispatcher_for_events.tx_queue(),
+                    ) {
+                        send_tx_approval_prompt(&http, discord_channel_id, &tx, tx_queue).await;
+                    }
+                    continue;
+                }
+
                 let message_text = match event.event.as_str() {
                     "agent.tool_call" => {
                         let tool_name = event.data.get("tool_name")
@@ -464,7 +926,7 @@ impl DiscordHandler {
         // Delete the status message now that we have the final response
         // This keeps the chat clean - users see only their message and the final answer
         if let Some(msg_id) = status_message_id {
-            if let Err(e) = msg.channel_id.delete_message(&ctx.http, msg_id).await {
+            if let Err(e) = reply_channel.delete_message(&ctx.http, msg_id).await {
                 log::warn!("Discord: Failed to delete status message: {}", e);
             } else {
                 log::info!("Discord: Deleted status message {}", msg_id);
@@ -474,22 +936,28 @@ impl DiscordHandler {
         log::info!("Discord: Unsubscribed from events, client {}", client_id);
 
         // Send final response
+        let dispatch_failed = result.error.is_some();
         if result.error.is_none() && !result.response.is_empty() {
             // Discord has a 2000 character limit per message
             let response = &result.response;
             let chunks = util::split_message(response, 2000);
 
             for chunk in chunks {
-                if let Err(e) = msg.channel_id.say(&ctx.http, &chunk).await {
+                if let Err(e) = reply_channel.say(&ctx.http, &chunk).await {
                     log::error!("Failed to send Discord message: {}", e);
                 }
             }
         } else if let Some(error) = result.error {
             let error_msg = format!("Sorry, I encountered an error: {}", error);
-            let _ = msg.channel_id.say(&ctx.http, &error_msg).await;
-        } else if result.response.is_empty() {
+            let _ = reply_channel.say(&ctx.http, &error_msg).await;
+        } else if result.response.is_empty() && result.attachments.is_empty() {
             log::debug!("Discord: Empty final response for user {}", user_name);
         }
+
+        // Upload files attached by say_to_user
+        if !dispatch_failed && !result.attachments.is_empty() {
+            send_discord_files(&ctx.http, reply_channel, result.attachments).await;
+        }
     }
 }
 
@@ -520,6 +988,8 @@ pub async fn start_discord_listener(
         broadcaster: broadcaster.clone(),
         db,
         safe_mode_rate_limiter,
+        bot_user_id: OnceLock::new(),
+        bot_threads: DashMap::new(),
     };
 
     // Create client
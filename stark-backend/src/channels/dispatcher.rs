@@ -1,7 +1,7 @@
 use crate::ai::{
     multi_agent::{types::{AgentSubtype, AgentMode}, Orchestrator, ProcessResult as OrchestratorResult, SubAgentManager},
     AiClient, ArchetypeId, ArchetypeRegistry, AiResponse, Message, MessageRole, ModelArchetype,
-    ThinkingLevel, ToolHistoryEntry, ToolResponse,
+    RetentionReport, ThinkingLevel, ToolHistoryEntry, ToolHistoryRetention, ToolLedger, ToolResponse,
 };
 use crate::channels::types::{DispatchResult, NormalizedMessage};
 use crate::config::MemoryConfig;
@@ -18,6 +18,8 @@ use crate::telemetry::{
     RewardEmitter, TelemetryStore, Watchdog, WatchdogConfig, ResourceManager,
 };
 use crate::tools::{ToolConfig, ToolContext, ToolDefinition, ToolExecution, ToolRegistry};
+use crate::workflows;
+use async_trait::async_trait;
 use chrono::Utc;
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -28,7 +30,7 @@ use tokio::time::interval;
 
 /// Compiled regex patterns - avoid recompiling on every call
 static INLINE_THINKING_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)^/(?:t|think|thinking):(\w+)\s+(.+)$").unwrap()
+    Regex::new(r"(?is)^/(?:t|think|thinking):(\w+)\s+(.+)$").unwrap()
 });
 static THINKING_DIRECTIVE_PATTERN: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(?i)^/(?:t|think|thinking)(?::(\w+))?$").unwrap()
@@ -89,6 +91,114 @@ struct ToolCallProcessed {
     user_question_content: Option<String>,
 }
 
+/// How a registry tool call ended: rejected by a validator, or executed (possibly timed out).
+enum ToolCallOutcome {
+    Rejected(String),
+    Executed {
+        result: crate::tools::ToolResult,
+        duration_ms: u64,
+    },
+}
+
+/// Executes workflow tool steps through the same validators, watchdog and UI events
+/// as tool calls made by the agent.
+struct WorkflowStepRunner<'a> {
+    dispatcher: &'a MessageDispatcher,
+    tool_context: &'a ToolContext,
+    exec_config: ToolConfig,
+    original_message: &'a NormalizedMessage,
+    session_id: i64,
+    is_safe_mode: bool,
+    watchdog: &'a Arc<Watchdog>,
+}
+
+#[async_trait]
+impl workflows::StepRunner for WorkflowStepRunner<'_> {
+    async fn run_tool(&self, tool: &str, args: &Value) -> crate::tools::ToolResult {
+        let dispatcher = self.dispatcher;
+        let channel_id = self.original_message.channel_id;
+        let args_pretty = serde_json::to_string_pretty(args).unwrap_or_else(|_| args.to_string());
+        log::info!("[WORKFLOW] Step calling tool '{}' with args:\n{}", tool, args_pretty);
+
+        dispatcher.broadcaster.broadcast(GatewayEvent::tool_execution(channel_id, tool, args));
+        dispatcher.session_writer.send(
+            self.session_id,
+            DbMessageRole::ToolCall,
+            format!("🔧 **Tool Call:** `{}` (workflow)\n```json\n{}\n```", tool, args_pretty),
+            Some(tool),
+        );
+
+        if let Some(ref validator_registry) = dispatcher.validator_registry {
+            let validation_ctx = crate::tool_validators::ValidationContext::new(
+                tool.to_string(),
+                args.clone(),
+                Arc::new(self.tool_context.clone()),
+            );
+            if let Some(error_msg) = validator_registry.validate(&validation_ctx).await.to_error_message() {
+                telemetry::emit_annotation("tool_validator_rejected", serde_json::json!({
+                    "tool_name": tool,
+                    "error": error_msg,
+                }));
+                return crate::tools::ToolResult::error(error_msg);
+            }
+        }
+
+        let start = std::time::Instant::now();
+        let result = match self.watchdog.guard_tool_call(
+            tool,
+            dispatcher.execute_tool(tool, args, self.tool_context, &self.exec_config),
+        ).await {
+            Some(result) => result,
+            None => crate::tools::ToolResult::error(format!(
+                "Tool '{}' timed out after {}s",
+                tool, self.watchdog.config().timeout_for_tool(tool).as_secs()
+            )),
+        };
+        let duration_ms = start.elapsed().as_millis() as u64;
+        self.watchdog.reward_emitter().tool_completed(tool, result.success, duration_ms);
+
+        dispatcher.broadcaster.broadcast(GatewayEvent::tool_result(
+            channel_id,
+            Some(&self.original_message.chat_id),
+            tool,
+            result.success,
+            duration_ms as i64,
+            &result.content,
+            self.is_safe_mode,
+        ));
+        dispatcher.session_writer.send(
+            self.session_id,
+            DbMessageRole::ToolResult,
+            format!(
+                "**{}:** {}\n{}",
+                if result.success { "Result" } else { "Error" },
+                tool,
+                result.content
+            ),
+            Some(tool),
+        );
+
+        result
+    }
+}
+
+/// Log and emit telemetry for a tool history retention pass that changed something
+fn record_retention_report(loop_name: &str, report: &RetentionReport) {
+    if report.is_noop() {
+        return;
+    }
+    log::info!(
+        "[{}] Tool history retention: evicted {:?}, truncated {} results, dropped {} chars, {} pinned, ledger {} lines",
+        loop_name,
+        report.evicted_tools,
+        report.truncated_results,
+        report.dropped_chars,
+        report.pinned_entries,
+        report.ledger_lines
+    );
+    telemetry::emit_annotation("tool_history_retention", report.to_json());
+}
+
 /// Dispatcher routes messages to the AI and returns responses
 pub struct MessageDispatcher {
     db: Arc<Database>,
@@ -130,6 +240,9 @@ pub struct MessageDispatcher {
     /// Mock AI client for integration tests (bypasses real AI API)
     #[cfg(test)]
     mock_ai_client: Option<crate::ai::MockAiClient>,
+    /// Recorded tool results returned instead of executing tools (session replay)
+    #[cfg(test)]
+    tool_stubs: Option<Arc<crate::telemetry::ToolStubs>>,
 }
 
 impl MessageDispatcher {
@@ -234,6 +347,8 @@ impl MessageDispatcher {
             watchdog_config: WatchdogConfig::default(),
             #[cfg(test)]
             mock_ai_client: None,
+            #[cfg(test)]
+            tool_stubs: None,
         }
     }
 
@@ -273,6 +388,13 @@ impl MessageDispatcher {
         self.mock_ai_client.as_ref().map(|m| m.get_trace()).unwrap_or_default()
     }
 
+    /// Return recorded tool results instead of executing tools (session replay)
+    #[cfg(test)]
+    pub fn with_tool_stubs(mut self, stubs: Arc<crate::telemetry::ToolStubs>) -> Self {
+        self.tool_stubs = Some(stubs);
+        self
+    }
+
     /// Create a dispatcher without tool support (for backwards compatibility)
     pub fn new_without_tools(db: Arc<Database>, broadcaster: Arc<EventBroadcaster>) -> Self {
         // Create a minimal execution tracker for legacy use
@@ -321,6 +443,8 @@ impl MessageDispatcher {
             watchdog_config: WatchdogConfig::default(),
             #[cfg(test)]
             mock_ai_client: None,
+            #[cfg(test)]
+            tool_stubs: None,
         }
     }
 
@@ -334,6 +458,26 @@ impl MessageDispatcher {
         self.subagent_manager.clone()
     }
 
+    /// Get the event broadcaster
+    pub fn broadcaster(&self) -> &Arc<EventBroadcaster> {
+        &self.broadcaster
+    }
+
+    /// Get the transaction queue manager (if available)
+    pub fn tx_queue(&self) -> Option<Arc<crate::tx_queue::TxQueueManager>> {
+        self.tx_queue.clone()
+    }
+
+    /// Get the disk quota manager (if available)
+    pub fn disk_quota(&self) -> Option<Arc<crate::disk_quota::DiskQuotaManager>> {
+        self.disk_quota.clone()
+    }
+
+    /// Get the wallet provider (if available)
+    pub fn wallet_provider(&self) -> Option<Arc<dyn crate::wallet::WalletProvider>> {
+        self.wallet_provider.clone()
+    }
+
     /// Get the TelemetryStore
     pub fn telemetry_store(&self) -> &Arc<TelemetryStore> {
         &self.telemetry_store
@@ -360,6 +504,22 @@ impl MessageDispatcher {
             return self.handle_reset_command(&message).await;
         }
 
+        // Self-service identity linking (/link) is handled without the agent
+        if message.session_mode.is_none()
+            && let Some(response) = crate::identity_linking::handle_command(
+                &self.db,
+                self.memory_store.as_deref(),
+                &message,
+            )
+        {
+            self.broadcaster.broadcast(GatewayEvent::agent_response(
+                message.channel_id,
+                &message.user_name,
+                &response,
+            ));
+            return DispatchResult::success(response);
+        }
+
         // Check for thinking directives (session-level setting)
         if let Some(thinking_response) = self.handle_thinking_directive(&message).await {
             return thinking_response;
@@ -367,6 +527,9 @@ impl MessageDispatcher {
 
         // Parse inline thinking directive and extract clean message
         let (thinking_level, clean_text) = self.parse_inline_thinking(&message.text);
+        // A per-run thinking level (e.g. from a cron job) applies unless the text has its own directive
+        let thinking_level = thinking_level
+            .or_else(|| message.thinking_level.as_deref().and_then(ThinkingLevel::from_str));
 
         // Start execution tracking with user message for descriptive display
         let user_msg = clean_text.as_deref().unwrap_or(&message.text);
@@ -442,11 +605,12 @@ impl MessageDispatcher {
             }
         };
 
-        // Determine session scope based on session_mode (for cron) or chat context
+        // Determine session scope based on session_mode (for cron/webhooks) or chat context
         let scope = if let Some(ref mode) = message.session_mode {
-            // Cron job with explicit session_mode
+            // Cron job or webhook with explicit session_mode
             match mode.as_str() {
                 "isolated" => SessionScope::Cron,
+                "webhook" => SessionScope::Webhook,
                 "main" => {
                     // Main mode uses existing session logic (shares with web chat)
                     if message.chat_id != message.user_id {
@@ -470,7 +634,9 @@ impl MessageDispatcher {
         // to prevent context from growing too large. Previous conversation context is
         // preserved by including the last 10 messages in the system prompt.
         let channel_type_lower = message.channel_type.to_lowercase();
-        let is_gateway_channel = channel_type_lower == "discord" || channel_type_lower == "telegram";
+        // Messages pinned to an existing session (e.g. a rewound branch) skip this entirely.
+        let is_gateway_channel = (channel_type_lower == "discord" || channel_type_lower == "telegram")
+            && message.target_session_id.is_none();
 
         // Collect previous session messages for gateway channels (max 10)
         let previous_gateway_messages: Vec<crate::models::SessionMessage> = if is_gateway_channel {
@@ -503,7 +669,26 @@ impl MessageDispatcher {
         };
 
         // Get or create chat session
-        let session = if is_gateway_channel {
+        let session = if let Some(target_id) = message.target_session_id {
+            // Continue on an explicit session (session rewind / branch replay)
+            match self.db.get_chat_session(target_id) {
+                Ok(Some(s)) => s,
+                Ok(None) | Err(_) => {
+                    let error_msg = format!("Session error: session {} not found", target_id);
+                    log::error!("[DISPATCH] {}", error_msg);
+                    self.broadcaster.broadcast(GatewayEvent::agent_error(
+                        message.channel_id,
+                        &error_msg,
+                    ));
+                    self.execution_tracker.complete_execution(message.channel_id);
+                    self.rollout_manager.fail_attempt(&mut rollout, &error_msg, &span_collector);
+                    self.telemetry_store.persist_spans(&span_collector);
+                    heartbeat_handle.abort();
+                    telemetry::clear_active_collector();
+                    return DispatchResult::error(error_msg);
+                }
+            }
+        } else if is_gateway_channel {
             // Always create a fresh session for gateway channels
             match self.db.create_gateway_session(
                 &message.channel_type,
@@ -586,8 +771,13 @@ impl MessageDispatcher {
             }
         }
 
-        // Use clean text (with inline thinking directive removed) for storage
-        let message_text = clean_text.as_deref().unwrap_or(&message.text);
+        // Use clean text (with inline thinking directive removed) for storage,
+        // listing any attachments so later turns (and read_file) can find them
+        let message_text_with_attachments = crate::channels::attachments::append_to_text(
+            clean_text.as_deref().unwrap_or(&message.text),
+            &message.attachments,
+        );
+        let message_text = message_text_with_attachments.as_str();
 
         // Estimate tokens for the user message
         let user_tokens = estimate_tokens(message_text);
@@ -609,7 +799,7 @@ impl MessageDispatcher {
         }
 
         // Get active agent settings from database, falling back to kimi defaults
-        let settings = match self.db.get_active_agent_settings() {
+        let mut settings = match self.db.get_active_agent_settings() {
             Ok(Some(settings)) => settings,
             Ok(None) => {
                 log::info!("No agent configured, using default kimi settings");
@@ -635,6 +825,33 @@ impl MessageDispatcher {
             }
         };
 
+        // Per-message endpoint override (ai_endpoints.ron preset key)
+        if let Some(ref key) = message.endpoint_override {
+            match crate::ai_endpoint_config::get_ai_endpoint(key) {
+                Some(preset) => {
+                    log::info!("[DISPATCH] Overriding endpoint with preset '{}'", key);
+                    settings.endpoint = preset.endpoint;
+                    settings.model_archetype = preset.model_archetype;
+                    settings.supports_vision = preset.supports_vision;
+                }
+                None => log::warn!("[DISPATCH] Unknown endpoint override '{}', using active settings", key),
+            }
+        } else if let Some(key) = message
+            .agent_subtype
+            .as_deref()
+            .and_then(AgentSubtype::from_str)
+            .and_then(|s| s.definition())
+            .and_then(|d| d.preferred_endpoint.as_deref())
+        {
+            // The run's subtype prefers a specific endpoint
+            if let Some(preset) = crate::ai_endpoint_config::get_ai_endpoint(key) {
+                log::info!("[DISPATCH] Using subtype-preferred endpoint preset '{}'", key);
+                settings.endpoint = preset.endpoint;
+                settings.model_archetype = preset.model_archetype;
+                settings.supports_vision = preset.supports_vision;
+            }
+        }
+
         // Infer archetype from settings
         let archetype_id = AiClient::infer_archetype(&settings);
         log::info!(
@@ -717,13 +934,40 @@ impl MessageDispatcher {
             .map(|ch| ch.safe_mode)
             .unwrap_or(false);
 
-        let is_safe_mode = channel_safe_mode || message.force_safe_mode;
+        // A safe_mode profile override gets the full safe mode treatment, not just the profile
+        let profile_override = message.tool_profile.as_deref().and_then(crate::tools::ToolProfile::from_str);
+        let profile_safe_mode = profile_override == Some(crate::tools::ToolProfile::SafeMode);
+
+        // Role assigned to the caller (per channel user, identity or channel default).
+        // Resolved before the adapter's safe-mode fallback for non-admins, which only applies
+        // when no role is found. A forced message may come from an unauthenticated sender
+        // (e.g. email that failed DKIM/DMARC for an address that has a role), so it never
+        // picks up a role. Channel safe_mode stays absolute.
+        let caller_role = if message.session_mode.is_none() && !message.force_safe_mode {
+            self.db
+                .resolve_role(message.channel_id, &message.user_id, Some(&identity.identity_id))
+                .unwrap_or_else(|e| {
+                    log::warn!("[DISPATCH] Failed to resolve role for {}: {}", message.user_id, e);
+                    None
+                })
+        } else {
+            None
+        };
+        let force_safe_mode = message.force_safe_mode || match caller_role {
+            Some(ref role) => role.is_safe_mode(),
+            None => message.safe_mode_fallback,
+        };
+
+        let is_safe_mode = channel_safe_mode || force_safe_mode || profile_safe_mode;
+        let mut role_tool_config = None;
 
         if is_safe_mode {
             log::info!(
-                "[DISPATCH] Safe mode enabled (channel={}, force={}), restricting tools",
+                "[DISPATCH] Safe mode enabled (channel={}, force={}, profile={}, role={:?}), restricting tools",
                 channel_safe_mode,
-                message.force_safe_mode
+                force_safe_mode,
+                profile_safe_mode,
+                caller_role.as_ref().map(|r| r.name.as_str())
             );
             // Mark session as safe mode for UI display
             if let Err(e) = self.db.set_session_safe_mode(session.id) {
@@ -733,6 +977,25 @@ impl MessageDispatcher {
             // ToolConfig::safe_mode() is the single source of truth for safe mode permissions.
             // This discards any channel-level overrides — safe mode is absolute.
             tool_config = crate::tools::ToolConfig::safe_mode();
+        } else if let Some(ref role) = caller_role {
+            // The role decides the tools; the channel's own deny lists still apply on top
+            log::info!("[DISPATCH] Role '{}' for user {} (profile: {})", role.name, message.user_id, role.tool_profile);
+            let mut role_config = role.tool_config();
+            role_config.deny_list.append(&mut tool_config.deny_list);
+            role_config.denied_groups.append(&mut tool_config.denied_groups);
+            tool_config = role_config;
+            // Sub-agents spawned in this turn run under the same role config
+            role_tool_config = Some(tool_config.clone());
+        } else if let Some(ref profile_name) = message.tool_profile {
+            // Per-message profile (e.g. a webhook's configured profile). Channel allow/deny
+            // lists still apply; Custom has no groups of its own so it is not accepted here.
+            match profile_override {
+                Some(profile) if profile != crate::tools::ToolProfile::Custom => {
+                    log::info!("[DISPATCH] Tool profile override: {:?}", profile);
+                    tool_config.profile = profile;
+                }
+                _ => log::warn!("[DISPATCH] Ignoring invalid tool profile override '{}'", profile_name),
+            }
         }
 
         // Twitter has no interactive session — ask_user can never work, so block it.
@@ -766,6 +1029,7 @@ impl MessageDispatcher {
         let mut messages = vec![Message {
             role: MessageRole::System,
             content: system_prompt.clone(),
+            images: Vec::new(),
         }];
 
         // Add combined context (compaction summary + cross-session memories) if available
@@ -773,6 +1037,7 @@ impl MessageDispatcher {
             messages.push(Message {
                 role: MessageRole::System,
                 content: context,
+                images: Vec::new(),
             });
         }
 
@@ -799,6 +1064,7 @@ impl MessageDispatcher {
             messages.push(Message {
                 role: MessageRole::System,
                 content: context_text,
+                images: Vec::new(),
             });
             log::info!(
                 "[DISPATCH] Added {} previous gateway messages to context",
@@ -819,6 +1085,7 @@ impl MessageDispatcher {
                         "## Context Bank\nThe following key terms were detected in the user's input: {}",
                         context_bank_text
                     ),
+                    images: Vec::new(),
                 });
             }
         }
@@ -840,13 +1107,26 @@ impl MessageDispatcher {
             messages.push(Message {
                 role,
                 content: msg.content.clone(),
+                images: Vec::new(),
             });
         }
 
+        // Images go to the model only on the turn they arrive, and only if it has vision;
+        // otherwise the attachment descriptions already in the text stand in for them
+        let images = if AiClient::supports_vision(&settings) {
+            crate::channels::attachments::load_vision_images(&message.attachments).await
+        } else {
+            Vec::new()
+        };
+        if !images.is_empty() {
+            log::info!("[DISPATCH] Attaching {} image(s) to the user message", images.len());
+        }
+
         // Add current user message (use clean text without thinking directive)
         messages.push(Message {
             role: MessageRole::User,
             content: message_text.to_string(),
+            images,
         });
 
         // Debug: Log user message
@@ -934,6 +1214,34 @@ impl MessageDispatcher {
             );
         }
 
+        // Per-role spending cap, enforced when transactions are broadcast
+        if let Some(ref role) = caller_role {
+            tool_context.extra.insert("role".to_string(), serde_json::json!(role.name));
+            if let Some(ref config) = role_tool_config {
+                tool_context.extra.insert(
+                    "role_tool_config".to_string(),
+                    serde_json::to_value(config).unwrap_or_default(),
+                );
+            }
+            if let Some(ref cap) = role.max_tx_value_wei {
+                tool_context.extra.insert("max_tx_value_wei".to_string(), serde_json::json!(cap));
+                tool_context.extra.insert("max_tx_value_source".to_string(), serde_json::json!(format!("role '{}'", role.name)));
+            }
+        }
+        // Per-run spending cap (e.g. from a cron job); the stricter of the two caps wins
+        if let Some(cap) = message.max_tx_value_wei.as_deref().and_then(|c| c.parse::<u128>().ok()) {
+            let current = tool_context.extra.get("max_tx_value_wei")
+                .and_then(|v| v.as_str())
+                .and_then(|c| c.parse::<u128>().ok());
+            if current.is_none_or(|current| cap < current) {
+                tool_context.extra.insert("max_tx_value_wei".to_string(), serde_json::json!(cap.to_string()));
+                tool_context.extra.insert(
+                    "max_tx_value_source".to_string(),
+                    serde_json::json!(format!("this {} run", message.channel_type)),
+                );
+            }
+        }
+
         // Populate tool context with the context bank items scanned earlier
         if !context_bank_items.is_empty() {
             tool_context.context_bank.add_all(context_bank_items.clone());
@@ -951,6 +1259,25 @@ impl MessageDispatcher {
             }
         }
 
+        // Forked and imported sessions resume the registers and context bank they carried over
+        let stored_tool_state = self.db.get_session_tool_state(session.id).ok().flatten();
+        let resumes_tool_state = matches!(self.db.get_session_branch(session.id), Ok(Some(_)))
+            || matches!(self.db.get_session_import(session.id), Ok(Some(_)));
+        if resumes_tool_state
+            && let Some(ref state) = stored_tool_state
+        {
+            tool_context.registers.restore(&state.registers);
+            if let Ok(items) = serde_json::from_value::<Vec<crate::tools::ContextBankItem>>(state.context_bank.clone()) {
+                tool_context.context_bank.add_all(items);
+            }
+            log::info!(
+                "[DISPATCH] Restored tool state for session {} ({} registers, {} context items)",
+                session.id,
+                tool_context.registers.keys().len(),
+                tool_context.context_bank.len()
+            );
+        }
+
         // Ensure workspace directory exists
         let _ = std::fs::create_dir_all(&workspace_dir);
 
@@ -1030,7 +1357,10 @@ impl MessageDispatcher {
                 ).await
             } else {
                 // Simple generation without tools - with x402 event emission
-                match client.generate_text_with_events(messages.clone(), &self.broadcaster, message.channel_id).await {
+                let ai_started = std::time::Instant::now();
+                let text_result = client.generate_text_with_events(messages.clone(), &self.broadcaster, message.channel_id).await;
+                telemetry::record_text_response(&text_result, ai_started.elapsed().as_millis() as u64);
+                match text_result {
                     Ok((content, payment)) => {
                         // Save x402 payment if one was made
                         if let Some(ref payment_info) = payment {
@@ -1112,6 +1442,20 @@ impl MessageDispatcher {
             }
         };
 
+        // Snapshot registers and context bank so a fork of this session can resume them.
+        // Only written when it differs from the stored snapshot (or there is state to keep).
+        let registers = tool_context.registers.snapshot();
+        let context_bank = serde_json::json!(tool_context.context_bank.items());
+        let tool_state_changed = match stored_tool_state {
+            Some(ref state) => state.registers != registers || state.context_bank != context_bank,
+            None => !tool_context.registers.keys().is_empty() || !tool_context.context_bank.is_empty(),
+        };
+        if tool_state_changed
+            && let Err(e) = self.db.save_session_tool_state(session.id, &registers, &context_bank)
+        {
+            log::warn!("[DISPATCH] Failed to save tool state for session {}: {}", session.id, e);
+        }
+
         match final_response {
             Ok((response, delivered_via_say_to_user)) => {
                 // Estimate tokens for the response
@@ -1226,7 +1570,22 @@ impl MessageDispatcher {
                 heartbeat_handle.abort();
                 telemetry::clear_active_collector();
 
-                DispatchResult::success(response)
+                // Files queued by say_to_user are delivered by the channel adapter;
+                // anything the channel can't take is reported in the response instead
+                let (attachments, skipped) = crate::channels::attachments::split_for_upload(
+                    tool_context.outbound_files.take(),
+                    &message.channel_type,
+                    tool_context.output_type,
+                );
+                if !attachments.is_empty() {
+                    log::info!("[DISPATCH] Delivering {} outbound file(s) with the response", attachments.len());
+                }
+                let response = if skipped.is_empty() {
+                    response
+                } else {
+                    format!("{}\n\n{}", response, skipped.join("\n"))
+                };
+                DispatchResult::success(response).with_attachments(attachments)
             }
             Err(e) => {
                 let mut error = format!("AI generation error ({}): {}", archetype_id, e);
@@ -1326,6 +1685,8 @@ impl MessageDispatcher {
                 // Clear active skill at the start of each new message to prevent stale skills
                 // from being used. Skills should only be active for the turn they were invoked.
                 orch.clear_active_skill();
+                // ...except for a skill whose workflow run is still waiting on this session
+                self.reactivate_workflow_skill(&mut orch, session_id);
                 orch
             }
             Ok(None) => {
@@ -1364,6 +1725,11 @@ impl MessageDispatcher {
         // Broadcast initial task state
         self.broadcast_tasks_update(original_message.channel_id, session_id, &orchestrator);
 
+        // A per-run subtype (e.g. from a cron job) replaces whatever the session last used
+        if let Some(subtype) = original_message.agent_subtype.as_deref().and_then(AgentSubtype::from_str) {
+            orchestrator.set_subtype(subtype);
+        }
+
         // Get the current subtype
         let subtype = orchestrator.current_subtype();
 
@@ -1401,7 +1767,10 @@ impl MessageDispatcher {
 
         if tools.is_empty() {
             log::warn!("[TOOL_LOOP] No tools available, falling back to text-only generation");
-            let (content, payment) = client.generate_text_with_events(messages, &self.broadcaster, original_message.channel_id).await?;
+            let ai_started = std::time::Instant::now();
+            let text_result = client.generate_text_with_events(messages, &self.broadcaster, original_message.channel_id).await;
+            telemetry::record_text_response(&text_result, ai_started.elapsed().as_millis() as u64);
+            let (content, payment) = text_result?;
             // Save x402 payment if one was made
             if let Some(ref payment_info) = payment {
                 if let Err(e) = self.db.record_x402_payment(
@@ -1602,10 +1971,11 @@ impl MessageDispatcher {
     ///
     /// 1. **Subtype group filtering** — each `AgentSubtype` allows specific `ToolGroup`s.
     ///    Tools outside those groups are excluded.
-    /// 2. **Skill `requires_tools` force-inclusion** — if the active skill specifies
-    ///    `requires_tools`, those tools are force-included even if their group isn't
-    ///    allowed by the subtype.
+    /// 2. **Skill/subtype `requires_tools` force-inclusion** — tools required by the
+    ///    active skill or the subtype definition are force-included even if their group
+    ///    isn't allowed by the subtype.
     /// 3. **`use_skill` pseudo-tool** — added if any skills are enabled in the DB.
+    ///    The `workflow` pseudo-tool is added alongside it when the active skill declares a workflow.
     /// 4. **Orchestrator mode tools** — e.g. `define_tasks` in TaskPlanner mode.
     /// 5. **`define_tasks` stripping** — removed unless the active skill's
     ///    `requires_tools` explicitly includes it (keeps it out of Assistant mode).
@@ -1617,10 +1987,15 @@ impl MessageDispatcher {
         subtype: AgentSubtype,
         orchestrator: &Orchestrator,
     ) -> Vec<ToolDefinition> {
-        let requires_tools = orchestrator.context().active_skill
+        let mut requires_tools = orchestrator.context().active_skill
             .as_ref()
             .map(|s| s.requires_tools.clone())
             .unwrap_or_default();
+        for tool in subtype.required_tools() {
+            if !requires_tools.contains(&tool) {
+                requires_tools.push(tool);
+            }
+        }
 
         let mut tools = if !requires_tools.is_empty() {
             self.tool_registry
@@ -1638,6 +2013,10 @@ impl MessageDispatcher {
             tools.push(skill_tool);
         }
 
+        if let Some(workflow_tool) = self.create_workflow_tool_definition(orchestrator) {
+            tools.push(workflow_tool);
+        }
+
         tools.extend(orchestrator.get_mode_tools());
 
         let skill_requires_define_tasks = requires_tools.iter().any(|t| t == "define_tasks");
@@ -1796,6 +2175,172 @@ impl MessageDispatcher {
         }
     }
 
+    /// Execute a tool through the registry and record its result for session replay.
+    /// Under test, recorded tool stubs take precedence over real execution.
+    async fn execute_tool(
+        &self,
+        tool_name: &str,
+        tool_arguments: &Value,
+        tool_context: &ToolContext,
+        exec_config: &ToolConfig,
+    ) -> crate::tools::ToolResult {
+        let start = std::time::Instant::now();
+        let result = self.execute_tool_unrecorded(tool_name, tool_arguments, tool_context, exec_config).await;
+        telemetry::record_tool_result(tool_name, tool_arguments, &result, start.elapsed().as_millis() as u64);
+        result
+    }
+
+    /// Execute a tool without recording its telemetry span (the caller records it)
+    async fn execute_tool_unrecorded(
+        &self,
+        tool_name: &str,
+        tool_arguments: &Value,
+        tool_context: &ToolContext,
+        exec_config: &ToolConfig,
+    ) -> crate::tools::ToolResult {
+        #[cfg(test)]
+        if let Some(result) = self.tool_stubs.as_ref().and_then(|s| s.next_result(tool_name)) {
+            return result;
+        }
+
+        self.tool_registry
+            .execute(tool_name, tool_arguments.clone(), tool_context, Some(exec_config))
+            .await
+    }
+
+    /// Run validators, then the tool under the watchdog timeout. With `record_span` false
+    /// the caller records the telemetry span (parallel groups record in call order).
+    async fn run_tool_call(
+        &self,
+        tool_name: &str,
+        tool_arguments: &Value,
+        tool_context: &ToolContext,
+        exec_config: &ToolConfig,
+        watchdog: &Arc<Watchdog>,
+        record_span: bool,
+    ) -> ToolCallOutcome {
+        if let Some(ref validator_registry) = self.validator_registry {
+            let validation_ctx = crate::tool_validators::ValidationContext::new(
+                tool_name.to_string(),
+                tool_arguments.clone(),
+                Arc::new(tool_context.clone()),
+            );
+            let validation_result = validator_registry.validate(&validation_ctx).await;
+            if let Some(error_msg) = validation_result.to_error_message() {
+                // Emit a skipped tool span for validator rejection
+                telemetry::emit_annotation("tool_validator_rejected", serde_json::json!({
+                    "tool_name": tool_name,
+                    "error": error_msg,
+                }));
+                return ToolCallOutcome::Rejected(error_msg);
+            }
+        }
+
+        let start = std::time::Instant::now();
+        let execution = async {
+            if record_span {
+                self.execute_tool(tool_name, tool_arguments, tool_context, exec_config).await
+            } else {
+                self.execute_tool_unrecorded(tool_name, tool_arguments, tool_context, exec_config).await
+            }
+        };
+        let result = match watchdog.guard_tool_call(tool_name, execution).await {
+            Some(result) => result,
+            None => crate::tools::ToolResult::error(format!(
+                "Tool '{}' timed out after {}s",
+                tool_name, watchdog.config().timeout_for_tool(tool_name).as_secs()
+            )),
+        };
+        ToolCallOutcome::Executed {
+            result,
+            duration_ms: start.elapsed().as_millis() as u64,
+        }
+    }
+
+    /// Config a tool call executes with: a skill that requires the tool may run it
+    /// regardless of profile/group (never in safe mode).
+    fn exec_config_for<'a>(
+        &self,
+        tool_name: &str,
+        tool_config: &'a ToolConfig,
+        orchestrator: &Orchestrator,
+        is_safe_mode: bool,
+    ) -> std::borrow::Cow<'a, ToolConfig> {
+        let skill_requires_this_tool = !is_safe_mode
+            && orchestrator.context().active_skill.as_ref()
+                .is_some_and(|s| s.requires_tools.iter().any(|t| t == tool_name));
+        if skill_requires_this_tool && !tool_config.allow_list.iter().any(|t| t == tool_name) {
+            let mut c = tool_config.clone();
+            c.allow_list.push(tool_name.to_string());
+            std::borrow::Cow::Owned(c)
+        } else {
+            std::borrow::Cow::Borrowed(tool_config)
+        }
+    }
+
+    /// Until a subtype is selected only System tools and the active skill's tools may run
+    fn blocked_until_subtype_selected(
+        tool_name: &str,
+        orchestrator: &Orchestrator,
+        current_tools: &[ToolDefinition],
+    ) -> bool {
+        let is_system_tool = current_tools.iter().any(|t| t.name == tool_name && t.group == crate::tools::types::ToolGroup::System);
+        let is_skill_required_tool = orchestrator.context().active_skill.as_ref()
+            .is_some_and(|s| s.requires_tools.iter().any(|t| t == tool_name));
+        !orchestrator.current_subtype().is_selected() && !is_system_tool && !is_skill_required_tool
+    }
+
+    /// Whether a call from a multi-call AI response may join a parallel group
+    fn can_run_in_parallel(
+        &self,
+        tool_name: &str,
+        orchestrator: &Orchestrator,
+        current_tools: &[ToolDefinition],
+    ) -> bool {
+        self.tool_registry.is_parallel_safe(tool_name)
+            && !Self::blocked_until_subtype_selected(tool_name, orchestrator, current_tools)
+    }
+
+    /// Execute a run of parallel-safe tool calls concurrently. Outcomes (and telemetry
+    /// spans) come back in call order whatever order the calls finish in.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_tool_calls_in_parallel(
+        &self,
+        calls: &[crate::ai::ToolCall],
+        tool_config: &ToolConfig,
+        tool_context: &ToolContext,
+        is_safe_mode: bool,
+        orchestrator: &Orchestrator,
+        watchdog: &Arc<Watchdog>,
+    ) -> Vec<ToolCallOutcome> {
+        let names: Vec<&str> = calls.iter().map(|c| c.name.as_str()).collect();
+        log::info!("[ORCHESTRATED_LOOP] Running {} tool calls in parallel: {:?}", calls.len(), names);
+
+        let configs: Vec<_> = calls
+            .iter()
+            .map(|c| self.exec_config_for(&c.name, tool_config, orchestrator, is_safe_mode))
+            .collect();
+        let started = std::time::Instant::now();
+        let outcomes = futures_util::future::join_all(calls.iter().zip(&configs).map(|(call, config)| {
+            self.run_tool_call(&call.name, &call.arguments, tool_context, config, watchdog, false)
+        }))
+        .await;
+
+        for (position, (call, outcome)) in calls.iter().zip(&outcomes).enumerate() {
+            if let ToolCallOutcome::Executed { result, duration_ms } = outcome {
+                telemetry::record_parallel_tool_result(
+                    &call.name, &call.arguments, result, *duration_ms, position, calls.len(),
+                );
+            }
+        }
+        telemetry::emit_annotation("parallel_tool_calls", serde_json::json!({
+            "tools": names,
+            "wall_time_ms": started.elapsed().as_millis() as u64,
+        }));
+
+        outcomes
+    }
+
     /// Shared per-tool-call processing used by both native and text tool paths.
     ///
     /// Processes a single tool call: logging, orchestrator dispatch, skill handling,
@@ -1823,6 +2368,8 @@ impl MessageDispatcher {
         // The current tools visible to the AI this iteration (for subtype check)
         current_tools: &[ToolDefinition],
         watchdog: &Arc<Watchdog>,
+        // Outcome of a call already executed as part of a parallel group
+        prefetched: Option<ToolCallOutcome>,
     ) -> ToolCallProcessed {
         let args_pretty = serde_json::to_string_pretty(tool_arguments)
             .unwrap_or_else(|_| tool_arguments.to_string());
@@ -2002,14 +2549,15 @@ impl MessageDispatcher {
                 }
                 skill_result
             }
+        } else if tool_name == workflows::TOOL_NAME {
+            self.execute_workflow_tool(
+                tool_arguments, tool_config, tool_context, original_message,
+                session_id, is_safe_mode, orchestrator, watchdog,
+            ).await
         } else {
             // Check if subtype is None - allow System tools and skill-required tools,
             // but block everything else until a subtype is selected
-            let current_subtype = orchestrator.current_subtype();
-            let is_system_tool = current_tools.iter().any(|t| t.name == tool_name && t.group == crate::tools::types::ToolGroup::System);
-            let is_skill_required_tool = orchestrator.context().active_skill.as_ref()
-                .map_or(false, |s| s.requires_tools.iter().any(|t| t == tool_name));
-            if !current_subtype.is_selected() && !is_system_tool && !is_skill_required_tool {
+            if Self::blocked_until_subtype_selected(tool_name, orchestrator, current_tools) {
                 log::warn!(
                     "[SUBTYPE] Blocked tool '{}' - no subtype selected. Must call set_agent_subtype first.",
                     tool_name
@@ -2023,77 +2571,23 @@ impl MessageDispatcher {
                     tool_name
                 ))
             } else {
-                // If a skill is active and requires this tool (and we're not in safe mode),
-                // create a config override that allows execution regardless of profile/group.
-                let skill_requires_this_tool = !is_safe_mode
-                    && orchestrator.context().active_skill.as_ref()
-                        .map_or(false, |s| s.requires_tools.iter().any(|t| t == tool_name));
-                let effective_config;
-                let exec_config = if skill_requires_this_tool {
-                    effective_config = {
-                        let mut c = tool_config.clone();
-                        if !c.allow_list.iter().any(|t| t == tool_name) {
-                            c.allow_list.push(tool_name.to_string());
-                        }
-                        c
-                    };
-                    &effective_config
-                } else {
-                    tool_config
+                // Calls from a parallel group already ran; only their bookkeeping is left
+                let outcome = match prefetched {
+                    Some(outcome) => outcome,
+                    None => {
+                        let exec_config = self.exec_config_for(tool_name, tool_config, orchestrator, is_safe_mode);
+                        self.run_tool_call(tool_name, tool_arguments, tool_context, &exec_config, watchdog, true).await
+                    }
                 };
-
-                // Run tool validators before execution
-                if let Some(ref validator_registry) = self.validator_registry {
-                    let validation_ctx = crate::tool_validators::ValidationContext::new(
-                        tool_name.to_string(),
-                        tool_arguments.clone(),
-                        Arc::new(tool_context.clone()),
-                    );
-                    let validation_result = validator_registry.validate(&validation_ctx).await;
-                    if let Some(error_msg) = validation_result.to_error_message() {
-                        // Emit a skipped tool span for validator rejection
-                        telemetry::emit_annotation("tool_validator_rejected", serde_json::json!({
-                            "tool_name": tool_name,
-                            "error": error_msg,
-                        }));
-                        crate::tools::ToolResult::error(error_msg)
-                    } else {
-                        let start = std::time::Instant::now();
-                        let tool_result = match watchdog.guard_tool_call(
-                            tool_name,
-                            self.tool_registry.execute(tool_name, tool_arguments.clone(), tool_context, Some(exec_config)),
-                        ).await {
-                            Some(result) => result,
-                            None => crate::tools::ToolResult::error(format!(
-                                "Tool '{}' timed out after {}s",
-                                tool_name, watchdog.config().timeout_for_tool(tool_name).as_secs()
-                            )),
-                        };
-                        let duration_ms = start.elapsed().as_millis() as u64;
+                match outcome {
+                    ToolCallOutcome::Rejected(error_msg) => crate::tools::ToolResult::error(error_msg),
+                    ToolCallOutcome::Executed { result: tool_result, duration_ms } => {
                         if tool_result.success {
                             orchestrator.record_tool_call(tool_name);
                         }
                         watchdog.reward_emitter().tool_completed(tool_name, tool_result.success, duration_ms);
                         tool_result
                     }
-                } else {
-                    let start = std::time::Instant::now();
-                    let tool_result = match watchdog.guard_tool_call(
-                        tool_name,
-                        self.tool_registry.execute(tool_name, tool_arguments.clone(), tool_context, Some(exec_config)),
-                    ).await {
-                        Some(result) => result,
-                        None => crate::tools::ToolResult::error(format!(
-                            "Tool '{}' timed out after {}s",
-                            tool_name, watchdog.config().timeout_for_tool(tool_name).as_secs()
-                        )),
-                    };
-                    let duration_ms = start.elapsed().as_millis() as u64;
-                    if tool_result.success {
-                        orchestrator.record_tool_call(tool_name);
-                    }
-                    watchdog.reward_emitter().tool_completed(tool_name, tool_result.success, duration_ms);
-                    tool_result
                 }
             }
         };
@@ -2666,6 +3160,9 @@ impl MessageDispatcher {
         let max_tool_iterations = self.db.get_bot_settings()
             .map(|s| s.max_tool_iterations as usize)
             .unwrap_or(FALLBACK_MAX_TOOL_ITERATIONS);
+        // A per-run cap can only lower the limit
+        let max_tool_iterations = original_message.max_tool_iterations
+            .map_or(max_tool_iterations, |cap| max_tool_iterations.min(cap as usize));
 
         // Build conversation with orchestrator's system prompt prepended
         let mut conversation = messages.clone();
@@ -2700,6 +3197,7 @@ impl MessageDispatcher {
                 conversation.push(Message {
                     role: MessageRole::System,
                     content: merged_content,
+                    images: Vec::new(),
                 });
             }
             conversation.extend(non_system);
@@ -2709,6 +3207,7 @@ impl MessageDispatcher {
         orchestrator.clear_waiting_for_user_context();
 
         let mut tool_history: Vec<ToolHistoryEntry> = Vec::new();
+        let mut tool_ledger = ToolLedger::default();
         let mut iterations = 0;
         let mut tool_call_log: Vec<String> = Vec::new();
         let mut orchestrator_complete = false;
@@ -2977,6 +3476,7 @@ impl MessageDispatcher {
             );
 
             // Generate with native tool support and progress notifications
+            tool_ledger.render_into(&mut conversation);
             let mut ai_response = match self.generate_with_progress(
                 &client,
                 conversation.clone(),
@@ -3094,6 +3594,7 @@ impl MessageDispatcher {
                     conversation.push(Message {
                         role: MessageRole::Assistant,
                         content: ai_response.content.clone(),
+                        images: Vec::new(),
                     });
                     conversation.push(Message {
                         role: MessageRole::User,
@@ -3101,6 +3602,7 @@ impl MessageDispatcher {
                             "[SYSTEM ERROR] {}\n\nYou MUST call tools to gather information. Do not respond with made-up data.",
                             warning_msg
                         ),
+                        images: Vec::new(),
                     });
 
                     // Continue the loop to force tool calling
@@ -3117,10 +3619,12 @@ impl MessageDispatcher {
                     conversation.push(Message {
                         role: MessageRole::Assistant,
                         content: ai_response.content.clone(),
+                        images: Vec::new(),
                     });
                     conversation.push(Message {
                         role: MessageRole::User,
                         content: "[SYSTEM] You have pending tasks to complete. Please call the appropriate tools to continue working on the current task.".to_string(),
+                        images: Vec::new(),
                     });
                     continue;
                 }
@@ -3231,8 +3735,32 @@ impl MessageDispatcher {
             }
 
             let mut batch_state = BatchState::new();
+            let calls = &ai_response.tool_calls;
+            let mut prefetched: Vec<Option<ToolCallOutcome>> = calls.iter().map(|_| None).collect();
+
+            for (index, call) in calls.iter().enumerate() {
+                // At the start of a run of consecutive parallel-safe calls, execute the whole
+                // run concurrently; results are still processed one by one in call order.
+                if prefetched[index].is_none() && !batch_state.define_tasks_replaced_queue {
+                    let run_len = calls[index..]
+                        .iter()
+                        .take_while(|c| self.can_run_in_parallel(&c.name, orchestrator, &current_tools))
+                        .count();
+                    if run_len > 1 {
+                        let outcomes = self.run_tool_calls_in_parallel(
+                            &calls[index..index + run_len],
+                            tool_config,
+                            tool_context,
+                            is_safe_mode,
+                            orchestrator,
+                            watchdog,
+                        ).await;
+                        for (slot, outcome) in prefetched[index..].iter_mut().zip(outcomes) {
+                            *slot = Some(outcome);
+                        }
+                    }
+                }
 
-            for call in &ai_response.tool_calls {
                 let processed = self.process_tool_call_result(
                     &call.name,
                     &call.arguments,
@@ -3249,6 +3777,7 @@ impl MessageDispatcher {
                     orchestrator,
                     &current_tools,
                     watchdog,
+                    prefetched[index].take(),
                 ).await;
 
                 // Update loop-level flags from the processed result
@@ -3282,16 +3811,15 @@ impl MessageDispatcher {
                 orchestrator_complete = false;
             }
 
-            // Add to tool history (keep only last N entries to prevent context bloat)
-            const MAX_TOOL_HISTORY: usize = 10;
+            // Add to tool history, then apply the retention policy to prevent context bloat
+            // (evicted results are summarized into the ledger, register-backed results are pinned)
             tool_history.push(ToolHistoryEntry::new(
                 ai_response.tool_calls,
                 tool_responses,
             ));
-            if tool_history.len() > MAX_TOOL_HISTORY {
-                // Remove oldest entries, keeping the most recent
-                tool_history.drain(0..tool_history.len() - MAX_TOOL_HISTORY);
-            }
+            let retention_report = ToolHistoryRetention::default()
+                .apply(&mut tool_history, &mut tool_ledger, &tool_context.registers.source_tools());
+            record_retention_report("ORCHESTRATED_LOOP", &retention_report);
 
             // If orchestrator is complete, break the loop
             if orchestrator_complete {
@@ -3347,6 +3875,9 @@ impl MessageDispatcher {
         let max_tool_iterations = self.db.get_bot_settings()
             .map(|s| s.max_tool_iterations as usize)
             .unwrap_or(FALLBACK_MAX_TOOL_ITERATIONS);
+        // A per-run cap can only lower the limit
+        let max_tool_iterations = original_message.max_tool_iterations
+            .map_or(max_tool_iterations, |cap| max_tool_iterations.min(cap as usize));
 
         // Note: define_tasks stripping is handled by build_tool_list() at the call site
 
@@ -3382,6 +3913,7 @@ impl MessageDispatcher {
                 conversation.push(Message {
                     role: MessageRole::System,
                     content: merged_content,
+                    images: Vec::new(),
                 });
             }
             conversation.extend(non_system);
@@ -3391,6 +3923,7 @@ impl MessageDispatcher {
         orchestrator.clear_waiting_for_user_context();
 
         let mut final_response = String::new();
+        let mut tool_ledger = ToolLedger::default();
         let mut iterations = 0;
         let mut tool_call_log: Vec<String> = Vec::new();
         let mut orchestrator_complete = false;
@@ -3483,11 +4016,15 @@ impl MessageDispatcher {
                 tools.iter().map(|t| &t.name).collect::<Vec<_>>()
             );
 
-            let (ai_content, payment) = match client.generate_text_with_events(
+            tool_ledger.render_into(&mut conversation);
+            let ai_started = std::time::Instant::now();
+            let text_result = client.generate_text_with_events(
                 conversation.clone(),
                 &self.broadcaster,
                 original_message.channel_id,
-            ).await {
+            ).await;
+            telemetry::record_text_response(&text_result, ai_started.elapsed().as_millis() as u64);
+            let (ai_content, payment) = match text_result {
                 Ok(result) => result,
                 Err(e) => {
                     // AI generation failed - save summary of work done so far
@@ -3561,6 +4098,7 @@ impl MessageDispatcher {
                             conversation.push(Message {
                                 role: MessageRole::User,
                                 content: loop_warning,
+                                images: Vec::new(),
                             });
 
                             // Give the AI one more chance to correct, then break
@@ -3610,6 +4148,7 @@ impl MessageDispatcher {
                             orchestrator,
                             &current_tools_snapshot,
                             watchdog,
+                            None,
                         ).await;
 
                         // Update loop-level flags
@@ -3632,6 +4171,7 @@ impl MessageDispatcher {
                         conversation.push(Message {
                             role: MessageRole::Assistant,
                             content: ai_content.clone(),
+                            images: Vec::new(),
                         });
                         conversation.push(Message {
                             role: MessageRole::User,
@@ -3640,18 +4180,15 @@ impl MessageDispatcher {
                                 &tool_result_content,
                                 true,
                             ),
+                            images: Vec::new(),
                         });
 
-                        // Truncate conversation to prevent context bloat
-                        // Keep system prompt(s) at start + last N message pairs
+                        // Apply the retention policy to prevent context bloat:
+                        // keep system prompt(s) + last N messages, summarize the rest into the ledger
                         const MAX_CONVERSATION_MESSAGES: usize = 20;
-                        let system_count = conversation.iter()
-                            .take_while(|m| m.role == MessageRole::System)
-                            .count();
-                        if conversation.len() > system_count + MAX_CONVERSATION_MESSAGES {
-                            let remove_count = conversation.len() - system_count - MAX_CONVERSATION_MESSAGES;
-                            conversation.drain(system_count..system_count + remove_count);
-                        }
+                        let retention_report = ToolHistoryRetention::default()
+                            .apply_to_conversation(&mut conversation, &mut tool_ledger, MAX_CONVERSATION_MESSAGES);
+                        record_retention_report("TEXT_ORCHESTRATED", &retention_report);
 
                         if orchestrator_complete {
                             break;
@@ -3688,6 +4225,7 @@ impl MessageDispatcher {
                             conversation.push(Message {
                                 role: MessageRole::Assistant,
                                 content: agent_response.body.clone(),
+                                images: Vec::new(),
                             });
                             conversation.push(Message {
                                 role: MessageRole::User,
@@ -3695,6 +4233,7 @@ impl MessageDispatcher {
                                     "[SYSTEM ERROR] {}\n\nYou MUST call tools to gather information. Do not respond with made-up data.",
                                     warning_msg
                                 ),
+                                images: Vec::new(),
                             });
 
                             // Continue the loop to force tool calling
@@ -3815,8 +4354,18 @@ impl MessageDispatcher {
                     result.push_str("\n\n");
                 }
 
+                let workflow = skill.workflow.as_deref()
+                    .and_then(|source| workflows::WorkflowDefinition::parse(source, &skill.requires_tools).ok());
+                if let Some(ref definition) = workflow {
+                    result.push_str(&workflows::skill_instructions(definition));
+                }
+
                 result.push_str(&format!("### User Query:\n{}\n\n", input));
-                result.push_str("**IMPORTANT:** Now call the actual tools mentioned in the instructions above. Do NOT call use_skill again.");
+                if workflow.is_some() {
+                    result.push_str("**IMPORTANT:** Now call `workflow` with action \"start\". Do NOT call use_skill again.");
+                } else {
+                    result.push_str("**IMPORTANT:** Now call the actual tools mentioned in the instructions above. Do NOT call use_skill again.");
+                }
 
                 crate::tools::ToolResult::success(&result)
             }
@@ -3834,6 +4383,276 @@ impl MessageDispatcher {
         }
     }
 
+    /// Parse the workflow declared by an enabled skill, logging (and ignoring) invalid ones
+    fn load_skill_workflow(&self, skill_name: &str) -> Option<(crate::skills::DbSkill, workflows::WorkflowDefinition)> {
+        let skill = self.db.get_enabled_skill_by_name(skill_name).ok().flatten()?;
+        let source = skill.workflow.as_deref()?;
+        match workflows::WorkflowDefinition::parse(source, &skill.requires_tools) {
+            Ok(definition) => Some((skill, definition)),
+            Err(e) => {
+                log::warn!("[WORKFLOW] Skill '{}' has an invalid workflow: {}", skill_name, e);
+                None
+            }
+        }
+    }
+
+    /// Create the "workflow" tool definition if the active skill declares a workflow
+    fn create_workflow_tool_definition(&self, orchestrator: &Orchestrator) -> Option<ToolDefinition> {
+        let skill_name = orchestrator.context().active_skill.as_ref()?.name.clone();
+        let (skill, definition) = self.load_skill_workflow(&skill_name)?;
+        Some(workflows::tool_definition(&skill.name, &definition))
+    }
+
+    /// Re-activate the skill of a session's unfinished workflow run, so the agent can
+    /// approve, answer or resume it in the new turn
+    fn reactivate_workflow_skill(&self, orchestrator: &mut Orchestrator, session_id: i64) {
+        let run = match self.db.get_open_workflow_run(session_id) {
+            Ok(Some(run)) => run,
+            Ok(None) => return,
+            Err(e) => {
+                log::warn!("[WORKFLOW] Failed to load open run for session {}: {}", session_id, e);
+                return;
+            }
+        };
+        let Some((skill, definition)) = self.load_skill_workflow(&run.skill_name) else {
+            log::warn!(
+                "[WORKFLOW] Run {} belongs to skill '{}', which is no longer enabled",
+                run.run_id, run.skill_name
+            );
+            return;
+        };
+
+        let skill_base_dir = format!("{}/{}", crate::config::skills_dir(), skill.name);
+        let mut instructions = workflows::resume_note(&run, &definition);
+        instructions.push_str(&skill.body.replace("{baseDir}", &skill_base_dir));
+
+        log::info!(
+            "[WORKFLOW] Session {} has an open '{}' run ({}), re-activating the skill",
+            session_id, run.skill_name, run.status.as_str()
+        );
+        orchestrator.context_mut().active_skill = Some(crate::ai::multi_agent::types::ActiveSkill {
+            name: skill.name,
+            instructions,
+            activated_at: chrono::Utc::now().to_rfc3339(),
+            tool_calls_made: 0,
+            requires_tools: skill.requires_tools,
+        });
+    }
+
+    /// Execute the special "workflow" tool: start, continue or inspect the active
+    /// skill's workflow. Steps run here, not through the agent.
+    #[allow(clippy::too_many_arguments)]
+    async fn execute_workflow_tool(
+        &self,
+        params: &Value,
+        tool_config: &ToolConfig,
+        tool_context: &ToolContext,
+        original_message: &NormalizedMessage,
+        session_id: i64,
+        is_safe_mode: bool,
+        orchestrator: &mut Orchestrator,
+        watchdog: &Arc<Watchdog>,
+    ) -> crate::tools::ToolResult {
+        use crate::models::WorkflowRunStatus;
+        use crate::tools::ToolResult;
+
+        let action = params.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        let open_run = match self.db.get_open_workflow_run(session_id) {
+            Ok(run) => run,
+            Err(e) => return ToolResult::error(format!("Failed to load workflow state: {}", e)),
+        };
+
+        let (mut run, skill, definition) = if action == "start" {
+            if let Some(run) = open_run {
+                return ToolResult::error(format!(
+                    "A `{}` workflow is already in progress ({}). Resume, finish or cancel it before starting another.",
+                    run.skill_name,
+                    run.status.as_str()
+                ));
+            }
+            let Some(skill_name) = orchestrator.context().active_skill.as_ref().map(|s| s.name.clone()) else {
+                return ToolResult::error("No skill is active. Call use_skill first.");
+            };
+            let Some((skill, definition)) = self.load_skill_workflow(&skill_name) else {
+                return ToolResult::error(format!("Skill '{}' has no valid workflow", skill_name));
+            };
+            let supplied = params.get("inputs").and_then(|v| v.as_object()).cloned().unwrap_or_default();
+            let variables = match definition.bind_inputs(&supplied) {
+                Ok(v) => v,
+                Err(e) => return ToolResult::error(format!("{}\n\nInputs:\n{}", e, definition.describe_inputs())),
+            };
+            let source = skill.workflow.clone().unwrap_or_default();
+            let run = match self.db.create_workflow_run(
+                session_id,
+                original_message.channel_id,
+                &skill.name,
+                &source,
+                &variables,
+            ) {
+                Ok(run) => run,
+                Err(e) => return ToolResult::error(format!("Failed to start workflow: {}", e)),
+            };
+            log::info!("[WORKFLOW] Started '{}' run {} in session {}", skill.name, run.run_id, session_id);
+            (run, skill, definition)
+        } else {
+            let Some(mut run) = open_run else {
+                return ToolResult::error("No workflow is in progress in this session.");
+            };
+
+            match action {
+                "status" => {
+                    return ToolResult::success(format!(
+                        "Workflow `{}` is {} ({} step(s) done).\n\n{}",
+                        run.skill_name,
+                        run.status.as_str(),
+                        run.current_step,
+                        workflows::progress_summary(&run)
+                    ));
+                }
+                "cancel" => {
+                    let reason = params.get("reason").and_then(|v| v.as_str()).unwrap_or("Canceled by the agent");
+                    run.status = WorkflowRunStatus::Canceled;
+                    run.error = Some(reason.to_string());
+                    run.paused_at = None;
+                    if let Err(e) = self.db.save_workflow_run(&run) {
+                        return ToolResult::error(format!("Failed to cancel workflow: {}", e));
+                    }
+                    return ToolResult::success(format!("Workflow `{}` canceled.", run.skill_name));
+                }
+                _ => {}
+            }
+
+            // The run keeps the definition it started with; tools are checked against the skill as it is now
+            let Some(skill) = self.db.get_enabled_skill_by_name(&run.skill_name).ok().flatten() else {
+                return ToolResult::error(format!(
+                    "Skill '{}' is no longer enabled. Cancel the workflow.",
+                    run.skill_name
+                ));
+            };
+            let definition = match workflows::WorkflowDefinition::parse(&run.definition, &skill.requires_tools) {
+                Ok(d) => d,
+                Err(e) => return ToolResult::error(format!("{}. Cancel the workflow.", e)),
+            };
+
+            let step_result = match action {
+                "approve" => {
+                    let paused_at = run.paused_at.as_deref()
+                        .and_then(|p| chrono::DateTime::parse_from_rfc3339(p).ok())
+                        .map(|dt| dt.with_timezone(&Utc));
+                    let last_user_message = self.db.get_last_user_message_at(session_id).ok().flatten();
+                    let answered = matches!((paused_at, last_user_message), (Some(p), Some(m)) if m > p);
+                    if run.status == WorkflowRunStatus::AwaitingApproval && !answered {
+                        return ToolResult::error(
+                            "The user has not replied to the approval request yet. Do not approve on their behalf; \
+                             wait for their next message.",
+                        );
+                    }
+                    workflows::engine::approve(&definition, &mut run)
+                }
+                "reject" => {
+                    let reason = params.get("reason").and_then(|v| v.as_str()).unwrap_or("Declined by the user");
+                    match workflows::engine::reject(&definition, &mut run, reason) {
+                        Ok(()) => {
+                            if let Err(e) = self.db.save_workflow_run(&run) {
+                                log::warn!("[WORKFLOW] Failed to save rejected run {}: {}", run.run_id, e);
+                            }
+                            return ToolResult::success(format!(
+                                "Workflow `{}` stopped; nothing further was executed.\n\n{}",
+                                run.skill_name,
+                                workflows::progress_summary(&run)
+                            ));
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+                "provide" => {
+                    let values = params.get("values").and_then(|v| v.as_object()).cloned().unwrap_or_default();
+                    workflows::engine::provide(&definition, &mut run, &values)
+                }
+                "resume" => Ok(()),
+                other => Err(format!("Unknown workflow action '{}'", other)),
+            };
+            if let Err(e) = step_result {
+                return ToolResult::error(e);
+            }
+
+            tool_context.registers.restore(&run.registers);
+            (run, skill, definition)
+        };
+
+        // Steps get the same permissions the skill grants its tools when the agent calls them
+        let mut exec_config = tool_config.clone();
+        if !is_safe_mode {
+            for tool in &skill.requires_tools {
+                if !exec_config.allow_list.contains(tool) {
+                    exec_config.allow_list.push(tool.clone());
+                }
+            }
+        }
+        let runner = WorkflowStepRunner {
+            dispatcher: self,
+            tool_context,
+            exec_config,
+            original_message,
+            session_id,
+            is_safe_mode,
+            watchdog,
+        };
+        let db = self.db.clone();
+        let mut checkpoint = move |run: &crate::models::WorkflowRun| {
+            if let Err(e) = db.save_workflow_run(run) {
+                log::warn!("[WORKFLOW] Failed to checkpoint run {}: {}", run.run_id, e);
+            }
+        };
+
+        let logged = run.step_log.len();
+        let outcome = workflows::engine::advance(
+            &definition,
+            &mut run,
+            &tool_context.registers,
+            &runner,
+            &mut checkpoint,
+        ).await;
+        for record in &run.step_log[logged..] {
+            if let (Some(tool), "done") = (record.tool.as_deref(), record.outcome.as_str()) {
+                orchestrator.record_tool_call(tool);
+            }
+        }
+
+        let progress = workflows::progress_summary(&run);
+        let run_metadata = serde_json::json!({
+            "workflow_run_id": run.run_id,
+            "workflow_status": run.status.as_str(),
+        });
+        match outcome {
+            workflows::Advance::Completed => {
+                let variables = serde_json::to_string_pretty(&run.variables).unwrap_or_default();
+                ToolResult::success(format!(
+                    "✅ Workflow `{}` completed.\n\n{}\n\nOutputs:\n```json\n{}\n```\n\nReport the outcome to the user.",
+                    run.skill_name, progress, variables
+                ))
+                .with_metadata(run_metadata)
+            }
+            workflows::Advance::AwaitingApproval { prompt, .. } => {
+                let mut metadata = run_metadata;
+                metadata["requires_user_response"] = Value::Bool(true);
+                ToolResult::success(format!("{}\n\nReply to approve or decline.", prompt)).with_metadata(metadata)
+            }
+            workflows::Advance::AwaitingInput { step, prompt, outputs } => ToolResult::success(format!(
+                "Workflow paused at step `{}`: {}\n\nCall `workflow` with action \"provide\" and `values` for: {}\n\n{}",
+                step,
+                prompt,
+                outputs.join(", "),
+                progress
+            ))
+            .with_metadata(run_metadata),
+            workflows::Advance::Failed { step, error } => ToolResult::error(format!(
+                "Workflow `{}` failed at step `{}`: {}\n\n{}",
+                run.skill_name, step, error, progress
+            )),
+        }
+    }
+
     /// Load SOUL.md content if it exists
     fn load_soul() -> Option<String> {
         // Primary: soul directory from config (stark-backend/soul/SOUL.md)
@@ -4160,6 +4979,7 @@ impl MessageDispatcher {
         ));
 
         // Spawn the actual AI request
+        let ai_started = std::time::Instant::now();
         let ai_future = client.generate_with_tools(conversation, tool_history, tools.clone());
         tokio::pin!(ai_future);
 
@@ -4229,11 +5049,16 @@ impl MessageDispatcher {
                         let _ = hook_manager.execute(HookEvent::OnWatchdogTimeout, &mut hook_ctx).await;
                     }
 
-                    return Err(crate::ai::AiError::new(
+                    let timeout_err = Err(crate::ai::AiError::new(
                         format!("LLM call timed out after {}s", llm_timeout.as_secs())
                     ));
+                    telemetry::record_ai_response(&timeout_err, ai_started.elapsed().as_millis() as u64);
+                    return timeout_err;
                 }
                 result = &mut ai_future => {
+                    // Record the raw response for session replay
+                    telemetry::record_ai_response(&result, ai_started.elapsed().as_millis() as u64);
+
                     // Complete the thinking task
                     if let Some(ref task_id) = thinking_task_id {
                         self.execution_tracker.complete_task(task_id);
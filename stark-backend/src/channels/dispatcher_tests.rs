@@ -12,6 +12,7 @@ use crate::execution::ExecutionTracker;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
 use crate::skills::SkillRegistry;
+use crate::telemetry::ReplayRecording;
 use crate::tools::{self, ToolRegistry};
 use serde_json::json;
 use std::sync::Arc;
@@ -23,6 +24,7 @@ use tokio::time::{timeout, Duration};
 /// and a MessageDispatcher with a MockAiClient.
 struct TestHarness {
     dispatcher: MessageDispatcher,
+    db: Arc<Database>,
     _client_id: String,
     event_rx: mpsc::Receiver<GatewayEvent>,
     channel_id: i64,
@@ -106,6 +108,7 @@ impl TestHarness {
 
         TestHarness {
             dispatcher,
+            db,
             _client_id: client_id,
             event_rx,
             channel_id,
@@ -173,12 +176,43 @@ impl TestHarness {
 
         TestHarness {
             dispatcher,
+            db,
             _client_id: client_id,
             event_rx,
             channel_id,
         }
     }
 
+    /// Build a harness that replays a recorded session: AI responses come from
+    /// the recording and tool calls return the recorded results instead of executing.
+    /// Returns the stubs so tests can check every recorded result was consumed.
+    fn replay(
+        channel_type: &str,
+        safe_mode: bool,
+        recording: &ReplayRecording,
+    ) -> (Self, Arc<crate::telemetry::ToolStubs>) {
+        let mut harness = Self::new(channel_type, safe_mode, false, vec![]);
+        let stubs = Arc::new(recording.tool_stubs());
+        harness.dispatcher = harness
+            .dispatcher
+            .with_mock_ai_client(recording.mock_client())
+            .with_tool_stubs(stubs.clone());
+        (harness, stubs)
+    }
+
+    /// Collect the replay recording (AI responses + tool results) persisted for this harness's sessions.
+    fn recording(&self) -> ReplayRecording {
+        let mut sessions: Vec<_> = self.db.list_chat_sessions().expect("list sessions")
+            .into_iter()
+            .filter(|s| s.channel_id == self.channel_id)
+            .collect();
+        sessions.sort_by_key(|s| s.id);
+        let spans = sessions.iter()
+            .flat_map(|s| self.dispatcher.telemetry_store().get_session_spans(s.id))
+            .collect();
+        ReplayRecording::from_spans(spans)
+    }
+
     /// Create a NormalizedMessage for this harness.
     fn make_message(&self, text: &str, force_safe_mode: bool) -> NormalizedMessage {
         NormalizedMessage {
@@ -193,6 +227,15 @@ impl TestHarness {
             session_mode: None,
             selected_network: None,
             force_safe_mode,
+            safe_mode_fallback: false,
+            endpoint_override: None,
+            target_session_id: None,
+            attachments: Vec::new(),
+            tool_profile: None,
+            thinking_level: None,
+            agent_subtype: None,
+            max_tool_iterations: None,
+            max_tx_value_wei: None,
         }
     }
 
@@ -885,6 +928,15 @@ async fn swap_flow_realistic() {
         session_mode: None,
         selected_network: None,
         force_safe_mode: false,
+        safe_mode_fallback: false,
+        endpoint_override: None,
+        target_session_id: None,
+        attachments: Vec::new(),
+        tool_profile: None,
+        thinking_level: None,
+        agent_subtype: None,
+        max_tool_iterations: None,
+        max_tx_value_wei: None,
     };
 
     eprintln!("  Dispatching: \"{}\"", msg.text);
@@ -1553,6 +1605,76 @@ async fn say_to_user_mixed_with_other_tools_does_not_trigger_loop_break() {
     );
 }
 
+// ============================================================================
+// Session replay: record a session, re-run it offline, diff the traces
+// ============================================================================
+
+#[tokio::test]
+async fn replay_reproduces_recorded_session() {
+    let responses = vec![
+        AiResponse::with_tools(
+            String::new(),
+            vec![
+                tool_call("say_to_user", json!({"message": "Starting work..."})),
+                tool_call("set_agent_subtype", json!({"subtype": "general"})),
+            ],
+        ),
+        AiResponse::with_tools(
+            String::new(),
+            vec![tool_call(
+                "task_fully_completed",
+                json!({"summary": "All done."}),
+            )],
+        ),
+    ];
+
+    let mut original = TestHarness::new("web", false, false, responses);
+    let (result, _events) = original.dispatch("do something", false).await;
+    assert!(result.error.is_none(), "dispatch should succeed: {:?}", result.error);
+
+    let recording = original.recording();
+    assert!(recording.ai_response_count() >= 2, "AI responses should be recorded");
+    assert!(recording.tool_result_count() >= 1, "tool results should be recorded");
+
+    // Re-run offline from the recording alone
+    let (mut replay, stubs) = TestHarness::replay("web", false, &recording);
+    let (replay_result, _events) = replay.dispatch("do something", false).await;
+    assert!(replay_result.error.is_none(), "replay should succeed: {:?}", replay_result.error);
+    assert_eq!(replay_result.response, result.response);
+    assert_eq!(stubs.remaining(), 0, "every recorded tool result should be consumed");
+
+    let diff = recording.diff(&replay.recording());
+    assert!(diff.is_identical(), "{}", diff.summary());
+}
+
+#[tokio::test]
+async fn replay_diff_reports_first_divergence() {
+    let responses = vec![
+        AiResponse::with_tools(
+            String::new(),
+            vec![tool_call("say_to_user", json!({"message": "Here's your answer", "finished_task": true}))],
+        ),
+    ];
+
+    let mut harness = TestHarness::new("web", false, false, responses);
+    harness.dispatch("do something", false).await;
+    let recording = harness.recording();
+
+    // A "fixed" run where the AI says something different
+    let mut changed = recording.clone();
+    let idx = changed.steps.iter().position(|s| matches!(s, crate::telemetry::ReplayStep::AiResponse { .. }))
+        .expect("recorded AI response");
+    changed.steps[idx] = crate::telemetry::ReplayStep::AiResponse {
+        response: Some(AiResponse::text("something else".to_string())),
+        error: None,
+    };
+
+    let diff = recording.diff(&changed);
+    assert!(!diff.is_identical());
+    assert_eq!(diff.divergences[0].index, idx);
+    assert_eq!(diff.matched_steps, recording.steps.len() - 1);
+}
+
 // ============================================================================
 // build_tool_list() unit tests
 // ============================================================================
@@ -1816,3 +1938,271 @@ fn test_build_tool_list_consistent_across_subtypes() {
     let names2: Vec<&str> = tools2.iter().map(|t| t.name.as_str()).collect();
     assert_eq!(names1, names2, "Same inputs should always produce same tool list");
 }
+
+// ============================================================================
+// Roles never lift safe mode that the adapter forced (e.g. unauthenticated email),
+// but do replace the safe-mode fallback adapters give non-admin chat users
+// ============================================================================
+
+fn say_and_finish() -> Vec<AiResponse> {
+    vec![AiResponse::with_tools(
+        String::new(),
+        vec![tool_call("say_to_user", json!({"message": "hi", "finished_task": true}))],
+    )]
+}
+
+fn harness_sessions_safe_mode(harness: &TestHarness) -> Vec<bool> {
+    harness.db.list_chat_sessions().expect("list sessions")
+        .into_iter()
+        .filter(|s| s.channel_id == harness.channel_id)
+        .map(|s| s.safe_mode)
+        .collect()
+}
+
+#[tokio::test]
+async fn role_does_not_override_forced_safe_mode() {
+    let mut harness = TestHarness::new("email", false, true, say_and_finish());
+    harness.db.assign_role("trader", Some(harness.channel_id), Some("test-user"), None).unwrap();
+
+    let (result, _) = harness.dispatch("hello", true).await;
+    assert!(result.error.is_none(), "dispatch should succeed: {:?}", result.error);
+    let sessions = harness_sessions_safe_mode(&harness);
+    assert!(!sessions.is_empty() && sessions.iter().all(|safe| *safe), "forced message must stay in safe mode");
+}
+
+#[tokio::test]
+async fn role_applies_to_unforced_message() {
+    let mut harness = TestHarness::new("email", false, false, say_and_finish());
+    harness.db.assign_role("trader", Some(harness.channel_id), Some("test-user"), None).unwrap();
+
+    let (result, _) = harness.dispatch("hello", false).await;
+    assert!(result.error.is_none(), "dispatch should succeed: {:?}", result.error);
+    assert!(harness_sessions_safe_mode(&harness).iter().all(|safe| !*safe));
+}
+
+/// Dispatch a message the way the chat adapters send it for a non-admin user
+async fn dispatch_non_admin(harness: &TestHarness) -> DispatchResult {
+    let mut msg = harness.make_message("hello", false);
+    msg.safe_mode_fallback = true;
+    harness.dispatcher.dispatch(msg).await
+}
+
+#[tokio::test]
+async fn role_replaces_non_admin_safe_mode_fallback() {
+    let harness = TestHarness::new("telegram", false, false, say_and_finish());
+    harness.db.assign_role("trader", Some(harness.channel_id), Some("test-user"), None).unwrap();
+
+    let result = dispatch_non_admin(&harness).await;
+    assert!(result.error.is_none(), "dispatch should succeed: {:?}", result.error);
+    let sessions = harness_sessions_safe_mode(&harness);
+    assert!(!sessions.is_empty() && sessions.iter().all(|safe| !*safe), "role should replace the fallback");
+}
+
+#[tokio::test]
+async fn non_admin_without_role_falls_back_to_safe_mode() {
+    let harness = TestHarness::new("telegram", false, false, say_and_finish());
+
+    let result = dispatch_non_admin(&harness).await;
+    assert!(result.error.is_none(), "dispatch should succeed: {:?}", result.error);
+    let sessions = harness_sessions_safe_mode(&harness);
+    assert!(!sessions.is_empty() && sessions.iter().all(|safe| *safe), "no role means safe mode");
+}
+
+#[tokio::test]
+async fn dispatch_without_tool_state_writes_no_snapshot() {
+    let mut harness = TestHarness::new("telegram", false, false, say_and_finish());
+
+    let (result, _) = harness.dispatch("hello", false).await;
+    assert!(result.error.is_none(), "dispatch should succeed: {:?}", result.error);
+    for session in harness.db.list_chat_sessions().unwrap().into_iter().filter(|s| s.channel_id == harness.channel_id) {
+        assert!(harness.db.get_session_tool_state(session.id).unwrap().is_none(), "nothing to snapshot");
+    }
+}
+
+// ============================================================================
+// Parallel tool calls: consecutive parallel-safe calls from one AI response run
+// concurrently; results and spans still come back in call order
+// ============================================================================
+
+/// When a stub tool started and finished
+type Window = (std::time::Instant, std::time::Instant);
+
+/// (tool name, execution window) for each stub execution, in completion order
+type ExecutionLog = Arc<std::sync::Mutex<Vec<(String, Window)>>>;
+
+/// System-group tool that sleeps for `delay_ms` and logs when it ran
+struct TimedStubTool {
+    name: String,
+    delay_ms: u64,
+    safety_level: crate::tools::ToolSafetyLevel,
+    log: ExecutionLog,
+}
+
+#[async_trait::async_trait]
+impl crate::tools::Tool for TimedStubTool {
+    fn definition(&self) -> crate::tools::ToolDefinition {
+        crate::tools::ToolDefinition {
+            name: self.name.clone(),
+            description: format!("Stub {} tool", self.name),
+            input_schema: crate::tools::ToolInputSchema::default(),
+            group: crate::tools::ToolGroup::System,
+            hidden: false,
+        }
+    }
+
+    async fn execute(&self, _params: serde_json::Value, _context: &crate::tools::ToolContext) -> crate::tools::ToolResult {
+        let started = std::time::Instant::now();
+        tokio::time::sleep(Duration::from_millis(self.delay_ms)).await;
+        self.log.lock().unwrap().push((self.name.clone(), (started, std::time::Instant::now())));
+        crate::tools::ToolResult::success(format!("{} done", self.name))
+    }
+
+    fn safety_level(&self) -> crate::tools::ToolSafetyLevel {
+        self.safety_level
+    }
+}
+
+impl TestHarness {
+    /// Register a timed stub tool on the dispatcher's registry
+    fn register_stub(&self, name: &str, delay_ms: u64, read_only: bool, log: &ExecutionLog) {
+        let safety_level = if read_only {
+            crate::tools::ToolSafetyLevel::ReadOnly
+        } else {
+            crate::tools::ToolSafetyLevel::Standard
+        };
+        self.dispatcher.tool_registry.register(Arc::new(TimedStubTool {
+            name: name.to_string(),
+            delay_ms,
+            safety_level,
+            log: log.clone(),
+        }));
+    }
+
+    /// Tool call spans recorded for this harness's sessions, in recording order
+    fn tool_spans(&self) -> Vec<crate::telemetry::Span> {
+        let mut sessions: Vec<_> = self.db.list_chat_sessions().expect("list sessions")
+            .into_iter()
+            .filter(|s| s.channel_id == self.channel_id)
+            .collect();
+        sessions.sort_by_key(|s| s.id);
+        sessions.iter()
+            .flat_map(|s| self.dispatcher.telemetry_store().get_session_spans(s.id))
+            .filter(|span| span.span_type == crate::telemetry::SpanType::ToolCall)
+            .collect()
+    }
+}
+
+/// Execution window of a stub tool from the log
+fn window(log: &ExecutionLog, name: &str) -> Window {
+    log.lock().unwrap().iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, w)| *w)
+        .unwrap_or_else(|| panic!("{} never ran", name))
+}
+
+fn overlaps(a: Window, b: Window) -> bool {
+    a.0 < b.1 && b.0 < a.1
+}
+
+/// Leave TaskPlanner mode with a single task so the (System group) stub tools are offered
+fn plan_single_task() -> Vec<AiResponse> {
+    vec![AiResponse::with_tools(
+        String::new(),
+        vec![tool_call("define_tasks", json!({"tasks": ["Run the stub tools."]}))],
+    )]
+}
+
+#[tokio::test]
+async fn read_only_calls_run_concurrently_in_call_order() {
+    let calls = vec![
+        tool_call("read_a", json!({})),
+        tool_call("read_b", json!({})),
+        tool_call("read_c", json!({})),
+    ];
+    let call_ids: Vec<String> = calls.iter().map(|c| c.id.clone()).collect();
+    let mut responses = plan_single_task();
+    responses.push(AiResponse::with_tools(String::new(), calls));
+    responses.push(AiResponse::with_tools(
+        String::new(),
+        vec![tool_call("task_fully_completed", json!({"summary": "Read everything."}))],
+    ));
+
+    let mut harness = TestHarness::new("web", false, false, responses);
+    let log: ExecutionLog = Default::default();
+    // Later calls finish first
+    harness.register_stub("read_a", 300, true, &log);
+    harness.register_stub("read_b", 200, true, &log);
+    harness.register_stub("read_c", 100, true, &log);
+
+    let (result, _events) = harness.dispatch("read three things", false).await;
+    assert!(result.error.is_none(), "dispatch should succeed: {:?}", result.error);
+
+    let (a, b, c) = (window(&log, "read_a"), window(&log, "read_b"), window(&log, "read_c"));
+    assert!(overlaps(a, b) && overlaps(a, c) && overlaps(b, c), "read-only calls should run concurrently");
+    let finished: Vec<String> = log.lock().unwrap().iter().map(|(n, _)| n.clone()).collect();
+    assert_eq!(finished, vec!["read_c", "read_b", "read_a"]);
+
+    // The next AI iteration sees the results in call order
+    let trace = harness.get_trace();
+    let last = trace.last().expect("AI iterations");
+    let entry = last.input_tool_history.iter()
+        .find(|h| h.tool_calls.iter().any(|c| c.name == "read_a"))
+        .expect("tool history for the parallel batch");
+    let response_ids: Vec<&str> = entry.tool_responses.iter().map(|r| r.tool_call_id.as_str()).collect();
+    assert_eq!(response_ids, call_ids);
+    let contents: Vec<&str> = entry.tool_responses.iter().map(|r| r.content.as_str()).collect();
+    assert_eq!(contents, vec!["read_a done", "read_b done", "read_c done"]);
+
+    // Spans are recorded in call order with their position in the group
+    let spans: Vec<_> = harness.tool_spans().into_iter().filter(|s| s.name.starts_with("read_")).collect();
+    let names: Vec<&str> = spans.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["read_a", "read_b", "read_c"]);
+    for (position, span) in spans.iter().enumerate() {
+        assert_eq!(span.attributes["parallel"]["position"], json!(position));
+        assert_eq!(span.attributes["parallel"]["group_size"], json!(3));
+    }
+}
+
+#[tokio::test]
+async fn ask_user_and_unsafe_tools_run_sequentially() {
+    let mut responses = plan_single_task();
+    responses.push(AiResponse::with_tools(
+        String::new(),
+        vec![
+            tool_call("read_a", json!({})),
+            tool_call("read_b", json!({})),
+            tool_call("write_x", json!({})),
+            tool_call("read_c", json!({})),
+            tool_call("read_d", json!({})),
+            tool_call("ask_user", json!({"question": "Which one?"})),
+        ],
+    ));
+
+    let mut harness = TestHarness::new("web", false, false, responses);
+    let log: ExecutionLog = Default::default();
+    for name in ["read_a", "read_b", "read_c", "read_d"] {
+        harness.register_stub(name, 100, true, &log);
+    }
+    harness.register_stub("write_x", 100, false, &log);
+
+    let (result, _events) = harness.dispatch("read, write, then ask", false).await;
+    assert!(result.error.is_none(), "dispatch should succeed: {:?}", result.error);
+
+    // The standard tool splits the reads into two groups and overlaps neither
+    let write = window(&log, "write_x");
+    let reads: Vec<_> = ["read_a", "read_b", "read_c", "read_d"].iter().map(|n| window(&log, n)).collect();
+    assert!(overlaps(reads[0], reads[1]), "reads before the write should run together");
+    assert!(overlaps(reads[2], reads[3]), "reads after the write should run together");
+    assert!(reads.iter().all(|r| !overlaps(*r, write)), "the write must run on its own");
+    assert!(reads[1].1 <= write.0 && write.1 <= reads[2].0, "the write runs between the two groups");
+
+    // ask_user and the write ran outside any parallel group; spans follow call order
+    let spans: Vec<_> = harness.tool_spans().into_iter().filter(|s| s.name != "define_tasks").collect();
+    let names: Vec<&str> = spans.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["read_a", "read_b", "write_x", "read_c", "read_d", "ask_user"]);
+    for span in &spans {
+        let group_size = span.attributes["parallel"]["group_size"].as_u64();
+        let expected = if span.name.starts_with("read_") { Some(2) } else { None };
+        assert_eq!(group_size, expected, "{} group size", span.name);
+    }
+}
@@ -0,0 +1,787 @@
+//! Matrix channel adapter using the client-server API
+//!
+//! Long-polls `/sync` for room events, accepts room invites, and answers when
+//! the bot is mentioned, replied to or messaged in a DM. Thread messages are
+//! answered in their thread (one session per thread), and tool progress is shown
+//! in a single status notice that is edited in place and redacted once the final
+//! answer is sent. Encrypted rooms are not supported. Works against any
+//! homeserver, including a local Synapse or Conduit.
+
+use crate::channels::dispatcher::MessageDispatcher;
+use crate::channels::safe_mode_rate_limiter::SafeModeChannelRateLimiter;
+use crate::channels::types::{ChannelType, NormalizedMessage};
+use crate::channels::util;
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::models::{Channel, ChannelSettingKey};
+use dashmap::{DashMap, DashSet};
+use reqwest::Method;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// Long-poll timeout for /sync
+const SYNC_TIMEOUT_MS: u64 = 30_000;
+
+/// Maximum delay between retries after sync failures
+const MAX_RETRY_DELAY_SECS: u64 = 60;
+
+/// Maximum characters per message (events are capped at 64 KiB)
+const MAX_MESSAGE_CHARS: usize = 16_000;
+
+/// Filter for the steady-state sync: skip presence and ephemeral noise
+const SYNC_FILTER: &str = r#"{"presence":{"types":[]},"account_data":{"types":[]},"room":{"ephemeral":{"types":[]},"account_data":{"types":[]}}}"#;
+
+/// Filter for the first sync: only the batch token is used, so keep it tiny
+const INITIAL_SYNC_FILTER: &str = r#"{"presence":{"types":[]},"account_data":{"types":[]},"room":{"timeline":{"limit":1},"state":{"types":[]},"ephemeral":{"types":[]},"account_data":{"types":[]}}}"#;
+
+/// Which room invites the bot accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AutoJoin {
+    Admins,
+    Anyone,
+    Off,
+}
+
+impl AutoJoin {
+    fn from_str(s: &str) -> Self {
+        match s {
+            "anyone" => Self::Anyone,
+            "off" => Self::Off,
+            _ => Self::Admins,
+        }
+    }
+}
+
+/// Settings re-read on every event, so admin changes apply without a restart
+struct MatrixAccessConfig {
+    admin_user_ids: HashSet<String>,
+    auto_join: AutoJoin,
+}
+
+impl MatrixAccessConfig {
+    fn from_channel_settings(db: &Database, channel_id: i64) -> Self {
+        let get = |key: ChannelSettingKey| {
+            db.get_channel_setting(channel_id, key.as_ref())
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| key.default_value().to_string())
+        };
+        Self {
+            admin_user_ids: get(ChannelSettingKey::MatrixAdminUserIds)
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            auto_join: AutoJoin::from_str(get(ChannelSettingKey::MatrixAutoJoin).trim()),
+        }
+    }
+
+    fn is_admin(&self, user_id: &str) -> bool {
+        self.admin_user_ids.contains(user_id)
+    }
+}
+
+/// Minimal client for the Matrix client-server API
+struct MatrixClient {
+    http: reqwest::Client,
+    homeserver: String,
+    access_token: String,
+    user_id: String,
+    display_name: Option<String>,
+}
+
+impl MatrixClient {
+    /// Validate the access token and look up the bot's user ID and display name
+    async fn connect(homeserver: &str, access_token: &str) -> Result<Self, String> {
+        let mut client = Self {
+            http: crate::http::shared_client().clone(),
+            homeserver: homeserver.trim_end_matches('/').to_string(),
+            access_token: access_token.to_string(),
+            user_id: String::new(),
+            display_name: None,
+        };
+        let whoami = client.request(Method::GET, "/account/whoami", &[], None).await?;
+        client.user_id = whoami
+            .get("user_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| "whoami returned no user_id".to_string())?
+            .to_string();
+        client.display_name = client
+            .request(
+                Method::GET,
+                &format!("/profile/{}/displayname", enc(&client.user_id)),
+                &[],
+                None,
+            )
+            .await
+            .ok()
+            .and_then(|v| v.get("displayname").and_then(|d| d.as_str()).map(|s| s.to_string()));
+        Ok(client)
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<Value>,
+    ) -> Result<Value, String> {
+        let mut request = self
+            .http
+            .request(method, format!("{}/_matrix/client/v3{}", self.homeserver, path))
+            .bearer_auth(&self.access_token)
+            .query(query);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Matrix request failed: {}", e))?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            return Err(format!(
+                "Matrix API error ({}): {} {}",
+                status,
+                body.get("errcode").and_then(|v| v.as_str()).unwrap_or(""),
+                body.get("error").and_then(|v| v.as_str()).unwrap_or("")
+            ));
+        }
+        Ok(body)
+    }
+
+    async fn sync(&self, since: Option<&str>, timeout_ms: u64, filter: &str) -> Result<Value, String> {
+        let mut query = vec![
+            ("timeout", timeout_ms.to_string()),
+            ("filter", filter.to_string()),
+        ];
+        if let Some(since) = since {
+            query.push(("since", since.to_string()));
+        }
+        self.request(Method::GET, "/sync", &query, None).await
+    }
+
+    async fn join(&self, room_id: &str) -> Result<(), String> {
+        self.request(Method::POST, &format!("/join/{}", enc(room_id)), &[], Some(json!({})))
+            .await
+            .map(|_| ())
+    }
+
+    async fn leave(&self, room_id: &str) -> Result<(), String> {
+        self.request(Method::POST, &format!("/rooms/{}/leave", enc(room_id)), &[], Some(json!({})))
+            .await
+            .map(|_| ())
+    }
+
+    /// Send an m.room.message event, returning its event ID
+    async fn send(&self, room_id: &str, content: Value) -> Result<String, String> {
+        let txn_id = uuid::Uuid::new_v4().simple().to_string();
+        let response = self
+            .request(
+                Method::PUT,
+                &format!("/rooms/{}/send/m.room.message/{}", enc(room_id), txn_id),
+                &[],
+                Some(content),
+            )
+            .await?;
+        response
+            .get("event_id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .ok_or_else(|| "send returned no event_id".to_string())
+    }
+
+    async fn redact(&self, room_id: &str, event_id: &str) -> Result<(), String> {
+        let txn_id = uuid::Uuid::new_v4().simple().to_string();
+        self.request(
+            Method::PUT,
+            &format!("/rooms/{}/redact/{}/{}", enc(room_id), enc(event_id), txn_id),
+            &[],
+            Some(json!({})),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn set_typing(&self, room_id: &str, typing: bool) {
+        let body = if typing {
+            json!({ "typing": true, "timeout": SYNC_TIMEOUT_MS })
+        } else {
+            json!({ "typing": false })
+        };
+        let path = format!("/rooms/{}/typing/{}", enc(room_id), enc(&self.user_id));
+        if let Err(e) = self.request(Method::PUT, &path, &[], Some(body)).await {
+            log::debug!("Matrix: Failed to set typing: {}", e);
+        }
+    }
+
+    async fn get_event(&self, room_id: &str, event_id: &str) -> Result<Value, String> {
+        self.request(
+            Method::GET,
+            &format!("/rooms/{}/event/{}", enc(room_id), enc(event_id)),
+            &[],
+            None,
+        )
+        .await
+    }
+
+    async fn joined_member_count(&self, room_id: &str) -> Result<u64, String> {
+        let members = self
+            .request(Method::GET, &format!("/rooms/{}/joined_members", enc(room_id)), &[], None)
+            .await?;
+        Ok(members
+            .get("joined")
+            .and_then(|v| v.as_object())
+            .map(|m| m.len() as u64)
+            .unwrap_or(0))
+    }
+}
+
+fn enc(s: &str) -> String {
+    urlencoding::encode(s).into_owned()
+}
+
+/// Where a reply goes: the triggering event, inside its thread if it has one
+#[derive(Debug, Clone)]
+struct ReplyTarget {
+    event_id: String,
+    thread_root: Option<String>,
+}
+
+impl ReplyTarget {
+    fn relates_to(&self) -> Value {
+        match &self.thread_root {
+            Some(root) => json!({
+                "rel_type": "m.thread",
+                "event_id": root,
+                "is_falling_back": false,
+                "m.in_reply_to": { "event_id": self.event_id },
+            }),
+            None => json!({ "m.in_reply_to": { "event_id": self.event_id } }),
+        }
+    }
+
+    fn content(&self, msgtype: &str, body: &str) -> Value {
+        json!({ "msgtype": msgtype, "body": body, "m.relates_to": self.relates_to() })
+    }
+}
+
+/// Content that edits an earlier message in place
+fn edit_content(original_event_id: &str, msgtype: &str, body: &str) -> Value {
+    json!({
+        "msgtype": msgtype,
+        "body": format!("* {}", body),
+        "m.new_content": { "msgtype": msgtype, "body": body },
+        "m.relates_to": { "rel_type": "m.replace", "event_id": original_event_id },
+    })
+}
+
+/// Whether the bot is mentioned: intentional mentions (m.mentions), a matrix.to
+/// pill in the formatted body, or the user ID in plain text
+fn is_mentioned(content: &Value, user_id: &str) -> bool {
+    let in_mentions = content
+        .pointer("/m.mentions/user_ids")
+        .and_then(|v| v.as_array())
+        .map(|ids| ids.iter().any(|id| id.as_str() == Some(user_id)))
+        .unwrap_or(false);
+    let in_formatted = content
+        .get("formatted_body")
+        .and_then(|v| v.as_str())
+        .map(|html| html.contains(&format!("matrix.to/#/{}", user_id)))
+        .unwrap_or(false);
+    let in_body = content
+        .get("body")
+        .and_then(|v| v.as_str())
+        .map(|body| body.contains(user_id))
+        .unwrap_or(false);
+    in_mentions || in_formatted || in_body
+}
+
+/// Strip the reply fallback, the bot's user ID and a leading "Name:" mention pill
+fn extract_command_text(body: &str, user_id: &str, display_name: Option<&str>) -> String {
+    // Legacy clients prefix replies with the quoted parent ("> <@user> text")
+    let without_fallback: Vec<&str> = body
+        .lines()
+        .skip_while(|line| line.starts_with("> ") || *line == ">")
+        .collect();
+    let mut text = without_fallback.join("\n").replace(user_id, "");
+    if let Some(name) = display_name.filter(|n| !n.is_empty()) {
+        let trimmed = text.trim_start();
+        if let Some(rest) = trimmed.strip_prefix(name) {
+            text = rest.trim_start_matches(':').to_string();
+        }
+    }
+    text.trim().trim_start_matches(':').trim().to_string()
+}
+
+struct MatrixHandler {
+    channel_id: i64,
+    client: MatrixClient,
+    dispatcher: Arc<MessageDispatcher>,
+    broadcaster: Arc<EventBroadcaster>,
+    db: Arc<Database>,
+    safe_mode_rate_limiter: SafeModeChannelRateLimiter,
+    /// Joined member counts from sync summaries (2 = direct chat)
+    member_counts: DashMap<String, u64>,
+    /// Encrypted rooms already warned about
+    encrypted_rooms: DashSet<String>,
+}
+
+impl MatrixHandler {
+    /// Handle one sync response: invites, then new room messages
+    async fn process_sync(self: &Arc<Self>, sync: &Value) {
+        if let Some(invites) = sync.pointer("/rooms/invite").and_then(|v| v.as_object()) {
+            for (room_id, room) in invites {
+                self.handle_invite(room_id, room).await;
+            }
+        }
+
+        let Some(joined) = sync.pointer("/rooms/join").and_then(|v| v.as_object()) else {
+            return;
+        };
+        for (room_id, room) in joined {
+            if let Some(count) = room.pointer("/summary/m.joined_member_count").and_then(|v| v.as_u64()) {
+                self.member_counts.insert(room_id.clone(), count);
+            }
+            let events = room
+                .pointer("/timeline/events")
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default();
+            for event in events {
+                let sender = event.get("sender").and_then(|v| v.as_str()).unwrap_or("");
+                if sender == self.client.user_id {
+                    continue;
+                }
+                match event.get("type").and_then(|v| v.as_str()) {
+                    Some("m.room.message") => {
+                        let handler = self.clone();
+                        let room_id = room_id.clone();
+                        tokio::spawn(async move { handler.handle_message(&room_id, &event).await });
+                    }
+                    Some("m.room.encrypted") if self.encrypted_rooms.insert(room_id.clone()) => {
+                        log::warn!("Matrix: Room {} is encrypted — messages there cannot be read", room_id);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    async fn handle_invite(&self, room_id: &str, room: &Value) {
+        let inviter = room
+            .pointer("/invite_state/events")
+            .and_then(|v| v.as_array())
+            .and_then(|events| {
+                events.iter().find(|e| {
+                    e.get("type").and_then(|v| v.as_str()) == Some("m.room.member")
+                        && e.get("state_key").and_then(|v| v.as_str()) == Some(self.client.user_id.as_str())
+                })
+            })
+            .and_then(|e| e.get("sender").and_then(|v| v.as_str()))
+            .unwrap_or("")
+            .to_string();
+
+        let config = MatrixAccessConfig::from_channel_settings(&self.db, self.channel_id);
+        let accept = match config.auto_join {
+            AutoJoin::Anyone => true,
+            AutoJoin::Admins => config.is_admin(&inviter),
+            AutoJoin::Off => false,
+        };
+
+        if accept {
+            match self.client.join(room_id).await {
+                Ok(()) => log::info!("Matrix: Joined {} (invited by {})", room_id, inviter),
+                Err(e) => log::error!("Matrix: Failed to join {}: {}", room_id, e),
+            }
+        } else {
+            log::info!("Matrix: Declining invite to {} from {}", room_id, inviter);
+            if let Err(e) = self.client.leave(room_id).await {
+                log::warn!("Matrix: Failed to decline invite to {}: {}", room_id, e);
+            }
+        }
+    }
+
+    async fn is_direct_chat(&self, room_id: &str) -> bool {
+        if let Some(count) = self.member_counts.get(room_id) {
+            return *count == 2;
+        }
+        match self.client.joined_member_count(room_id).await {
+            Ok(count) => {
+                self.member_counts.insert(room_id.to_string(), count);
+                count == 2
+            }
+            Err(e) => {
+                log::warn!("Matrix: Failed to get members of {}: {}", room_id, e);
+                false
+            }
+        }
+    }
+
+    async fn handle_message(&self, room_id: &str, event: &Value) {
+        let content = event.get("content").cloned().unwrap_or(Value::Null);
+        // m.notice is what bots send — ignoring it avoids bot-to-bot loops
+        if content.get("msgtype").and_then(|v| v.as_str()) != Some("m.text") {
+            return;
+        }
+        let relation = content.get("m.relates_to").cloned().unwrap_or(Value::Null);
+        // Edits re-send the whole message; only the original is answered
+        if relation.get("rel_type").and_then(|v| v.as_str()) == Some("m.replace") {
+            return;
+        }
+
+        let (Some(event_id), Some(sender), Some(body)) = (
+            event.get("event_id").and_then(|v| v.as_str()),
+            event.get("sender").and_then(|v| v.as_str()),
+            content.get("body").and_then(|v| v.as_str()),
+        ) else {
+            return;
+        };
+
+        let thread_root = if relation.get("rel_type").and_then(|v| v.as_str()) == Some("m.thread") {
+            relation.get("event_id").and_then(|v| v.as_str()).map(|s| s.to_string())
+        } else {
+            None
+        };
+        let reply_to = relation
+            .pointer("/m.in_reply_to/event_id")
+            .and_then(|v| v.as_str())
+            // In threads, clients set in_reply_to to the latest thread event as a fallback
+            .filter(|_| relation.get("is_falling_back").and_then(|v| v.as_bool()) != Some(true));
+
+        // Fetch the replied-to event once: it decides reply-to-bot and gives context
+        let replied = match reply_to {
+            Some(id) => self.client.get_event(room_id, id).await.ok(),
+            None => None,
+        };
+        let is_reply_to_bot = replied
+            .as_ref()
+            .and_then(|e| e.get("sender").and_then(|v| v.as_str()))
+            == Some(self.client.user_id.as_str());
+
+        if !is_mentioned(&content, &self.client.user_id)
+            && !is_reply_to_bot
+            && !self.is_direct_chat(room_id).await
+        {
+            return;
+        }
+
+        let command_text = extract_command_text(body, &self.client.user_id, self.client.display_name.as_deref());
+        if command_text.is_empty() {
+            return;
+        }
+
+        let target = ReplyTarget {
+            event_id: event_id.to_string(),
+            thread_root: thread_root.clone(),
+        };
+
+        // Admins get standard mode; everyone else (including when no admins are configured) falls
+        // back to safe mode unless a role is assigned
+        let config = MatrixAccessConfig::from_channel_settings(&self.db, self.channel_id);
+        let safe_mode_fallback = !config.is_admin(sender);
+
+        if safe_mode_fallback
+            && let Err(rate_limit_msg) = self.safe_mode_rate_limiter.check_and_record_query(sender, "matrix")
+        {
+            log::info!("Matrix: Rate limiting user {} - {}", sender, rate_limit_msg);
+            let _ = self
+                .client
+                .send(room_id, target.content("m.notice", &format!("⏳ {}", rate_limit_msg)))
+                .await;
+            return;
+        }
+
+        log::info!(
+            "Matrix: {} from {} in {}: {}",
+            if safe_mode_fallback { "Safe mode query" } else { "Admin command" },
+            sender,
+            room_id,
+            if command_text.len() > 50 {
+                format!("{}...", command_text.chars().take(50).collect::<String>())
+            } else {
+                command_text.clone()
+            }
+        );
+
+        let reply_context = replied
+            .as_ref()
+            .and_then(|e| {
+                let who = e.get("sender").and_then(|v| v.as_str())?;
+                let text = e.pointer("/content/body").and_then(|v| v.as_str())?;
+                let preview: String = text.chars().take(300).collect();
+                Some(format!("[REPLYING TO {}:]\n{}\n\n", who, preview))
+            })
+            .unwrap_or_default();
+        let text_with_hint = if reply_context.is_empty() {
+            format!("[MATRIX MESSAGE]\n\n{}", command_text)
+        } else {
+            format!("[MATRIX MESSAGE]\n\n{}[MESSAGE DIRECTED TO YOU:]\n{}", reply_context, command_text)
+        };
+
+        // Each thread is its own session; the main timeline shares the room session
+        let chat_id = match &thread_root {
+            Some(root) => format!("{}|{}", room_id, root),
+            None => room_id.to_string(),
+        };
+
+        let normalized = NormalizedMessage {
+            channel_id: self.channel_id,
+            channel_type: ChannelType::Matrix.to_string(),
+            chat_id,
+            chat_name: None,
+            user_id: sender.to_string(),
+            user_name: sender.to_string(),
+            text: text_with_hint,
+            message_id: Some(event_id.to_string()),
+            session_mode: None,
+            selected_network: None,
+            force_safe_mode: false,
+            safe_mode_fallback,
+            endpoint_override: None,
+            target_session_id: None,
+            attachments: Vec::new(),
+            tool_profile: None,
+            thinking_level: None,
+            agent_subtype: None,
+            max_tool_iterations: None,
+            max_tx_value_wei: None,
+        };
+
+        self.dispatch_and_respond(room_id, target, normalized).await;
+    }
+
+    /// Dispatch a message to the AI, stream progress into a status notice and send the response
+    async fn dispatch_and_respond(&self, room_id: &str, target: ReplyTarget, normalized: NormalizedMessage) {
+        let user_name = normalized.user_name.clone();
+        let chat_id = normalized.chat_id.clone();
+        self.client.set_typing(room_id, true).await;
+
+        // Subscribe to events for real-time tool progress
+        let (client_id, mut event_rx) = self.broadcaster.subscribe();
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        let channel_id = self.channel_id;
+        let forward_task = tokio::spawn(async move {
+            while let Some(event) = event_rx.recv().await {
+                if !util::event_matches_session(&event.data, channel_id, &chat_id) {
+                    continue;
+                }
+                let tool_name = event.data.get("tool_name").and_then(|v| v.as_str()).unwrap_or("unknown");
+                let line = match event.event.as_str() {
+                    "agent.tool_call" => Some(format!("🔧 Calling: {}", tool_name)),
+                    // say_to_user content comes through result.response
+                    "tool.result" if tool_name != "say_to_user" => {
+                        let success = event.data.get("success").and_then(|v| v.as_bool()).unwrap_or(false);
+                        let duration_ms = event.data.get("duration_ms").and_then(|v| v.as_i64()).unwrap_or(0);
+                        Some(format!(
+                            "{} Result: {} ({} ms)",
+                            if success { "✅" } else { "❌" },
+                            tool_name,
+                            duration_ms
+                        ))
+                    }
+                    _ => None,
+                };
+                if let Some(line) = line
+                    && progress_tx.send(line).is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        // Single status notice, edited in place for each update
+        let mut status_event_id: Option<String> = None;
+        let dispatch = self.dispatcher.dispatch(normalized);
+        tokio::pin!(dispatch);
+        let result = loop {
+            tokio::select! {
+                result = &mut dispatch => break result,
+                Some(line) = progress_rx.recv() => {
+                    let sent = match &status_event_id {
+                        Some(id) => self.client.send(room_id, edit_content(id, "m.notice", &line)).await.map(|_| ()),
+                        None => self.client.send(room_id, target.content("m.notice", &line)).await.map(|id| {
+                            status_event_id = Some(id);
+                        }),
+                    };
+                    if let Err(e) = sent {
+                        log::warn!("Matrix: Failed to update status message: {}", e);
+                    }
+                    self.client.set_typing(room_id, true).await;
+                }
+            }
+        };
+
+        self.broadcaster.unsubscribe(&client_id);
+        forward_task.abort();
+        self.client.set_typing(room_id, false).await;
+
+        // Remove the status notice so the room only shows the question and the answer
+        if let Some(id) = status_event_id
+            && let Err(e) = self.client.redact(room_id, &id).await
+        {
+            log::warn!("Matrix: Failed to redact status message: {}", e);
+        }
+
+        log::info!("Matrix: Dispatch complete for {}, error={:?}", user_name, result.error);
+
+        if let Some(error) = result.error {
+            let error_msg = format!("Sorry, I encountered an error: {}", error);
+            let _ = self.client.send(room_id, target.content("m.notice", &error_msg)).await;
+        } else if !result.response.is_empty() {
+            for chunk in util::split_message(&result.response, MAX_MESSAGE_CHARS) {
+                if let Err(e) = self.client.send(room_id, target.content("m.text", &chunk)).await {
+                    log::error!("Failed to send Matrix message: {}", e);
+                }
+            }
+        } else {
+            log::debug!("Matrix: Empty final response for user {}", user_name);
+        }
+    }
+}
+
+/// Start a Matrix sync listener
+pub async fn start_matrix_listener(
+    channel: Channel,
+    dispatcher: Arc<MessageDispatcher>,
+    broadcaster: Arc<EventBroadcaster>,
+    db: Arc<Database>,
+    safe_mode_rate_limiter: SafeModeChannelRateLimiter,
+    mut shutdown_rx: oneshot::Receiver<()>,
+) -> Result<(), String> {
+    let channel_id = channel.id;
+    let channel_name = channel.name.clone();
+
+    log::info!("Starting Matrix listener for channel: {}", channel_name);
+
+    let homeserver = db
+        .get_channel_setting(channel_id, ChannelSettingKey::MatrixHomeserverUrl.as_ref())
+        .ok()
+        .flatten()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "Matrix homeserver URL not configured".to_string())?;
+    if channel.bot_token.is_empty() {
+        return Err("Matrix access token not configured".to_string());
+    }
+
+    let client = MatrixClient::connect(&homeserver, &channel.bot_token).await?;
+    log::info!("Matrix: Logged in as {} on {}", client.user_id, client.homeserver);
+
+    // First sync only provides the batch token so history is not replayed
+    let initial = client.sync(None, 0, INITIAL_SYNC_FILTER).await?;
+    let mut since = initial
+        .get("next_batch")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Initial sync returned no next_batch".to_string())?
+        .to_string();
+
+    let handler = Arc::new(MatrixHandler {
+        channel_id,
+        client,
+        dispatcher,
+        broadcaster: broadcaster.clone(),
+        db,
+        safe_mode_rate_limiter,
+        member_counts: DashMap::new(),
+        encrypted_rooms: DashSet::new(),
+    });
+
+    // Invites that arrived while the channel was stopped
+    if let Some(invites) = initial.pointer("/rooms/invite").and_then(|v| v.as_object()) {
+        for (room_id, room) in invites {
+            handler.handle_invite(room_id, room).await;
+        }
+    }
+
+    broadcaster.broadcast(GatewayEvent::channel_started(
+        channel_id,
+        ChannelType::Matrix.as_str(),
+        &channel_name,
+    ));
+
+    let mut failures: u32 = 0;
+    loop {
+        let outcome = tokio::select! {
+            _ = &mut shutdown_rx => None,
+            result = handler.client.sync(Some(&since), SYNC_TIMEOUT_MS, SYNC_FILTER) => Some(result),
+        };
+        let Some(result) = outcome else {
+            log::info!("Matrix listener {} received shutdown signal", channel_name);
+            break;
+        };
+
+        match result {
+            Ok(sync) => {
+                failures = 0;
+                if let Some(next) = sync.get("next_batch").and_then(|v| v.as_str()) {
+                    since = next.to_string();
+                }
+                handler.process_sync(&sync).await;
+            }
+            Err(e) => {
+                failures += 1;
+                let delay = (5u64 * 2u64.pow(failures.min(4))).min(MAX_RETRY_DELAY_SECS);
+                log::error!("Matrix: Sync failed ({}), retrying in {}s", e, delay);
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    _ = tokio::time::sleep(Duration::from_secs(delay)) => {}
+                }
+            }
+        }
+    }
+
+    broadcaster.broadcast(GatewayEvent::channel_stopped(
+        channel_id,
+        ChannelType::Matrix.as_str(),
+        &channel_name,
+    ));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_mentioned() {
+        let bot = "@stark:example.org";
+        assert!(is_mentioned(&json!({ "body": "hi", "m.mentions": { "user_ids": [bot] } }), bot));
+        assert!(is_mentioned(
+            &json!({ "body": "Stark: hi", "formatted_body": "<a href=\"https://matrix.to/#/@stark:example.org\">Stark</a>: hi" }),
+            bot
+        ));
+        assert!(is_mentioned(&json!({ "body": "@stark:example.org what's up" }), bot));
+        assert!(!is_mentioned(&json!({ "body": "talking about stark", "m.mentions": {} }), bot));
+    }
+
+    #[test]
+    fn test_extract_command_text() {
+        let bot = "@stark:example.org";
+        assert_eq!(extract_command_text("Stark: check my balance", bot, Some("Stark")), "check my balance");
+        assert_eq!(extract_command_text("@stark:example.org: price of ETH?", bot, None), "price of ETH?");
+        assert_eq!(
+            extract_command_text("> <@alice:example.org> earlier\n\nStark: and now?", bot, Some("Stark")),
+            "and now?"
+        );
+    }
+
+    #[test]
+    fn test_reply_target_relations() {
+        let in_thread = ReplyTarget { event_id: "$reply".into(), thread_root: Some("$root".into()) };
+        let content = in_thread.content("m.text", "hello");
+        assert_eq!(content["m.relates_to"]["rel_type"], "m.thread");
+        assert_eq!(content["m.relates_to"]["event_id"], "$root");
+        assert_eq!(content["m.relates_to"]["m.in_reply_to"]["event_id"], "$reply");
+
+        let edit = edit_content("$status", "m.notice", "🔧 Calling: web_fetch");
+        assert_eq!(edit["m.relates_to"]["rel_type"], "m.replace");
+        assert_eq!(edit["m.new_content"]["body"], "🔧 Calling: web_fetch");
+    }
+}
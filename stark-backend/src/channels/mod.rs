@@ -1,10 +1,15 @@
+pub mod attachments;
+pub mod delivery;
 pub mod discord;
+pub mod email;
 pub mod dispatcher;
+pub mod matrix;
 pub mod safe_mode_rate_limiter;
 pub mod session_writer;
 pub mod slack;
 pub mod telegram;
 pub mod twitter;
+pub mod tx_approval;
 pub mod types;
 pub mod util;
 
@@ -113,7 +118,8 @@ impl ChannelManager {
                 "discord" => "discord_bot_token",
                 "telegram" => "telegram_bot_token",
                 "slack" => "slack_bot_token",
-                _ => "", // Twitter and ExternalChannel don't use bot_token
+                "matrix" => "matrix_access_token",
+                _ => "", // Twitter, ExternalChannel and Email don't use bot_token
             };
             if !setting_key.is_empty() {
                 if let Ok(Some(token)) = self.db.get_channel_setting(channel_id, setting_key) {
@@ -268,6 +274,50 @@ impl ChannelManager {
                     running_channels.remove(&channel_id);
                 });
             }
+            types::ChannelType::Email => {
+                let db = self.db.clone();
+                tokio::spawn(async move {
+                    let result = email::start_email_listener(
+                        channel,
+                        dispatcher,
+                        broadcaster.clone(),
+                        db,
+                        shutdown_rx,
+                    )
+                    .await;
+
+                    if let Err(e) = result {
+                        log::error!("Email listener error: {}", e);
+                        broadcaster.broadcast(GatewayEvent::channel_error(channel_id, &e));
+                    }
+
+                    // Remove from running channels
+                    running_channels.remove(&channel_id);
+                });
+            }
+            types::ChannelType::Matrix => {
+                let db = self.db.clone();
+                let safe_mode_rate_limiter = SafeModeChannelRateLimiter::new(db.clone());
+                tokio::spawn(async move {
+                    let result = matrix::start_matrix_listener(
+                        channel,
+                        dispatcher,
+                        broadcaster.clone(),
+                        db,
+                        safe_mode_rate_limiter,
+                        shutdown_rx,
+                    )
+                    .await;
+
+                    if let Err(e) = result {
+                        log::error!("Matrix listener error: {}", e);
+                        broadcaster.broadcast(GatewayEvent::channel_error(channel_id, &e));
+                    }
+
+                    // Remove from running channels
+                    running_channels.remove(&channel_id);
+                });
+            }
             types::ChannelType::ExternalChannel => {
                 // No listener needed — HTTP request/response model.
                 // Channel being in running_channels is sufficient.
@@ -148,7 +148,7 @@ impl SafeModeChannelRateLimiter {
         platform: &str,
     ) -> Result<Channel, String> {
         // Check per-user rate limit BEFORE queueing
-        let user_key = format!("{}:{}", platform, user_id);
+        let user_key = self.user_key(user_id, platform);
         let user_limit = self.get_user_query_limit();
 
         {
@@ -368,9 +368,18 @@ impl SafeModeChannelRateLimiter {
         }
     }
 
+    /// Rate-limit key for a user. Verified linked accounts share one budget,
+    /// keyed by their canonical identity.
+    fn user_key(&self, user_id: &str, platform: &str) -> String {
+        match self.db.get_identity_by_platform(platform, user_id) {
+            Ok(Some(link)) if link.is_verified => format!("identity:{}", link.identity_id),
+            _ => format!("{}:{}", platform, user_id),
+        }
+    }
+
     /// Get user's remaining queries in the current 10-minute window
     pub fn get_user_remaining_queries(&self, user_id: &str, platform: &str) -> (usize, i32) {
-        let user_key = format!("{}:{}", platform, user_id);
+        let user_key = self.user_key(user_id, platform);
         let limit = self.get_user_query_limit();
 
         let mut state = self.state.lock().unwrap();
@@ -389,7 +398,7 @@ impl SafeModeChannelRateLimiter {
     /// Returns Ok(remaining_queries) if allowed, Err(message) if rate limited.
     /// This is used for rate limiting queries (not channel creation).
     pub fn check_and_record_query(&self, user_id: &str, platform: &str) -> Result<SafeModeQueryResult, String> {
-        let user_key = format!("{}:{}", platform, user_id);
+        let user_key = self.user_key(user_id, platform);
         let user_limit = self.get_user_query_limit();
 
         let mut state = self.state.lock().unwrap();
@@ -561,6 +570,22 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_verified_links_share_budget() {
+        let db = Arc::new(Database::new(":memory:").expect("Failed to create test db"));
+        let discord = db.get_or_create_identity("discord", "user1", None).unwrap();
+        db.join_identity("telegram", "tg1", None, &discord.identity_id, "code").unwrap();
+        db.mark_identity_verified("discord", "user1", "code").unwrap();
+        let limiter = SafeModeChannelRateLimiter::new(db);
+
+        for _ in 0..5 {
+            let _ = limiter.check_and_record_query("user1", "discord");
+        }
+
+        // The linked Telegram account draws from the same budget
+        assert!(limiter.check_and_record_query("tg1", "telegram").is_err());
+    }
+
     #[test]
     fn test_get_remaining_queries() {
         let db = Arc::new(Database::new(":memory:").expect("Failed to create test db"));
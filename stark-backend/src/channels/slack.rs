@@ -1,13 +1,17 @@
+use crate::channels::attachments::{self, Attachment};
 use crate::channels::dispatcher::MessageDispatcher;
 use crate::channels::safe_mode_rate_limiter::SafeModeChannelRateLimiter;
+use crate::channels::tx_approval::{self, TxApprovalAction};
 use crate::channels::types::{ChannelType, NormalizedMessage};
 use crate::channels::util;
 use crate::db::Database;
 use crate::discord_hooks::db as user_db;
+use crate::disk_quota::DiskQuotaManager;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
 use crate::models::channel_settings::ChannelSettingKey;
 use crate::models::{Channel, ToolOutputVerbosity};
+use crate::tx_queue::{QueuedTransaction, TxQueueManager};
 use rand::seq::SliceRandom;
 use slack_morphism::prelude::*;
 use std::sync::Arc;
@@ -85,6 +89,90 @@ async fn delete_slack_message(
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Transaction approval prompts (Block Kit buttons)
+// ---------------------------------------------------------------------------
+
+fn text_section(text: &str) -> SlackBlock {
+    SlackBlock::Section(
+        SlackSectionBlock::new().with_text(SlackBlockText::Plain(SlackBlockPlainText::new(text.to_string()))),
+    )
+}
+
+/// Replace a message's text and blocks (drops any buttons)
+async fn replace_slack_blocks(
+    client: &SlackHyperClient,
+    token: &SlackApiToken,
+    channel: &SlackChannelId,
+    ts: &SlackTs,
+    text: &str,
+) -> Result<(), String> {
+    let session = client.open_session(token);
+    let content = SlackMessageContent::new()
+        .with_text(text.to_string())
+        .with_blocks(vec![text_section(text)]);
+    let req = SlackApiChatUpdateRequest::new(channel.clone(), content, ts.clone());
+    session
+        .chat_update(&req)
+        .await
+        .map_err(|e| format!("chat.update failed: {}", e))?;
+    Ok(())
+}
+
+/// Post a transaction approval prompt with Approve/Deny buttons in the reply thread.
+/// The buttons are removed if the approval window passes without a decision.
+async fn send_tx_approval_prompt(
+    client: Arc<SlackHyperClient>,
+    token: SlackApiToken,
+    channel: SlackChannelId,
+    thread_ts: SlackTs,
+    tx: &QueuedTransaction,
+    tx_queue: Arc<TxQueueManager>,
+) {
+    let prompt = tx_approval::format_prompt(tx);
+    let button = |action: TxApprovalAction, style: &str| {
+        SlackActionBlockElement::Button(
+            SlackBlockButtonElement::new(
+                action.callback_id(&tx.uuid).into(),
+                action.label().to_string().into(),
+            )
+            .with_value(tx.uuid.clone())
+            .with_style(style.to_string()),
+        )
+    };
+    let content = SlackMessageContent::new()
+        .with_text(prompt.clone())
+        .with_blocks(vec![
+            text_section(&prompt),
+            SlackBlock::Actions(SlackActionsBlock::new(vec![
+                button(TxApprovalAction::Approve, "primary"),
+                button(TxApprovalAction::Deny, "danger"),
+            ])),
+        ]);
+    let req = SlackApiChatPostMessageRequest::new(channel.clone(), content).with_thread_ts(thread_ts);
+
+    let session = client.open_session(&token);
+    match session.chat_post_message(&req).await {
+        Ok(resp) => {
+            log::info!("Slack: Posted approval prompt for transaction {}", tx.uuid);
+            let uuid = tx.uuid.clone();
+            tokio::spawn(async move {
+                if tx_approval::wait_for_expiry(tx_queue, uuid.clone()).await {
+                    let _ = replace_slack_blocks(
+                        &client,
+                        &token,
+                        &channel,
+                        &resp.ts,
+                        &tx_approval::format_expired(&uuid),
+                    )
+                    .await;
+                }
+            });
+        }
+        Err(e) => log::error!("Slack: Failed to send approval prompt: {}", e),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Chat context via conversations.history
 // ---------------------------------------------------------------------------
@@ -375,6 +463,84 @@ async fn handle_shortcircuit_command(
     }
 }
 
+/// Download files shared with a message into the workspace attachment cache.
+/// Slack file URLs are private and need the bot token as a bearer token.
+async fn download_slack_files(
+    files: &[SlackFile],
+    token: &SlackApiToken,
+    channel_id: i64,
+    disk_quota: Option<Arc<DiskQuotaManager>>,
+) -> Vec<Attachment> {
+    let mut downloaded = Vec::new();
+    for (i, file) in files.iter().enumerate() {
+        let name = file
+            .name
+            .clone()
+            .or_else(|| file.title.clone())
+            .unwrap_or_else(|| format!("{}.bin", file.id));
+        let mut attachment = Attachment::new(
+            name,
+            file.mimetype.as_ref().map(|m| m.to_string()),
+            0,
+            Some(file.id.to_string()),
+        );
+        let url = file.url_private_download.as_ref().or(file.url_private.as_ref());
+        match url {
+            Some(_) if i >= attachments::MAX_ATTACHMENTS_PER_MESSAGE => {
+                attachment.error = Some("too many attachments in one message".to_string());
+                downloaded.push(attachment);
+            }
+            Some(url) => downloaded.push(
+                attachments::download(
+                    attachment,
+                    url.as_str(),
+                    Some(token.token_value.0.as_str()),
+                    channel_id,
+                    disk_quota.as_deref(),
+                )
+                .await,
+            ),
+            None => {
+                attachment.error = Some("file has no download URL".to_string());
+                downloaded.push(attachment);
+            }
+        }
+    }
+    downloaded
+}
+
+/// Upload outbound files into the reply thread (files.upload v2)
+async fn send_slack_files(
+    client: &SlackHyperClient,
+    token: &SlackApiToken,
+    channel: &SlackChannelId,
+    thread_ts: &SlackTs,
+    files: Vec<Attachment>,
+) {
+    let http = reqwest::Client::new();
+    for file in files {
+        if let Err(e) = attachments::upload_slack_file(
+            &http,
+            token.token_value.0.as_str(),
+            channel.as_ref(),
+            Some(thread_ts.as_ref()),
+            &file,
+        )
+        .await
+        {
+            log::error!("Slack: Failed to upload {}: {}", file.file_name, e);
+            let _ = send_slack_message(
+                client,
+                token,
+                channel,
+                &format!("📎 Failed to send {}: {}", file.file_name, e),
+                Some(thread_ts),
+            )
+            .await;
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Core message processing
 // ---------------------------------------------------------------------------
@@ -387,6 +553,7 @@ async fn process_slack_message(
     raw_text: String,
     message_ts: SlackTs,
     thread_ts: Option<SlackTs>,
+    files: Vec<SlackFile>,
 ) {
     let channel_id = state.channel_id;
     let bot_user_id = state.bot_user_id.clone();
@@ -396,7 +563,7 @@ async fn process_slack_message(
 
     // Strip bot mention from text
     let clean_text = strip_slack_mention(&raw_text, &bot_user_id);
-    let clean_text = if clean_text.is_empty() {
+    let clean_text = if clean_text.is_empty() && files.is_empty() {
         "hello".to_string()
     } else {
         clean_text
@@ -434,8 +601,8 @@ async fn process_slack_message(
         return;
     }
 
-    // Determine safe mode from admin setting
-    let force_safe_mode = match &state.admin_user_ids {
+    // Non-admins fall back to safe mode unless the dispatcher finds a role for them
+    let safe_mode_fallback = match &state.admin_user_ids {
         Some(admin_ids) => {
             let is_admin = admin_ids
                 .split(',')
@@ -446,7 +613,7 @@ async fn process_slack_message(
                 false
             } else {
                 log::info!(
-                    "Slack: User {} ({}) is not admin — using safe mode",
+                    "Slack: User {} ({}) is not admin — safe mode unless a role is assigned",
                     user_name,
                     user_id
                 );
@@ -457,7 +624,7 @@ async fn process_slack_message(
     };
 
     // Check safe mode rate limit for non-admin queries
-    if force_safe_mode {
+    if safe_mode_fallback {
         if let Err(rate_limit_msg) = state.safe_mode_rate_limiter.check_and_record_query(&user_id, "slack") {
             log::info!("Slack: Rate limiting user {} - {}", user_id, rate_limit_msg);
             let _ = send_slack_message(
@@ -489,6 +656,15 @@ async fn process_slack_message(
         None => format!("[SLACK MESSAGE]\n\n{}", clean_text),
     };
 
+    // Download any shared files into the workspace
+    let attachments = download_slack_files(
+        &files,
+        &state.bot_token,
+        channel_id,
+        state.dispatcher.disk_quota(),
+    )
+    .await;
+
     let normalized = NormalizedMessage {
         channel_id,
         channel_type: ChannelType::Slack.to_string(),
@@ -500,7 +676,16 @@ async fn process_slack_message(
         message_id: Some(message_ts.to_string()),
         session_mode: None,
         selected_network: None,
-        force_safe_mode,
+        force_safe_mode: false,
+        safe_mode_fallback,
+        endpoint_override: None,
+        target_session_id: None,
+        attachments,
+        tool_profile: None,
+        thinking_level: None,
+        agent_subtype: None,
+        max_tool_iterations: None,
+        max_tx_value_wei: None,
     };
 
     // Subscribe to events for real-time tool call forwarding
@@ -514,6 +699,7 @@ async fn process_slack_message(
     let thread_for_events = reply_thread_ts.clone();
     let channel_id_for_events = channel_id;
     let chat_id_for_events = slack_channel.to_string();
+    let dispatcher_for_events = state.dispatcher.clone();
 
     let event_task = tokio::spawn(async move {
         let mut status_ts: Option<SlackTs> = None;
@@ -529,6 +715,25 @@ async fn process_slack_message(
                 continue;
             }
 
+            // Partner mode: post the tx with Approve/Deny buttons
+            if event.event == "tx_queue.confirmation_required" {
+                if let (Some(tx), Some(tx_queue)) = (
+                    tx_approval::pending_tx_from_event(&dispatcher_for_events, &event.data),
+                    dispatcher_for_events.tx_queue(),
+                ) {
+                    send_tx_approval_prompt(
+                        client_for_events.clone(),
+                        token_for_events.clone(),
+                        channel_for_events.clone(),
+                        thread_for_events.clone(),
+                        &tx,
+                        tx_queue,
+                    )
+                    .await;
+                }
+                continue;
+            }
+
             let message_text = match event.event.as_str() {
                 "agent.tool_call" => {
                     let tool_name = event
@@ -725,6 +930,7 @@ async fn process_slack_message(
     log::info!("Slack: Unsubscribed from events, client {}", client_id);
 
     // Send final response in thread
+    let dispatch_failed = result.error.is_some();
     if result.error.is_none() && !result.response.is_empty() {
         let chunks = util::split_message(&result.response, 4000);
         for chunk in chunks {
@@ -750,26 +956,30 @@ async fn process_slack_message(
             Some(&reply_thread_ts),
         )
         .await;
-    } else if result.response.is_empty() {
+    } else if result.response.is_empty() && result.attachments.is_empty() {
         log::debug!("Slack: Empty final response for user {}", user_name);
     }
+
+    // Upload files attached by say_to_user
+    if !dispatch_failed && !result.attachments.is_empty() {
+        send_slack_files(&client, &state.bot_token, &slack_channel, &reply_thread_ts, result.attachments).await;
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Socket Mode event handler
 // ---------------------------------------------------------------------------
 
+/// Future returned by the socket-mode event callbacks
+type SlackEventFuture = std::pin::Pin<
+    Box<dyn std::future::Future<Output = Result<(), Box<dyn std::error::Error + Send + Sync>>> + Send>,
+>;
+
 fn handle_push_event(
     event: SlackPushEventCallback,
     client: Arc<SlackHyperClient>,
     user_state: SlackClientEventsUserState,
-) -> std::pin::Pin<
-    Box<
-        dyn std::future::Future<
-                Output = Result<(), Box<dyn std::error::Error + Send + Sync>>,
-            > + Send,
-    >,
-> {
+) -> SlackEventFuture {
     Box::pin(async move {
         // Retrieve our app state
         let state = {
@@ -792,7 +1002,8 @@ fn handle_push_event(
                     .as_deref()
                     .unwrap_or("")
                     .to_string();
-                if text.is_empty() {
+                let files = mention.content.files.clone().unwrap_or_default();
+                if text.is_empty() && files.is_empty() {
                     return Ok(());
                 }
 
@@ -820,6 +1031,7 @@ fn handle_push_event(
                     text,
                     message_ts,
                     thread_ts,
+                    files,
                 ));
             }
 
@@ -861,7 +1073,12 @@ fn handle_push_event(
                     .as_ref()
                     .and_then(|c| c.text.clone())
                     .unwrap_or_default();
-                if text.is_empty() {
+                let files = msg_event
+                    .content
+                    .as_ref()
+                    .and_then(|c| c.files.clone())
+                    .unwrap_or_default();
+                if text.is_empty() && files.is_empty() {
                     return Ok(());
                 }
 
@@ -891,6 +1108,7 @@ fn handle_push_event(
                     text,
                     message_ts,
                     thread_ts,
+                    files,
                 ));
             }
 
@@ -903,6 +1121,83 @@ fn handle_push_event(
     })
 }
 
+/// Approve/Deny button presses on transaction approval prompts
+fn handle_interaction_event(
+    event: SlackInteractionEvent,
+    client: Arc<SlackHyperClient>,
+    user_state: SlackClientEventsUserState,
+) -> SlackEventFuture {
+    Box::pin(async move {
+        let SlackInteractionEvent::BlockActions(block_actions) = event else {
+            return Ok(());
+        };
+
+        let state = {
+            let guard = user_state.read().await;
+            match guard.get_user_state::<SlackAppState>() {
+                Some(s) => s.clone(),
+                None => {
+                    log::error!("Slack: No SlackAppState in user_state — cannot process interaction");
+                    return Ok(());
+                }
+            }
+        };
+
+        let Some((action, uuid)) = block_actions
+            .actions
+            .iter()
+            .flatten()
+            .find_map(|a| tx_approval::parse_callback_id(a.action_id.as_ref()))
+        else {
+            return Ok(());
+        };
+        let (Some(user), Some(channel), Some(message)) =
+            (block_actions.user, block_actions.channel, block_actions.message)
+        else {
+            return Ok(());
+        };
+        let user_id = user.id.to_string();
+        let message_ts = message.origin.ts;
+
+        if !tx_approval::is_admin(state.admin_user_ids.as_deref(), &user_id) {
+            log::warn!(
+                "Slack: Non-admin user {} tried to {} transaction {}",
+                user_id,
+                action.as_str(),
+                uuid
+            );
+            let session = client.open_session(&state.bot_token);
+            let content = SlackMessageContent::new()
+                .with_text("Only admins can approve or deny transactions.".to_string());
+            let req = SlackApiChatPostEphemeralRequest::new(channel.id.clone(), user.id.clone(), content);
+            if let Err(e) = session.chat_post_ephemeral(&req).await {
+                log::warn!("Slack: Failed to send ephemeral approval notice: {}", e);
+            }
+            return Ok(());
+        }
+
+        // Socket Mode acks when this callback returns — broadcast in the background
+        tokio::spawn(async move {
+            let user_name = resolve_user_name(&client, &state.bot_token, &user_id).await;
+            let outcome = tx_approval::handle_action(
+                &state.dispatcher,
+                state.channel_id,
+                action,
+                &uuid,
+                &user_name,
+            )
+            .await;
+            if let Err(e) =
+                replace_slack_blocks(&client, &state.bot_token, &channel.id, &message_ts, &outcome).await
+            {
+                log::warn!("Slack: Failed to update approval prompt: {}", e);
+            }
+        });
+
+        Ok(())
+    })
+}
+
 // ---------------------------------------------------------------------------
 // Public entry point
 // ---------------------------------------------------------------------------
@@ -995,8 +1290,9 @@ pub async fn start_slack_listener(
     );
 
     // Create Socket Mode callbacks
-    let socket_mode_callbacks =
-        SlackSocketModeListenerCallbacks::new().with_push_events(handle_push_event);
+    let socket_mode_callbacks = SlackSocketModeListenerCallbacks::new()
+        .with_push_events(handle_push_event)
+        .with_interaction_events(handle_interaction_event);
 
     // Create socket mode listener
     let socket_mode_listener = SlackClientSocketModeListener::new(
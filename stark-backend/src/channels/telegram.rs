@@ -1,17 +1,21 @@
+use crate::channels::attachments::{self, Attachment};
 use crate::channels::dispatcher::MessageDispatcher;
+use crate::channels::tx_approval::{self, TxApprovalAction};
 use crate::channels::types::{ChannelType, NormalizedMessage};
 use crate::channels::util;
 use crate::db::Database;
 use crate::discord_hooks::db as user_db;
+use crate::disk_quota::DiskQuotaManager;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
 use crate::models::channel_settings::ChannelSettingKey;
 use crate::models::{Channel, ToolOutputVerbosity};
+use crate::tx_queue::{QueuedTransaction, TxQueueManager};
 use rand::seq::SliceRandom;
 use std::sync::Arc;
 use teloxide::prelude::*;
 use teloxide::requests::Requester;
-use teloxide::types::MessageId;
+use teloxide::types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, InputFile, MessageId};
 use tokio::sync::oneshot;
 
 /// Format a tool call event for Telegram display based on verbosity
@@ -230,6 +234,110 @@ async fn handle_shortcircuit_command(
     }
 }
 
+/// Upload outbound files: inline photos via sendPhoto, everything else via sendDocument
+async fn send_telegram_files(bot: &Bot, chat_id: ChatId, reply_to: MessageId, files: Vec<Attachment>) {
+    for file in files {
+        let Some(path) = file.absolute_path() else { continue };
+        let input = InputFile::file(path).file_name(file.file_name.clone());
+        let sent = if file.is_telegram_photo() {
+            bot.send_photo(chat_id, input).reply_to_message_id(reply_to).await
+        } else {
+            bot.send_document(chat_id, input).reply_to_message_id(reply_to).await
+        };
+        if let Err(e) = sent {
+            log::error!("Telegram: Failed to upload {}: {}", file.file_name, e);
+            let _ = bot
+                .send_message(chat_id, format!("📎 Failed to send {}: {}", file.file_name, e))
+                .reply_to_message_id(reply_to)
+                .await;
+        }
+    }
+}
+
+/// Collect the photo (largest size) and document on a Telegram message and
+/// download them into the workspace attachment cache
+async fn download_telegram_attachments(
+    bot: &Bot,
+    msg: &teloxide::types::Message,
+    channel_id: i64,
+    disk_quota: Option<Arc<DiskQuotaManager>>,
+) -> Vec<Attachment> {
+    let mut pending = Vec::new();
+    if let Some(photo) = msg.photo().and_then(|sizes| sizes.last()) {
+        pending.push(Attachment::new(
+            format!("photo_{}.jpg", msg.id.0),
+            Some("image/jpeg".to_string()),
+            photo.file.size as u64,
+            Some(photo.file.id.clone()),
+        ));
+    }
+    if let Some(doc) = msg.document() {
+        pending.push(Attachment::new(
+            doc.file_name.clone().unwrap_or_else(|| format!("document_{}", msg.id.0)),
+            doc.mime_type.as_ref().map(|m| m.to_string()),
+            doc.file.size as u64,
+            Some(doc.file.id.clone()),
+        ));
+    }
+
+    let mut downloaded = Vec::new();
+    for mut attachment in pending {
+        let file_id = attachment.platform_file_id.clone().unwrap_or_default();
+        match bot.get_file(file_id).await {
+            Ok(file) => {
+                let url = format!("https://api.telegram.org/file/bot{}/{}", bot.token(), file.path);
+                downloaded.push(
+                    attachments::download(attachment, &url, None, channel_id, disk_quota.as_deref()).await,
+                );
+            }
+            Err(e) => {
+                log::warn!("Telegram: getFile failed for {}: {}", attachment.file_name, e);
+                attachment.error = Some(format!("Telegram getFile failed: {}", e));
+                downloaded.push(attachment);
+            }
+        }
+    }
+    downloaded
+}
+
+/// Post a transaction approval prompt with inline Approve/Deny buttons.
+/// The buttons are removed if the approval window passes without a decision.
+async fn send_tx_approval_prompt(
+    bot: &Bot,
+    chat_id: ChatId,
+    tx: &QueuedTransaction,
+    tx_queue: Arc<TxQueueManager>,
+) {
+    let button = |action: TxApprovalAction| {
+        InlineKeyboardButton::callback(action.label(), action.callback_id(&tx.uuid))
+    };
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![
+        button(TxApprovalAction::Approve),
+        button(TxApprovalAction::Deny),
+    ]]);
+
+    match bot
+        .send_message(chat_id, tx_approval::format_prompt(tx))
+        .reply_markup(keyboard)
+        .await
+    {
+        Ok(sent) => {
+            log::info!("Telegram: Posted approval prompt for transaction {}", tx.uuid);
+            let bot = bot.clone();
+            let uuid = tx.uuid.clone();
+            tokio::spawn(async move {
+                if tx_approval::wait_for_expiry(tx_queue, uuid.clone()).await {
+                    // Editing the text without a reply markup drops the buttons
+                    let _ = bot
+                        .edit_message_text(chat_id, sent.id, tx_approval::format_expired(&uuid))
+                        .await;
+                }
+            });
+        }
+        Err(e) => log::error!("Telegram: Failed to send approval prompt: {}", e),
+    }
+}
+
 /// Start a Telegram bot listener
 pub async fn start_telegram_listener(
     channel: Channel,
@@ -300,9 +408,10 @@ pub async fn start_telegram_listener(
     let broadcaster_for_handler = broadcaster.clone();
     let bot_username_for_handler = bot_username.clone();
     let db_for_handler = db.clone();
+    let admin_user_id_for_callbacks = admin_user_id.clone();
 
     // Create message handler
-    let handler = Update::filter_message().endpoint(
+    let message_handler = Update::filter_message().endpoint(
         move |bot: Bot, msg: teloxide::types::Message, dispatcher: Arc<MessageDispatcher>, db: Arc<Database>| {
             let channel_id = channel_id;
             let broadcaster = broadcaster_for_handler.clone();
@@ -312,8 +421,9 @@ pub async fn start_telegram_listener(
             async move {
                 log::info!("Telegram: Received update from chat {}", msg.chat.id);
 
-                // Only handle text messages
-                if let Some(text) = msg.text() {
+                // Handle text messages, and photos/documents (caption as text)
+                let has_attachment = msg.photo().is_some() || msg.document().is_some();
+                if let Some(text) = msg.text().or_else(|| msg.caption()).or(has_attachment.then_some("")) {
                     // Ignore messages from the bot itself to prevent self-triggered loops
                     if msg.from().map(|u| u.id == bot_user_id).unwrap_or(false) {
                         log::debug!("Telegram: Ignoring self-message from bot user {}", bot_user_id);
@@ -357,7 +467,7 @@ pub async fn start_telegram_listener(
 
                     // Strip bot @mention from text
                     let clean_text = strip_bot_mention(text, &bot_username);
-                    let clean_text = if clean_text.is_empty() {
+                    let clean_text = if clean_text.is_empty() && !has_attachment {
                         "hello".to_string()
                     } else {
                         clean_text
@@ -396,15 +506,16 @@ pub async fn start_telegram_listener(
                         return Ok(());
                     }
 
-                    // Determine safe mode: if admin is configured, only admin gets full access
-                    let force_safe_mode = match &admin_user_id {
+                    // If admin is configured, everyone else falls back to safe mode unless the
+                    // dispatcher finds a role for them
+                    let safe_mode_fallback = match &admin_user_id {
                         Some(admin_id) => admin_id != &user_id,
                         None => false,
                     };
 
-                    if force_safe_mode {
+                    if safe_mode_fallback {
                         log::info!(
-                            "Telegram: User {} ({}) is not admin — using safe mode",
+                            "Telegram: User {} ({}) is not admin — safe mode unless a role is assigned",
                             user_name, user_id
                         );
                     } else if admin_user_id.is_some() {
@@ -447,6 +558,13 @@ pub async fn start_telegram_listener(
                         }
                     };
 
+                    // Download any photo/document into the workspace
+                    let attachments = if has_attachment {
+                        download_telegram_attachments(&bot, &msg, channel_id, dispatcher.disk_quota()).await
+                    } else {
+                        Vec::new()
+                    };
+
                     let normalized = NormalizedMessage {
                         channel_id,
                         channel_type: ChannelType::Telegram.to_string(),
@@ -458,7 +576,16 @@ pub async fn start_telegram_listener(
                         message_id: Some(msg.id.to_string()),
                         session_mode: None,
                         selected_network: None,
-                        force_safe_mode,
+                        force_safe_mode: false,
+                        safe_mode_fallback,
+                        endpoint_override: None,
+                        target_session_id: None,
+                        attachments,
+                        tool_profile: None,
+                        thinking_level: None,
+                        agent_subtype: None,
+                        max_tool_iterations: None,
+                        max_tx_value_wei: None,
                     };
 
                     // Subscribe to events for real-time tool call forwarding
@@ -467,6 +594,7 @@ pub async fn start_telegram_listener(
 
                     // Clone for event forwarder task
                     let bot_for_events = bot.clone();
+                    let dispatcher_for_events = dispatcher.clone();
                     let telegram_chat_id = msg.chat.id;
                     let channel_id_for_events = channel_id;
                     let chat_id_str_for_events = telegram_chat_id.to_string();
@@ -487,6 +615,17 @@ pub async fn start_telegram_listener(
                                 continue;
                             }
 
+                            // Partner mode: post the tx with inline Approve/Deny buttons
+                            if event.event == "tx_queue.confirmation_required" {
+                                if let (Some(tx), Some(tx_queue)) = (
+                                    tx_approval::pending_tx_from_event(&dispatcher_for_events, &event.data),
+                                    dispatcher_for_events.tx_queue(),
+                                ) {
+                                    send_tx_approval_prompt(&bot_for_events, telegram_chat_id, &tx, tx_queue).await;
+                                }
+                                continue;
+                            }
+
                             let message_text = match event.event.as_str() {
                                 "agent.tool_call" => {
                                     let tool_name = event
@@ -681,6 +820,7 @@ pub async fn start_telegram_listener(
                     );
 
                     // Send final response
+                    let dispatch_failed = result.error.is_some();
                     if result.error.is_none() && !result.response.is_empty() {
                         // Log bot response in passive chat log
                         let _ = db.store_telegram_chat_message(
@@ -710,9 +850,55 @@ pub async fn start_telegram_listener(
                             .send_message(msg.chat.id, &error_msg)
                             .reply_to_message_id(msg.id)
                             .await;
-                    } else if result.response.is_empty() {
+                    } else if result.response.is_empty() && result.attachments.is_empty() {
                         log::debug!("Telegram: Empty final response for user {}", user_name);
                     }
+
+                    // Upload files attached by say_to_user
+                    if !dispatch_failed && !result.attachments.is_empty() {
+                        send_telegram_files(&bot, msg.chat.id, msg.id, result.attachments).await;
+                    }
+                }
+
+                Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+            }
+        },
+    );
+
+    // Approve/Deny button presses on transaction approval prompts
+    let callback_handler = Update::filter_callback_query().endpoint(
+        move |bot: Bot, q: CallbackQuery, dispatcher: Arc<MessageDispatcher>| {
+            let admin_user_id = admin_user_id_for_callbacks.clone();
+            async move {
+                let Some((action, uuid)) = q.data.as_deref().and_then(tx_approval::parse_callback_id) else {
+                    return Ok(());
+                };
+
+                let user_id = q.from.id.to_string();
+                if !tx_approval::is_admin(admin_user_id.as_deref(), &user_id) {
+                    log::warn!(
+                        "Telegram: Non-admin user {} tried to {} transaction {}",
+                        user_id, action.as_str(), uuid
+                    );
+                    let _ = bot
+                        .answer_callback_query(q.id.clone())
+                        .text("Only admins can approve or deny transactions.")
+                        .show_alert(true)
+                        .await;
+                    return Ok(());
+                }
+
+                // Acknowledge immediately — broadcasting can take longer than the callback timeout
+                let _ = bot.answer_callback_query(q.id.clone()).await;
+
+                let user_name = q.from.username.clone().unwrap_or_else(|| q.from.first_name.clone());
+                let outcome = tx_approval::handle_action(&dispatcher, channel_id, action, &uuid, &user_name).await;
+
+                if let Some(message) = q.message {
+                    // Editing the text without a reply markup drops the buttons
+                    if let Err(e) = bot.edit_message_text(message.chat.id, message.id, outcome).await {
+                        log::warn!("Telegram: Failed to update approval prompt: {}", e);
+                    }
                 }
 
                 Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
@@ -720,6 +906,10 @@ pub async fn start_telegram_listener(
         },
     );
 
+    let handler = dptree::entry()
+        .branch(message_handler)
+        .branch(callback_handler);
+
     // Create dispatcher
     let mut tg_dispatcher = Dispatcher::builder(bot, handler)
         .dependencies(dptree::deps![dispatcher, db_for_handler])
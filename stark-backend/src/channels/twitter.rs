@@ -318,8 +318,9 @@ pub async fn start_twitter_listener(
     // Load configuration
     let mut config = TwitterConfig::from_channel(&channel, &db)?;
 
-    // SECURITY: Safe mode is always handled per-message via force_safe_mode.
-    // If an admin X account is configured, admin tweets get standard mode while others get safe mode.
+    // SECURITY: Safe mode is always handled per-message via safe_mode_fallback.
+    // If an admin X account is configured, admin tweets get standard mode while others get safe
+    // mode (or the role assigned to them).
     // If no admin is configured, ALL tweets get safe mode.
     // We never set channel-level safe_mode, so the channel stays eligible for cloud backup.
     if config.admin_user_id.is_some() {
@@ -543,15 +544,15 @@ pub async fn start_twitter_listener(
                                     }
                                 );
 
-                                // Determine safe mode: admin gets standard mode, everyone else gets safe mode.
-                                // When no admin is configured, all tweets are safe mode.
+                                // Admin gets standard mode, everyone else falls back to safe mode unless
+                                // a role is assigned. When no admin is configured, that covers all tweets.
                                 // (is_admin was computed above, before rate limit check)
-                                let force_safe_mode = !is_admin;
+                                let safe_mode_fallback = !is_admin;
 
                                 if is_admin {
                                     log::info!("Twitter: @{} is admin — using standard mode", author_username);
                                 } else {
-                                    log::info!("Twitter: @{} is not admin — using safe mode", author_username);
+                                    log::info!("Twitter: @{} is not admin — safe mode unless a role is assigned", author_username);
                                 }
 
                                 // Process the mention
@@ -560,7 +561,7 @@ pub async fn start_twitter_listener(
                                     &author_username,
                                     &config,
                                     channel_id,
-                                    force_safe_mode,
+                                    safe_mode_fallback,
                                     &dispatcher,
                                     &broadcaster,
                                     &bot_mention_regex,
@@ -961,7 +962,7 @@ async fn process_mention(
     author_username: &str,
     config: &TwitterConfig,
     channel_id: i64,
-    force_safe_mode: bool,
+    safe_mode_fallback: bool,
     dispatcher: &Arc<MessageDispatcher>,
     broadcaster: &Arc<EventBroadcaster>,
     bot_mention_regex: &Regex,
@@ -1024,7 +1025,16 @@ async fn process_mention(
         message_id: Some(tweet.id.clone()),
         session_mode: None,
         selected_network: None,
-        force_safe_mode,
+        force_safe_mode: false,
+        safe_mode_fallback,
+        endpoint_override: None,
+        target_session_id: None,
+        attachments: Vec::new(),
+        tool_profile: None,
+        thinking_level: None,
+        agent_subtype: None,
+        max_tool_iterations: None,
+        max_tx_value_wei: None,
     };
 
     // Subscribe to events to capture say_to_user messages.
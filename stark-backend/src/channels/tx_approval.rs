@@ -0,0 +1,243 @@
+//! In-chat approval buttons for partner-mode transactions
+//!
+//! When a tool emits `tx_queue.confirmation_required`, the Telegram, Discord and
+//! Slack listeners post the transaction summary with Approve/Deny buttons.
+//! Presses are only honoured from the channel's configured admin user IDs and go
+//! through `tx_queue::approval` — the same path as the web UI confirmation modal.
+
+use std::sync::Arc;
+
+use crate::channels::dispatcher::MessageDispatcher;
+use crate::tx_queue::{
+    approve_queued_tx, deny_queued_tx, expire_if_stale, QueuedTransaction, QueuedTxStatus,
+    TxQueueManager, TX_APPROVAL_TTL_SECS,
+};
+
+/// Prefix for button callback IDs (`tx_approve:<uuid>`, `tx_deny:<uuid>`).
+/// Kept short: Telegram limits callback data to 64 bytes.
+const CALLBACK_PREFIX: &str = "tx_";
+
+/// Button action on a queued transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxApprovalAction {
+    Approve,
+    Deny,
+}
+
+impl TxApprovalAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TxApprovalAction::Approve => "approve",
+            TxApprovalAction::Deny => "deny",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "approve" => Some(TxApprovalAction::Approve),
+            "deny" => Some(TxApprovalAction::Deny),
+            _ => None,
+        }
+    }
+
+    /// Callback/custom/action ID for this action's button
+    pub fn callback_id(&self, uuid: &str) -> String {
+        format!("{}{}:{}", CALLBACK_PREFIX, self.as_str(), uuid)
+    }
+
+    /// Button label
+    pub fn label(&self) -> &'static str {
+        match self {
+            TxApprovalAction::Approve => "✅ Approve",
+            TxApprovalAction::Deny => "🚫 Deny",
+        }
+    }
+}
+
+/// Parse a button callback ID into (action, tx uuid)
+pub fn parse_callback_id(data: &str) -> Option<(TxApprovalAction, String)> {
+    let rest = data.strip_prefix(CALLBACK_PREFIX)?;
+    let (action, uuid) = rest.split_once(':')?;
+    if uuid.is_empty() {
+        return None;
+    }
+    Some((TxApprovalAction::from_str(action)?, uuid.to_string()))
+}
+
+/// Check a user against a comma-separated admin ID setting.
+/// Unlike chat access, approvals are denied when no admins are configured.
+pub fn is_admin(admin_user_ids: Option<&str>, user_id: &str) -> bool {
+    admin_user_ids
+        .map(|ids| ids.split(',').map(|s| s.trim()).any(|id| !id.is_empty() && id == user_id))
+        .unwrap_or(false)
+}
+
+/// Look up the pending transaction referenced by a `tx_queue.confirmation_required` event
+pub fn pending_tx_from_event(
+    dispatcher: &MessageDispatcher,
+    data: &serde_json::Value,
+) -> Option<QueuedTransaction> {
+    let uuid = data.get("uuid").and_then(|v| v.as_str())?;
+    dispatcher
+        .tx_queue()?
+        .get(uuid)
+        .filter(|tx| tx.status == QueuedTxStatus::Pending)
+}
+
+/// Plain-text transaction summary shown above the buttons
+pub fn format_prompt(tx: &QueuedTransaction) -> String {
+    let expires = tx.expires_at(TX_APPROVAL_TTL_SECS);
+    format!(
+        "🔐 Transaction awaiting approval\n\n\
+        Network: {}\n\
+        From: {}\n\
+        To: {}\n\
+        Value: {}\n\
+        Data: {}\n\
+        UUID: {}\n\n\
+        Expires at {} UTC. Only admins can approve or deny.",
+        tx.network,
+        tx.from,
+        tx.to,
+        tx.format_value_eth(),
+        format_calldata(&tx.data),
+        tx.uuid,
+        expires.format("%H:%M:%S"),
+    )
+}
+
+/// Text that replaces the prompt once the approval window has passed
+pub fn format_expired(uuid: &str) -> String {
+    format!("⌛ Transaction {} expired without approval.", uuid)
+}
+
+fn format_calldata(data: &str) -> String {
+    let data = data.trim();
+    if data.is_empty() || data == "0x" {
+        "(none — plain transfer)".to_string()
+    } else if data.len() > 10 {
+        format!("{}… ({} bytes)", &data[..10], (data.len() - 2) / 2)
+    } else {
+        data.to_string()
+    }
+}
+
+/// Wait until the transaction's approval window closes.
+/// Returns true if it was still pending and is now expired (the prompt should be updated).
+pub async fn wait_for_expiry(tx_queue: Arc<TxQueueManager>, uuid: String) -> bool {
+    let Some(tx) = tx_queue.get(&uuid) else {
+        return false;
+    };
+    let remaining = (tx.expires_at(TX_APPROVAL_TTL_SECS) - chrono::Utc::now())
+        .to_std()
+        .unwrap_or_default();
+    tokio::time::sleep(remaining).await;
+    expire_if_stale(&tx_queue, &uuid)
+}
+
+/// Apply a button press from an admin. Returns the text that replaces the prompt.
+pub async fn handle_action(
+    dispatcher: &MessageDispatcher,
+    channel_id: i64,
+    action: TxApprovalAction,
+    uuid: &str,
+    user_name: &str,
+) -> String {
+    let Some(tx_queue) = dispatcher.tx_queue() else {
+        return "Transaction queue not available.".to_string();
+    };
+
+    // Only transactions queued from this channel can be approved here
+    match tx_queue.get(uuid) {
+        Some(tx) if tx.channel_id == Some(channel_id) => {}
+        _ => return format!("Transaction {} not found.", uuid),
+    }
+
+    log::info!(
+        "[tx_approval] {} requested {} for transaction {}",
+        user_name,
+        action.as_str(),
+        uuid
+    );
+
+    match action {
+        TxApprovalAction::Approve => {
+            if expire_if_stale(&tx_queue, uuid) {
+                return format_expired(uuid);
+            }
+            match approve_queued_tx(
+                uuid,
+                channel_id,
+                tx_queue,
+                dispatcher.broadcaster().clone(),
+                dispatcher.wallet_provider(),
+            )
+            .await
+            {
+                Ok(approval) => format!(
+                    "✅ Approved by {} — broadcast as {}\n{}",
+                    user_name, approval.tx_hash, approval.explorer_url
+                ),
+                Err(e) => format!("❌ Approval failed: {}", e),
+            }
+        }
+        TxApprovalAction::Deny => {
+            match deny_queued_tx(uuid, channel_id, &tx_queue, dispatcher.broadcaster()) {
+                Ok(()) => format!("🚫 Denied by {} — transaction {} discarded.", user_name, uuid),
+                Err(e) => format!("❌ Deny failed: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_callback_id_roundtrip() {
+        let uuid = "6f1c2a4e-2b7d-4c1e-9a55-0e4b7f3d9c21";
+        for action in [TxApprovalAction::Approve, TxApprovalAction::Deny] {
+            let id = action.callback_id(uuid);
+            assert!(id.len() <= 64, "Telegram callback data limit");
+            assert_eq!(parse_callback_id(&id), Some((action, uuid.to_string())));
+        }
+        assert_eq!(parse_callback_id("tx_approve:"), None);
+        assert_eq!(parse_callback_id("tx_launch:abc"), None);
+        assert_eq!(parse_callback_id("other:abc"), None);
+    }
+
+    #[test]
+    fn test_is_admin_requires_configured_ids() {
+        assert!(is_admin(Some("111, 222"), "222"));
+        assert!(!is_admin(Some("111, 222"), "333"));
+        assert!(!is_admin(Some(" , "), ""));
+        assert!(!is_admin(None, "111"));
+    }
+
+    #[test]
+    fn test_expire_if_stale() {
+        let tx_queue = TxQueueManager::new();
+        let mut tx = QueuedTransaction::new(
+            "stale".to_string(),
+            "base".to_string(),
+            "0x1234".to_string(),
+            "0x5678".to_string(),
+            "0".to_string(),
+            "0x".to_string(),
+            "21000".to_string(),
+            "1".to_string(),
+            "1".to_string(),
+            0,
+            "0xabcd".to_string(),
+            Some(1),
+        );
+        tx_queue.queue(tx.clone());
+        assert!(!expire_if_stale(&tx_queue, "stale"));
+
+        tx.created_at = chrono::Utc::now() - chrono::Duration::seconds(TX_APPROVAL_TTL_SECS + 1);
+        tx_queue.queue(tx);
+        assert!(expire_if_stale(&tx_queue, "stale"));
+        assert_eq!(tx_queue.get("stale").unwrap().status, QueuedTxStatus::Expired);
+    }
+}
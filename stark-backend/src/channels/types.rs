@@ -9,6 +9,8 @@ pub enum ChannelType {
     Discord,
     Twitter,
     ExternalChannel,
+    Email,
+    Matrix,
 }
 
 impl ChannelType {
@@ -20,6 +22,8 @@ impl ChannelType {
             Self::Discord => "discord",
             Self::Twitter => "twitter",
             Self::ExternalChannel => "external_channel",
+            Self::Email => "email",
+            Self::Matrix => "matrix",
         }
     }
 
@@ -31,13 +35,27 @@ impl ChannelType {
             "discord" => Some(Self::Discord),
             "twitter" => Some(Self::Twitter),
             "external_channel" => Some(Self::ExternalChannel),
+            "email" => Some(Self::Email),
+            "matrix" => Some(Self::Matrix),
             _ => None,
         }
     }
 
     /// All supported channel types
     pub fn all() -> &'static [ChannelType] {
-        &[Self::Telegram, Self::Slack, Self::Discord, Self::Twitter, Self::ExternalChannel]
+        &[Self::Telegram, Self::Slack, Self::Discord, Self::Twitter, Self::ExternalChannel, Self::Email, Self::Matrix]
+    }
+
+    /// Largest file the bot can upload to this platform (None = no outbound files)
+    pub fn max_upload_bytes(&self) -> Option<u64> {
+        match self {
+            // Bot API limit for sendDocument (sendPhoto is capped lower)
+            Self::Telegram => Some(50 * 1024 * 1024),
+            // Default limit for bots in unboosted servers
+            Self::Discord => Some(10 * 1024 * 1024),
+            Self::Slack => Some(1024 * 1024 * 1024),
+            Self::Twitter | Self::ExternalChannel | Self::Email | Self::Matrix => None,
+        }
     }
 
     /// Display name for UI
@@ -48,6 +66,8 @@ impl ChannelType {
             Self::Discord => "Discord",
             Self::Twitter => "Twitter",
             Self::ExternalChannel => "External Channel",
+            Self::Email => "Email",
+            Self::Matrix => "Matrix",
         }
     }
 }
@@ -85,9 +105,39 @@ pub struct NormalizedMessage {
     /// Used as default for web3 operations unless user explicitly specifies otherwise
     #[serde(default)]
     pub selected_network: Option<String>,
-    /// Force safe mode for this message (e.g., non-admin Discord queries)
+    /// Force safe mode for this message (e.g. unauthenticated email, safe-mode A2A runs).
+    /// Absolute: the caller's role is not consulted.
     #[serde(default)]
     pub force_safe_mode: bool,
+    /// Safe mode for a caller without a role (e.g. non-admin Telegram/Discord/Slack users).
+    /// Unlike force_safe_mode, a role assigned to the caller replaces it.
+    #[serde(default)]
+    pub safe_mode_fallback: bool,
+    /// Dispatch into this existing session instead of resolving one from the chat
+    /// (used by session rewind to continue on a forked branch)
+    #[serde(default)]
+    pub target_session_id: Option<i64>,
+    /// AI endpoint preset key (from ai_endpoints.ron) overriding the active agent settings
+    #[serde(default)]
+    pub endpoint_override: Option<String>,
+    /// Tool profile overriding the channel's tool config (e.g. set per webhook)
+    #[serde(default)]
+    pub tool_profile: Option<String>,
+    /// Thinking level for this message when no inline /think directive is given
+    #[serde(default)]
+    pub thinking_level: Option<String>,
+    /// Agent subtype the orchestrator starts in (e.g. set per cron job)
+    #[serde(default)]
+    pub agent_subtype: Option<String>,
+    /// Cap on tool loop iterations (can only lower the bot-wide setting)
+    #[serde(default)]
+    pub max_tool_iterations: Option<u32>,
+    /// Largest value (wei) a single transaction may move while handling this message
+    #[serde(default)]
+    pub max_tx_value_wei: Option<String>,
+    /// Files attached to the message (photos, documents, uploads)
+    #[serde(default)]
+    pub attachments: Vec<super::attachments::Attachment>,
 }
 
 /// Handle to a running channel listener
@@ -119,6 +169,8 @@ impl ChannelHandle {
 pub struct DispatchResult {
     pub response: String,
     pub error: Option<String>,
+    /// Workspace files to deliver with the response (queued by say_to_user)
+    pub attachments: Vec<super::attachments::Attachment>,
 }
 
 impl DispatchResult {
@@ -126,6 +178,7 @@ impl DispatchResult {
         Self {
             response,
             error: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -133,6 +186,12 @@ impl DispatchResult {
         Self {
             response: String::new(),
             error: Some(error),
+            attachments: Vec::new(),
         }
     }
+
+    pub fn with_attachments(mut self, attachments: Vec<super::attachments::Attachment>) -> Self {
+        self.attachments = attachments;
+        self
+    }
 }
@@ -343,10 +343,12 @@ impl ContextManager {
             Message {
                 role: MessageRole::System,
                 content: "You summarize conversations accurately and concisely.".to_string(),
+                images: Vec::new(),
             },
             Message {
                 role: MessageRole::User,
                 content: summary_prompt,
+                images: Vec::new(),
             },
         ];
 
@@ -446,10 +448,12 @@ impl ContextManager {
             Message {
                 role: MessageRole::System,
                 content: "You are a memory extraction assistant. Extract important information from conversations and format it as markdown.".to_string(),
+                images: Vec::new(),
             },
             Message {
                 role: MessageRole::User,
                 content: flush_prompt,
+                images: Vec::new(),
             },
         ];
 
@@ -577,10 +581,12 @@ impl ContextManager {
             Message {
                 role: MessageRole::System,
                 content: "You are a helpful assistant that summarizes conversations accurately and concisely.".to_string(),
+                images: Vec::new(),
             },
             Message {
                 role: MessageRole::User,
                 content: summary_prompt,
+                images: Vec::new(),
             },
         ];
 
@@ -775,10 +781,12 @@ pub async fn save_session_memory(
         Message {
             role: MessageRole::System,
             content: "You summarize conversations concisely. Respond only with the requested TITLE and SUMMARY format.".to_string(),
+            images: Vec::new(),
         },
         Message {
             role: MessageRole::User,
             content: summary_prompt,
+            images: Vec::new(),
         },
     ];
 
@@ -0,0 +1,472 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::Value;
+
+use crate::a2a::server::{self, Caller, InboundRun, CHANNEL_TYPE};
+use crate::a2a::types::{
+    parts_text, Artifact, JsonRpcRequest, JsonRpcResponse, MessageSendParams, Part, TaskArtifactUpdateEvent,
+    TaskIdParams, TaskStatusUpdateEvent, INTERNAL_ERROR, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND,
+    PARSE_ERROR, TASK_NOT_CANCELABLE, TASK_NOT_FOUND,
+};
+use crate::controllers::external_channel::{constant_time_eq, extract_bearer_token};
+use crate::models::chat_session::SessionScope;
+use crate::models::{A2aDirection, A2aTask, A2aTaskState, Channel};
+use crate::AppState;
+
+// ── Route configuration ─────────────────────────────────────────────────
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::scope("/api/a2a").route("", web::post().to(a2a_rpc)));
+}
+
+// ── Channel / caller resolution ─────────────────────────────────────────
+
+/// The external channel serving A2A and whether the request carried its token
+struct A2aChannel {
+    channel: Channel,
+    authorized: bool,
+    safe_mode: bool,
+    price: Option<u64>,
+}
+
+fn setting(state: &web::Data<AppState>, channel_id: i64, key: &str) -> String {
+    state
+        .db
+        .get_channel_setting(channel_id, key)
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+}
+
+/// Pick the A2A channel: the one whose API token was presented, otherwise the
+/// first running external channel with A2A enabled. A wrong token is an error
+/// rather than a silent downgrade to public access.
+fn resolve_channel(state: &web::Data<AppState>, req: &HttpRequest) -> Result<A2aChannel, HttpResponse> {
+    let token = extract_bearer_token(req).filter(|t| !t.is_empty());
+    let channel_manager = state.gateway.channel_manager();
+
+    let channels: Vec<Channel> = state
+        .db
+        .list_channels()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|ch| ch.channel_type == CHANNEL_TYPE)
+        .filter(|ch| setting(state, ch.id, "external_channel_a2a_enabled") == "true")
+        .filter(|ch| channel_manager.is_running(ch.id))
+        .collect();
+
+    let chosen = match token {
+        Some(ref token) => channels
+            .into_iter()
+            .find(|ch| {
+                let stored = setting(state, ch.id, "external_channel_api_token");
+                !stored.is_empty() && constant_time_eq(token.as_bytes(), stored.as_bytes())
+            })
+            .map(|ch| (ch, true))
+            .ok_or_else(|| {
+                HttpResponse::Unauthorized().json(JsonRpcResponse::error(Value::Null, INVALID_REQUEST, "Invalid A2A token"))
+            })?,
+        None => channels.into_iter().next().map(|ch| (ch, false)).ok_or_else(|| {
+            HttpResponse::NotFound().json(JsonRpcResponse::error(Value::Null, INVALID_REQUEST, "A2A is not enabled on this agent"))
+        })?,
+    };
+
+    let (channel, authorized) = chosen;
+    let price = match server::parse_price_usdc(&setting(state, channel.id, "external_channel_a2a_price_usdc")) {
+        Ok(price) => price,
+        Err(e) => {
+            log::error!("[A2A] Channel {} has an invalid price: {}", channel.id, e);
+            return Err(HttpResponse::ServiceUnavailable().json(JsonRpcResponse::error(
+                Value::Null,
+                INTERNAL_ERROR,
+                "A2A pricing is misconfigured",
+            )));
+        }
+    };
+    let safe_mode = setting(state, channel.id, "external_channel_safe_mode") == "true";
+
+    Ok(A2aChannel { channel, authorized, safe_mode, price })
+}
+
+/// Public URL of the JSON-RPC endpoint, as seen by the caller
+fn rpc_url(req: &HttpRequest) -> String {
+    let info = req.connection_info();
+    format!("{}://{}/api/a2a", info.scheme(), info.host())
+}
+
+/// Decide how a message sender gets in: token, a valid x402 payment, or free access.
+/// Unpaid messages on a priced channel get a 402 with the payment requirements.
+fn authorize_message(
+    state: &web::Data<AppState>,
+    req: &HttpRequest,
+    a2a: &A2aChannel,
+) -> Result<Caller, HttpResponse> {
+    if a2a.authorized {
+        return Ok(Caller::Token);
+    }
+    let Some(amount) = a2a.price else {
+        return Ok(Caller::Public);
+    };
+
+    let Some(ref wallet_provider) = state.wallet_provider else {
+        log::error!("[A2A] Channel {} has a price but no wallet is configured to receive payments", a2a.channel.id);
+        return Err(HttpResponse::ServiceUnavailable().json(JsonRpcResponse::error(
+            Value::Null,
+            INTERNAL_ERROR,
+            "Paid A2A access is unavailable",
+        )));
+    };
+    let requirements = server::payment_requirements(&wallet_provider.get_address(), amount, &rpc_url(req));
+
+    let payment_required = |error: &str| {
+        let body = server::payment_required_body(&requirements, error);
+        let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, body.to_string());
+        HttpResponse::PaymentRequired()
+            .insert_header(("payment-required", encoded))
+            .json(body)
+    };
+
+    let header = match req.headers().get("X-PAYMENT").and_then(|h| h.to_str().ok()) {
+        Some(h) if !h.trim().is_empty() => h,
+        _ => return Err(payment_required("X-PAYMENT header is required")),
+    };
+
+    let payment = crate::x402::verify_payment_header(header, &requirements).map_err(|e| {
+        log::warn!("[A2A] Rejected payment: {}", e);
+        payment_required(&e)
+    })?;
+    if state.db.is_a2a_payment_nonce_used(&payment.nonce).unwrap_or(true) {
+        return Err(payment_required("Payment authorization has already been used"));
+    }
+
+    Ok(Caller::Paid(Box::new(payment)))
+}
+
+/// Token-created tasks are only visible to token holders; other task IDs act as bearer secrets
+fn visible_to(task: &A2aTask, a2a: &A2aChannel) -> bool {
+    task.channel_id == Some(a2a.channel.id) && (a2a.authorized || task.caller.as_deref() != Some("token"))
+}
+
+// ── Endpoint handler ────────────────────────────────────────────────────
+
+/// POST /api/a2a — A2A JSON-RPC endpoint
+async fn a2a_rpc(state: web::Data<AppState>, req: HttpRequest, body: web::Bytes) -> HttpResponse {
+    let request: JsonRpcRequest = match serde_json::from_slice(&body) {
+        Ok(r) => r,
+        Err(e) => {
+            return HttpResponse::Ok().json(JsonRpcResponse::error(Value::Null, PARSE_ERROR, format!("Invalid JSON-RPC request: {}", e)));
+        }
+    };
+    if request.jsonrpc != "2.0" {
+        return HttpResponse::Ok().json(JsonRpcResponse::error(request.id, INVALID_REQUEST, "jsonrpc must be \"2.0\""));
+    }
+
+    let a2a = match resolve_channel(&state, &req) {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+
+    match request.method.as_str() {
+        "message/send" | "message/stream" => handle_message(state, req, a2a, request).await,
+        "tasks/get" => handle_get(&state, &a2a, request),
+        "tasks/cancel" => handle_cancel(&state, &a2a, request),
+        other => HttpResponse::Ok().json(JsonRpcResponse::error(
+            request.id,
+            METHOD_NOT_FOUND,
+            format!("Method '{}' is not supported", other),
+        )),
+    }
+}
+
+async fn handle_message(state: web::Data<AppState>, req: HttpRequest, a2a: A2aChannel, request: JsonRpcRequest) -> HttpResponse {
+    let id = request.id.clone();
+    let params: MessageSendParams = match serde_json::from_value(request.params) {
+        Ok(p) => p,
+        Err(e) => return HttpResponse::Ok().json(JsonRpcResponse::error(id, INVALID_PARAMS, format!("Invalid params: {}", e))),
+    };
+    let text = parts_text(&params.message.parts);
+    if text.trim().is_empty() {
+        return HttpResponse::Ok().json(JsonRpcResponse::error(id, INVALID_PARAMS, "Message has no text parts"));
+    }
+
+    let caller = match authorize_message(&state, &req, &a2a) {
+        Ok(c) => c,
+        Err(resp) => return resp,
+    };
+    let caller_label = caller.label();
+
+    // A context continues one conversation (one session); only its creator may add to it
+    let context_id = params
+        .message
+        .context_id
+        .clone()
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    if let Ok(Some(previous)) = state.db.get_latest_a2a_task_in_context(A2aDirection::Inbound, &context_id)
+        && (previous.channel_id != Some(a2a.channel.id) || previous.caller.as_deref() != Some(caller_label.as_str()))
+    {
+        return HttpResponse::Ok().json(JsonRpcResponse::error(id, INVALID_PARAMS, "contextId belongs to another caller"));
+    }
+
+    let chat_id = server::chat_id_for_context(&context_id);
+    let session = match state.db.get_or_create_chat_session(CHANNEL_TYPE, a2a.channel.id, &chat_id, SessionScope::Api, None) {
+        Ok(s) => s,
+        Err(e) => {
+            log::error!("[A2A] Failed to create session: {}", e);
+            return HttpResponse::Ok().json(JsonRpcResponse::error(id, INTERNAL_ERROR, "Failed to create session"));
+        }
+    };
+
+    let task_id = uuid::Uuid::new_v4().to_string();
+    if let Err(e) = state.db.create_a2a_task(
+        &task_id,
+        A2aDirection::Inbound,
+        &context_id,
+        None,
+        None,
+        Some(&caller_label),
+        Some(a2a.channel.id),
+        Some(session.id),
+        &text,
+        A2aTaskState::Working,
+    ) {
+        log::error!("[A2A] Failed to record task: {}", e);
+        return HttpResponse::Ok().json(JsonRpcResponse::error(id, INTERNAL_ERROR, "Failed to record task"));
+    }
+
+    if let Caller::Paid(ref payment) = caller {
+        // The unique nonce index makes a concurrent replay fail here
+        if let Err(e) = state.db.set_a2a_task_payment(
+            A2aDirection::Inbound,
+            &task_id,
+            &payment.amount,
+            req.headers().get("X-PAYMENT").and_then(|h| h.to_str().ok()),
+            Some(&payment.nonce),
+        ) {
+            log::warn!("[A2A] Payment for task {} rejected: {}", task_id, e);
+            let _ = state.db.update_a2a_task_state(
+                A2aDirection::Inbound,
+                &task_id,
+                A2aTaskState::Rejected,
+                None,
+                Some("Payment authorization has already been used"),
+            );
+            return HttpResponse::PaymentRequired().json(JsonRpcResponse::error(id, INVALID_REQUEST, "Payment authorization has already been used"));
+        }
+
+        // Collect the payment before doing any work; the nonce stays recorded either way
+        let settled = match state.wallet_provider {
+            Some(ref wallet_provider) => crate::x402::settle_payment(payment, wallet_provider).await,
+            None => Err("No wallet is configured to receive payments".to_string()),
+        };
+        match settled {
+            Ok(tx_hash) => log::info!("[A2A] Settled payment for task {} in {}", task_id, tx_hash),
+            Err(e) => {
+                log::warn!("[A2A] Settling payment for task {} failed: {}", task_id, e);
+                let _ = state.db.update_a2a_task_state(
+                    A2aDirection::Inbound,
+                    &task_id,
+                    A2aTaskState::Rejected,
+                    None,
+                    Some(&format!("Payment settlement failed: {}", e)),
+                );
+                return HttpResponse::PaymentRequired().json(JsonRpcResponse::error(
+                    id,
+                    INVALID_REQUEST,
+                    format!("Payment settlement failed: {}", e),
+                ));
+            }
+        }
+    }
+
+    log::info!(
+        "[A2A] Task {} on '{}' (id={}) from {}: {} chars",
+        task_id,
+        a2a.channel.name,
+        a2a.channel.id,
+        caller_label,
+        text.len()
+    );
+
+    let run = InboundRun {
+        channel_id: a2a.channel.id,
+        session_id: session.id,
+        task_id: task_id.clone(),
+        context_id,
+        caller: caller_label,
+        text,
+        safe_mode: caller.safe_mode(a2a.safe_mode),
+    };
+
+    if request.method == "message/stream" {
+        return stream_task(state, id, run);
+    }
+
+    let blocking = params.configuration.and_then(|c| c.blocking).unwrap_or(true);
+    let (db, dispatcher, broadcaster) = (state.db.clone(), state.dispatcher.clone(), state.broadcaster.clone());
+    if blocking {
+        server::run_inbound_task(db, dispatcher, broadcaster, run, None).await;
+    } else {
+        tokio::spawn(server::run_inbound_task(db, dispatcher, broadcaster, run, None));
+    }
+
+    task_response(&state, id, &task_id)
+}
+
+/// message/stream: the task, then artifact chunks as the agent speaks, then a final status update
+fn stream_task(state: web::Data<AppState>, id: Value, run: InboundRun) -> HttpResponse {
+    let (tx, sse_rx) = tokio::sync::mpsc::channel::<web::Bytes>(64);
+    let db = state.db.clone();
+    let dispatcher = state.dispatcher.clone();
+    let broadcaster = state.broadcaster.clone();
+
+    tokio::spawn(async move {
+        let send = |value: JsonRpcResponse| {
+            let tx = tx.clone();
+            async move {
+                if let Ok(json) = serde_json::to_string(&value) {
+                    let _ = tx.send(web::Bytes::from(format!("data: {}\n\n", json))).await;
+                }
+            }
+        };
+
+        let task_id = run.task_id.clone();
+        let context_id = run.context_id.clone();
+        if let Ok(Some(row)) = db.get_a2a_task(A2aDirection::Inbound, &task_id) {
+            send(JsonRpcResponse::result(id.clone(), server::task_from_row(&row))).await;
+        }
+
+        let (out_tx, mut out_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        let running = tokio::spawn(server::run_inbound_task(db.clone(), dispatcher, broadcaster, run, Some(out_tx)));
+
+        let mut chunks = 0;
+        while let Some(text) = out_rx.recv().await {
+            let event = TaskArtifactUpdateEvent {
+                kind: "artifact-update".to_string(),
+                task_id: task_id.clone(),
+                context_id: context_id.clone(),
+                artifact: Artifact {
+                    artifact_id: "response".to_string(),
+                    parts: vec![Part::text(text)],
+                },
+                append: chunks > 0,
+            };
+            chunks += 1;
+            send(JsonRpcResponse::result(id.clone(), event)).await;
+        }
+        let _ = running.await;
+
+        if let Ok(Some(row)) = db.get_a2a_task(A2aDirection::Inbound, &task_id) {
+            let task = server::task_from_row(&row);
+            // Replies that were not streamed (plain final responses) go out as one artifact
+            if chunks == 0 {
+                for artifact in task.artifacts.iter().cloned() {
+                    send(JsonRpcResponse::result(
+                        id.clone(),
+                        TaskArtifactUpdateEvent {
+                            kind: "artifact-update".to_string(),
+                            task_id: task_id.clone(),
+                            context_id: context_id.clone(),
+                            artifact,
+                            append: false,
+                        },
+                    ))
+                    .await;
+                }
+            }
+            send(JsonRpcResponse::result(
+                id.clone(),
+                TaskStatusUpdateEvent {
+                    kind: "status-update".to_string(),
+                    task_id: task_id.clone(),
+                    context_id: context_id.clone(),
+                    status: task.status,
+                    is_final: true,
+                },
+            ))
+            .await;
+        }
+    });
+
+    let stream = futures_util::stream::unfold(sse_rx, |mut rx| async move {
+        rx.recv().await.map(|bytes| (Ok::<_, actix_web::Error>(bytes), rx))
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(stream)
+}
+
+fn handle_get(state: &web::Data<AppState>, a2a: &A2aChannel, request: JsonRpcRequest) -> HttpResponse {
+    let params: TaskIdParams = match serde_json::from_value(request.params) {
+        Ok(p) => p,
+        Err(e) => return HttpResponse::Ok().json(JsonRpcResponse::error(request.id, INVALID_PARAMS, format!("Invalid params: {}", e))),
+    };
+    match state.db.get_a2a_task(A2aDirection::Inbound, &params.id) {
+        Ok(Some(task)) if visible_to(&task, a2a) => {
+            HttpResponse::Ok().json(JsonRpcResponse::result(request.id, server::task_from_row(&task)))
+        }
+        _ => HttpResponse::Ok().json(JsonRpcResponse::error(request.id, TASK_NOT_FOUND, "Task not found")),
+    }
+}
+
+fn handle_cancel(state: &web::Data<AppState>, a2a: &A2aChannel, request: JsonRpcRequest) -> HttpResponse {
+    let params: TaskIdParams = match serde_json::from_value(request.params) {
+        Ok(p) => p,
+        Err(e) => return HttpResponse::Ok().json(JsonRpcResponse::error(request.id, INVALID_PARAMS, format!("Invalid params: {}", e))),
+    };
+    let task = match state.db.get_a2a_task(A2aDirection::Inbound, &params.id) {
+        Ok(Some(task)) if visible_to(&task, a2a) => task,
+        _ => return HttpResponse::Ok().json(JsonRpcResponse::error(request.id, TASK_NOT_FOUND, "Task not found")),
+    };
+    if task.state_enum().is_terminal() {
+        return HttpResponse::Ok().json(JsonRpcResponse::error(
+            request.id,
+            TASK_NOT_CANCELABLE,
+            format!("Task is already {}", task.state),
+        ));
+    }
+
+    if let Err(e) = state.db.update_a2a_task_state(A2aDirection::Inbound, &task.task_id, A2aTaskState::Canceled, None, None) {
+        log::error!("[A2A] Failed to cancel task {}: {}", task.task_id, e);
+        return HttpResponse::Ok().json(JsonRpcResponse::error(request.id, INTERNAL_ERROR, "Failed to cancel task"));
+    }
+    if let Some(session_id) = task.session_id {
+        state.execution_tracker.cancel_execution_for_session(session_id);
+    }
+    log::info!("[A2A] Task {} canceled by peer", task.task_id);
+
+    task_response(state, request.id, &task.task_id)
+}
+
+fn task_response(state: &web::Data<AppState>, id: Value, task_id: &str) -> HttpResponse {
+    match state.db.get_a2a_task(A2aDirection::Inbound, task_id) {
+        Ok(Some(task)) => HttpResponse::Ok().json(JsonRpcResponse::result(id, server::task_from_row(&task))),
+        _ => HttpResponse::Ok().json(JsonRpcResponse::error(id, INTERNAL_ERROR, "Task disappeared")),
+    }
+}
+
+/// GET /.well-known/agent-card.json — public A2A agent card (404 unless A2A is enabled)
+pub async fn agent_card(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    let channel_manager = state.gateway.channel_manager();
+    let channel = state
+        .db
+        .list_channels()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|ch| ch.channel_type == CHANNEL_TYPE && channel_manager.is_running(ch.id))
+        .find(|ch| setting(&state, ch.id, "external_channel_a2a_enabled") == "true");
+
+    match channel {
+        Some(ch) => {
+            let price = server::parse_price_usdc(&setting(&state, ch.id, "external_channel_a2a_price_usdc"))
+                .ok()
+                .flatten();
+            HttpResponse::Ok().json(server::build_agent_card(&state.db, &rpc_url(&req), price))
+        }
+        None => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "A2A is not enabled on this agent"
+        })),
+    }
+}
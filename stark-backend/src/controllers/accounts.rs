@@ -0,0 +1,322 @@
+//! Dashboard account controller
+//!
+//! Admins manage operator accounts (wallet and/or password + TOTP login) and
+//! their roles. Every signed-in account can view itself, change its password
+//! and set up TOTP under /api/accounts/me. The auth middleware checks roles
+//! per route; the admin handlers check again so they never rely on path matching.
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+
+use crate::dashboard_auth;
+use crate::middleware::auth_guard;
+use crate::models::{CreateDashboardUserRequest, DashboardPrincipal, DashboardRole, UpdateDashboardUserRequest};
+use crate::AppState;
+
+/// Configure account routes
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/accounts")
+            .route("", web::get().to(list_accounts))
+            .route("", web::post().to(create_account))
+            .route("/me", web::get().to(get_me))
+            .route("/me/password", web::post().to(change_password))
+            .route("/me/totp/setup", web::post().to(setup_totp))
+            .route("/me/totp/enable", web::post().to(enable_totp))
+            .route("/{id}", web::put().to(update_account))
+            .route("/{id}", web::delete().to(delete_account)),
+    );
+}
+
+fn require_principal(req: &HttpRequest) -> Result<DashboardPrincipal, HttpResponse> {
+    auth_guard::principal(req).ok_or_else(|| {
+        HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "No authorization token provided"
+        }))
+    })
+}
+
+/// Account management is for admin login sessions only
+fn require_admin(req: &HttpRequest) -> Result<DashboardPrincipal, HttpResponse> {
+    let principal = require_principal(req)?;
+    if principal.role != DashboardRole::Admin || principal.scopes.is_some() {
+        return Err(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Only admins can manage accounts"
+        })));
+    }
+    Ok(principal)
+}
+
+/// /me routes need a real account, not the legacy env-configured admin
+fn require_account(req: &HttpRequest) -> Result<i64, HttpResponse> {
+    let principal = require_principal(req)?;
+    principal.user_id.ok_or_else(|| {
+        bad_request("The LOGIN_ADMIN_PUBLIC_ADDRESS login has no account; create one under /api/accounts")
+    })
+}
+
+fn bad_request(error: impl Into<String>) -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({ "error": error.into() }))
+}
+
+fn db_error(context: &str, e: rusqlite::Error) -> HttpResponse {
+    log::error!("{}: {}", context, e);
+    HttpResponse::InternalServerError().json(serde_json::json!({
+        "error": format!("Database error: {}", e)
+    }))
+}
+
+fn parse_role(role: &str) -> Result<DashboardRole, HttpResponse> {
+    DashboardRole::from_str(role)
+        .ok_or_else(|| bad_request(format!("Unknown role '{}' (admin, operator or auditor)", role)))
+}
+
+fn normalize_wallet(address: &str) -> Result<String, HttpResponse> {
+    let address = address.trim().to_lowercase();
+    if !address.is_empty() && (!address.starts_with("0x") || address.len() != 42) {
+        return Err(bad_request("Invalid wallet address"));
+    }
+    Ok(address)
+}
+
+async fn list_accounts(state: web::Data<AppState>, req: HttpRequest) -> HttpResponse {
+    if let Err(resp) = require_admin(&req) {
+        return resp;
+    }
+
+    match state.db.list_dashboard_users() {
+        Ok(accounts) => HttpResponse::Ok().json(serde_json::json!({ "accounts": accounts })),
+        Err(e) => db_error("Failed to list accounts", e),
+    }
+}
+
+async fn create_account(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CreateDashboardUserRequest>,
+) -> HttpResponse {
+    if let Err(resp) = require_admin(&req) {
+        return resp;
+    }
+
+    let body = body.into_inner();
+    let username = body.username.trim().to_string();
+    if username.is_empty() || !username.chars().all(|c| c.is_ascii_alphanumeric() || "-_.@".contains(c)) {
+        return bad_request("Username must be letters, digits, '-', '_', '.' or '@'");
+    }
+    let role = match parse_role(&body.role) {
+        Ok(r) => r,
+        Err(resp) => return resp,
+    };
+    let wallet = match body.wallet_address.as_deref().map(normalize_wallet).transpose() {
+        Ok(w) => w.filter(|w| !w.is_empty()),
+        Err(resp) => return resp,
+    };
+    let password_hash = match body.password.as_deref() {
+        Some(password) => match dashboard_auth::validate_password(password) {
+            Ok(()) => Some(dashboard_auth::hash_password(password)),
+            Err(e) => return bad_request(e),
+        },
+        None => None,
+    };
+    if wallet.is_none() && password_hash.is_none() {
+        return bad_request("Set a wallet_address and/or password so the account can sign in");
+    }
+
+    match state.db.create_dashboard_user(&username, role, wallet.as_deref(), password_hash.as_deref()) {
+        Ok(account) => {
+            log::info!("[ACCOUNTS] Created '{}' ({})", account.username, account.role);
+            HttpResponse::Created().json(account)
+        }
+        Err(e) if e.to_string().contains("UNIQUE") => HttpResponse::Conflict()
+            .json(serde_json::json!({ "error": "Username or wallet address already in use" })),
+        Err(e) => db_error("Failed to create account", e),
+    }
+}
+
+async fn update_account(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<UpdateDashboardUserRequest>,
+) -> HttpResponse {
+    let principal = match require_admin(&req) {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+
+    let id = path.into_inner();
+    let body = body.into_inner();
+    let role = match body.role.as_deref().map(parse_role).transpose() {
+        Ok(r) => r,
+        Err(resp) => return resp,
+    };
+    if principal.user_id == Some(id) && role.is_some_and(|r| r != DashboardRole::Admin) {
+        return bad_request("You cannot remove your own admin role");
+    }
+    let wallet = match body.wallet_address.as_deref().map(normalize_wallet).transpose() {
+        Ok(w) => w,
+        Err(resp) => return resp,
+    };
+    let password_hash = match body.password.as_deref() {
+        Some(password) => match dashboard_auth::validate_password(password) {
+            Ok(()) => Some(dashboard_auth::hash_password(password)),
+            Err(e) => return bad_request(e),
+        },
+        None => None,
+    };
+    // TOTP can only be switched on by its owner confirming a code
+    if body.totp_enabled == Some(true) {
+        return bad_request("TOTP is enabled by the account owner via /api/accounts/me/totp");
+    }
+
+    match state.db.update_dashboard_user(id, role, wallet.as_deref(), password_hash.as_deref(), body.totp_enabled) {
+        Ok(Some(account)) => HttpResponse::Ok().json(account),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({ "error": "Account not found" })),
+        Err(e) if e.to_string().contains("UNIQUE") => HttpResponse::Conflict()
+            .json(serde_json::json!({ "error": "Wallet address already in use" })),
+        Err(e) => db_error("Failed to update account", e),
+    }
+}
+
+async fn delete_account(state: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> HttpResponse {
+    let principal = match require_admin(&req) {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+
+    let id = path.into_inner();
+    if principal.user_id == Some(id) {
+        return bad_request("You cannot delete your own account");
+    }
+
+    match state.db.delete_dashboard_user(id) {
+        Ok(true) => {
+            log::info!("[ACCOUNTS] Deleted account {} (by '{}')", id, principal.username);
+            HttpResponse::Ok().json(serde_json::json!({ "success": true }))
+        }
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({ "error": "Account not found" })),
+        Err(e) => db_error("Failed to delete account", e),
+    }
+}
+
+async fn get_me(state: web::Data<AppState>, req: HttpRequest) -> HttpResponse {
+    let principal = match require_principal(&req) {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+
+    let account = match principal.user_id {
+        Some(id) => match state.db.get_dashboard_user(id) {
+            Ok(account) => account,
+            Err(e) => return db_error("Failed to get account", e),
+        },
+        None => None,
+    };
+    HttpResponse::Ok().json(serde_json::json!({
+        "username": principal.username,
+        "role": principal.role,
+        "account": account,
+        "api_token_id": principal.api_token_id,
+        "scopes": principal.scopes,
+    }))
+}
+
+#[derive(Deserialize)]
+struct ChangePasswordRequest {
+    #[serde(default)]
+    current_password: Option<String>,
+    new_password: String,
+}
+
+async fn change_password(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<ChangePasswordRequest>,
+) -> HttpResponse {
+    let id = match require_account(&req) {
+        Ok(id) => id,
+        Err(resp) => return resp,
+    };
+    let account = match state.db.get_dashboard_user(id) {
+        Ok(Some(a)) => a,
+        Ok(None) => return HttpResponse::NotFound().json(serde_json::json!({ "error": "Account not found" })),
+        Err(e) => return db_error("Failed to get account", e),
+    };
+
+    // Changing an existing password needs the old one
+    if let Some(ref hash) = account.password_hash {
+        let current = body.current_password.as_deref().unwrap_or_default();
+        if !dashboard_auth::verify_password(current, hash) {
+            return HttpResponse::Unauthorized()
+                .json(serde_json::json!({ "error": "Current password is incorrect" }));
+        }
+    }
+    if let Err(e) = dashboard_auth::validate_password(&body.new_password) {
+        return bad_request(e);
+    }
+
+    let hash = dashboard_auth::hash_password(&body.new_password);
+    match state.db.update_dashboard_user(id, None, None, Some(&hash), None) {
+        // All of the account's sessions are signed out; the client signs in again
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Err(e) => db_error("Failed to change password", e),
+    }
+}
+
+async fn setup_totp(state: web::Data<AppState>, req: HttpRequest) -> HttpResponse {
+    let id = match require_account(&req) {
+        Ok(id) => id,
+        Err(resp) => return resp,
+    };
+    let account = match state.db.get_dashboard_user(id) {
+        Ok(Some(a)) => a,
+        Ok(None) => return HttpResponse::NotFound().json(serde_json::json!({ "error": "Account not found" })),
+        Err(e) => return db_error("Failed to get account", e),
+    };
+    if account.totp_enabled {
+        return bad_request("TOTP is already enabled; ask an admin to reset it first");
+    }
+
+    let secret = dashboard_auth::generate_totp_secret();
+    if let Err(e) = state.db.set_dashboard_user_totp_secret(id, &secret) {
+        return db_error("Failed to store TOTP secret", e);
+    }
+    HttpResponse::Ok().json(serde_json::json!({
+        "secret": secret,
+        "otpauth_uri": dashboard_auth::totp_uri(&secret, &account.username),
+    }))
+}
+
+#[derive(Deserialize)]
+struct EnableTotpRequest {
+    code: String,
+}
+
+async fn enable_totp(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<EnableTotpRequest>,
+) -> HttpResponse {
+    let id = match require_account(&req) {
+        Ok(id) => id,
+        Err(resp) => return resp,
+    };
+    let account = match state.db.get_dashboard_user(id) {
+        Ok(Some(a)) => a,
+        Ok(None) => return HttpResponse::NotFound().json(serde_json::json!({ "error": "Account not found" })),
+        Err(e) => return db_error("Failed to get account", e),
+    };
+
+    let Some(secret) = account.totp_secret.as_deref() else {
+        return bad_request("Call /api/accounts/me/totp/setup first");
+    };
+    if !dashboard_auth::verify_totp(secret, &body.code) {
+        return bad_request("Invalid authenticator code");
+    }
+
+    match state.db.enable_dashboard_user_totp(id) {
+        Ok(()) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Err(e) => db_error("Failed to enable TOTP", e),
+    }
+}
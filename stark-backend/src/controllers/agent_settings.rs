@@ -165,7 +165,15 @@ pub async fn update_agent_settings(
         request.secret_key.is_some()
     );
 
-    match state.db.save_agent_settings(&request.endpoint, &request.model_archetype, request.max_response_tokens, request.max_context_tokens, request.secret_key.as_deref()) {
+    let saved = state
+        .db
+        .save_agent_settings(&request.endpoint, &request.model_archetype, request.max_response_tokens, request.max_context_tokens, request.secret_key.as_deref())
+        .and_then(|mut settings| {
+            state.db.set_agent_settings_vision(&request.endpoint, request.supports_vision)?;
+            settings.supports_vision = request.supports_vision;
+            Ok(settings)
+        });
+    match saved {
         Ok(settings) => {
             log::info!("Updated agent settings to use {} endpoint with {} archetype", request.endpoint, request.model_archetype);
             let response: AgentSettingsResponse = settings.into();
@@ -244,6 +252,14 @@ pub async fn update_bot_settings(
         }
     }
 
+    if let Some(max) = request.kanban_max_concurrent
+        && !(1..=20).contains(&max)
+    {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "kanban_max_concurrent must be between 1 and 20"
+        }));
+    }
+
     // Update KEYSTORE_CLIENT URL if keystore_url is being changed
     if let Some(ref url) = request.keystore_url {
         let new_url = if url.is_empty() { DEFAULT_KEYSTORE_URL } else { url.as_str() };
@@ -266,6 +282,7 @@ pub async fn update_bot_settings(
         request.theme_accent.as_deref(),
         request.proxy_url.as_deref(),
         request.kanban_auto_execute,
+        request.kanban_max_concurrent,
     ) {
         Ok(settings) => {
             log::info!(
@@ -4,9 +4,10 @@ use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, EnumIter, EnumString, IntoEnumIterator};
 
 use crate::backup::{ApiKeyEntry, BackupData};
-use crate::db::tables::mind_nodes::CreateMindNodeRequest;
+use crate::db::tables::mind_nodes::{CreateMindNodeRequest, MindEdgeType};
 use crate::keystore_client::KEYSTORE_CLIENT;
-use crate::models::ApiKeyResponse;
+use crate::middleware::auth_guard;
+use crate::models::{ApiKeyResponse, DashboardRole};
 use crate::AppState;
 
 /// Derive wallet address from private key
@@ -508,6 +509,8 @@ fn validate_session_from_request(
     }
 }
 
+/// Plaintext key value, for admin login sessions only. Checked here as well as in
+/// the auth guard so the handler doesn't depend on how the guard matches paths.
 async fn get_api_key_value(
     state: web::Data<AppState>,
     req: HttpRequest,
@@ -516,6 +519,16 @@ async fn get_api_key_value(
     if let Err(resp) = validate_session_from_request(&state, &req) {
         return resp;
     }
+    let is_admin_session = auth_guard::principal(&req)
+        .is_some_and(|p| p.role == DashboardRole::Admin && p.scopes.is_none());
+    if !is_admin_session {
+        return HttpResponse::Forbidden().json(GetApiKeyValueResponse {
+            success: false,
+            key_name: None,
+            key_value: None,
+            error: Some("Only admin sessions can reveal secret values".to_string()),
+        });
+    }
 
     match state.db.get_api_key(&query.key_name) {
         Ok(Some(key)) => HttpResponse::Ok().json(GetApiKeyValueResponse {
@@ -1223,7 +1236,8 @@ async fn restore_from_cloud(state: web::Data<AppState>, req: HttpRequest) -> imp
         let new_parent_id = old_to_new_id.get(&conn.parent_id);
         let new_child_id = old_to_new_id.get(&conn.child_id);
         if let (Some(&parent_id), Some(&child_id)) = (new_parent_id, new_child_id) {
-            match state.db.create_mind_node_connection(parent_id, child_id) {
+            let edge_type = MindEdgeType::from_str(&conn.edge_type).unwrap_or_default();
+            match state.db.create_typed_mind_node_connection(parent_id, child_id, edge_type, conn.weight) {
                 Ok(_) => restored_connections += 1,
                 Err(e) => {
                     log::warn!("Failed to restore connection: {}", e);
@@ -1261,6 +1275,7 @@ async fn restore_from_cloud(state: web::Data<AppState>, req: HttpRequest) -> imp
             settings.theme_accent.as_deref(),
             None, // Don't restore proxy_url - it's infrastructure config
             None, // Don't restore kanban_auto_execute - keep current setting
+            None, // Don't restore kanban_max_concurrent - keep current setting
         ) {
             log::warn!("Failed to restore bot settings: {}", e);
         }
@@ -1552,6 +1567,7 @@ async fn restore_from_cloud(state: web::Data<AppState>, req: HttpRequest) -> imp
             tags: skill_entry.tags.clone(),
             subagent_type: skill_entry.subagent_type.clone(),
             requires_api_keys,
+            workflow: skill_entry.workflow.clone(),
             created_at: now.clone(),
             updated_at: now.clone(),
         };
@@ -1604,6 +1620,9 @@ async fn restore_from_cloud(state: web::Data<AppState>, req: HttpRequest) -> imp
                 entry.secret_key.as_deref(),
             ) {
                 Ok(saved) => {
+                    if let Err(e) = state.db.set_agent_settings_vision(&entry.endpoint, entry.supports_vision) {
+                        log::warn!("Failed to restore vision setting for {}: {}", entry.endpoint, e);
+                    }
                     // save_agent_settings enables the last one saved; if the backup entry was
                     // disabled we need to disable all again and rely on the enabled one being
                     // saved last (they are ordered by id in the backup).
@@ -1645,11 +1664,16 @@ async fn restore_from_cloud(state: web::Data<AppState>, req: HttpRequest) -> imp
         }
 
         let mut restored_kanban = 0;
+        let mut old_kanban_to_new_id: std::collections::HashMap<i64, i64> = std::collections::HashMap::new();
         for item in &backup_data.kanban_items {
             let request = crate::db::tables::kanban::CreateKanbanItemRequest {
                 title: item.title.clone(),
                 description: Some(item.description.clone()),
                 priority: Some(item.priority),
+                max_retries: Some(item.max_retries),
+                timeout_seconds: item.timeout_seconds,
+                agent_subtype: item.agent_subtype.clone(),
+                ..Default::default()
             };
             match state.db.create_kanban_item(&request) {
                 Ok(new_item) => {
@@ -1659,11 +1683,25 @@ async fn restore_from_cloud(state: web::Data<AppState>, req: HttpRequest) -> imp
                         ..Default::default()
                     };
                     let _ = state.db.update_kanban_item(new_item.id, &update_req);
+                    old_kanban_to_new_id.insert(item.id, new_item.id);
                     restored_kanban += 1;
                 }
                 Err(e) => log::warn!("Failed to restore kanban item: {}", e),
             }
         }
+        // Dependencies point at backup IDs; remap once every item exists
+        for item in backup_data.kanban_items.iter().filter(|i| !i.blocked_by.is_empty()) {
+            if let Some(new_id) = old_kanban_to_new_id.get(&item.id) {
+                let blocked_by = item.blocked_by.iter()
+                    .filter_map(|old| old_kanban_to_new_id.get(old).copied())
+                    .collect();
+                let update_req = crate::db::tables::kanban::UpdateKanbanItemRequest {
+                    blocked_by: Some(blocked_by),
+                    ..Default::default()
+                };
+                let _ = state.db.update_kanban_item(*new_id, &update_req);
+            }
+        }
         if restored_kanban > 0 {
             log::info!("Restored {} kanban board items", restored_kanban);
         }
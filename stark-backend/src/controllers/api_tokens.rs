@@ -0,0 +1,134 @@
+//! API token controller
+//!
+//! Long-lived bearer tokens for automation, limited to scopes such as
+//! `tx_queue:read` or `kanban:write`. The plaintext token is returned once at
+//! creation; only its hash is stored. Non-admin accounts see and revoke only
+//! their own tokens.
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use chrono::{Duration, Utc};
+
+use crate::dashboard_auth;
+use crate::middleware::auth_guard;
+use crate::models::{CreateApiTokenRequest, DashboardPrincipal, DashboardRole};
+use crate::AppState;
+
+/// Configure API token routes
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/api-tokens")
+            .route("", web::get().to(list_tokens))
+            .route("", web::post().to(create_token))
+            .route("/{id}", web::delete().to(revoke_token)),
+    );
+}
+
+fn require_principal(req: &HttpRequest) -> Result<DashboardPrincipal, HttpResponse> {
+    auth_guard::principal(req).ok_or_else(|| {
+        HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "No authorization token provided"
+        }))
+    })
+}
+
+fn bad_request(error: impl Into<String>) -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({ "error": error.into() }))
+}
+
+fn db_error(context: &str, e: rusqlite::Error) -> HttpResponse {
+    log::error!("{}: {}", context, e);
+    HttpResponse::InternalServerError().json(serde_json::json!({
+        "error": format!("Database error: {}", e)
+    }))
+}
+
+/// Admins (including the legacy admin login) see every token
+fn owner_filter(principal: &DashboardPrincipal) -> Option<i64> {
+    match principal.role {
+        DashboardRole::Admin => None,
+        _ => principal.user_id,
+    }
+}
+
+async fn list_tokens(state: web::Data<AppState>, req: HttpRequest) -> HttpResponse {
+    let principal = match require_principal(&req) {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+
+    match state.db.list_api_tokens(owner_filter(&principal)) {
+        Ok(tokens) => HttpResponse::Ok().json(serde_json::json!({ "tokens": tokens })),
+        Err(e) => db_error("Failed to list API tokens", e),
+    }
+}
+
+async fn create_token(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CreateApiTokenRequest>,
+) -> HttpResponse {
+    let principal = match require_principal(&req) {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+
+    let body = body.into_inner();
+    let name = body.name.trim();
+    if name.is_empty() {
+        return bad_request("Token name is required");
+    }
+    if body.scopes.is_empty() {
+        return bad_request("At least one scope is required (e.g. \"tx_queue:read\")");
+    }
+    let scopes: Vec<String> = body.scopes.iter().map(|s| s.trim().to_lowercase()).collect();
+    if let Some(err) = scopes.iter().find_map(|s| auth_guard::validate_scope(s).err()) {
+        return bad_request(err);
+    }
+    // Tokens act with the creator's role, so read-only accounts can only mint read scopes
+    if !principal.role.can_write() && scopes.iter().any(|s| !s.ends_with(":read")) {
+        return bad_request(format!("Role '{}' can only create :read scopes", principal.role.as_str()));
+    }
+    let expires_at = match body.expires_in_days {
+        Some(days) if days <= 0 => return bad_request("expires_in_days must be positive"),
+        Some(days) => Some(Utc::now() + Duration::days(days)),
+        None => None,
+    };
+
+    let (token, hash) = dashboard_auth::generate_api_token();
+    let prefix = &token[..dashboard_auth::API_TOKEN_PREFIX.len() + 6];
+    match state.db.create_api_token(name, &hash, prefix, &scopes, principal.user_id, expires_at) {
+        Ok(api_token) => {
+            log::info!(
+                "[API_TOKENS] '{}' created token '{}' with scopes {:?}",
+                principal.username, api_token.name, api_token.scopes
+            );
+            HttpResponse::Created().json(serde_json::json!({
+                "token": token,
+                "api_token": api_token,
+            }))
+        }
+        Err(e) => db_error("Failed to create API token", e),
+    }
+}
+
+async fn revoke_token(state: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> HttpResponse {
+    let principal = match require_principal(&req) {
+        Ok(p) => p,
+        Err(resp) => return resp,
+    };
+
+    let id = path.into_inner();
+    let api_token = match state.db.get_api_token(id) {
+        Ok(Some(t)) => t,
+        Ok(None) => return HttpResponse::NotFound().json(serde_json::json!({ "error": "Token not found" })),
+        Err(e) => return db_error("Failed to get API token", e),
+    };
+    if owner_filter(&principal).is_some_and(|uid| api_token.user_id != Some(uid)) {
+        return HttpResponse::NotFound().json(serde_json::json!({ "error": "Token not found" }));
+    }
+
+    match state.db.revoke_api_token(id) {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Err(e) => db_error("Failed to revoke API token", e),
+    }
+}
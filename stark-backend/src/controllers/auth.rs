@@ -1,9 +1,8 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use chrono::Utc;
-use ethers::core::types::Signature;
-use ethers::utils::hash_message;
 use serde::{Deserialize, Serialize};
 
+use crate::dashboard_auth;
 use crate::AppState;
 
 const SERVICE_NAME: &str = "StarkBot";
@@ -29,6 +28,14 @@ pub struct ValidateAuthRequest {
     signature: String,
 }
 
+#[derive(Deserialize)]
+pub struct PasswordLoginRequest {
+    username: String,
+    password: String,
+    #[serde(default)]
+    totp_code: Option<String>,
+}
+
 #[derive(Serialize)]
 pub struct LoginResponse {
     success: bool,
@@ -60,6 +67,7 @@ pub fn config(cfg: &mut web::ServiceConfig) {
         web::scope("/api/auth")
             .route("/generate_challenge", web::post().to(generate_challenge))
             .route("/validate_auth", web::post().to(validate_auth))
+            .route("/login", web::post().to(password_login))
             .route("/logout", web::post().to(logout))
             .route("/validate", web::get().to(validate)),
     );
@@ -76,16 +84,6 @@ fn generate_challenge_text(public_address: &str, unix_timestamp: i64) -> String
     )
 }
 
-fn recover_address(msg: &str, signature: &str) -> Option<String> {
-    let sig_bytes = hex::decode(signature.strip_prefix("0x").unwrap_or(signature)).ok()?;
-    let sig = Signature::try_from(sig_bytes.as_slice()).ok()?;
-
-    let msg_hash = hash_message(msg);
-    let recovered = sig.recover(msg_hash).ok()?;
-
-    Some(format!("{:?}", recovered).to_lowercase())
-}
-
 async fn generate_challenge(
     state: web::Data<AppState>,
     body: web::Json<GenerateChallengeRequest>,
@@ -139,21 +137,35 @@ async fn validate_auth(
         });
     }
 
-    // Check that login is configured
-    let admin_address = match &state.config.login_admin_public_address {
-        Some(addr) => addr.to_lowercase(),
-        None => {
-            return HttpResponse::ServiceUnavailable().json(LoginResponse {
+    // Match the address to the env admin or a dashboard account with wallet login
+    let admin_address = state.config.login_admin_public_address.as_ref().map(|a| a.to_lowercase());
+    let account = match state.db.get_dashboard_user_by_wallet(&public_address) {
+        Ok(account) => account,
+        Err(e) => {
+            log::error!("Failed to look up dashboard account: {}", e);
+            return HttpResponse::InternalServerError().json(LoginResponse {
                 success: false,
                 token: None,
                 expires_at: None,
-                error: Some("Login not configured. Set LOGIN_ADMIN_PUBLIC_ADDRESS or BURNER_WALLET_BOT_PRIVATE_KEY environment variable.".to_string()),
+                error: Some("Database error".to_string()),
             });
         }
     };
 
-    // Check that this address is the admin address
-    if public_address != admin_address {
+    if account.is_none() && admin_address.as_deref() != Some(public_address.as_str()) {
+        let any_wallet_accounts = state
+            .db
+            .list_dashboard_users()
+            .map(|users| users.iter().any(|u| u.wallet_address.is_some()))
+            .unwrap_or(false);
+        if admin_address.is_none() && !any_wallet_accounts {
+            return HttpResponse::ServiceUnavailable().json(LoginResponse {
+                success: false,
+                token: None,
+                expires_at: None,
+                error: Some("Login not configured. Set LOGIN_ADMIN_PUBLIC_ADDRESS or BURNER_WALLET_BOT_PRIVATE_KEY environment variable.".to_string()),
+            });
+        }
         return HttpResponse::Unauthorized().json(LoginResponse {
             success: false,
             token: None,
@@ -185,7 +197,7 @@ async fn validate_auth(
     }
 
     // Verify signature
-    let recovered_address = recover_address(challenge, signature);
+    let recovered_address = crate::siwa::recover_signer(challenge, signature);
     if recovered_address.as_deref() != Some(public_address.as_str()) {
         return HttpResponse::Unauthorized().json(LoginResponse {
             success: false,
@@ -198,8 +210,19 @@ async fn validate_auth(
     // Delete the used challenge
     let _ = state.db.delete_challenge(&public_address);
 
-    // Create session
-    match state.db.create_session_for_address(Some(&public_address)) {
+    // Create session (the env admin without an account gets the legacy admin session)
+    let session = match account {
+        Some(ref user) => {
+            let _ = state.db.record_dashboard_login(user.id);
+            state.db.create_session_for_user(user.id, Some(&public_address))
+        }
+        None => state.db.create_session_for_address(Some(&public_address)),
+    };
+    session_response(session)
+}
+
+fn session_response(session: rusqlite::Result<crate::models::Session>) -> HttpResponse {
+    match session {
         Ok(session) => HttpResponse::Ok().json(LoginResponse {
             success: true,
             token: Some(session.token),
@@ -218,6 +241,108 @@ async fn validate_auth(
     }
 }
 
+fn login_failed(error: &str) -> HttpResponse {
+    HttpResponse::Unauthorized().json(LoginResponse {
+        success: false,
+        token: None,
+        expires_at: None,
+        error: Some(error.to_string()),
+    })
+}
+
+/// Username + password login for dashboard accounts, with a TOTP code when enabled.
+/// Failures are throttled per account and per client IP (see `dashboard_auth::LoginThrottle`).
+async fn password_login(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<PasswordLoginRequest>,
+) -> impl Responder {
+    let throttle = &*dashboard_auth::LOGIN_THROTTLE;
+    let username = body.username.trim().to_string();
+    let user_key = format!("user:{}", username.to_lowercase());
+    let ip_key = format!("ip:{}", req.connection_info().realip_remote_addr().unwrap_or("unknown"));
+    let now = Utc::now().timestamp();
+
+    if let Some(wait) = [&user_key, &ip_key].iter().filter_map(|k| throttle.locked_for(k, now)).max() {
+        log::warn!("[AUTH] Password login for '{}' throttled ({}s left)", username, wait);
+        return HttpResponse::TooManyRequests()
+            .insert_header(("Retry-After", wait.to_string()))
+            .json(LoginResponse {
+                success: false,
+                token: None,
+                expires_at: None,
+                error: Some(format!("Too many failed attempts. Try again in {} minutes", (wait + 59) / 60)),
+            });
+    }
+    let fail = |error: &str| {
+        throttle.record_failure(&user_key, now);
+        throttle.record_failure(&ip_key, now);
+        login_failed(error)
+    };
+
+    // PBKDF2 is deliberately slow: run the lookup and check off the async workers.
+    // Unknown users are checked against a dummy hash so timing does not reveal them.
+    let db = state.db.clone();
+    let password = body.password.clone();
+    let lookup = web::block(move || {
+        db.get_dashboard_user_by_username(&username).map(|user| {
+            let valid = dashboard_auth::verify_password_or_dummy(
+                &password,
+                user.as_ref().and_then(|u| u.password_hash.as_deref()),
+            );
+            (user, valid)
+        })
+    })
+    .await;
+
+    let user = match lookup {
+        // Same error for unknown users and wrong passwords
+        Ok(Ok((Some(user), true))) => user,
+        Ok(Ok(_)) => return fail("Invalid username or password"),
+        Ok(Err(e)) => {
+            log::error!("Failed to look up dashboard account: {}", e);
+            return HttpResponse::InternalServerError().json(LoginResponse {
+                success: false,
+                token: None,
+                expires_at: None,
+                error: Some("Database error".to_string()),
+            });
+        }
+        Err(e) => {
+            log::error!("Password check failed: {}", e);
+            return HttpResponse::InternalServerError().json(LoginResponse {
+                success: false,
+                token: None,
+                expires_at: None,
+                error: Some("Internal server error".to_string()),
+            });
+        }
+    };
+
+    if user.totp_enabled {
+        let Some(code) = body.totp_code.as_deref().filter(|c| !c.trim().is_empty()) else {
+            return HttpResponse::Unauthorized().json(serde_json::json!({
+                "success": false,
+                "totp_required": true,
+                "error": "Authenticator code required"
+            }));
+        };
+        let valid = user
+            .totp_secret
+            .as_deref()
+            .map(|secret| dashboard_auth::verify_totp(secret, code))
+            .unwrap_or(false);
+        if !valid {
+            return fail("Invalid authenticator code");
+        }
+    }
+
+    throttle.clear(&user_key);
+    log::info!("[AUTH] Password login for '{}' ({})", user.username, user.role);
+    let _ = state.db.record_dashboard_login(user.id);
+    session_response(state.db.create_session_for_user(user.id, user.wallet_address.as_deref()))
+}
+
 async fn logout(state: web::Data<AppState>, body: web::Json<LogoutRequest>) -> impl Responder {
     match state.db.delete_session(&body.token) {
         Ok(_) => HttpResponse::Ok().json(LogoutResponse { success: true }),
@@ -183,7 +183,7 @@ async fn create_channel(
         return HttpResponse::BadRequest().json(ChannelOperationResponse {
             success: false,
             channel: None,
-            error: Some("Invalid channel type. Valid options: telegram, slack, discord, twitter, external_channel".to_string()),
+            error: Some("Invalid channel type. Valid options: telegram, slack, discord, twitter, external_channel, email, matrix".to_string()),
         });
     }
 
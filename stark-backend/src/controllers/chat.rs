@@ -25,6 +25,9 @@ pub struct ChatRequest {
 pub struct ChatMessage {
     pub role: String,
     pub content: String,
+    /// Workspace files sent with an assistant reply (readable via /api/files/read)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<crate::channels::attachments::Attachment>,
 }
 
 #[derive(Serialize)]
@@ -208,6 +211,15 @@ async fn chat(
         session_mode: None,
         selected_network: body.network.clone(),
         force_safe_mode: false,
+        safe_mode_fallback: false,
+        endpoint_override: None,
+        target_session_id: None,
+        attachments: Vec::new(),
+        tool_profile: None,
+        thinking_level: None,
+        agent_subtype: None,
+        max_tool_iterations: None,
+        max_tx_value_wei: None,
     };
 
     // Dispatch through the unified pipeline
@@ -229,6 +241,7 @@ async fn chat(
         message: Some(ChatMessage {
             role: "assistant".to_string(),
             content: result.response,
+            attachments: result.attachments,
         }),
         error: None,
         session_id: None, // Could return session ID if needed
@@ -62,7 +62,9 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("/jobs/{id}/run", web::post().to(run_job))
             .route("/jobs/{id}/runs", web::get().to(get_job_runs))
             .route("/jobs/{id}/pause", web::post().to(pause_job))
-            .route("/jobs/{id}/resume", web::post().to(resume_job)),
+            .route("/jobs/{id}/resume", web::post().to(resume_job))
+            .route("/runs/{run_id}", web::get().to(get_run))
+            .route("/runs/{run_id}/replay", web::post().to(replay_run)),
     );
 
     cfg.service(
@@ -144,7 +146,25 @@ async fn create_job(
         });
     }
 
-    match state.db.create_cron_job(
+    if let Some(Err(e)) = body.run_overrides.as_ref().map(|o| o.validate()) {
+        return HttpResponse::BadRequest().json(CronJobResponse {
+            success: false,
+            job: None,
+            jobs: None,
+            error: Some(e),
+        });
+    }
+
+    if matches!(body.alert_after_failures, Some(n) if n < 1) {
+        return HttpResponse::BadRequest().json(CronJobResponse {
+            success: false,
+            job: None,
+            jobs: None,
+            error: Some("alert_after_failures must be at least 1".to_string()),
+        });
+    }
+
+    let created = state.db.create_cron_job(
         &body.name,
         body.description.as_deref(),
         &body.schedule_type,
@@ -160,7 +180,25 @@ async fn create_job(
         body.thinking_level.as_deref(),
         body.timeout_seconds,
         body.delete_after_run,
-    ) {
+    );
+    let created = match (created, body.run_overrides.as_ref()) {
+        (Ok(job), Some(overrides)) => state
+            .db
+            .set_cron_job_run_overrides(job.id, overrides)
+            .and_then(|_| state.db.get_cron_job(job.id))
+            .map(|refreshed| refreshed.unwrap_or(job)),
+        (result, _) => result,
+    };
+    let created = match (created, body.alert_after_failures) {
+        (Ok(job), Some(threshold)) => state
+            .db
+            .set_cron_job_alert_threshold(job.id, Some(threshold))
+            .and_then(|_| state.db.get_cron_job(job.id))
+            .map(|refreshed| refreshed.unwrap_or(job)),
+        (result, _) => result,
+    };
+
+    match created {
         Ok(job) => HttpResponse::Created().json(CronJobResponse {
             success: true,
             job: Some(job),
@@ -238,6 +276,47 @@ async fn update_job(
         }
     }
 
+    if let Some(Err(e)) = body.run_overrides.as_ref().map(|o| o.validate()) {
+        return HttpResponse::BadRequest().json(CronJobResponse {
+            success: false,
+            job: None,
+            jobs: None,
+            error: Some(e),
+        });
+    }
+
+    if matches!(body.alert_after_failures, Some(n) if n < 0) {
+        return HttpResponse::BadRequest().json(CronJobResponse {
+            success: false,
+            job: None,
+            jobs: None,
+            error: Some("alert_after_failures cannot be negative".to_string()),
+        });
+    }
+
+    if let Some(threshold) = body.alert_after_failures {
+        let threshold = if threshold == 0 { None } else { Some(threshold) };
+        if let Err(e) = state.db.set_cron_job_alert_threshold(id, threshold) {
+            return HttpResponse::InternalServerError().json(CronJobResponse {
+                success: false,
+                job: None,
+                jobs: None,
+                error: Some(format!("Failed to update job: {}", e)),
+            });
+        }
+    }
+
+    if let Some(ref overrides) = body.run_overrides
+        && let Err(e) = state.db.set_cron_job_run_overrides(id, overrides)
+    {
+        return HttpResponse::InternalServerError().json(CronJobResponse {
+            success: false,
+            job: None,
+            jobs: None,
+            error: Some(format!("Failed to update job: {}", e)),
+        });
+    }
+
     match state.db.update_cron_job(
         id,
         body.name.as_deref(),
@@ -378,6 +457,55 @@ async fn get_job_runs(
     }
 }
 
+/// Get a single run (inputs, session, usage and delivery outcome)
+async fn get_run(state: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    match state.db.get_cron_job_run(path.into_inner()) {
+        Ok(Some(run)) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "run": run
+        })),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "success": false,
+            "error": "Run not found"
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+/// Re-run a past run with the same message and settings
+async fn replay_run(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    scheduler: web::Data<Arc<Scheduler>>,
+    path: web::Path<i64>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    let run_id = path.into_inner();
+    match scheduler.replay_cron_run(run_id).await {
+        Ok(new_run_id) => {
+            let run = new_run_id.and_then(|id| state.db.get_cron_job_run(id).ok().flatten());
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "run": run
+            }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "error": e
+        })),
+    }
+}
+
 #[derive(serde::Deserialize)]
 struct LimitQuery {
     limit: Option<i32>,
@@ -527,6 +655,23 @@ async fn update_heartbeat_config(
         }
     };
 
+    if let Some(ref overrides) = body.run_overrides {
+        if let Err(e) = overrides.validate() {
+            return HttpResponse::BadRequest().json(HeartbeatConfigResponse {
+                success: false,
+                config: None,
+                error: Some(e),
+            });
+        }
+        if let Err(e) = state.db.set_heartbeat_run_overrides(config.id, overrides) {
+            return HttpResponse::InternalServerError().json(HeartbeatConfigResponse {
+                success: false,
+                config: None,
+                error: Some(format!("Failed to update config: {}", e)),
+            });
+        }
+    }
+
     match state.db.update_heartbeat_config(
         config.id,
         body.interval_minutes,
@@ -658,6 +803,23 @@ async fn update_channel_heartbeat_config(
         }
     };
 
+    if let Some(ref overrides) = body.run_overrides {
+        if let Err(e) = overrides.validate() {
+            return HttpResponse::BadRequest().json(HeartbeatConfigResponse {
+                success: false,
+                config: None,
+                error: Some(e),
+            });
+        }
+        if let Err(e) = state.db.set_heartbeat_run_overrides(config.id, overrides) {
+            return HttpResponse::InternalServerError().json(HeartbeatConfigResponse {
+                success: false,
+                config: None,
+                error: Some(format!("Failed to update config: {}", e)),
+            });
+        }
+    }
+
     match state.db.update_heartbeat_config(
         config.id,
         body.interval_minutes,
@@ -73,6 +73,15 @@ async fn dev_chat(
         session_mode: None,
         selected_network: None,
         force_safe_mode: false,
+        safe_mode_fallback: false,
+        endpoint_override: None,
+        target_session_id: None,
+        attachments: Vec::new(),
+        tool_profile: None,
+        thinking_level: None,
+        agent_subtype: None,
+        max_tool_iterations: None,
+        max_tx_value_wei: None,
     };
 
     let result = state.dispatcher.dispatch(normalized).await;
@@ -115,6 +124,7 @@ async fn dev_chat(
         message: Some(ChatMessage {
             role: "assistant".to_string(),
             content: response_text,
+            attachments: result.attachments,
         }),
         error: None,
         session_id,
@@ -111,7 +111,7 @@ pub fn config(cfg: &mut web::ServiceConfig) {
 // ── Auth helpers ────────────────────────────────────────────────────────
 
 /// Constant-time byte comparison to prevent timing attacks
-fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
     if a.len() != b.len() {
         return false;
     }
@@ -123,7 +123,7 @@ fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
 }
 
 /// Extract Bearer token from Authorization header
-fn extract_bearer_token(req: &HttpRequest) -> Option<String> {
+pub(crate) fn extract_bearer_token(req: &HttpRequest) -> Option<String> {
     req.headers()
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
@@ -322,6 +322,15 @@ async fn gateway_chat(
         session_mode: None,
         selected_network: None,
         force_safe_mode: safe_mode,
+        safe_mode_fallback: false,
+        endpoint_override: None,
+        target_session_id: None,
+        attachments: Vec::new(),
+        tool_profile: None,
+        thinking_level: None,
+        agent_subtype: None,
+        max_tool_iterations: None,
+        max_tx_value_wei: None,
     };
 
     let result = state.dispatcher.dispatch(normalized).await;
@@ -420,6 +429,15 @@ async fn gateway_chat_stream(
             session_mode: None,
             selected_network: None,
             force_safe_mode: safe_mode,
+            safe_mode_fallback: false,
+            endpoint_override: None,
+            target_session_id: None,
+            attachments: Vec::new(),
+            tool_profile: None,
+            thinking_level: None,
+            agent_subtype: None,
+            max_tool_iterations: None,
+            max_tx_value_wei: None,
         };
         let _ = dispatcher.dispatch(normalized).await;
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
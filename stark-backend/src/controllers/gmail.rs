@@ -233,6 +233,15 @@ async fn dispatch_email(
         session_mode: None,
         selected_network: None,
         force_safe_mode: false,
+        safe_mode_fallback: false,
+        endpoint_override: None,
+        target_session_id: None,
+        attachments: Vec::new(),
+        tool_profile: None,
+        thinking_level: None,
+        agent_subtype: None,
+        max_tool_iterations: None,
+        max_tx_value_wei: None,
     };
 
     // Broadcast event
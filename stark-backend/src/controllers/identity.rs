@@ -181,6 +181,8 @@ async fn link_identity(
         &body.channel_type,
         &body.platform_user_id,
         body.platform_user_name.as_deref(),
+        // The dashboard admin vouches for links they create
+        Some("admin"),
     ) {
         Ok(link) => {
             // Get all linked accounts for this identity
@@ -1,7 +1,9 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::Deserialize;
 
-use crate::db::tables::kanban::{CreateKanbanItemRequest, UpdateKanbanItemRequest};
+use crate::db::tables::kanban::{
+    validate_kanban_options, CreateKanbanItemRequest, UpdateKanbanItemRequest, KANBAN_STATUSES,
+};
 use crate::gateway::protocol::GatewayEvent;
 use crate::AppState;
 
@@ -81,7 +83,14 @@ async fn create_item(
         return resp;
     }
 
-    match data.db.create_kanban_item(&body.into_inner()) {
+    let request = body.into_inner();
+    if let Err(e) = validate_kanban_options(request.max_retries, request.timeout_seconds, request.agent_subtype.as_deref())
+        .and_then(|_| data.db.check_kanban_dependencies(None, &request.blocked_by))
+    {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": e }));
+    }
+
+    match data.db.create_kanban_item(&request) {
         Ok(item) => {
             // Broadcast event for real-time updates
             data.broadcaster.broadcast(GatewayEvent::new(
@@ -137,8 +146,24 @@ async fn update_item(
     }
 
     let item_id = path.into_inner();
+    let request = body.into_inner();
+
+    if let Some(ref status) = request.status
+        && !KANBAN_STATUSES.contains(&status.as_str())
+    {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Invalid status '{}'. Valid options: {}", status, KANBAN_STATUSES.join(", "))
+        }));
+    }
+    let mut check = validate_kanban_options(request.max_retries, request.timeout_seconds, request.agent_subtype.as_deref());
+    if let Some(ref blocked_by) = request.blocked_by {
+        check = check.and_then(|_| data.db.check_kanban_dependencies(Some(item_id), blocked_by));
+    }
+    if let Err(e) = check {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": e }));
+    }
 
-    match data.db.update_kanban_item(item_id, &body.into_inner()) {
+    match data.db.update_kanban_item(item_id, &request) {
         Ok(Some(item)) => {
             // Broadcast event for real-time updates
             data.broadcaster.broadcast(GatewayEvent::new(
@@ -1,7 +1,7 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::Deserialize;
 
-use crate::db::tables::mind_nodes::{CreateMindNodeRequest, UpdateMindNodeRequest};
+use crate::db::tables::mind_nodes::{CreateMindNodeRequest, MindEdgeType, UpdateMindNodeRequest};
 use crate::AppState;
 
 /// Validate session token from request
@@ -109,7 +109,13 @@ async fn create_node(
     }
 
     match data.db.create_mind_node(&body.into_inner()) {
-        Ok(node) => HttpResponse::Created().json(node),
+        Ok(node) => {
+            // Link the new node to similar existing ideas
+            if let Err(e) = data.db.auto_link_mind_node(node.id) {
+                log::warn!("Failed to auto-link mind node {}: {}", node.id, e);
+            }
+            HttpResponse::Created().json(node)
+        }
         Err(e) => {
             log::error!("Failed to create mind node: {}", e);
             HttpResponse::InternalServerError().json(serde_json::json!({
@@ -226,6 +232,8 @@ async fn list_connections(
 struct CreateConnectionRequest {
     parent_id: i64,
     child_id: i64,
+    #[serde(default)]
+    edge_type: MindEdgeType,
 }
 
 async fn create_connection(
@@ -237,7 +245,7 @@ async fn create_connection(
         return resp;
     }
 
-    match data.db.create_mind_node_connection(body.parent_id, body.child_id) {
+    match data.db.create_typed_mind_node_connection(body.parent_id, body.child_id, body.edge_type, None) {
         Ok(connection) => HttpResponse::Created().json(connection),
         Err(e) => {
             log::error!("Failed to create mind node connection: {}", e);
@@ -277,6 +285,85 @@ async fn delete_connection(
     }
 }
 
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    limit: Option<i32>,
+}
+
+/// Full-text search over node bodies
+async fn search_nodes(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<SearchQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    match data.db.search_mind_nodes(&query.q, limit) {
+        Ok(hits) => HttpResponse::Ok().json(hits),
+        Err(e) => {
+            log::error!("Failed to search mind nodes: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SubgraphQuery {
+    hops: Option<u32>,
+    /// Comma-separated edge types to follow (all types when omitted)
+    edge_types: Option<String>,
+}
+
+/// Export the neighborhood of a node within N hops
+async fn get_subgraph(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    query: web::Query<SubgraphQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+
+    let node_id = path.into_inner();
+    let edge_types = match query.edge_types.as_deref() {
+        None => None,
+        Some(list) => {
+            let mut types = Vec::new();
+            for name in list.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                match MindEdgeType::from_str(name) {
+                    Some(t) => types.push(t),
+                    None => {
+                        return HttpResponse::BadRequest().json(serde_json::json!({
+                            "error": format!("Unknown edge type: {}", name)
+                        }));
+                    }
+                }
+            }
+            Some(types)
+        }
+    };
+
+    match data.db.get_mind_subgraph(node_id, query.hops.unwrap_or(1), edge_types.as_deref()) {
+        Ok(Some(graph)) => HttpResponse::Ok().json(graph),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Node not found"
+        })),
+        Err(e) => {
+            log::error!("Failed to get mind subgraph: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
 /// Heartbeat session info for the sidebar
 #[derive(serde::Serialize)]
 struct HeartbeatSessionInfo {
@@ -327,9 +414,11 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("/graph/guest", web::get().to(get_graph_guest))
             .route("/nodes", web::get().to(list_nodes))
             .route("/nodes", web::post().to(create_node))
+            .route("/nodes/search", web::get().to(search_nodes))
             .route("/nodes/{id}", web::get().to(get_node))
             .route("/nodes/{id}", web::put().to(update_node))
             .route("/nodes/{id}", web::delete().to(delete_node))
+            .route("/nodes/{id}/subgraph", web::get().to(get_subgraph))
             .route("/connections", web::get().to(list_connections))
             .route("/heartbeat-sessions", web::get().to(list_heartbeat_sessions))
             .route("/connections", web::post().to(create_connection))
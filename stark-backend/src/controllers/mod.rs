@@ -1,3 +1,4 @@
+pub mod a2a;
 pub mod agent_settings;
 pub mod api_keys;
 pub mod auth;
@@ -24,6 +25,11 @@ pub mod sessions;
 pub mod skills;
 pub mod tools;
 pub mod tx_queue;
+pub mod roles;
+pub mod accounts;
+pub mod api_tokens;
+pub mod triggers;
+pub mod webhooks;
 pub mod well_known;
 pub mod system;
 pub mod telemetry;
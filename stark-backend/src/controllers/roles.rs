@@ -0,0 +1,285 @@
+//! Role controller
+//!
+//! Roles map channel users to tool permissions (a ToolProfile plus allow/deny
+//! lists) and a per-transaction spending cap. Assignments are per channel user,
+//! per identity (all linked accounts), or a channel default.
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+
+use crate::models::{AssignRoleRequest, CreateRoleRequest, UpdateRoleRequest};
+use crate::tools::{ToolGroup, ToolProfile};
+use crate::AppState;
+
+/// Configure role routes
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/roles")
+            .route("", web::get().to(list_roles))
+            .route("", web::post().to(create_role))
+            .route("/assignments", web::get().to(list_assignments))
+            .route("/assignments", web::post().to(assign_role))
+            .route("/assignments/{id}", web::delete().to(delete_assignment))
+            .route("/{name}", web::get().to(get_role))
+            .route("/{name}", web::put().to(update_role))
+            .route("/{name}", web::delete().to(delete_role)),
+    );
+}
+
+/// Validate session token from request
+fn validate_session_from_request(
+    state: &web::Data<AppState>,
+    req: &HttpRequest,
+) -> Result<(), HttpResponse> {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.trim_start_matches("Bearer ").to_string());
+
+    let token = match token {
+        Some(t) => t,
+        None => {
+            return Err(HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "No authorization token provided"
+            })));
+        }
+    };
+
+    match state.db.validate_session(&token) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
+            "error": "Invalid or expired session"
+        }))),
+        Err(e) => {
+            log::error!("Session validation error: {}", e);
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+fn bad_request(error: impl Into<String>) -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({ "error": error.into() }))
+}
+
+fn db_error(context: &str, e: rusqlite::Error) -> HttpResponse {
+    log::error!("{}: {}", context, e);
+    HttpResponse::InternalServerError().json(serde_json::json!({
+        "error": format!("Database error: {}", e)
+    }))
+}
+
+fn validate_role_fields(
+    tool_profile: Option<&str>,
+    allowed_groups: Option<&[String]>,
+    max_tx_value_wei: Option<&str>,
+) -> Result<(), HttpResponse> {
+    if let Some(profile) = tool_profile
+        && ToolProfile::from_str(profile).is_none()
+    {
+        return Err(bad_request(format!("Unknown tool profile '{}'", profile)));
+    }
+    if let Some(groups) = allowed_groups {
+        let known: Vec<&str> = ToolGroup::all().iter().map(|g| g.as_str()).collect();
+        if let Some(bad) = groups.iter().find(|g| !known.contains(&g.as_str())) {
+            return Err(bad_request(format!("Unknown tool group '{}'", bad)));
+        }
+    }
+    if let Some(cap) = max_tx_value_wei.filter(|c| !c.is_empty())
+        && cap.parse::<u128>().is_err()
+    {
+        return Err(bad_request("max_tx_value_wei must be a whole number of wei"));
+    }
+    Ok(())
+}
+
+async fn list_roles(state: web::Data<AppState>, req: HttpRequest) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    match state.db.list_roles() {
+        Ok(roles) => HttpResponse::Ok().json(serde_json::json!({ "roles": roles })),
+        Err(e) => db_error("Failed to list roles", e),
+    }
+}
+
+async fn get_role(state: web::Data<AppState>, req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    match state.db.get_role(&path.into_inner()) {
+        Ok(Some(role)) => HttpResponse::Ok().json(role),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({ "error": "Role not found" })),
+        Err(e) => db_error("Failed to get role", e),
+    }
+}
+
+async fn create_role(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CreateRoleRequest>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    let body = body.into_inner();
+    let name = body.name.trim().to_lowercase();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return bad_request("Role name must be letters, digits, '-' or '_'");
+    }
+    if let Err(resp) = validate_role_fields(
+        Some(&body.tool_profile),
+        Some(&body.allowed_groups),
+        body.max_tx_value_wei.as_deref(),
+    ) {
+        return resp;
+    }
+
+    match state.db.create_role(
+        &name,
+        body.description.as_deref(),
+        &body.tool_profile,
+        &body.allow_list,
+        &body.deny_list,
+        &body.allowed_groups,
+        body.max_tx_value_wei.as_deref().filter(|c| !c.is_empty()),
+    ) {
+        Ok(role) => {
+            log::info!("[ROLES] Created role '{}' (profile: {})", role.name, role.tool_profile);
+            HttpResponse::Created().json(role)
+        }
+        Err(e) if e.to_string().contains("UNIQUE") => HttpResponse::Conflict()
+            .json(serde_json::json!({ "error": format!("Role '{}' already exists", name) })),
+        Err(e) => db_error("Failed to create role", e),
+    }
+}
+
+async fn update_role(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Json<UpdateRoleRequest>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    let body = body.into_inner();
+    if let Err(resp) = validate_role_fields(
+        body.tool_profile.as_deref(),
+        body.allowed_groups.as_deref(),
+        body.max_tx_value_wei.as_deref(),
+    ) {
+        return resp;
+    }
+
+    match state.db.update_role(
+        &path.into_inner(),
+        body.description.as_deref(),
+        body.tool_profile.as_deref(),
+        body.allow_list.as_deref(),
+        body.deny_list.as_deref(),
+        body.allowed_groups.as_deref(),
+        body.max_tx_value_wei.as_deref(),
+    ) {
+        Ok(Some(role)) => HttpResponse::Ok().json(role),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({ "error": "Role not found" })),
+        Err(e) => db_error("Failed to update role", e),
+    }
+}
+
+async fn delete_role(state: web::Data<AppState>, req: HttpRequest, path: web::Path<String>) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    match state.db.delete_role(&path.into_inner()) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => HttpResponse::NotFound()
+            .json(serde_json::json!({ "error": "Role not found or built-in" })),
+        Err(e) => db_error("Failed to delete role", e),
+    }
+}
+
+#[derive(Deserialize)]
+struct AssignmentQuery {
+    channel_id: Option<i64>,
+}
+
+async fn list_assignments(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<AssignmentQuery>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    match state.db.list_role_assignments(query.channel_id) {
+        Ok(assignments) => HttpResponse::Ok().json(serde_json::json!({ "assignments": assignments })),
+        Err(e) => db_error("Failed to list role assignments", e),
+    }
+}
+
+async fn assign_role(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<AssignRoleRequest>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    let body = body.into_inner();
+    let user_id = body.user_id.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    let identity_id = body.identity_id.as_deref().map(str::trim).filter(|s| !s.is_empty());
+
+    // Valid subjects: channel + user, identity (optionally scoped to a channel), channel default
+    match (body.channel_id, user_id, identity_id) {
+        (_, Some(_), Some(_)) => return bad_request("Set either user_id or identity_id, not both"),
+        (None, Some(_), None) => return bad_request("user_id assignments need a channel_id"),
+        (None, None, None) => return bad_request("Set channel_id, user_id and/or identity_id"),
+        _ => {}
+    }
+
+    match state.db.get_role(&body.role) {
+        Ok(Some(_)) => {}
+        Ok(None) => return bad_request(format!("Role '{}' not found", body.role)),
+        Err(e) => return db_error("Failed to look up role", e),
+    }
+    if let Some(channel_id) = body.channel_id {
+        match state.db.get_channel(channel_id) {
+            Ok(Some(_)) => {}
+            Ok(None) => return bad_request(format!("Channel {} not found", channel_id)),
+            Err(e) => return db_error("Failed to look up channel", e),
+        }
+    }
+
+    match state.db.assign_role(&body.role, body.channel_id, user_id, identity_id) {
+        Ok(assignment) => {
+            log::info!(
+                "[ROLES] Assigned '{}' (channel={:?}, user={:?}, identity={:?})",
+                assignment.role, assignment.channel_id, assignment.user_id, assignment.identity_id
+            );
+            HttpResponse::Ok().json(assignment)
+        }
+        Err(e) => db_error("Failed to assign role", e),
+    }
+}
+
+async fn delete_assignment(state: web::Data<AppState>, req: HttpRequest, path: web::Path<i64>) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    match state.db.delete_role_assignment(path.into_inner()) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({ "error": "Assignment not found" })),
+        Err(e) => db_error("Failed to delete role assignment", e),
+    }
+}
@@ -1,12 +1,19 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::Deserialize;
 
+use crate::ai::ThinkingLevel;
+use crate::channels::NormalizedMessage;
 use crate::models::{
-    ChatSessionResponse, CompletionStatus, GetOrCreateSessionRequest, SessionScope,
+    ChatSessionResponse, CompletionStatus, ForkSessionRequest, GetOrCreateSessionRequest,
+    MessageRole, RewindSessionRequest, SessionComparison, SessionScope,
     SessionTranscriptResponse, UpdateResetPolicyRequest,
 };
+use crate::session_export;
 use crate::AppState;
 
+/// Maximum accepted size of an imported transcript
+const MAX_TRANSCRIPT_BYTES: usize = 32 * 1024 * 1024;
+
 /// Validate session token from request
 fn validate_session_from_request(
     state: &web::Data<AppState>,
@@ -517,18 +524,342 @@ async fn get_transcript(
     }
 }
 
+/// Fork a session into a new branch (optionally at a specific message)
+async fn fork_session(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<ForkSessionRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+    let session_id = path.into_inner();
+
+    match data.db.get_chat_session(session_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Session not found"
+            }));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }));
+        }
+    }
+
+    if let Some(message_id) = body.message_id {
+        match data.db.get_session_message(message_id) {
+            Ok(Some(msg)) if msg.session_id == session_id => {}
+            Ok(_) => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Message does not belong to this session"
+                }));
+            }
+            Err(e) => {
+                return HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }));
+            }
+        }
+    }
+
+    match data.db.fork_chat_session(session_id, body.message_id, body.label.as_deref()) {
+        Ok(branch) => {
+            let mut response: ChatSessionResponse = branch.into();
+            if let Ok(count) = data.db.count_session_messages(response.id) {
+                response.message_count = Some(count);
+            }
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "session": response,
+                "branch": data.db.get_session_branch(response.id).ok().flatten()
+            }))
+        }
+        Err(e) => {
+            log::error!("Failed to fork session {}: {}", session_id, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
+/// Rewind a session to a user message and retry it on a new branch,
+/// optionally with a different endpoint or thinking level
+async fn rewind_session(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<RewindSessionRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+    let session_id = path.into_inner();
+
+    let target = match data.db.get_session_message(body.message_id) {
+        Ok(Some(msg)) if msg.session_id == session_id && msg.role == MessageRole::User => msg,
+        Ok(_) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Rewind target must be a user message in this session"
+            }));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }));
+        }
+    };
+
+    let thinking_level = match body.thinking_level.as_deref() {
+        Some(level) => match ThinkingLevel::from_str(level) {
+            Some(_) => Some(level.to_string()),
+            None => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!("Invalid thinking level: {}", level)
+                }));
+            }
+        },
+        None => None,
+    };
+
+    if let Some(ref endpoint) = body.endpoint
+        && crate::ai_endpoint_config::get_ai_endpoint(endpoint).is_none()
+    {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Unknown endpoint preset: {}", endpoint)
+        }));
+    }
+
+    // Branch from the message right before the target; the target is re-sent below
+    let branch_point = match data.db.get_previous_session_message_id(session_id, target.id) {
+        Ok(prev) => prev,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }));
+        }
+    };
+    // A rewind to the very first message starts an empty branch (no message has id 0)
+    let branch = match data.db.fork_chat_session(session_id, Some(branch_point.unwrap_or(0)), body.label.as_deref()) {
+        Ok(b) => b,
+        Err(e) => {
+            log::error!("Failed to fork session {} for rewind: {}", session_id, e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }));
+        }
+    };
+
+    let user_id = target.user_id.clone().unwrap_or_else(|| branch.platform_chat_id.clone());
+
+    let normalized = NormalizedMessage {
+        channel_id: branch.channel_id,
+        channel_type: branch.channel_type.clone(),
+        chat_id: branch.platform_chat_id.clone(),
+        chat_name: None,
+        user_id: user_id.clone(),
+        user_name: target.user_name.clone().unwrap_or(user_id),
+        text: target.content.clone(),
+        message_id: None,
+        session_mode: None,
+        selected_network: None,
+        force_safe_mode: branch.safe_mode,
+        safe_mode_fallback: false,
+        target_session_id: Some(branch.id),
+        endpoint_override: body.endpoint.clone(),
+        attachments: Vec::new(),
+        tool_profile: None,
+        thinking_level,
+        agent_subtype: None,
+        max_tool_iterations: None,
+        max_tx_value_wei: None,
+    };
+
+    let result = data.dispatcher.dispatch(normalized).await;
+
+    let mut response: ChatSessionResponse = branch.into();
+    if let Ok(count) = data.db.count_session_messages(response.id) {
+        response.message_count = Some(count);
+    }
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": result.error.is_none(),
+        "session": response,
+        "response": result.response,
+        "error": result.error
+    }))
+}
+
+/// List branches forked from a session
+async fn list_branches(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+    let session_id = path.into_inner();
+
+    match data.db.list_session_branches(session_id) {
+        Ok(branches) => HttpResponse::Ok().json(serde_json::json!({
+            "session_id": session_id,
+            "parent": data.db.get_session_branch(session_id).ok().flatten(),
+            "branches": branches
+        })),
+        Err(e) => {
+            log::error!("Failed to list session branches: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
+/// Export a session as a versioned JSONL transcript
+async fn export_session(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+    let session_id = path.into_inner();
+
+    let session = match data.db.get_chat_session(session_id) {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Session not found"
+            }));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }));
+        }
+    };
+
+    let jsonl = session_export::build_transcript(&data.db, Some(data.tx_queue.as_ref()), &session)
+        .and_then(|t| t.to_jsonl());
+    match jsonl {
+        Ok(body) => HttpResponse::Ok()
+            .content_type("application/x-ndjson")
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"session-{}.jsonl\"", session_id),
+            ))
+            .body(body),
+        Err(e) => {
+            log::error!("Failed to export session {}: {}", session_id, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": e
+            }))
+        }
+    }
+}
+
+/// Import a JSONL transcript as a new session
+async fn import_session(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Bytes,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+
+    let input = match std::str::from_utf8(&body) {
+        Ok(s) => s,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Transcript must be UTF-8 JSONL"
+            }));
+        }
+    };
+
+    let transcript = match session_export::Transcript::from_jsonl(input) {
+        Ok(t) => t,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": e
+            }));
+        }
+    };
+
+    match session_export::import_transcript(&data.db, &transcript) {
+        Ok(report) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "import": report
+        })),
+        Err(e) => {
+            log::error!("Failed to import transcript: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": e
+            }))
+        }
+    }
+}
+
+/// Query for comparing two sessions
+#[derive(Deserialize)]
+struct CompareQuery {
+    left: i64,
+    right: i64,
+}
+
+/// Compare two session transcripts side by side
+async fn compare_sessions(
+    data: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<CompareQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+
+    let left = data.db.get_session_messages(query.left);
+    let right = data.db.get_session_messages(query.right);
+    match (left, right) {
+        (Ok(left), Ok(right)) => HttpResponse::Ok().json(SessionComparison::from_transcripts(
+            query.left, left, query.right, right,
+        )),
+        (Err(e), _) | (_, Err(e)) => {
+            log::error!("Failed to compare sessions: {}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Database error: {}", e)
+            }))
+        }
+    }
+}
+
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/sessions")
             .route("", web::get().to(list_sessions))
             .route("", web::post().to(get_or_create_session))
             .route("", web::delete().to(delete_all_sessions))
+            .route("/compare", web::get().to(compare_sessions))
+            .service(
+                // Transcripts can be much larger than the default 256KB payload limit
+                web::resource("/import")
+                    .app_data(web::PayloadConfig::new(MAX_TRANSCRIPT_BYTES))
+                    .route(web::post().to(import_session)),
+            )
             .route("/{id}", web::get().to(get_session))
             .route("/{id}", web::delete().to(delete_session))
             .route("/{id}/reset", web::post().to(reset_session))
             .route("/{id}/stop", web::post().to(stop_session))
             .route("/{id}/resume", web::post().to(resume_session))
             .route("/{id}/policy", web::put().to(update_reset_policy))
-            .route("/{id}/transcript", web::get().to(get_transcript)),
+            .route("/{id}/transcript", web::get().to(get_transcript))
+            .route("/{id}/fork", web::post().to(fork_session))
+            .route("/{id}/rewind", web::post().to(rewind_session))
+            .route("/{id}/branches", web::get().to(list_branches))
+            .route("/{id}/export", web::get().to(export_session)),
     );
 }
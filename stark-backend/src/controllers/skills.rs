@@ -486,6 +486,7 @@ async fn update_skill(
         tags: existing.metadata.tags.clone(),
         subagent_type: existing.metadata.subagent_type.clone(),
         requires_api_keys: existing.metadata.requires_api_keys.clone(),
+        workflow: existing.metadata.workflow.clone(),
         created_at: now.clone(),
         updated_at: now,
     };
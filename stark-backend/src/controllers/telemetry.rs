@@ -4,7 +4,7 @@ use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
 
 use crate::AppState;
-use crate::telemetry::{Resource, ResourceType};
+use crate::telemetry::{ReplayRecording, Resource, ResourceType};
 
 #[derive(Serialize)]
 struct ErrorResponse {
@@ -15,6 +15,7 @@ pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/telemetry")
             .route("/session/{id}/timeline", web::get().to(get_session_timeline))
+            .route("/session/{id}/replay", web::get().to(get_session_replay))
             .route("/rollout/{id}/summary", web::get().to(get_rollout_summary))
             .route("/rollout/{id}/triplets", web::get().to(get_rollout_triplets))
             .route("/rewards/stats", web::get().to(get_reward_stats))
@@ -37,6 +38,18 @@ async fn get_session_timeline(
     HttpResponse::Ok().json(timeline)
 }
 
+/// Recorded AI responses and tool results for a session, in replay order.
+/// Save the body as a fixture to reproduce the session offline.
+async fn get_session_replay(
+    state: web::Data<AppState>,
+    path: web::Path<i64>,
+    _req: HttpRequest,
+) -> impl Responder {
+    let session_id = path.into_inner();
+    let recording = ReplayRecording::from_spans(state.telemetry_store.get_session_spans(session_id));
+    HttpResponse::Ok().json(recording)
+}
+
 async fn get_rollout_summary(
     state: web::Data<AppState>,
     path: web::Path<String>,
@@ -0,0 +1,318 @@
+//! Scheduler condition trigger controller
+//!
+//! CRUD for triggers that run a cron job when a price crosses a threshold, a
+//! watched wallet makes a large trade, a keyword spikes, or a queued tx confirms.
+
+use actix_web::{web, HttpRequest, HttpResponse};
+
+use crate::models::{
+    validate_trigger_timing, CreateTriggerRequest, TriggerResponse, UpdateTriggerRequest,
+};
+use crate::scheduler::triggers;
+use crate::AppState;
+
+/// Default seconds between condition checks
+const DEFAULT_CHECK_INTERVAL_SECS: i64 = 60;
+
+/// Default seconds between firings
+const DEFAULT_COOLDOWN_SECS: i64 = 900;
+
+/// Configure trigger routes
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/triggers")
+            .route("", web::get().to(list_triggers))
+            .route("", web::post().to(create_trigger))
+            .route("/{id}", web::get().to(get_trigger))
+            .route("/{id}", web::put().to(update_trigger))
+            .route("/{id}", web::delete().to(delete_trigger))
+            .route("/{id}/runs", web::get().to(list_trigger_runs))
+            .route("/{id}/test", web::post().to(test_trigger)),
+    );
+}
+
+fn error_response(error: impl Into<String>) -> TriggerResponse {
+    TriggerResponse {
+        success: false,
+        trigger: None,
+        triggers: None,
+        runs: None,
+        error: Some(error.into()),
+    }
+}
+
+/// The target cron job must exist
+fn validate_job(state: &web::Data<AppState>, job_id: i64) -> Result<(), HttpResponse> {
+    match state.db.get_cron_job(job_id) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(HttpResponse::BadRequest()
+            .json(error_response(format!("Cron job {} not found", job_id)))),
+        Err(e) => {
+            log::error!("Failed to look up cron job {}: {}", job_id, e);
+            Err(HttpResponse::InternalServerError().json(error_response("Database error")))
+        }
+    }
+}
+
+async fn list_triggers(state: web::Data<AppState>, req: HttpRequest) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    match state.db.list_scheduler_triggers() {
+        Ok(triggers) => HttpResponse::Ok().json(TriggerResponse {
+            success: true,
+            trigger: None,
+            triggers: Some(triggers),
+            runs: None,
+            error: None,
+        }),
+        Err(e) => {
+            log::error!("Failed to list triggers: {}", e);
+            HttpResponse::InternalServerError().json(error_response(format!("Database error: {}", e)))
+        }
+    }
+}
+
+async fn get_trigger(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    match state.db.get_scheduler_trigger(path.into_inner()) {
+        Ok(Some(trigger)) => HttpResponse::Ok().json(TriggerResponse {
+            success: true,
+            trigger: Some(trigger),
+            triggers: None,
+            runs: None,
+            error: None,
+        }),
+        Ok(None) => HttpResponse::NotFound().json(error_response("Trigger not found")),
+        Err(e) => {
+            log::error!("Failed to get trigger: {}", e);
+            HttpResponse::InternalServerError().json(error_response(format!("Database error: {}", e)))
+        }
+    }
+}
+
+async fn create_trigger(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CreateTriggerRequest>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    let body = body.into_inner();
+    let name = body.name.trim();
+    if name.is_empty() {
+        return HttpResponse::BadRequest().json(error_response("Name is required"));
+    }
+    if let Err(e) = body.condition.validate() {
+        return HttpResponse::BadRequest().json(error_response(e));
+    }
+    if let Err(e) = validate_trigger_timing(body.check_interval_secs, body.cooldown_secs, body.confirm_checks) {
+        return HttpResponse::BadRequest().json(error_response(e));
+    }
+    if let Err(resp) = validate_job(&state, body.job_id) {
+        return resp;
+    }
+
+    match state.db.create_scheduler_trigger(
+        name,
+        body.job_id,
+        &body.condition,
+        body.check_interval_secs.unwrap_or(DEFAULT_CHECK_INTERVAL_SECS),
+        body.cooldown_secs.unwrap_or(DEFAULT_COOLDOWN_SECS),
+        body.confirm_checks.unwrap_or(1),
+        body.enabled.unwrap_or(true),
+    ) {
+        Ok(trigger) => {
+            log::info!("Created scheduler trigger '{}' ({})", trigger.name, trigger.condition.kind());
+            HttpResponse::Ok().json(TriggerResponse {
+                success: true,
+                trigger: Some(trigger),
+                triggers: None,
+                runs: None,
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("Failed to create trigger: {}", e);
+            HttpResponse::InternalServerError().json(error_response(format!("Database error: {}", e)))
+        }
+    }
+}
+
+async fn update_trigger(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<UpdateTriggerRequest>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    let body = body.into_inner();
+    if matches!(body.name.as_deref(), Some(n) if n.trim().is_empty()) {
+        return HttpResponse::BadRequest().json(error_response("Name cannot be empty"));
+    }
+    if let Some(ref condition) = body.condition
+        && let Err(e) = condition.validate()
+    {
+        return HttpResponse::BadRequest().json(error_response(e));
+    }
+    if let Err(e) = validate_trigger_timing(body.check_interval_secs, body.cooldown_secs, body.confirm_checks) {
+        return HttpResponse::BadRequest().json(error_response(e));
+    }
+    if let Some(job_id) = body.job_id
+        && let Err(resp) = validate_job(&state, job_id)
+    {
+        return resp;
+    }
+
+    match state.db.update_scheduler_trigger(
+        path.into_inner(),
+        body.name.as_deref().map(str::trim),
+        body.job_id,
+        body.condition.as_ref(),
+        body.check_interval_secs,
+        body.cooldown_secs,
+        body.confirm_checks,
+        body.enabled,
+    ) {
+        Ok(Some(trigger)) => HttpResponse::Ok().json(TriggerResponse {
+            success: true,
+            trigger: Some(trigger),
+            triggers: None,
+            runs: None,
+            error: None,
+        }),
+        Ok(None) => HttpResponse::NotFound().json(error_response("Trigger not found")),
+        Err(e) => {
+            log::error!("Failed to update trigger: {}", e);
+            HttpResponse::InternalServerError().json(error_response(format!("Database error: {}", e)))
+        }
+    }
+}
+
+async fn delete_trigger(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    match state.db.delete_scheduler_trigger(path.into_inner()) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => HttpResponse::NotFound().json(error_response("Trigger not found")),
+        Err(e) => {
+            log::error!("Failed to delete trigger: {}", e);
+            HttpResponse::InternalServerError().json(error_response(format!("Database error: {}", e)))
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct LimitQuery {
+    limit: Option<i64>,
+}
+
+/// Firing history for a trigger
+async fn list_trigger_runs(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    query: web::Query<LimitQuery>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    match state.db.list_scheduler_trigger_runs(path.into_inner(), limit) {
+        Ok(runs) => HttpResponse::Ok().json(TriggerResponse {
+            success: true,
+            trigger: None,
+            triggers: None,
+            runs: Some(runs),
+            error: None,
+        }),
+        Err(e) => {
+            log::error!("Failed to list trigger runs: {}", e);
+            HttpResponse::InternalServerError().json(error_response(format!("Database error: {}", e)))
+        }
+    }
+}
+
+/// Evaluate a trigger's condition now without firing it or changing its state
+async fn test_trigger(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    let trigger = match state.db.get_scheduler_trigger(path.into_inner()) {
+        Ok(Some(t)) => t,
+        Ok(None) => return HttpResponse::NotFound().json(error_response("Trigger not found")),
+        Err(e) => {
+            log::error!("Failed to get trigger: {}", e);
+            return HttpResponse::InternalServerError().json(error_response(format!("Database error: {}", e)));
+        }
+    };
+
+    match triggers::evaluate(&trigger.condition, &trigger.state, &state.db).await {
+        Ok(evaluation) => {
+            let (would_fire, _) = triggers::decide(&trigger, &evaluation, chrono::Utc::now());
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "met": evaluation.met,
+                "would_fire": would_fire,
+                "value": evaluation.value,
+                "detail": evaluation.detail,
+            }))
+        }
+        Err(e) => HttpResponse::Ok().json(serde_json::json!({
+            "success": false,
+            "error": e,
+        })),
+    }
+}
+
+fn validate_session_from_request(
+    state: &web::Data<AppState>,
+    req: &HttpRequest,
+) -> Result<(), HttpResponse> {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.trim_start_matches("Bearer ").to_string());
+
+    let token = match token {
+        Some(t) => t,
+        None => {
+            return Err(HttpResponse::Unauthorized().json(error_response("No authorization token provided")));
+        }
+    };
+
+    match state.db.validate_session(&token) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(HttpResponse::Unauthorized().json(error_response("Invalid or expired session"))),
+        Err(e) => {
+            log::error!("Failed to validate session: {}", e);
+            Err(HttpResponse::InternalServerError().json(error_response("Internal server error")))
+        }
+    }
+}
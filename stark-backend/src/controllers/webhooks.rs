@@ -0,0 +1,378 @@
+//! Inbound webhook controller
+//!
+//! Management endpoints (session auth) plus the public receiver
+//! `POST /api/webhooks/in/{slug}`, which is authenticated by the HMAC signature.
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde_json::Value;
+
+use crate::models::{
+    CreateWebhookRequest, UpdateWebhookRequest, WebhookKind, WebhookResponse,
+};
+use crate::tools::ToolProfile;
+use crate::AppState;
+
+/// Configure webhook routes
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/webhooks")
+            // Receiver (no session auth - signature verified)
+            .route("/in/{slug}", web::post().to(receive_webhook))
+            // Management endpoints (require auth)
+            .route("", web::get().to(list_webhooks))
+            .route("", web::post().to(create_webhook))
+            .route("/{id}", web::get().to(get_webhook))
+            .route("/{id}", web::put().to(update_webhook))
+            .route("/{id}", web::delete().to(delete_webhook)),
+    );
+}
+
+fn error_response(error: impl Into<String>) -> WebhookResponse {
+    WebhookResponse {
+        success: false,
+        webhook: None,
+        webhooks: None,
+        secret: None,
+        error: Some(error.into()),
+    }
+}
+
+fn validate_tool_profile(profile: &str) -> Result<(), HttpResponse> {
+    match ToolProfile::from_str(profile) {
+        Some(_) => Ok(()),
+        None => Err(HttpResponse::BadRequest().json(error_response(format!(
+            "Unknown tool profile '{}'",
+            profile
+        )))),
+    }
+}
+
+fn validate_slug(slug: &str) -> Result<(), HttpResponse> {
+    let valid = !slug.is_empty()
+        && slug.len() <= 64
+        && slug.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(HttpResponse::BadRequest().json(error_response(
+            "Slug must be 1-64 characters of letters, digits, '-' or '_'",
+        )))
+    }
+}
+
+/// Both delivery fields must be set together, and the channel must exist
+fn validate_delivery(
+    state: &web::Data<AppState>,
+    channel_id: Option<i64>,
+    deliver_to: Option<&str>,
+) -> Result<(), HttpResponse> {
+    match (channel_id, deliver_to) {
+        (None, None) => Ok(()),
+        (Some(id), Some(to)) if !to.trim().is_empty() => match state.db.get_channel(id) {
+            Ok(Some(_)) => Ok(()),
+            Ok(None) => Err(HttpResponse::BadRequest()
+                .json(error_response(format!("Channel {} not found", id)))),
+            Err(e) => {
+                log::error!("Failed to look up channel {}: {}", id, e);
+                Err(HttpResponse::InternalServerError().json(error_response("Database error")))
+            }
+        },
+        _ => Err(HttpResponse::BadRequest().json(error_response(
+            "deliver_channel_id and deliver_to must be set together",
+        ))),
+    }
+}
+
+async fn list_webhooks(state: web::Data<AppState>, req: HttpRequest) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    match state.db.list_webhooks() {
+        Ok(webhooks) => HttpResponse::Ok().json(WebhookResponse {
+            success: true,
+            webhook: None,
+            webhooks: Some(webhooks),
+            secret: None,
+            error: None,
+        }),
+        Err(e) => {
+            log::error!("Failed to list webhooks: {}", e);
+            HttpResponse::InternalServerError().json(error_response(format!("Database error: {}", e)))
+        }
+    }
+}
+
+async fn get_webhook(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    match state.db.get_webhook(path.into_inner()) {
+        Ok(Some(webhook)) => HttpResponse::Ok().json(WebhookResponse {
+            success: true,
+            webhook: Some(webhook),
+            webhooks: None,
+            secret: None,
+            error: None,
+        }),
+        Ok(None) => HttpResponse::NotFound().json(error_response("Webhook not found")),
+        Err(e) => {
+            log::error!("Failed to get webhook: {}", e);
+            HttpResponse::InternalServerError().json(error_response(format!("Database error: {}", e)))
+        }
+    }
+}
+
+async fn create_webhook(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CreateWebhookRequest>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    let body = body.into_inner();
+    if body.name.trim().is_empty() {
+        return HttpResponse::BadRequest().json(error_response("Name is required"));
+    }
+    let kind = match WebhookKind::from_str(&body.kind) {
+        Some(k) => k,
+        None => {
+            return HttpResponse::BadRequest()
+                .json(error_response(format!("Unknown webhook kind '{}'", body.kind)))
+        }
+    };
+    if let Err(resp) = validate_tool_profile(&body.tool_profile) {
+        return resp;
+    }
+    if let Err(resp) = validate_delivery(&state, body.deliver_channel_id, body.deliver_to.as_deref()) {
+        return resp;
+    }
+
+    let slug = match body.slug.filter(|s| !s.trim().is_empty()) {
+        Some(slug) => {
+            if let Err(resp) = validate_slug(&slug) {
+                return resp;
+            }
+            slug
+        }
+        None => crate::webhooks::generate_slug(&body.name),
+    };
+    let secret = body
+        .secret
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(crate::webhooks::generate_secret);
+    let prompt_template = body
+        .prompt_template
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| kind.default_prompt_template().to_string());
+
+    match state.db.create_webhook(
+        &slug,
+        body.name.trim(),
+        kind.as_str(),
+        &secret,
+        &prompt_template,
+        &body.tool_profile,
+        body.deliver_channel_id,
+        body.deliver_to.as_deref(),
+    ) {
+        Ok(webhook) => {
+            log::info!("[WEBHOOK] Created '{}' ({}) at /api/webhooks/in/{}", webhook.name, webhook.kind, webhook.slug);
+            HttpResponse::Created().json(WebhookResponse {
+                success: true,
+                webhook: Some(webhook),
+                webhooks: None,
+                secret: Some(secret),
+                error: None,
+            })
+        }
+        Err(e) => {
+            log::error!("Failed to create webhook: {}", e);
+            if e.to_string().contains("UNIQUE") {
+                HttpResponse::Conflict().json(error_response(format!("Slug '{}' is already in use", slug)))
+            } else {
+                HttpResponse::InternalServerError().json(error_response(format!("Database error: {}", e)))
+            }
+        }
+    }
+}
+
+async fn update_webhook(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<UpdateWebhookRequest>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    let id = path.into_inner();
+    let existing = match state.db.get_webhook(id) {
+        Ok(Some(w)) => w,
+        Ok(None) => return HttpResponse::NotFound().json(error_response("Webhook not found")),
+        Err(e) => {
+            log::error!("Failed to get webhook: {}", e);
+            return HttpResponse::InternalServerError().json(error_response(format!("Database error: {}", e)));
+        }
+    };
+
+    if let Some(ref profile) = body.tool_profile
+        && let Err(resp) = validate_tool_profile(profile)
+    {
+        return resp;
+    }
+    // Validate the delivery target as it will be after the update
+    let channel_id = body.deliver_channel_id.or(existing.deliver_channel_id);
+    let deliver_to = body.deliver_to.as_deref().or(existing.deliver_to.as_deref());
+    if let Err(resp) = validate_delivery(&state, channel_id, deliver_to) {
+        return resp;
+    }
+
+    match state.db.update_webhook(
+        id,
+        body.name.as_deref(),
+        body.secret.as_deref().filter(|s| !s.is_empty()),
+        body.prompt_template.as_deref(),
+        body.tool_profile.as_deref(),
+        body.deliver_channel_id,
+        body.deliver_to.as_deref(),
+        body.enabled,
+    ) {
+        Ok(Some(webhook)) => HttpResponse::Ok().json(WebhookResponse {
+            success: true,
+            webhook: Some(webhook),
+            webhooks: None,
+            secret: None,
+            error: None,
+        }),
+        Ok(None) => HttpResponse::NotFound().json(error_response("Webhook not found")),
+        Err(e) => {
+            log::error!("Failed to update webhook: {}", e);
+            HttpResponse::InternalServerError().json(error_response(format!("Database error: {}", e)))
+        }
+    }
+}
+
+async fn delete_webhook(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    match state.db.delete_webhook(path.into_inner()) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => HttpResponse::NotFound().json(error_response("Webhook not found")),
+        Err(e) => {
+            log::error!("Failed to delete webhook: {}", e);
+            HttpResponse::InternalServerError().json(error_response(format!("Database error: {}", e)))
+        }
+    }
+}
+
+/// Receive a signed webhook call and run the agent in the background
+async fn receive_webhook(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    body: web::Bytes,
+) -> HttpResponse {
+    let slug = path.into_inner();
+
+    // Unknown and disabled webhooks get the same response so slugs can't be probed
+    let webhook = match state.db.get_webhook_by_slug(&slug) {
+        Ok(Some(w)) if w.enabled => w,
+        Ok(_) => {
+            return HttpResponse::NotFound()
+                .json(serde_json::json!({ "success": false, "error": "Not found" }))
+        }
+        Err(e) => {
+            log::error!("[WEBHOOK] Database error: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(serde_json::json!({ "success": false, "error": "Database error" }));
+        }
+    };
+
+    let kind = webhook.kind_enum();
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|h| h.to_str().ok())
+            .map(|s| s.to_string())
+    };
+
+    if let Err(e) = crate::webhooks::verify_signature(
+        &webhook.secret,
+        &body,
+        header(kind.signature_header()).as_deref(),
+    ) {
+        log::warn!("[WEBHOOK] Rejected call to '{}': {}", webhook.name, e);
+        return HttpResponse::Unauthorized()
+            .json(serde_json::json!({ "success": false, "error": "Invalid signature" }));
+    }
+
+    let payload: Value = serde_json::from_slice(&body)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&body).into_owned()));
+    let event = kind.event_header().and_then(&header);
+
+    // GitHub sends a ping when the hook is created — acknowledge without running the agent
+    if kind == WebhookKind::Github && event.as_deref() == Some("ping") {
+        return HttpResponse::Ok().json(serde_json::json!({ "success": true, "pong": true }));
+    }
+
+    let delivery_id = header("X-GitHub-Delivery");
+    log::info!(
+        "[WEBHOOK] '{}' triggered (event: {})",
+        webhook.name,
+        event.as_deref().unwrap_or("none")
+    );
+
+    tokio::spawn(crate::webhooks::run_webhook(
+        state.db.clone(),
+        state.dispatcher.clone(),
+        state.broadcaster.clone(),
+        webhook,
+        event,
+        delivery_id,
+        payload,
+    ));
+
+    HttpResponse::Accepted().json(serde_json::json!({ "success": true }))
+}
+
+fn validate_session_from_request(
+    state: &web::Data<AppState>,
+    req: &HttpRequest,
+) -> Result<(), HttpResponse> {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.trim_start_matches("Bearer ").to_string());
+
+    let token = match token {
+        Some(t) => t,
+        None => {
+            return Err(HttpResponse::Unauthorized().json(error_response("No authorization token provided")));
+        }
+    };
+
+    match state.db.validate_session(&token) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(HttpResponse::Unauthorized().json(error_response("Invalid or expired session"))),
+        Err(e) => {
+            log::error!("Failed to validate session: {}", e);
+            Err(HttpResponse::InternalServerError().json(error_response("Internal server error")))
+        }
+    }
+}
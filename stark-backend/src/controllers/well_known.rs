@@ -24,6 +24,9 @@ async fn agent_registration(state: web::Data<AppState>) -> impl Responder {
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/.well-known")
-            .route("/agent-registration.json", web::get().to(agent_registration)),
+            .route("/agent-registration.json", web::get().to(agent_registration))
+            .route("/agent-card.json", web::get().to(super::a2a::agent_card))
+            // Older A2A peers look for the card here
+            .route("/agent.json", web::get().to(super::a2a::agent_card)),
     );
 }
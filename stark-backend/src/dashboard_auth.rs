@@ -0,0 +1,299 @@
+//! Credentials for dashboard accounts
+//!
+//! - Passwords: PBKDF2-HMAC-SHA256, stored as `pbkdf2-sha256$<rounds>$<salt hex>$<hash hex>`
+//! - TOTP: RFC 6238 (SHA-1, 30s steps, 6 digits) with base32 secrets, compatible with
+//!   common authenticator apps
+//! - API tokens: `sbk_` + 40 hex chars; only the SHA-256 is stored
+//! - Password logins: failures are counted per account and per client IP; too many
+//!   within the window lock the key out until the oldest failure ages out
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use hmac::{Hmac, Mac};
+use once_cell::sync::Lazy;
+use rand::Rng;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// Prefix that marks a bearer token as an API token rather than a login session
+pub const API_TOKEN_PREFIX: &str = "sbk_";
+
+const PBKDF2_ROUNDS: u32 = 210_000;
+const TOTP_STEP_SECS: i64 = 30;
+const TOTP_DIGITS: u32 = 6;
+/// Accept codes from one step before/after to allow for clock drift
+const TOTP_SKEW_STEPS: i64 = 1;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+/// Failed password/TOTP attempts allowed per key within the window
+const LOGIN_MAX_FAILURES: usize = 5;
+/// Window failures are counted over (and the longest a lockout lasts)
+const LOGIN_WINDOW_SECS: i64 = 15 * 60;
+/// Above this many tracked keys, stale entries are dropped on the next failure
+const LOGIN_THROTTLE_PRUNE_AT: usize = 10_000;
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    rand::thread_rng().fill(&mut bytes[..]);
+    bytes
+}
+
+/// Compare without leaking where the first difference is
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], rounds: u32) -> [u8; 32] {
+    // Single output block (dkLen == hLen), so only block index 1 is needed
+    let prf = Hmac::<Sha256>::new_from_slice(password).expect("HMAC accepts any key length");
+    let mut mac = prf.clone();
+    mac.update(salt);
+    mac.update(&1u32.to_be_bytes());
+    let mut u = [0u8; 32];
+    u.copy_from_slice(&mac.finalize().into_bytes());
+    let mut out = u;
+    for _ in 1..rounds {
+        let mut mac = prf.clone();
+        mac.update(&u);
+        u.copy_from_slice(&mac.finalize().into_bytes());
+        for (o, b) in out.iter_mut().zip(u.iter()) {
+            *o ^= b;
+        }
+    }
+    out
+}
+
+/// Hash a password for storage
+pub fn hash_password(password: &str) -> String {
+    let salt = random_bytes::<16>();
+    let hash = pbkdf2_sha256(password.as_bytes(), &salt, PBKDF2_ROUNDS);
+    format!("pbkdf2-sha256${}${}${}", PBKDF2_ROUNDS, hex::encode(salt), hex::encode(hash))
+}
+
+/// Check a password against a stored hash
+pub fn verify_password(password: &str, stored: &str) -> bool {
+    let parts: Vec<&str> = stored.split('$').collect();
+    let [scheme, rounds, salt, hash] = parts.as_slice() else {
+        return false;
+    };
+    if *scheme != "pbkdf2-sha256" {
+        return false;
+    }
+    let (Ok(rounds), Ok(salt), Ok(hash)) = (rounds.parse::<u32>(), hex::decode(salt), hex::decode(hash)) else {
+        return false;
+    };
+    constant_time_eq(&pbkdf2_sha256(password.as_bytes(), &salt, rounds), &hash)
+}
+
+/// Hash checked when the account is unknown or has no password, so the response
+/// takes as long as a real check and does not reveal which usernames exist
+static DUMMY_PASSWORD_HASH: Lazy<String> = Lazy::new(|| hash_password("dummy password for timing"));
+
+/// Check a password against an account's stored hash (if any) in constant work
+pub fn verify_password_or_dummy(password: &str, stored: Option<&str>) -> bool {
+    match stored {
+        Some(stored) => verify_password(password, stored),
+        None => {
+            verify_password(password, &DUMMY_PASSWORD_HASH);
+            false
+        }
+    }
+}
+
+/// Failed login tracker keyed by `user:<name>` and `ip:<addr>`
+#[derive(Default)]
+pub struct LoginThrottle {
+    failures: Mutex<HashMap<String, Vec<i64>>>,
+}
+
+impl LoginThrottle {
+    /// Seconds until `key` may try again, when it is locked out at `now`
+    pub fn locked_for(&self, key: &str, now: i64) -> Option<i64> {
+        let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        let times = failures.get_mut(key)?;
+        times.retain(|t| now - t < LOGIN_WINDOW_SECS);
+        if times.len() < LOGIN_MAX_FAILURES {
+            return None;
+        }
+        Some(LOGIN_WINDOW_SECS - (now - times[times.len() - LOGIN_MAX_FAILURES]))
+    }
+
+    pub fn record_failure(&self, key: &str, now: i64) {
+        let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        if failures.len() >= LOGIN_THROTTLE_PRUNE_AT {
+            failures.retain(|_, times| times.iter().any(|t| now - t < LOGIN_WINDOW_SECS));
+        }
+        failures.entry(key.to_string()).or_default().push(now);
+    }
+
+    pub fn clear(&self, key: &str) {
+        self.failures.lock().unwrap_or_else(|e| e.into_inner()).remove(key);
+    }
+}
+
+/// Process-wide password login throttle
+pub static LOGIN_THROTTLE: Lazy<LoginThrottle> = Lazy::new(LoginThrottle::default);
+
+/// Minimum password policy for dashboard accounts
+pub fn validate_password(password: &str) -> Result<(), String> {
+    if password.chars().count() < 12 {
+        return Err("Password must be at least 12 characters".to_string());
+    }
+    Ok(())
+}
+
+fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            out.push(BASE32_ALPHABET[((buffer >> (bits - 5)) & 31) as usize] as char);
+            bits -= 5;
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    out
+}
+
+fn base32_decode(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in s.chars().filter(|c| *c != '=' && !c.is_whitespace()) {
+        let value = BASE32_ALPHABET.iter().position(|&a| a as char == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            out.push((buffer >> (bits - 8)) as u8);
+            bits -= 8;
+        }
+    }
+    Some(out)
+}
+
+/// New random TOTP secret (base32, 160 bits)
+pub fn generate_totp_secret() -> String {
+    base32_encode(&random_bytes::<20>())
+}
+
+/// otpauth:// URL for authenticator apps (shown as a QR code by the dashboard)
+pub fn totp_uri(secret: &str, username: &str) -> String {
+    format!(
+        "otpauth://totp/StarkBot:{}?secret={}&issuer=StarkBot&digits={}&period={}",
+        urlencoding::encode(username),
+        secret,
+        TOTP_DIGITS,
+        TOTP_STEP_SECS
+    )
+}
+
+fn totp_at(key: &[u8], counter: u64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let code = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]])
+        & 0x7fff_ffff;
+    code % 10u32.pow(TOTP_DIGITS)
+}
+
+/// Check a 6-digit code against a base32 secret at the given unix time
+pub fn verify_totp_at(secret: &str, code: &str, unix_time: i64) -> bool {
+    let code = code.trim().replace(' ', "");
+    let (Some(key), Ok(code)) = (base32_decode(secret), code.parse::<u32>()) else {
+        return false;
+    };
+    let step = unix_time / TOTP_STEP_SECS;
+    (-TOTP_SKEW_STEPS..=TOTP_SKEW_STEPS).any(|skew| {
+        let counter = step + skew;
+        counter >= 0 && totp_at(&key, counter as u64) == code
+    })
+}
+
+/// Check a 6-digit code against a base32 secret now
+pub fn verify_totp(secret: &str, code: &str) -> bool {
+    verify_totp_at(secret, code, chrono::Utc::now().timestamp())
+}
+
+/// New API token (shown once) and the hash to store
+pub fn generate_api_token() -> (String, String) {
+    let token = format!("{}{}", API_TOKEN_PREFIX, hex::encode(random_bytes::<20>()));
+    let hash = hash_api_token(&token);
+    (token, hash)
+}
+
+pub fn hash_api_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_roundtrip() {
+        let stored = hash_password("correct horse battery");
+        assert!(verify_password("correct horse battery", &stored));
+        assert!(!verify_password("wrong horse battery", &stored));
+        assert!(!verify_password("anything", "garbage"));
+    }
+
+    #[test]
+    fn test_login_throttle_locks_after_repeated_failures() {
+        let throttle = LoginThrottle::default();
+        let now = 1_000_000;
+        for i in 0..LOGIN_MAX_FAILURES as i64 {
+            assert!(throttle.locked_for("user:alice", now + i).is_none());
+            throttle.record_failure("user:alice", now + i);
+        }
+        let wait = throttle.locked_for("user:alice", now + 10).unwrap();
+        assert_eq!(wait, LOGIN_WINDOW_SECS - 10);
+        assert!(throttle.locked_for("user:bob", now + 10).is_none());
+
+        // Unlocks once the oldest failure leaves the window
+        assert!(throttle.locked_for("user:alice", now + LOGIN_WINDOW_SECS).is_none());
+
+        throttle.record_failure("user:alice", now + LOGIN_WINDOW_SECS);
+        throttle.clear("user:alice");
+        assert!(throttle.locked_for("user:alice", now + LOGIN_WINDOW_SECS).is_none());
+    }
+
+    #[test]
+    fn test_verify_password_or_dummy() {
+        let stored = hash_password("correct horse battery");
+        assert!(verify_password_or_dummy("correct horse battery", Some(&stored)));
+        assert!(!verify_password_or_dummy("correct horse battery", None));
+    }
+
+    #[test]
+    fn test_pbkdf2_known_vector() {
+        // RFC 7914 section 11 (PBKDF2-HMAC-SHA256, c=1, first 32 bytes)
+        assert_eq!(
+            hex::encode(pbkdf2_sha256(b"passwd", b"salt", 1)),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc"
+        );
+    }
+
+    #[test]
+    fn test_totp_rfc6238_vector() {
+        // RFC 6238 appendix B, SHA-1 seed "12345678901234567890", T=59 -> 94287082 (8 digits)
+        let secret = base32_encode(b"12345678901234567890");
+        assert_eq!(secret, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert!(verify_totp_at(&secret, "287082", 59));
+        assert!(!verify_totp_at(&secret, "287083", 59));
+        assert_eq!(base32_decode(&secret).unwrap(), b"12345678901234567890");
+    }
+
+    #[test]
+    fn test_api_token_format() {
+        let (token, hash) = generate_api_token();
+        assert!(token.starts_with(API_TOKEN_PREFIX));
+        assert_eq!(token.len(), API_TOKEN_PREFIX.len() + 40);
+        assert_eq!(hash_api_token(&token), hash);
+    }
+}
@@ -119,6 +119,42 @@ impl Database {
             [],
         )?;
 
+        // Dashboard account a session belongs to (NULL = legacy single-admin login)
+        let _ = conn.execute("ALTER TABLE auth_sessions ADD COLUMN user_id INTEGER", []);
+
+        // Dashboard operator accounts (wallet and/or password + TOTP login)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dashboard_users (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                username TEXT UNIQUE NOT NULL COLLATE NOCASE,
+                role TEXT NOT NULL DEFAULT 'auditor',
+                wallet_address TEXT UNIQUE,
+                password_hash TEXT,
+                totp_secret TEXT,
+                totp_enabled INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                last_login_at TEXT
+            )",
+            [],
+        )?;
+
+        // Long-lived scoped API tokens for automation (only the hash is stored)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS api_tokens (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                token_hash TEXT UNIQUE NOT NULL,
+                token_prefix TEXT NOT NULL,
+                scopes TEXT NOT NULL DEFAULT '[]',
+                user_id INTEGER,
+                created_at TEXT NOT NULL,
+                expires_at TEXT,
+                last_used_at TEXT,
+                revoked INTEGER NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+
         // External API keys table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS external_api_keys (
@@ -256,6 +292,9 @@ impl Database {
             conn.execute("ALTER TABLE agent_settings ADD COLUMN secret_key TEXT", [])?;
         }
 
+        // Migration: Add supports_vision override to agent_settings (NULL = decide from endpoint)
+        let _ = conn.execute("ALTER TABLE agent_settings ADD COLUMN supports_vision INTEGER", []);
+
         // Migration: Add web3_tx_requires_confirmation column to bot_settings if it doesn't exist
         let has_web3_tx_confirmation: bool = conn
             .query_row(
@@ -425,6 +464,9 @@ impl Database {
             conn.execute("ALTER TABLE bot_settings ADD COLUMN kanban_auto_execute INTEGER NOT NULL DEFAULT 1", [])?;
         }
 
+        // Migration: Add kanban_max_concurrent column to bot_settings
+        let _ = conn.execute("ALTER TABLE bot_settings ADD COLUMN kanban_max_concurrent INTEGER NOT NULL DEFAULT 2", []);
+
         // Initialize bot_settings with defaults if empty
         let bot_settings_count: i64 = conn
             .query_row("SELECT COUNT(*) FROM bot_settings", [], |row| row.get(0))
@@ -496,6 +538,49 @@ impl Database {
             [],
         )?;
 
+        // Session branches - fork/rewind lineage between chat sessions
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_branches (
+                session_id INTEGER PRIMARY KEY,
+                parent_session_id INTEGER NOT NULL,
+                branch_point_message_id INTEGER,
+                label TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (session_id) REFERENCES chat_sessions(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_session_branches_parent ON session_branches(parent_session_id)",
+            [],
+        )?;
+
+        // Session tool state - register and context bank snapshots (carried into forks)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_tool_state (
+                session_id INTEGER PRIMARY KEY,
+                registers_json TEXT NOT NULL DEFAULT '{}',
+                context_bank_json TEXT NOT NULL DEFAULT '[]',
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (session_id) REFERENCES chat_sessions(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Session imports - provenance for sessions recreated from a transcript export
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_imports (
+                session_id INTEGER PRIMARY KEY,
+                format_version INTEGER NOT NULL,
+                source_session_id INTEGER,
+                source_channel_type TEXT,
+                archive_json TEXT NOT NULL DEFAULT '{}',
+                imported_at TEXT NOT NULL,
+                FOREIGN KEY (session_id) REFERENCES chat_sessions(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
         // Telegram chat messages - passive log of ALL messages in Telegram chats
         // Independent of session system, used by telegram_read readHistory
         conn.execute(
@@ -533,6 +618,34 @@ impl Database {
             )",
             [],
         )?;
+        // Migration: record how a link was verified ('admin', 'code', 'wallet')
+        let _ = conn.execute("ALTER TABLE identity_links ADD COLUMN verification_method TEXT", []);
+        // Links created by an admin before verification existed were vouched for by the admin
+        let _ = conn.execute(
+            "UPDATE identity_links SET is_verified = 1, verification_method = 'admin', verified_at = updated_at
+             WHERE is_verified = 0 AND identity_id IN (
+                 SELECT identity_id FROM identity_links GROUP BY identity_id HAVING COUNT(*) > 1
+             )",
+            [],
+        );
+
+        // Pending self-service identity verifications (one-time codes, wallet challenges)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS identity_verifications (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                channel_type TEXT NOT NULL,
+                platform_user_id TEXT NOT NULL,
+                target_channel_type TEXT NOT NULL,
+                target_user_id TEXT NOT NULL,
+                secret TEXT NOT NULL,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                expires_at TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                UNIQUE(kind, channel_type, platform_user_id)
+            )",
+            [],
+        )?;
 
         // Memories table - daily logs, long-term memories, preferences, facts, entities, tasks
         conn.execute(
@@ -715,6 +828,9 @@ impl Database {
         // Migration: Add requires_api_keys column to skills if it doesn't exist
         let _ = conn.execute("ALTER TABLE skills ADD COLUMN requires_api_keys TEXT NOT NULL DEFAULT '{}'", []);
 
+        // Migration: Add workflow column to skills if it doesn't exist
+        let _ = conn.execute("ALTER TABLE skills ADD COLUMN workflow TEXT", []);
+
         // Skill scripts table (Python/Bash scripts bundled with skills)
         conn.execute(
             "CREATE TABLE IF NOT EXISTS skill_scripts (
@@ -802,6 +918,18 @@ impl Database {
             [],
         )?;
 
+        // Result delivery outcome per run: delivered, failed or skipped (NULL = not configured)
+        let _ = conn.execute("ALTER TABLE cron_job_runs ADD COLUMN delivery_status TEXT", []);
+        let _ = conn.execute("ALTER TABLE cron_job_runs ADD COLUMN delivery_error TEXT", []);
+
+        // Per-run session, usage, x402 spend, and the inputs needed to replay it
+        let _ = conn.execute("ALTER TABLE cron_job_runs ADD COLUMN session_id INTEGER", []);
+        let _ = conn.execute("ALTER TABLE cron_job_runs ADD COLUMN tool_calls INTEGER", []);
+        let _ = conn.execute("ALTER TABLE cron_job_runs ADD COLUMN tokens_used INTEGER", []);
+        let _ = conn.execute("ALTER TABLE cron_job_runs ADD COLUMN x402_spend_usdc REAL", []);
+        let _ = conn.execute("ALTER TABLE cron_job_runs ADD COLUMN input TEXT", []);
+        let _ = conn.execute("ALTER TABLE cron_job_runs ADD COLUMN replay_of INTEGER", []);
+
         // Index for job runs lookup
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_cron_job_runs_job ON cron_job_runs(job_id, started_at DESC)",
@@ -841,6 +969,14 @@ impl Database {
             [],
         );
 
+        // Migration: per-run agent settings (endpoint, thinking, tools, caps) as JSON
+        let _ = conn.execute("ALTER TABLE cron_jobs ADD COLUMN run_overrides TEXT", []);
+        let _ = conn.execute("ALTER TABLE heartbeat_configs ADD COLUMN run_overrides TEXT", []);
+
+        // Migration: failure streak and alert threshold per cron job
+        let _ = conn.execute("ALTER TABLE cron_jobs ADD COLUMN consecutive_failures INTEGER NOT NULL DEFAULT 0", []);
+        let _ = conn.execute("ALTER TABLE cron_jobs ADD COLUMN alert_after_failures INTEGER", []);
+
         // Gmail integration configuration
         conn.execute(
             "CREATE TABLE IF NOT EXISTS gmail_configs (
@@ -863,6 +999,114 @@ impl Database {
             [],
         )?;
 
+        // Inbound webhook triggers (GitHub, Alchemy notify, generic JSON)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS webhooks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                slug TEXT UNIQUE NOT NULL,
+                name TEXT NOT NULL,
+                kind TEXT NOT NULL DEFAULT 'generic',
+                secret TEXT NOT NULL,
+                prompt_template TEXT NOT NULL,
+                tool_profile TEXT NOT NULL DEFAULT 'safe_mode',
+                deliver_channel_id INTEGER,
+                deliver_to TEXT,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                trigger_count INTEGER NOT NULL DEFAULT 0,
+                last_triggered_at TEXT,
+                last_error TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Scheduler condition triggers: run a cron job when a condition fires
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS scheduler_triggers (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                job_id INTEGER NOT NULL,
+                condition TEXT NOT NULL,
+                check_interval_secs INTEGER NOT NULL DEFAULT 60,
+                cooldown_secs INTEGER NOT NULL DEFAULT 900,
+                confirm_checks INTEGER NOT NULL DEFAULT 1,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                state TEXT,
+                last_checked_at TEXT,
+                last_fired_at TEXT,
+                fire_count INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS scheduler_trigger_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                trigger_id INTEGER NOT NULL,
+                fired_at TEXT NOT NULL,
+                detail TEXT NOT NULL,
+                value REAL,
+                cron_run_id INTEGER,
+                success INTEGER,
+                error TEXT
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_scheduler_trigger_runs_trigger ON scheduler_trigger_runs(trigger_id, fired_at)",
+            [],
+        )?;
+
+        // Roles: named tool permission sets for channel users
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS roles (
+                name TEXT PRIMARY KEY,
+                description TEXT,
+                tool_profile TEXT NOT NULL,
+                allow_list TEXT NOT NULL DEFAULT '[]',
+                deny_list TEXT NOT NULL DEFAULT '[]',
+                allowed_groups TEXT NOT NULL DEFAULT '[]',
+                max_tx_value_wei TEXT,
+                builtin INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        let now = chrono::Utc::now().to_rfc3339();
+        for (name, description, profile) in crate::models::role::BUILTIN_ROLES {
+            conn.execute(
+                "INSERT OR IGNORE INTO roles (name, description, tool_profile, builtin, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, 1, ?4, ?4)",
+                rusqlite::params![name, description, profile, &now],
+            )?;
+        }
+
+        // Role assignments: per channel user, per identity, or channel default
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS role_assignments (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                role TEXT NOT NULL,
+                channel_id INTEGER,
+                user_id TEXT,
+                identity_id TEXT,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_role_assignments_subject
+             ON role_assignments(IFNULL(channel_id, 0), IFNULL(user_id, ''), IFNULL(identity_id, ''))",
+            [],
+        )?;
+
         // =====================================================
         // EIP-8004 Tables (Trustless Agents)
         // =====================================================
@@ -1164,6 +1408,19 @@ impl Database {
             [],
         )?;
 
+        // Migration: fan-out groups, per-child caps and usage for sub_agents
+        let _ = conn.execute("ALTER TABLE sub_agents ADD COLUMN group_id TEXT", []);
+        let _ = conn.execute("ALTER TABLE sub_agents ADD COLUMN cancel_siblings_on_failure INTEGER NOT NULL DEFAULT 0", []);
+        let _ = conn.execute("ALTER TABLE sub_agents ADD COLUMN max_iterations INTEGER", []);
+        let _ = conn.execute("ALTER TABLE sub_agents ADD COLUMN max_cost_usdc REAL", []);
+        let _ = conn.execute("ALTER TABLE sub_agents ADD COLUMN iterations INTEGER NOT NULL DEFAULT 0", []);
+        let _ = conn.execute("ALTER TABLE sub_agents ADD COLUMN cost_usdc REAL NOT NULL DEFAULT 0", []);
+        let _ = conn.execute("ALTER TABLE sub_agents ADD COLUMN read_only INTEGER NOT NULL DEFAULT 0", []);
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_sub_agents_group ON sub_agents(group_id)",
+            [],
+        )?;
+
         // Migration: Add subtype column to agent_contexts if it doesn't exist
         let _ = conn.execute(
             "ALTER TABLE agent_contexts ADD COLUMN subtype TEXT NOT NULL DEFAULT 'finance'",
@@ -1203,6 +1460,17 @@ impl Database {
             [],
         )?;
 
+        // Queued transactions awaiting approval, so they survive a restart. A row is
+        // removed as soon as the transaction leaves Pending (broadcast, expired or denied).
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS queued_transactions (
+                uuid TEXT PRIMARY KEY,
+                tx_json TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
         // Channel settings table - per-channel configuration
         conn.execute(
             "CREATE TABLE IF NOT EXISTS channel_settings (
@@ -1261,6 +1529,62 @@ impl Database {
             [],
         )?;
 
+        // Migration: typed mind map edges (existing connections are structural)
+        let _ = conn.execute(
+            "ALTER TABLE mind_node_connections ADD COLUMN edge_type TEXT NOT NULL DEFAULT 'part_of'",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE mind_node_connections ADD COLUMN weight REAL",
+            [],
+        );
+
+        // Full-text index over mind node bodies, kept in sync by triggers
+        conn.execute(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS mind_nodes_fts USING fts5(
+                body,
+                content='mind_nodes',
+                content_rowid='id',
+                tokenize='porter'
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TRIGGER IF NOT EXISTS mind_nodes_fts_insert AFTER INSERT ON mind_nodes BEGIN
+                INSERT INTO mind_nodes_fts(rowid, body) VALUES (new.id, new.body);
+            END",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TRIGGER IF NOT EXISTS mind_nodes_fts_delete AFTER DELETE ON mind_nodes BEGIN
+                INSERT INTO mind_nodes_fts(mind_nodes_fts, rowid, body) VALUES ('delete', old.id, old.body);
+            END",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TRIGGER IF NOT EXISTS mind_nodes_fts_update AFTER UPDATE OF body ON mind_nodes BEGIN
+                INSERT INTO mind_nodes_fts(mind_nodes_fts, rowid, body) VALUES ('delete', old.id, old.body);
+                INSERT INTO mind_nodes_fts(rowid, body) VALUES (new.id, new.body);
+            END",
+            [],
+        )?;
+
+        // Index nodes created before the FTS table existed
+        conn.execute("INSERT INTO mind_nodes_fts(mind_nodes_fts) VALUES ('rebuild')", [])?;
+
+        // Heartbeat visit history per mind node (for branch selection)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS mind_node_visits (
+                node_id INTEGER PRIMARY KEY,
+                visit_count INTEGER NOT NULL DEFAULT 0,
+                last_visited_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
         // Kanban board items table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS kanban_items (
@@ -1282,6 +1606,14 @@ impl Database {
             [],
         )?;
 
+        // Migration: kanban dependencies, retry policy, per-task timeout and subtype
+        let _ = conn.execute("ALTER TABLE kanban_items ADD COLUMN blocked_by TEXT", []);
+        let _ = conn.execute("ALTER TABLE kanban_items ADD COLUMN max_retries INTEGER NOT NULL DEFAULT 0", []);
+        let _ = conn.execute("ALTER TABLE kanban_items ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0", []);
+        let _ = conn.execute("ALTER TABLE kanban_items ADD COLUMN next_attempt_at TEXT", []);
+        let _ = conn.execute("ALTER TABLE kanban_items ADD COLUMN timeout_seconds INTEGER", []);
+        let _ = conn.execute("ALTER TABLE kanban_items ADD COLUMN agent_subtype TEXT", []);
+
         // NOTE: discord_user_profiles table is now owned by the discord_tipping module.
         // It gets created when the module is installed (init_tables).
 
@@ -1310,6 +1642,29 @@ impl Database {
             [],
         )?;
 
+        // Email channel messages - dedupe by Message-ID and map messages to their thread root.
+        // Both received and sent messages are stored so replies to the bot resolve to the same thread.
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS email_messages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel_id INTEGER NOT NULL,
+                message_id TEXT NOT NULL,
+                thread_id TEXT NOT NULL,
+                direction TEXT NOT NULL,
+                sender TEXT NOT NULL,
+                subject TEXT NOT NULL DEFAULT '',
+                created_at TEXT NOT NULL,
+                UNIQUE(channel_id, message_id),
+                FOREIGN KEY (channel_id) REFERENCES external_channels(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_email_messages_thread ON email_messages(channel_id, thread_id)",
+            [],
+        )?;
+
         // Installed modules - plugin system registry
         conn.execute(
             "CREATE TABLE IF NOT EXISTS installed_modules (
@@ -1499,6 +1854,69 @@ impl Database {
             [],
         );
 
+        // A2A tasks - served to peers (inbound) or delegated to peers (outbound)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS a2a_tasks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                task_id TEXT NOT NULL,
+                direction TEXT NOT NULL,
+                context_id TEXT NOT NULL,
+                peer_url TEXT,
+                peer_agent_id INTEGER,
+                caller TEXT,
+                channel_id INTEGER,
+                session_id INTEGER,
+                message TEXT NOT NULL,
+                state TEXT NOT NULL DEFAULT 'submitted',
+                result TEXT,
+                error TEXT,
+                payment_amount TEXT,
+                payment_header TEXT,
+                payment_nonce TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE(direction, task_id)
+            )",
+            [],
+        )?;
+
+        let _ = conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_a2a_tasks_context ON a2a_tasks(direction, context_id)",
+            [],
+        );
+        let _ = conn.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_a2a_tasks_payment_nonce ON a2a_tasks(payment_nonce)
+             WHERE payment_nonce IS NOT NULL",
+            [],
+        );
+
+        // Workflow runs - declarative skill workflows, checkpointed after every step
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS workflow_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                run_id TEXT UNIQUE NOT NULL,
+                session_id INTEGER NOT NULL,
+                channel_id INTEGER NOT NULL,
+                skill_name TEXT NOT NULL,
+                definition TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'running',
+                current_step INTEGER NOT NULL DEFAULT 0,
+                variables TEXT NOT NULL DEFAULT '{}',
+                registers TEXT NOT NULL DEFAULT '{}',
+                step_log TEXT NOT NULL DEFAULT '[]',
+                error TEXT,
+                paused_at TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        let _ = conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_workflow_runs_session ON workflow_runs(session_id, status)",
+            [],
+        );
+
         Ok(())
     }
 
@@ -1687,6 +2105,57 @@ impl Database {
         Ok(())
     }
 
+    /// List x402 payments made during a session.
+    ///
+    /// Most payments are recorded with only a channel_id, so payments on the
+    /// session's channel inside its active window are included as well.
+    pub fn list_x402_payments_for_session(
+        &self,
+        session_id: i64,
+        channel_id: i64,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<crate::eip8004::types::X402PaymentRecord>, rusqlite::Error> {
+        let conn = self.conn();
+        // created_at uses SQLite's datetime('now') format
+        let from_str = from.format("%Y-%m-%d %H:%M:%S").to_string();
+        let to_str = to.format("%Y-%m-%d %H:%M:%S").to_string();
+        let mut stmt = conn.prepare(
+            "SELECT id, channel_id, session_id, execution_id, tool_name, resource, amount, amount_formatted,
+                    asset, pay_to, from_address, tx_hash, block_number, feedback_submitted, feedback_id, created_at
+             FROM x402_payments
+             WHERE session_id = ?1
+                OR (session_id IS NULL AND channel_id = ?2 AND created_at BETWEEN ?3 AND ?4)
+             ORDER BY created_at ASC, id ASC",
+        )?;
+
+        let payments = stmt
+            .query_map(rusqlite::params![session_id, channel_id, from_str, to_str], |row| {
+                Ok(crate::eip8004::types::X402PaymentRecord {
+                    id: row.get(0)?,
+                    channel_id: row.get(1)?,
+                    session_id: row.get(2)?,
+                    execution_id: row.get(3)?,
+                    tool_name: row.get(4)?,
+                    resource: row.get(5)?,
+                    amount: row.get(6)?,
+                    amount_formatted: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+                    asset: row.get(8)?,
+                    pay_to: row.get(9)?,
+                    from_address: row.get(10)?,
+                    tx_hash: row.get(11)?,
+                    block_number: row.get(12)?,
+                    feedback_submitted: row.get::<_, i64>(13)? != 0,
+                    feedback_id: row.get(14)?,
+                    created_at: row.get(15)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(payments)
+    }
+
     // =====================================================
     // Keystore State Operations
     // =====================================================
@@ -0,0 +1,203 @@
+//! A2A task database operations (agent-to-agent protocol, both directions)
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+
+use crate::models::{A2aDirection, A2aTask, A2aTaskState};
+use super::super::Database;
+
+const A2A_TASK_COLUMNS: &str = "id, task_id, direction, context_id, peer_url, peer_agent_id, caller,
+    channel_id, session_id, message, state, result, error, payment_amount, payment_header,
+    payment_nonce, created_at, updated_at";
+
+impl Database {
+    /// Record a new A2A task
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_a2a_task(
+        &self,
+        task_id: &str,
+        direction: A2aDirection,
+        context_id: &str,
+        peer_url: Option<&str>,
+        peer_agent_id: Option<i64>,
+        caller: Option<&str>,
+        channel_id: Option<i64>,
+        session_id: Option<i64>,
+        message: &str,
+        state: A2aTaskState,
+    ) -> SqliteResult<A2aTask> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO a2a_tasks (task_id, direction, context_id, peer_url, peer_agent_id, caller,
+                                    channel_id, session_id, message, state, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?11)",
+            rusqlite::params![
+                task_id, direction.as_str(), context_id, peer_url, peer_agent_id, caller,
+                channel_id, session_id, message, state.as_str(), &now
+            ],
+        )?;
+
+        drop(conn);
+        self.get_a2a_task(direction, task_id).map(|opt| opt.unwrap())
+    }
+
+    /// Get a task by its A2A task ID
+    pub fn get_a2a_task(&self, direction: A2aDirection, task_id: &str) -> SqliteResult<Option<A2aTask>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM a2a_tasks WHERE direction = ?1 AND task_id = ?2",
+            A2A_TASK_COLUMNS
+        ))?;
+        Ok(stmt
+            .query_row(rusqlite::params![direction.as_str(), task_id], Self::map_a2a_task_row)
+            .ok())
+    }
+
+    /// List tasks, newest first
+    pub fn list_a2a_tasks(&self, direction: Option<A2aDirection>, limit: usize) -> SqliteResult<Vec<A2aTask>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM a2a_tasks WHERE (?1 IS NULL OR direction = ?1)
+             ORDER BY created_at DESC, id DESC LIMIT ?2",
+            A2A_TASK_COLUMNS
+        ))?;
+
+        let tasks = stmt
+            .query_map(rusqlite::params![direction.map(|d| d.as_str()), limit as i64], |row| {
+                Self::map_a2a_task_row(row)
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(tasks)
+    }
+
+    /// Most recent task of a conversation (used to check who owns an inbound contextId)
+    pub fn get_latest_a2a_task_in_context(
+        &self,
+        direction: A2aDirection,
+        context_id: &str,
+    ) -> SqliteResult<Option<A2aTask>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM a2a_tasks WHERE direction = ?1 AND context_id = ?2
+             ORDER BY created_at DESC, id DESC LIMIT 1",
+            A2A_TASK_COLUMNS
+        ))?;
+        Ok(stmt
+            .query_row(rusqlite::params![direction.as_str(), context_id], Self::map_a2a_task_row)
+            .ok())
+    }
+
+    /// Move a task to a new state. Terminal states are final, so a task that was
+    /// canceled while running keeps its canceled state. Returns whether the row changed.
+    pub fn update_a2a_task_state(
+        &self,
+        direction: A2aDirection,
+        task_id: &str,
+        state: A2aTaskState,
+        result: Option<&str>,
+        error: Option<&str>,
+    ) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+
+        let rows = conn.execute(
+            "UPDATE a2a_tasks SET state = ?1, result = COALESCE(?2, result), error = COALESCE(?3, error),
+                                  updated_at = ?4
+             WHERE direction = ?5 AND task_id = ?6
+               AND state NOT IN ('completed', 'canceled', 'failed', 'rejected')",
+            rusqlite::params![state.as_str(), result, error, &now, direction.as_str(), task_id],
+        )?;
+
+        Ok(rows > 0)
+    }
+
+    /// Attach x402 payment details to a task
+    pub fn set_a2a_task_payment(
+        &self,
+        direction: A2aDirection,
+        task_id: &str,
+        amount: &str,
+        header: Option<&str>,
+        nonce: Option<&str>,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+
+        conn.execute(
+            "UPDATE a2a_tasks SET payment_amount = ?1, payment_header = ?2, payment_nonce = ?3
+             WHERE direction = ?4 AND task_id = ?5",
+            rusqlite::params![amount, header, nonce, direction.as_str(), task_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Whether an inbound x402 authorization nonce has already paid for a task
+    pub fn is_a2a_payment_nonce_used(&self, nonce: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM a2a_tasks WHERE payment_nonce = ?1",
+            [nonce],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    fn map_a2a_task_row(row: &rusqlite::Row) -> rusqlite::Result<A2aTask> {
+        Ok(A2aTask {
+            id: row.get(0)?,
+            task_id: row.get(1)?,
+            direction: row.get(2)?,
+            context_id: row.get(3)?,
+            peer_url: row.get(4)?,
+            peer_agent_id: row.get(5)?,
+            caller: row.get(6)?,
+            channel_id: row.get(7)?,
+            session_id: row.get(8)?,
+            message: row.get(9)?,
+            state: row.get(10)?,
+            result: row.get(11)?,
+            error: row.get(12)?,
+            payment_amount: row.get(13)?,
+            payment_header: row.get(14)?,
+            payment_nonce: row.get(15)?,
+            created_at: row.get(16)?,
+            updated_at: row.get(17)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::Database;
+    use crate::models::{A2aDirection, A2aTaskState};
+
+    #[test]
+    fn test_terminal_state_is_final() {
+        let db = Database::new(":memory:").unwrap();
+        let task = db
+            .create_a2a_task("t-1", A2aDirection::Inbound, "ctx-1", None, None, Some("public"), Some(1), None, "hi", A2aTaskState::Working)
+            .unwrap();
+        assert_eq!(task.state_enum(), A2aTaskState::Working);
+
+        assert!(db.update_a2a_task_state(A2aDirection::Inbound, "t-1", A2aTaskState::Canceled, None, None).unwrap());
+        // The run finishing afterwards must not resurrect the task
+        assert!(!db.update_a2a_task_state(A2aDirection::Inbound, "t-1", A2aTaskState::Completed, Some("done"), None).unwrap());
+
+        let task = db.get_a2a_task(A2aDirection::Inbound, "t-1").unwrap().unwrap();
+        assert_eq!(task.state_enum(), A2aTaskState::Canceled);
+        assert!(task.result.is_none());
+        assert!(db.get_a2a_task(A2aDirection::Outbound, "t-1").unwrap().is_none());
+
+        db.set_a2a_task_payment(A2aDirection::Inbound, "t-1", "10000", Some("eyJ4"), Some("0xabc")).unwrap();
+        assert!(db.is_a2a_payment_nonce_used("0xabc").unwrap());
+        assert!(!db.is_a2a_payment_nonce_used("0xdef").unwrap());
+        assert_eq!(
+            db.get_latest_a2a_task_in_context(A2aDirection::Inbound, "ctx-1").unwrap().unwrap().caller.as_deref(),
+            Some("public")
+        );
+    }
+}
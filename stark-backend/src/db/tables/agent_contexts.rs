@@ -85,6 +85,8 @@ impl Database {
             .unwrap_or_else(|_| "[]".to_string());
         let active_skill_json: Option<String> = context.active_skill.as_ref()
             .and_then(|s| serde_json::to_string(s).ok());
+        let tasks_json = serde_json::to_string(&context.task_queue)
+            .unwrap_or_else(|_| "{\"tasks\":[]}".to_string());
 
         // Use INSERT OR REPLACE for upsert behavior
         // Note: Using simplified schema - old columns will be NULL/defaults
@@ -96,7 +98,7 @@ impl Database {
                 created_at, updated_at
             ) VALUES (
                 ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9,
-                0, 0, '[]', NULL, '{\"tasks\":[]}',
+                0, 0, '[]', NULL, ?11,
                 COALESCE((SELECT created_at FROM agent_contexts WHERE session_id = ?1), ?10),
                 ?10
             )",
@@ -111,12 +113,32 @@ impl Database {
                 context.subtype.as_str(),
                 active_skill_json,
                 now,
+                tasks_json,
             ],
         )?;
 
         Ok(())
     }
 
+    /// Get the last saved task queue for a session.
+    ///
+    /// `get_agent_context` resets the queue on load; this reads the persisted
+    /// copy for inspection and export.
+    pub fn get_agent_task_queue(&self, session_id: i64) -> SqliteResult<Option<TaskQueue>> {
+        let conn = self.conn();
+        let result = conn.query_row(
+            "SELECT tasks_json FROM agent_contexts WHERE session_id = ?",
+            params![session_id],
+            |row| row.get::<_, String>(0),
+        );
+
+        match result {
+            Ok(json) => Ok(serde_json::from_str(&json).ok()),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Delete agent context for a session (e.g., on session reset)
     pub fn delete_agent_context(&self, session_id: i64) -> SqliteResult<()> {
         let conn = self.conn();
@@ -16,7 +16,7 @@ impl Database {
         let conn = self.conn();
 
         let mut stmt = conn.prepare(
-            "SELECT id, endpoint, model_archetype, max_response_tokens, max_context_tokens, enabled, secret_key, created_at, updated_at
+            "SELECT id, endpoint, model_archetype, max_response_tokens, max_context_tokens, enabled, secret_key, created_at, updated_at, supports_vision
              FROM agent_settings WHERE enabled = 1 LIMIT 1",
         )?;
 
@@ -33,7 +33,7 @@ impl Database {
         let conn = self.conn();
 
         let mut stmt = conn.prepare(
-            "SELECT id, endpoint, model_archetype, max_response_tokens, max_context_tokens, enabled, secret_key, created_at, updated_at
+            "SELECT id, endpoint, model_archetype, max_response_tokens, max_context_tokens, enabled, secret_key, created_at, updated_at, supports_vision
              FROM agent_settings WHERE endpoint = ?1",
         )?;
 
@@ -49,7 +49,7 @@ impl Database {
         let conn = self.conn();
 
         let mut stmt = conn.prepare(
-            "SELECT id, endpoint, model_archetype, max_response_tokens, max_context_tokens, enabled, secret_key, created_at, updated_at
+            "SELECT id, endpoint, model_archetype, max_response_tokens, max_context_tokens, enabled, secret_key, created_at, updated_at, supports_vision
              FROM agent_settings ORDER BY id",
         )?;
 
@@ -111,6 +111,18 @@ impl Database {
             .map(|opt| opt.unwrap())
     }
 
+    /// Set (or clear, with None) the vision override for an endpoint's settings
+    pub fn set_agent_settings_vision(&self, endpoint: &str, supports_vision: Option<bool>) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE agent_settings SET supports_vision = ?1, updated_at = ?2 WHERE endpoint = ?3",
+            rusqlite::params![supports_vision, Utc::now().to_rfc3339(), endpoint],
+        )?;
+        drop(conn);
+        self.cache.invalidate_agent_settings();
+        Ok(())
+    }
+
     /// Disable all agent settings (no AI provider active)
     pub fn disable_agent_settings(&self) -> SqliteResult<()> {
         let conn = self.conn();
@@ -132,6 +144,7 @@ impl Database {
             max_context_tokens: row.get::<_, Option<i32>>(4)?.unwrap_or(DEFAULT_CONTEXT_TOKENS),
             enabled: row.get::<_, i32>(5)? != 0,
             secret_key: row.get(6)?,
+            supports_vision: row.get::<_, Option<i32>>(9)?.map(|v| v != 0),
             created_at: DateTime::parse_from_rfc3339(&created_at_str)
                 .unwrap()
                 .with_timezone(&Utc),
@@ -50,6 +50,10 @@ impl Database {
     }
 
     pub fn validate_session(&self, token: &str) -> SqliteResult<Option<Session>> {
+        if token.starts_with(crate::dashboard_auth::API_TOKEN_PREFIX) {
+            return self.api_token_as_session(token);
+        }
+
         let conn = self.conn();
         let now = Utc::now();
         let now_str = now.to_rfc3339();
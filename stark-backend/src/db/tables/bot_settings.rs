@@ -4,7 +4,7 @@ use chrono::{DateTime, Utc};
 use rusqlite::Result as SqliteResult;
 use std::collections::HashMap;
 
-use crate::models::{BotSettings, DEFAULT_MAX_TOOL_ITERATIONS, DEFAULT_SAFE_MODE_MAX_QUERIES_PER_10MIN};
+use crate::models::{BotSettings, DEFAULT_KANBAN_MAX_CONCURRENT, DEFAULT_MAX_TOOL_ITERATIONS, DEFAULT_SAFE_MODE_MAX_QUERIES_PER_10MIN};
 use super::super::Database;
 
 impl Database {
@@ -17,7 +17,7 @@ impl Database {
         let conn = self.conn();
 
         let result = conn.query_row(
-            "SELECT id, bot_name, bot_email, web3_tx_requires_confirmation, rpc_provider, custom_rpc_endpoints, max_tool_iterations, rogue_mode_enabled, safe_mode_max_queries_per_10min, keystore_url, chat_session_memory_generation, guest_dashboard_enabled, theme_accent, proxy_url, kanban_auto_execute, created_at, updated_at FROM bot_settings LIMIT 1",
+            "SELECT id, bot_name, bot_email, web3_tx_requires_confirmation, rpc_provider, custom_rpc_endpoints, max_tool_iterations, rogue_mode_enabled, safe_mode_max_queries_per_10min, keystore_url, chat_session_memory_generation, guest_dashboard_enabled, theme_accent, proxy_url, kanban_auto_execute, created_at, updated_at, kanban_max_concurrent FROM bot_settings LIMIT 1",
             [],
             |row| {
                 let web3_tx_confirmation: i64 = row.get(3)?;
@@ -34,6 +34,7 @@ impl Database {
                 let kanban_auto_execute: i64 = row.get::<_, Option<i64>>(14)?.unwrap_or(1);
                 let created_at_str: String = row.get(15)?;
                 let updated_at_str: String = row.get(16)?;
+                let kanban_max_concurrent: i32 = row.get::<_, Option<i32>>(17)?.unwrap_or(DEFAULT_KANBAN_MAX_CONCURRENT);
 
                 let custom_rpc_endpoints: Option<HashMap<String, String>> = custom_rpc_endpoints_json
                     .and_then(|json| serde_json::from_str(&json).ok());
@@ -54,6 +55,7 @@ impl Database {
                     theme_accent,
                     proxy_url,
                     kanban_auto_execute: kanban_auto_execute != 0,
+                    kanban_max_concurrent,
                     created_at: DateTime::parse_from_rfc3339(&created_at_str)
                         .unwrap()
                         .with_timezone(&Utc),
@@ -79,7 +81,7 @@ impl Database {
         bot_email: Option<&str>,
         web3_tx_requires_confirmation: Option<bool>,
     ) -> SqliteResult<BotSettings> {
-        self.update_bot_settings_full(bot_name, bot_email, web3_tx_requires_confirmation, None, None, None, None, None, None, None, None, None, None, None)
+        self.update_bot_settings_full(bot_name, bot_email, web3_tx_requires_confirmation, None, None, None, None, None, None, None, None, None, None, None, None)
     }
 
     /// Update bot settings with all fields including RPC config and keystore URL
@@ -99,6 +101,7 @@ impl Database {
         theme_accent: Option<&str>,
         proxy_url: Option<&str>,
         kanban_auto_execute: Option<bool>,
+        kanban_max_concurrent: Option<i32>,
     ) -> SqliteResult<BotSettings> {
         let conn = self.conn();
         let now = Utc::now().to_rfc3339();
@@ -202,6 +205,12 @@ impl Database {
                     rusqlite::params![if enabled { 1 } else { 0 }, &now],
                 )?;
             }
+            if let Some(max) = kanban_max_concurrent {
+                conn.execute(
+                    "UPDATE bot_settings SET kanban_max_concurrent = ?1, updated_at = ?2",
+                    rusqlite::params![max, &now],
+                )?;
+            }
         } else {
             // Insert new
             let name = bot_name.unwrap_or("StarkBot");
@@ -220,9 +229,10 @@ impl Database {
             let theme_accent_value: Option<&str> = theme_accent.filter(|u| !u.is_empty());
             let proxy_url_value: Option<&str> = proxy_url.filter(|u| !u.is_empty());
             let kanban_auto = kanban_auto_execute.unwrap_or(true);
+            let kanban_max = kanban_max_concurrent.unwrap_or(DEFAULT_KANBAN_MAX_CONCURRENT);
             conn.execute(
-                "INSERT INTO bot_settings (bot_name, bot_email, web3_tx_requires_confirmation, rpc_provider, custom_rpc_endpoints, max_tool_iterations, rogue_mode_enabled, safe_mode_max_queries_per_10min, keystore_url, chat_session_memory_generation, guest_dashboard_enabled, theme_accent, proxy_url, kanban_auto_execute, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
-                rusqlite::params![name, email, if confirmation { 1 } else { 0 }, provider, endpoints_json, max_iterations, if rogue_mode { 1 } else { 0 }, safe_mode_queries, keystore_url_value, if session_memory { 1 } else { 0 }, if guest_dashboard { 1 } else { 0 }, theme_accent_value, proxy_url_value, if kanban_auto { 1 } else { 0 }, &now, &now],
+                "INSERT INTO bot_settings (bot_name, bot_email, web3_tx_requires_confirmation, rpc_provider, custom_rpc_endpoints, max_tool_iterations, rogue_mode_enabled, safe_mode_max_queries_per_10min, keystore_url, chat_session_memory_generation, guest_dashboard_enabled, theme_accent, proxy_url, kanban_auto_execute, created_at, updated_at, kanban_max_concurrent) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+                rusqlite::params![name, email, if confirmation { 1 } else { 0 }, provider, endpoints_json, max_iterations, if rogue_mode { 1 } else { 0 }, safe_mode_queries, keystore_url_value, if session_memory { 1 } else { 0 }, if guest_dashboard { 1 } else { 0 }, theme_accent_value, proxy_url_value, if kanban_auto { 1 } else { 0 }, &now, &now, kanban_max],
             )?;
         }
 
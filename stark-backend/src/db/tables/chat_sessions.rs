@@ -12,7 +12,7 @@ impl Database {
     // ============================================
 
     /// Generate a session key from channel info
-    fn generate_session_key(channel_type: &str, channel_id: i64, platform_chat_id: &str) -> String {
+    pub(crate) fn generate_session_key(channel_type: &str, channel_id: i64, platform_chat_id: &str) -> String {
         format!("{}:{}:{}", channel_type, channel_id, platform_chat_id)
     }
 
@@ -328,6 +328,24 @@ impl Database {
             rusqlite::params![id],
         )?;
 
+        // Delete branch lineage and persisted tool state
+        conn.execute(
+            "DELETE FROM session_branches WHERE session_id = ?1",
+            rusqlite::params![id],
+        )?;
+        conn.execute(
+            "DELETE FROM session_tool_state WHERE session_id = ?1",
+            rusqlite::params![id],
+        )?;
+        conn.execute(
+            "DELETE FROM session_imports WHERE session_id = ?1",
+            rusqlite::params![id],
+        )?;
+        conn.execute(
+            "DELETE FROM workflow_runs WHERE session_id = ?1",
+            rusqlite::params![id],
+        )?;
+
         // Delete the session (messages are cascade deleted via FK constraint)
         let deleted = conn.execute(
             "DELETE FROM chat_sessions WHERE id = ?1",
@@ -356,6 +374,12 @@ impl Database {
         // Delete all sub_agents (FK constraint lacks ON DELETE CASCADE)
         conn.execute("DELETE FROM sub_agents", [])?;
 
+        // Delete all branch lineage and persisted tool state
+        conn.execute("DELETE FROM session_branches", [])?;
+        conn.execute("DELETE FROM session_tool_state", [])?;
+        conn.execute("DELETE FROM session_imports", [])?;
+        conn.execute("DELETE FROM workflow_runs", [])?;
+
         // Count sessions before deleting
         let count: i64 = conn.query_row("SELECT COUNT(*) FROM chat_sessions", [], |row| row.get(0))?;
 
@@ -585,6 +609,38 @@ impl Database {
         })
     }
 
+    /// Time of the latest user message in a session
+    pub fn get_last_user_message_at(&self, session_id: i64) -> SqliteResult<Option<chrono::DateTime<Utc>>> {
+        let conn = self.conn();
+        let created_at: Option<String> = conn.query_row(
+            "SELECT MAX(created_at) FROM session_messages WHERE session_id = ?1 AND role = 'user'",
+            [session_id],
+            |row| row.get(0),
+        )?;
+        Ok(created_at
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&Utc)))
+    }
+
+    /// Get the most recent user messages outside heartbeat sessions, newest first
+    pub fn get_recent_user_messages(&self, limit: i32) -> SqliteResult<Vec<String>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT m.content FROM session_messages m
+             JOIN chat_sessions s ON s.id = m.session_id
+             WHERE m.role = 'user' AND s.channel_type != 'heartbeat'
+             ORDER BY m.id DESC
+             LIMIT ?1",
+        )?;
+
+        let messages = stmt
+            .query_map([limit], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(messages)
+    }
+
     /// List heartbeat sessions with their associated mind node IDs
     /// Parses the node ID from the heartbeat message content
     pub fn list_heartbeat_sessions(&self, limit: i32) -> SqliteResult<Vec<(ChatSession, Option<i64>)>> {
@@ -643,7 +699,7 @@ impl Database {
         })
     }
 
-    fn row_to_session_message(row: &rusqlite::Row) -> rusqlite::Result<SessionMessage> {
+    pub(crate) fn row_to_session_message(row: &rusqlite::Row) -> rusqlite::Result<SessionMessage> {
         let created_at_str: String = row.get(8)?;
         let role_str: String = row.get(2)?;
 
@@ -4,9 +4,12 @@ use chrono::Utc;
 use rusqlite::{Connection, Result as SqliteResult};
 use uuid::Uuid;
 
-use crate::models::{CronJob, CronJobRun};
+use crate::models::{CronJob, CronJobRun, CronRunInput, CronRunStats, RunOverrides};
 use super::super::Database;
 
+const CRON_RUN_COLUMNS: &str = "id, job_id, started_at, completed_at, success, result, error, duration_ms,
+    delivery_status, delivery_error, session_id, tool_calls, tokens_used, x402_spend_usdc, input, replay_of";
+
 impl Database {
     /// Create a new cron job
     pub fn create_cron_job(
@@ -56,7 +59,7 @@ impl Database {
                     session_mode, message, system_event, channel_id, deliver_to, deliver,
                     model_override, thinking_level, timeout_seconds, delete_after_run,
                     status, last_run_at, next_run_at, run_count, error_count, last_error,
-                    created_at, updated_at
+                    created_at, updated_at, run_overrides, consecutive_failures, alert_after_failures
              FROM cron_jobs WHERE id = ?1",
             [id],
             |row| self.map_cron_job_row(row),
@@ -90,6 +93,9 @@ impl Database {
             last_error: row.get(22)?,
             created_at: row.get(23)?,
             updated_at: row.get(24)?,
+            run_overrides: RunOverrides::from_json(row.get(25)?),
+            consecutive_failures: row.get(26)?,
+            alert_after_failures: row.get(27)?,
         })
     }
 
@@ -111,7 +117,7 @@ impl Database {
                     session_mode, message, system_event, channel_id, deliver_to, deliver,
                     model_override, thinking_level, timeout_seconds, delete_after_run,
                     status, last_run_at, next_run_at, run_count, error_count, last_error,
-                    created_at, updated_at
+                    created_at, updated_at, run_overrides, consecutive_failures, alert_after_failures
              FROM cron_jobs WHERE job_id = ?1",
             [job_id],
             |row| self.map_cron_job_row(row),
@@ -130,7 +136,7 @@ impl Database {
                     session_mode, message, system_event, channel_id, deliver_to, deliver,
                     model_override, thinking_level, timeout_seconds, delete_after_run,
                     status, last_run_at, next_run_at, run_count, error_count, last_error,
-                    created_at, updated_at
+                    created_at, updated_at, run_overrides, consecutive_failures, alert_after_failures
              FROM cron_jobs ORDER BY created_at DESC"
         )?;
 
@@ -152,7 +158,7 @@ impl Database {
                     session_mode, message, system_event, channel_id, deliver_to, deliver,
                     model_override, thinking_level, timeout_seconds, delete_after_run,
                     status, last_run_at, next_run_at, run_count, error_count, last_error,
-                    created_at, updated_at
+                    created_at, updated_at, run_overrides, consecutive_failures, alert_after_failures
              FROM cron_jobs
              WHERE status = 'active' AND (next_run_at IS NULL OR next_run_at <= ?1)
              ORDER BY next_run_at ASC"
@@ -244,6 +250,31 @@ impl Database {
         self.get_cron_job_by_id_internal(&conn, id)
     }
 
+    /// Replace a cron job's run overrides (empty clears them)
+    pub fn set_cron_job_run_overrides(&self, id: i64, overrides: &RunOverrides) -> SqliteResult<()> {
+        let conn = self.conn();
+        let value = if overrides.is_empty() {
+            None
+        } else {
+            serde_json::to_string(overrides).ok()
+        };
+        conn.execute(
+            "UPDATE cron_jobs SET run_overrides = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![value, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Set how many consecutive failures trigger an alert (None turns alerts off)
+    pub fn set_cron_job_alert_threshold(&self, id: i64, alert_after_failures: Option<i32>) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE cron_jobs SET alert_after_failures = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![alert_after_failures, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
     /// Update cron job run status
     pub fn update_cron_job_run_status(
         &self,
@@ -260,7 +291,7 @@ impl Database {
             conn.execute(
                 "UPDATE cron_jobs SET
                     last_run_at = ?1, next_run_at = ?2, run_count = run_count + 1,
-                    last_error = NULL, updated_at = ?3
+                    consecutive_failures = 0, last_error = NULL, updated_at = ?3
                  WHERE id = ?4",
                 rusqlite::params![last_run_at, next_run_at, now, id],
             )?;
@@ -268,7 +299,7 @@ impl Database {
             conn.execute(
                 "UPDATE cron_jobs SET
                     last_run_at = ?1, next_run_at = ?2, error_count = error_count + 1,
-                    last_error = ?3, updated_at = ?4
+                    consecutive_failures = consecutive_failures + 1, last_error = ?3, updated_at = ?4
                  WHERE id = ?5",
                 rusqlite::params![last_run_at, next_run_at, error, now, id],
             )?;
@@ -336,33 +367,186 @@ impl Database {
             result: result.map(|s| s.to_string()),
             error: error.map(|s| s.to_string()),
             duration_ms,
+            delivery_status: None,
+            delivery_error: None,
+            session_id: None,
+            tool_calls: None,
+            tokens_used: None,
+            x402_spend_usdc: None,
+            input: None,
+            replay_of: None,
         })
     }
 
+    /// Record a run's inputs, session and usage
+    pub fn record_cron_job_run_details(
+        &self,
+        run_id: i64,
+        input: &CronRunInput,
+        replay_of: Option<i64>,
+        stats: &CronRunStats,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE cron_job_runs SET session_id = ?1, tool_calls = ?2, tokens_used = ?3,
+                    x402_spend_usdc = ?4, input = ?5, replay_of = ?6
+             WHERE id = ?7",
+            rusqlite::params![
+                stats.session_id,
+                stats.tool_calls,
+                stats.tokens_used,
+                stats.x402_spend_usdc,
+                serde_json::to_string(input).ok(),
+                replay_of,
+                run_id
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Highest session message and x402 payment ids, taken before a run so its usage can be counted after
+    pub fn cron_run_watermarks(&self) -> SqliteResult<(i64, i64)> {
+        let conn = self.conn();
+        let messages: i64 = conn.query_row("SELECT COALESCE(MAX(id), 0) FROM session_messages", [], |row| row.get(0))?;
+        let payments: i64 = conn.query_row("SELECT COALESCE(MAX(id), 0) FROM x402_payments", [], |row| row.get(0))?;
+        Ok((messages, payments))
+    }
+
+    /// Usage a run added after the given watermarks: tool calls and tokens in its session,
+    /// and USDC paid via x402 on its channel
+    pub fn cron_run_stats(
+        &self,
+        session_id: Option<i64>,
+        channel_id: i64,
+        watermarks: (i64, i64),
+    ) -> SqliteResult<CronRunStats> {
+        let conn = self.conn();
+        let (tool_calls, tokens_used) = match session_id {
+            Some(sid) => conn.query_row(
+                "SELECT COALESCE(SUM(CASE WHEN role = 'tool_call' THEN 1 ELSE 0 END), 0),
+                        COALESCE(SUM(tokens_used), 0)
+                 FROM session_messages WHERE session_id = ?1 AND id > ?2",
+                rusqlite::params![sid, watermarks.0],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+            )?,
+            None => (0, 0),
+        };
+        let x402_spend_usdc: f64 = conn.query_row(
+            "SELECT COALESCE(SUM(CAST(amount_formatted AS REAL)), 0) FROM x402_payments
+             WHERE channel_id = ?1 AND id > ?2 AND asset = 'USDC'",
+            rusqlite::params![channel_id, watermarks.1],
+            |row| row.get(0),
+        )?;
+
+        Ok(CronRunStats { session_id, tool_calls, tokens_used, x402_spend_usdc })
+    }
+
+    /// Get a single run
+    pub fn get_cron_job_run(&self, run_id: i64) -> SqliteResult<Option<CronJobRun>> {
+        let conn = self.conn();
+        match conn.query_row(
+            &format!("SELECT {} FROM cron_job_runs WHERE id = ?1", CRON_RUN_COLUMNS),
+            [run_id],
+            Self::map_cron_job_run_row,
+        ) {
+            Ok(run) => Ok(Some(run)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Record how result delivery went for a run
+    pub fn update_cron_job_run_delivery(
+        &self,
+        run_id: i64,
+        status: &str,
+        error: Option<&str>,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE cron_job_runs SET delivery_status = ?1, delivery_error = ?2 WHERE id = ?3",
+            rusqlite::params![status, error, run_id],
+        )?;
+        Ok(())
+    }
+
     /// Get recent runs for a cron job
     pub fn get_cron_job_runs(&self, job_id: i64, limit: i32) -> SqliteResult<Vec<CronJobRun>> {
         let conn = self.conn();
-        let mut stmt = conn.prepare(
-            "SELECT id, job_id, started_at, completed_at, success, result, error, duration_ms
-             FROM cron_job_runs WHERE job_id = ?1 ORDER BY started_at DESC LIMIT ?2"
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM cron_job_runs WHERE job_id = ?1 ORDER BY started_at DESC LIMIT ?2",
+            CRON_RUN_COLUMNS
+        ))?;
 
         let runs: Vec<CronJobRun> = stmt
-            .query_map([job_id, limit as i64], |row| {
-                Ok(CronJobRun {
-                    id: row.get(0)?,
-                    job_id: row.get(1)?,
-                    started_at: row.get(2)?,
-                    completed_at: row.get(3)?,
-                    success: row.get::<_, i32>(4)? != 0,
-                    result: row.get(5)?,
-                    error: row.get(6)?,
-                    duration_ms: row.get(7)?,
-                })
-            })?
+            .query_map([job_id, limit as i64], Self::map_cron_job_run_row)?
             .filter_map(|r| r.ok())
             .collect();
 
         Ok(runs)
     }
+
+    fn map_cron_job_run_row(row: &rusqlite::Row) -> SqliteResult<CronJobRun> {
+        Ok(CronJobRun {
+            id: row.get(0)?,
+            job_id: row.get(1)?,
+            started_at: row.get(2)?,
+            completed_at: row.get(3)?,
+            success: row.get::<_, i32>(4)? != 0,
+            result: row.get(5)?,
+            error: row.get(6)?,
+            duration_ms: row.get(7)?,
+            delivery_status: row.get(8)?,
+            delivery_error: row.get(9)?,
+            session_id: row.get(10)?,
+            tool_calls: row.get(11)?,
+            tokens_used: row.get(12)?,
+            x402_spend_usdc: row.get(13)?,
+            input: row
+                .get::<_, Option<String>>(14)?
+                .and_then(|s| serde_json::from_str(&s).ok()),
+            replay_of: row.get(15)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::Database;
+    use crate::models::{CronRunInput, CronRunStats, RunOverrides};
+
+    #[test]
+    fn test_failure_streak_and_run_details() {
+        let db = Database::new(":memory:").expect("Failed to create test db");
+        let job = db
+            .create_cron_job(
+                "job", None, "every", "60000", None, "isolated", Some("hi"), None,
+                None, None, false, None, None, None, false,
+            )
+            .unwrap();
+        db.set_cron_job_alert_threshold(job.id, Some(2)).unwrap();
+
+        let now = chrono::Utc::now().to_rfc3339();
+        db.update_cron_job_run_status(job.id, &now, None, false, Some("boom")).unwrap();
+        db.update_cron_job_run_status(job.id, &now, None, false, Some("boom")).unwrap();
+        let job = db.get_cron_job(job.id).unwrap().unwrap();
+        assert_eq!(job.consecutive_failures, 2);
+        assert_eq!(job.alert_after_failures, Some(2));
+
+        db.update_cron_job_run_status(job.id, &now, None, true, None).unwrap();
+        let job = db.get_cron_job(job.id).unwrap().unwrap();
+        assert_eq!(job.consecutive_failures, 0);
+        assert_eq!(job.error_count, 2);
+
+        let run = db.log_cron_job_run(job.id, &now, Some(&now), true, Some("ok"), None, Some(5)).unwrap();
+        let input = CronRunInput { message: "hi".to_string(), run_overrides: RunOverrides::default() };
+        let stats = CronRunStats { session_id: Some(7), tool_calls: 3, tokens_used: 120, x402_spend_usdc: 0.5 };
+        db.record_cron_job_run_details(run.id, &input, None, &stats).unwrap();
+
+        let stored = db.get_cron_job_run(run.id).unwrap().unwrap();
+        assert_eq!(stored.session_id, Some(7));
+        assert_eq!(stored.tool_calls, Some(3));
+        assert_eq!(stored.input, Some(input));
+        assert_eq!(stored.replay_of, None);
+    }
 }
@@ -0,0 +1,381 @@
+//! Dashboard accounts, account sessions and scoped API tokens
+
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::Result as SqliteResult;
+
+use crate::dashboard_auth::{hash_api_token, API_TOKEN_PREFIX};
+use crate::models::{ApiToken, DashboardPrincipal, DashboardRole, DashboardUser, Session};
+use super::super::Database;
+
+const USER_COLUMNS: &str = "id, username, role, wallet_address, password_hash, totp_secret, totp_enabled,
+    created_at, last_login_at";
+
+const TOKEN_COLUMNS: &str = "id, name, token_prefix, scopes, user_id, created_at, expires_at, last_used_at, revoked";
+
+impl Database {
+    // ============================================
+    // Dashboard accounts
+    // ============================================
+
+    pub fn create_dashboard_user(
+        &self,
+        username: &str,
+        role: DashboardRole,
+        wallet_address: Option<&str>,
+        password_hash: Option<&str>,
+    ) -> SqliteResult<DashboardUser> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO dashboard_users (username, role, wallet_address, password_hash, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                username,
+                role.as_str(),
+                wallet_address.map(|a| a.to_lowercase()),
+                password_hash,
+                &now
+            ],
+        )?;
+
+        let id = conn.last_insert_rowid();
+        drop(conn);
+        self.get_dashboard_user(id).map(|opt| opt.unwrap())
+    }
+
+    pub fn list_dashboard_users(&self) -> SqliteResult<Vec<DashboardUser>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM dashboard_users ORDER BY username", USER_COLUMNS))?;
+        let users = stmt
+            .query_map([], Self::map_dashboard_user_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(users)
+    }
+
+    pub fn get_dashboard_user(&self, id: i64) -> SqliteResult<Option<DashboardUser>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM dashboard_users WHERE id = ?1", USER_COLUMNS))?;
+        Ok(stmt.query_row([id], Self::map_dashboard_user_row).ok())
+    }
+
+    pub fn get_dashboard_user_by_username(&self, username: &str) -> SqliteResult<Option<DashboardUser>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM dashboard_users WHERE username = ?1 COLLATE NOCASE",
+            USER_COLUMNS
+        ))?;
+        Ok(stmt.query_row([username], Self::map_dashboard_user_row).ok())
+    }
+
+    pub fn get_dashboard_user_by_wallet(&self, wallet_address: &str) -> SqliteResult<Option<DashboardUser>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM dashboard_users WHERE wallet_address = ?1",
+            USER_COLUMNS
+        ))?;
+        Ok(stmt.query_row([wallet_address.to_lowercase()], Self::map_dashboard_user_row).ok())
+    }
+
+    /// Update an account (None fields are left unchanged; an empty wallet removes it).
+    /// Changing the role or credentials signs the account out everywhere.
+    pub fn update_dashboard_user(
+        &self,
+        id: i64,
+        role: Option<DashboardRole>,
+        wallet_address: Option<&str>,
+        password_hash: Option<&str>,
+        totp_enabled: Option<bool>,
+    ) -> SqliteResult<Option<DashboardUser>> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE dashboard_users SET
+                role = COALESCE(?1, role),
+                wallet_address = CASE WHEN ?2 IS NULL THEN wallet_address ELSE NULLIF(?2, '') END,
+                password_hash = COALESCE(?3, password_hash),
+                totp_enabled = COALESCE(?4, totp_enabled),
+                totp_secret = CASE WHEN ?4 = 0 THEN NULL ELSE totp_secret END
+             WHERE id = ?5",
+            rusqlite::params![
+                role.map(|r| r.as_str()),
+                wallet_address.map(|a| a.to_lowercase()),
+                password_hash,
+                totp_enabled.map(|e| e as i32),
+                id
+            ],
+        )?;
+        if role.is_some() || password_hash.is_some() || totp_enabled.is_some() {
+            conn.execute("DELETE FROM auth_sessions WHERE user_id = ?1", [id])?;
+        }
+        drop(conn);
+        self.get_dashboard_user(id)
+    }
+
+    /// Store a new (not yet confirmed) TOTP secret
+    pub fn set_dashboard_user_totp_secret(&self, id: i64, secret: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE dashboard_users SET totp_secret = ?1, totp_enabled = 0 WHERE id = ?2",
+            rusqlite::params![secret, id],
+        )?;
+        Ok(())
+    }
+
+    /// Turn on TOTP once the user has confirmed a code
+    pub fn enable_dashboard_user_totp(&self, id: i64) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE dashboard_users SET totp_enabled = 1 WHERE id = ?1 AND totp_secret IS NOT NULL",
+            [id],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_dashboard_login(&self, id: i64) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE dashboard_users SET last_login_at = ?1 WHERE id = ?2",
+            rusqlite::params![Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Delete an account, its sessions and its API tokens
+    pub fn delete_dashboard_user(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let tx = conn.unchecked_transaction()?;
+        let rows = tx.execute("DELETE FROM dashboard_users WHERE id = ?1", [id])?;
+        tx.execute("DELETE FROM auth_sessions WHERE user_id = ?1", [id])?;
+        tx.execute("UPDATE api_tokens SET revoked = 1 WHERE user_id = ?1", [id])?;
+        tx.commit()?;
+        Ok(rows > 0)
+    }
+
+    /// Create a login session for a dashboard account
+    pub fn create_session_for_user(&self, user_id: i64, public_address: Option<&str>) -> SqliteResult<Session> {
+        let session = self.create_session_for_address(public_address)?;
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE auth_sessions SET user_id = ?1 WHERE id = ?2",
+            rusqlite::params![user_id, session.id],
+        )?;
+        Ok(session)
+    }
+
+    // ============================================
+    // API tokens
+    // ============================================
+
+    pub fn create_api_token(
+        &self,
+        name: &str,
+        token_hash: &str,
+        token_prefix: &str,
+        scopes: &[String],
+        user_id: Option<i64>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> SqliteResult<ApiToken> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO api_tokens (name, token_hash, token_prefix, scopes, user_id, created_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                name,
+                token_hash,
+                token_prefix,
+                serde_json::to_string(scopes).unwrap_or_default(),
+                user_id,
+                Utc::now().to_rfc3339(),
+                expires_at.map(|e| e.to_rfc3339())
+            ],
+        )?;
+
+        let id = conn.last_insert_rowid();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM api_tokens WHERE id = ?1", TOKEN_COLUMNS))?;
+        stmt.query_row([id], Self::map_api_token_row)
+    }
+
+    /// List API tokens, optionally only those owned by one account
+    pub fn list_api_tokens(&self, user_id: Option<i64>) -> SqliteResult<Vec<ApiToken>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM api_tokens WHERE ?1 IS NULL OR user_id = ?1 ORDER BY created_at DESC",
+            TOKEN_COLUMNS
+        ))?;
+        let tokens = stmt
+            .query_map([user_id], Self::map_api_token_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(tokens)
+    }
+
+    pub fn get_api_token(&self, id: i64) -> SqliteResult<Option<ApiToken>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM api_tokens WHERE id = ?1", TOKEN_COLUMNS))?;
+        Ok(stmt.query_row([id], Self::map_api_token_row).ok())
+    }
+
+    pub fn revoke_api_token(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute("UPDATE api_tokens SET revoked = 1 WHERE id = ?1 AND revoked = 0", [id])?;
+        Ok(rows > 0)
+    }
+
+    /// Look up a live (unrevoked, unexpired) API token by its plaintext and mark it used
+    pub fn validate_api_token(&self, token: &str) -> SqliteResult<Option<ApiToken>> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM api_tokens
+             WHERE token_hash = ?1 AND revoked = 0 AND (expires_at IS NULL OR expires_at > ?2)",
+            TOKEN_COLUMNS
+        ))?;
+        let api_token = stmt
+            .query_row(rusqlite::params![hash_api_token(token), &now], Self::map_api_token_row)
+            .ok();
+
+        if let Some(ref t) = api_token {
+            let _ = conn.execute(
+                "UPDATE api_tokens SET last_used_at = ?1 WHERE id = ?2",
+                rusqlite::params![&now, t.id],
+            );
+        }
+        Ok(api_token)
+    }
+
+    /// Session view of an API token, so handlers that only check for a valid
+    /// credential accept tokens too. Scopes are enforced by the auth middleware.
+    pub(crate) fn api_token_as_session(&self, token: &str) -> SqliteResult<Option<Session>> {
+        Ok(self.validate_api_token(token)?.map(|t| {
+            let parse = |s: &str| DateTime::parse_from_rfc3339(s).map(|d| d.with_timezone(&Utc)).ok();
+            Session {
+                id: t.id,
+                token: token.to_string(),
+                created_at: parse(&t.created_at).unwrap_or_else(Utc::now),
+                expires_at: t
+                    .expires_at
+                    .as_deref()
+                    .and_then(parse)
+                    .unwrap_or_else(|| Utc::now() + Duration::days(365)),
+            }
+        }))
+    }
+
+    /// Resolve a bearer token (login session or API token) to the account behind it
+    pub fn resolve_dashboard_principal(&self, token: &str) -> SqliteResult<Option<DashboardPrincipal>> {
+        if token.starts_with(API_TOKEN_PREFIX) {
+            let Some(api_token) = self.validate_api_token(token)? else {
+                return Ok(None);
+            };
+            let (username, role) = match api_token.user_id {
+                Some(uid) => match self.get_dashboard_user(uid)? {
+                    Some(user) => (user.username.clone(), user.role_enum()),
+                    // Owner deleted: the token is dead
+                    None => return Ok(None),
+                },
+                None => ("admin".to_string(), DashboardRole::Admin),
+            };
+            return Ok(Some(DashboardPrincipal {
+                user_id: api_token.user_id,
+                username,
+                role,
+                api_token_id: Some(api_token.id),
+                scopes: Some(api_token.scopes),
+            }));
+        }
+
+        if self.validate_session(token)?.is_none() {
+            return Ok(None);
+        }
+        let user_id: Option<i64> = {
+            let conn = self.conn();
+            conn.query_row("SELECT user_id FROM auth_sessions WHERE token = ?1", [token], |row| row.get(0))
+                .ok()
+                .flatten()
+        };
+        let principal = match user_id {
+            Some(uid) => self.get_dashboard_user(uid)?.map(|user| DashboardPrincipal {
+                user_id: Some(user.id),
+                role: user.role_enum(),
+                username: user.username,
+                api_token_id: None,
+                scopes: None,
+            }),
+            None => Some(DashboardPrincipal {
+                user_id: None,
+                username: "admin".to_string(),
+                role: DashboardRole::Admin,
+                api_token_id: None,
+                scopes: None,
+            }),
+        };
+        Ok(principal)
+    }
+
+    fn map_dashboard_user_row(row: &rusqlite::Row) -> rusqlite::Result<DashboardUser> {
+        Ok(DashboardUser {
+            id: row.get(0)?,
+            username: row.get(1)?,
+            role: row.get(2)?,
+            wallet_address: row.get(3)?,
+            password_hash: row.get(4)?,
+            totp_secret: row.get(5)?,
+            totp_enabled: row.get::<_, i32>(6)? != 0,
+            created_at: row.get(7)?,
+            last_login_at: row.get(8)?,
+        })
+    }
+
+    fn map_api_token_row(row: &rusqlite::Row) -> rusqlite::Result<ApiToken> {
+        let scopes: String = row.get(3)?;
+        Ok(ApiToken {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            token_prefix: row.get(2)?,
+            scopes: serde_json::from_str(&scopes).unwrap_or_default(),
+            user_id: row.get(4)?,
+            created_at: row.get(5)?,
+            expires_at: row.get(6)?,
+            last_used_at: row.get(7)?,
+            revoked: row.get::<_, i32>(8)? != 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dashboard_auth::generate_api_token;
+    use crate::db::Database;
+    use crate::models::DashboardRole;
+
+    #[test]
+    fn test_principal_resolution() {
+        let db = Database::new(":memory:").expect("Failed to create test db");
+        let user = db.create_dashboard_user("alice", DashboardRole::Auditor, None, None).unwrap();
+
+        let session = db.create_session_for_user(user.id, None).unwrap();
+        let principal = db.resolve_dashboard_principal(&session.token).unwrap().unwrap();
+        assert_eq!(principal.role, DashboardRole::Auditor);
+        assert!(principal.scopes.is_none());
+
+        // Legacy sessions are the single admin
+        let legacy = db.create_session().unwrap();
+        assert_eq!(db.resolve_dashboard_principal(&legacy.token).unwrap().unwrap().role, DashboardRole::Admin);
+
+        let (token, hash) = generate_api_token();
+        let api_token = db
+            .create_api_token("ci", &hash, &token[..12], &["kanban:write".to_string()], Some(user.id), None)
+            .unwrap();
+        let principal = db.resolve_dashboard_principal(&token).unwrap().unwrap();
+        assert_eq!(principal.scopes, Some(vec!["kanban:write".to_string()]));
+        assert!(db.validate_session(&token).unwrap().is_some());
+
+        db.revoke_api_token(api_token.id).unwrap();
+        assert!(db.resolve_dashboard_principal(&token).unwrap().is_none());
+
+        // Deleting the account ends its sessions
+        db.delete_dashboard_user(user.id).unwrap();
+        assert!(db.resolve_dashboard_principal(&session.token).unwrap().is_none());
+    }
+}
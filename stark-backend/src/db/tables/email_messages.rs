@@ -0,0 +1,59 @@
+//! Email channel message tracking - dedupe and reply threading
+//!
+//! Every received and sent message is stored with the root Message-ID of its
+//! thread, so a reply that only references one of our own replies still lands
+//! in the original session.
+
+use crate::db::Database;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+
+impl Database {
+    /// Check if an email (by Message-ID) has already been handled on a channel
+    pub fn is_email_processed(&self, channel_id: i64, message_id: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM email_messages WHERE channel_id = ?1 AND message_id = ?2",
+            rusqlite::params![channel_id, message_id],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Record a received ("in") or sent ("out") email and the thread it belongs to
+    pub fn record_email_message(
+        &self,
+        channel_id: i64,
+        message_id: &str,
+        thread_id: &str,
+        direction: &str,
+        sender: &str,
+        subject: &str,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR IGNORE INTO email_messages
+             (channel_id, message_id, thread_id, direction, sender, subject, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, datetime('now'))",
+            rusqlite::params![channel_id, message_id, thread_id, direction, sender, subject],
+        )?;
+        Ok(())
+    }
+
+    /// Find the thread of the first known message among `message_ids`
+    /// (In-Reply-To and References of an incoming email)
+    pub fn find_email_thread(&self, channel_id: i64, message_ids: &[String]) -> SqliteResult<Option<String>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT thread_id FROM email_messages WHERE channel_id = ?1 AND message_id = ?2",
+        )?;
+        for message_id in message_ids {
+            let thread: Option<String> = stmt
+                .query_row(rusqlite::params![channel_id, message_id], |row| row.get(0))
+                .optional()?;
+            if thread.is_some() {
+                return Ok(thread);
+            }
+        }
+        Ok(None)
+    }
+}
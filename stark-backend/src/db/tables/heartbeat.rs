@@ -3,7 +3,7 @@
 use chrono::Utc;
 use rusqlite::{OptionalExtension, Result as SqliteResult};
 
-use crate::models::HeartbeatConfig;
+use crate::models::{HeartbeatConfig, RunOverrides};
 use super::super::Database;
 
 impl Database {
@@ -16,7 +16,7 @@ impl Database {
             conn.query_row(
                 "SELECT id, channel_id, interval_minutes, target, active_hours_start, active_hours_end,
                         active_days, enabled, last_beat_at, next_beat_at, current_mind_node_id, last_session_id,
-                        created_at, updated_at
+                        created_at, updated_at, run_overrides
                  FROM heartbeat_configs WHERE channel_id = ?1",
                 [cid],
                 |row| self.map_heartbeat_config_row(row),
@@ -25,7 +25,7 @@ impl Database {
             conn.query_row(
                 "SELECT id, channel_id, interval_minutes, target, active_hours_start, active_hours_end,
                         active_days, enabled, last_beat_at, next_beat_at, current_mind_node_id, last_session_id,
-                        created_at, updated_at
+                        created_at, updated_at, run_overrides
                  FROM heartbeat_configs WHERE channel_id IS NULL",
                 [],
                 |row| self.map_heartbeat_config_row(row),
@@ -60,6 +60,7 @@ impl Database {
             next_beat_at: None,
             current_mind_node_id: None,
             last_session_id: None,
+            run_overrides: RunOverrides::default(),
             created_at: now.clone(),
             updated_at: now,
         })
@@ -81,6 +82,7 @@ impl Database {
             last_session_id: row.get(11)?,
             created_at: row.get(12)?,
             updated_at: row.get(13)?,
+            run_overrides: RunOverrides::from_json(row.get(14)?),
         })
     }
 
@@ -129,13 +131,28 @@ impl Database {
         conn.query_row(
             "SELECT id, channel_id, interval_minutes, target, active_hours_start, active_hours_end,
                     active_days, enabled, last_beat_at, next_beat_at, current_mind_node_id, last_session_id,
-                    created_at, updated_at
+                    created_at, updated_at, run_overrides
              FROM heartbeat_configs WHERE id = ?1",
             [id],
             |row| self.map_heartbeat_config_row(row),
         )
     }
 
+    /// Replace a heartbeat config's run overrides (empty clears them)
+    pub fn set_heartbeat_run_overrides(&self, id: i64, overrides: &RunOverrides) -> SqliteResult<()> {
+        let conn = self.conn();
+        let value = if overrides.is_empty() {
+            None
+        } else {
+            serde_json::to_string(overrides).ok()
+        };
+        conn.execute(
+            "UPDATE heartbeat_configs SET run_overrides = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![value, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
     /// Update heartbeat next_beat_at BEFORE execution (prevents race conditions)
     pub fn update_heartbeat_next_beat(&self, id: i64, next_beat_at: &str) -> SqliteResult<()> {
         let conn = self.conn();
@@ -182,7 +199,7 @@ impl Database {
         let mut stmt = conn.prepare(
             "SELECT id, channel_id, interval_minutes, target, active_hours_start, active_hours_end,
                     active_days, enabled, last_beat_at, next_beat_at, current_mind_node_id, last_session_id,
-                    created_at, updated_at
+                    created_at, updated_at, run_overrides
              FROM heartbeat_configs ORDER BY id"
         )?;
 
@@ -201,7 +218,7 @@ impl Database {
         conn.query_row(
             "SELECT id, channel_id, interval_minutes, target, active_hours_start, active_hours_end,
                     active_days, enabled, last_beat_at, next_beat_at, current_mind_node_id, last_session_id,
-                    created_at, updated_at
+                    created_at, updated_at, run_overrides
              FROM heartbeat_configs WHERE id = ?1",
             [id],
             |row| self.map_heartbeat_config_row(row),
@@ -216,7 +233,7 @@ impl Database {
         let mut stmt = conn.prepare(
             "SELECT id, channel_id, interval_minutes, target, active_hours_start, active_hours_end,
                     active_days, enabled, last_beat_at, next_beat_at, current_mind_node_id, last_session_id,
-                    created_at, updated_at
+                    created_at, updated_at, run_overrides
              FROM heartbeat_configs
              WHERE enabled = 1 AND (next_beat_at IS NULL OR next_beat_at <= ?1)
              ORDER BY next_beat_at ASC"
@@ -4,7 +4,7 @@ use chrono::{DateTime, Utc};
 use rusqlite::Result as SqliteResult;
 use uuid::Uuid;
 
-use crate::models::IdentityLink;
+use crate::models::{IdentityLink, IdentityVerification, VerificationKind};
 use super::super::Database;
 
 impl Database {
@@ -51,27 +51,40 @@ impl Database {
             platform_user_name: platform_user_name.map(|s| s.to_string()),
             is_verified: false,
             verified_at: None,
+            verification_method: None,
             created_at: now,
             updated_at: now,
         })
     }
 
-    /// Link an existing identity to a new platform
+    /// Link an existing identity to a new platform.
+    /// `verification_method` marks the link verified (e.g. "admin" when an admin links it).
     pub fn link_identity(
         &self,
         identity_id: &str,
         channel_type: &str,
         platform_user_id: &str,
         platform_user_name: Option<&str>,
+        verification_method: Option<&str>,
     ) -> SqliteResult<IdentityLink> {
         let conn = self.conn();
         let now = Utc::now();
         let now_str = now.to_rfc3339();
+        let verified_at = verification_method.map(|_| now_str.clone());
 
         conn.execute(
-            "INSERT INTO identity_links (identity_id, channel_type, platform_user_id, platform_user_name, is_verified, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, 0, ?5, ?5)",
-            rusqlite::params![identity_id, channel_type, platform_user_id, platform_user_name, &now_str],
+            "INSERT INTO identity_links (identity_id, channel_type, platform_user_id, platform_user_name, is_verified, verified_at, verification_method, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+            rusqlite::params![
+                identity_id,
+                channel_type,
+                platform_user_id,
+                platform_user_name,
+                verification_method.is_some() as i32,
+                verified_at,
+                verification_method,
+                &now_str
+            ],
         )?;
 
         let id = conn.last_insert_rowid();
@@ -82,8 +95,9 @@ impl Database {
             channel_type: channel_type.to_string(),
             platform_user_id: platform_user_id.to_string(),
             platform_user_name: platform_user_name.map(|s| s.to_string()),
-            is_verified: false,
-            verified_at: None,
+            is_verified: verification_method.is_some(),
+            verified_at: verification_method.map(|_| now),
+            verification_method: verification_method.map(|s| s.to_string()),
             created_at: now,
             updated_at: now,
         })
@@ -101,7 +115,7 @@ impl Database {
         let conn = self.conn();
 
         let mut stmt = conn.prepare(
-            "SELECT id, identity_id, channel_type, platform_user_id, platform_user_name, is_verified, verified_at, created_at, updated_at
+            "SELECT id, identity_id, channel_type, platform_user_id, platform_user_name, is_verified, verified_at, created_at, updated_at, verification_method
              FROM identity_links WHERE channel_type = ?1 AND platform_user_id = ?2",
         )?;
 
@@ -117,7 +131,7 @@ impl Database {
         let conn = self.conn();
 
         let mut stmt = conn.prepare(
-            "SELECT id, identity_id, channel_type, platform_user_id, platform_user_name, is_verified, verified_at, created_at, updated_at
+            "SELECT id, identity_id, channel_type, platform_user_id, platform_user_name, is_verified, verified_at, created_at, updated_at, verification_method
              FROM identity_links WHERE identity_id = ?1",
         )?;
 
@@ -134,7 +148,7 @@ impl Database {
         let conn = self.conn();
 
         let mut stmt = conn.prepare(
-            "SELECT id, identity_id, channel_type, platform_user_id, platform_user_name, is_verified, verified_at, created_at, updated_at
+            "SELECT id, identity_id, channel_type, platform_user_id, platform_user_name, is_verified, verified_at, created_at, updated_at, verification_method
              FROM identity_links ORDER BY updated_at DESC LIMIT 100",
         )?;
 
@@ -163,6 +177,7 @@ impl Database {
                     .ok()
                     .map(|dt| dt.with_timezone(&Utc))
             }),
+            verification_method: row.get(9)?,
             created_at: DateTime::parse_from_rfc3339(&created_at_str)
                 .map(|dt| dt.with_timezone(&Utc))
                 .unwrap_or_else(|_| Utc::now()),
@@ -176,25 +191,11 @@ impl Database {
     pub fn get_sessions_for_identity(&self, identity_id: &str) -> SqliteResult<Vec<crate::models::ChatSession>> {
         let conn = self.conn();
 
-        // First get all platform_user_ids for this identity
-        let mut stmt = conn.prepare(
-            "SELECT platform_user_id FROM identity_links WHERE identity_id = ?1"
-        )?;
-        let platform_user_ids: Vec<String> = stmt
-            .query_map([identity_id], |row| row.get(0))?
-            .filter_map(|r| r.ok())
-            .collect();
-        drop(stmt);
-
-        if platform_user_ids.is_empty() {
+        let (account_filter, account_params) = Self::identity_account_filter(&conn, identity_id)?;
+        if account_params.is_empty() {
             return Ok(vec![]);
         }
 
-        // Build placeholders for IN clause
-        let placeholders: Vec<String> = platform_user_ids.iter().enumerate()
-            .map(|(i, _)| format!("?{}", i + 1))
-            .collect();
-
         let query = format!(
             "SELECT DISTINCT cs.id, cs.session_key, cs.agent_id, cs.scope, cs.channel_type, cs.channel_id,
                     cs.platform_chat_id, cs.is_active, cs.reset_policy, cs.idle_timeout_minutes,
@@ -202,10 +203,10 @@ impl Database {
                     cs.context_tokens, cs.max_context_tokens, cs.compaction_id, cs.completion_status, cs.safe_mode
              FROM chat_sessions cs
              INNER JOIN session_messages sm ON sm.session_id = cs.id
-             WHERE sm.user_id IN ({})
+             WHERE {}
              ORDER BY cs.last_activity_at DESC
              LIMIT 100",
-            placeholders.join(", ")
+            account_filter
         );
 
         let mut stmt = conn.prepare(&query)?;
@@ -213,7 +214,7 @@ impl Database {
         use crate::models::{ChatSession, CompletionStatus, ResetPolicy, SessionScope};
 
         let sessions = stmt
-            .query_map(rusqlite::params_from_iter(platform_user_ids.iter()), |row| {
+            .query_map(rusqlite::params_from_iter(account_params.iter()), |row| {
                 let created_at_str: String = row.get(11)?;
                 let updated_at_str: String = row.get(12)?;
                 let last_activity_str: String = row.get(13)?;
@@ -267,35 +268,23 @@ impl Database {
     pub fn get_tool_stats_for_identity(&self, identity_id: &str) -> SqliteResult<Vec<(String, i64, i64)>> {
         let conn = self.conn();
 
-        // Get all platform_user_ids for this identity
-        let mut stmt = conn.prepare(
-            "SELECT platform_user_id FROM identity_links WHERE identity_id = ?1"
-        )?;
-        let platform_user_ids: Vec<String> = stmt
-            .query_map([identity_id], |row| row.get(0))?
-            .filter_map(|r| r.ok())
-            .collect();
-        drop(stmt);
-
-        if platform_user_ids.is_empty() {
+        let (account_filter, account_params) = Self::identity_account_filter(&conn, identity_id)?;
+        if account_params.is_empty() {
             return Ok(vec![]);
         }
 
         // Get session IDs for this identity
-        let placeholders: Vec<String> = platform_user_ids.iter().enumerate()
-            .map(|(i, _)| format!("?{}", i + 1))
-            .collect();
-
         let query = format!(
             "SELECT DISTINCT sm.session_id
              FROM session_messages sm
-             WHERE sm.user_id IN ({})",
-            placeholders.join(", ")
+             INNER JOIN chat_sessions cs ON cs.id = sm.session_id
+             WHERE {}",
+            account_filter
         );
 
         let mut stmt = conn.prepare(&query)?;
         let session_ids: Vec<i64> = stmt
-            .query_map(rusqlite::params_from_iter(platform_user_ids.iter()), |row| row.get(0))?
+            .query_map(rusqlite::params_from_iter(account_params.iter()), |row| row.get(0))?
             .filter_map(|r| r.ok())
             .collect();
         drop(stmt);
@@ -337,35 +326,23 @@ impl Database {
     ) -> SqliteResult<Vec<crate::tools::ToolExecution>> {
         let conn = self.conn();
 
-        // Get all platform_user_ids for this identity
-        let mut stmt = conn.prepare(
-            "SELECT platform_user_id FROM identity_links WHERE identity_id = ?1"
-        )?;
-        let platform_user_ids: Vec<String> = stmt
-            .query_map([identity_id], |row| row.get(0))?
-            .filter_map(|r| r.ok())
-            .collect();
-        drop(stmt);
-
-        if platform_user_ids.is_empty() {
+        let (account_filter, account_params) = Self::identity_account_filter(&conn, identity_id)?;
+        if account_params.is_empty() {
             return Ok(vec![]);
         }
 
         // Get session IDs for this identity
-        let placeholders: Vec<String> = platform_user_ids.iter().enumerate()
-            .map(|(i, _)| format!("?{}", i + 1))
-            .collect();
-
         let query = format!(
             "SELECT DISTINCT sm.session_id
              FROM session_messages sm
-             WHERE sm.user_id IN ({})",
-            placeholders.join(", ")
+             INNER JOIN chat_sessions cs ON cs.id = sm.session_id
+             WHERE {}",
+            account_filter
         );
 
         let mut stmt = conn.prepare(&query)?;
         let session_ids: Vec<i64> = stmt
-            .query_map(rusqlite::params_from_iter(platform_user_ids.iter()), |row| row.get(0))?
+            .query_map(rusqlite::params_from_iter(account_params.iter()), |row| row.get(0))?
             .filter_map(|r| r.ok())
             .collect();
         drop(stmt);
@@ -416,4 +393,206 @@ impl Database {
 
         Ok(executions)
     }
+
+    /// SQL filter matching session messages from any account of an identity, as
+    /// `(cs.channel_type = ? AND sm.user_id = ?) OR ...` with its parameters.
+    /// Matching on the channel type too keeps equal user IDs on different platforms apart.
+    fn identity_account_filter(
+        conn: &rusqlite::Connection,
+        identity_id: &str,
+    ) -> SqliteResult<(String, Vec<String>)> {
+        let mut stmt = conn.prepare(
+            "SELECT channel_type, platform_user_id FROM identity_links WHERE identity_id = ?1",
+        )?;
+        let accounts: Vec<(String, String)> = stmt
+            .query_map([identity_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let clauses: Vec<String> = (0..accounts.len())
+            .map(|i| format!("(cs.channel_type = ?{} AND sm.user_id = ?{})", i * 2 + 1, i * 2 + 2))
+            .collect();
+        let params = accounts
+            .into_iter()
+            .flat_map(|(channel_type, user_id)| [channel_type, user_id])
+            .collect();
+        Ok((clauses.join(" OR "), params))
+    }
+
+    /// Move an account (and every account already sharing its identity) into another identity
+    /// and mark the account verified. Memory rows follow the accounts.
+    pub fn join_identity(
+        &self,
+        channel_type: &str,
+        platform_user_id: &str,
+        platform_user_name: Option<&str>,
+        into_identity_id: &str,
+        verification_method: &str,
+    ) -> SqliteResult<IdentityLink> {
+        let current = self.get_or_create_identity(channel_type, platform_user_id, platform_user_name)?;
+        {
+            let conn = self.conn();
+            let tx = conn.unchecked_transaction()?;
+            let now = Utc::now().to_rfc3339();
+            if current.identity_id != into_identity_id {
+                tx.execute(
+                    "UPDATE identity_links SET identity_id = ?1, updated_at = ?2 WHERE identity_id = ?3",
+                    rusqlite::params![into_identity_id, &now, &current.identity_id],
+                )?;
+                tx.execute(
+                    "UPDATE memories SET identity_id = ?1 WHERE identity_id = ?2",
+                    rusqlite::params![into_identity_id, &current.identity_id],
+                )?;
+            }
+            tx.execute(
+                "UPDATE identity_links SET is_verified = 1, verified_at = ?1, verification_method = ?2, updated_at = ?1
+                 WHERE channel_type = ?3 AND platform_user_id = ?4",
+                rusqlite::params![&now, verification_method, channel_type, platform_user_id],
+            )?;
+            tx.commit()?;
+        }
+        if current.identity_id != into_identity_id {
+            log::info!(
+                "[IDENTITY] Merged identity {} into {} ({}:{} verified by {})",
+                current.identity_id, into_identity_id, channel_type, platform_user_id, verification_method
+            );
+        }
+        self.get_identity_by_platform(channel_type, platform_user_id)?
+            .ok_or(rusqlite::Error::QueryReturnedNoRows)
+    }
+
+    /// Mark an account's link verified (keeps its identity)
+    pub fn mark_identity_verified(
+        &self,
+        channel_type: &str,
+        platform_user_id: &str,
+        verification_method: &str,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE identity_links SET is_verified = 1, verified_at = ?1, verification_method = ?2, updated_at = ?1
+             WHERE channel_type = ?3 AND platform_user_id = ?4",
+            rusqlite::params![&now, verification_method, channel_type, platform_user_id],
+        )?;
+        Ok(())
+    }
+
+    /// Start (or restart) a pending verification for an account. One per account and kind.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_identity_verification(
+        &self,
+        kind: VerificationKind,
+        channel_type: &str,
+        platform_user_id: &str,
+        target_channel_type: &str,
+        target_user_id: &str,
+        secret: &str,
+        expires_at: DateTime<Utc>,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR REPLACE INTO identity_verifications
+                (kind, channel_type, platform_user_id, target_channel_type, target_user_id, secret, attempts, expires_at, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, ?7, ?8)",
+            rusqlite::params![
+                kind.as_str(),
+                channel_type,
+                platform_user_id,
+                target_channel_type,
+                target_user_id,
+                secret,
+                expires_at.to_rfc3339(),
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Pending verification started by an account
+    pub fn get_identity_verification(
+        &self,
+        kind: VerificationKind,
+        channel_type: &str,
+        platform_user_id: &str,
+    ) -> SqliteResult<Option<IdentityVerification>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, channel_type, platform_user_id, target_channel_type, target_user_id, secret, attempts, expires_at, created_at
+             FROM identity_verifications WHERE kind = ?1 AND channel_type = ?2 AND platform_user_id = ?3",
+        )?;
+        let verification = stmt
+            .query_row(
+                rusqlite::params![kind.as_str(), channel_type, platform_user_id],
+                Self::row_to_identity_verification,
+            )
+            .ok();
+        Ok(verification)
+    }
+
+    /// Unexpired code verifications that name this account as their target
+    pub fn list_identity_verifications_for_target(
+        &self,
+        target_channel_type: &str,
+        target_user_id: &str,
+    ) -> SqliteResult<Vec<IdentityVerification>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, channel_type, platform_user_id, target_channel_type, target_user_id, secret, attempts, expires_at, created_at
+             FROM identity_verifications
+             WHERE kind = ?1 AND target_channel_type = ?2 AND target_user_id = ?3 AND expires_at > ?4",
+        )?;
+        let verifications = stmt
+            .query_map(
+                rusqlite::params![
+                    VerificationKind::Code.as_str(),
+                    target_channel_type,
+                    target_user_id,
+                    Utc::now().to_rfc3339()
+                ],
+                Self::row_to_identity_verification,
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(verifications)
+    }
+
+    /// Count a failed attempt against a verification, returning the new count
+    pub fn record_identity_verification_attempt(&self, id: i64) -> SqliteResult<i32> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE identity_verifications SET attempts = attempts + 1 WHERE id = ?1",
+            [id],
+        )?;
+        conn.query_row("SELECT attempts FROM identity_verifications WHERE id = ?1", [id], |row| row.get(0))
+    }
+
+    /// Delete a verification once used or exhausted
+    pub fn delete_identity_verification(&self, id: i64) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM identity_verifications WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    fn row_to_identity_verification(row: &rusqlite::Row) -> rusqlite::Result<IdentityVerification> {
+        let kind_str: String = row.get(1)?;
+        let expires_at_str: String = row.get(8)?;
+        let created_at_str: String = row.get(9)?;
+        Ok(IdentityVerification {
+            id: row.get(0)?,
+            kind: VerificationKind::from_str(&kind_str).unwrap_or(VerificationKind::Code),
+            channel_type: row.get(2)?,
+            platform_user_id: row.get(3)?,
+            target_channel_type: row.get(4)?,
+            target_user_id: row.get(5)?,
+            secret: row.get(6)?,
+            attempts: row.get(7)?,
+            expires_at: DateTime::parse_from_rfc3339(&expires_at_str)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            created_at: DateTime::parse_from_rfc3339(&created_at_str)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    }
 }
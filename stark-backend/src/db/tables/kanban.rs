@@ -1,11 +1,29 @@
 //! Kanban board database operations (kanban_items)
+//!
+//! Items can be blocked by other items (`blocked_by`), retried with backoff after
+//! a failed auto-execution (`max_retries`), and carry their own run timeout and
+//! agent subtype. A task whose retries run out ends up in the `failed` column.
 
-use chrono::{DateTime, Utc};
-use rusqlite::Result as SqliteResult;
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{OptionalExtension, Result as SqliteResult};
 use serde::{Deserialize, Serialize};
 
 use super::super::Database;
 
+/// Board columns an item can be in
+pub const KANBAN_STATUSES: &[&str] = &["ready", "in_progress", "complete", "failed"];
+
+/// Delay before the first retry of a failed task (doubles each attempt)
+const RETRY_BASE_DELAY_SECS: i64 = 60;
+
+/// Longest delay between retries
+const RETRY_MAX_DELAY_SECS: i64 = 3600;
+
+const KANBAN_COLUMNS: &str = "id, title, description, status, priority, session_id, result, created_at, updated_at,
+                 blocked_by, max_retries, attempts, next_attempt_at, timeout_seconds, agent_subtype";
+
 /// A kanban board item
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KanbanItem {
@@ -18,14 +36,31 @@ pub struct KanbanItem {
     pub result: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Items that must be complete before this one is picked
+    pub blocked_by: Vec<i64>,
+    /// Retries allowed after a failed auto-execution
+    pub max_retries: i32,
+    /// Failed auto-executions so far
+    pub attempts: i32,
+    /// Earliest time a retry may be picked
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    /// Run timeout (None = scheduler default)
+    pub timeout_seconds: Option<i64>,
+    /// Agent subtype to run the task as (None = session default)
+    pub agent_subtype: Option<String>,
 }
 
 /// Request to create a new kanban item
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct CreateKanbanItemRequest {
     pub title: String,
     pub description: Option<String>,
     pub priority: Option<i32>,
+    #[serde(default)]
+    pub blocked_by: Vec<i64>,
+    pub max_retries: Option<i32>,
+    pub timeout_seconds: Option<i64>,
+    pub agent_subtype: Option<String>,
 }
 
 /// Request to update a kanban item
@@ -37,6 +72,59 @@ pub struct UpdateKanbanItemRequest {
     pub priority: Option<i32>,
     pub session_id: Option<i64>,
     pub result: Option<String>,
+    pub blocked_by: Option<Vec<i64>>,
+    pub max_retries: Option<i32>,
+    /// 0 clears the timeout
+    pub timeout_seconds: Option<i64>,
+    /// Empty string clears the subtype
+    pub agent_subtype: Option<String>,
+}
+
+/// Check the fields shared by create and update requests
+pub fn validate_kanban_options(
+    max_retries: Option<i32>,
+    timeout_seconds: Option<i64>,
+    agent_subtype: Option<&str>,
+) -> Result<(), String> {
+    if let Some(retries) = max_retries
+        && !(0..=10).contains(&retries)
+    {
+        return Err("max_retries must be between 0 and 10".to_string());
+    }
+    if let Some(secs) = timeout_seconds
+        && secs < 0
+    {
+        return Err("timeout_seconds cannot be negative".to_string());
+    }
+    if let Some(subtype) = agent_subtype.filter(|s| !s.is_empty())
+        && crate::ai::multi_agent::types::AgentSubtype::from_str(subtype).is_none()
+    {
+        return Err(format!("Unknown agent subtype '{}'", subtype));
+    }
+    Ok(())
+}
+
+/// Delay before retry number `attempt` (1-based)
+pub fn kanban_retry_delay(attempt: i32) -> Duration {
+    let exp = (attempt.max(1) - 1).min(16) as u32;
+    Duration::seconds((RETRY_BASE_DELAY_SECS << exp).min(RETRY_MAX_DELAY_SECS))
+}
+
+/// Whether giving `item_id` these blockers would close a cycle in the dependency graph
+fn creates_dependency_cycle(edges: &HashMap<i64, Vec<i64>>, item_id: i64, blocked_by: &[i64]) -> bool {
+    let mut stack: Vec<i64> = blocked_by.to_vec();
+    let mut seen = HashSet::new();
+    while let Some(id) = stack.pop() {
+        if id == item_id {
+            return true;
+        }
+        if seen.insert(id)
+            && let Some(next) = edges.get(&id)
+        {
+            stack.extend(next.iter().copied());
+        }
+    }
+    false
 }
 
 impl Database {
@@ -46,11 +134,20 @@ impl Database {
         let now = Utc::now().to_rfc3339();
         let description = request.description.as_deref().unwrap_or("");
         let priority = request.priority.unwrap_or(0);
+        let max_retries = request.max_retries.unwrap_or(0);
+        let timeout_seconds = request.timeout_seconds.filter(|s| *s > 0);
+        let agent_subtype = request.agent_subtype.as_deref().filter(|s| !s.is_empty());
+        let blocked_by = if request.blocked_by.is_empty() {
+            None
+        } else {
+            serde_json::to_string(&request.blocked_by).ok()
+        };
 
         conn.execute(
-            "INSERT INTO kanban_items (title, description, priority, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?4)",
-            rusqlite::params![&request.title, description, priority, &now],
+            "INSERT INTO kanban_items (title, description, priority, created_at, updated_at,
+                 blocked_by, max_retries, timeout_seconds, agent_subtype)
+             VALUES (?1, ?2, ?3, ?4, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![&request.title, description, priority, &now, blocked_by, max_retries, timeout_seconds, agent_subtype],
         )?;
 
         let id = conn.last_insert_rowid();
@@ -68,16 +165,49 @@ impl Database {
             result: None,
             created_at,
             updated_at: created_at,
+            blocked_by: request.blocked_by.clone(),
+            max_retries,
+            attempts: 0,
+            next_attempt_at: None,
+            timeout_seconds,
+            agent_subtype: agent_subtype.map(|s| s.to_string()),
         })
     }
 
+    /// Check that blockers exist and don't form a cycle.
+    /// `item_id` is None for an item that hasn't been created yet.
+    pub fn check_kanban_dependencies(&self, item_id: Option<i64>, blocked_by: &[i64]) -> Result<(), String> {
+        if blocked_by.is_empty() {
+            return Ok(());
+        }
+        let edges: HashMap<i64, Vec<i64>> = self
+            .list_kanban_items()
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .map(|item| (item.id, item.blocked_by))
+            .collect();
+        for blocker in blocked_by {
+            if Some(*blocker) == item_id {
+                return Err("An item cannot block itself".to_string());
+            }
+            if !edges.contains_key(blocker) {
+                return Err(format!("Blocking item #{} not found", blocker));
+            }
+        }
+        if let Some(id) = item_id
+            && creates_dependency_cycle(&edges, id, blocked_by)
+        {
+            return Err("These dependencies would create a cycle".to_string());
+        }
+        Ok(())
+    }
+
     /// Get a kanban item by ID
     pub fn get_kanban_item(&self, id: i64) -> SqliteResult<Option<KanbanItem>> {
         let conn = self.conn();
         let item = conn
             .query_row(
-                "SELECT id, title, description, status, priority, session_id, result, created_at, updated_at
-                 FROM kanban_items WHERE id = ?1",
+                &format!("SELECT {} FROM kanban_items WHERE id = ?1", KANBAN_COLUMNS),
                 [id],
                 |row| Self::row_to_kanban_item(row),
             )
@@ -88,10 +218,10 @@ impl Database {
     /// List all kanban items ordered by priority DESC, created_at ASC
     pub fn list_kanban_items(&self) -> SqliteResult<Vec<KanbanItem>> {
         let conn = self.conn();
-        let mut stmt = conn.prepare(
-            "SELECT id, title, description, status, priority, session_id, result, created_at, updated_at
-             FROM kanban_items ORDER BY priority DESC, created_at ASC",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM kanban_items ORDER BY priority DESC, created_at ASC",
+            KANBAN_COLUMNS
+        ))?;
 
         let items = stmt
             .query_map([], |row| Self::row_to_kanban_item(row))?
@@ -104,10 +234,10 @@ impl Database {
     /// List kanban items filtered by status
     pub fn list_kanban_items_by_status(&self, status: &str) -> SqliteResult<Vec<KanbanItem>> {
         let conn = self.conn();
-        let mut stmt = conn.prepare(
-            "SELECT id, title, description, status, priority, session_id, result, created_at, updated_at
-             FROM kanban_items WHERE status = ?1 ORDER BY priority DESC, created_at ASC",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM kanban_items WHERE status = ?1 ORDER BY priority DESC, created_at ASC",
+            KANBAN_COLUMNS
+        ))?;
 
         let items = stmt
             .query_map([status], |row| Self::row_to_kanban_item(row))?
@@ -149,6 +279,27 @@ impl Database {
             updates.push(format!("result = ?{}", param_idx));
             param_idx += 1;
         }
+        if request.blocked_by.is_some() {
+            updates.push(format!("blocked_by = ?{}", param_idx));
+            param_idx += 1;
+        }
+        if request.max_retries.is_some() {
+            updates.push(format!("max_retries = ?{}", param_idx));
+            param_idx += 1;
+        }
+        if request.timeout_seconds.is_some() {
+            updates.push(format!("timeout_seconds = ?{}", param_idx));
+            param_idx += 1;
+        }
+        if request.agent_subtype.is_some() {
+            updates.push(format!("agent_subtype = ?{}", param_idx));
+            param_idx += 1;
+        }
+        // Moving an item back to ready starts its retry budget over
+        if request.status.as_deref() == Some("ready") {
+            updates.push("attempts = 0".to_string());
+            updates.push("next_attempt_at = NULL".to_string());
+        }
 
         let sql = format!(
             "UPDATE kanban_items SET {} WHERE id = ?{}",
@@ -175,6 +326,19 @@ impl Database {
         if let Some(ref result) = request.result {
             params.push(Box::new(result.clone()));
         }
+        if let Some(ref blocked_by) = request.blocked_by {
+            let value = if blocked_by.is_empty() { None } else { serde_json::to_string(blocked_by).ok() };
+            params.push(Box::new(value));
+        }
+        if let Some(max_retries) = request.max_retries {
+            params.push(Box::new(max_retries));
+        }
+        if let Some(secs) = request.timeout_seconds {
+            params.push(Box::new(Some(secs).filter(|s| *s > 0)));
+        }
+        if let Some(ref subtype) = request.agent_subtype {
+            params.push(Box::new(Some(subtype.clone()).filter(|s| !s.is_empty())));
+        }
         params.push(Box::new(id));
 
         let params_ref: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
@@ -191,34 +355,97 @@ impl Database {
         Ok(rows_affected > 0)
     }
 
-    /// Atomically pick the highest-priority ready task and move it to in_progress
+    /// Atomically pick the highest-priority ready task whose blockers are all
+    /// complete and whose retry wait (if any) has passed, and move it to in_progress
     pub fn pick_next_kanban_task(&self) -> SqliteResult<Option<KanbanItem>> {
         let conn = self.conn();
         let now = Utc::now().to_rfc3339();
 
-        // Find highest-priority ready item
-        let item_id: Option<i64> = conn
-            .query_row(
-                "SELECT id FROM kanban_items WHERE status = 'ready'
-                 ORDER BY priority DESC, created_at ASC LIMIT 1",
-                [],
-                |row| row.get(0),
-            )
-            .ok();
+        // Ready items in pick order; dependencies are checked below
+        let mut stmt = conn.prepare(
+            "SELECT id, blocked_by FROM kanban_items
+             WHERE status = 'ready' AND (next_attempt_at IS NULL OR next_attempt_at <= ?1)
+             ORDER BY priority DESC, created_at ASC",
+        )?;
+        let candidates: Vec<(i64, Vec<i64>)> = stmt
+            .query_map([&now], |row| {
+                let blocked_by: Option<String> = row.get(1)?;
+                Ok((row.get(0)?, parse_blocked_by(blocked_by)))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        drop(stmt);
 
-        let item_id = match item_id {
-            Some(id) => id,
-            None => return Ok(None),
+        for (item_id, blocked_by) in candidates {
+            if !Self::kanban_blockers_complete(&conn, &blocked_by)? {
+                continue;
+            }
+            let updated = conn.execute(
+                "UPDATE kanban_items SET status = 'in_progress', updated_at = ?1 WHERE id = ?2 AND status = 'ready'",
+                rusqlite::params![&now, item_id],
+            )?;
+            if updated == 0 {
+                // Picked by someone else in the meantime
+                continue;
+            }
+            drop(conn);
+            return self.get_kanban_item(item_id);
+        }
+        Ok(None)
+    }
+
+    /// Record a failed auto-execution: back to ready after a backoff delay while
+    /// retries remain, otherwise to failed
+    pub fn record_kanban_task_failure(&self, id: i64, error: &str) -> SqliteResult<Option<KanbanItem>> {
+        let Some(item) = self.get_kanban_item(id)? else {
+            return Ok(None);
+        };
+        let attempts = item.attempts + 1;
+        let now = Utc::now();
+        let (status, next_attempt_at) = if attempts <= item.max_retries {
+            ("ready", Some((now + kanban_retry_delay(attempts)).to_rfc3339()))
+        } else {
+            ("failed", None)
         };
 
-        // Atomically update to in_progress
+        let conn = self.conn();
         conn.execute(
-            "UPDATE kanban_items SET status = 'in_progress', updated_at = ?1 WHERE id = ?2 AND status = 'ready'",
-            rusqlite::params![&now, item_id],
+            "UPDATE kanban_items SET status = ?1, attempts = ?2, next_attempt_at = ?3, result = ?4, updated_at = ?5
+             WHERE id = ?6",
+            rusqlite::params![status, attempts, next_attempt_at, format!("Error: {}", error), now.to_rfc3339(), id],
         )?;
-
         drop(conn);
-        self.get_kanban_item(item_id)
+        self.get_kanban_item(id)
+    }
+
+    /// Ready items that were waiting on `id` and have no other incomplete blockers
+    pub fn list_unblocked_kanban_dependents(&self, id: i64) -> SqliteResult<Vec<KanbanItem>> {
+        let dependents: Vec<KanbanItem> = self
+            .list_kanban_items_by_status("ready")?
+            .into_iter()
+            .filter(|item| item.blocked_by.contains(&id))
+            .collect();
+        let conn = self.conn();
+        let mut unblocked = Vec::new();
+        for item in dependents {
+            if Self::kanban_blockers_complete(&conn, &item.blocked_by)? {
+                unblocked.push(item);
+            }
+        }
+        Ok(unblocked)
+    }
+
+    /// Whether every blocker is complete (deleted blockers no longer block)
+    fn kanban_blockers_complete(conn: &rusqlite::Connection, blocked_by: &[i64]) -> SqliteResult<bool> {
+        for blocker in blocked_by {
+            let status: Option<String> = conn
+                .query_row("SELECT status FROM kanban_items WHERE id = ?1", [blocker], |row| row.get(0))
+                .optional()?;
+            if matches!(status.as_deref(), Some(s) if s != "complete") {
+                return Ok(false);
+            }
+        }
+        Ok(true)
     }
 
     fn row_to_kanban_item(row: &rusqlite::Row) -> rusqlite::Result<KanbanItem> {
@@ -239,6 +466,44 @@ impl Database {
             updated_at: DateTime::parse_from_rfc3339(&updated_at_str)
                 .unwrap()
                 .with_timezone(&Utc),
+            blocked_by: parse_blocked_by(row.get(9)?),
+            max_retries: row.get(10)?,
+            attempts: row.get(11)?,
+            next_attempt_at: row
+                .get::<_, Option<String>>(12)?
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            timeout_seconds: row.get(13)?,
+            agent_subtype: row.get(14)?,
         })
     }
 }
+
+fn parse_blocked_by(value: Option<String>) -> Vec<i64> {
+    value
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off_to_cap() {
+        assert_eq!(kanban_retry_delay(1), Duration::seconds(60));
+        assert_eq!(kanban_retry_delay(2), Duration::seconds(120));
+        assert_eq!(kanban_retry_delay(4), Duration::seconds(480));
+        assert_eq!(kanban_retry_delay(10), Duration::seconds(RETRY_MAX_DELAY_SECS));
+    }
+
+    #[test]
+    fn test_dependency_cycle_detection() {
+        // 2 is blocked by 1, 3 is blocked by 2
+        let edges: HashMap<i64, Vec<i64>> = [(1, vec![]), (2, vec![1]), (3, vec![2])].into_iter().collect();
+        assert!(creates_dependency_cycle(&edges, 1, &[3]));
+        assert!(creates_dependency_cycle(&edges, 2, &[3]));
+        assert!(!creates_dependency_cycle(&edges, 3, &[1]));
+        assert!(!creates_dependency_cycle(&edges, 4, &[3, 1]));
+    }
+}
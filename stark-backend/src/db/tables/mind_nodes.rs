@@ -1,11 +1,64 @@
-//! Mind map database operations (mind_nodes, mind_node_connections)
+//! Mind map database operations (mind_nodes, mind_node_connections, mind_nodes_fts, mind_node_visits)
+//!
+//! Connections are typed: `part_of` forms the structural tree, `blocks` marks
+//! dependencies and `relates_to` links similar ideas (created automatically for
+//! new nodes, with the similarity stored as the edge weight). Node bodies are
+//! indexed in an FTS5 table kept in sync by triggers.
 
 use chrono::{DateTime, Utc};
 use rusqlite::Result as SqliteResult;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
 
+use crate::qmd_memory::store::escape_fts5_query;
 use super::super::Database;
 
+/// Maximum hops for a subgraph export
+pub const MAX_SUBGRAPH_HOPS: u32 = 5;
+/// Maximum nodes returned in a subgraph export
+pub const MAX_SUBGRAPH_NODES: usize = 200;
+/// Minimum term similarity for a new node to be auto-linked to an existing one
+pub const AUTO_LINK_MIN_SIMILARITY: f64 = 0.2;
+/// Maximum number of automatic links created for a new node
+pub const AUTO_LINK_MAX_LINKS: usize = 3;
+
+/// Relationship type of a mind map connection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MindEdgeType {
+    /// The child is part of the parent (the structural tree)
+    #[default]
+    PartOf,
+    /// The parent blocks progress on the child
+    Blocks,
+    /// Undirected association between related ideas
+    RelatesTo,
+}
+
+impl MindEdgeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MindEdgeType::PartOf => "part_of",
+            MindEdgeType::Blocks => "blocks",
+            MindEdgeType::RelatesTo => "relates_to",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "part_of" | "partof" | "child" => Some(MindEdgeType::PartOf),
+            "blocks" | "blocked_by" => Some(MindEdgeType::Blocks),
+            "relates_to" | "relatesto" | "related" => Some(MindEdgeType::RelatesTo),
+            _ => None,
+        }
+    }
+
+    /// Directed edge types must stay acyclic; `relates_to` is symmetric
+    pub fn is_directed(&self) -> bool {
+        !matches!(self, MindEdgeType::RelatesTo)
+    }
+}
+
 /// A node in the mind map
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MindNode {
@@ -24,9 +77,43 @@ pub struct MindNodeConnection {
     pub id: i64,
     pub parent_id: i64,
     pub child_id: i64,
+    #[serde(default)]
+    pub edge_type: MindEdgeType,
+    /// Similarity score for automatic links (None for manual connections)
+    #[serde(default)]
+    pub weight: Option<f64>,
     pub created_at: DateTime<Utc>,
 }
 
+/// A full-text search hit
+#[derive(Debug, Clone, Serialize)]
+pub struct MindNodeSearchHit {
+    pub node: MindNode,
+    /// Matching excerpt with `>>>` / `<<<` around matched terms
+    pub snippet: String,
+    /// BM25 score (lower is better)
+    pub score: f64,
+}
+
+/// A node in a subgraph export with its distance from the center
+#[derive(Debug, Clone, Serialize)]
+pub struct MindSubgraphNode {
+    #[serde(flatten)]
+    pub node: MindNode,
+    pub distance: u32,
+}
+
+/// Neighborhood of a node within N hops
+#[derive(Debug, Clone, Serialize)]
+pub struct MindSubgraph {
+    pub center_id: i64,
+    pub hops: u32,
+    pub nodes: Vec<MindSubgraphNode>,
+    pub connections: Vec<MindNodeConnection>,
+    /// True if the node cap was reached before all hops were explored
+    pub truncated: bool,
+}
+
 /// Request to create a new mind node
 #[derive(Debug, Deserialize)]
 pub struct CreateMindNodeRequest {
@@ -207,7 +294,7 @@ impl Database {
         self.get_mind_node(id)
     }
 
-    /// Delete a mind node (also deletes connections via CASCADE)
+    /// Delete a mind node along with its connections and visit history
     pub fn delete_mind_node(&self, id: i64) -> SqliteResult<bool> {
         let conn = self.conn();
 
@@ -221,6 +308,11 @@ impl Database {
         }
 
         let rows_affected = conn.execute("DELETE FROM mind_nodes WHERE id = ?1", [id])?;
+        conn.execute(
+            "DELETE FROM mind_node_connections WHERE parent_id = ?1 OR child_id = ?1",
+            [id],
+        )?;
+        conn.execute("DELETE FROM mind_node_visits WHERE node_id = ?1", [id])?;
         Ok(rows_affected > 0)
     }
 
@@ -228,7 +320,7 @@ impl Database {
     pub fn list_mind_node_connections(&self) -> SqliteResult<Vec<MindNodeConnection>> {
         let conn = self.conn();
         let mut stmt = conn.prepare(
-            "SELECT id, parent_id, child_id, created_at FROM mind_node_connections ORDER BY created_at ASC",
+            "SELECT id, parent_id, child_id, edge_type, weight, created_at FROM mind_node_connections ORDER BY created_at ASC",
         )?;
 
         let connections = stmt
@@ -239,9 +331,23 @@ impl Database {
         Ok(connections)
     }
 
-    /// Create a connection between two nodes
+    /// Create a structural (`part_of`) connection between two nodes
     /// Returns error if the connection would create a cycle
     pub fn create_mind_node_connection(&self, parent_id: i64, child_id: i64) -> SqliteResult<MindNodeConnection> {
+        self.create_typed_mind_node_connection(parent_id, child_id, MindEdgeType::PartOf, None)
+    }
+
+    /// Create a typed connection between two nodes.
+    /// Directed types (`part_of`, `blocks`) are rejected if they would create a
+    /// cycle among edges of the same type; `relates_to` is rejected if the
+    /// reverse link already exists.
+    pub fn create_typed_mind_node_connection(
+        &self,
+        parent_id: i64,
+        child_id: i64,
+        edge_type: MindEdgeType,
+        weight: Option<f64>,
+    ) -> SqliteResult<MindNodeConnection> {
         let conn = self.conn();
 
         // Prevent self-loops
@@ -251,32 +357,45 @@ impl Database {
             ));
         }
 
-        // Check if adding this connection would create a cycle
-        // A cycle exists if child_id can already reach parent_id through existing connections
-        let would_create_cycle: bool = conn.query_row(
-            "WITH RECURSIVE reachable(node_id) AS (
-                SELECT ?1
-                UNION
-                SELECT c.parent_id FROM reachable r
-                JOIN mind_node_connections c ON c.child_id = r.node_id
-                WHERE r.node_id != ?2
-            )
-            SELECT EXISTS(SELECT 1 FROM reachable WHERE node_id = ?2)",
-            rusqlite::params![child_id, parent_id],
-            |row| row.get(0),
-        ).unwrap_or(false);
-
-        if would_create_cycle {
-            return Err(rusqlite::Error::InvalidParameterName(
-                "Connection would create a cycle in the mind graph".to_string(),
-            ));
+        if edge_type.is_directed() {
+            // A cycle exists if child_id can already reach parent_id through existing connections of this type
+            let would_create_cycle: bool = conn.query_row(
+                "WITH RECURSIVE reachable(node_id) AS (
+                    SELECT ?1
+                    UNION
+                    SELECT c.parent_id FROM reachable r
+                    JOIN mind_node_connections c ON c.child_id = r.node_id AND c.edge_type = ?3
+                    WHERE r.node_id != ?2
+                )
+                SELECT EXISTS(SELECT 1 FROM reachable WHERE node_id = ?2)",
+                rusqlite::params![child_id, parent_id, edge_type.as_str()],
+                |row| row.get(0),
+            ).unwrap_or(false);
+
+            if would_create_cycle {
+                return Err(rusqlite::Error::InvalidParameterName(
+                    "Connection would create a cycle in the mind graph".to_string(),
+                ));
+            }
+        } else {
+            let reverse_exists: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM mind_node_connections WHERE parent_id = ?1 AND child_id = ?2)",
+                [child_id, parent_id],
+                |row| row.get(0),
+            ).unwrap_or(false);
+
+            if reverse_exists {
+                return Err(rusqlite::Error::InvalidParameterName(
+                    "Nodes are already connected".to_string(),
+                ));
+            }
         }
 
         let now = Utc::now().to_rfc3339();
 
         conn.execute(
-            "INSERT INTO mind_node_connections (parent_id, child_id, created_at) VALUES (?1, ?2, ?3)",
-            rusqlite::params![parent_id, child_id, &now],
+            "INSERT INTO mind_node_connections (parent_id, child_id, edge_type, weight, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![parent_id, child_id, edge_type.as_str(), weight, &now],
         )?;
 
         let id = conn.last_insert_rowid();
@@ -288,6 +407,8 @@ impl Database {
             id,
             parent_id,
             child_id,
+            edge_type,
+            weight,
             created_at,
         })
     }
@@ -312,6 +433,7 @@ impl Database {
 
         // Delete all non-trunk nodes
         let nodes_deleted = conn.execute("DELETE FROM mind_nodes WHERE is_trunk = 0", [])?;
+        conn.execute("DELETE FROM mind_node_visits", [])?;
 
         Ok((nodes_deleted, connections_deleted))
     }
@@ -368,11 +490,10 @@ impl Database {
 
     /// Get the next node for heartbeat meandering
     /// - If current_id is None, returns the trunk node (first heartbeat)
-    /// - Otherwise, randomly stays at current node (~40%) or hops to a neighbor (~60%)
-    /// - This naturally biases toward central nodes (more connections = more paths there)
-    pub fn get_next_heartbeat_node(&self, current_id: Option<i64>) -> SqliteResult<MindNode> {
-        use rand::Rng;
-
+    /// - Otherwise, scores the current node and its neighbors and returns the
+    ///   most relevant one (see `heartbeat_branch_score`). `focus` is recent
+    ///   conversation text used to judge relevance.
+    pub fn get_next_heartbeat_node(&self, current_id: Option<i64>, focus: Option<&str>) -> SqliteResult<MindNode> {
         // If no current position, start at trunk
         let current_id = match current_id {
             Some(id) => id,
@@ -383,8 +504,7 @@ impl Database {
         };
 
         // Get current node
-        let current_node = self.get_mind_node(current_id)?;
-        let current_node = match current_node {
+        let current_node = match self.get_mind_node(current_id)? {
             Some(n) => n,
             None => {
                 // Node was deleted, return to trunk
@@ -392,27 +512,228 @@ impl Database {
             }
         };
 
-        // Get neighbors
-        let neighbors = self.get_mind_node_neighbors(current_id)?;
+        let edges = self.get_mind_node_edges(current_id)?;
 
         // If no neighbors, stay at current node
-        if neighbors.is_empty() {
+        if edges.is_empty() {
             return Ok(current_node);
         }
 
-        // Random decision: 10% stay, 90% hop (biases toward exploration)
-        let mut rng = rand::thread_rng();
+        let focus_terms = mind_terms(focus.unwrap_or(""));
+        let mut candidate_ids: Vec<i64> = edges.iter().map(|(n, _)| n.id).collect();
+        candidate_ids.push(current_node.id);
+        let visits = self.get_mind_node_last_visits(&candidate_ids)?;
+        let now = Utc::now();
 
-        // gen_bool(p) returns true with probability p
-        // We want 10% chance to stay, so gen_bool(0.1) = true means stay
-        if rng.gen_bool(0.1) {
-            // Stay at current node
-            Ok(current_node)
-        } else {
-            // Hop to a random neighbor
-            let idx = rng.gen_range(0..neighbors.len());
-            Ok(neighbors.into_iter().nth(idx).unwrap())
+        let mut best = (
+            heartbeat_branch_score(&current_node, None, &focus_terms, visits.get(&current_node.id).copied(), now),
+            current_node,
+        );
+        for (node, edge) in edges {
+            let score = heartbeat_branch_score(&node, Some(edge.edge_type), &focus_terms, visits.get(&node.id).copied(), now);
+            if score > best.0 || (score == best.0 && node.id < best.1.id) {
+                best = (score, node);
+            }
+        }
+
+        log::debug!("[MINDMAP] Heartbeat selected node {} (score {:.2})", best.1.id, best.0);
+        Ok(best.1)
+    }
+
+    /// Record that a heartbeat visited a node
+    pub fn record_mind_node_visit(&self, node_id: i64) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO mind_node_visits (node_id, visit_count, last_visited_at) VALUES (?1, 1, ?2)
+             ON CONFLICT(node_id) DO UPDATE SET visit_count = visit_count + 1, last_visited_at = ?2",
+            rusqlite::params![node_id, &now],
+        )?;
+        Ok(())
+    }
+
+    /// Last heartbeat visit time for each of the given nodes (unvisited nodes are omitted)
+    fn get_mind_node_last_visits(&self, node_ids: &[i64]) -> SqliteResult<HashMap<i64, DateTime<Utc>>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT last_visited_at FROM mind_node_visits WHERE node_id = ?1")?;
+        let mut visits = HashMap::new();
+        for &id in node_ids {
+            let visited: Option<String> = stmt.query_row([id], |row| row.get(0)).ok();
+            if let Some(dt) = visited.and_then(|v| DateTime::parse_from_rfc3339(&v).ok()) {
+                visits.insert(id, dt.with_timezone(&Utc));
+            }
+        }
+        Ok(visits)
+    }
+
+    /// Get the neighbors of a node together with the connecting edge
+    pub fn get_mind_node_edges(&self, node_id: i64) -> SqliteResult<Vec<(MindNode, MindNodeConnection)>> {
+        let connections: Vec<MindNodeConnection> = {
+            let conn = self.conn();
+            let mut stmt = conn.prepare(
+                "SELECT id, parent_id, child_id, edge_type, weight, created_at
+                 FROM mind_node_connections WHERE parent_id = ?1 OR child_id = ?1
+                 ORDER BY id ASC",
+            )?;
+            stmt.query_map([node_id], Self::row_to_mind_connection)?
+                .filter_map(|r| r.ok())
+                .collect()
+        };
+
+        let mut edges = Vec::new();
+        for connection in connections {
+            let other = if connection.parent_id == node_id { connection.child_id } else { connection.parent_id };
+            if let Some(node) = self.get_mind_node(other)? {
+                edges.push((node, connection));
+            }
+        }
+        Ok(edges)
+    }
+
+    /// Full-text search over node bodies (BM25 ranked)
+    pub fn search_mind_nodes(&self, query: &str, limit: i32) -> SqliteResult<Vec<MindNodeSearchHit>> {
+        let escaped_query = escape_fts5_query(query);
+        if escaped_query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT n.id, n.body, n.position_x, n.position_y, n.is_trunk, n.created_at, n.updated_at,
+                    snippet(mind_nodes_fts, 0, '>>>', '<<<', '...', 32), bm25(mind_nodes_fts) AS score
+             FROM mind_nodes_fts
+             JOIN mind_nodes n ON n.id = mind_nodes_fts.rowid
+             WHERE mind_nodes_fts MATCH ?1
+             ORDER BY score
+             LIMIT ?2",
+        )?;
+
+        let hits = stmt
+            .query_map(rusqlite::params![escaped_query, limit], |row| {
+                Ok(MindNodeSearchHit {
+                    node: Self::row_to_mind_node(row)?,
+                    snippet: row.get(7)?,
+                    score: row.get(8)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(hits)
+    }
+
+    /// Export the neighborhood of a node within `hops` (undirected), optionally
+    /// following only the given edge types. Returns None if the node does not exist.
+    pub fn get_mind_subgraph(
+        &self,
+        center_id: i64,
+        hops: u32,
+        edge_types: Option<&[MindEdgeType]>,
+    ) -> SqliteResult<Option<MindSubgraph>> {
+        let center = match self.get_mind_node(center_id)? {
+            Some(n) => n,
+            None => return Ok(None),
+        };
+        let hops = hops.min(MAX_SUBGRAPH_HOPS);
+
+        let connections: Vec<MindNodeConnection> = self
+            .list_mind_node_connections()?
+            .into_iter()
+            .filter(|c| edge_types.map(|types| types.contains(&c.edge_type)).unwrap_or(true))
+            .collect();
+
+        let mut adjacency: HashMap<i64, Vec<i64>> = HashMap::new();
+        for c in &connections {
+            adjacency.entry(c.parent_id).or_default().push(c.child_id);
+            adjacency.entry(c.child_id).or_default().push(c.parent_id);
+        }
+
+        // BFS out to the hop limit
+        let mut distances: HashMap<i64, u32> = HashMap::new();
+        let mut order: Vec<i64> = vec![center_id];
+        let mut queue: VecDeque<(i64, u32)> = VecDeque::new();
+        distances.insert(center_id, 0);
+        queue.push_back((center_id, 0));
+        let mut truncated = false;
+
+        'bfs: while let Some((current, depth)) = queue.pop_front() {
+            if depth >= hops {
+                continue;
+            }
+            for &neighbor in adjacency.get(&current).map(|v| v.as_slice()).unwrap_or(&[]) {
+                if distances.contains_key(&neighbor) {
+                    continue;
+                }
+                if order.len() >= MAX_SUBGRAPH_NODES {
+                    truncated = true;
+                    break 'bfs;
+                }
+                distances.insert(neighbor, depth + 1);
+                order.push(neighbor);
+                queue.push_back((neighbor, depth + 1));
+            }
+        }
+
+        let mut nodes = vec![MindSubgraphNode { node: center, distance: 0 }];
+        for &id in order.iter().skip(1) {
+            if let Some(node) = self.get_mind_node(id)? {
+                nodes.push(MindSubgraphNode { node, distance: distances[&id] });
+            }
+        }
+
+        let included: HashSet<i64> = nodes.iter().map(|n| n.node.id).collect();
+        let connections = connections
+            .into_iter()
+            .filter(|c| included.contains(&c.parent_id) && included.contains(&c.child_id))
+            .collect();
+
+        Ok(Some(MindSubgraph {
+            center_id,
+            hops,
+            nodes,
+            connections,
+            truncated,
+        }))
+    }
+
+    /// Link a node to the most similar existing nodes with `relates_to` edges.
+    /// Candidates come from full-text search and are ranked by term similarity;
+    /// the trunk and nodes already connected to this one are skipped.
+    pub fn auto_link_mind_node(&self, node_id: i64) -> SqliteResult<Vec<MindNodeConnection>> {
+        let node = match self.get_mind_node(node_id)? {
+            Some(n) if !n.body.trim().is_empty() => n,
+            _ => return Ok(Vec::new()),
+        };
+
+        let connected: HashSet<i64> = self
+            .get_mind_node_edges(node_id)?
+            .into_iter()
+            .map(|(n, _)| n.id)
+            .collect();
+
+        let mut candidates: Vec<(f64, MindNode)> = self
+            .search_mind_nodes(&node.body, 20)?
+            .into_iter()
+            .map(|hit| hit.node)
+            .filter(|n| n.id != node_id && !n.is_trunk && !connected.contains(&n.id))
+            .map(|n| (term_similarity(&node.body, &n.body), n))
+            .filter(|(score, _)| *score >= AUTO_LINK_MIN_SIMILARITY)
+            .collect();
+        candidates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut links = Vec::new();
+        for (score, other) in candidates.into_iter().take(AUTO_LINK_MAX_LINKS) {
+            let weight = (score * 100.0).round() / 100.0;
+            match self.create_typed_mind_node_connection(other.id, node_id, MindEdgeType::RelatesTo, Some(weight)) {
+                Ok(link) => links.push(link),
+                Err(e) => log::debug!("[MINDMAP] Skipped auto-link {} → {}: {}", other.id, node_id, e),
+            }
+        }
+
+        if !links.is_empty() {
+            log::info!("[MINDMAP] Auto-linked node {} to {} similar node(s)", node_id, links.len());
         }
+        Ok(links)
     }
 
     /// Calculate the depth (distance from trunk) of a node
@@ -506,15 +827,146 @@ impl Database {
     }
 
     fn row_to_mind_connection(row: &rusqlite::Row) -> rusqlite::Result<MindNodeConnection> {
-        let created_at_str: String = row.get(3)?;
+        let edge_type_str: String = row.get(3)?;
+        let created_at_str: String = row.get(5)?;
 
         Ok(MindNodeConnection {
             id: row.get(0)?,
             parent_id: row.get(1)?,
             child_id: row.get(2)?,
+            edge_type: MindEdgeType::from_str(&edge_type_str).unwrap_or_default(),
+            weight: row.get(4)?,
             created_at: DateTime::parse_from_rfc3339(&created_at_str)
                 .unwrap()
                 .with_timezone(&Utc),
         })
     }
 }
+
+/// Words too common to say anything about a node's topic
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "with", "that", "this", "from", "into", "are", "was", "were", "has",
+    "have", "had", "not", "but", "you", "your", "our", "its", "can", "will", "should", "about",
+    "what", "when", "which", "who", "how", "all", "any", "more", "some", "than", "then", "there",
+];
+
+/// Normalized content terms of a text (lowercase, 3+ chars, no stopwords)
+pub(crate) fn mind_terms(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(|w| w.to_lowercase())
+        .filter(|w| w.chars().count() >= 3 && !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Jaccard similarity of the content terms of two texts (0.0–1.0)
+pub(crate) fn term_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (mind_terms(a), mind_terms(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let intersection = a.intersection(&b).count() as f64;
+    let union = a.union(&b).count() as f64;
+    intersection / union
+}
+
+/// Score a candidate node for the next heartbeat visit (higher is better):
+/// - relevance: share of the node's terms that appear in recent conversation (×2)
+/// - staleness: time since the last visit, saturating after a day (never visited = 1)
+/// - `blocks` edges get a small bonus since blockers hold up other work
+/// - empty nodes are penalized
+pub(crate) fn heartbeat_branch_score(
+    node: &MindNode,
+    edge_type: Option<MindEdgeType>,
+    focus_terms: &HashSet<String>,
+    last_visited: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> f64 {
+    let terms = mind_terms(&node.body);
+    let relevance = if terms.is_empty() || focus_terms.is_empty() {
+        0.0
+    } else {
+        terms.intersection(focus_terms).count() as f64 / terms.len() as f64
+    };
+
+    let staleness = last_visited
+        .map(|t| ((now - t).num_minutes() as f64 / (24.0 * 60.0)).clamp(0.0, 1.0))
+        .unwrap_or(1.0);
+
+    let edge_bonus = match edge_type {
+        Some(MindEdgeType::Blocks) => 0.25,
+        _ => 0.0,
+    };
+
+    let empty_penalty = if node.body.trim().is_empty() { 0.5 } else { 0.0 };
+
+    2.0 * relevance + staleness + edge_bonus - empty_penalty
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(db: &Database, body: &str, parent_id: Option<i64>) -> MindNode {
+        db.create_mind_node(&CreateMindNodeRequest {
+            body: Some(body.to_string()),
+            position_x: None,
+            position_y: None,
+            parent_id,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_search_auto_link_and_subgraph() {
+        let db = Database::new(":memory:").unwrap();
+        let trunk = db.get_or_create_trunk_node().unwrap();
+        let defi = create(&db, "DeFi yield strategies on Base", Some(trunk.id));
+        let lp = create(&db, "Uniswap liquidity positions", Some(defi.id));
+        let music = create(&db, "Learn jazz piano", Some(trunk.id));
+
+        let hits = db.search_mind_nodes("liquidity", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].node.id, lp.id);
+
+        let new = create(&db, "Base yield strategies for stablecoins", None);
+        let links = db.auto_link_mind_node(new.id).unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].parent_id, defi.id);
+        assert_eq!(links[0].edge_type, MindEdgeType::RelatesTo);
+        assert!(links[0].weight.unwrap() >= AUTO_LINK_MIN_SIMILARITY);
+
+        // relates_to is symmetric: the reverse link is rejected
+        assert!(db
+            .create_typed_mind_node_connection(new.id, defi.id, MindEdgeType::RelatesTo, None)
+            .is_err());
+
+        let one_hop = db.get_mind_subgraph(defi.id, 1, None).unwrap().unwrap();
+        let ids: HashSet<i64> = one_hop.nodes.iter().map(|n| n.node.id).collect();
+        assert!(ids.contains(&trunk.id) && ids.contains(&lp.id) && ids.contains(&new.id));
+        assert!(!ids.contains(&music.id));
+
+        let two_hops = db.get_mind_subgraph(defi.id, 2, Some(&[MindEdgeType::PartOf])).unwrap().unwrap();
+        let ids: HashSet<i64> = two_hops.nodes.iter().map(|n| n.node.id).collect();
+        assert!(ids.contains(&music.id));
+        assert!(!ids.contains(&new.id), "relates_to edges are filtered out");
+    }
+
+    #[test]
+    fn test_heartbeat_prefers_relevant_unvisited_branch() {
+        let db = Database::new(":memory:").unwrap();
+        let trunk = db.get_or_create_trunk_node().unwrap();
+        let swaps = create(&db, "Review failed token swaps", Some(trunk.id));
+        let garden = create(&db, "Plan the garden", Some(trunk.id));
+        db.record_mind_node_visit(trunk.id).unwrap();
+
+        let next = db
+            .get_next_heartbeat_node(Some(trunk.id), Some("why did my token swaps fail yesterday?"))
+            .unwrap();
+        assert_eq!(next.id, swaps.id);
+
+        // Once visited, the other branch becomes the better choice
+        db.record_mind_node_visit(swaps.id).unwrap();
+        let next = db.get_next_heartbeat_node(Some(trunk.id), None).unwrap();
+        assert_eq!(next.id, garden.id);
+    }
+}
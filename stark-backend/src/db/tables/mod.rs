@@ -9,18 +9,28 @@ mod channel_settings; // channel_settings (per-channel config)
 mod agent_settings; // agent_settings
 mod bot_settings;   // bot_settings
 mod chat_sessions;  // chat_sessions, session_messages (+ compaction)
-mod identities;     // identity_links
+mod identities;     // identity_links, identity_verifications
 mod tool_configs;   // tool_configs, tool_executions
 mod skills;         // skills, skill_scripts
 mod cron_jobs;      // cron_jobs, cron_job_runs
 mod heartbeat;      // heartbeat_configs
+mod scheduler_triggers; // scheduler_triggers, scheduler_trigger_runs (condition triggers)
 mod gmail;          // gmail_configs
+mod webhooks;       // webhooks (inbound webhook triggers)
+mod roles;          // roles, role_assignments (channel user permissions)
+mod dashboard_users; // dashboard_users, api_tokens (dashboard accounts + automation tokens)
 mod agent_contexts; // agent_contexts (multi-agent orchestrator state)
 mod twitter_mentions; // twitter_processed_mentions (track processed tweets)
+mod email_messages; // email_messages (email channel dedupe + threading)
 pub mod broadcasted_transactions; // broadcasted_transactions (crypto tx history)
+mod queued_transactions;  // queued_transactions (pending tx queue across restarts)
 pub mod mind_nodes;  // mind_nodes, mind_node_connections (mind map feature)
 pub mod telegram_chat_log; // telegram_chat_messages (passive chat log for readHistory)
 pub mod x402_payment_limits; // x402_payment_limits (per-call max amounts per token)
 pub mod kanban;          // kanban_items (kanban board task management)
 pub mod modules;         // installed_modules (plugin system registry)
 pub mod telemetry;       // execution_spans, rollouts, attempts, resource_versions
+pub mod session_branches; // session_branches, session_tool_state (session fork/rewind)
+pub mod session_imports;  // session_imports (transcript import provenance)
+pub mod a2a_tasks;       // a2a_tasks (agent-to-agent tasks, inbound and outbound)
+pub mod workflow_runs;   // workflow_runs (skill workflow checkpoints)
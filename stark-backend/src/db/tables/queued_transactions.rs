@@ -0,0 +1,49 @@
+//! Queued transaction persistence (queued_transactions)
+//!
+//! The tx queue itself lives in memory (`TxQueueManager`); transactions still
+//! waiting for approval are mirrored here so a restart neither loses them nor
+//! brings back ones that already expired or were broadcast.
+
+use rusqlite::Result as SqliteResult;
+
+use crate::tx_queue::QueuedTransaction;
+use super::super::Database;
+
+impl Database {
+    /// Store (or replace) a pending queued transaction
+    pub fn save_queued_transaction(&self, tx: &QueuedTransaction) -> SqliteResult<()> {
+        let conn = self.conn();
+        let tx_json = serde_json::to_string(tx)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        conn.execute(
+            "INSERT OR REPLACE INTO queued_transactions (uuid, tx_json, created_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![&tx.uuid, &tx_json, tx.created_at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a queued transaction once it is no longer pending
+    pub fn delete_queued_transaction(&self, uuid: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute("DELETE FROM queued_transactions WHERE uuid = ?1", [uuid])?;
+        Ok(rows > 0)
+    }
+
+    /// All persisted queued transactions, oldest first. Rows that no longer parse are skipped.
+    pub fn list_queued_transactions(&self) -> SqliteResult<Vec<QueuedTransaction>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT uuid, tx_json FROM queued_transactions ORDER BY created_at ASC")?;
+        let txs = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .filter_map(|r| r.ok())
+            .filter_map(|(uuid, json)| match serde_json::from_str(&json) {
+                Ok(tx) => Some(tx),
+                Err(e) => {
+                    log::warn!("[TxQueue] Skipping unreadable queued transaction {}: {}", uuid, e);
+                    None
+                }
+            })
+            .collect();
+        Ok(txs)
+    }
+}
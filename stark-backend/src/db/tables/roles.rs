@@ -0,0 +1,271 @@
+//! Role and role assignment database operations
+
+use chrono::Utc;
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+
+use crate::models::{Role, RoleAssignment};
+use super::super::Database;
+
+const ROLE_COLUMNS: &str = "name, description, tool_profile, allow_list, deny_list, allowed_groups,
+    max_tx_value_wei, builtin, created_at, updated_at";
+
+const ASSIGNMENT_COLUMNS: &str = "id, role, channel_id, user_id, identity_id, created_at";
+
+impl Database {
+    /// Create a role
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_role(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        tool_profile: &str,
+        allow_list: &[String],
+        deny_list: &[String],
+        allowed_groups: &[String],
+        max_tx_value_wei: Option<&str>,
+    ) -> SqliteResult<Role> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO roles (name, description, tool_profile, allow_list, deny_list, allowed_groups,
+                                max_tx_value_wei, builtin, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 0, ?8, ?8)",
+            rusqlite::params![
+                name,
+                description,
+                tool_profile,
+                serde_json::to_string(allow_list).unwrap_or_default(),
+                serde_json::to_string(deny_list).unwrap_or_default(),
+                serde_json::to_string(allowed_groups).unwrap_or_default(),
+                max_tx_value_wei,
+                &now
+            ],
+        )?;
+
+        drop(conn);
+        self.get_role(name).map(|opt| opt.unwrap())
+    }
+
+    /// List all roles
+    pub fn list_roles(&self) -> SqliteResult<Vec<Role>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM roles ORDER BY builtin DESC, name",
+            ROLE_COLUMNS
+        ))?;
+
+        let roles = stmt
+            .query_map([], Self::map_role_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(roles)
+    }
+
+    /// Get a role by name
+    pub fn get_role(&self, name: &str) -> SqliteResult<Option<Role>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM roles WHERE name = ?1", ROLE_COLUMNS))?;
+        stmt.query_row([name], Self::map_role_row).optional()
+    }
+
+    /// Update a role (None fields are left unchanged; an empty cap removes it)
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_role(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        tool_profile: Option<&str>,
+        allow_list: Option<&[String]>,
+        deny_list: Option<&[String]>,
+        allowed_groups: Option<&[String]>,
+        max_tx_value_wei: Option<&str>,
+    ) -> SqliteResult<Option<Role>> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let to_json = |list: Option<&[String]>| list.map(|l| serde_json::to_string(l).unwrap_or_default());
+
+        conn.execute(
+            "UPDATE roles SET
+                description = COALESCE(?1, description),
+                tool_profile = COALESCE(?2, tool_profile),
+                allow_list = COALESCE(?3, allow_list),
+                deny_list = COALESCE(?4, deny_list),
+                allowed_groups = COALESCE(?5, allowed_groups),
+                max_tx_value_wei = CASE WHEN ?6 IS NULL THEN max_tx_value_wei ELSE NULLIF(?6, '') END,
+                updated_at = ?7
+             WHERE name = ?8",
+            rusqlite::params![
+                description,
+                tool_profile,
+                to_json(allow_list),
+                to_json(deny_list),
+                to_json(allowed_groups),
+                max_tx_value_wei,
+                &now,
+                name
+            ],
+        )?;
+
+        drop(conn);
+        self.get_role(name)
+    }
+
+    /// Delete a custom role and its assignments. Built-in roles are kept.
+    pub fn delete_role(&self, name: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let tx = conn.unchecked_transaction()?;
+        let rows = tx.execute("DELETE FROM roles WHERE name = ?1 AND builtin = 0", [name])?;
+        if rows > 0 {
+            tx.execute("DELETE FROM role_assignments WHERE role = ?1", [name])?;
+        }
+        tx.commit()?;
+        Ok(rows > 0)
+    }
+
+    /// Assign a role, replacing any role the same subject already had
+    pub fn assign_role(
+        &self,
+        role: &str,
+        channel_id: Option<i64>,
+        user_id: Option<&str>,
+        identity_id: Option<&str>,
+    ) -> SqliteResult<RoleAssignment> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO role_assignments (role, channel_id, user_id, identity_id, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(IFNULL(channel_id, 0), IFNULL(user_id, ''), IFNULL(identity_id, ''))
+             DO UPDATE SET role = excluded.role, created_at = excluded.created_at",
+            rusqlite::params![role, channel_id, user_id, identity_id, &now],
+        )?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM role_assignments
+             WHERE IFNULL(channel_id, 0) = IFNULL(?1, 0) AND IFNULL(user_id, '') = IFNULL(?2, '')
+               AND IFNULL(identity_id, '') = IFNULL(?3, '')",
+            ASSIGNMENT_COLUMNS
+        ))?;
+        stmt.query_row(rusqlite::params![channel_id, user_id, identity_id], |row| {
+            Self::map_role_assignment_row(row)
+        })
+    }
+
+    /// List role assignments, optionally only those for one channel
+    pub fn list_role_assignments(&self, channel_id: Option<i64>) -> SqliteResult<Vec<RoleAssignment>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM role_assignments WHERE ?1 IS NULL OR channel_id = ?1 ORDER BY id",
+            ASSIGNMENT_COLUMNS
+        ))?;
+
+        let assignments = stmt
+            .query_map([channel_id], Self::map_role_assignment_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(assignments)
+    }
+
+    /// Remove a role assignment
+    pub fn delete_role_assignment(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute("DELETE FROM role_assignments WHERE id = ?1", [id])?;
+        Ok(rows > 0)
+    }
+
+    /// Resolve the role for a user in a channel. Most specific wins:
+    /// channel + platform user, then identity, then the channel default.
+    pub fn resolve_role(
+        &self,
+        channel_id: i64,
+        user_id: &str,
+        identity_id: Option<&str>,
+    ) -> SqliteResult<Option<Role>> {
+        let role_name: Option<String> = {
+            let conn = self.conn();
+            let mut stmt = conn.prepare(
+                "SELECT role FROM role_assignments
+                 WHERE (channel_id = ?1 AND user_id = ?2)
+                    OR (identity_id = ?3 AND (channel_id IS NULL OR channel_id = ?1))
+                    OR (channel_id = ?1 AND user_id IS NULL AND identity_id IS NULL)
+                 ORDER BY CASE
+                    WHEN user_id IS NOT NULL THEN 0
+                    WHEN identity_id IS NOT NULL AND channel_id IS NOT NULL THEN 1
+                    WHEN identity_id IS NOT NULL THEN 2
+                    ELSE 3
+                 END
+                 LIMIT 1",
+            )?;
+            stmt.query_row(rusqlite::params![channel_id, user_id, identity_id], |row| row.get(0))
+                .optional()?
+        };
+
+        match role_name {
+            Some(name) => self.get_role(&name),
+            None => Ok(None),
+        }
+    }
+
+    fn map_role_row(row: &rusqlite::Row) -> rusqlite::Result<Role> {
+        let parse_list = |s: String| serde_json::from_str::<Vec<String>>(&s).unwrap_or_default();
+        Ok(Role {
+            name: row.get(0)?,
+            description: row.get(1)?,
+            tool_profile: row.get(2)?,
+            allow_list: parse_list(row.get(3)?),
+            deny_list: parse_list(row.get(4)?),
+            allowed_groups: parse_list(row.get(5)?),
+            max_tx_value_wei: row.get(6)?,
+            builtin: row.get::<_, i32>(7)? != 0,
+            created_at: row.get(8)?,
+            updated_at: row.get(9)?,
+        })
+    }
+
+    fn map_role_assignment_row(row: &rusqlite::Row) -> rusqlite::Result<RoleAssignment> {
+        Ok(RoleAssignment {
+            id: row.get(0)?,
+            role: row.get(1)?,
+            channel_id: row.get(2)?,
+            user_id: row.get(3)?,
+            identity_id: row.get(4)?,
+            created_at: row.get(5)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::Database;
+
+    #[test]
+    fn test_resolve_role_precedence() {
+        let db = Database::new(":memory:").expect("Failed to create test db");
+
+        // Built-in roles are seeded
+        assert!(db.get_role("trader").unwrap().is_some());
+        assert!(db.resolve_role(1, "alice", Some("id-a")).unwrap().is_none());
+
+        db.assign_role("viewer", Some(1), None, None).unwrap();
+        assert_eq!(db.resolve_role(1, "alice", Some("id-a")).unwrap().unwrap().name, "viewer");
+
+        db.assign_role("trader", None, None, Some("id-a")).unwrap();
+        assert_eq!(db.resolve_role(1, "alice", Some("id-a")).unwrap().unwrap().name, "trader");
+        assert_eq!(db.resolve_role(2, "alice", Some("id-a")).unwrap().unwrap().name, "trader");
+
+        db.assign_role("developer", Some(1), Some("alice"), None).unwrap();
+        assert_eq!(db.resolve_role(1, "alice", Some("id-a")).unwrap().unwrap().name, "developer");
+
+        // Reassigning the same subject replaces its role
+        db.assign_role("admin", Some(1), Some("alice"), None).unwrap();
+        assert_eq!(db.resolve_role(1, "alice", Some("id-a")).unwrap().unwrap().name, "admin");
+        assert_eq!(db.resolve_role(1, "bob", Some("id-b")).unwrap().unwrap().name, "viewer");
+
+        // Built-in roles can't be deleted
+        assert!(!db.delete_role("admin").unwrap());
+    }
+}
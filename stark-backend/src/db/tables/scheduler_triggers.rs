@@ -0,0 +1,269 @@
+//! Scheduler condition trigger database operations
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+
+use crate::models::{SchedulerTrigger, TriggerCondition, TriggerRun, TriggerState};
+use super::super::Database;
+
+const TRIGGER_COLUMNS: &str = "id, name, job_id, condition, check_interval_secs, cooldown_secs,
+    confirm_checks, enabled, state, last_checked_at, last_fired_at, fire_count, last_error,
+    created_at, updated_at";
+
+const TRIGGER_RUN_COLUMNS: &str = "id, trigger_id, fired_at, detail, value, cron_run_id, success, error";
+
+impl Database {
+    /// Create a trigger
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_scheduler_trigger(
+        &self,
+        name: &str,
+        job_id: i64,
+        condition: &TriggerCondition,
+        check_interval_secs: i64,
+        cooldown_secs: i64,
+        confirm_checks: i64,
+        enabled: bool,
+    ) -> SqliteResult<SchedulerTrigger> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let condition_json = serde_json::to_string(condition).unwrap_or_default();
+
+        conn.execute(
+            "INSERT INTO scheduler_triggers (name, job_id, condition, check_interval_secs, cooldown_secs,
+                                             confirm_checks, enabled, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+            rusqlite::params![
+                name, job_id, condition_json, check_interval_secs, cooldown_secs,
+                confirm_checks, enabled as i32, &now
+            ],
+        )?;
+
+        let id = conn.last_insert_rowid();
+        drop(conn);
+        self.get_scheduler_trigger(id).map(|opt| opt.unwrap())
+    }
+
+    /// List all triggers
+    pub fn list_scheduler_triggers(&self) -> SqliteResult<Vec<SchedulerTrigger>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM scheduler_triggers ORDER BY created_at DESC",
+            TRIGGER_COLUMNS
+        ))?;
+
+        let triggers = stmt
+            .query_map([], Self::map_trigger_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(triggers)
+    }
+
+    /// Enabled triggers whose check interval has elapsed
+    pub fn list_due_scheduler_triggers(&self) -> SqliteResult<Vec<SchedulerTrigger>> {
+        let now = Utc::now();
+        Ok(self
+            .list_scheduler_triggers()?
+            .into_iter()
+            .filter(|t| t.enabled)
+            .filter(|t| {
+                t.last_checked_at
+                    .as_deref()
+                    .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+                    .map(|last| now.signed_duration_since(last).num_seconds() >= t.check_interval_secs)
+                    .unwrap_or(true)
+            })
+            .collect())
+    }
+
+    /// Get a trigger by ID
+    pub fn get_scheduler_trigger(&self, id: i64) -> SqliteResult<Option<SchedulerTrigger>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM scheduler_triggers WHERE id = ?1", TRIGGER_COLUMNS))?;
+        Ok(stmt.query_row([id], Self::map_trigger_row).ok())
+    }
+
+    /// Update a trigger (None fields are left unchanged; a new condition resets the state)
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_scheduler_trigger(
+        &self,
+        id: i64,
+        name: Option<&str>,
+        job_id: Option<i64>,
+        condition: Option<&TriggerCondition>,
+        check_interval_secs: Option<i64>,
+        cooldown_secs: Option<i64>,
+        confirm_checks: Option<i64>,
+        enabled: Option<bool>,
+    ) -> SqliteResult<Option<SchedulerTrigger>> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let condition_json = condition.map(|c| serde_json::to_string(c).unwrap_or_default());
+
+        conn.execute(
+            "UPDATE scheduler_triggers SET
+                name = COALESCE(?1, name),
+                job_id = COALESCE(?2, job_id),
+                condition = COALESCE(?3, condition),
+                state = CASE WHEN ?3 IS NULL THEN state ELSE NULL END,
+                check_interval_secs = COALESCE(?4, check_interval_secs),
+                cooldown_secs = COALESCE(?5, cooldown_secs),
+                confirm_checks = COALESCE(?6, confirm_checks),
+                enabled = COALESCE(?7, enabled),
+                updated_at = ?8
+             WHERE id = ?9",
+            rusqlite::params![
+                name, job_id, condition_json, check_interval_secs, cooldown_secs,
+                confirm_checks, enabled.map(|e| e as i32), &now, id
+            ],
+        )?;
+
+        drop(conn);
+        self.get_scheduler_trigger(id)
+    }
+
+    /// Delete a trigger and its run history
+    pub fn delete_scheduler_trigger(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM scheduler_trigger_runs WHERE trigger_id = ?1", [id])?;
+        let rows = conn.execute("DELETE FROM scheduler_triggers WHERE id = ?1", [id])?;
+        Ok(rows > 0)
+    }
+
+    /// Record a condition check: new state, and the evaluation error if it failed
+    pub fn record_scheduler_trigger_check(
+        &self,
+        id: i64,
+        state: &TriggerState,
+        error: Option<&str>,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let state_json = serde_json::to_string(state).unwrap_or_default();
+
+        conn.execute(
+            "UPDATE scheduler_triggers SET state = ?1, last_checked_at = ?2, last_error = ?3 WHERE id = ?4",
+            rusqlite::params![state_json, &now, error, id],
+        )?;
+
+        Ok(())
+    }
+
+    /// Mark a trigger as checked now, before evaluating it (keeps the next tick from picking it up again)
+    pub fn touch_scheduler_trigger(&self, id: i64) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE scheduler_triggers SET last_checked_at = ?1 WHERE id = ?2",
+            rusqlite::params![&now, id],
+        )?;
+        Ok(())
+    }
+
+    /// Record a firing and return the new run (one-shot triggers are disabled)
+    pub fn record_scheduler_trigger_fire(
+        &self,
+        id: i64,
+        detail: &str,
+        value: Option<f64>,
+        disable: bool,
+    ) -> SqliteResult<TriggerRun> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "UPDATE scheduler_triggers SET fire_count = fire_count + 1, last_fired_at = ?1,
+                    enabled = CASE WHEN ?2 THEN 0 ELSE enabled END
+             WHERE id = ?3",
+            rusqlite::params![&now, disable, id],
+        )?;
+        conn.execute(
+            "INSERT INTO scheduler_trigger_runs (trigger_id, fired_at, detail, value) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![id, &now, detail, value],
+        )?;
+
+        Ok(TriggerRun {
+            id: conn.last_insert_rowid(),
+            trigger_id: id,
+            fired_at: now,
+            detail: detail.to_string(),
+            value,
+            cron_run_id: None,
+            success: None,
+            error: None,
+        })
+    }
+
+    /// Record the outcome of the job run a firing started
+    pub fn complete_scheduler_trigger_run(
+        &self,
+        run_id: i64,
+        cron_run_id: Option<i64>,
+        success: bool,
+        error: Option<&str>,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE scheduler_trigger_runs SET cron_run_id = ?1, success = ?2, error = ?3 WHERE id = ?4",
+            rusqlite::params![cron_run_id, success as i32, error, run_id],
+        )?;
+        Ok(())
+    }
+
+    /// Firing history for a trigger, newest first
+    pub fn list_scheduler_trigger_runs(&self, trigger_id: i64, limit: i64) -> SqliteResult<Vec<TriggerRun>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM scheduler_trigger_runs WHERE trigger_id = ?1 ORDER BY fired_at DESC, id DESC LIMIT ?2",
+            TRIGGER_RUN_COLUMNS
+        ))?;
+
+        let runs = stmt
+            .query_map(rusqlite::params![trigger_id, limit], |row| {
+                Ok(TriggerRun {
+                    id: row.get(0)?,
+                    trigger_id: row.get(1)?,
+                    fired_at: row.get(2)?,
+                    detail: row.get(3)?,
+                    value: row.get(4)?,
+                    cron_run_id: row.get(5)?,
+                    success: row.get::<_, Option<i32>>(6)?.map(|v| v != 0),
+                    error: row.get(7)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(runs)
+    }
+
+    fn map_trigger_row(row: &rusqlite::Row) -> rusqlite::Result<SchedulerTrigger> {
+        let condition_json: String = row.get(3)?;
+        let condition: TriggerCondition = serde_json::from_str(&condition_json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+        let state: TriggerState = row
+            .get::<_, Option<String>>(8)?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+
+        Ok(SchedulerTrigger {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            job_id: row.get(2)?,
+            condition,
+            check_interval_secs: row.get(4)?,
+            cooldown_secs: row.get(5)?,
+            confirm_checks: row.get(6)?,
+            enabled: row.get::<_, i32>(7)? != 0,
+            state,
+            last_checked_at: row.get(9)?,
+            last_fired_at: row.get(10)?,
+            fire_count: row.get(11)?,
+            last_error: row.get(12)?,
+            created_at: row.get(13)?,
+            updated_at: row.get(14)?,
+        })
+    }
+}
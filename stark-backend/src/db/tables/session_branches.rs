@@ -0,0 +1,342 @@
+//! Session branching database operations (session_branches, session_tool_state)
+//!
+//! A fork copies a session's messages (optionally up to a given message) and
+//! compaction summary into a new session, and records the lineage in
+//! `session_branches`. Agent context and tool state (registers + context bank)
+//! are only carried over when the fork includes the whole transcript.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{ChatSession, ResetPolicy, SessionBranch};
+use super::super::Database;
+
+/// Snapshot of a session's register store and context bank
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionToolState {
+    pub session_id: i64,
+    /// Register snapshot (see `RegisterStore::snapshot`)
+    pub registers: serde_json::Value,
+    /// Context bank items
+    pub context_bank: serde_json::Value,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Database {
+    /// Fork a session into a new branch.
+    ///
+    /// Messages are copied up to and including `up_to_message_id` (all messages
+    /// when None). The branch gets a unique session key derived from the source
+    /// and a manual reset policy so it is never reset underneath the user.
+    ///
+    /// Agent context and registers describe the end of the source transcript, so a
+    /// fork before the tail starts without them. The compaction summary only covers
+    /// messages older than any still stored, so it is kept unless nothing is copied.
+    pub fn fork_chat_session(
+        &self,
+        source_id: i64,
+        up_to_message_id: Option<i64>,
+        label: Option<&str>,
+    ) -> SqliteResult<ChatSession> {
+        let source = self
+            .get_chat_session(source_id)?
+            .ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+
+        let conn = self.conn();
+        let now = Utc::now();
+        let now_str = now.to_rfc3339();
+
+        // Resolve the last parent message that will be copied
+        let branch_point: Option<i64> = conn.query_row(
+            "SELECT MAX(id) FROM session_messages WHERE session_id = ?1 AND (?2 IS NULL OR id <= ?2)",
+            rusqlite::params![source_id, up_to_message_id],
+            |row| row.get(0),
+        )?;
+        let tail: Option<i64> = conn.query_row(
+            "SELECT MAX(id) FROM session_messages WHERE session_id = ?1",
+            [source_id],
+            |row| row.get(0),
+        )?;
+        let at_tail = branch_point == tail;
+
+        let platform_chat_id = format!("{}:branch-{}", source.platform_chat_id, uuid::Uuid::new_v4());
+        let session_key = Self::generate_session_key(&source.channel_type, source.channel_id, &platform_chat_id);
+
+        let tx = conn.unchecked_transaction()?;
+
+        tx.execute(
+            "INSERT INTO chat_sessions (session_key, agent_id, scope, channel_type, channel_id, platform_chat_id,
+             is_active, reset_policy, idle_timeout_minutes, daily_reset_hour, created_at, updated_at, last_activity_at,
+             max_context_tokens, compaction_summary, compaction_generation, safe_mode)
+             SELECT ?1, agent_id, scope, channel_type, channel_id, ?2,
+             1, ?3, NULL, daily_reset_hour, ?4, ?4, ?4,
+             max_context_tokens,
+             CASE WHEN ?6 THEN compaction_summary ELSE NULL END,
+             CASE WHEN ?6 THEN compaction_generation ELSE 0 END,
+             safe_mode
+             FROM chat_sessions WHERE id = ?5",
+            rusqlite::params![
+                &session_key,
+                &platform_chat_id,
+                ResetPolicy::Manual.as_str(),
+                &now_str,
+                source_id,
+                branch_point.is_some(),
+            ],
+        )?;
+        let new_id = tx.last_insert_rowid();
+
+        // Copy the transcript up to the branch point (keeps original timestamps)
+        if let Some(point) = branch_point {
+            tx.execute(
+                "INSERT INTO session_messages (session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at)
+                 SELECT ?1, role, content, user_id, user_name, platform_message_id, tokens_used, created_at
+                 FROM session_messages WHERE session_id = ?2 AND id <= ?3 ORDER BY id ASC",
+                rusqlite::params![new_id, source_id, point],
+            )?;
+        }
+
+        tx.execute(
+            "UPDATE chat_sessions SET context_tokens = (
+                SELECT COALESCE(SUM(tokens_used), 0) FROM session_messages WHERE session_id = ?1
+             ) WHERE id = ?1",
+            [new_id],
+        )?;
+
+        // Continuing from the tail: copy orchestrator and tool state so the branch resumes
+        // with the same mode, subtype, skill and registers
+        if at_tail {
+            tx.execute(
+                "INSERT INTO agent_contexts (
+                    session_id, original_request, mode, subtype, context_sufficient, plan_ready,
+                    mode_iterations, total_iterations, exploration_notes, findings, plan_summary,
+                    scratchpad, tasks_json, active_skill_json, created_at, updated_at
+                 )
+                 SELECT ?1, original_request, mode, subtype, context_sufficient, plan_ready,
+                    mode_iterations, total_iterations, exploration_notes, findings, plan_summary,
+                    scratchpad, tasks_json, active_skill_json, ?2, ?2
+                 FROM agent_contexts WHERE session_id = ?3",
+                rusqlite::params![new_id, &now_str, source_id],
+            )?;
+
+            tx.execute(
+                "INSERT INTO session_tool_state (session_id, registers_json, context_bank_json, updated_at)
+                 SELECT ?1, registers_json, context_bank_json, ?2
+                 FROM session_tool_state WHERE session_id = ?3",
+                rusqlite::params![new_id, &now_str, source_id],
+            )?;
+        }
+
+        tx.execute(
+            "INSERT INTO session_branches (session_id, parent_session_id, branch_point_message_id, label, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![new_id, source_id, branch_point, label, &now_str],
+        )?;
+
+        tx.commit()?;
+        drop(conn);
+
+        log::info!(
+            "[SESSIONS] Forked session {} into {} at message {:?}",
+            source_id,
+            new_id,
+            branch_point
+        );
+
+        self.get_chat_session(new_id).map(|opt| opt.unwrap())
+    }
+
+    /// Get the id of the message immediately before `message_id` in its session
+    pub fn get_previous_session_message_id(&self, session_id: i64, message_id: i64) -> SqliteResult<Option<i64>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT MAX(id) FROM session_messages WHERE session_id = ?1 AND id < ?2",
+            rusqlite::params![session_id, message_id],
+            |row| row.get(0),
+        )
+    }
+
+    /// Get a single session message by id
+    pub fn get_session_message(&self, message_id: i64) -> SqliteResult<Option<crate::models::SessionMessage>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT id, session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at
+             FROM session_messages WHERE id = ?1",
+            [message_id],
+            Self::row_to_session_message,
+        )
+        .optional()
+    }
+
+    /// Get branch metadata for a session (None if it is not a fork)
+    pub fn get_session_branch(&self, session_id: i64) -> SqliteResult<Option<SessionBranch>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT session_id, parent_session_id, branch_point_message_id, label, created_at
+             FROM session_branches WHERE session_id = ?1",
+            [session_id],
+            Self::row_to_session_branch,
+        )
+        .optional()
+    }
+
+    /// List the direct branches forked from a session, oldest first
+    pub fn list_session_branches(&self, parent_session_id: i64) -> SqliteResult<Vec<SessionBranch>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT session_id, parent_session_id, branch_point_message_id, label, created_at
+             FROM session_branches WHERE parent_session_id = ?1 ORDER BY created_at ASC",
+        )?;
+
+        let branches = stmt
+            .query_map([parent_session_id], Self::row_to_session_branch)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(branches)
+    }
+
+    /// Save the register and context bank snapshot for a session
+    pub fn save_session_tool_state(
+        &self,
+        session_id: i64,
+        registers: &serde_json::Value,
+        context_bank: &serde_json::Value,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO session_tool_state (session_id, registers_json, context_bank_json, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(session_id) DO UPDATE SET
+                registers_json = ?2,
+                context_bank_json = ?3,
+                updated_at = ?4",
+            rusqlite::params![session_id, registers.to_string(), context_bank.to_string(), &now],
+        )?;
+        Ok(())
+    }
+
+    /// Get the register and context bank snapshot for a session
+    pub fn get_session_tool_state(&self, session_id: i64) -> SqliteResult<Option<SessionToolState>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT session_id, registers_json, context_bank_json, updated_at
+             FROM session_tool_state WHERE session_id = ?1",
+            [session_id],
+            |row| {
+                let registers_json: String = row.get(1)?;
+                let context_bank_json: String = row.get(2)?;
+                let updated_at_str: String = row.get(3)?;
+                Ok(SessionToolState {
+                    session_id: row.get(0)?,
+                    registers: serde_json::from_str(&registers_json).unwrap_or_default(),
+                    context_bank: serde_json::from_str(&context_bank_json).unwrap_or_default(),
+                    updated_at: DateTime::parse_from_rfc3339(&updated_at_str)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                })
+            },
+        )
+        .optional()
+    }
+
+    fn row_to_session_branch(row: &rusqlite::Row) -> rusqlite::Result<SessionBranch> {
+        let created_at_str: String = row.get(4)?;
+        Ok(SessionBranch {
+            session_id: row.get(0)?,
+            parent_session_id: row.get(1)?,
+            branch_point_message_id: row.get(2)?,
+            label: row.get(3)?,
+            created_at: DateTime::parse_from_rfc3339(&created_at_str)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ai::multi_agent::types::AgentContext;
+    use crate::db::Database;
+    use crate::models::{MessageRole, SessionScope};
+
+    #[test]
+    fn test_fork_copies_messages_up_to_branch_point() {
+        let db = Database::new(":memory:").unwrap();
+        let session = db
+            .get_or_create_chat_session("web", 0, "user-1", SessionScope::Dm, None)
+            .unwrap();
+        let first = db
+            .add_session_message(session.id, MessageRole::User, "swap 1 eth", None, None, None, Some(3))
+            .unwrap();
+        db.add_session_message(session.id, MessageRole::Assistant, "failed", None, None, None, Some(1))
+            .unwrap();
+        db.save_session_tool_state(session.id, &serde_json::json!({"amount": {"value": "1", "source_tool": "t"}}), &serde_json::json!([]))
+            .unwrap();
+
+        let branch = db.fork_chat_session(session.id, Some(first.id), Some("retry")).unwrap();
+
+        assert_ne!(branch.id, session.id);
+        assert_eq!(branch.channel_type, "web");
+        let messages = db.get_session_messages(branch.id).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content, "swap 1 eth");
+        assert_eq!(branch.context_tokens, 3);
+
+        let meta = db.get_session_branch(branch.id).unwrap().unwrap();
+        assert_eq!(meta.parent_session_id, session.id);
+        assert_eq!(meta.branch_point_message_id, Some(first.id));
+        assert_eq!(db.list_session_branches(session.id).unwrap().len(), 1);
+
+        // Registers were set after the branch point, so they don't carry over
+        assert!(db.get_session_tool_state(branch.id).unwrap().is_none());
+
+        // The source session is untouched
+        assert_eq!(db.get_session_messages(session.id).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_fork_before_tool_call_drops_later_state() {
+        let db = Database::new(":memory:").unwrap();
+        let session = db
+            .get_or_create_chat_session("web", 0, "user-1", SessionScope::Dm, None)
+            .unwrap();
+        let request = db
+            .add_session_message(session.id, MessageRole::User, "quote 1 eth", None, None, None, Some(3))
+            .unwrap();
+        db.add_session_message(session.id, MessageRole::ToolCall, "token_lookup", None, None, None, Some(1))
+            .unwrap();
+        db.add_session_message(session.id, MessageRole::ToolResult, "sell_token = 0xc02a...", None, None, None, Some(1))
+            .unwrap();
+        db.save_session_tool_state(session.id, &serde_json::json!({"sell_token": {"value": "0xc02a", "source_tool": "token_lookup"}}), &serde_json::json!([]))
+            .unwrap();
+        let context = AgentContext { original_request: "quote 1 eth".to_string(), ..Default::default() };
+        db.save_agent_context(session.id, &context).unwrap();
+        db.set_session_compaction_summary(session.id, "earlier: user set slippage to 1%").unwrap();
+
+        // Rewinding past the tool call leaves its registers and agent state behind
+        let rewound = db.fork_chat_session(session.id, Some(request.id), None).unwrap();
+        assert_eq!(db.get_session_messages(rewound.id).unwrap().len(), 1);
+        assert!(db.get_session_tool_state(rewound.id).unwrap().is_none());
+        assert!(db.get_agent_context(rewound.id).unwrap().is_none());
+        // The summary predates every stored message, so it still applies
+        assert_eq!(
+            db.get_session_compaction_summary(rewound.id).unwrap().as_deref(),
+            Some("earlier: user set slippage to 1%")
+        );
+
+        // Forking from the tail keeps everything
+        let copy = db.fork_chat_session(session.id, None, None).unwrap();
+        let state = db.get_session_tool_state(copy.id).unwrap().unwrap();
+        assert!(state.registers.get("sell_token").is_some());
+        assert!(db.get_agent_context(copy.id).unwrap().is_some());
+        assert_ne!(copy.session_key, rewound.session_key);
+
+        // Rewinding to before the first message keeps nothing
+        let empty = db.fork_chat_session(session.id, Some(0), None).unwrap();
+        assert!(db.get_session_messages(empty.id).unwrap().is_empty());
+        assert!(db.get_session_compaction_summary(empty.id).unwrap().is_none());
+    }
+}
@@ -0,0 +1,137 @@
+//! Imported session database operations (session_imports)
+//!
+//! Sessions imported from a transcript export are recreated as new manual-reset
+//! sessions; the parts of the export that must not be replayed into live state
+//! (x402 payments, queued transactions, registers and context bank) are archived
+//! here as provenance.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{OptionalExtension, Result as SqliteResult};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{ChatSession, ResetPolicy, SessionMessage, SessionScope};
+use super::super::Database;
+
+/// Provenance record for an imported session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionImport {
+    pub session_id: i64,
+    /// Transcript format version of the imported file
+    pub format_version: u32,
+    /// Session id on the exporting instance
+    pub source_session_id: Option<i64>,
+    /// Channel type on the exporting instance
+    pub source_channel_type: Option<String>,
+    /// Archived records that were not replayed (payments, queued txs)
+    pub archive: serde_json::Value,
+    pub imported_at: DateTime<Utc>,
+}
+
+impl Database {
+    /// Create a new session from imported messages, preserving roles, content,
+    /// token counts and timestamps. Returns the new session.
+    pub fn create_imported_session(
+        &self,
+        channel_type: &str,
+        channel_id: i64,
+        scope: SessionScope,
+        compaction_summary: Option<&str>,
+        messages: &[SessionMessage],
+    ) -> SqliteResult<ChatSession> {
+        let conn = self.conn();
+        let now_str = Utc::now().to_rfc3339();
+
+        let platform_chat_id = format!("import-{}", uuid::Uuid::new_v4());
+        let session_key = Self::generate_session_key(channel_type, channel_id, &platform_chat_id);
+        let context_tokens: i64 = messages.iter().map(|m| m.tokens_used.unwrap_or(0) as i64).sum();
+
+        let tx = conn.unchecked_transaction()?;
+
+        tx.execute(
+            "INSERT INTO chat_sessions (session_key, agent_id, scope, channel_type, channel_id, platform_chat_id,
+             is_active, reset_policy, idle_timeout_minutes, daily_reset_hour, created_at, updated_at, last_activity_at,
+             context_tokens, compaction_summary)
+             VALUES (?1, NULL, ?2, ?3, ?4, ?5, 1, ?6, NULL, 0, ?7, ?7, ?7, ?8, ?9)",
+            rusqlite::params![
+                &session_key,
+                scope.as_str(),
+                channel_type,
+                channel_id,
+                &platform_chat_id,
+                ResetPolicy::Manual.as_str(),
+                &now_str,
+                context_tokens,
+                compaction_summary,
+            ],
+        )?;
+        let session_id = tx.last_insert_rowid();
+
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO session_messages (session_id, role, content, user_id, user_name, platform_message_id, tokens_used, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for msg in messages {
+                stmt.execute(rusqlite::params![
+                    session_id,
+                    msg.role.as_str(),
+                    &msg.content,
+                    &msg.user_id,
+                    &msg.user_name,
+                    &msg.platform_message_id,
+                    msg.tokens_used,
+                    msg.created_at.to_rfc3339(),
+                ])?;
+            }
+        }
+
+        tx.commit()?;
+        drop(conn);
+
+        self.get_chat_session(session_id).map(|opt| opt.unwrap())
+    }
+
+    /// Record provenance for an imported session
+    pub fn record_session_import(&self, import: &SessionImport) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR REPLACE INTO session_imports
+                (session_id, format_version, source_session_id, source_channel_type, archive_json, imported_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                import.session_id,
+                import.format_version,
+                import.source_session_id,
+                &import.source_channel_type,
+                import.archive.to_string(),
+                import.imported_at.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get provenance for an imported session (None if it was not imported)
+    pub fn get_session_import(&self, session_id: i64) -> SqliteResult<Option<SessionImport>> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT session_id, format_version, source_session_id, source_channel_type, archive_json, imported_at
+             FROM session_imports WHERE session_id = ?1",
+            [session_id],
+            |row| {
+                let archive_json: String = row.get(4)?;
+                let imported_at_str: String = row.get(5)?;
+                Ok(SessionImport {
+                    session_id: row.get(0)?,
+                    format_version: row.get(1)?,
+                    source_session_id: row.get(2)?,
+                    source_channel_type: row.get(3)?,
+                    archive: serde_json::from_str(&archive_json).unwrap_or_default(),
+                    imported_at: DateTime::parse_from_rfc3339(&imported_at_str)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                })
+            },
+        )
+        .optional()
+    }
+}
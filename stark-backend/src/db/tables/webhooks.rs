@@ -0,0 +1,152 @@
+//! Inbound webhook database operations
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+
+use crate::models::Webhook;
+use super::super::Database;
+
+const WEBHOOK_COLUMNS: &str = "id, slug, name, kind, secret, prompt_template, tool_profile,
+    deliver_channel_id, deliver_to, enabled, trigger_count, last_triggered_at, last_error,
+    created_at, updated_at";
+
+impl Database {
+    /// Create a webhook
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_webhook(
+        &self,
+        slug: &str,
+        name: &str,
+        kind: &str,
+        secret: &str,
+        prompt_template: &str,
+        tool_profile: &str,
+        deliver_channel_id: Option<i64>,
+        deliver_to: Option<&str>,
+    ) -> SqliteResult<Webhook> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO webhooks (slug, name, kind, secret, prompt_template, tool_profile,
+                                   deliver_channel_id, deliver_to, enabled, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 1, ?9, ?9)",
+            rusqlite::params![
+                slug, name, kind, secret, prompt_template, tool_profile,
+                deliver_channel_id, deliver_to, &now
+            ],
+        )?;
+
+        let id = conn.last_insert_rowid();
+        drop(conn);
+        self.get_webhook(id).map(|opt| opt.unwrap())
+    }
+
+    /// List all webhooks
+    pub fn list_webhooks(&self) -> SqliteResult<Vec<Webhook>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM webhooks ORDER BY created_at DESC",
+            WEBHOOK_COLUMNS
+        ))?;
+
+        let webhooks = stmt
+            .query_map([], Self::map_webhook_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(webhooks)
+    }
+
+    /// Get a webhook by ID
+    pub fn get_webhook(&self, id: i64) -> SqliteResult<Option<Webhook>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM webhooks WHERE id = ?1", WEBHOOK_COLUMNS))?;
+        Ok(stmt.query_row([id], Self::map_webhook_row).ok())
+    }
+
+    /// Get a webhook by its URL slug
+    pub fn get_webhook_by_slug(&self, slug: &str) -> SqliteResult<Option<Webhook>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM webhooks WHERE slug = ?1", WEBHOOK_COLUMNS))?;
+        Ok(stmt.query_row([slug], Self::map_webhook_row).ok())
+    }
+
+    /// Update a webhook (None fields are left unchanged)
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_webhook(
+        &self,
+        id: i64,
+        name: Option<&str>,
+        secret: Option<&str>,
+        prompt_template: Option<&str>,
+        tool_profile: Option<&str>,
+        deliver_channel_id: Option<i64>,
+        deliver_to: Option<&str>,
+        enabled: Option<bool>,
+    ) -> SqliteResult<Option<Webhook>> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "UPDATE webhooks SET
+                name = COALESCE(?1, name),
+                secret = COALESCE(?2, secret),
+                prompt_template = COALESCE(?3, prompt_template),
+                tool_profile = COALESCE(?4, tool_profile),
+                deliver_channel_id = COALESCE(?5, deliver_channel_id),
+                deliver_to = COALESCE(?6, deliver_to),
+                enabled = COALESCE(?7, enabled),
+                updated_at = ?8
+             WHERE id = ?9",
+            rusqlite::params![
+                name, secret, prompt_template, tool_profile,
+                deliver_channel_id, deliver_to, enabled.map(|e| e as i32), &now, id
+            ],
+        )?;
+
+        drop(conn);
+        self.get_webhook(id)
+    }
+
+    /// Delete a webhook
+    pub fn delete_webhook(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute("DELETE FROM webhooks WHERE id = ?1", [id])?;
+        Ok(rows > 0)
+    }
+
+    /// Record a delivery attempt (error is cleared on success)
+    pub fn record_webhook_trigger(&self, id: i64, error: Option<&str>) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "UPDATE webhooks SET trigger_count = trigger_count + 1, last_triggered_at = ?1, last_error = ?2
+             WHERE id = ?3",
+            rusqlite::params![&now, error, id],
+        )?;
+
+        Ok(())
+    }
+
+    fn map_webhook_row(row: &rusqlite::Row) -> rusqlite::Result<Webhook> {
+        Ok(Webhook {
+            id: row.get(0)?,
+            slug: row.get(1)?,
+            name: row.get(2)?,
+            kind: row.get(3)?,
+            secret: row.get(4)?,
+            prompt_template: row.get(5)?,
+            tool_profile: row.get(6)?,
+            deliver_channel_id: row.get(7)?,
+            deliver_to: row.get(8)?,
+            enabled: row.get::<_, i32>(9)? != 0,
+            trigger_count: row.get(10)?,
+            last_triggered_at: row.get(11)?,
+            last_error: row.get(12)?,
+            created_at: row.get(13)?,
+            updated_at: row.get(14)?,
+        })
+    }
+}
@@ -0,0 +1,150 @@
+//! Workflow run database operations (skill workflow checkpoints)
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+use serde_json::{Map, Value};
+
+use crate::models::{WorkflowRun, WorkflowRunStatus};
+use super::super::Database;
+
+const WORKFLOW_RUN_COLUMNS: &str = "id, run_id, session_id, channel_id, skill_name, definition, status,
+    current_step, variables, registers, step_log, error, paused_at, created_at, updated_at";
+
+impl Database {
+    /// Start a new workflow run
+    pub fn create_workflow_run(
+        &self,
+        session_id: i64,
+        channel_id: i64,
+        skill_name: &str,
+        definition: &str,
+        variables: &Map<String, Value>,
+    ) -> SqliteResult<WorkflowRun> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let run_id = uuid::Uuid::new_v4().to_string();
+        let variables_json = serde_json::to_string(variables).unwrap_or_else(|_| "{}".to_string());
+
+        conn.execute(
+            "INSERT INTO workflow_runs (run_id, session_id, channel_id, skill_name, definition, status,
+                                        variables, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)",
+            rusqlite::params![
+                &run_id, session_id, channel_id, skill_name, definition,
+                WorkflowRunStatus::Running.as_str(), variables_json, &now
+            ],
+        )?;
+
+        drop(conn);
+        self.get_workflow_run(&run_id).map(|opt| opt.unwrap())
+    }
+
+    /// Get a run by its run ID
+    pub fn get_workflow_run(&self, run_id: &str) -> SqliteResult<Option<WorkflowRun>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM workflow_runs WHERE run_id = ?1",
+            WORKFLOW_RUN_COLUMNS
+        ))?;
+        Ok(stmt.query_row([run_id], Self::map_workflow_run_row).ok())
+    }
+
+    /// The session's unfinished run, if any (a session runs one workflow at a time)
+    pub fn get_open_workflow_run(&self, session_id: i64) -> SqliteResult<Option<WorkflowRun>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM workflow_runs
+             WHERE session_id = ?1 AND status NOT IN ('completed', 'failed', 'canceled')
+             ORDER BY id DESC LIMIT 1",
+            WORKFLOW_RUN_COLUMNS
+        ))?;
+        Ok(stmt.query_row([session_id], Self::map_workflow_run_row).ok())
+    }
+
+    /// Checkpoint a run's progress
+    pub fn save_workflow_run(&self, run: &WorkflowRun) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let variables_json = serde_json::to_string(&run.variables).unwrap_or_else(|_| "{}".to_string());
+        let registers_json = serde_json::to_string(&run.registers).unwrap_or_else(|_| "{}".to_string());
+        let step_log_json = serde_json::to_string(&run.step_log).unwrap_or_else(|_| "[]".to_string());
+
+        conn.execute(
+            "UPDATE workflow_runs SET status = ?1, current_step = ?2, variables = ?3, registers = ?4,
+                                      step_log = ?5, error = ?6, paused_at = ?7, updated_at = ?8
+             WHERE run_id = ?9",
+            rusqlite::params![
+                run.status.as_str(), run.current_step as i64, variables_json, registers_json,
+                step_log_json, run.error, run.paused_at, &now, run.run_id
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Mark runs that were executing when the process stopped. Returns how many were found.
+    pub fn mark_running_workflow_runs_interrupted(&self) -> SqliteResult<usize> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE workflow_runs SET status = 'interrupted', updated_at = ?1 WHERE status = 'running'",
+            [&now],
+        )
+    }
+
+    fn map_workflow_run_row(row: &rusqlite::Row) -> rusqlite::Result<WorkflowRun> {
+        let status: String = row.get(6)?;
+        let variables: String = row.get(8)?;
+        let registers: String = row.get(9)?;
+        let step_log: String = row.get(10)?;
+
+        Ok(WorkflowRun {
+            id: row.get(0)?,
+            run_id: row.get(1)?,
+            session_id: row.get(2)?,
+            channel_id: row.get(3)?,
+            skill_name: row.get(4)?,
+            definition: row.get(5)?,
+            status: WorkflowRunStatus::from_str(&status).unwrap_or(WorkflowRunStatus::Interrupted),
+            current_step: row.get::<_, i64>(7)? as usize,
+            variables: serde_json::from_str(&variables).unwrap_or_default(),
+            registers: serde_json::from_str(&registers).unwrap_or(Value::Null),
+            step_log: serde_json::from_str(&step_log).unwrap_or_default(),
+            error: row.get(11)?,
+            paused_at: row.get(12)?,
+            created_at: row.get(13)?,
+            updated_at: row.get(14)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::Database;
+    use crate::models::WorkflowRunStatus;
+    use serde_json::json;
+
+    #[test]
+    fn test_checkpoint_and_interrupt() {
+        let db = Database::new(":memory:").unwrap();
+        let variables = json!({"symbol": "USDC"}).as_object().unwrap().clone();
+        let mut run = db.create_workflow_run(7, 1, "swap", "(steps: [])", &variables).unwrap();
+        assert_eq!(run.status, WorkflowRunStatus::Running);
+        assert_eq!(run.variables.get("symbol"), Some(&json!("USDC")));
+
+        run.current_step = 2;
+        run.registers = json!({"sell_token": {"value": "0xabc", "source_tool": "token_lookup"}});
+        db.save_workflow_run(&run).unwrap();
+
+        // A restart finds the run mid-step
+        assert_eq!(db.mark_running_workflow_runs_interrupted().unwrap(), 1);
+        let open = db.get_open_workflow_run(7).unwrap().unwrap();
+        assert_eq!(open.status, WorkflowRunStatus::Interrupted);
+        assert_eq!(open.current_step, 2);
+        assert_eq!(open.registers["sell_token"]["value"], "0xabc");
+
+        run.status = WorkflowRunStatus::Completed;
+        db.save_workflow_run(&run).unwrap();
+        assert!(db.get_open_workflow_run(7).unwrap().is_none());
+    }
+}
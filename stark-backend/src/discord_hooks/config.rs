@@ -5,7 +5,7 @@ use std::sync::Arc;
 
 use crate::db::Database;
 use crate::models::ChannelSettingKey;
-use serenity::all::{Context, Message, Permissions};
+use serenity::all::{Context, GuildId, Message, Permissions};
 
 /// Configuration for the Discord hooks module
 #[derive(Debug, Clone)]
@@ -17,6 +17,8 @@ pub struct DiscordHooksConfig {
     pub require_mention_in_servers: bool,
     /// Whether to allow DMs without @mention (default: true)
     pub allow_dm_without_mention: bool,
+    /// Whether server-channel conversations move into their own thread (default: false)
+    pub auto_thread: bool,
 }
 
 impl DiscordHooksConfig {
@@ -50,10 +52,18 @@ impl DiscordHooksConfig {
             );
         }
 
+        let auto_thread = db
+            .get_channel_setting(channel_id, ChannelSettingKey::DiscordAutoThread.as_ref())
+            .ok()
+            .flatten()
+            .map(|v| v == "true")
+            .unwrap_or(false);
+
         Self {
             admin_user_ids: admin_ids,
             require_mention_in_servers: true,
             allow_dm_without_mention: true,
+            auto_thread,
         }
     }
 
@@ -86,6 +96,7 @@ impl DiscordHooksConfig {
             admin_user_ids: admin_ids,
             require_mention_in_servers: true,
             allow_dm_without_mention: true,
+            auto_thread: false,
         }
     }
 
@@ -95,6 +106,7 @@ impl DiscordHooksConfig {
             admin_user_ids: HashSet::new(),
             require_mention_in_servers: true,
             allow_dm_without_mention: true,
+            auto_thread: false,
         }
     }
 
@@ -104,6 +116,7 @@ impl DiscordHooksConfig {
             admin_user_ids: admin_ids.into_iter().collect(),
             require_mention_in_servers: true,
             allow_dm_without_mention: true,
+            auto_thread: false,
         }
     }
 
@@ -124,6 +137,28 @@ impl DiscordHooksConfig {
         Self::has_discord_admin_permission(msg, ctx).await
     }
 
+    /// Check if the user of a slash command is admin. Same rules as `is_admin`, but uses the
+    /// permissions Discord resolves into the interaction instead of fetching the guild.
+    pub fn is_admin_for_interaction(
+        &self,
+        user_id: &str,
+        guild_id: Option<GuildId>,
+        permissions: Option<Permissions>,
+    ) -> bool {
+        if !self.admin_user_ids.is_empty() {
+            return self.admin_user_ids.contains(user_id);
+        }
+
+        // DMs don't have guild permissions - treat as admin, matching message handling
+        if guild_id.is_none() {
+            return true;
+        }
+
+        permissions
+            .map(|p| p.contains(Permissions::ADMINISTRATOR))
+            .unwrap_or(false)
+    }
+
     /// Check if the message author has Discord Administrator permission
     pub async fn has_discord_admin_permission(msg: &Message, ctx: &Context) -> bool {
         // DMs don't have guild permissions - treat as admin for convenience
@@ -237,4 +272,21 @@ mod tests {
         assert_eq!(config.admin_count(), 2);
         assert!(config.has_explicit_admins());
     }
+
+    #[test]
+    fn test_is_admin_for_interaction() {
+        let guild = Some(GuildId::new(1));
+
+        // Explicit list wins over the Administrator permission
+        let config = DiscordHooksConfig::with_admins(vec!["123".to_string()]);
+        assert!(config.is_admin_for_interaction("123", guild, None));
+        assert!(!config.is_admin_for_interaction("456", guild, Some(Permissions::ADMINISTRATOR)));
+
+        // No explicit admins: fall back to the resolved permission
+        let config = DiscordHooksConfig::empty();
+        assert!(config.is_admin_for_interaction("456", guild, Some(Permissions::ADMINISTRATOR)));
+        assert!(!config.is_admin_for_interaction("456", guild, Some(Permissions::SEND_MESSAGES)));
+        assert!(!config.is_admin_for_interaction("456", guild, None));
+        assert!(config.is_admin_for_interaction("456", None, None));
+    }
 }
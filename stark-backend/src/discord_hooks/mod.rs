@@ -5,6 +5,7 @@
 //! - Limited command handling for regular users (register, status, help)
 //! - Discord user profile management with public address registration
 //! - Tool for resolving Discord mentions to registered public addresses
+//! - Application (slash) command definitions
 //!
 //! ## Admin Flow
 //!
@@ -15,6 +16,7 @@
 pub mod commands;
 pub mod config;
 pub mod db;
+pub mod slash;
 pub mod tools;
 
 use rand::seq::SliceRandom;
@@ -129,6 +131,9 @@ pub fn extract_command_text(content: &str, bot_id: UserId) -> String {
 /// - `handled: true` with `response` - Send the response directly
 /// - `handled: true` with `forward_to_agent` - Forward to agent dispatcher
 ///
+/// `in_bot_thread` is set when the message was posted in a thread the bot opened
+/// for a conversation; those messages are handled without a mention.
+///
 /// Note: The config is reloaded from the database on each message to pick up
 /// changes to admin user IDs without requiring a channel restart.
 pub async fn process(
@@ -136,6 +141,7 @@ pub async fn process(
     ctx: &Context,
     db: &std::sync::Arc<crate::db::Database>,
     channel_id: i64,
+    in_bot_thread: bool,
 ) -> Result<ProcessResult, String> {
     // Reload config from database to pick up any changes
     let config = DiscordHooksConfig::from_channel_settings(db, channel_id);
@@ -168,8 +174,8 @@ pub async fn process(
         is_reply_to_bot
     );
 
-    // Check if bot is mentioned OR if user is replying to the bot
-    if !is_reply_to_bot && !is_bot_mentioned(msg, bot_id) {
+    // Check if bot is mentioned OR if user is replying to the bot OR it's one of the bot's threads
+    if !is_reply_to_bot && !in_bot_thread && !is_bot_mentioned(msg, bot_id) {
         // Check if they mentioned a role the bot has (common mistake)
         if !msg.mention_roles.is_empty() {
             if let Some(guild_id) = msg.guild_id {
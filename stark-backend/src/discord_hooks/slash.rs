@@ -0,0 +1,158 @@
+//! Application (slash) commands
+//!
+//! Registered globally when the bot connects. `/ask` goes through the same agent
+//! path as an @mention; `/reset` and `/thinking` reuse the dispatcher's `/reset`
+//! and `/think:<level>` directives so sessions behave the same as text commands.
+
+use serenity::all::{CommandData, CommandOptionType, CreateCommand, CreateCommandOption};
+
+use crate::tx_queue::QueuedTxSummary;
+
+/// Thinking levels offered as `/thinking` choices (label, value)
+const THINKING_LEVELS: &[(&str, &str)] = &[
+    ("Off", "off"),
+    ("Minimal (~1K tokens)", "minimal"),
+    ("Low (~4K tokens)", "low"),
+    ("Medium (~10K tokens)", "medium"),
+    ("High (~32K tokens)", "high"),
+    ("Maximum (~64K tokens)", "xhigh"),
+];
+
+/// Most recent transactions shown by `/tx list`
+const TX_LIST_LIMIT: usize = 10;
+
+/// A parsed slash command invocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlashCommand {
+    /// `/ask prompt:<text>` - ask the agent
+    Ask(String),
+    /// `/reset` - start a new session in this channel or thread
+    Reset,
+    /// `/thinking level:<level>` - set the session's thinking level
+    Thinking(String),
+    /// `/tx list` - recent transactions queued from this channel
+    TxList,
+    /// `/status` - access level and registration status
+    Status,
+}
+
+impl SlashCommand {
+    /// Commands that change the agent's session or expose wallet activity
+    pub fn requires_admin(&self) -> bool {
+        matches!(self, Self::Reset | Self::Thinking(_) | Self::TxList)
+    }
+}
+
+/// Command definitions registered with Discord
+pub fn definitions() -> Vec<CreateCommand> {
+    let level = THINKING_LEVELS.iter().fold(
+        CreateCommandOption::new(CommandOptionType::String, "level", "Thinking level")
+            .required(true),
+        |option, (label, value)| option.add_string_choice(*label, *value),
+    );
+
+    vec![
+        CreateCommand::new("ask")
+            .description("Ask the agent")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "prompt", "What do you want to ask?")
+                    .required(true)
+                    .max_length(4000),
+            ),
+        CreateCommand::new("reset").description("Start a new conversation in this channel or thread"),
+        CreateCommand::new("thinking")
+            .description("Set how much the agent thinks before answering")
+            .add_option(level),
+        CreateCommand::new("tx")
+            .description("Transactions queued from this channel")
+            .add_option(CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "list",
+                "List recent transactions",
+            )),
+        CreateCommand::new("status").description("Show your access level and registration status"),
+    ]
+}
+
+/// Parse an invoked command. Returns None for unknown commands or missing options.
+pub fn parse(data: &CommandData) -> Option<SlashCommand> {
+    let string_option = |name: &str| {
+        data.options
+            .iter()
+            .find(|o| o.name == name)
+            .and_then(|o| o.value.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    match data.name.as_str() {
+        "ask" => string_option("prompt").map(SlashCommand::Ask),
+        "reset" => Some(SlashCommand::Reset),
+        "thinking" => string_option("level").map(SlashCommand::Thinking),
+        "tx" => match data.options.first() {
+            Some(sub) if sub.name == "list" => Some(SlashCommand::TxList),
+            _ => None,
+        },
+        "status" => Some(SlashCommand::Status),
+        _ => None,
+    }
+}
+
+/// Format `/tx list` output, newest first
+pub fn format_tx_list(mut txs: Vec<QueuedTxSummary>) -> String {
+    if txs.is_empty() {
+        return "No transactions have been queued from this channel.".to_string();
+    }
+
+    txs.sort_by_key(|t| std::cmp::Reverse(t.created_at));
+    let total = txs.len();
+    let mut out = String::from("**Recent transactions**\n");
+    for tx in txs.iter().take(TX_LIST_LIMIT) {
+        out.push_str(&format!(
+            "\n`{}` **{}** — {} to `{}` on {} ({} UTC)",
+            tx.uuid,
+            tx.status,
+            tx.value_formatted,
+            tx.to,
+            tx.network,
+            tx.created_at.format("%Y-%m-%d %H:%M"),
+        ));
+        if let Some(url) = &tx.explorer_url {
+            out.push_str(&format!(" · <{}>", url));
+        }
+    }
+    if total > TX_LIST_LIMIT {
+        out.push_str(&format!("\n\n…and {} older", total - TX_LIST_LIMIT));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::ThinkingLevel;
+
+    #[test]
+    fn test_thinking_choices_are_valid_levels() {
+        for (_, value) in THINKING_LEVELS {
+            assert!(ThinkingLevel::from_str(value).is_some(), "invalid level {}", value);
+        }
+    }
+
+    #[test]
+    fn test_requires_admin() {
+        assert!(!SlashCommand::Ask("hi".to_string()).requires_admin());
+        assert!(!SlashCommand::Status.requires_admin());
+        assert!(SlashCommand::Reset.requires_admin());
+        assert!(SlashCommand::Thinking("low".to_string()).requires_admin());
+        assert!(SlashCommand::TxList.requires_admin());
+    }
+
+    #[test]
+    fn test_format_empty_tx_list() {
+        assert_eq!(
+            format_tx_list(Vec::new()),
+            "No transactions have been queued from this channel."
+        );
+    }
+}
@@ -184,7 +184,7 @@ impl DiskQuotaManager {
 }
 
 /// Format bytes into a human-readable string (e.g. "12.3MB").
-fn format_bytes(bytes: u64) -> String {
+pub(crate) fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = 1024 * 1024;
     const GB: u64 = 1024 * 1024 * 1024;
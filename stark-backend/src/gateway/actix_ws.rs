@@ -7,6 +7,8 @@ use crate::db::Database;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::methods;
 use crate::gateway::protocol::{ChannelIdParams, RpcError, RpcRequest, RpcResponse};
+use crate::middleware::auth_guard;
+use crate::models::DashboardPrincipal;
 use crate::tx_queue::TxQueueManager;
 use crate::wallet::WalletProvider;
 use actix_web::{web, HttpRequest, HttpResponse};
@@ -75,14 +77,14 @@ async fn handle_ws_connection(
         .max_continuation_size(64 * 1024);
 
     // Phase 1: Authentication required before full access
-    let authenticated = match tokio::time::timeout(
+    let principal = match tokio::time::timeout(
         Duration::from_secs(AUTH_TIMEOUT_SECS),
         wait_for_auth(&mut session, &mut msg_stream, &db),
     )
     .await
     {
-        Ok(Ok(true)) => true,
-        Ok(Ok(false)) => {
+        Ok(Ok(Some(principal))) => principal,
+        Ok(Ok(None)) => {
             log::warn!("Gateway client failed authentication");
             let _ = session.close(None).await;
             return;
@@ -106,12 +108,7 @@ async fn handle_ws_connection(
         }
     };
 
-    if !authenticated {
-        let _ = session.close(None).await;
-        return;
-    }
-
-    log::info!("Gateway client authenticated successfully");
+    log::info!("Gateway client '{}' authenticated successfully", principal.username);
 
     // Phase 2: Full access after authentication
     // Subscribe to events
@@ -184,7 +181,7 @@ async fn handle_ws_connection(
         match msg_result {
             Ok(AggregatedMessage::Text(text)) => {
                 log::debug!("[DATAGRAM] <<< FROM AGENT (RPC request):\n{}", text);
-                let response = process_request(&text, &db, &channel_manager, &broadcaster, &tx_queue, &wallet_provider).await;
+                let response = process_request(&text, &principal, &db, &channel_manager, &broadcaster, &tx_queue, &wallet_provider).await;
                 if let Ok(json) = serde_json::to_string(&response) {
                     let _ = tx.send(json).await;
                 }
@@ -212,12 +209,14 @@ async fn handle_ws_connection(
     log::info!("Gateway client {} disconnected", client_id);
 }
 
-/// Wait for authentication from the client
+/// Wait for authentication from the client.
+/// Only dashboard login sessions may open the socket: API tokens are rejected because
+/// the event stream is not scoped and `/ws` sits outside the `/api` auth guard.
 async fn wait_for_auth(
     session: &mut actix_ws::Session,
     msg_stream: &mut (impl StreamExt<Item = Result<AggregatedMessage, actix_ws::ProtocolError>> + Unpin),
     db: &Arc<Database>,
-) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<Option<DashboardPrincipal>, Box<dyn std::error::Error + Send + Sync>> {
     while let Some(msg_result) = msg_stream.next().await {
         match msg_result {
             Ok(AggregatedMessage::Text(text)) => {
@@ -256,17 +255,28 @@ async fn wait_for_auth(
                             }
                         };
 
+                        if params.token.starts_with(crate::dashboard_auth::API_TOKEN_PREFIX) {
+                            let response = RpcResponse::error(
+                                request.id,
+                                RpcError::new(-32001, "API tokens cannot open the gateway socket".to_string()),
+                            );
+                            if let Ok(json) = serde_json::to_string(&response) {
+                                let _ = session.text(json).await;
+                            }
+                            return Ok(None);
+                        }
+
                         // Validate token against database
-                        match db.validate_session(&params.token) {
-                            Ok(Some(_session)) => {
+                        match db.resolve_dashboard_principal(&params.token) {
+                            Ok(Some(principal)) => {
                                 let response = RpcResponse::success(
                                     request.id,
-                                    serde_json::json!({"authenticated": true}),
+                                    serde_json::json!({"authenticated": true, "role": principal.role.as_str()}),
                                 );
                                 if let Ok(json) = serde_json::to_string(&response) {
                                     let _ = session.text(json).await;
                                 }
-                                return Ok(true);
+                                return Ok(Some(principal));
                             }
                             Ok(None) => {
                                 let response = RpcResponse::error(
@@ -276,7 +286,7 @@ async fn wait_for_auth(
                                 if let Ok(json) = serde_json::to_string(&response) {
                                     let _ = session.text(json).await;
                                 }
-                                return Ok(false);
+                                return Ok(None);
                             }
                             Err(e) => {
                                 log::error!("Database error validating token: {}", e);
@@ -287,7 +297,7 @@ async fn wait_for_auth(
                                 if let Ok(json) = serde_json::to_string(&response) {
                                     let _ = session.text(json).await;
                                 }
-                                return Ok(false);
+                                return Ok(None);
                             }
                         }
                     }
@@ -316,7 +326,7 @@ async fn wait_for_auth(
                 let _ = session.pong(&data).await;
             }
             Ok(AggregatedMessage::Close(_)) => {
-                return Ok(false);
+                return Ok(None);
             }
             Err(e) => {
                 log::error!("WebSocket error during auth: {:?}", e);
@@ -326,11 +336,12 @@ async fn wait_for_auth(
         }
     }
 
-    Ok(false)
+    Ok(None)
 }
 
 async fn process_request(
     text: &str,
+    principal: &DashboardPrincipal,
     db: &Arc<Database>,
     channel_manager: &Arc<ChannelManager>,
     broadcaster: &Arc<EventBroadcaster>,
@@ -346,7 +357,13 @@ async fn process_request(
 
     let id = request.id.clone();
 
-    let result = dispatch_method(&request, db, channel_manager, broadcaster, tx_queue, wallet_provider).await;
+    let result = match authorize_rpc(principal, &request.method) {
+        Ok(()) => dispatch_method(&request, db, channel_manager, broadcaster, tx_queue, wallet_provider).await,
+        Err(error) => {
+            log::warn!("[AUTH] Denied RPC '{}' for '{}': {}", request.method, principal.username, error.message);
+            Err(error)
+        }
+    };
 
     match result {
         Ok(value) => RpcResponse::success(id, value),
@@ -354,6 +371,24 @@ async fn process_request(
     }
 }
 
+/// REST resource whose write access a state-changing RPC method needs
+fn rpc_write_resource(method: &str) -> Option<&'static str> {
+    match method {
+        "channels.start" | "channels.stop" | "channels.restart" => Some("channels"),
+        "tx_queue.confirm" | "tx_queue.deny" => Some("tx_queue"),
+        _ => None,
+    }
+}
+
+/// Apply the dashboard role rules of the `/api` auth guard to an RPC call
+fn authorize_rpc(principal: &DashboardPrincipal, method: &str) -> Result<(), RpcError> {
+    let Some(resource) = rpc_write_resource(method) else {
+        return Ok(());
+    };
+    auth_guard::authorize(principal, &actix_web::http::Method::POST, &format!("/api/{}/{}", resource, method))
+        .map_err(|reason| RpcError::new(-32003, reason))
+}
+
 async fn dispatch_method(
     request: &RpcRequest,
     db: &Arc<Database>,
@@ -396,3 +431,36 @@ async fn dispatch_method(
         _ => Err(RpcError::method_not_found()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DashboardRole;
+
+    fn principal(role: DashboardRole) -> DashboardPrincipal {
+        DashboardPrincipal {
+            user_id: Some(1),
+            username: "test".to_string(),
+            role,
+            api_token_id: None,
+            scopes: None,
+        }
+    }
+
+    #[test]
+    fn test_auditor_cannot_call_state_changing_methods() {
+        let auditor = principal(DashboardRole::Auditor);
+        for method in ["tx_queue.confirm", "tx_queue.deny", "channels.start", "channels.stop", "channels.restart"] {
+            assert!(authorize_rpc(&auditor, method).is_err(), "{} must be denied", method);
+        }
+        assert!(authorize_rpc(&auditor, "channels.status").is_ok());
+        assert!(authorize_rpc(&auditor, "status").is_ok());
+    }
+
+    #[test]
+    fn test_operator_can_confirm_transactions() {
+        let operator = principal(DashboardRole::Operator);
+        assert!(authorize_rpc(&operator, "tx_queue.confirm").is_ok());
+        assert!(authorize_rpc(&operator, "channels.restart").is_ok());
+    }
+}
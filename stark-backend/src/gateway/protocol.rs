@@ -709,8 +709,11 @@ impl GatewayEvent {
     // =====================================================
 
     /// Transaction queue confirmation required - partner mode needs user approval
+    /// `chat_id` scopes the prompt to the originating platform chat (in-chat approval buttons)
+    #[allow(clippy::too_many_arguments)]
     pub fn tx_queue_confirmation_required(
         channel_id: i64,
+        chat_id: Option<&str>,
         uuid: &str,
         network: &str,
         from: &str,
@@ -723,6 +726,7 @@ impl GatewayEvent {
             EventType::TxQueueConfirmationRequired,
             serde_json::json!({
                 "channel_id": channel_id,
+                "chat_id": chat_id,
                 "uuid": uuid,
                 "network": network,
                 "from": from,
@@ -0,0 +1,400 @@
+//! Self-service identity linking
+//!
+//! Users link their own accounts from any chat channel with `/link`:
+//! - `/link` shows this account's link ID and its linked accounts
+//! - `/link <channel_type>:<user_id>`, sent from account A, issues a one-time code
+//!   that only the named account can redeem
+//! - `/link <code>`, sent from the named account, verifies both and joins A's identity
+//! - `/link wallet <address>` returns a SIWE message to sign; `/link wallet <signature>`
+//!   verifies it and joins the wallet's identity (or links the wallet to this one)
+//!
+//! Codes are bound to their target account, so a code seen by someone else (e.g. in a
+//! group chat) is useless to them. Verified links share one `identity_id`, which keys
+//! memory and safe-mode rate limits.
+
+use chrono::{Duration, Utc};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use crate::channels::types::{ChannelType, NormalizedMessage};
+use crate::db::Database;
+use crate::models::VerificationKind;
+use crate::qmd_memory::MemoryStore;
+use crate::siwa::{build_siwa_message, recover_signer, SiwaMessageFields};
+
+/// Pseudo channel type for wallet links
+pub const WALLET_CHANNEL_TYPE: &str = "wallet";
+
+const CODE_TTL_MINS: i64 = 10;
+const WALLET_CHALLENGE_TTL_MINS: i64 = 15;
+const CODE_DIGITS: usize = 8;
+/// Wrong codes allowed against one pending link before it is discarded
+const MAX_CODE_ATTEMPTS: i32 = 5;
+
+/// A parsed `/link` command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkCommand {
+    Status,
+    Issue { channel_type: String, user_id: String },
+    Redeem(String),
+    WalletChallenge(String),
+    WalletSignature(String),
+    Usage,
+}
+
+/// Text the user typed, without the hint/context block channel adapters prepend
+fn command_body(text: &str) -> &str {
+    let body = text
+        .rsplit_once("[MESSAGE DIRECTED TO YOU:]")
+        .map(|(_, rest)| rest)
+        .unwrap_or(text)
+        .trim();
+    if body.starts_with('[') {
+        body.split_once("\n\n").map(|(_, rest)| rest.trim()).unwrap_or(body)
+    } else {
+        body
+    }
+}
+
+fn is_hex(s: &str) -> bool {
+    s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Parse a `/link` command; None if the message isn't one
+pub fn parse_command(text: &str) -> Option<LinkCommand> {
+    let body = command_body(text);
+    let mut parts = body.split_whitespace();
+    if !parts.next()?.eq_ignore_ascii_case("/link") {
+        return None;
+    }
+    let args: Vec<&str> = parts.collect();
+
+    let command = match args.as_slice() {
+        [] => LinkCommand::Status,
+        [kind, value] if kind.eq_ignore_ascii_case("wallet") => {
+            let hex_part = value.strip_prefix("0x").unwrap_or(value);
+            match hex_part.len() {
+                40 if is_hex(hex_part) => LinkCommand::WalletChallenge(format!("0x{}", hex_part.to_lowercase())),
+                130 if is_hex(hex_part) => LinkCommand::WalletSignature(format!("0x{}", hex_part)),
+                _ => LinkCommand::Usage,
+            }
+        }
+        [arg] => {
+            let digits: String = arg.chars().filter(|c| *c != '-').collect();
+            if digits.len() == CODE_DIGITS && digits.chars().all(|c| c.is_ascii_digit()) {
+                LinkCommand::Redeem(digits)
+            } else if let Some((channel_type, user_id)) = arg.split_once(':') {
+                LinkCommand::Issue {
+                    channel_type: channel_type.to_lowercase(),
+                    user_id: user_id.to_string(),
+                }
+            } else {
+                LinkCommand::Usage
+            }
+        }
+        _ => LinkCommand::Usage,
+    };
+    Some(command)
+}
+
+fn hash_code(code: &str) -> String {
+    hex::encode(Sha256::digest(code.as_bytes()))
+}
+
+fn generate_code() -> String {
+    let mut rng = rand::thread_rng();
+    (0..CODE_DIGITS).map(|_| char::from(b'0' + rng.gen_range(0..10u8))).collect()
+}
+
+fn usage(link_id: &str) -> String {
+    format!(
+        "**Link your accounts**\n\
+        Your link ID here is `{}`.\n\n\
+        - From this account: `/link <link ID of your other account>` — you get a one-time code\n\
+        - From the other account: `/link <code>` within {} minutes\n\
+        - Or prove a wallet: `/link wallet <address>`, then `/link wallet <signature>`",
+        link_id, CODE_TTL_MINS
+    )
+}
+
+/// Handle a `/link` command. Returns the reply, or None if the message isn't a link command.
+pub fn handle_command(
+    db: &Database,
+    memory_store: Option<&MemoryStore>,
+    message: &NormalizedMessage,
+) -> Option<String> {
+    let command = parse_command(&message.text)?;
+    let channel_type = message.channel_type.to_lowercase();
+    let link_id = format!("{}:{}", channel_type, message.user_id);
+
+    let result = match command {
+        LinkCommand::Status => status(db, message, &link_id),
+        LinkCommand::Usage => Ok(usage(&link_id)),
+        LinkCommand::Issue { channel_type: target_type, user_id: target_id } => {
+            issue_code(db, message, &target_type, &target_id)
+        }
+        LinkCommand::Redeem(code) => redeem_code(db, memory_store, message, &code, &link_id),
+        LinkCommand::WalletChallenge(address) => wallet_challenge(db, message, &address),
+        LinkCommand::WalletSignature(signature) => wallet_signature(db, memory_store, message, &signature),
+    };
+
+    Some(result.unwrap_or_else(|e| {
+        log::error!("[IDENTITY] /link failed for {}: {}", link_id, e);
+        "Sorry, linking failed due to an internal error.".to_string()
+    }))
+}
+
+/// Join the sender's identity into another one, carrying its memory files along
+fn join_with_memories(
+    db: &Database,
+    memory_store: Option<&MemoryStore>,
+    message: &NormalizedMessage,
+    into_identity_id: &str,
+    kind: VerificationKind,
+) -> Result<(), rusqlite::Error> {
+    let channel_type = message.channel_type.to_lowercase();
+    let previous = db.get_or_create_identity(&channel_type, &message.user_id, Some(&message.user_name))?;
+    db.join_identity(&channel_type, &message.user_id, Some(&message.user_name), into_identity_id, kind.as_str())?;
+    if let Some(store) = memory_store
+        && let Err(e) = store.merge_identity(&previous.identity_id, into_identity_id)
+    {
+        log::warn!("[IDENTITY] Failed to merge memory files into {}: {}", into_identity_id, e);
+    }
+    Ok(())
+}
+
+fn status(db: &Database, message: &NormalizedMessage, link_id: &str) -> Result<String, rusqlite::Error> {
+    let link = db.get_or_create_identity(&message.channel_type, &message.user_id, Some(&message.user_name))?;
+    let others: Vec<String> = db
+        .get_linked_identities(&link.identity_id)?
+        .into_iter()
+        .filter(|l| l.id != link.id)
+        .map(|l| {
+            format!(
+                "- `{}:{}`{}",
+                l.channel_type,
+                l.platform_user_id,
+                if l.is_verified { " ✅" } else { "" }
+            )
+        })
+        .collect();
+
+    let linked = if others.is_empty() {
+        "No other accounts are linked yet.".to_string()
+    } else {
+        format!("Linked accounts:\n{}", others.join("\n"))
+    };
+    Ok(format!("{}\n\n{}", usage(link_id), linked))
+}
+
+fn issue_code(
+    db: &Database,
+    message: &NormalizedMessage,
+    target_type: &str,
+    target_id: &str,
+) -> Result<String, rusqlite::Error> {
+    if ChannelType::from_str(target_type).is_none() || target_id.trim().is_empty() {
+        return Ok(format!(
+            "`{}:{}` isn't a valid link ID. Send `/link` from your other account to see its link ID.",
+            target_type, target_id
+        ));
+    }
+    if target_type == message.channel_type.to_lowercase() && target_id == message.user_id {
+        return Ok("That's this account. Use the link ID of your other account.".to_string());
+    }
+
+    // Make sure this account has an identity for the target to join
+    db.get_or_create_identity(&message.channel_type, &message.user_id, Some(&message.user_name))?;
+
+    let code = generate_code();
+    db.create_identity_verification(
+        VerificationKind::Code,
+        &message.channel_type.to_lowercase(),
+        &message.user_id,
+        target_type,
+        target_id,
+        &hash_code(&code),
+        Utc::now() + Duration::minutes(CODE_TTL_MINS),
+    )?;
+
+    log::info!(
+        "[IDENTITY] Issued link code from {}:{} for {}:{}",
+        message.channel_type, message.user_id, target_type, target_id
+    );
+    Ok(format!(
+        "Your link code is **{}**.\n\nFrom `{}:{}`, send `/link {}` within {} minutes. \
+        Only that account can use this code.",
+        code, target_type, target_id, code, CODE_TTL_MINS
+    ))
+}
+
+fn redeem_code(
+    db: &Database,
+    memory_store: Option<&MemoryStore>,
+    message: &NormalizedMessage,
+    code: &str,
+    link_id: &str,
+) -> Result<String, rusqlite::Error> {
+    let channel_type = message.channel_type.to_lowercase();
+    let pending = db.list_identity_verifications_for_target(&channel_type, &message.user_id)?;
+    if pending.is_empty() {
+        return Ok(format!(
+            "There is no pending link for this account. From your other account, send `/link {}` first.",
+            link_id
+        ));
+    }
+
+    let code_hash = hash_code(code);
+    let Some(verification) = pending.iter().find(|v| v.secret == code_hash) else {
+        for v in &pending {
+            if db.record_identity_verification_attempt(v.id)? >= MAX_CODE_ATTEMPTS {
+                db.delete_identity_verification(v.id)?;
+            }
+        }
+        return Ok("That code is invalid or has expired.".to_string());
+    };
+
+    let Some(issuer) = db.get_identity_by_platform(&verification.channel_type, &verification.platform_user_id)? else {
+        db.delete_identity_verification(verification.id)?;
+        return Ok("The account that issued this code no longer exists.".to_string());
+    };
+
+    join_with_memories(db, memory_store, message, &issuer.identity_id, VerificationKind::Code)?;
+    db.mark_identity_verified(&issuer.channel_type, &issuer.platform_user_id, VerificationKind::Code.as_str())?;
+    db.delete_identity_verification(verification.id)?;
+
+    Ok(format!(
+        "✅ Linked `{}` with `{}:{}`. Both accounts now share memories and limits.",
+        link_id, issuer.channel_type, issuer.platform_user_id
+    ))
+}
+
+fn wallet_challenge(db: &Database, message: &NormalizedMessage, address: &str) -> Result<String, rusqlite::Error> {
+    let checksummed = match address.parse::<ethers::types::Address>() {
+        Ok(addr) => ethers::utils::to_checksum(&addr, None),
+        Err(_) => return Ok("That isn't a valid wallet address.".to_string()),
+    };
+    let channel_type = message.channel_type.to_lowercase();
+    let now = Utc::now();
+    let expires_at = now + Duration::minutes(WALLET_CHALLENGE_TTL_MINS);
+    let nonce: String = uuid::Uuid::new_v4().simple().to_string()[..16].to_string();
+
+    let challenge = build_siwa_message(&SiwaMessageFields {
+        domain: "starkbot".to_string(),
+        address: checksummed,
+        uri: "starkbot:identity-link".to_string(),
+        agent_id: None,
+        agent_registry: None,
+        chain_id: 1,
+        nonce,
+        issued_at: now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        expiration_time: expires_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        statement: Some(format!(
+            "Link this wallet to {}:{} on StarkBot.",
+            channel_type, message.user_id
+        )),
+    });
+
+    db.create_identity_verification(
+        VerificationKind::Wallet,
+        &channel_type,
+        &message.user_id,
+        WALLET_CHANNEL_TYPE,
+        address,
+        &challenge,
+        expires_at,
+    )?;
+
+    Ok(format!(
+        "Sign this exact message with your wallet (personal_sign), then send `/link wallet <signature>` \
+        within {} minutes:\n\n```\n{}\n```",
+        WALLET_CHALLENGE_TTL_MINS, challenge
+    ))
+}
+
+fn wallet_signature(
+    db: &Database,
+    memory_store: Option<&MemoryStore>,
+    message: &NormalizedMessage,
+    signature: &str,
+) -> Result<String, rusqlite::Error> {
+    let channel_type = message.channel_type.to_lowercase();
+    let pending = db
+        .get_identity_verification(VerificationKind::Wallet, &channel_type, &message.user_id)?
+        .filter(|v| v.expires_at > Utc::now());
+    let Some(verification) = pending else {
+        return Ok("There is no pending wallet link. Start with `/link wallet <address>`.".to_string());
+    };
+
+    if recover_signer(&verification.secret, signature).as_deref() != Some(verification.target_user_id.as_str()) {
+        return Ok("That signature doesn't match the wallet and message. Sign the exact message you were sent.".to_string());
+    }
+
+    let address = verification.target_user_id.clone();
+    match db.get_identity_by_platform(WALLET_CHANNEL_TYPE, &address)? {
+        // The wallet already belongs to an identity — join it
+        Some(wallet_link) => {
+            join_with_memories(db, memory_store, message, &wallet_link.identity_id, VerificationKind::Wallet)?;
+        }
+        None => {
+            let link = db.get_or_create_identity(&channel_type, &message.user_id, Some(&message.user_name))?;
+            db.link_identity(
+                &link.identity_id,
+                WALLET_CHANNEL_TYPE,
+                &address,
+                None,
+                Some(VerificationKind::Wallet.as_str()),
+            )?;
+            db.mark_identity_verified(&channel_type, &message.user_id, VerificationKind::Wallet.as_str())?;
+        }
+    }
+    db.delete_identity_verification(verification.id)?;
+
+    log::info!("[IDENTITY] {}:{} verified wallet {}", channel_type, message.user_id, address);
+    Ok(format!(
+        "✅ Wallet `{}` verified and linked. Any account that proves this wallet joins the same identity.",
+        address
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("/link"), Some(LinkCommand::Status));
+        assert_eq!(parse_command("hello"), None);
+        assert_eq!(parse_command("/linker"), None);
+        assert_eq!(
+            parse_command("/link matrix:@me:example.org"),
+            Some(LinkCommand::Issue { channel_type: "matrix".into(), user_id: "@me:example.org".into() })
+        );
+        assert_eq!(parse_command("/link 1234-5678"), Some(LinkCommand::Redeem("12345678".into())));
+        assert_eq!(
+            parse_command("/link wallet 0xABCDEFabcdef0123456789abcdef0123456789AB"),
+            Some(LinkCommand::WalletChallenge("0xabcdefabcdef0123456789abcdef0123456789ab".into()))
+        );
+        let sig = format!("0x{}", "ab".repeat(65));
+        assert_eq!(parse_command(&format!("/link wallet {}", sig)), Some(LinkCommand::WalletSignature(sig)));
+        assert_eq!(parse_command("/link wallet nope"), Some(LinkCommand::Usage));
+        assert_eq!(parse_command("/link something"), Some(LinkCommand::Usage));
+    }
+
+    #[test]
+    fn test_parse_strips_adapter_hints() {
+        assert_eq!(parse_command("[MATRIX MESSAGE]\n\n/link"), Some(LinkCommand::Status));
+        assert_eq!(
+            parse_command("[DISCORD MESSAGE]\n\n[RECENT CHAT CONTEXT:]\n@a: hi\n\n[MESSAGE DIRECTED TO YOU:]\n/link 12345678"),
+            Some(LinkCommand::Redeem("12345678".into()))
+        );
+    }
+
+    #[test]
+    fn test_generate_code() {
+        let code = generate_code();
+        assert_eq!(code.len(), CODE_DIGITS);
+        assert!(code.chars().all(|c| c.is_ascii_digit()));
+        assert_ne!(hash_code(&code), code);
+    }
+}
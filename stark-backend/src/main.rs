@@ -4,6 +4,7 @@ use actix_web::{middleware::Logger, web, App, HttpServer};
 use dotenv::dotenv;
 use std::sync::Arc;
 
+mod a2a;
 mod ai;
 mod ai_endpoint_config;
 mod backup;
@@ -11,6 +12,7 @@ mod channels;
 mod config;
 mod context;
 mod controllers;
+mod dashboard_auth;
 mod db;
 mod disk_quota;
 mod discord_hooks;
@@ -22,6 +24,9 @@ mod middleware;
 mod models;
 mod qmd_memory;
 mod scheduler;
+mod session_export;
+mod webhooks;
+mod workflows;
 mod skills;
 mod tools;
 mod siwa;
@@ -36,6 +41,7 @@ mod tx_queue;
 mod web3;
 mod keystore_client;
 mod identity_client;
+mod identity_linking;
 mod modules;
 mod telemetry;
 
@@ -337,7 +343,8 @@ async fn restore_backup_data(
             old_to_new_id.get(&conn.parent_id),
             old_to_new_id.get(&conn.child_id),
         ) {
-            match db.create_mind_node_connection(parent_id, child_id) {
+            let edge_type = db::tables::mind_nodes::MindEdgeType::from_str(&conn.edge_type).unwrap_or_default();
+            match db.create_typed_mind_node_connection(parent_id, child_id, edge_type, conn.weight) {
                 Ok(_) => restored_connections += 1,
                 Err(e) => {
                     if !e.to_string().contains("UNIQUE constraint") {
@@ -371,6 +378,7 @@ async fn restore_backup_data(
             settings.theme_accent.as_deref(),
             None, // Don't restore proxy_url - it's infrastructure config
             None, // Don't restore kanban_auto_execute - keep current setting
+            None, // Don't restore kanban_max_concurrent - keep current setting
         ) {
             Ok(_) => log::info!("[Keystore] Restored bot settings"),
             Err(e) => log::warn!("[Keystore] Failed to restore bot settings: {}", e),
@@ -669,6 +677,7 @@ async fn restore_backup_data(
             tags: skill_entry.tags.clone(),
             subagent_type: skill_entry.subagent_type.clone(),
             requires_api_keys,
+            workflow: skill_entry.workflow.clone(),
             created_at: now.clone(),
             updated_at: now.clone(),
         };
@@ -714,6 +723,9 @@ async fn restore_backup_data(
                 entry.secret_key.as_deref(),
             ) {
                 Ok(saved) => {
+                    if let Err(e) = db.set_agent_settings_vision(&entry.endpoint, entry.supports_vision) {
+                        log::warn!("[Keystore] Failed to restore vision setting for {}: {}", entry.endpoint, e);
+                    }
                     if !entry.enabled {
                         let _ = db.disable_agent_settings();
                     }
@@ -872,6 +884,8 @@ async fn main() -> std::io::Result<()> {
     tools::rpc_config::load_rpc_providers(config_dir);
     log::info!("Loading AI endpoint presets from config directory");
     ai_endpoint_config::load_ai_endpoints(config_dir);
+    log::info!("Loading agent subtypes from config directory");
+    ai::multi_agent::subtype_config::load_agent_subtypes(config_dir);
     log::info!("Loading x402 payment limit defaults from config directory");
     x402::payment_limits::load_defaults(config_dir);
 
@@ -1000,6 +1014,7 @@ async fn main() -> std::io::Result<()> {
 
     let tool_registry = Arc::new(tool_registry_mut);
     log::info!("Registered {} tools", tool_registry.len());
+    ai::multi_agent::subtype_config::check_required_tools(&tool_registry);
 
     // Initialize Skill Registry (database-backed)
     log::info!("Initializing skill registry");
@@ -1012,6 +1027,13 @@ async fn main() -> std::io::Result<()> {
     });
     log::info!("Loaded {} skills from disk, {} total in database", skill_count, skill_registry.len());
 
+    // Workflow runs still marked running were cut off mid-step by the last shutdown
+    match db.mark_running_workflow_runs_interrupted() {
+        Ok(0) => {}
+        Ok(n) => log::info!("[WORKFLOW] Marked {} workflow run(s) interrupted; they continue when their session resumes", n),
+        Err(e) => log::warn!("[WORKFLOW] Failed to mark interrupted workflow runs: {}", e),
+    }
+
     // Load skills from enabled modules
     {
         let installed_modules = db.list_installed_modules().unwrap_or_default();
@@ -1307,6 +1329,8 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::Data::new(Arc::clone(&bcast)))
             .app_data(web::Data::new(Arc::clone(&tx_q)))
             .app_data(web::Data::new(wallet_prov.clone()))
+            // Dashboard auth (roles + API token scopes) runs innermost, after CORS
+            .wrap(actix_web::middleware::from_fn(middleware::auth_guard::auth_guard))
             .wrap(Logger::default())
             .wrap(cors)
             .configure(controllers::health::config_routes)
@@ -1338,6 +1362,12 @@ async fn main() -> std::io::Result<()> {
             .configure(controllers::x402_limits::config)
             .configure(controllers::telemetry::config)
             .configure(controllers::external_channel::config)
+            .configure(controllers::a2a::config)
+            .configure(controllers::triggers::config)
+            .configure(controllers::webhooks::config)
+            .configure(controllers::roles::config)
+            .configure(controllers::accounts::config)
+            .configure(controllers::api_tokens::config)
             // WebSocket Gateway route (same port as HTTP, required for single-port platforms)
             .route("/ws", web::get().to(gateway::actix_ws::ws_handler));
 
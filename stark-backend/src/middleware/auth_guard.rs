@@ -0,0 +1,280 @@
+// Dashboard API authorization middleware
+//
+// Runs in front of every /api route except the public ones (health, login,
+// inbound webhooks, gateway and A2A endpoints with their own tokens, module proxies).
+// Resolves the bearer token to a DashboardPrincipal (login session or scoped API
+// token), checks the account role and token scopes against the route, and
+// attaches the principal to the request for handlers that need it.
+
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+
+use crate::models::{DashboardPrincipal, DashboardRole};
+use crate::AppState;
+
+/// Routes reachable without a dashboard credential
+const PUBLIC_PREFIXES: &[&str] = &[
+    "/api/health",
+    "/api/version",
+    "/api/auth/",
+    "/api/webhooks/in/",
+    "/api/gateway/",
+    "/api/dev/",
+];
+
+/// Public routes matched exactly, so neighbouring paths don't inherit the exemption
+const PUBLIC_ROUTES: &[&str] = &["/api/a2a"];
+
+/// Resources that manage credentials; never reachable with an API token
+const CREDENTIAL_RESOURCES: &[&str] = &["accounts", "api_tokens"];
+
+/// Routes that return plaintext secrets despite being GETs: admin login sessions only
+const SECRET_ROUTES: &[&str] = &["/api/keys/value"];
+
+fn is_public(path: &str) -> bool {
+    // Token generation is a dashboard action despite living under /api/gateway
+    if path == "/api/gateway/token/generate" {
+        return false;
+    }
+    if PUBLIC_ROUTES.contains(&path) || PUBLIC_PREFIXES.iter().any(|p| path.starts_with(p)) {
+        return true;
+    }
+    // Module dashboards load proxied assets in an iframe without the auth header
+    let mut segments = path.trim_start_matches("/api/modules/").split('/');
+    path.starts_with("/api/modules/") && segments.next().is_some() && segments.next() == Some("proxy")
+}
+
+/// Scope resource name for a route: the first segment after /api/ with '-' as '_'
+/// (e.g. /api/tx-queue/pending -> "tx_queue")
+pub fn resource_for_path(path: &str) -> String {
+    path.trim_start_matches("/api/")
+        .split('/')
+        .next()
+        .unwrap_or_default()
+        .replace('-', "_")
+}
+
+fn is_read(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD)
+}
+
+/// Whether a scope list grants an access level on a resource.
+/// Scopes look like `kanban:write`, `tx_queue:read`, `kanban:*`, `*:read` or `*`;
+/// write implies read.
+pub fn scopes_allow(scopes: &[String], resource: &str, write: bool) -> bool {
+    scopes.iter().any(|scope| {
+        let (res, level) = scope.split_once(':').unwrap_or((scope.as_str(), "*"));
+        let res_ok = res == "*" || res == resource;
+        let level_ok = match level {
+            "*" | "write" => true,
+            "read" => !write,
+            _ => false,
+        };
+        res_ok && level_ok
+    })
+}
+
+/// Check that a scope string is well formed
+pub fn validate_scope(scope: &str) -> Result<(), String> {
+    let (res, level) = scope.split_once(':').unwrap_or((scope, "*"));
+    if res.is_empty() || !res.chars().all(|c| c.is_ascii_lowercase() || c == '_' || c == '*') {
+        return Err(format!("Invalid scope resource in '{}'", scope));
+    }
+    if CREDENTIAL_RESOURCES.contains(&res) {
+        return Err(format!("API tokens cannot be scoped to '{}'", res));
+    }
+    if !matches!(level, "read" | "write" | "*") {
+        return Err(format!("Scope level must be read, write or * in '{}'", scope));
+    }
+    Ok(())
+}
+
+/// Decide whether a principal may call a route
+pub fn authorize(principal: &DashboardPrincipal, method: &Method, path: &str) -> Result<(), String> {
+    let resource = resource_for_path(path);
+    let write = !is_read(method);
+
+    if SECRET_ROUTES.contains(&path) && (principal.scopes.is_some() || principal.role != DashboardRole::Admin) {
+        return Err("Only admin sessions can reveal secret values".to_string());
+    }
+
+    if let Some(ref scopes) = principal.scopes {
+        if CREDENTIAL_RESOURCES.contains(&resource.as_str()) {
+            return Err("API tokens cannot manage accounts or tokens".to_string());
+        }
+        if !scopes_allow(scopes, &resource, write) {
+            return Err(format!(
+                "Token lacks scope {}:{}",
+                resource,
+                if write { "write" } else { "read" }
+            ));
+        }
+    }
+
+    match resource.as_str() {
+        // Own profile, password and TOTP are open to every signed-in account
+        "accounts" if path.starts_with("/api/accounts/me") => Ok(()),
+        "accounts" if principal.role != DashboardRole::Admin => {
+            Err("Only admins can manage accounts".to_string())
+        }
+        // Listing/creating/revoking own tokens; the controller limits non-admins to their own
+        "api_tokens" => Ok(()),
+        _ if write && !principal.role.can_write() => {
+            Err(format!("Role '{}' is read-only", principal.role.as_str()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The principal attached by the middleware (None on public routes)
+pub fn principal(req: &HttpRequest) -> Option<DashboardPrincipal> {
+    req.extensions().get::<DashboardPrincipal>().cloned()
+}
+
+/// The path as the router matches it. `req.path()` is still percent-encoded,
+/// so `/api/%61ccounts` would dodge checks keyed on `/api/accounts` and still
+/// reach the accounts handlers.
+fn routed_path(req: &ServiceRequest) -> String {
+    req.match_info().as_str().to_string()
+}
+
+pub async fn auth_guard<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, actix_web::Error> {
+    let path = routed_path(&req);
+    if !path.starts_with("/api/") || *req.method() == Method::OPTIONS || is_public(&path) {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    }
+
+    let Some(state) = req.app_data::<web::Data<AppState>>().cloned() else {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    };
+
+    let token = super::session_auth::extract_token(req.request());
+    let principal = match token.as_deref().map(|t| state.db.resolve_dashboard_principal(t)) {
+        None => {
+            return Ok(deny(req, HttpResponse::Unauthorized(), "No authorization token provided"));
+        }
+        Some(Ok(Some(principal))) => principal,
+        Some(Ok(None)) => {
+            return Ok(deny(req, HttpResponse::Unauthorized(), "Invalid or expired session"));
+        }
+        Some(Err(e)) => {
+            log::error!("Session validation error: {}", e);
+            return Ok(deny(req, HttpResponse::InternalServerError(), "Internal server error"));
+        }
+    };
+
+    if let Err(reason) = authorize(&principal, req.method(), &path) {
+        log::warn!("[AUTH] Denied {} {} for '{}': {}", req.method(), path, principal.username, reason);
+        return Ok(deny(req, HttpResponse::Forbidden(), &reason));
+    }
+
+    req.extensions_mut().insert(principal);
+    next.call(req).await.map(ServiceResponse::map_into_left_body)
+}
+
+fn deny<B>(
+    req: ServiceRequest,
+    mut builder: actix_web::HttpResponseBuilder,
+    error: &str,
+) -> ServiceResponse<EitherBody<B>> {
+    let resp = builder.json(serde_json::json!({ "error": error }));
+    req.into_response(resp).map_into_right_body()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn principal(role: DashboardRole, scopes: Option<&[&str]>) -> DashboardPrincipal {
+        DashboardPrincipal {
+            user_id: Some(1),
+            username: "test".to_string(),
+            role,
+            api_token_id: scopes.map(|_| 1),
+            scopes: scopes.map(|s| s.iter().map(|x| x.to_string()).collect()),
+        }
+    }
+
+    #[test]
+    fn test_public_routes() {
+        assert!(is_public("/api/health"));
+        assert!(is_public("/api/auth/login"));
+        assert!(is_public("/api/webhooks/in/deploy"));
+        assert!(is_public("/api/modules/wallet/proxy/index.html"));
+        assert!(!is_public("/api/modules/wallet/status"));
+        assert!(!is_public("/api/webhooks"));
+        assert!(!is_public("/api/gateway/token/generate"));
+        assert!(is_public("/api/a2a"));
+        assert!(!is_public("/api/a2a-tasks"));
+        assert!(!is_public("/api/a2a/tasks"));
+    }
+
+    #[test]
+    fn test_role_authorization() {
+        let auditor = principal(DashboardRole::Auditor, None);
+        assert!(authorize(&auditor, &Method::GET, "/api/tx-queue/pending").is_ok());
+        assert!(authorize(&auditor, &Method::POST, "/api/kanban").is_err());
+        assert!(authorize(&auditor, &Method::POST, "/api/accounts/me/password").is_ok());
+        assert!(authorize(&auditor, &Method::GET, "/api/accounts").is_err());
+
+        let operator = principal(DashboardRole::Operator, None);
+        assert!(authorize(&operator, &Method::POST, "/api/kanban").is_ok());
+        assert!(authorize(&operator, &Method::POST, "/api/accounts").is_err());
+        assert!(authorize(&principal(DashboardRole::Admin, None), &Method::POST, "/api/accounts").is_ok());
+    }
+
+    #[test]
+    fn test_token_scopes() {
+        let token = principal(DashboardRole::Operator, Some(&["tx_queue:read", "kanban:write"]));
+        assert!(authorize(&token, &Method::GET, "/api/tx-queue/pending").is_ok());
+        assert!(authorize(&token, &Method::POST, "/api/tx-queue/1/approve").is_err());
+        assert!(authorize(&token, &Method::GET, "/api/kanban").is_ok());
+        assert!(authorize(&token, &Method::DELETE, "/api/kanban/3").is_ok());
+        assert!(authorize(&token, &Method::GET, "/api/memory").is_err());
+        assert!(authorize(&token, &Method::GET, "/api/api-tokens").is_err());
+
+        // Scopes never exceed the owner's role
+        let auditor_token = principal(DashboardRole::Auditor, Some(&["*"]));
+        assert!(authorize(&auditor_token, &Method::GET, "/api/memory").is_ok());
+        assert!(authorize(&auditor_token, &Method::POST, "/api/kanban").is_err());
+
+        assert!(validate_scope("kanban:write").is_ok());
+        assert!(validate_scope("*:read").is_ok());
+        assert!(validate_scope("accounts:read").is_err());
+        assert!(validate_scope("kanban:admin").is_err());
+    }
+
+    #[test]
+    fn test_secret_values_are_admin_session_only() {
+        let path = "/api/keys/value";
+        assert!(authorize(&principal(DashboardRole::Auditor, None), &Method::GET, path).is_err());
+        assert!(authorize(&principal(DashboardRole::Operator, None), &Method::GET, path).is_err());
+        assert!(authorize(&principal(DashboardRole::Admin, Some(&["keys:read"])), &Method::GET, path).is_err());
+        assert!(authorize(&principal(DashboardRole::Admin, Some(&["*:read"])), &Method::GET, path).is_err());
+        assert!(authorize(&principal(DashboardRole::Admin, None), &Method::GET, path).is_ok());
+        // Listing (masked) keys stays readable
+        assert!(authorize(&principal(DashboardRole::Auditor, None), &Method::GET, "/api/keys").is_ok());
+    }
+
+    #[test]
+    fn test_percent_encoded_paths_are_authorized_as_routed() {
+        use actix_web::test::TestRequest;
+
+        let operator = principal(DashboardRole::Operator, None);
+        let req = TestRequest::post().uri("/api/%61ccounts").to_srv_request();
+        assert_eq!(routed_path(&req), "/api/accounts");
+        assert!(authorize(&operator, &Method::POST, &routed_path(&req)).is_err());
+
+        let req = TestRequest::get().uri("/api/keys/%76alue?key_name=OPENAI").to_srv_request();
+        assert_eq!(routed_path(&req), "/api/keys/value");
+        assert!(authorize(&operator, &Method::GET, &routed_path(&req)).is_err());
+        let reader = principal(DashboardRole::Admin, Some(&["*:read"]));
+        assert!(authorize(&reader, &Method::GET, &routed_path(&req)).is_err());
+    }
+}
@@ -1 +1,2 @@
+pub mod auth_guard;
 pub mod session_auth;
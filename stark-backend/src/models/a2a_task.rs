@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+/// Lifecycle state of an A2A task (wire names follow the A2A spec)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum A2aTaskState {
+    Submitted,
+    Working,
+    InputRequired,
+    Completed,
+    Canceled,
+    Failed,
+    Rejected,
+    AuthRequired,
+    Unknown,
+}
+
+impl A2aTaskState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            A2aTaskState::Submitted => "submitted",
+            A2aTaskState::Working => "working",
+            A2aTaskState::InputRequired => "input-required",
+            A2aTaskState::Completed => "completed",
+            A2aTaskState::Canceled => "canceled",
+            A2aTaskState::Failed => "failed",
+            A2aTaskState::Rejected => "rejected",
+            A2aTaskState::AuthRequired => "auth-required",
+            A2aTaskState::Unknown => "unknown",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "submitted" => Some(A2aTaskState::Submitted),
+            "working" => Some(A2aTaskState::Working),
+            "input-required" => Some(A2aTaskState::InputRequired),
+            "completed" => Some(A2aTaskState::Completed),
+            "canceled" | "cancelled" => Some(A2aTaskState::Canceled),
+            "failed" => Some(A2aTaskState::Failed),
+            "rejected" => Some(A2aTaskState::Rejected),
+            "auth-required" => Some(A2aTaskState::AuthRequired),
+            "unknown" => Some(A2aTaskState::Unknown),
+            _ => None,
+        }
+    }
+
+    /// Terminal states never change again (a canceled task stays canceled even if its run finishes)
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            A2aTaskState::Completed | A2aTaskState::Canceled | A2aTaskState::Failed | A2aTaskState::Rejected
+        )
+    }
+}
+
+/// Which side of the conversation this bot is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum A2aDirection {
+    /// A peer sent us the task through /api/a2a
+    Inbound,
+    /// We sent the task to a peer with the a2a_agent tool
+    Outbound,
+}
+
+impl A2aDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            A2aDirection::Inbound => "inbound",
+            A2aDirection::Outbound => "outbound",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "inbound" => Some(A2aDirection::Inbound),
+            "outbound" => Some(A2aDirection::Outbound),
+            _ => None,
+        }
+    }
+}
+
+/// An agent-to-agent task, served by us (inbound) or delegated to a peer (outbound)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct A2aTask {
+    pub id: i64,
+    /// Task ID on the serving agent (ours for inbound, the peer's for outbound)
+    pub task_id: String,
+    pub direction: String,
+    /// A2A contextId grouping the tasks of one conversation
+    pub context_id: String,
+    /// Peer JSON-RPC endpoint (outbound only)
+    pub peer_url: Option<String>,
+    /// Peer EIP-8004 agent ID, when it was resolved through discovery (outbound only)
+    pub peer_agent_id: Option<i64>,
+    /// Who created the task (inbound): "token", the x402 payer address, or "public"
+    pub caller: Option<String>,
+    /// External channel serving the task (inbound only)
+    pub channel_id: Option<i64>,
+    /// Chat session the task ran in (inbound only)
+    pub session_id: Option<i64>,
+    pub message: String,
+    pub state: String,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    /// x402 amount in the asset's smallest unit (micro-USDC)
+    pub payment_amount: Option<String>,
+    /// Signed X-PAYMENT authorization received for the task, kept for settlement (inbound only)
+    #[serde(skip_serializing)]
+    pub payment_header: Option<String>,
+    /// EIP-3009 nonce of that authorization, used to reject replays (inbound only)
+    pub payment_nonce: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl A2aTask {
+    pub fn state_enum(&self) -> A2aTaskState {
+        A2aTaskState::from_str(&self.state).unwrap_or(A2aTaskState::Unknown)
+    }
+
+    pub fn direction_enum(&self) -> A2aDirection {
+        A2aDirection::from_str(&self.direction).unwrap_or(A2aDirection::Inbound)
+    }
+}
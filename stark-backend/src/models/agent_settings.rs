@@ -11,6 +11,8 @@ pub struct AgentSettings {
     pub max_context_tokens: i32,
     pub enabled: bool,
     pub secret_key: Option<String>,
+    /// Whether images are sent to this endpoint; None decides from the endpoint and archetype
+    pub supports_vision: Option<bool>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -32,6 +34,7 @@ impl Default for AgentSettings {
             max_context_tokens: DEFAULT_CONTEXT_TOKENS,
             enabled: true,
             secret_key: None,
+            supports_vision: None,
             created_at: now,
             updated_at: now,
         }
@@ -48,6 +51,7 @@ pub struct AgentSettingsResponse {
     pub max_context_tokens: i32,
     pub enabled: bool,
     pub has_secret_key: bool,
+    pub supports_vision: Option<bool>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -62,6 +66,7 @@ impl From<AgentSettings> for AgentSettingsResponse {
             max_context_tokens: settings.max_context_tokens,
             enabled: settings.enabled,
             has_secret_key: settings.secret_key.is_some(),
+            supports_vision: settings.supports_vision,
             created_at: settings.created_at,
             updated_at: settings.updated_at,
         }
@@ -79,6 +84,9 @@ pub struct UpdateAgentSettingsRequest {
     #[serde(default = "default_max_context_tokens")]
     pub max_context_tokens: i32,
     pub secret_key: Option<String>,
+    /// Vision override for this endpoint (omit or null for automatic)
+    #[serde(default)]
+    pub supports_vision: Option<bool>,
 }
 
 fn default_archetype() -> String {
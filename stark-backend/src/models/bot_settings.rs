@@ -8,6 +8,9 @@ pub const DEFAULT_MAX_TOOL_ITERATIONS: i32 = 100;
 /// Default max safe mode queries per user per 10 minutes
 pub const DEFAULT_SAFE_MODE_MAX_QUERIES_PER_10MIN: i32 = 5;
 
+/// Default max kanban tasks the scheduler auto-executes at once
+pub const DEFAULT_KANBAN_MAX_CONCURRENT: i32 = 2;
+
 /// Bot settings stored in database
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BotSettings {
@@ -37,6 +40,8 @@ pub struct BotSettings {
     pub proxy_url: Option<String>,
     /// Whether kanban "ready" tasks are auto-executed by the scheduler
     pub kanban_auto_execute: bool,
+    /// Maximum kanban tasks auto-executed at the same time
+    pub kanban_max_concurrent: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -59,6 +64,7 @@ impl Default for BotSettings {
             theme_accent: None,
             proxy_url: None,
             kanban_auto_execute: true,
+            kanban_max_concurrent: DEFAULT_KANBAN_MAX_CONCURRENT,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -85,4 +91,6 @@ pub struct UpdateBotSettingsRequest {
     pub proxy_url: Option<String>,
     /// Whether kanban "ready" tasks are auto-executed by the scheduler
     pub kanban_auto_execute: Option<bool>,
+    /// Maximum kanban tasks auto-executed at the same time
+    pub kanban_max_concurrent: Option<i32>,
 }
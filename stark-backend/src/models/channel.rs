@@ -9,6 +9,8 @@ pub enum ChannelType {
     Discord,
     Twitter,
     ExternalChannel,
+    Email,
+    Matrix,
 }
 
 impl ChannelType {
@@ -19,6 +21,8 @@ impl ChannelType {
             ChannelType::Discord => "discord",
             ChannelType::Twitter => "twitter",
             ChannelType::ExternalChannel => "external_channel",
+            ChannelType::Email => "email",
+            ChannelType::Matrix => "matrix",
         }
     }
 
@@ -29,6 +33,8 @@ impl ChannelType {
             "discord" => Some(ChannelType::Discord),
             "twitter" => Some(ChannelType::Twitter),
             "external_channel" => Some(ChannelType::ExternalChannel),
+            "email" => Some(ChannelType::Email),
+            "matrix" => Some(ChannelType::Matrix),
             _ => None,
         }
     }
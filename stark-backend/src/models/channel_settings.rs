@@ -58,6 +58,8 @@ pub enum ChannelSettingKey {
     /// Discord: Comma-separated list of Discord user IDs with admin access
     /// If empty, falls back to Discord's built-in Administrator permission
     DiscordAdminUserIds,
+    /// Discord: Open a thread for each conversation started in a server channel
+    DiscordAutoThread,
     /// Telegram: Bot authentication token from @BotFather
     TelegramBotToken,
     /// Slack: Bot OAuth token (xoxb-...)
@@ -84,6 +86,50 @@ pub enum ChannelSettingKey {
     ExternalChannelApiToken,
     /// External Gateway: Enable safe mode (restricts tool access for untrusted input)
     ExternalChannelSafeMode,
+    /// External Gateway: URL that scheduled results (cron jobs) are POSTed to
+    ExternalChannelCallbackUrl,
+    /// External Gateway: Serve the A2A task protocol (agent card + /api/a2a) through this channel
+    ExternalChannelA2aEnabled,
+    /// External Gateway: USDC price per A2A message for callers without the API token (x402)
+    ExternalChannelA2aPriceUsdc,
+    /// Email: Address the bot receives mail at and sends replies from
+    EmailAddress,
+    /// Email: IMAP/SMTP login (defaults to the email address)
+    EmailUsername,
+    /// Email: IMAP/SMTP password or app password
+    EmailPassword,
+    /// Email: IMAP server hostname
+    EmailImapHost,
+    /// Email: IMAP server port
+    EmailImapPort,
+    /// Email: IMAP connection security (tls, plain)
+    EmailImapSecurity,
+    /// Email: SMTP server hostname
+    EmailSmtpHost,
+    /// Email: SMTP server port
+    EmailSmtpPort,
+    /// Email: SMTP connection security (tls, starttls, plain)
+    EmailSmtpSecurity,
+    /// Email: Mailbox folder to watch
+    EmailMailbox,
+    /// Email: Poll interval in seconds when the server lacks IMAP IDLE
+    EmailPollIntervalSecs,
+    /// Email: Comma-separated sender addresses with admin access — everyone else uses safe mode
+    EmailAdminAddresses,
+    /// Email: Comma-separated addresses or @domains allowed to reach the agent (empty = anyone)
+    EmailAllowedSenders,
+    /// Email: Require a passing DMARC/DKIM Authentication-Results header for admin access
+    EmailRequireSenderAuth,
+    /// Email: Comma-separated authserv-ids of the receiving servers whose Authentication-Results are trusted
+    EmailTrustedAuthservIds,
+    /// Matrix: Homeserver base URL (e.g., "https://matrix.org")
+    MatrixHomeserverUrl,
+    /// Matrix: Access token of the bot account
+    MatrixAccessToken,
+    /// Matrix: Comma-separated Matrix user IDs with admin access — everyone else uses safe mode
+    MatrixAdminUserIds,
+    /// Matrix: Which room invites to accept (admins, anyone, off)
+    MatrixAutoJoin,
 }
 
 impl ChannelSettingKey {
@@ -93,6 +139,7 @@ impl ChannelSettingKey {
             Self::AutoStartOnBoot => "Auto-Start on Boot",
             Self::DiscordBotToken => "Bot Token",
             Self::DiscordAdminUserIds => "Admin User IDs (Optional)",
+            Self::DiscordAutoThread => "Thread Per Conversation",
             Self::TelegramBotToken => "Bot Token",
             Self::SlackBotToken => "Bot Token",
             Self::SlackAppToken => "App Token (Socket Mode)",
@@ -106,6 +153,28 @@ impl ChannelSettingKey {
             Self::SlackAdminUserIds => "Admin User IDs (Optional)",
             Self::ExternalChannelApiToken => "API Token",
             Self::ExternalChannelSafeMode => "Safe Mode",
+            Self::ExternalChannelCallbackUrl => "Callback URL (Optional)",
+            Self::ExternalChannelA2aEnabled => "A2A Endpoint",
+            Self::ExternalChannelA2aPriceUsdc => "A2A Price per Message (USDC)",
+            Self::EmailAddress => "Email Address",
+            Self::EmailUsername => "Username (Optional)",
+            Self::EmailPassword => "Password",
+            Self::EmailImapHost => "IMAP Host",
+            Self::EmailImapPort => "IMAP Port",
+            Self::EmailImapSecurity => "IMAP Security",
+            Self::EmailSmtpHost => "SMTP Host",
+            Self::EmailSmtpPort => "SMTP Port",
+            Self::EmailSmtpSecurity => "SMTP Security",
+            Self::EmailMailbox => "Mailbox",
+            Self::EmailPollIntervalSecs => "Poll Interval (seconds)",
+            Self::EmailAdminAddresses => "Admin Addresses (Optional)",
+            Self::EmailAllowedSenders => "Allowed Senders (Optional)",
+            Self::EmailRequireSenderAuth => "Require Sender Authentication",
+            Self::EmailTrustedAuthservIds => "Trusted Authentication Servers",
+            Self::MatrixHomeserverUrl => "Homeserver URL",
+            Self::MatrixAccessToken => "Access Token",
+            Self::MatrixAdminUserIds => "Admin User IDs (Optional)",
+            Self::MatrixAutoJoin => "Accept Room Invites",
         }
     }
 
@@ -126,6 +195,11 @@ impl ChannelSettingKey {
                  If any IDs are set, ONLY those users have admin access (Discord admin role is ignored). \
                  Get your ID: enable Developer Mode in Discord settings, then right-click your username."
             }
+            Self::DiscordAutoThread => {
+                "When enabled, a conversation started with an @mention or /ask in a server channel \
+                 opens a new thread. Each thread is its own session, and the bot answers every \
+                 message in its threads without needing a mention. Requires the Create Public Threads permission."
+            }
             Self::TelegramBotToken => {
                 "Your Telegram bot token from @BotFather. \
                  Create a bot with /newbot and copy the token provided."
@@ -187,6 +261,83 @@ impl ChannelSettingKey {
                  tool access is restricted to a safe subset. Disable for full agent access \
                  (only if you trust the clients connecting to this channel)."
             }
+            Self::ExternalChannelCallbackUrl => {
+                "Results of cron jobs delivered to this channel are POSTed here as JSON \
+                 ({\"text\", \"deliver_to\", \"source\"}), with the API token as a Bearer header."
+            }
+            Self::ExternalChannelA2aEnabled => {
+                "Publish an A2A agent card at /.well-known/agent-card.json and accept agent-to-agent \
+                 tasks at /api/a2a on this channel. Peers presenting the API token get this channel's \
+                 normal access; everyone else always runs in safe mode."
+            }
+            Self::ExternalChannelA2aPriceUsdc => {
+                "If set, A2A peers without the API token must pay this much USDC per message via x402 \
+                 (HTTP 402 + X-PAYMENT). Signed payment authorizations are verified and stored with \
+                 each task; settle them on-chain separately. Leave empty to serve A2A for free."
+            }
+            Self::EmailAddress => {
+                "The mailbox address the agent reads and replies from (e.g., 'agent@example.com'). \
+                 Use a dedicated mailbox — every unread message is answered."
+            }
+            Self::EmailUsername => {
+                "Login for the IMAP and SMTP servers. Leave empty to use the email address."
+            }
+            Self::EmailPassword => {
+                "Password for the IMAP and SMTP servers. For Gmail, Outlook and iCloud use an app password."
+            }
+            Self::EmailImapHost => "Hostname of the IMAP server (e.g., 'imap.fastmail.com').",
+            Self::EmailImapPort => "IMAP port — usually 993 for TLS or 143 for plain connections.",
+            Self::EmailImapSecurity => {
+                "Use TLS for real mail servers. Plain is only for local test servers (GreenMail, Dovecot containers)."
+            }
+            Self::EmailSmtpHost => "Hostname of the SMTP server used to send replies (e.g., 'smtp.fastmail.com').",
+            Self::EmailSmtpPort => "SMTP port — usually 465 for TLS, 587 for STARTTLS or 25 for plain connections.",
+            Self::EmailSmtpSecurity => {
+                "Use TLS or STARTTLS for real mail servers. Plain is only for local test servers."
+            }
+            Self::EmailMailbox => "IMAP folder to watch for incoming mail.",
+            Self::EmailPollIntervalSecs => {
+                "How often to check for new mail when the server does not support IMAP IDLE. \
+                 With IDLE, new mail is picked up immediately. Minimum is 15 seconds."
+            }
+            Self::EmailAdminAddresses => {
+                "Comma-separated sender addresses with full agent access. \
+                 Mail from any other sender is handled in safe mode. \
+                 WARNING: These senders get full agent access — only list addresses you control."
+            }
+            Self::EmailAllowedSenders => {
+                "Comma-separated addresses or domains (e.g., 'alice@example.com, @example.org') allowed to reach the agent. \
+                 Mail from other senders is marked read and ignored. Leave empty to answer anyone (in safe mode)."
+            }
+            Self::EmailRequireSenderAuth => {
+                "From addresses are easy to forge. When enabled, admin access is only granted if the receiving \
+                 server's Authentication-Results header shows DMARC passing, or DKIM passing for the sender's domain. \
+                 Only headers from the trusted authentication servers below count. \
+                 Disable only for local test servers that do not add this header."
+            }
+            Self::EmailTrustedAuthservIds => {
+                "Comma-separated authserv-ids (the first field of the Authentication-Results header, e.g. \
+                 'mx.google.com') written by your mail provider. Anyone can add an Authentication-Results \
+                 header to a message they send, so only results from these servers count. \
+                 Leave empty and no sender is treated as authenticated."
+            }
+            Self::MatrixHomeserverUrl => {
+                "Base URL of the bot account's homeserver (e.g., 'https://matrix.org' or \
+                 'http://localhost:8008' for a local Synapse/Conduit)."
+            }
+            Self::MatrixAccessToken => {
+                "Access token of the bot's Matrix account. In Element: Settings > Help & About > Access Token, \
+                 or log in via the /_matrix/client/v3/login API. Encrypted rooms are not supported."
+            }
+            Self::MatrixAdminUserIds => {
+                "Comma-separated Matrix user IDs (e.g., '@alice:example.org') with full agent access. \
+                 All other users are restricted to safe mode. If empty, everyone uses safe mode — \
+                 anyone can create a room and invite the bot, so room power levels are not trusted."
+            }
+            Self::MatrixAutoJoin => {
+                "Which room invites the bot accepts automatically. 'Admins only' joins rooms when invited \
+                 by an admin user ID; 'Anyone' joins any room it is invited to."
+            }
         }
     }
 
@@ -196,6 +347,7 @@ impl ChannelSettingKey {
             Self::AutoStartOnBoot => SettingInputType::Toggle,
             Self::DiscordBotToken => SettingInputType::Text,
             Self::DiscordAdminUserIds => SettingInputType::Text,
+            Self::DiscordAutoThread => SettingInputType::Toggle,
             Self::TelegramBotToken => SettingInputType::Text,
             Self::SlackBotToken => SettingInputType::Text,
             Self::SlackAppToken => SettingInputType::Text,
@@ -209,6 +361,28 @@ impl ChannelSettingKey {
             Self::SlackAdminUserIds => SettingInputType::Text,
             Self::ExternalChannelApiToken => SettingInputType::Text,
             Self::ExternalChannelSafeMode => SettingInputType::Toggle,
+            Self::ExternalChannelCallbackUrl => SettingInputType::Text,
+            Self::ExternalChannelA2aEnabled => SettingInputType::Toggle,
+            Self::ExternalChannelA2aPriceUsdc => SettingInputType::Text,
+            Self::EmailAddress => SettingInputType::Text,
+            Self::EmailUsername => SettingInputType::Text,
+            Self::EmailPassword => SettingInputType::Text,
+            Self::EmailImapHost => SettingInputType::Text,
+            Self::EmailImapPort => SettingInputType::Number,
+            Self::EmailImapSecurity => SettingInputType::Select,
+            Self::EmailSmtpHost => SettingInputType::Text,
+            Self::EmailSmtpPort => SettingInputType::Number,
+            Self::EmailSmtpSecurity => SettingInputType::Select,
+            Self::EmailMailbox => SettingInputType::Text,
+            Self::EmailPollIntervalSecs => SettingInputType::Number,
+            Self::EmailAdminAddresses => SettingInputType::Text,
+            Self::EmailAllowedSenders => SettingInputType::Text,
+            Self::EmailRequireSenderAuth => SettingInputType::Toggle,
+            Self::EmailTrustedAuthservIds => SettingInputType::Text,
+            Self::MatrixHomeserverUrl => SettingInputType::Text,
+            Self::MatrixAccessToken => SettingInputType::Text,
+            Self::MatrixAdminUserIds => SettingInputType::Text,
+            Self::MatrixAutoJoin => SettingInputType::Select,
         }
     }
 
@@ -218,6 +392,7 @@ impl ChannelSettingKey {
             Self::AutoStartOnBoot => "",
             Self::DiscordBotToken => "MTIz...abc",
             Self::DiscordAdminUserIds => "123456789012345678, 987654321098765432",
+            Self::DiscordAutoThread => "",
             Self::TelegramBotToken => "123456:ABC-DEF...",
             Self::SlackBotToken => "xoxb-...",
             Self::SlackAppToken => "xapp-...",
@@ -231,6 +406,28 @@ impl ChannelSettingKey {
             Self::SlackAdminUserIds => "U12345678,U87654321",
             Self::ExternalChannelApiToken => "Click dice to generate a secure token",
             Self::ExternalChannelSafeMode => "",
+            Self::ExternalChannelCallbackUrl => "https://example.com/starkbot/callback",
+            Self::ExternalChannelA2aEnabled => "",
+            Self::ExternalChannelA2aPriceUsdc => "0.01",
+            Self::EmailAddress => "agent@example.com",
+            Self::EmailUsername => "agent@example.com",
+            Self::EmailPassword => "app password",
+            Self::EmailImapHost => "imap.example.com",
+            Self::EmailImapPort => "993",
+            Self::EmailImapSecurity => "",
+            Self::EmailSmtpHost => "smtp.example.com",
+            Self::EmailSmtpPort => "465",
+            Self::EmailSmtpSecurity => "",
+            Self::EmailMailbox => "INBOX",
+            Self::EmailPollIntervalSecs => "60",
+            Self::EmailAdminAddresses => "me@example.com",
+            Self::EmailAllowedSenders => "alice@example.com, @example.org",
+            Self::EmailRequireSenderAuth => "",
+            Self::EmailTrustedAuthservIds => "mx.google.com",
+            Self::MatrixHomeserverUrl => "https://matrix.example.org",
+            Self::MatrixAccessToken => "syt_...",
+            Self::MatrixAdminUserIds => "@alice:example.org, @bob:example.org",
+            Self::MatrixAutoJoin => "",
         }
     }
 
@@ -245,6 +442,20 @@ impl ChannelSettingKey {
                 ("5", "5%"),
                 ("1", "1%"),
             ]),
+            Self::EmailImapSecurity => Some(vec![
+                ("tls", "TLS"),
+                ("plain", "Plain (local testing only)"),
+            ]),
+            Self::EmailSmtpSecurity => Some(vec![
+                ("tls", "TLS"),
+                ("starttls", "STARTTLS"),
+                ("plain", "Plain (local testing only)"),
+            ]),
+            Self::MatrixAutoJoin => Some(vec![
+                ("admins", "Admins only"),
+                ("anyone", "Anyone"),
+                ("off", "Off"),
+            ]),
             _ => None,
         }
     }
@@ -255,6 +466,7 @@ impl ChannelSettingKey {
             Self::AutoStartOnBoot => "false",
             Self::DiscordBotToken => "",
             Self::DiscordAdminUserIds => "",
+            Self::DiscordAutoThread => "false",
             Self::TelegramBotToken => "",
             Self::SlackBotToken => "",
             Self::SlackAppToken => "",
@@ -268,6 +480,28 @@ impl ChannelSettingKey {
             Self::SlackAdminUserIds => "",
             Self::ExternalChannelApiToken => "",
             Self::ExternalChannelSafeMode => "false",
+            Self::ExternalChannelCallbackUrl => "",
+            Self::ExternalChannelA2aEnabled => "false",
+            Self::ExternalChannelA2aPriceUsdc => "",
+            Self::EmailAddress => "",
+            Self::EmailUsername => "",
+            Self::EmailPassword => "",
+            Self::EmailImapHost => "",
+            Self::EmailImapPort => "993",
+            Self::EmailImapSecurity => "tls",
+            Self::EmailSmtpHost => "",
+            Self::EmailSmtpPort => "465",
+            Self::EmailSmtpSecurity => "tls",
+            Self::EmailMailbox => "INBOX",
+            Self::EmailPollIntervalSecs => "60",
+            Self::EmailAdminAddresses => "",
+            Self::EmailAllowedSenders => "",
+            Self::EmailRequireSenderAuth => "true",
+            Self::EmailTrustedAuthservIds => "",
+            Self::MatrixHomeserverUrl => "",
+            Self::MatrixAccessToken => "",
+            Self::MatrixAdminUserIds => "",
+            Self::MatrixAutoJoin => "admins",
         }
     }
 
@@ -385,6 +619,7 @@ pub fn get_settings_for_channel_type(channel_type: ChannelType) -> Vec<ChannelSe
         ChannelType::Discord => vec![
             ChannelSettingKey::DiscordBotToken.into(),
             ChannelSettingKey::DiscordAdminUserIds.into(),
+            ChannelSettingKey::DiscordAutoThread.into(),
         ],
         ChannelType::Telegram => vec![
             ChannelSettingKey::TelegramBotToken.into(),
@@ -406,6 +641,32 @@ pub fn get_settings_for_channel_type(channel_type: ChannelType) -> Vec<ChannelSe
         ChannelType::ExternalChannel => vec![
             ChannelSettingKey::ExternalChannelApiToken.into(),
             ChannelSettingKey::ExternalChannelSafeMode.into(),
+            ChannelSettingKey::ExternalChannelCallbackUrl.into(),
+            ChannelSettingKey::ExternalChannelA2aEnabled.into(),
+            ChannelSettingKey::ExternalChannelA2aPriceUsdc.into(),
+        ],
+        ChannelType::Email => vec![
+            ChannelSettingKey::EmailAddress.into(),
+            ChannelSettingKey::EmailUsername.into(),
+            ChannelSettingKey::EmailPassword.into(),
+            ChannelSettingKey::EmailImapHost.into(),
+            ChannelSettingKey::EmailImapPort.into(),
+            ChannelSettingKey::EmailImapSecurity.into(),
+            ChannelSettingKey::EmailSmtpHost.into(),
+            ChannelSettingKey::EmailSmtpPort.into(),
+            ChannelSettingKey::EmailSmtpSecurity.into(),
+            ChannelSettingKey::EmailMailbox.into(),
+            ChannelSettingKey::EmailPollIntervalSecs.into(),
+            ChannelSettingKey::EmailAdminAddresses.into(),
+            ChannelSettingKey::EmailAllowedSenders.into(),
+            ChannelSettingKey::EmailRequireSenderAuth.into(),
+            ChannelSettingKey::EmailTrustedAuthservIds.into(),
+        ],
+        ChannelType::Matrix => vec![
+            ChannelSettingKey::MatrixHomeserverUrl.into(),
+            ChannelSettingKey::MatrixAccessToken.into(),
+            ChannelSettingKey::MatrixAdminUserIds.into(),
+            ChannelSettingKey::MatrixAutoJoin.into(),
         ],
     };
 
@@ -426,11 +687,12 @@ mod tests {
     #[test]
     fn test_discord_settings() {
         let settings = get_settings_for_channel_type(ChannelType::Discord);
-        // 1 common + 2 Discord-specific (bot_token, admin_user_ids)
-        assert_eq!(settings.len(), 3);
+        // 1 common + 3 Discord-specific (bot_token, admin_user_ids, auto_thread)
+        assert_eq!(settings.len(), 4);
         assert_eq!(settings[0].key, "auto_start_on_boot");
         assert_eq!(settings[1].key, "discord_bot_token");
         assert_eq!(settings[2].key, "discord_admin_user_ids");
+        assert_eq!(settings[3].key, "discord_auto_thread");
     }
 
     #[test]
@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::session_message::SessionMessage;
+
 /// Session scope determines the context type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -242,3 +244,131 @@ impl From<ChatSession> for ChatSessionResponse {
         }
     }
 }
+
+/// Branch metadata for a session forked from another session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionBranch {
+    /// The forked (child) session
+    pub session_id: i64,
+    /// The session it was forked from
+    pub parent_session_id: i64,
+    /// Last parent message copied into the branch (None if the branch starts empty)
+    pub branch_point_message_id: Option<i64>,
+    /// Optional human-readable label (e.g. "retry with claude")
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request to fork a session
+#[derive(Debug, Clone, Deserialize)]
+pub struct ForkSessionRequest {
+    /// Copy messages up to and including this message (None = whole transcript)
+    #[serde(default)]
+    pub message_id: Option<i64>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Request to rewind a session to a user message and retry it on a new branch
+#[derive(Debug, Clone, Deserialize)]
+pub struct RewindSessionRequest {
+    /// The user message to retry; everything from this message on is left out of the branch
+    pub message_id: i64,
+    /// Endpoint preset key from ai_endpoints.ron to use for the retry
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// Thinking level for the retry (e.g. "off", "low", "medium", "high")
+    #[serde(default)]
+    pub thinking_level: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Side-by-side comparison of two session transcripts
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionComparison {
+    pub left_session_id: i64,
+    pub right_session_id: i64,
+    /// Number of leading messages with identical role and content
+    pub common_prefix_len: usize,
+    /// Messages of the left session after the common prefix
+    pub left_tail: Vec<SessionMessage>,
+    /// Messages of the right session after the common prefix
+    pub right_tail: Vec<SessionMessage>,
+}
+
+impl SessionComparison {
+    /// Compare two transcripts by role and content (ids and timestamps differ between branches)
+    pub fn from_transcripts(
+        left_session_id: i64,
+        left: Vec<SessionMessage>,
+        right_session_id: i64,
+        right: Vec<SessionMessage>,
+    ) -> Self {
+        let common_prefix_len = left
+            .iter()
+            .zip(right.iter())
+            .take_while(|(a, b)| a.role == b.role && a.content == b.content)
+            .count();
+
+        Self {
+            left_session_id,
+            right_session_id,
+            common_prefix_len,
+            left_tail: left.into_iter().skip(common_prefix_len).collect(),
+            right_tail: right.into_iter().skip(common_prefix_len).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MessageRole;
+
+    fn msg(id: i64, role: MessageRole, content: &str) -> SessionMessage {
+        SessionMessage {
+            id,
+            session_id: 0,
+            role,
+            content: content.to_string(),
+            user_id: None,
+            user_name: None,
+            platform_message_id: None,
+            tokens_used: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_comparison_finds_divergence_point() {
+        let left = vec![
+            msg(1, MessageRole::User, "swap 1 eth"),
+            msg(2, MessageRole::Assistant, "done via 0x"),
+        ];
+        let right = vec![
+            msg(10, MessageRole::User, "swap 1 eth"),
+            msg(11, MessageRole::Assistant, "failed: slippage"),
+            msg(12, MessageRole::User, "retry"),
+        ];
+
+        let cmp = SessionComparison::from_transcripts(1, left, 2, right);
+
+        assert_eq!(cmp.common_prefix_len, 1);
+        assert_eq!(cmp.left_tail.len(), 1);
+        assert_eq!(cmp.right_tail.len(), 2);
+        assert_eq!(cmp.right_tail[0].content, "failed: slippage");
+    }
+
+    #[test]
+    fn test_comparison_identical_transcripts() {
+        let left = vec![msg(1, MessageRole::User, "hi")];
+        let right = vec![msg(5, MessageRole::User, "hi")];
+
+        let cmp = SessionComparison::from_transcripts(1, left, 2, right);
+
+        assert_eq!(cmp.common_prefix_len, 1);
+        assert!(cmp.left_tail.is_empty());
+        assert!(cmp.right_tail.is_empty());
+    }
+}
@@ -90,6 +90,90 @@ impl JobStatus {
     }
 }
 
+/// Agent settings applied to a single scheduled run (cron job or heartbeat)
+/// instead of the global agent settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunOverrides {
+    /// AI endpoint preset key from ai_endpoints.ron (selects endpoint + archetype)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Thinking level (off, minimal, low, medium, high, xhigh)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking_level: Option<String>,
+    /// Tool profile replacing the channel's (e.g. "safe_mode", "finance")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_profile: Option<String>,
+    /// Agent subtype to start in (e.g. "finance", "secretary")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_subtype: Option<String>,
+    /// Tool loop iteration cap (only lowers the bot-wide setting)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tool_iterations: Option<u32>,
+    /// Largest value (wei) any single transaction in this run may move.
+    /// A per-transaction limit like a role's, not a budget for the whole run.
+    #[serde(default, alias = "max_tx_value_wei", skip_serializing_if = "Option::is_none")]
+    pub max_value_per_tx_wei: Option<String>,
+}
+
+impl RunOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == RunOverrides::default()
+    }
+
+    /// Parse the stored JSON column (NULL/invalid = no overrides)
+    pub fn from_json(value: Option<String>) -> Self {
+        value
+            .and_then(|v| serde_json::from_str(&v).ok())
+            .unwrap_or_default()
+    }
+
+    /// Check every set field names something that exists
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(ref key) = self.endpoint
+            && crate::ai_endpoint_config::get_ai_endpoint(key).is_none()
+        {
+            return Err(format!("Unknown AI endpoint preset '{}'", key));
+        }
+        if let Some(ref level) = self.thinking_level
+            && crate::ai::ThinkingLevel::from_str(level).is_none()
+        {
+            return Err(format!("Invalid thinking level '{}'", level));
+        }
+        if let Some(ref profile) = self.tool_profile {
+            match crate::tools::ToolProfile::from_str(profile) {
+                Some(crate::tools::ToolProfile::Custom) | None => {
+                    return Err(format!("Invalid tool profile '{}'", profile));
+                }
+                Some(_) => {}
+            }
+        }
+        if let Some(ref subtype) = self.agent_subtype
+            && crate::ai::multi_agent::types::AgentSubtype::from_str(subtype).is_none()
+        {
+            return Err(format!("Unknown agent subtype '{}'", subtype));
+        }
+        if self.max_tool_iterations == Some(0) {
+            return Err("max_tool_iterations must be at least 1".to_string());
+        }
+        if let Some(ref cap) = self.max_value_per_tx_wei
+            && cap.parse::<u128>().is_err()
+        {
+            return Err("max_value_per_tx_wei must be a whole number of wei".to_string());
+        }
+        Ok(())
+    }
+
+    /// Apply to the message the scheduler dispatches
+    pub fn apply_to(&self, message: &mut crate::channels::NormalizedMessage) {
+        message.endpoint_override = self.endpoint.clone();
+        message.thinking_level = self.thinking_level.clone();
+        message.tool_profile = self.tool_profile.clone();
+        message.agent_subtype = self.agent_subtype.clone();
+        message.max_tool_iterations = self.max_tool_iterations;
+        message.max_tx_value_wei = self.max_value_per_tx_wei.clone();
+    }
+}
+
 /// A scheduled cron job
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CronJob {
@@ -113,7 +197,7 @@ pub struct CronJob {
     pub deliver_to: Option<String>,
     /// Whether to auto-deliver results
     pub deliver: bool,
-    /// Model override (e.g., "claude-opus-4")
+    /// AI endpoint preset key from ai_endpoints.ron (e.g., "kimi")
     pub model_override: Option<String>,
     /// Thinking level override
     pub thinking_level: Option<String>,
@@ -127,6 +211,15 @@ pub struct CronJob {
     pub run_count: i32,
     pub error_count: i32,
     pub last_error: Option<String>,
+    /// Endpoint/thinking/tool/cap settings for this job's runs
+    #[serde(default)]
+    pub run_overrides: RunOverrides,
+    /// Failed runs since the last successful one
+    #[serde(default)]
+    pub consecutive_failures: i32,
+    /// Send an alert when this many runs fail in a row (None = no alerts)
+    #[serde(default)]
+    pub alert_after_failures: Option<i32>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -161,6 +254,10 @@ pub struct CreateCronJobRequest {
     pub timeout_seconds: Option<i32>,
     #[serde(default)]
     pub delete_after_run: bool,
+    #[serde(default)]
+    pub run_overrides: Option<RunOverrides>,
+    #[serde(default)]
+    pub alert_after_failures: Option<i32>,
 }
 
 fn default_session_mode() -> String {
@@ -202,6 +299,12 @@ pub struct UpdateCronJobRequest {
     pub delete_after_run: Option<bool>,
     #[serde(default)]
     pub status: Option<String>,
+    /// Replaces the job's run overrides when set
+    #[serde(default)]
+    pub run_overrides: Option<RunOverrides>,
+    /// Failure alert threshold (0 turns alerts off)
+    #[serde(default)]
+    pub alert_after_failures: Option<i32>,
 }
 
 /// Response for cron job operations
@@ -224,6 +327,44 @@ pub struct CronJobRun {
     pub result: Option<String>,
     pub error: Option<String>,
     pub duration_ms: Option<i64>,
+    /// "delivered", "failed" or "skipped" when the job delivers results
+    pub delivery_status: Option<String>,
+    pub delivery_error: Option<String>,
+    /// Chat session the run executed in
+    #[serde(default)]
+    pub session_id: Option<i64>,
+    /// Tool calls made during the run
+    #[serde(default)]
+    pub tool_calls: Option<i64>,
+    /// Estimated tokens of the messages the run added
+    #[serde(default)]
+    pub tokens_used: Option<i64>,
+    /// USDC paid via x402 during the run
+    #[serde(default)]
+    pub x402_spend_usdc: Option<f64>,
+    /// What the run was started with (used to replay it)
+    #[serde(default)]
+    pub input: Option<CronRunInput>,
+    /// Run this one replayed
+    #[serde(default)]
+    pub replay_of: Option<i64>,
+}
+
+/// The message and settings a cron run was dispatched with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CronRunInput {
+    pub message: String,
+    #[serde(default)]
+    pub run_overrides: RunOverrides,
+}
+
+/// Usage collected for a finished cron run
+#[derive(Debug, Clone, Default)]
+pub struct CronRunStats {
+    pub session_id: Option<i64>,
+    pub tool_calls: i64,
+    pub tokens_used: i64,
+    pub x402_spend_usdc: f64,
 }
 
 /// Heartbeat configuration
@@ -248,6 +389,9 @@ pub struct HeartbeatConfig {
     pub current_mind_node_id: Option<i64>,
     /// Last heartbeat session ID (for context continuity)
     pub last_session_id: Option<i64>,
+    /// Endpoint/thinking/tool/cap settings for heartbeat runs
+    #[serde(default)]
+    pub run_overrides: RunOverrides,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -267,6 +411,9 @@ pub struct UpdateHeartbeatConfigRequest {
     pub active_days: Option<String>,
     #[serde(default)]
     pub enabled: Option<bool>,
+    /// Replaces the heartbeat's run overrides when set
+    #[serde(default)]
+    pub run_overrides: Option<RunOverrides>,
 }
 
 /// Response for heartbeat config operations
@@ -278,6 +425,18 @@ pub struct HeartbeatConfigResponse {
 }
 
 impl CronJob {
+    /// Run overrides, falling back to the older model_override/thinking_level columns
+    pub fn effective_run_overrides(&self) -> RunOverrides {
+        let mut overrides = self.run_overrides.clone();
+        if overrides.endpoint.is_none() {
+            overrides.endpoint = self.model_override.clone().filter(|m| !m.is_empty());
+        }
+        if overrides.thinking_level.is_none() {
+            overrides.thinking_level = self.thinking_level.clone().filter(|t| !t.is_empty());
+        }
+        overrides
+    }
+
     /// Calculate the next run time based on schedule
     pub fn calculate_next_run(&self) -> Option<DateTime<Utc>> {
         let now = Utc::now();
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+/// What a dashboard account may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DashboardRole {
+    /// Everything, including account management
+    Admin,
+    /// Everything except managing other accounts
+    Operator,
+    /// Read-only access
+    Auditor,
+}
+
+impl DashboardRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DashboardRole::Admin => "admin",
+            DashboardRole::Operator => "operator",
+            DashboardRole::Auditor => "auditor",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "admin" => Some(DashboardRole::Admin),
+            "operator" => Some(DashboardRole::Operator),
+            "auditor" | "read_only" | "readonly" => Some(DashboardRole::Auditor),
+            _ => None,
+        }
+    }
+
+    pub fn can_write(&self) -> bool {
+        !matches!(self, DashboardRole::Auditor)
+    }
+}
+
+/// A dashboard operator account (wallet and/or password + TOTP login)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardUser {
+    pub id: i64,
+    pub username: String,
+    pub role: String,
+    /// Lowercase 0x address allowed to sign in with a wallet signature
+    pub wallet_address: Option<String>,
+    #[serde(skip_serializing)]
+    pub password_hash: Option<String>,
+    #[serde(skip_serializing)]
+    pub totp_secret: Option<String>,
+    pub totp_enabled: bool,
+    pub created_at: String,
+    pub last_login_at: Option<String>,
+}
+
+impl DashboardUser {
+    pub fn role_enum(&self) -> DashboardRole {
+        // Unknown roles fail closed to read-only
+        DashboardRole::from_str(&self.role).unwrap_or(DashboardRole::Auditor)
+    }
+
+    pub fn has_password(&self) -> bool {
+        self.password_hash.is_some()
+    }
+}
+
+/// A long-lived API token for automation. Only the SHA-256 of the token is stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: i64,
+    pub name: String,
+    /// First characters of the token, for recognising it in lists
+    pub token_prefix: String,
+    /// e.g. ["tx_queue:read", "kanban:write"]
+    pub scopes: Vec<String>,
+    /// Owning account; None for tokens created by the legacy admin login
+    pub user_id: Option<i64>,
+    pub created_at: String,
+    pub expires_at: Option<String>,
+    pub last_used_at: Option<String>,
+    pub revoked: bool,
+}
+
+/// Request to create a dashboard account
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateDashboardUserRequest {
+    pub username: String,
+    pub role: String,
+    #[serde(default)]
+    pub wallet_address: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// Request to update a dashboard account (None fields are left unchanged)
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateDashboardUserRequest {
+    #[serde(default)]
+    pub role: Option<String>,
+    /// Empty string removes wallet login
+    #[serde(default)]
+    pub wallet_address: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Set false to reset a lost authenticator
+    #[serde(default)]
+    pub totp_enabled: Option<bool>,
+}
+
+/// Request to create an API token
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub expires_in_days: Option<i64>,
+}
+
+/// Who is making a dashboard API request, attached to the request by the auth middleware
+#[derive(Debug, Clone)]
+pub struct DashboardPrincipal {
+    /// None for the legacy single-admin login (LOGIN_ADMIN_PUBLIC_ADDRESS / Flash)
+    pub user_id: Option<i64>,
+    pub username: String,
+    pub role: DashboardRole,
+    /// Set when the request authenticated with an API token
+    pub api_token_id: Option<i64>,
+    /// Scopes of the API token; None for login sessions
+    pub scopes: Option<Vec<String>>,
+}
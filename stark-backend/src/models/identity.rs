@@ -11,10 +11,56 @@ pub struct IdentityLink {
     pub platform_user_name: Option<String>,
     pub is_verified: bool,
     pub verified_at: Option<DateTime<Utc>>,
+    /// How the link was verified: "admin", "code" or "wallet"
+    pub verification_method: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Kind of pending self-service verification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationKind {
+    /// One-time code issued on one account, redeemed on the target account
+    Code,
+    /// Wallet signature over a SIWE challenge
+    Wallet,
+}
+
+impl VerificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Code => "code",
+            Self::Wallet => "wallet",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "code" => Some(Self::Code),
+            "wallet" => Some(Self::Wallet),
+            _ => None,
+        }
+    }
+}
+
+/// A pending verification started by one account for a target account or wallet
+#[derive(Debug, Clone)]
+pub struct IdentityVerification {
+    pub id: i64,
+    pub kind: VerificationKind,
+    /// Account that started the verification
+    pub channel_type: String,
+    pub platform_user_id: String,
+    /// Account (or "wallet" + address) that completes it
+    pub target_channel_type: String,
+    pub target_user_id: String,
+    /// SHA-256 of the code, or the challenge message to sign
+    pub secret: String,
+    pub attempts: i32,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
 /// Request to get or create an identity link
 #[derive(Debug, Clone, Deserialize)]
 pub struct GetOrCreateIdentityRequest {
@@ -39,6 +85,7 @@ pub struct LinkedAccountInfo {
     pub platform_user_id: String,
     pub platform_user_name: Option<String>,
     pub is_verified: bool,
+    pub verification_method: Option<String>,
 }
 
 /// Response containing identity information
@@ -56,6 +103,7 @@ impl From<&IdentityLink> for LinkedAccountInfo {
             platform_user_id: link.platform_user_id.clone(),
             platform_user_name: link.platform_user_name.clone(),
             is_verified: link.is_verified,
+            verification_method: link.verification_method.clone(),
         }
     }
 }
@@ -1,3 +1,4 @@
+pub mod a2a_task;
 pub mod agent_settings;
 pub mod api_key;
 pub mod bot_settings;
@@ -5,13 +6,19 @@ pub mod channel;
 pub mod channel_settings;
 pub mod chat_session;
 pub mod cron_job;
+pub mod dashboard_user;
 pub mod execution;
 pub mod identity;
+pub mod role;
+pub mod scheduler_trigger;
 pub mod session;
 pub mod session_message;
+pub mod webhook;
+pub mod workflow_run;
 
+pub use a2a_task::{A2aDirection, A2aTask, A2aTaskState};
 pub use agent_settings::{AgentSettings, AgentSettingsResponse, UpdateAgentSettingsRequest, MIN_CONTEXT_TOKENS, DEFAULT_CONTEXT_TOKENS};
-pub use bot_settings::{BotSettings, UpdateBotSettingsRequest, DEFAULT_MAX_TOOL_ITERATIONS, DEFAULT_SAFE_MODE_MAX_QUERIES_PER_10MIN};
+pub use bot_settings::{BotSettings, UpdateBotSettingsRequest, DEFAULT_KANBAN_MAX_CONCURRENT, DEFAULT_MAX_TOOL_ITERATIONS, DEFAULT_SAFE_MODE_MAX_QUERIES_PER_10MIN};
 pub use api_key::{ApiKey, ApiKeyResponse};
 pub use channel::{Channel, ChannelResponse, ChannelType, CreateChannelRequest, CreateSafeModeChannelRequest, UpdateChannelRequest};
 pub use channel_settings::{
@@ -20,18 +27,30 @@ pub use channel_settings::{
     SettingUpdate, ToolOutputVerbosity, UpdateChannelSettingsRequest,
 };
 pub use chat_session::{
-    ChatSession, ChatSessionResponse, CompletionStatus, GetOrCreateSessionRequest, ResetPolicy,
-    SessionScope, UpdateResetPolicyRequest,
+    ChatSession, ChatSessionResponse, CompletionStatus, ForkSessionRequest,
+    GetOrCreateSessionRequest, ResetPolicy, RewindSessionRequest, SessionBranch,
+    SessionComparison, SessionScope, UpdateResetPolicyRequest,
 };
 pub use identity::{
-    GetOrCreateIdentityRequest, IdentityLink, IdentityResponse, LinkIdentityRequest,
-    LinkedAccountInfo,
+    GetOrCreateIdentityRequest, IdentityLink, IdentityResponse, IdentityVerification,
+    LinkIdentityRequest, LinkedAccountInfo, VerificationKind,
+};
+pub use role::{AssignRoleRequest, CreateRoleRequest, Role, RoleAssignment, UpdateRoleRequest};
+pub use scheduler_trigger::{
+    validate_trigger_timing, CreateTriggerRequest, SchedulerTrigger, TriggerCondition, TriggerResponse,
+    TriggerRun, TriggerState, UpdateTriggerRequest,
 };
 pub use session::Session;
 pub use session_message::{AddMessageRequest, MessageRole, SessionMessage, SessionTranscriptResponse};
 pub use cron_job::{
-    CreateCronJobRequest, CronJob, CronJobResponse, CronJobRun, HeartbeatConfig,
+    CreateCronJobRequest, CronJob, CronJobResponse, CronJobRun, CronRunInput, CronRunStats, HeartbeatConfig, RunOverrides,
     HeartbeatConfigResponse, JobStatus, ScheduleType, SessionMode, UpdateCronJobRequest,
     UpdateHeartbeatConfigRequest,
 };
+pub use dashboard_user::{
+    ApiToken, CreateApiTokenRequest, CreateDashboardUserRequest, DashboardPrincipal, DashboardRole,
+    DashboardUser, UpdateDashboardUserRequest,
+};
 pub use execution::{ExecutionTask, TaskMetrics, TaskStatus, TaskType};
+pub use webhook::{CreateWebhookRequest, UpdateWebhookRequest, Webhook, WebhookKind, WebhookResponse};
+pub use workflow_run::{WorkflowRun, WorkflowRunStatus, WorkflowStepRecord};
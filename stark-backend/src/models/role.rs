@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+
+use crate::tools::{ToolConfig, ToolProfile};
+
+/// Roles created on first start. They can be edited but not deleted.
+pub const BUILTIN_ROLES: &[(&str, &str, &str)] = &[
+    ("viewer", "Read-only questions; same tools as safe mode", "safe_mode"),
+    ("trader", "Web, filesystem and finance tools", "finance"),
+    ("developer", "Web, filesystem, exec and development tools", "developer"),
+    ("admin", "All tools", "full"),
+];
+
+/// A named permission set that channel users can be assigned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Role {
+    pub name: String,
+    pub description: Option<String>,
+    /// ToolProfile name; "custom" uses `allowed_groups` instead of the profile's groups
+    pub tool_profile: String,
+    /// Tools allowed regardless of groups
+    pub allow_list: Vec<String>,
+    /// Tools always denied
+    pub deny_list: Vec<String>,
+    /// Tool groups for the custom profile
+    pub allowed_groups: Vec<String>,
+    /// Max value of a single transaction or x402 payment this role may make, in 18-decimal
+    /// units (wei for native value; token amounts are scaled); None = no cap.
+    /// See `ToolContext::check_role_tx`.
+    pub max_tx_value_wei: Option<String>,
+    pub builtin: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Role {
+    pub fn profile(&self) -> ToolProfile {
+        ToolProfile::from_str(&self.tool_profile).unwrap_or(ToolProfile::SafeMode)
+    }
+
+    /// Roles on the safe_mode profile get the full safe mode treatment
+    pub fn is_safe_mode(&self) -> bool {
+        self.profile() == ToolProfile::SafeMode
+    }
+
+    /// Tool config granted by this role (not meaningful for safe mode roles,
+    /// which always use `ToolConfig::safe_mode()`)
+    pub fn tool_config(&self) -> ToolConfig {
+        ToolConfig {
+            id: None,
+            channel_id: None,
+            profile: self.profile(),
+            allow_list: self.allow_list.clone(),
+            deny_list: self.deny_list.clone(),
+            allowed_groups: self.allowed_groups.clone(),
+            denied_groups: vec![],
+        }
+    }
+}
+
+/// Assignment of a role. Resolution picks the most specific match:
+/// channel + platform user, then identity (any channel), then the channel default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleAssignment {
+    pub id: i64,
+    pub role: String,
+    /// Channel the assignment applies to; None = all channels (identity assignments)
+    pub channel_id: Option<i64>,
+    /// Platform user ID within the channel; None with no identity = channel default
+    pub user_id: Option<String>,
+    /// Canonical identity (see identity linking); applies on every linked account
+    pub identity_id: Option<String>,
+    pub created_at: String,
+}
+
+/// Request to create a role
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRoleRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub tool_profile: String,
+    #[serde(default)]
+    pub allow_list: Vec<String>,
+    #[serde(default)]
+    pub deny_list: Vec<String>,
+    #[serde(default)]
+    pub allowed_groups: Vec<String>,
+    #[serde(default)]
+    pub max_tx_value_wei: Option<String>,
+}
+
+/// Request to update a role (None fields are left unchanged)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRoleRequest {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tool_profile: Option<String>,
+    #[serde(default)]
+    pub allow_list: Option<Vec<String>>,
+    #[serde(default)]
+    pub deny_list: Option<Vec<String>>,
+    #[serde(default)]
+    pub allowed_groups: Option<Vec<String>>,
+    /// Empty string removes the cap
+    #[serde(default)]
+    pub max_tx_value_wei: Option<String>,
+}
+
+/// Request to assign a role
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignRoleRequest {
+    pub role: String,
+    #[serde(default)]
+    pub channel_id: Option<i64>,
+    #[serde(default)]
+    pub user_id: Option<String>,
+    #[serde(default)]
+    pub identity_id: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(profile: &str) -> Role {
+        Role {
+            name: "test".to_string(),
+            description: None,
+            tool_profile: profile.to_string(),
+            allow_list: vec!["say_to_user".to_string()],
+            deny_list: vec!["exec".to_string()],
+            allowed_groups: vec![],
+            max_tx_value_wei: None,
+            builtin: false,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_role_tool_config() {
+        let config = role("developer").tool_config();
+        assert_eq!(config.profile, ToolProfile::Developer);
+        assert!(config.deny_list.contains(&"exec".to_string()));
+        assert!(!config.is_tool_allowed("exec", crate::tools::ToolGroup::Exec));
+
+        assert!(role("safe_mode").is_safe_mode());
+        // Unknown profiles fail closed
+        assert!(role("bogus").is_safe_mode());
+    }
+}
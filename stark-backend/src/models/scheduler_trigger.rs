@@ -0,0 +1,278 @@
+use serde::{Deserialize, Serialize};
+
+/// Most mentions a keyword spike can require (the social monitor returns at most 200 tweets per query)
+pub const MAX_KEYWORD_SPIKE_MENTIONS: u32 = 200;
+
+/// Shortest allowed interval between condition checks
+pub const MIN_TRIGGER_CHECK_INTERVAL_SECS: i64 = 30;
+
+/// A condition that fires a trigger.
+///
+/// Level conditions (price, keyword) fire once per excursion: after holding for
+/// `confirm_checks` consecutive checks, and not again until they stop holding.
+/// Event conditions (large trade, tx confirmed) fire for new events since the
+/// last firing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TriggerCondition {
+    /// Token USD price from DexScreener or GeckoTerminal crosses a threshold
+    PriceCross {
+        /// "dexscreener" or "geckoterminal"
+        #[serde(default = "default_price_source")]
+        source: String,
+        /// Chain/network id as the source names it (e.g. "base", "ethereum", "eth")
+        chain: String,
+        token_address: String,
+        /// "above" or "below"
+        direction: String,
+        threshold_usd: f64,
+    },
+    /// The wallet_monitor module flags a large trade
+    LargeTrade {
+        /// Only trades at or above this USD value (default: any flagged trade)
+        #[serde(default)]
+        min_usd: Option<f64>,
+        /// Only this watched wallet
+        #[serde(default)]
+        address: Option<String>,
+        #[serde(default)]
+        chain: Option<String>,
+    },
+    /// A keyword shows up in at least `min_mentions` tweets captured by the
+    /// social_monitor module within the window
+    KeywordSpike {
+        keyword: String,
+        #[serde(default = "default_window_minutes")]
+        window_minutes: u32,
+        min_mentions: u32,
+    },
+    /// A queued transaction confirms on-chain
+    TxConfirmed {
+        /// A specific queued tx (the trigger disables itself after firing); any tx when omitted
+        #[serde(default)]
+        tx_uuid: Option<String>,
+        #[serde(default)]
+        network: Option<String>,
+    },
+}
+
+fn default_price_source() -> String {
+    "dexscreener".to_string()
+}
+
+fn default_window_minutes() -> u32 {
+    60
+}
+
+impl TriggerCondition {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TriggerCondition::PriceCross { .. } => "price_cross",
+            TriggerCondition::LargeTrade { .. } => "large_trade",
+            TriggerCondition::KeywordSpike { .. } => "keyword_spike",
+            TriggerCondition::TxConfirmed { .. } => "tx_confirmed",
+        }
+    }
+
+    /// Event conditions consume new events; level conditions track a streak
+    pub fn is_event(&self) -> bool {
+        matches!(self, TriggerCondition::LargeTrade { .. } | TriggerCondition::TxConfirmed { .. })
+    }
+
+    /// Conditions that can only ever fire once
+    pub fn is_one_shot(&self) -> bool {
+        matches!(self, TriggerCondition::TxConfirmed { tx_uuid: Some(_), .. })
+    }
+
+    /// Module the condition reads from, if any
+    pub fn required_module(&self) -> Option<&'static str> {
+        match self {
+            TriggerCondition::LargeTrade { .. } => Some("wallet_monitor"),
+            TriggerCondition::KeywordSpike { .. } => Some("social_monitor"),
+            _ => None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            TriggerCondition::PriceCross { source, chain, token_address, direction, threshold_usd } => {
+                if !matches!(source.as_str(), "dexscreener" | "geckoterminal") {
+                    return Err(format!("Unknown price source '{}' (use dexscreener or geckoterminal)", source));
+                }
+                if chain.trim().is_empty() || token_address.trim().is_empty() {
+                    return Err("chain and token_address are required".to_string());
+                }
+                if !matches!(direction.as_str(), "above" | "below") {
+                    return Err("direction must be 'above' or 'below'".to_string());
+                }
+                if !threshold_usd.is_finite() || *threshold_usd <= 0.0 {
+                    return Err("threshold_usd must be a positive number".to_string());
+                }
+            }
+            TriggerCondition::LargeTrade { min_usd, .. } => {
+                if matches!(min_usd, Some(v) if !v.is_finite() || *v < 0.0) {
+                    return Err("min_usd cannot be negative".to_string());
+                }
+            }
+            TriggerCondition::KeywordSpike { keyword, window_minutes, min_mentions } => {
+                if keyword.trim().is_empty() {
+                    return Err("keyword is required".to_string());
+                }
+                if *window_minutes == 0 || *window_minutes > 7 * 24 * 60 {
+                    return Err("window_minutes must be between 1 and 10080".to_string());
+                }
+                if *min_mentions == 0 || *min_mentions > MAX_KEYWORD_SPIKE_MENTIONS {
+                    return Err(format!("min_mentions must be between 1 and {}", MAX_KEYWORD_SPIKE_MENTIONS));
+                }
+            }
+            TriggerCondition::TxConfirmed { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// One-line human description for logs and the agent message
+    pub fn describe(&self) -> String {
+        match self {
+            TriggerCondition::PriceCross { source, chain, token_address, direction, threshold_usd } => {
+                format!("{} price of {} on {} goes {} ${}", source, token_address, chain, direction, threshold_usd)
+            }
+            TriggerCondition::LargeTrade { min_usd, address, .. } => {
+                let who = address.as_deref().unwrap_or("a watched wallet");
+                match min_usd {
+                    Some(v) => format!("large trade of ${}+ by {}", v, who),
+                    None => format!("large trade by {}", who),
+                }
+            }
+            TriggerCondition::KeywordSpike { keyword, window_minutes, min_mentions } => {
+                format!("'{}' mentioned {}+ times in {} minutes", keyword, min_mentions, window_minutes)
+            }
+            TriggerCondition::TxConfirmed { tx_uuid, .. } => match tx_uuid {
+                Some(uuid) => format!("queued tx {} confirms", uuid),
+                None => "a queued transaction confirms".to_string(),
+            },
+        }
+    }
+}
+
+/// Evaluation state carried between checks
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TriggerState {
+    /// Last observed value (price, mention count)
+    #[serde(default)]
+    pub last_value: Option<f64>,
+    /// Consecutive checks the level condition has held
+    #[serde(default)]
+    pub streak: u32,
+    /// Whether the current streak already fired
+    #[serde(default)]
+    pub fired: bool,
+    /// Event watermark (last consumed activity id / confirmation time)
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+/// A condition trigger that runs a cron job when it fires
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulerTrigger {
+    pub id: i64,
+    pub name: String,
+    /// Cron job (database id) run when the trigger fires; pause the job to run it only from triggers
+    pub job_id: i64,
+    pub condition: TriggerCondition,
+    /// Seconds between condition checks
+    pub check_interval_secs: i64,
+    /// Minimum seconds between firings
+    pub cooldown_secs: i64,
+    /// Consecutive checks a level condition must hold before firing (debounce)
+    pub confirm_checks: i64,
+    pub enabled: bool,
+    pub state: TriggerState,
+    pub last_checked_at: Option<String>,
+    pub last_fired_at: Option<String>,
+    pub fire_count: i64,
+    /// Last evaluation error (cleared on a successful check)
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// One firing of a trigger and the job run it caused
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerRun {
+    pub id: i64,
+    pub trigger_id: i64,
+    pub fired_at: String,
+    /// What the condition saw
+    pub detail: String,
+    pub value: Option<f64>,
+    /// cron_job_runs row for the job run (None while running or if it failed to start)
+    pub cron_run_id: Option<i64>,
+    pub success: Option<bool>,
+    pub error: Option<String>,
+}
+
+/// Request to create a trigger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTriggerRequest {
+    pub name: String,
+    pub job_id: i64,
+    pub condition: TriggerCondition,
+    #[serde(default)]
+    pub check_interval_secs: Option<i64>,
+    #[serde(default)]
+    pub cooldown_secs: Option<i64>,
+    #[serde(default)]
+    pub confirm_checks: Option<i64>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+/// Request to update a trigger (a new condition resets its state)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateTriggerRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub job_id: Option<i64>,
+    #[serde(default)]
+    pub condition: Option<TriggerCondition>,
+    #[serde(default)]
+    pub check_interval_secs: Option<i64>,
+    #[serde(default)]
+    pub cooldown_secs: Option<i64>,
+    #[serde(default)]
+    pub confirm_checks: Option<i64>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+/// Check the timing fields shared by create and update requests
+pub fn validate_trigger_timing(
+    check_interval_secs: Option<i64>,
+    cooldown_secs: Option<i64>,
+    confirm_checks: Option<i64>,
+) -> Result<(), String> {
+    if matches!(check_interval_secs, Some(s) if s < MIN_TRIGGER_CHECK_INTERVAL_SECS) {
+        return Err(format!("check_interval_secs must be at least {}", MIN_TRIGGER_CHECK_INTERVAL_SECS));
+    }
+    if matches!(cooldown_secs, Some(s) if s < 0) {
+        return Err("cooldown_secs cannot be negative".to_string());
+    }
+    if matches!(confirm_checks, Some(n) if !(1..=100).contains(&n)) {
+        return Err("confirm_checks must be between 1 and 100".to_string());
+    }
+    Ok(())
+}
+
+/// Response for trigger operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<SchedulerTrigger>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub triggers: Option<Vec<SchedulerTrigger>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runs: Option<Vec<TriggerRun>>,
+    pub error: Option<String>,
+}
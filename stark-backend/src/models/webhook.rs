@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+
+/// Source of an inbound webhook — decides how the signature is checked
+/// and which prompt template is used by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    /// GitHub repository/organization webhooks (X-Hub-Signature-256)
+    Github,
+    /// Alchemy Notify address activity / mined transaction webhooks (X-Alchemy-Signature)
+    Alchemy,
+    /// Any JSON sender that signs the body with HMAC-SHA256 (X-Webhook-Signature)
+    Generic,
+}
+
+impl WebhookKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookKind::Github => "github",
+            WebhookKind::Alchemy => "alchemy",
+            WebhookKind::Generic => "generic",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "github" => Some(WebhookKind::Github),
+            "alchemy" => Some(WebhookKind::Alchemy),
+            "generic" | "json" => Some(WebhookKind::Generic),
+            _ => None,
+        }
+    }
+
+    /// Header carrying the hex HMAC-SHA256 of the raw body
+    pub fn signature_header(&self) -> &'static str {
+        match self {
+            WebhookKind::Github => "X-Hub-Signature-256",
+            WebhookKind::Alchemy => "X-Alchemy-Signature",
+            WebhookKind::Generic => "X-Webhook-Signature",
+        }
+    }
+
+    /// Header naming the event type, if the sender provides one
+    pub fn event_header(&self) -> Option<&'static str> {
+        match self {
+            WebhookKind::Github => Some("X-GitHub-Event"),
+            WebhookKind::Alchemy | WebhookKind::Generic => None,
+        }
+    }
+
+    /// Prompt template used when none is configured
+    pub fn default_prompt_template(&self) -> &'static str {
+        match self {
+            WebhookKind::Github => {
+                "GitHub sent a `{{event}}` event for {{payload.repository.full_name}}.\n\
+                 Summarize what happened and flag anything that needs attention (failed checks, security alerts).\n\n\
+                 {{payload}}"
+            }
+            WebhookKind::Alchemy => {
+                "Alchemy notification ({{payload.type}}) on {{payload.event.network}}.\n\
+                 Summarize the on-chain activity and flag anything unusual.\n\n\
+                 {{payload}}"
+            }
+            WebhookKind::Generic => "Webhook \"{{webhook.name}}\" received:\n\n{{payload}}",
+        }
+    }
+}
+
+/// A configured inbound webhook endpoint (`POST /api/webhooks/in/{slug}`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: i64,
+    /// URL path segment identifying the endpoint
+    pub slug: String,
+    pub name: String,
+    pub kind: String,
+    /// HMAC-SHA256 secret shared with the sender (only returned on creation)
+    #[serde(skip_serializing)]
+    pub secret: String,
+    /// Template rendered into the agent message ({{payload}}, {{payload.a.b}}, {{event}}, {{webhook.name}})
+    pub prompt_template: String,
+    /// Tool profile for the run (default: safe_mode — payloads are untrusted)
+    pub tool_profile: String,
+    /// Channel to deliver the agent's response to
+    pub deliver_channel_id: Option<i64>,
+    /// Chat/channel ID within the delivery channel (Telegram chat, Discord/Slack channel)
+    pub deliver_to: Option<String>,
+    pub enabled: bool,
+    pub trigger_count: i64,
+    pub last_triggered_at: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Webhook {
+    pub fn kind_enum(&self) -> WebhookKind {
+        WebhookKind::from_str(&self.kind).unwrap_or(WebhookKind::Generic)
+    }
+}
+
+/// Request to create a webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub name: String,
+    #[serde(default)]
+    pub slug: Option<String>,
+    #[serde(default = "default_kind")]
+    pub kind: String,
+    /// Generated when omitted
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub prompt_template: Option<String>,
+    #[serde(default = "default_tool_profile")]
+    pub tool_profile: String,
+    #[serde(default)]
+    pub deliver_channel_id: Option<i64>,
+    #[serde(default)]
+    pub deliver_to: Option<String>,
+}
+
+fn default_kind() -> String {
+    "generic".to_string()
+}
+
+fn default_tool_profile() -> String {
+    "safe_mode".to_string()
+}
+
+/// Request to update a webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateWebhookRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub prompt_template: Option<String>,
+    #[serde(default)]
+    pub tool_profile: Option<String>,
+    #[serde(default)]
+    pub deliver_channel_id: Option<i64>,
+    #[serde(default)]
+    pub deliver_to: Option<String>,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+/// Response for webhook operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookResponse {
+    pub success: bool,
+    pub webhook: Option<Webhook>,
+    pub webhooks: Option<Vec<Webhook>>,
+    /// Signing secret, included only when a webhook is created or its secret rotated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub error: Option<String>,
+}
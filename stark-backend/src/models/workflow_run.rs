@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Lifecycle state of a workflow run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkflowRunStatus {
+    /// Executing steps (a run left in this state was interrupted by a restart)
+    Running,
+    /// Waiting for the agent to supply values for an input step
+    AwaitingInput,
+    /// Waiting for the user to approve a gate
+    AwaitingApproval,
+    /// Stopped mid-step by a restart; resuming re-runs the current step
+    Interrupted,
+    Completed,
+    Failed,
+    Canceled,
+}
+
+impl WorkflowRunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkflowRunStatus::Running => "running",
+            WorkflowRunStatus::AwaitingInput => "awaiting_input",
+            WorkflowRunStatus::AwaitingApproval => "awaiting_approval",
+            WorkflowRunStatus::Interrupted => "interrupted",
+            WorkflowRunStatus::Completed => "completed",
+            WorkflowRunStatus::Failed => "failed",
+            WorkflowRunStatus::Canceled => "canceled",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "running" => Some(WorkflowRunStatus::Running),
+            "awaiting_input" => Some(WorkflowRunStatus::AwaitingInput),
+            "awaiting_approval" => Some(WorkflowRunStatus::AwaitingApproval),
+            "interrupted" => Some(WorkflowRunStatus::Interrupted),
+            "completed" => Some(WorkflowRunStatus::Completed),
+            "failed" => Some(WorkflowRunStatus::Failed),
+            "canceled" | "cancelled" => Some(WorkflowRunStatus::Canceled),
+            _ => None,
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            WorkflowRunStatus::Completed | WorkflowRunStatus::Failed | WorkflowRunStatus::Canceled
+        )
+    }
+}
+
+/// Outcome of one executed (or skipped) step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowStepRecord {
+    pub step: String,
+    /// "done", "skipped", "failed", "approved" or "rejected"
+    pub outcome: String,
+    #[serde(default)]
+    pub tool: Option<String>,
+    #[serde(default)]
+    pub attempts: u32,
+    /// Truncated tool output or error
+    #[serde(default)]
+    pub detail: String,
+    pub at: String,
+}
+
+/// A skill workflow execution, checkpointed after every step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRun {
+    pub id: i64,
+    pub run_id: String,
+    pub session_id: i64,
+    pub channel_id: i64,
+    pub skill_name: String,
+    /// RON source the run started with, so editing the skill does not disturb runs in flight
+    pub definition: String,
+    pub status: WorkflowRunStatus,
+    /// Index of the next step to execute
+    pub current_step: usize,
+    /// Inputs and step outputs, referenced from templates
+    pub variables: Map<String, Value>,
+    /// Register store snapshot (restored when the run resumes in a new turn or after a restart)
+    pub registers: Value,
+    pub step_log: Vec<WorkflowStepRecord>,
+    pub error: Option<String>,
+    /// When the run last paused at a gate; approvals must come from a later user message
+    pub paused_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
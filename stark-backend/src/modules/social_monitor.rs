@@ -22,7 +22,7 @@ impl SocialMonitorModule {
         Arc::new(SocialMonitorClient::new(&url))
     }
 
-    fn url_from_env() -> String {
+    pub(crate) fn url_from_env() -> String {
         std::env::var("SOCIAL_MONITOR_URL")
             .unwrap_or_else(|_| {
                 let port = std::env::var("SOCIAL_MONITOR_PORT")
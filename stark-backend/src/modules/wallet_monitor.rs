@@ -17,7 +17,7 @@ impl WalletMonitorModule {
         Arc::new(WalletMonitorClient::new(&url))
     }
 
-    fn url_from_env() -> String {
+    pub(crate) fn url_from_env() -> String {
         std::env::var("WALLET_MONITOR_URL")
             .unwrap_or_else(|_| "http://127.0.0.1:9100".to_string())
     }
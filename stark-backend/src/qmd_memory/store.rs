@@ -288,6 +288,33 @@ impl MemoryStore {
             .collect())
     }
 
+    /// Move one identity's memory files into another's, appending where both have
+    /// the same file (e.g. MEMORY.md). Used when accounts are linked.
+    pub fn merge_identity(&self, from_identity_id: &str, into_identity_id: &str) -> std::io::Result<usize> {
+        let from_dir = self.memory_dir.join(from_identity_id);
+        if from_identity_id == into_identity_id || !from_dir.is_dir() {
+            return Ok(0);
+        }
+        file_ops::ensure_memory_dirs(&self.memory_dir, Some(into_identity_id))?;
+
+        let mut moved = 0;
+        for path in file_ops::list_memory_files(&from_dir)? {
+            let Ok(relative) = path.strip_prefix(&from_dir) else { continue };
+            let target = self.memory_dir.join(into_identity_id).join(relative);
+            let content = file_ops::read_file(&path)?;
+            file_ops::append_raw(&target, &content)?;
+            moved += 1;
+        }
+        std::fs::remove_dir_all(&from_dir)?;
+        self.reindex().ok();
+
+        log::info!(
+            "[QMD_MEMORY] Merged {} memory files from identity {} into {}",
+            moved, from_identity_id, into_identity_id
+        );
+        Ok(moved)
+    }
+
     /// Index or update a single file in the FTS index
     fn index_file(&self, file_path: &PathBuf) -> SqliteResult<()> {
         let conn = self.conn.lock().unwrap();
@@ -313,7 +340,7 @@ impl MemoryStore {
 }
 
 /// Escape special characters for FTS5 query
-fn escape_fts5_query(query: &str) -> String {
+pub fn escape_fts5_query(query: &str) -> String {
     // Split into words and join with OR for multi-word queries
     let words: Vec<&str> = query.split_whitespace().collect();
 
@@ -422,4 +449,24 @@ mod tests {
         assert!(user2_mem.contains("tea"));
         assert!(!user2_mem.contains("coffee"));
     }
+
+    #[test]
+    fn test_merge_identity() {
+        let dir = tempdir().unwrap();
+        let mem_dir = dir.path().join("memory");
+        let db_path = dir.path().join("test.db");
+
+        let store =
+            MemoryStore::new(mem_dir.clone(), db_path.to_str().unwrap()).expect("Failed to create store");
+
+        store.append_long_term("Likes coffee", Some("a")).expect("Failed to append");
+        store.append_long_term("Likes tea", Some("b")).expect("Failed to append");
+
+        assert_eq!(store.merge_identity("b", "a").expect("Failed to merge"), 1);
+
+        let merged = store.get_long_term(Some("a")).expect("Failed to read");
+        assert!(merged.contains("coffee"));
+        assert!(merged.contains("tea"));
+        assert!(!mem_dir.join("b").exists());
+    }
 }
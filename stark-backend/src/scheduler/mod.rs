@@ -1,3 +1,4 @@
 pub mod runner;
+pub mod triggers;
 
 pub use runner::{Scheduler, SchedulerConfig};
@@ -1,13 +1,16 @@
+use crate::channels::delivery;
 use crate::channels::dispatcher::MessageDispatcher;
 use crate::channels::types::NormalizedMessage;
 use crate::db::Database;
 use crate::execution::ExecutionTracker;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
-use crate::models::{CronJob, HeartbeatConfig, JobStatus, ScheduleType};
+use crate::models::{CronJob, CronRunInput, HeartbeatConfig, JobStatus, ScheduleType, SchedulerTrigger};
+use crate::scheduler::triggers;
 use crate::tools::ToolRegistry;
 use crate::wallet;
 use chrono::{DateTime, Duration, Local, NaiveTime, Utc, Weekday, Datelike, Timelike};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::oneshot;
 use tokio::time::{interval, timeout, Duration as TokioDuration};
@@ -46,6 +49,17 @@ impl Default for SchedulerConfig {
 /// Maximum time for a heartbeat execution before timeout (60 seconds)
 const HEARTBEAT_TIMEOUT_SECS: u64 = 60;
 
+/// Number of recent user messages used to judge which mind map branch is most relevant
+const HEARTBEAT_FOCUS_MESSAGES: i32 = 20;
+
+/// Recent conversation text that steers heartbeat branch selection
+fn heartbeat_focus(db: &Database) -> Option<String> {
+    db.get_recent_user_messages(HEARTBEAT_FOCUS_MESSAGES)
+        .ok()
+        .filter(|m| !m.is_empty())
+        .map(|m| m.join("\n"))
+}
+
 /// Default timeout for cron job execution (10 minutes)
 const DEFAULT_CRON_JOB_TIMEOUT_SECS: u64 = 10 * 60;
 
@@ -59,11 +73,67 @@ const ERROR_BACKOFF_SECS: &[u64] = &[
     60 * 60,  // 5th+ error →  60 min
 ];
 
+/// The message and settings a cron job runs with
+fn cron_run_input(job: &CronJob) -> CronRunInput {
+    CronRunInput {
+        message: job
+            .message
+            .clone()
+            .or_else(|| job.system_event.clone())
+            .unwrap_or_else(|| format!("[Cron: {}]", job.name)),
+        run_overrides: job.effective_run_overrides(),
+    }
+}
+
 fn error_backoff_secs(error_count: i32) -> u64 {
     let idx = (error_count.max(1) - 1) as usize;
     ERROR_BACKOFF_SECS[idx.min(ERROR_BACKOFF_SECS.len() - 1)]
 }
 
+/// One slot of the kanban concurrency limit, released when the task holding it ends
+/// (including by panic or cancellation). A task that ends without recording its
+/// outcome is sent back through the retry/backoff path instead of staying in_progress.
+struct KanbanSlot {
+    running: Arc<AtomicUsize>,
+    /// Task still owing an outcome
+    task: Option<(Arc<Database>, i64)>,
+}
+
+impl KanbanSlot {
+    fn acquire(running: &Arc<AtomicUsize>) -> Self {
+        running.fetch_add(1, Ordering::SeqCst);
+        Self { running: Arc::clone(running), task: None }
+    }
+
+    fn for_task(mut self, db: &Arc<Database>, task_id: i64) -> Self {
+        self.task = Some((Arc::clone(db), task_id));
+        self
+    }
+
+    /// The task recorded its own outcome (complete, retry or failed)
+    fn finish(&mut self) {
+        self.task = None;
+    }
+}
+
+impl Drop for KanbanSlot {
+    fn drop(&mut self) {
+        if let Some((db, task_id)) = self.task.take() {
+            let reason = if std::thread::panicking() { "task panicked" } else { "task was cancelled" };
+            log::error!("Kanban task #{} ended without an outcome: {}", task_id, reason);
+            // Off the unwinding thread, so a database error can't turn a panic into an abort
+            if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                handle.spawn_blocking(move || {
+                    if let Err(e) = db.record_kanban_task_failure(task_id, reason) {
+                        log::error!("Failed to record kanban task #{} failure: {}", task_id, e);
+                    }
+                });
+            }
+        }
+        self.running.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 /// The scheduler service that runs cron jobs and heartbeats
 pub struct Scheduler {
     db: Arc<Database>,
@@ -73,6 +143,8 @@ pub struct Scheduler {
     config: SchedulerConfig,
     /// Wallet provider for x402 payments in scheduled tasks (heartbeats, cron jobs)
     wallet_provider: Option<Arc<dyn wallet::WalletProvider>>,
+    /// Kanban tasks currently being auto-executed (shared across clones)
+    kanban_running: Arc<AtomicUsize>,
 }
 
 impl Scheduler {
@@ -91,6 +163,7 @@ impl Scheduler {
             execution_tracker,
             config,
             wallet_provider,
+            kanban_running: Arc::new(AtomicUsize::new(0)),
         }
     }
 
@@ -146,6 +219,13 @@ impl Scheduler {
             log::error!("Error processing kanban tasks: {}", e);
         }
 
+        // Check condition triggers (they run their cron job when they fire)
+        if self.config.cron_enabled
+            && let Err(e) = self.process_triggers().await
+        {
+            log::error!("Error processing scheduler triggers: {}", e);
+        }
+
         // Process heartbeats (always enabled - individual configs control their own enabled state)
         if let Err(e) = self.process_heartbeats().await {
             log::error!("Error processing heartbeats: {}", e);
@@ -206,7 +286,117 @@ impl Scheduler {
         Ok(())
     }
 
-    /// Process kanban tasks that are in "ready" status (auto-execute)
+    /// Check condition triggers whose interval has elapsed (each check runs in its own task)
+    async fn process_triggers(&self) -> Result<(), String> {
+        let due = self
+            .db
+            .list_due_scheduler_triggers()
+            .map_err(|e| format!("Failed to list due triggers: {}", e))?;
+
+        for trigger in due {
+            // Stamp the check time up front so a slow source isn't re-checked next tick
+            if let Err(e) = self.db.touch_scheduler_trigger(trigger.id) {
+                log::error!("Failed to mark trigger '{}' as checked: {}", trigger.name, e);
+                continue;
+            }
+            let scheduler = self.clone_inner();
+            tokio::spawn(async move {
+                if let Err(e) = scheduler.check_trigger(&trigger).await {
+                    log::error!("Scheduler trigger '{}' failed: {}", trigger.name, e);
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Evaluate one trigger and, if it fires, run its job and record the outcome
+    async fn check_trigger(&self, trigger: &SchedulerTrigger) -> Result<(), String> {
+        let evaluation = match triggers::evaluate(&trigger.condition, &trigger.state, &self.db).await {
+            Ok(evaluation) => evaluation,
+            Err(e) => {
+                log::warn!("Scheduler trigger '{}' check failed: {}", trigger.name, e);
+                let _ = self.db.record_scheduler_trigger_check(trigger.id, &trigger.state, Some(&e));
+                return Ok(());
+            }
+        };
+
+        let (fire, state) = triggers::decide(trigger, &evaluation, Utc::now());
+        self.db
+            .record_scheduler_trigger_check(trigger.id, &state, None)
+            .map_err(|e| format!("Failed to record trigger check: {}", e))?;
+        if !fire {
+            return Ok(());
+        }
+
+        log::info!("Scheduler trigger '{}' fired: {}", trigger.name, evaluation.detail);
+        let run = self
+            .db
+            .record_scheduler_trigger_fire(trigger.id, &evaluation.detail, evaluation.value, trigger.condition.is_one_shot())
+            .map_err(|e| format!("Failed to record trigger firing: {}", e))?;
+
+        self.broadcaster.broadcast(GatewayEvent::custom(
+            "scheduler_trigger_fired",
+            serde_json::json!({
+                "trigger_id": trigger.id,
+                "name": trigger.name,
+                "condition": trigger.condition.kind(),
+                "detail": evaluation.detail,
+                "value": evaluation.value,
+                "run_id": run.id,
+            }),
+        ));
+
+        let job = match self.db.get_cron_job(trigger.job_id) {
+            Ok(Some(job)) => job,
+            Ok(None) => {
+                let error = format!("Cron job {} no longer exists", trigger.job_id);
+                let _ = self.db.complete_scheduler_trigger_run(run.id, None, false, Some(&error));
+                return Err(error);
+            }
+            Err(e) => {
+                let error = format!("Failed to load cron job {}: {}", trigger.job_id, e);
+                let _ = self.db.complete_scheduler_trigger_run(run.id, None, false, Some(&error));
+                return Err(error);
+            }
+        };
+
+        let context = format!(
+            "[Trigger: {}] {}\nObserved: {}",
+            trigger.name,
+            trigger.condition.describe(),
+            evaluation.detail
+        );
+        let mut input = cron_run_input(&job);
+        input.message = format!("{}\n\n{}", input.message, context);
+        let (success, cron_run_id, error) = match self.run_cron_job(&job, input, None).await {
+            Ok((success, cron_run_id)) => {
+                let error = if success { None } else { Some("Job run failed (see cron run log)".to_string()) };
+                (success, cron_run_id, error)
+            }
+            Err(e) => (false, None, Some(e)),
+        };
+        if let Err(e) = self.db.complete_scheduler_trigger_run(run.id, cron_run_id, success, error.as_deref()) {
+            log::error!("Failed to record trigger run outcome for '{}': {}", trigger.name, e);
+        }
+
+        self.broadcaster.broadcast(GatewayEvent::custom(
+            "scheduler_trigger_completed",
+            serde_json::json!({
+                "trigger_id": trigger.id,
+                "name": trigger.name,
+                "run_id": run.id,
+                "cron_run_id": cron_run_id,
+                "success": success,
+                "error": error,
+            }),
+        ));
+
+        Ok(())
+    }
+
+    /// Process kanban tasks that are in "ready" status (auto-execute).
+    /// Picks unblocked tasks until `kanban_max_concurrent` are running.
     async fn process_kanban_tasks(&self) -> Result<(), String> {
         // Check if auto-execute is enabled in bot settings
         let settings = self.db.get_bot_settings()
@@ -214,31 +404,42 @@ impl Scheduler {
         if !settings.kanban_auto_execute {
             return Ok(());
         }
+        let max_concurrent = settings.kanban_max_concurrent.max(1) as usize;
 
         // Pick tasks one at a time in a loop (pick_next_kanban_task atomically moves to in_progress)
-        loop {
+        while self.kanban_running.load(Ordering::SeqCst) < max_concurrent {
             let task = self.db.pick_next_kanban_task()
                 .map_err(|e| format!("Failed to pick kanban task: {}", e))?;
 
             let task = match task {
                 Some(t) => t,
-                None => break, // No more ready tasks
+                None => break, // No ready, unblocked tasks
             };
 
-            log::info!("Auto-executing kanban task #{}: {}", task.id, task.title);
+            log::info!(
+                "Auto-executing kanban task #{} (attempt {}): {}",
+                task.id, task.attempts + 1, task.title
+            );
 
             // Broadcast that the task was picked up
             self.broadcaster.broadcast(GatewayEvent::new(
                 "kanban_item_updated",
                 serde_json::json!({ "item": &task }),
             ));
+            self.broadcaster.broadcast(GatewayEvent::custom(
+                "kanban_task_started",
+                serde_json::json!({ "item_id": task.id, "title": task.title, "attempt": task.attempts + 1 }),
+            ));
 
             // Spawn execution in background
+            let mut slot = KanbanSlot::acquire(&self.kanban_running).for_task(&self.db, task.id);
             let scheduler = self.clone_inner();
             let task_id = task.id;
             let task_title = task.title.clone();
             tokio::spawn(async move {
-                if let Err(e) = scheduler.execute_kanban_task(&task).await {
+                let result = scheduler.execute_kanban_task(&task).await;
+                slot.finish();
+                if let Err(e) = result {
                     log::error!("Kanban task #{} '{}' failed: {}", task_id, task_title, e);
                 }
             });
@@ -273,11 +474,24 @@ impl Scheduler {
             session_mode: Some("isolated".to_string()),
             selected_network: None,
             force_safe_mode: false,
+            safe_mode_fallback: false,
+            endpoint_override: None,
+            target_session_id: None,
+            attachments: Vec::new(),
+            tool_profile: None,
+            thinking_level: None,
+            agent_subtype: task.agent_subtype.clone(),
+            max_tool_iterations: None,
+            max_tx_value_wei: None,
         };
 
-        // Execute with 10-minute timeout (same as cron default)
+        // Per-task timeout, else the cron default (10 minutes)
+        let timeout_secs = task.timeout_seconds
+            .filter(|s| *s > 0)
+            .map(|s| s as u64)
+            .unwrap_or(DEFAULT_CRON_JOB_TIMEOUT_SECS);
         let dispatch_result = timeout(
-            TokioDuration::from_secs(DEFAULT_CRON_JOB_TIMEOUT_SECS),
+            TokioDuration::from_secs(timeout_secs),
             self.dispatcher.dispatch(normalized),
         ).await;
 
@@ -287,7 +501,7 @@ impl Scheduler {
                 (ok, result.response, result.error)
             }
             Err(_) => {
-                let err_msg = format!("Kanban task timed out after {}s", DEFAULT_CRON_JOB_TIMEOUT_SECS);
+                let err_msg = format!("Kanban task timed out after {}s", timeout_secs);
                 log::warn!("Kanban task #{} timed out", task.id);
                 (false, String::new(), Some(err_msg))
             }
@@ -315,15 +529,54 @@ impl Scheduler {
             };
             let _ = self.db.update_kanban_item(task.id, &update);
             log::info!("Kanban task #{} completed successfully", task.id);
+            self.broadcaster.broadcast(GatewayEvent::custom(
+                "kanban_task_completed",
+                serde_json::json!({ "item_id": task.id, "title": task.title, "session_id": session_id }),
+            ));
+
+            // Let the board know which waiting tasks this one unblocked
+            for dependent in self.db.list_unblocked_kanban_dependents(task.id).unwrap_or_default() {
+                self.broadcaster.broadcast(GatewayEvent::custom(
+                    "kanban_task_unblocked",
+                    serde_json::json!({ "item_id": dependent.id, "title": dependent.title, "unblocked_by": task.id }),
+                ));
+            }
         } else {
-            // Revert to ready so it can be retried, store error in result
-            let update = crate::db::tables::kanban::UpdateKanbanItemRequest {
-                status: Some("ready".to_string()),
-                result: Some(format!("Error: {}", error_msg.as_deref().unwrap_or("unknown"))),
-                ..Default::default()
-            };
-            let _ = self.db.update_kanban_item(task.id, &update);
-            log::warn!("Kanban task #{} failed, reverted to ready: {:?}", task.id, error_msg);
+            // Back to ready after a backoff while retries remain, otherwise failed
+            let error = error_msg.as_deref().unwrap_or("unknown");
+            match self.db.record_kanban_task_failure(task.id, error) {
+                Ok(Some(item)) if item.status == "ready" => {
+                    log::warn!(
+                        "Kanban task #{} failed (attempt {}/{}), retrying at {:?}: {}",
+                        task.id, item.attempts, item.max_retries + 1, item.next_attempt_at, error
+                    );
+                    self.broadcaster.broadcast(GatewayEvent::custom(
+                        "kanban_task_retry_scheduled",
+                        serde_json::json!({
+                            "item_id": item.id,
+                            "title": item.title,
+                            "attempt": item.attempts,
+                            "max_retries": item.max_retries,
+                            "next_attempt_at": item.next_attempt_at,
+                            "error": error,
+                        }),
+                    ));
+                }
+                Ok(Some(item)) => {
+                    log::warn!("Kanban task #{} failed after {} attempt(s): {}", task.id, item.attempts, error);
+                    self.broadcaster.broadcast(GatewayEvent::custom(
+                        "kanban_task_failed",
+                        serde_json::json!({
+                            "item_id": item.id,
+                            "title": item.title,
+                            "attempts": item.attempts,
+                            "error": error,
+                        }),
+                    ));
+                }
+                Ok(None) => {}
+                Err(e) => log::error!("Failed to record kanban task #{} failure: {}", task.id, e),
+            }
         }
 
         // Broadcast update for UI refresh
@@ -345,11 +598,23 @@ impl Scheduler {
             execution_tracker: Arc::clone(&self.execution_tracker),
             config: self.config.clone(),
             wallet_provider: self.wallet_provider.clone(),
+            kanban_running: Arc::clone(&self.kanban_running),
         }
     }
 
     /// Execute a single cron job
     async fn execute_cron_job(&self, job: &CronJob) -> Result<(), String> {
+        self.run_cron_job(job, cron_run_input(job), None).await.map(|_| ())
+    }
+
+    /// Run a cron job with the given input (the job's own, a trigger-augmented message,
+    /// or a past run's when replaying). Returns whether the run succeeded and its run log id.
+    async fn run_cron_job(
+        &self,
+        job: &CronJob,
+        input: CronRunInput,
+        replay_of: Option<i64>,
+    ) -> Result<(bool, Option<i64>), String> {
         let started_at = Utc::now();
         let started_at_str = started_at.to_rfc3339();
 
@@ -376,13 +641,6 @@ impl Scheduler {
         // Track if this is main mode for later stop event
         let is_main_mode = job.session_mode == "main";
 
-        // Build the message to dispatch
-        let message_text = job
-            .message
-            .clone()
-            .or_else(|| job.system_event.clone())
-            .unwrap_or_else(|| format!("[Cron: {}]", job.name));
-
         // Determine channel ID based on session_mode
         // - "main" mode: use channel 0 (web channel) to share session with web UI
         // - "isolated" mode (default): use unique negative channel ID to avoid collision
@@ -418,19 +676,33 @@ impl Scheduler {
         }
 
         // Create a normalized message for the dispatcher
-        let normalized = NormalizedMessage {
+        let mut normalized = NormalizedMessage {
             channel_id: cron_channel_id,
             channel_type: "cron".to_string(),
             chat_id: format!("cron:{}", job.job_id),
             chat_name: None,
             user_id: "system".to_string(),
             user_name: format!("Cron: {}", job.name),
-            text: message_text,
+            text: input.message.clone(),
             message_id: Some(format!("cron-run-{}", started_at.timestamp())),
             session_mode: Some(job.session_mode.clone()),
             selected_network: None,
             force_safe_mode: false,
+            safe_mode_fallback: false,
+            endpoint_override: None,
+            target_session_id: None,
+            attachments: Vec::new(),
+            tool_profile: None,
+            thinking_level: None,
+            agent_subtype: None,
+            max_tool_iterations: None,
+            max_tx_value_wei: None,
         };
+        // Per-job endpoint, thinking level, tool profile/subtype and caps
+        input.run_overrides.apply_to(&mut normalized);
+
+        // Usage after these marks belongs to this run
+        let watermarks = self.db.cron_run_watermarks().unwrap_or((i64::MAX, i64::MAX));
 
         // Execute the job with timeout
         let timeout_secs = job.timeout_seconds
@@ -498,7 +770,7 @@ impl Scheduler {
             .map_err(|e| format!("Failed to update job status: {}", e))?;
 
         // Log the run
-        let _ = self.db.log_cron_job_run(
+        let run = self.db.log_cron_job_run(
             job.id,
             &started_at_str,
             Some(&completed_at.to_rfc3339()),
@@ -508,17 +780,47 @@ impl Scheduler {
             Some(duration_ms),
         );
 
+        // Record the run's inputs, session and usage
+        let session_key = Database::generate_session_key("cron", cron_channel_id, &format!("cron:{}", job.job_id));
+        let session_id = self.db.get_chat_session_by_key(&session_key).ok().flatten().map(|s| s.id);
+        let stats = self.db.cron_run_stats(session_id, cron_channel_id, watermarks).unwrap_or_default();
+        if let Ok(ref run) = run
+            && let Err(e) = self.db.record_cron_job_run_details(run.id, &input, replay_of, &stats)
+        {
+            log::warn!("Failed to record run details for cron job '{}': {}", job.name, e);
+        }
+
+        // Alert once when the failure streak reaches the job's threshold
+        if !success && job.alert_after_failures == Some(job.consecutive_failures + 1) {
+            self.send_failure_alert(job, error_msg.as_deref().unwrap_or("unknown error")).await;
+        }
+
+        // Deliver the result if configured and record the outcome on the run
+        if job.deliver && job.channel_id.is_some() {
+            let (status, delivery_error) = if !success || response.trim().is_empty() {
+                ("skipped", None)
+            } else {
+                match self.deliver_result(job, &response).await {
+                    Ok(()) => ("delivered", None),
+                    Err(e) => {
+                        log::error!("Cron job '{}' delivery failed: {}", job.name, e);
+                        ("failed", Some(e))
+                    }
+                }
+            };
+            if let Ok(ref run) = run
+                && let Err(e) = self.db.update_cron_job_run_delivery(run.id, status, delivery_error.as_deref())
+            {
+                log::warn!("Failed to record delivery status for cron job '{}': {}", job.name, e);
+            }
+        }
+
         // Handle delete_after_run for one-shot jobs
         if success && job.delete_after_run {
             log::info!("Deleting one-shot cron job '{}' after successful run", job.name);
             let _ = self.db.delete_cron_job(job.id);
         }
 
-        // Handle delivery if configured
-        if job.deliver && job.channel_id.is_some() {
-            self.deliver_result(job, &response).await?;
-        }
-
         // Broadcast job completion event
         self.broadcaster.broadcast(GatewayEvent::custom(
             "cron_job_completed",
@@ -527,6 +829,11 @@ impl Scheduler {
                 "name": job.name,
                 "success": success,
                 "duration_ms": duration_ms,
+                "run_id": run.as_ref().ok().map(|r| r.id),
+                "session_id": session_id,
+                "tool_calls": stats.tool_calls,
+                "tokens_used": stats.tokens_used,
+                "x402_spend_usdc": stats.x402_spend_usdc,
             }),
         ));
 
@@ -546,7 +853,7 @@ impl Scheduler {
             success
         );
 
-        Ok(())
+        Ok((success, run.ok().map(|r| r.id)))
     }
 
     /// Calculate the next run time for a job
@@ -574,24 +881,43 @@ impl Scheduler {
 
     /// Deliver job result to the configured channel
     async fn deliver_result(&self, job: &CronJob, response: &str) -> Result<(), String> {
-        // For now, we just log that we would deliver
-        // In a full implementation, this would send to the channel
-        log::info!(
-            "Would deliver cron job '{}' result to channel {} (to: {:?}): {}",
-            job.name,
-            job.channel_id.unwrap_or(0),
-            job.deliver_to,
-            if response.len() > 100 {
-                format!("{}...", &response[..100])
-            } else {
-                response.to_string()
-            }
-        );
+        let channel_id = job.channel_id.ok_or_else(|| "No delivery channel set".to_string())?;
+        let subject = format!("Cron: {}", job.name);
+        delivery::deliver_text(
+            &self.db,
+            channel_id,
+            job.deliver_to.as_deref().unwrap_or_default(),
+            &subject,
+            response,
+        )
+        .await
+    }
 
-        // TODO: Implement actual channel delivery
-        // This would involve looking up the channel and sending a message
+    /// Tell the operator a job has failed `alert_after_failures` times in a row:
+    /// always over the gateway, and on the job's delivery channel when it has one
+    async fn send_failure_alert(&self, job: &CronJob, error: &str) {
+        let failures = job.consecutive_failures + 1;
+        log::warn!("Cron job '{}' has failed {} times in a row", job.name, failures);
 
-        Ok(())
+        self.broadcaster.broadcast(GatewayEvent::custom(
+            "cron_job_alert",
+            serde_json::json!({
+                "job_id": job.job_id,
+                "name": job.name,
+                "consecutive_failures": failures,
+                "error": error,
+            }),
+        ));
+
+        if job.channel_id.is_some() {
+            let text = format!(
+                "⚠️ Cron job '{}' has failed {} times in a row.\nLast error: {}",
+                job.name, failures, error
+            );
+            if let Err(e) = self.deliver_result(job, &text).await {
+                log::error!("Failed to deliver failure alert for cron job '{}': {}", job.name, e);
+            }
+        }
     }
 
     /// Process due heartbeats
@@ -704,9 +1030,13 @@ impl Scheduler {
         }
 
         // === MIND MAP MEANDERING ===
-        // Get the next node to visit (starts at trunk, then meanders)
-        let next_node = self.db.get_next_heartbeat_node(config.current_mind_node_id)
+        // Get the next node to visit (starts at trunk, then follows the most relevant branch)
+        let focus = heartbeat_focus(&self.db);
+        let next_node = self.db.get_next_heartbeat_node(config.current_mind_node_id, focus.as_deref())
             .map_err(|e| format!("Failed to get next heartbeat node: {}", e))?;
+        if let Err(e) = self.db.record_mind_node_visit(next_node.id) {
+            log::warn!("Failed to record mind node visit: {}", e);
+        }
 
         let node_depth = self.db.get_mind_node_depth(next_node.id).unwrap_or(0);
 
@@ -758,7 +1088,7 @@ impl Scheduler {
         // Use fixed constants for heartbeat identity, but isolated session mode
         // to prevent session state corruption from breaking other functionality
         // IMPORTANT: Always use HEARTBEAT_CHANNEL_ID (-999) to avoid polluting web UI (channel 0)
-        let normalized = NormalizedMessage {
+        let mut normalized = NormalizedMessage {
             channel_id: HEARTBEAT_CHANNEL_ID,
             channel_type: HEARTBEAT_CHANNEL_TYPE.to_string(),
             chat_id: HEARTBEAT_CHAT_ID.to_string(),
@@ -770,7 +1100,17 @@ impl Scheduler {
             session_mode: Some("isolated".to_string()), // Isolated to prevent state corruption
             selected_network: None,
             force_safe_mode: false,
+            safe_mode_fallback: false,
+            endpoint_override: None,
+            target_session_id: None,
+            attachments: Vec::new(),
+            tool_profile: None,
+            thinking_level: None,
+            agent_subtype: None,
+            max_tool_iterations: None,
+            max_tx_value_wei: None,
         };
+        config.run_overrides.apply_to(&mut normalized);
 
         // Execute the heartbeat
         let result = self.dispatcher.dispatch(normalized).await;
@@ -834,6 +1174,26 @@ impl Scheduler {
         Ok(format!("Job '{}' executed successfully", job.name))
     }
 
+    /// Re-run a past cron run with the same message and settings.
+    /// Returns the new run's id.
+    pub async fn replay_cron_run(&self, run_id: i64) -> Result<Option<i64>, String> {
+        let run = self
+            .db
+            .get_cron_job_run(run_id)
+            .map_err(|e| format!("Database error: {}", e))?
+            .ok_or_else(|| format!("Run not found: {}", run_id))?;
+        let job = self
+            .db
+            .get_cron_job(run.job_id)
+            .map_err(|e| format!("Database error: {}", e))?
+            .ok_or_else(|| format!("Job not found: {}", run.job_id))?;
+
+        // Runs logged before inputs were recorded replay with the job's current input
+        let input = run.input.unwrap_or_else(|| cron_run_input(&job));
+        let (_, new_run_id) = self.run_cron_job(&job, input, Some(run_id)).await?;
+        Ok(new_run_id)
+    }
+
     /// Trigger a heartbeat pulse (fire and forget, like a channel message)
     ///
     /// Returns immediately after spawning the background task.
@@ -936,7 +1296,8 @@ async fn execute_heartbeat_isolated(
 
     // Get the next node to visit
     log::info!("[HEARTBEAT-ISOLATED] Getting next heartbeat node...");
-    let next_node = match db.get_next_heartbeat_node(config.current_mind_node_id) {
+    let focus = heartbeat_focus(db);
+    let next_node = match db.get_next_heartbeat_node(config.current_mind_node_id, focus.as_deref()) {
         Ok(node) => {
             log::info!("[HEARTBEAT-ISOLATED] Got next node: id={}", node.id);
             node
@@ -946,6 +1307,9 @@ async fn execute_heartbeat_isolated(
             return Err(format!("Failed to get next heartbeat node: {}", e));
         }
     };
+    if let Err(e) = db.record_mind_node_visit(next_node.id) {
+        log::warn!("[HEARTBEAT-ISOLATED] Failed to record mind node visit: {}", e);
+    }
 
     // Calculate depth using iterative BFS (safe from cycles)
     let node_depth = db.get_mind_node_depth(next_node.id).unwrap_or(0);
@@ -1008,7 +1372,7 @@ async fn execute_heartbeat_isolated(
     );
 
     // IMPORTANT: Always use HEARTBEAT_CHANNEL_ID (-999) to avoid polluting web UI (channel 0)
-    let normalized = NormalizedMessage {
+    let mut normalized = NormalizedMessage {
         channel_id: HEARTBEAT_CHANNEL_ID,
         channel_type: HEARTBEAT_CHANNEL_TYPE.to_string(),
         chat_id: HEARTBEAT_CHAT_ID.to_string(),
@@ -1020,7 +1384,17 @@ async fn execute_heartbeat_isolated(
         session_mode: Some("isolated".to_string()),
         selected_network: None,
         force_safe_mode: false,
+        safe_mode_fallback: false,
+        endpoint_override: None,
+        target_session_id: None,
+        attachments: Vec::new(),
+        tool_profile: None,
+        thinking_level: None,
+        agent_subtype: None,
+        max_tool_iterations: None,
+        max_tx_value_wei: None,
     };
+    config.run_overrides.apply_to(&mut normalized);
 
     // === DEFERRED AI CALL (fire and forget) ===
     let dispatcher = Arc::clone(dispatcher);
@@ -1080,3 +1454,61 @@ async fn execute_heartbeat_isolated(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_kanban_slot_released_when_task_panics() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let slot = KanbanSlot::acquire(&running);
+        assert_eq!(running.load(Ordering::SeqCst), 1);
+
+        let handle = tokio::spawn(async move {
+            let _slot = slot;
+            panic!("kanban task blew up");
+        });
+        assert!(handle.await.is_err());
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_panicked_kanban_task_goes_back_through_retry() {
+        use crate::db::tables::kanban::{CreateKanbanItemRequest, UpdateKanbanItemRequest};
+
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let item = db.create_kanban_item(&CreateKanbanItemRequest {
+            title: "flaky".to_string(),
+            max_retries: Some(1),
+            ..Default::default()
+        }).unwrap();
+        db.update_kanban_item(item.id, &UpdateKanbanItemRequest {
+            status: Some("ready".to_string()),
+            ..Default::default()
+        }).unwrap();
+        let task = db.pick_next_kanban_task().unwrap().unwrap();
+        assert_eq!(task.status, "in_progress");
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let slot = KanbanSlot::acquire(&running).for_task(&db, task.id);
+        let handle = tokio::spawn(async move {
+            let _slot = slot;
+            panic!("kanban task blew up");
+        });
+        assert!(handle.await.is_err());
+
+        let mut item = db.get_kanban_item(task.id).unwrap().unwrap();
+        for _ in 0..50 {
+            if item.status != "in_progress" {
+                break;
+            }
+            tokio::time::sleep(TokioDuration::from_millis(20)).await;
+            item = db.get_kanban_item(task.id).unwrap().unwrap();
+        }
+        assert_eq!(item.status, "ready");
+        assert_eq!(item.attempts, 1);
+        assert!(item.next_attempt_at.is_some());
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,359 @@
+//! Condition trigger engine
+//!
+//! Evaluates scheduler triggers (price crosses, large trades, keyword spikes,
+//! tx confirmations) and decides, with debounce and cooldown, whether they fire.
+//! Evaluation reads external sources; the firing decision is pure so it can be tested.
+
+use crate::db::Database;
+use crate::integrations::social_monitor_client::SocialMonitorClient;
+use crate::integrations::wallet_monitor_client::WalletMonitorClient;
+use crate::models::{SchedulerTrigger, TriggerCondition, TriggerState};
+use crate::modules::social_monitor::SocialMonitorModule;
+use crate::modules::wallet_monitor::WalletMonitorModule;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+
+/// Most large trades fetched per check
+const LARGE_TRADE_FETCH_LIMIT: usize = 50;
+
+/// Most confirmed transactions scanned per check
+const TX_CONFIRMED_FETCH_LIMIT: usize = 100;
+
+/// Result of one condition check
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    /// Whether the condition holds (level) or new matching events arrived (event)
+    pub met: bool,
+    /// Observed value (price, mention count, trade USD value)
+    pub value: Option<f64>,
+    /// Human-readable description of what was seen
+    pub detail: String,
+    /// Event watermark after this check (event conditions only)
+    pub cursor: Option<String>,
+}
+
+/// Check a condition against its source
+pub async fn evaluate(
+    condition: &TriggerCondition,
+    state: &TriggerState,
+    db: &Database,
+) -> Result<Evaluation, String> {
+    if let Some(module) = condition.required_module()
+        && !db.is_module_enabled(module).unwrap_or(false)
+    {
+        return Err(format!("Module '{}' is not enabled", module));
+    }
+
+    match condition {
+        TriggerCondition::PriceCross { source, chain, token_address, direction, threshold_usd } => {
+            let price = fetch_price(source, chain, token_address).await?;
+            let met = if direction == "above" { price >= *threshold_usd } else { price <= *threshold_usd };
+            Ok(Evaluation {
+                met,
+                value: Some(price),
+                detail: format!(
+                    "{} price ${} ({} threshold ${})",
+                    token_address, price, direction, threshold_usd
+                ),
+                cursor: None,
+            })
+        }
+        TriggerCondition::LargeTrade { min_usd, address, chain } => {
+            let client = WalletMonitorClient::new(&WalletMonitorModule::url_from_env());
+            let trades = client
+                .query_activity(&wallet_monitor_types::ActivityFilter {
+                    address: address.clone(),
+                    chain: chain.clone(),
+                    large_only: true,
+                    limit: Some(LARGE_TRADE_FETCH_LIMIT),
+                    ..Default::default()
+                })
+                .await?;
+
+            let last_seen = state.cursor.as_deref().and_then(|c| c.parse::<i64>().ok());
+            let newest_id = trades.iter().map(|t| t.id).max().or(last_seen);
+            let mut fresh: Vec<_> = trades
+                .iter()
+                .filter(|t| last_seen.is_some_and(|seen| t.id > seen))
+                .filter(|t| min_usd.is_none_or(|min| t.usd_value.unwrap_or(0.0) >= min))
+                .collect();
+            fresh.sort_by_key(|t| t.id);
+
+            let detail = match fresh.last() {
+                Some(t) => format!(
+                    "{} new large trade(s); latest {} {} ${:.2} on {} (tx {})",
+                    fresh.len(),
+                    t.activity_type,
+                    t.asset_symbol.as_deref().unwrap_or("?"),
+                    t.usd_value.unwrap_or(0.0),
+                    t.chain,
+                    t.tx_hash
+                ),
+                None => "no new large trades".to_string(),
+            };
+            Ok(Evaluation {
+                met: !fresh.is_empty(),
+                value: fresh.last().and_then(|t| t.usd_value),
+                detail,
+                cursor: newest_id.map(|id| id.to_string()),
+            })
+        }
+        TriggerCondition::KeywordSpike { keyword, window_minutes, min_mentions } => {
+            let client = SocialMonitorClient::new(&SocialMonitorModule::url_from_env());
+            let since = Utc::now() - Duration::minutes(*window_minutes as i64);
+            let tweets = client
+                .query_tweets(&social_monitor_types::TweetFilter {
+                    search_text: Some(keyword.clone()),
+                    since: Some(since.to_rfc3339()),
+                    limit: Some(crate::models::scheduler_trigger::MAX_KEYWORD_SPIKE_MENTIONS as usize),
+                    ..Default::default()
+                })
+                .await?;
+
+            let count = tweets.len() as u32;
+            Ok(Evaluation {
+                met: count >= *min_mentions,
+                value: Some(count as f64),
+                detail: format!(
+                    "'{}' mentioned in {} tweet(s) in the last {} minutes (threshold {})",
+                    keyword, count, window_minutes, min_mentions
+                ),
+                cursor: None,
+            })
+        }
+        TriggerCondition::TxConfirmed { tx_uuid, network } => {
+            let confirmed = db
+                .list_broadcasted_transactions(Some("confirmed"), network.as_deref(), None, Some(TX_CONFIRMED_FETCH_LIMIT))
+                .map_err(|e| format!("Failed to list transactions: {}", e))?;
+
+            if let Some(uuid) = tx_uuid {
+                return Ok(match confirmed.iter().find(|tx| &tx.uuid == uuid) {
+                    Some(tx) => Evaluation {
+                        met: true,
+                        value: None,
+                        detail: format!(
+                            "tx {} confirmed on {} ({})",
+                            tx.uuid,
+                            tx.network,
+                            tx.tx_hash.as_deref().unwrap_or("no hash")
+                        ),
+                        cursor: Some(uuid.clone()),
+                    },
+                    None => Evaluation {
+                        met: false,
+                        value: None,
+                        detail: format!("tx {} not confirmed yet", uuid),
+                        cursor: Some(uuid.clone()),
+                    },
+                });
+            }
+
+            let last_seen = state
+                .cursor
+                .as_deref()
+                .and_then(|c| DateTime::parse_from_rfc3339(c).ok())
+                .map(|dt| dt.with_timezone(&Utc));
+            let newest = confirmed.iter().filter_map(|tx| tx.confirmed_at).max().or(last_seen);
+            let fresh: Vec<_> = confirmed
+                .iter()
+                .filter(|tx| match (tx.confirmed_at, last_seen) {
+                    (Some(at), Some(seen)) => at > seen,
+                    _ => false,
+                })
+                .collect();
+
+            let detail = match fresh.iter().max_by_key(|tx| tx.confirmed_at) {
+                Some(tx) => format!(
+                    "{} tx(s) confirmed; latest {} on {} ({})",
+                    fresh.len(),
+                    tx.uuid,
+                    tx.network,
+                    tx.tx_hash.as_deref().unwrap_or("no hash")
+                ),
+                None => "no new confirmations".to_string(),
+            };
+            Ok(Evaluation {
+                met: !fresh.is_empty(),
+                value: None,
+                detail,
+                // An empty history still needs a watermark so the first confirmation fires
+                cursor: Some(newest.unwrap_or_else(Utc::now).to_rfc3339()),
+            })
+        }
+    }
+}
+
+/// Decide whether a trigger fires for an evaluation, and compute its next state.
+///
+/// Level conditions fire once per streak, after `confirm_checks` consecutive holds.
+/// Event conditions take a watermark on their first check without firing, and only
+/// consume events when they fire (events seen during a cooldown fire once it ends).
+pub fn decide(trigger: &SchedulerTrigger, eval: &Evaluation, now: DateTime<Utc>) -> (bool, TriggerState) {
+    let cooled_down = trigger
+        .last_fired_at
+        .as_deref()
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .is_none_or(|last| now.signed_duration_since(last).num_seconds() >= trigger.cooldown_secs);
+
+    let mut state = trigger.state.clone();
+    state.last_value = eval.value.or(state.last_value);
+
+    if trigger.condition.is_event() {
+        if state.cursor.is_none() && !trigger.condition.is_one_shot() {
+            state.cursor = eval.cursor.clone();
+            return (false, state);
+        }
+        let fire = eval.met && cooled_down;
+        if fire || !eval.met {
+            state.cursor = eval.cursor.clone();
+        }
+        return (fire, state);
+    }
+
+    if !eval.met {
+        state.streak = 0;
+        state.fired = false;
+        return (false, state);
+    }
+
+    state.streak = state.streak.saturating_add(1);
+    let fire = !state.fired && state.streak as i64 >= trigger.confirm_checks.max(1) && cooled_down;
+    if fire {
+        state.fired = true;
+    }
+    (fire, state)
+}
+
+/// Extracts a token's USD price from a price source's response body
+type PricePicker = fn(&Value, &str) -> Option<f64>;
+
+/// Current USD price of a token from DexScreener (most liquid pair) or GeckoTerminal
+async fn fetch_price(source: &str, chain: &str, token_address: &str) -> Result<f64, String> {
+    let client = crate::http::shared_client();
+    let (url, pick): (String, PricePicker) = match source {
+        "geckoterminal" => (
+            format!(
+                "https://api.geckoterminal.com/api/v2/simple/networks/{}/token_price/{}",
+                chain, token_address
+            ),
+            geckoterminal_price,
+        ),
+        _ => (
+            format!("https://api.dexscreener.com/tokens/v1/{}/{}", chain, token_address),
+            dexscreener_price,
+        ),
+    };
+
+    let body: Value = client
+        .get(&url)
+        .timeout(std::time::Duration::from_secs(15))
+        .send()
+        .await
+        .map_err(|e| format!("{} request failed: {}", source, e))?
+        .error_for_status()
+        .map_err(|e| format!("{} returned an error: {}", source, e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid {} response: {}", source, e))?;
+
+    pick(&body, token_address).ok_or_else(|| format!("{} has no price for {} on {}", source, token_address, chain))
+}
+
+fn dexscreener_price(body: &Value, _token_address: &str) -> Option<f64> {
+    body.as_array()?
+        .iter()
+        .filter_map(|pair| {
+            let price = pair.get("priceUsd")?.as_str()?.parse::<f64>().ok()?;
+            let liquidity = pair.pointer("/liquidity/usd").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            Some((liquidity, price))
+        })
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, price)| price)
+}
+
+fn geckoterminal_price(body: &Value, token_address: &str) -> Option<f64> {
+    let prices = body.pointer("/data/attributes/token_prices")?.as_object()?;
+    prices
+        .iter()
+        .find(|(addr, _)| addr.eq_ignore_ascii_case(token_address))
+        .or_else(|| prices.iter().next())
+        .and_then(|(_, v)| v.as_str().and_then(|s| s.parse::<f64>().ok()).or_else(|| v.as_f64()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trigger(condition: TriggerCondition, confirm_checks: i64, cooldown_secs: i64) -> SchedulerTrigger {
+        SchedulerTrigger {
+            id: 1,
+            name: "t".to_string(),
+            job_id: 1,
+            condition,
+            check_interval_secs: 60,
+            cooldown_secs,
+            confirm_checks,
+            enabled: true,
+            state: TriggerState::default(),
+            last_checked_at: None,
+            last_fired_at: None,
+            fire_count: 0,
+            last_error: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn eval(met: bool, cursor: Option<&str>) -> Evaluation {
+        Evaluation { met, value: Some(1.0), detail: String::new(), cursor: cursor.map(String::from) }
+    }
+
+    #[test]
+    fn test_level_condition_debounces_and_fires_once_per_streak() {
+        let price = TriggerCondition::PriceCross {
+            source: "dexscreener".to_string(),
+            chain: "base".to_string(),
+            token_address: "0xabc".to_string(),
+            direction: "above".to_string(),
+            threshold_usd: 1.0,
+        };
+        let mut t = trigger(price, 2, 0);
+        let now = Utc::now();
+
+        let (fire, state) = decide(&t, &eval(true, None), now);
+        assert!(!fire);
+        t.state = state;
+        let (fire, state) = decide(&t, &eval(true, None), now);
+        assert!(fire);
+        t.state = state;
+        let (fire, state) = decide(&t, &eval(true, None), now);
+        assert!(!fire, "fires only once while the condition keeps holding");
+        t.state = state;
+        let (_, state) = decide(&t, &eval(false, None), now);
+        assert_eq!(state.streak, 0);
+        assert!(!state.fired);
+    }
+
+    #[test]
+    fn test_event_condition_initializes_cursor_and_respects_cooldown() {
+        let trade = TriggerCondition::LargeTrade { min_usd: None, address: None, chain: None };
+        let mut t = trigger(trade, 1, 600);
+        let now = Utc::now();
+
+        let (fire, state) = decide(&t, &eval(true, Some("10")), now);
+        assert!(!fire, "first check only takes a watermark");
+        assert_eq!(state.cursor.as_deref(), Some("10"));
+        t.state = state;
+
+        t.last_fired_at = Some((now - Duration::seconds(60)).to_rfc3339());
+        let (fire, state) = decide(&t, &eval(true, Some("12")), now);
+        assert!(!fire);
+        assert_eq!(state.cursor.as_deref(), Some("10"), "events are kept until the cooldown ends");
+        t.state = state;
+
+        t.last_fired_at = Some((now - Duration::seconds(601)).to_rfc3339());
+        let (fire, state) = decide(&t, &eval(true, Some("12")), now);
+        assert!(fire);
+        assert_eq!(state.cursor.as_deref(), Some("12"));
+    }
+}
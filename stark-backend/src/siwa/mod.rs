@@ -1,3 +1,5 @@
 pub mod message;
+pub mod verify;
 
 pub use message::{build_siwa_message, SiwaMessageFields};
+pub use verify::recover_signer;
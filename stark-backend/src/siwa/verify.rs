@@ -0,0 +1,39 @@
+use ethers::core::types::Signature;
+use ethers::utils::hash_message;
+
+/// Recover the lowercase 0x address that produced an EIP-191 personal_sign signature.
+///
+/// Returns `None` for malformed hex or signatures that don't recover.
+pub fn recover_signer(message: &str, signature: &str) -> Option<String> {
+    let signature = signature.trim();
+    let sig_bytes = hex::decode(signature.strip_prefix("0x").unwrap_or(signature)).ok()?;
+    let sig = Signature::try_from(sig_bytes.as_slice()).ok()?;
+
+    let msg_hash = hash_message(message);
+    let recovered = sig.recover(msg_hash).ok()?;
+
+    Some(format!("{:?}", recovered).to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::signers::{LocalWallet, Signer};
+
+    #[tokio::test]
+    async fn test_recover_signer_roundtrip() {
+        let wallet: LocalWallet = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            .parse()
+            .unwrap();
+        let message = "link me";
+        let signature = wallet.sign_message(message).await.unwrap();
+        let expected = format!("{:?}", wallet.address()).to_lowercase();
+
+        assert_eq!(recover_signer(message, &format!("0x{}", signature)), Some(expected));
+        assert_ne!(
+            recover_signer("other message", &signature.to_string()),
+            Some(format!("{:?}", wallet.address()).to_lowercase())
+        );
+        assert_eq!(recover_signer(message, "0xnothex"), None);
+    }
+}
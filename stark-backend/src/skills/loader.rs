@@ -1,5 +1,5 @@
 use crate::skills::types::{Skill, SkillMetadata, SkillSource};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Parse a SKILL.md file content into a Skill
 pub fn parse_skill_file(content: &str, path: &str, source: SkillSource) -> Result<Skill, String> {
@@ -50,7 +50,60 @@ pub async fn load_skill_from_file(path: &Path, source: SkillSource) -> Result<Sk
         .await
         .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
 
-    parse_skill_file(&content, &path.to_string_lossy(), source)
+    let mut skill = parse_skill_file(&content, &path.to_string_lossy(), source)?;
+    skill.metadata.workflow = resolve_workflow_source(skill.metadata.workflow.take(), path).await;
+    Ok(skill)
+}
+
+/// Whether a frontmatter `workflow:` value names a sidecar file rather than holding RON
+pub fn is_workflow_file_reference(value: &str) -> bool {
+    let value = value.trim();
+    !value.contains('\n') && value.ends_with(".ron")
+}
+
+/// Sidecar looked up when the frontmatter declares no workflow:
+/// `swap.md` → `swap.workflow.ron`, `swap/SKILL.md` → `swap/workflow.ron`
+fn default_sidecar_path(skill_path: &Path) -> Option<PathBuf> {
+    let file_name = skill_path.file_name()?.to_string_lossy();
+    let dir = skill_path.parent()?;
+    if file_name.eq_ignore_ascii_case("SKILL.md") {
+        Some(dir.join("workflow.ron"))
+    } else {
+        let stem = skill_path.file_stem()?.to_string_lossy();
+        Some(dir.join(format!("{}.workflow.ron", stem)))
+    }
+}
+
+/// Turn the declared workflow into RON source: inline blocks pass through, file
+/// references are read from the skill's directory, and an undeclared workflow
+/// falls back to the default sidecar if one exists.
+async fn resolve_workflow_source(declared: Option<String>, skill_path: &Path) -> Option<String> {
+    let file = match declared {
+        Some(ref value) if is_workflow_file_reference(value) => {
+            let name = value.trim();
+            if name.contains('/') || name.contains('\\') || name.contains("..") {
+                log::warn!("Ignoring workflow '{}' for {}: must be a file next to the skill", name, skill_path.display());
+                return None;
+            }
+            skill_path.parent()?.join(name)
+        }
+        Some(source) => return Some(source),
+        None => {
+            let sidecar = default_sidecar_path(skill_path)?;
+            if !sidecar.is_file() {
+                return None;
+            }
+            sidecar
+        }
+    };
+
+    match tokio::fs::read_to_string(&file).await {
+        Ok(source) => Some(source),
+        Err(e) => {
+            log::warn!("Failed to read workflow {}: {}", file.display(), e);
+            None
+        }
+    }
 }
 
 /// Load all skills from a directory
@@ -144,6 +197,8 @@ fn serde_yaml_parse(yaml: &str) -> Result<SkillMetadata, String> {
         default: None,
     };
     let mut current_api_key_name = String::new();
+    let mut in_workflow_block = false;
+    let mut workflow_lines: Vec<String> = Vec::new();
     let mut current_api_key = crate::skills::types::SkillApiKey {
         description: String::new(),
         secret: true,
@@ -158,6 +213,15 @@ fn serde_yaml_parse(yaml: &str) -> Result<SkillMetadata, String> {
         // Check indentation level
         let indent = line.len() - line.trim_start().len();
 
+        // `workflow: |` block: keep the indented RON verbatim (minus the block indent)
+        if in_workflow_block {
+            if indent >= 2 {
+                workflow_lines.push(line[2..].to_string());
+                continue;
+            }
+            in_workflow_block = false;
+        }
+
         if indent == 0 {
             // Flush pending argument/api_key before switching sections
             if in_arguments && !current_arg_name.is_empty() {
@@ -205,6 +269,14 @@ fn serde_yaml_parse(yaml: &str) -> Result<SkillMetadata, String> {
                             metadata.tags = parse_inline_list(value);
                         }
                     }
+                    "workflow" => {
+                        // Either a block (`|`) or the name of a sidecar file
+                        if value == "|" || value == "|-" {
+                            in_workflow_block = true;
+                        } else if !value.is_empty() {
+                            metadata.workflow = Some(unquote(value));
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -281,6 +353,9 @@ fn serde_yaml_parse(yaml: &str) -> Result<SkillMetadata, String> {
     if in_api_keys && !current_api_key_name.is_empty() {
         metadata.requires_api_keys.insert(current_api_key_name, current_api_key);
     }
+    if !workflow_lines.is_empty() {
+        metadata.workflow = Some(workflow_lines.join("\n"));
+    }
 
     Ok(metadata)
 }
@@ -343,4 +418,44 @@ You are a code reviewer. Review the code at {{path}} and provide feedback.
         let result = parse_skill_file(content, "/test/SKILL.md", SkillSource::Bundled);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_parse_inline_workflow() {
+        let content = r#"---
+name: greet
+description: Look up a user and greet them
+requires_tools: [lookup_user, say_to_user]
+workflow: |
+  (
+      inputs: [(name: "user")],
+      steps: [
+          (id: "profile", action: Tool(tool: "lookup_user", args: {"name": "{{user}}"})),
+      ],
+  )
+tags: [demo]
+---
+Greet the user.
+"#;
+
+        let skill = parse_skill_file(content, "/test/greet.md", SkillSource::Bundled).unwrap();
+        let workflow = skill.metadata.workflow.unwrap();
+        assert!(workflow.starts_with("(\n    inputs:"));
+        assert_eq!(skill.metadata.tags, vec!["demo"]);
+        let definition = crate::workflows::WorkflowDefinition::parse(&workflow, &skill.metadata.requires_tools).unwrap();
+        assert_eq!(definition.steps[0].id, "profile");
+    }
+
+    #[tokio::test]
+    async fn test_swap_skill_loads_sidecar_workflow() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().join("skills/swap.md");
+        let skill = load_skill_from_file(&path, SkillSource::Bundled).await.unwrap();
+        let workflow = skill.metadata.workflow.expect("swap.workflow.ron sidecar");
+        let definition = crate::workflows::WorkflowDefinition::parse(&workflow, &skill.metadata.requires_tools).unwrap();
+        let position = |id: &str| definition.steps.iter().position(|s| s.id == id).unwrap();
+        // Nothing is broadcast before the user confirms
+        for step in ["wrap_broadcast", "approve_broadcast", "swap_tx"] {
+            assert!(position("confirm") < position(step), "confirm must precede {}", step);
+        }
+        assert!(!is_workflow_file_reference(&workflow));
+    }
 }
@@ -1,6 +1,8 @@
 use crate::db::Database;
 use crate::skills::types::{DbSkill, DbSkillScript, Skill, SkillSource};
+use crate::skills::loader::is_workflow_file_reference;
 use crate::skills::zip_parser::{parse_skill_md, parse_skill_zip, ParsedSkill};
+use crate::workflows::WorkflowDefinition;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -119,6 +121,7 @@ impl SkillRegistry {
             tags: metadata.tags,
             subagent_type: metadata.subagent_type,
             requires_api_keys: metadata.requires_api_keys,
+            workflow: metadata.workflow,
             scripts: Vec::new(),
         };
 
@@ -143,6 +146,7 @@ impl SkillRegistry {
             tags: metadata.tags,
             subagent_type: metadata.subagent_type,
             requires_api_keys: metadata.requires_api_keys,
+            workflow: metadata.workflow,
             scripts: Vec::new(), // No scripts for plain markdown
         };
 
@@ -163,6 +167,7 @@ impl SkillRegistry {
             homepage: parsed.homepage,
             metadata: parsed.metadata,
             enabled: true,
+            workflow: validated_workflow(&parsed.name, parsed.workflow, &parsed.requires_tools),
             requires_tools: parsed.requires_tools,
             requires_binaries: parsed.requires_binaries,
             arguments: parsed.arguments,
@@ -211,6 +216,7 @@ impl SkillRegistry {
             homepage: parsed.homepage,
             metadata: parsed.metadata,
             enabled: true,
+            workflow: validated_workflow(&parsed.name, parsed.workflow, &parsed.requires_tools),
             requires_tools: parsed.requires_tools,
             requires_binaries: parsed.requires_binaries,
             arguments: parsed.arguments,
@@ -341,6 +347,11 @@ impl SkillRegistry {
             tags: skill.metadata.tags.clone(),
             subagent_type: skill.metadata.subagent_type.clone(),
             requires_api_keys: skill.metadata.requires_api_keys.clone(),
+            workflow: validated_workflow(
+                &skill.metadata.name,
+                skill.metadata.workflow.clone(),
+                &skill.metadata.requires_tools,
+            ),
             created_at: now.clone(),
             updated_at: now,
         };
@@ -388,6 +399,26 @@ impl SkillRegistry {
     }
 }
 
+/// Keep a skill's workflow only if it parses and validates. A broken workflow is logged
+/// and dropped so the skill still loads with its prose instructions.
+fn validated_workflow(skill_name: &str, workflow: Option<String>, requires_tools: &[String]) -> Option<String> {
+    let source = workflow?;
+    if is_workflow_file_reference(&source) {
+        log::warn!(
+            "Skill '{}': workflow file '{}' is only resolved for skills loaded from disk or a ZIP",
+            skill_name, source.trim()
+        );
+        return None;
+    }
+    match WorkflowDefinition::parse(&source, requires_tools) {
+        Ok(_) => Some(source),
+        Err(e) => {
+            log::error!("Skill '{}': dropping workflow: {}", skill_name, e);
+            None
+        }
+    }
+}
+
 /// Create a default skill registry with standard paths
 /// Uses config::skills_dir() so paths are stable regardless of CWD
 pub fn create_default_registry(db: Arc<Database>) -> SkillRegistry {
@@ -89,6 +89,9 @@ pub struct SkillMetadata {
     pub subagent_type: Option<String>,
     #[serde(default)]
     pub requires_api_keys: HashMap<String, SkillApiKey>,
+    /// Workflow RON source (inline block or resolved sidecar file), see `crate::workflows`
+    #[serde(default)]
+    pub workflow: Option<String>,
 }
 
 fn default_version() -> String {
@@ -110,6 +113,7 @@ impl Default for SkillMetadata {
             metadata: None,
             subagent_type: None,
             requires_api_keys: HashMap::new(),
+            workflow: None,
         }
     }
 }
@@ -206,6 +210,8 @@ pub struct DbSkill {
     pub tags: Vec<String>,
     pub subagent_type: Option<String>,
     pub requires_api_keys: HashMap<String, SkillApiKey>,
+    /// Workflow RON source, when the skill declares one
+    pub workflow: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -227,6 +233,7 @@ impl DbSkill {
                 metadata: self.metadata,
                 subagent_type: self.subagent_type,
                 requires_api_keys: self.requires_api_keys,
+                workflow: self.workflow,
             },
             prompt_template: self.body,
             source: SkillSource::Managed, // All DB skills are "managed"
@@ -1,3 +1,4 @@
+use crate::skills::loader::is_workflow_file_reference;
 use crate::skills::types::{SkillApiKey, SkillArgument, SkillMetadata};
 use std::collections::HashMap;
 use std::io::{Cursor, Read};
@@ -19,6 +20,8 @@ pub struct ParsedSkill {
     pub tags: Vec<String>,
     pub subagent_type: Option<String>,
     pub requires_api_keys: HashMap<String, SkillApiKey>,
+    /// Workflow RON source (inline in SKILL.md or a `.ron` file in the archive)
+    pub workflow: Option<String>,
     pub scripts: Vec<ParsedScript>,
 }
 
@@ -104,13 +107,44 @@ pub fn parse_skill_zip(data: &[u8]) -> Result<ParsedSkill, String> {
     } else {
         return Err("ZIP file must contain a SKILL.md file".to_string());
     };
-    let (metadata, body) = parse_skill_md(&skill_md)?;
+    let (mut metadata, body) = parse_skill_md(&skill_md)?;
 
     // Third pass: collect scripts
     let base_dir = skill_md_path.as_ref()
         .and_then(|p| p.rsplit('/').nth(1))
         .unwrap_or("");
 
+    // Resolve a sidecar workflow: the file named in frontmatter, else `workflow.ron`
+    let workflow_file = match metadata.workflow {
+        Some(ref value) if is_workflow_file_reference(value) => Some(value.trim().to_string()),
+        Some(_) => None,
+        None => Some("workflow.ron".to_string()),
+    };
+    if let Some(file_name) = workflow_file {
+        let declared = metadata.workflow.take().is_some();
+        let entry = (0..archive.len()).find_map(|i| {
+            let name = archive.by_index(i).ok()?.name().to_string();
+            let normalized = normalize_zip_path(&name);
+            let in_skill_dir = normalized == file_name
+                || (!base_dir.is_empty() && normalized == format!("{}/{}", base_dir, file_name));
+            in_skill_dir.then_some(name)
+        });
+        match entry {
+            Some(name) => {
+                let mut content = String::new();
+                archive.by_name(&name)
+                    .map_err(|e| format!("Failed to read {}: {}", file_name, e))?
+                    .read_to_string(&mut content)
+                    .map_err(|e| format!("Failed to read {}: {}", file_name, e))?;
+                metadata.workflow = Some(content);
+            }
+            None if declared => {
+                return Err(format!("Workflow file '{}' not found in ZIP", file_name));
+            }
+            None => {}
+        }
+    }
+
     for i in 0..archive.len() {
         let mut file = archive.by_index(i)
             .map_err(|e| format!("Failed to read ZIP entry: {}", e))?;
@@ -167,6 +201,7 @@ pub fn parse_skill_zip(data: &[u8]) -> Result<ParsedSkill, String> {
         tags: metadata.tags,
         subagent_type: metadata.subagent_type,
         requires_api_keys: metadata.requires_api_keys,
+        workflow: metadata.workflow,
         scripts,
     })
 }
@@ -219,6 +254,8 @@ fn parse_yaml_frontmatter(yaml: &str) -> Result<SkillMetadata, String> {
         default: None,
     };
     let mut current_api_key_name = String::new();
+    let mut in_workflow_block = false;
+    let mut workflow_lines: Vec<String> = Vec::new();
     let mut current_api_key = SkillApiKey {
         description: String::new(),
         secret: true,
@@ -233,6 +270,15 @@ fn parse_yaml_frontmatter(yaml: &str) -> Result<SkillMetadata, String> {
         // Check indentation level
         let indent = line.len() - line.trim_start().len();
 
+        // `workflow: |` block: keep the indented RON verbatim (minus the block indent)
+        if in_workflow_block {
+            if indent >= 2 {
+                workflow_lines.push(line[2..].to_string());
+                continue;
+            }
+            in_workflow_block = false;
+        }
+
         if indent == 0 {
             // Flush pending argument/api_key before switching sections
             if in_arguments && !current_arg_name.is_empty() {
@@ -279,6 +325,14 @@ fn parse_yaml_frontmatter(yaml: &str) -> Result<SkillMetadata, String> {
                             metadata.tags = parse_inline_list(value);
                         }
                     }
+                    "workflow" => {
+                        // Either a block (`|`) or the name of a sidecar file
+                        if value == "|" || value == "|-" {
+                            in_workflow_block = true;
+                        } else if !value.is_empty() {
+                            metadata.workflow = Some(unquote(value));
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -355,6 +409,9 @@ fn parse_yaml_frontmatter(yaml: &str) -> Result<SkillMetadata, String> {
     if in_api_keys && !current_api_key_name.is_empty() {
         metadata.requires_api_keys.insert(current_api_key_name, current_api_key);
     }
+    if !workflow_lines.is_empty() {
+        metadata.workflow = Some(workflow_lines.join("\n"));
+    }
 
     Ok(metadata)
 }
@@ -4,6 +4,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
 
 use super::span::{Span, SpanStatus, SpanType};
 
@@ -25,6 +26,12 @@ pub struct TimelineEntry {
     pub duration_ms: Option<u64>,
     pub summary: String,
     pub attributes: Value,
+    /// Attempt within the rollout (0-indexed)
+    #[serde(default)]
+    pub attempt_idx: u32,
+    /// Error message if the span failed
+    #[serde(default)]
+    pub error: Option<String>,
 }
 
 /// A chronologically ordered timeline of execution events.
@@ -76,6 +83,8 @@ impl Adapter<Span, Timeline> for SpansToTimeline {
                     duration_ms: span.duration_ms,
                     summary,
                     attributes: span.attributes.clone(),
+                    attempt_idx: span.attempt_idx,
+                    error: span.error.clone(),
                 }
             })
             .collect();
@@ -108,6 +117,54 @@ impl Adapter<Span, Timeline> for SpansToTimeline {
     }
 }
 
+/// Group spans by rollout and convert each group to a timeline, oldest first.
+pub fn spans_to_timelines(spans: Vec<Span>) -> Vec<Timeline> {
+    let mut by_rollout: BTreeMap<String, Vec<Span>> = BTreeMap::new();
+    for span in spans {
+        by_rollout.entry(span.rollout_id.clone()).or_default().push(span);
+    }
+    let mut timelines: Vec<Timeline> = by_rollout
+        .values()
+        .map(|spans| SpansToTimeline.transform(spans))
+        .collect();
+    timelines.sort_by_key(|t| t.started_at);
+    timelines
+}
+
+/// Rebuilds spans from timeline entries (e.g. an imported transcript) so they
+/// can be persisted under a new session. Span IDs are regenerated.
+pub struct TimelineToSpans {
+    pub session_id: i64,
+    pub rollout_id: String,
+}
+
+impl Adapter<TimelineEntry, Vec<Span>> for TimelineToSpans {
+    fn transform(&self, entries: &[TimelineEntry]) -> Vec<Span> {
+        entries
+            .iter()
+            .map(|entry| {
+                let mut span = Span::new(
+                    entry.sequence_id,
+                    self.rollout_id.clone(),
+                    self.session_id,
+                    entry.attempt_idx,
+                    entry.span_type,
+                    entry.name.clone(),
+                );
+                span.status = entry.status;
+                span.started_at = entry.timestamp;
+                span.duration_ms = entry.duration_ms;
+                span.completed_at = entry
+                    .duration_ms
+                    .map(|d| entry.timestamp + chrono::Duration::milliseconds(d as i64));
+                span.attributes = entry.attributes.clone();
+                span.error = entry.error.clone();
+                span
+            })
+            .collect()
+    }
+}
+
 // ─── Summary ───────────────────────────────────────────────────────
 
 /// An aggregated summary of an execution.
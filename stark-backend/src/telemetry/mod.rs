@@ -1,7 +1,8 @@
 //! Agent-Lightning inspired telemetry system.
 //!
 //! Provides structured execution traces, rollout/attempt lifecycle with retry,
-//! reward signals, watchdog enforcement, resource versioning, and execution replay.
+//! reward signals, watchdog enforcement, resource versioning, and execution replay
+//! (see `replay` for deterministic re-execution of recorded sessions).
 //!
 //! Philosophy: "Agents emit spans, algorithms consume spans to improve resources."
 
@@ -13,6 +14,7 @@ pub mod watchdog;
 pub mod resource_version;
 pub mod adapter;
 pub mod store;
+pub mod replay;
 
 // Re-export key types for convenience
 pub use span::{Span, SpanCollector, SpanGuard, SpanStatus, SpanType};
@@ -21,5 +23,14 @@ pub use emitter::{clear_active_collector, emit_annotation, set_active_collector}
 pub use reward::RewardEmitter;
 pub use watchdog::{Watchdog, WatchdogConfig, WatchdogError};
 pub use resource_version::{Resource, ResourceBundle, ResourceManager, ResourceType};
-pub use adapter::{Adapter, ExecutionSummary, SpansToSummary, SpansToTimeline, SpansToTriplets, Timeline, Triplet};
+pub use adapter::{
+    spans_to_timelines, Adapter, ExecutionSummary, SpansToSummary, SpansToTimeline,
+    SpansToTriplets, Timeline, TimelineToSpans, Triplet,
+};
+pub use replay::{
+    record_ai_response, record_parallel_tool_result, record_text_response, record_tool_result,
+    ReplayRecording,
+};
+#[cfg(test)]
+pub use replay::{ReplayStep, ToolStubs};
 pub use store::{RetentionPolicy, RewardStats, TelemetryStore};
@@ -0,0 +1,383 @@
+//! Deterministic session replay.
+//!
+//! During dispatch every AI response and every tool execution result is
+//! recorded as a span (`LlmCall` named `ai_response`, `ToolCall` named after
+//! the tool) carrying the full payload in its attributes. A `ReplayRecording`
+//! rebuilds the ordered steps from those spans — or from the timelines of an
+//! exported transcript — and is served by the telemetry API as a fixture.
+//! In tests it turns into a `MockAiClient` plus stubbed tool results, so the
+//! dispatcher can be re-run offline; diffing the replayed trace against the
+//! recording shows where behavior changed.
+
+#[cfg(test)]
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+#[cfg(test)]
+use std::collections::{HashMap, VecDeque};
+
+use crate::ai::{AiError, AiResponse};
+#[cfg(test)]
+use crate::ai::MockAiClient;
+use crate::tools::ToolResult;
+use crate::x402::X402PaymentInfo;
+
+use super::adapter::{spans_to_timelines, Timeline};
+use super::emitter::with_active_collector;
+use super::span::{Span, SpanType};
+
+/// Span name used for recorded AI responses.
+pub const AI_RESPONSE_SPAN: &str = "ai_response";
+
+// ─── Recording ─────────────────────────────────────────────────────
+
+/// Record an AI response (or error) on the active collector.
+pub fn record_ai_response(result: &Result<AiResponse, AiError>, duration_ms: u64) {
+    with_active_collector(|collector| {
+        let mut span = collector.start_span(SpanType::LlmCall, AI_RESPONSE_SPAN);
+        match result {
+            Ok(response) => {
+                span.attributes = json!({
+                    "ai_response": response,
+                    "tool_call_count": response.tool_calls.len(),
+                });
+                span.succeed();
+            }
+            Err(e) => {
+                span.attributes = json!({ "status_code": e.status_code });
+                span.fail(e.message.clone());
+            }
+        }
+        backdate(&mut span, duration_ms);
+        collector.record(span);
+    });
+}
+
+/// Record a plain text generation (no tool calls) on the active collector.
+pub fn record_text_response(result: &Result<(String, Option<X402PaymentInfo>), String>, duration_ms: u64) {
+    let result = match result {
+        Ok((content, payment)) => {
+            Ok(AiResponse::text(content.clone()).with_x402_payment(payment.clone()))
+        }
+        Err(e) => Err(AiError::new(e.clone())),
+    };
+    record_ai_response(&result, duration_ms);
+}
+
+/// Record a tool execution result on the active collector.
+pub fn record_tool_result(tool_name: &str, arguments: &Value, result: &ToolResult, duration_ms: u64) {
+    record_tool_span(tool_name, arguments, result, duration_ms, None);
+}
+
+/// Record the result of a call that ran concurrently with others from the same AI
+/// response. Callers record in call order once the group finishes, so span sequence
+/// (and therefore replay order) does not depend on which call finished first.
+pub fn record_parallel_tool_result(
+    tool_name: &str,
+    arguments: &Value,
+    result: &ToolResult,
+    duration_ms: u64,
+    position: usize,
+    group_size: usize,
+) {
+    record_tool_span(
+        tool_name,
+        arguments,
+        result,
+        duration_ms,
+        Some(json!({ "position": position, "group_size": group_size })),
+    );
+}
+
+fn record_tool_span(tool_name: &str, arguments: &Value, result: &ToolResult, duration_ms: u64, parallel: Option<Value>) {
+    with_active_collector(|collector| {
+        let mut span = collector.start_span(SpanType::ToolCall, tool_name);
+        span.attributes = json!({
+            "tool_name": tool_name,
+            "arguments": arguments,
+            "tool_result": result,
+        });
+        if let Some(parallel) = parallel {
+            span.attributes["parallel"] = parallel;
+        }
+        if result.success {
+            span.succeed();
+        } else {
+            span.fail(result.error.clone().unwrap_or_else(|| result.content.clone()));
+        }
+        backdate(&mut span, duration_ms);
+        collector.record(span);
+    });
+}
+
+/// Spans are recorded after the fact; shift the start back by the measured duration.
+fn backdate(span: &mut Span, duration_ms: u64) {
+    span.started_at -= chrono::Duration::milliseconds(duration_ms as i64);
+    span.duration_ms = Some(duration_ms);
+}
+
+// ─── Recording → Replay ────────────────────────────────────────────
+
+/// A single recorded step of a session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReplayStep {
+    AiResponse {
+        response: Option<AiResponse>,
+        error: Option<String>,
+    },
+    ToolResult {
+        tool_name: String,
+        arguments: Value,
+        result: ToolResult,
+    },
+}
+
+#[cfg(test)]
+impl ReplayStep {
+    /// Short human-readable label for summaries.
+    pub fn label(&self) -> String {
+        match self {
+            ReplayStep::AiResponse { response: Some(r), .. } if !r.tool_calls.is_empty() => {
+                let names: Vec<&str> = r.tool_calls.iter().map(|tc| tc.name.as_str()).collect();
+                format!("AI → [{}]", names.join(", "))
+            }
+            ReplayStep::AiResponse { response: Some(_), .. } => "AI → text".to_string(),
+            ReplayStep::AiResponse { error, .. } => {
+                format!("AI error: {}", error.as_deref().unwrap_or("unknown"))
+            }
+            ReplayStep::ToolResult { tool_name, result, .. } => {
+                format!("Tool {} ({})", tool_name, if result.success { "ok" } else { "failed" })
+            }
+        }
+    }
+}
+
+/// The ordered AI responses and tool results of a recorded session.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplayRecording {
+    pub steps: Vec<ReplayStep>,
+}
+
+impl ReplayRecording {
+    /// Build a recording from persisted spans (e.g. `get_spans_by_session`).
+    pub fn from_spans(spans: Vec<Span>) -> Self {
+        Self::from_timelines(&spans_to_timelines(spans))
+    }
+
+    /// Build a recording from timelines (e.g. the timelines of an exported transcript).
+    pub fn from_timelines(timelines: &[Timeline]) -> Self {
+        let mut steps = Vec::new();
+        for timeline in timelines {
+            for entry in &timeline.entries {
+                match entry.span_type {
+                    SpanType::LlmCall if entry.name == AI_RESPONSE_SPAN => {
+                        let response = entry
+                            .attributes
+                            .get("ai_response")
+                            .and_then(|v| serde_json::from_value::<AiResponse>(v.clone()).ok());
+                        let error = if response.is_none() {
+                            Some(entry.error.clone().unwrap_or_else(|| "Unknown error".to_string()))
+                        } else {
+                            None
+                        };
+                        steps.push(ReplayStep::AiResponse { response, error });
+                    }
+                    SpanType::ToolCall => {
+                        let Some(result) = entry
+                            .attributes
+                            .get("tool_result")
+                            .and_then(|v| serde_json::from_value::<ToolResult>(v.clone()).ok())
+                        else {
+                            continue;
+                        };
+                        steps.push(ReplayStep::ToolResult {
+                            tool_name: entry.name.clone(),
+                            arguments: entry.attributes.get("arguments").cloned().unwrap_or(Value::Null),
+                            result,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Self { steps }
+    }
+}
+
+// ─── Test harness ──────────────────────────────────────────────────
+
+#[cfg(test)]
+impl ReplayRecording {
+    /// Number of recorded AI responses.
+    pub fn ai_response_count(&self) -> usize {
+        self.steps.iter().filter(|s| matches!(s, ReplayStep::AiResponse { .. })).count()
+    }
+
+    /// Number of recorded tool results.
+    pub fn tool_result_count(&self) -> usize {
+        self.steps.iter().filter(|s| matches!(s, ReplayStep::ToolResult { .. })).count()
+    }
+
+    /// Mock AI client that returns the recorded responses in order.
+    pub fn mock_client(&self) -> MockAiClient {
+        let responses = self
+            .steps
+            .iter()
+            .filter_map(|step| match step {
+                ReplayStep::AiResponse { response: Some(r), .. } => Some(Ok(r.clone())),
+                ReplayStep::AiResponse { error, .. } => Some(Err(AiError::new(
+                    error.clone().unwrap_or_else(|| "Unknown error".to_string()),
+                ))),
+                ReplayStep::ToolResult { .. } => None,
+            })
+            .collect();
+        MockAiClient::new(responses)
+    }
+
+    /// Stubbed tool results, returned per tool in recorded order.
+    pub fn tool_stubs(&self) -> ToolStubs {
+        let mut results: HashMap<String, VecDeque<ToolResult>> = HashMap::new();
+        for step in &self.steps {
+            if let ReplayStep::ToolResult { tool_name, result, .. } = step {
+                results.entry(tool_name.clone()).or_default().push_back(result.clone());
+            }
+        }
+        ToolStubs { results: Mutex::new(results) }
+    }
+
+    /// Compare this (expected) recording against a replayed one, step by step.
+    pub fn diff(&self, actual: &ReplayRecording) -> TraceDiff {
+        let mut matched_steps = 0;
+        let mut divergences = Vec::new();
+
+        for index in 0..self.steps.len().max(actual.steps.len()) {
+            let expected = self.steps.get(index);
+            let replayed = actual.steps.get(index);
+            let reason = match (expected, replayed) {
+                (Some(e), Some(a)) => step_mismatch(e, a),
+                (Some(_), None) => Some("step missing from replay".to_string()),
+                (None, Some(_)) => Some("unexpected extra step in replay".to_string()),
+                (None, None) => None,
+            };
+            match reason {
+                Some(reason) => divergences.push(TraceDivergence {
+                    index,
+                    expected: expected.cloned(),
+                    actual: replayed.cloned(),
+                    reason,
+                }),
+                None => matched_steps += 1,
+            }
+        }
+
+        TraceDiff { matched_steps, divergences }
+    }
+}
+
+/// Describe why two steps differ (None if they match). Tool call IDs are ignored.
+#[cfg(test)]
+fn step_mismatch(expected: &ReplayStep, actual: &ReplayStep) -> Option<String> {
+    match (expected, actual) {
+        (
+            ReplayStep::AiResponse { response: er, error: ee },
+            ReplayStep::AiResponse { response: ar, error: ae },
+        ) => {
+            if ee != ae {
+                return Some(format!("AI error differs: {:?} vs {:?}", ee, ae));
+            }
+            let (Some(er), Some(ar)) = (er, ar) else {
+                return None;
+            };
+            if er.content.trim() != ar.content.trim() {
+                return Some("AI response content differs".to_string());
+            }
+            let calls = |r: &AiResponse| -> Vec<(String, Value)> {
+                r.tool_calls.iter().map(|tc| (tc.name.clone(), tc.arguments.clone())).collect()
+            };
+            if calls(er) != calls(ar) {
+                return Some("AI tool calls differ".to_string());
+            }
+            None
+        }
+        (
+            ReplayStep::ToolResult { tool_name: en, arguments: ea, result: er },
+            ReplayStep::ToolResult { tool_name: an, arguments: aa, result: ar },
+        ) => {
+            if en != an {
+                return Some(format!("expected tool {}, replay ran {}", en, an));
+            }
+            if ea != aa {
+                return Some(format!("{} called with different arguments", en));
+            }
+            if er.success != ar.success {
+                return Some(format!("{} success differs: {} vs {}", en, er.success, ar.success));
+            }
+            if er.content != ar.content {
+                return Some(format!("{} result content differs", en));
+            }
+            None
+        }
+        (e, a) => Some(format!("expected {}, replay produced {}", e.label(), a.label())),
+    }
+}
+
+/// Recorded tool results handed out in order, keyed by tool name.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct ToolStubs {
+    results: Mutex<HashMap<String, VecDeque<ToolResult>>>,
+}
+
+#[cfg(test)]
+impl ToolStubs {
+    /// Pop the next recorded result for a tool (None once exhausted).
+    pub fn next_result(&self, tool_name: &str) -> Option<ToolResult> {
+        self.results.lock().get_mut(tool_name).and_then(|q| q.pop_front())
+    }
+
+    /// Number of recorded results not yet consumed.
+    pub fn remaining(&self) -> usize {
+        self.results.lock().values().map(|q| q.len()).sum()
+    }
+}
+
+// ─── Diff ──────────────────────────────────────────────────────────
+
+/// A step where the replay diverged from the recording.
+#[cfg(test)]
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceDivergence {
+    pub index: usize,
+    pub expected: Option<ReplayStep>,
+    pub actual: Option<ReplayStep>,
+    pub reason: String,
+}
+
+/// Result of diffing a replayed trace against the original recording.
+#[cfg(test)]
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceDiff {
+    pub matched_steps: usize,
+    pub divergences: Vec<TraceDivergence>,
+}
+
+#[cfg(test)]
+impl TraceDiff {
+    pub fn is_identical(&self) -> bool {
+        self.divergences.is_empty()
+    }
+
+    /// One-line summary, naming the first divergence.
+    pub fn summary(&self) -> String {
+        match self.divergences.first() {
+            None => format!("Replay identical ({} steps)", self.matched_steps),
+            Some(first) => format!(
+                "{} divergence(s), first at step {}: {}",
+                self.divergences.len(),
+                first.index,
+                first.reason
+            ),
+        }
+    }
+}
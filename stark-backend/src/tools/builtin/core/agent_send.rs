@@ -1,6 +1,8 @@
+use crate::channels::attachments::{self, Attachment};
+use crate::channels::types::ChannelType;
 use crate::tools::registry::Tool;
 use crate::tools::types::{
-    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+    ChannelOutputType, PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
 };
 use async_trait::async_trait;
 use serde::Deserialize;
@@ -64,6 +66,23 @@ impl AgentSendTool {
             },
         );
 
+        properties.insert(
+            "files".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "Optional workspace file paths to upload after the message (images are sent as photos where supported)".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "Path relative to the workspace directory".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
         AgentSendTool {
             definition: ToolDefinition {
                 name: "agent_send".to_string(),
@@ -92,6 +111,8 @@ struct AgentSendParams {
     message: String,
     reply_to: Option<String>,
     platform: Option<String>,
+    #[serde(default)]
+    files: Vec<String>,
 }
 
 #[async_trait]
@@ -127,9 +148,22 @@ impl Tool for AgentSendTool {
             }
         });
 
+        // Resolve files up front so a bad path fails before anything is sent
+        let workspace = context
+            .workspace_dir
+            .clone()
+            .unwrap_or_else(crate::config::workspace_dir);
+        let mut files = Vec::new();
+        for path in &params.files {
+            match Attachment::from_workspace_file(&workspace, path) {
+                Ok(file) => files.push(file),
+                Err(e) => return ToolResult::error(format!("Cannot attach '{}': {}", path, e)),
+            }
+        }
+
         // For now, we'll implement a simple version that uses HTTP APIs directly
         // In a full implementation, this would integrate with the ChannelManager
-        match platform.as_str() {
+        let mut result = match platform.as_str() {
             "telegram" => {
                 self.send_telegram(&params, context).await
             }
@@ -142,11 +176,125 @@ impl Tool for AgentSendTool {
             other => {
                 ToolResult::error(format!("Unsupported platform: {}. Supported: telegram, discord, slack", other))
             }
+        };
+
+        if result.success
+            && !files.is_empty()
+            && let Some(channel_type) = ChannelType::from_str(&platform)
+        {
+            let notes = self.send_files(channel_type, &params, files, context).await;
+            result.content = format!("{}\n{}", result.content, notes.join("\n"));
         }
+        result
     }
 }
 
 impl AgentSendTool {
+    /// Upload files after the text message, within the platform's size limit.
+    /// Returns one status line per file.
+    async fn send_files(
+        &self,
+        channel_type: ChannelType,
+        params: &AgentSendParams,
+        files: Vec<Attachment>,
+        context: &ToolContext,
+    ) -> Vec<String> {
+        let (uploadable, mut notes) = attachments::split_for_upload(files, channel_type.as_str(), ChannelOutputType::TextOnly);
+        for file in uploadable {
+            let outcome = match channel_type {
+                ChannelType::Telegram => self.upload_telegram_file(params, &file, context).await,
+                ChannelType::Discord => self.upload_discord_file(params, &file, context).await,
+                ChannelType::Slack => match context.find_channel_bot_token("slack", "slack_bot_token") {
+                    Some(token) => {
+                        attachments::upload_slack_file(
+                            &context.http_client(),
+                            &token,
+                            &params.channel,
+                            params.reply_to.as_deref(),
+                            &file,
+                        )
+                        .await
+                    }
+                    None => Err("Slack bot token not available".to_string()),
+                },
+                _ => Err(format!("{} does not support file uploads", channel_type.display_name())),
+            };
+            match outcome {
+                Ok(()) => notes.push(format!("📎 Sent {}", file.file_name)),
+                Err(e) => notes.push(format!("📎 Failed to send {}: {}", file.file_name, e)),
+            }
+        }
+        notes
+    }
+
+    async fn upload_telegram_file(
+        &self,
+        params: &AgentSendParams,
+        file: &Attachment,
+        context: &ToolContext,
+    ) -> Result<(), String> {
+        let bot_token = context
+            .find_channel_bot_token("telegram", "telegram_bot_token")
+            .ok_or_else(|| "Telegram bot token not available".to_string())?;
+        let (method, field) = if file.is_telegram_photo() {
+            ("sendPhoto", "photo")
+        } else {
+            ("sendDocument", "document")
+        };
+        let url = format!("https://api.telegram.org/bot{}/{}", bot_token, method);
+
+        let mut form = reqwest::multipart::Form::new()
+            .text("chat_id", params.channel.clone())
+            .part(field, file_part(file).await?);
+        if let Some(ref reply_to) = params.reply_to {
+            form = form.text("reply_to_message_id", reply_to.clone());
+        }
+
+        let response = context
+            .http_client()
+            .post(&url)
+            .multipart(form)
+            .send()
+            .await
+            // The URL contains the bot token
+            .map_err(|e| format!("Upload failed: {}", e.without_url()))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(format!("Telegram API error ({}): {}", status, response.text().await.unwrap_or_default()));
+        }
+        Ok(())
+    }
+
+    async fn upload_discord_file(
+        &self,
+        params: &AgentSendParams,
+        file: &Attachment,
+        context: &ToolContext,
+    ) -> Result<(), String> {
+        let bot_token = context
+            .find_channel_bot_token("discord", "discord_bot_token")
+            .ok_or_else(|| "Discord bot token not available".to_string())?;
+        let url = format!("https://discord.com/api/v10/channels/{}/messages", params.channel);
+
+        let form = reqwest::multipart::Form::new()
+            .text("payload_json", json!({ "attachments": [{ "id": 0, "filename": file.file_name }] }).to_string())
+            .part("files[0]", file_part(file).await?);
+
+        let response = context
+            .http_client()
+            .post(&url)
+            .header("Authorization", format!("Bot {}", bot_token))
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| format!("Upload failed: {}", e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(format!("Discord API error ({}): {}", status, response.text().await.unwrap_or_default()));
+        }
+        Ok(())
+    }
+
     async fn send_telegram(&self, params: &AgentSendParams, context: &ToolContext) -> ToolResult {
         // Get bot token from channel settings
         let bot_token = match context.find_channel_bot_token("telegram", "telegram_bot_token") {
@@ -331,6 +479,20 @@ impl AgentSendTool {
     }
 }
 
+/// Multipart body for a workspace file
+async fn file_part(file: &Attachment) -> Result<reqwest::multipart::Part, String> {
+    let path = file
+        .absolute_path()
+        .ok_or_else(|| format!("{} has no local copy", file.file_name))?;
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", file.file_name, e))?;
+    reqwest::multipart::Part::bytes(bytes)
+        .file_name(file.file_name.clone())
+        .mime_str(&file.mime_type)
+        .map_err(|e| format!("Invalid MIME type {}: {}", file.mime_type, e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,4 +154,9 @@ impl Tool for AskUserTool {
     fn safety_level(&self) -> ToolSafetyLevel {
         ToolSafetyLevel::ReadOnly
     }
+
+    /// Asking ends the turn; never run it alongside other calls
+    fn parallel_safe(&self) -> bool {
+        false
+    }
 }
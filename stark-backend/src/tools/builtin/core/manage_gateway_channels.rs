@@ -57,6 +57,8 @@ impl ManageGatewayChannelsTool {
                     "discord".to_string(),
                     "twitter".to_string(),
                     "external_channel".to_string(),
+                    "email".to_string(),
+                    "matrix".to_string(),
                 ]),
             },
         );
@@ -165,6 +167,8 @@ const VALID_CHANNEL_TYPES: &[&str] = &[
     "discord",
     "twitter",
     "external_channel",
+    "email",
+    "matrix",
 ];
 
 #[async_trait]
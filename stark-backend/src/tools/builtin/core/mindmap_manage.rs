@@ -6,10 +6,14 @@
 //! - create: Add a new node (optionally connected to a parent)
 //! - update: Edit a node's body
 //! - delete: Remove a node
-//! - connect: Create a connection between two nodes
+//! - connect: Create a typed connection between two nodes
 //! - disconnect: Remove a connection between two nodes
+//! - search: Full-text search over node bodies
+//! - subgraph: Export a node's neighborhood within N hops
 
-use crate::db::tables::mind_nodes::{CreateMindNodeRequest, UpdateMindNodeRequest};
+use crate::db::tables::mind_nodes::{
+    CreateMindNodeRequest, MindEdgeType, MindNodeConnection, UpdateMindNodeRequest,
+};
 use crate::tools::registry::Tool;
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
@@ -31,7 +35,7 @@ impl MindmapManageTool {
             "action".to_string(),
             PropertySchema {
                 schema_type: "string".to_string(),
-                description: "The action to perform: 'list' (show all nodes), 'get' (get node by ID), 'create' (add node), 'update' (edit node body), 'delete' (remove node), 'connect' (link two nodes), 'disconnect' (unlink two nodes)".to_string(),
+                description: "The action to perform: 'list' (show all nodes), 'get' (get node by ID), 'create' (add node), 'update' (edit node body), 'delete' (remove node), 'connect' (link two nodes), 'disconnect' (unlink two nodes), 'search' (full-text search node bodies), 'subgraph' (nodes within N hops of node_id)".to_string(),
                 default: None,
                 items: None,
                 enum_values: Some(vec![
@@ -42,6 +46,8 @@ impl MindmapManageTool {
                     "delete".to_string(),
                     "connect".to_string(),
                     "disconnect".to_string(),
+                    "search".to_string(),
+                    "subgraph".to_string(),
                 ]),
             },
         );
@@ -50,7 +56,7 @@ impl MindmapManageTool {
             "node_id".to_string(),
             PropertySchema {
                 schema_type: "integer".to_string(),
-                description: "Node ID (required for get/update/delete, and as parent for connect/disconnect)".to_string(),
+                description: "Node ID (required for get/update/delete/subgraph)".to_string(),
                 default: None,
                 items: None,
                 enum_values: None,
@@ -90,10 +96,58 @@ impl MindmapManageTool {
             },
         );
 
+        properties.insert(
+            "edge_type".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Connection type for connect: 'part_of' (child is part of parent, default), 'blocks' (parent blocks child), 'relates_to' (related ideas). For subgraph, restricts traversal to this type.".to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(vec![
+                    "part_of".to_string(),
+                    "blocks".to_string(),
+                    "relates_to".to_string(),
+                ]),
+            },
+        );
+
+        properties.insert(
+            "query".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Search text for the search action".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "hops".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "How many hops from node_id to include in a subgraph (default 1, max 5)".to_string(),
+                default: Some(json!(1)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "auto_link".to_string(),
+            PropertySchema {
+                schema_type: "boolean".to_string(),
+                description: "For create: automatically link the new node to similar existing nodes with relates_to edges (default true)".to_string(),
+                default: Some(json!(true)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
         MindmapManageTool {
             definition: ToolDefinition {
                 name: "mindmap_manage".to_string(),
-                description: "Manage the mind map: list nodes, create/edit/delete nodes, and connect or disconnect them. The mindmap is a knowledge graph of ideas, topics, and goals that the heartbeat system uses for automated reflection.".to_string(),
+                description: "Manage the mind map: list nodes, create/edit/delete nodes, connect or disconnect them with typed edges, search node text, and explore a node's neighborhood. The mindmap is a knowledge graph of ideas, topics, and goals that the heartbeat system uses for automated reflection.".to_string(),
                 input_schema: ToolInputSchema {
                     schema_type: "object".to_string(),
                     properties,
@@ -119,6 +173,10 @@ struct MindmapParams {
     body: Option<String>,
     parent_id: Option<i64>,
     child_id: Option<i64>,
+    edge_type: Option<String>,
+    query: Option<String>,
+    hops: Option<u32>,
+    auto_link: Option<bool>,
 }
 
 fn format_node(node: &crate::db::tables::mind_nodes::MindNode) -> String {
@@ -134,6 +192,25 @@ fn format_node(node: &crate::db::tables::mind_nodes::MindNode) -> String {
     format!("#{}{} — {}", node.id, trunk_label, body_preview)
 }
 
+fn format_connection(conn: &MindNodeConnection) -> String {
+    match conn.weight {
+        Some(w) => format!("#{} → #{} [{} {:.2}]", conn.parent_id, conn.child_id, conn.edge_type.as_str(), w),
+        None => format!("#{} → #{} [{}]", conn.parent_id, conn.child_id, conn.edge_type.as_str()),
+    }
+}
+
+fn parse_edge_type(edge_type: Option<&str>) -> Result<Option<MindEdgeType>, ToolResult> {
+    match edge_type {
+        None => Ok(None),
+        Some(s) => MindEdgeType::from_str(s).map(Some).ok_or_else(|| {
+            ToolResult::error(format!(
+                "Unknown edge_type '{}'. Valid types: part_of, blocks, relates_to",
+                s
+            ))
+        }),
+    }
+}
+
 fn format_node_detail(node: &crate::db::tables::mind_nodes::MindNode) -> String {
     let trunk_label = if node.is_trunk { " [TRUNK]" } else { "" };
     format!(
@@ -180,7 +257,7 @@ impl Tool for MindmapManageTool {
                         if !graph.connections.is_empty() {
                             output.push_str("\nConnections:\n");
                             for conn in &graph.connections {
-                                output.push_str(&format!("  #{} → #{}\n", conn.parent_id, conn.child_id));
+                                output.push_str(&format!("  {}\n", format_connection(conn)));
                             }
                         }
 
@@ -204,13 +281,13 @@ impl Tool for MindmapManageTool {
                     Ok(Some(node)) => {
                         let mut output = format_node_detail(&node);
 
-                        // Also show neighbors
-                        if let Ok(neighbors) = db.get_mind_node_neighbors(node_id) {
-                            if !neighbors.is_empty() {
-                                output.push_str(&format!("\n  Neighbors ({}):", neighbors.len()));
-                                for n in &neighbors {
-                                    output.push_str(&format!("\n    {}", format_node(n)));
-                                }
+                        // Also show neighbors with the connecting edge type
+                        if let Ok(edges) = db.get_mind_node_edges(node_id)
+                            && !edges.is_empty()
+                        {
+                            output.push_str(&format!("\n  Neighbors ({}):", edges.len()));
+                            for (n, edge) in &edges {
+                                output.push_str(&format!("\n    [{}] {}", edge.edge_type.as_str(), format_node(n)));
                             }
                         }
 
@@ -237,12 +314,30 @@ impl Tool for MindmapManageTool {
                             .map(|pid| format!(" (connected to parent #{})", pid))
                             .unwrap_or_default();
 
+                        let links = if params.auto_link.unwrap_or(true) {
+                            db.auto_link_mind_node(node.id).unwrap_or_else(|e| {
+                                log::warn!("[MINDMAP] Auto-link failed for node {}: {}", node.id, e);
+                                Vec::new()
+                            })
+                        } else {
+                            Vec::new()
+                        };
+                        let link_msg = if links.is_empty() {
+                            String::new()
+                        } else {
+                            format!(
+                                "\nAuto-linked to similar nodes: {}",
+                                links.iter().map(format_connection).collect::<Vec<_>>().join(", ")
+                            )
+                        };
+
                         ToolResult::success(format!(
-                            "Created node #{}{}: {}",
-                            node.id, parent_msg, if body.is_empty() { "(empty)" } else { &body }
+                            "Created node #{}{}: {}{}",
+                            node.id, parent_msg, if body.is_empty() { "(empty)" } else { &body }, link_msg
                         )).with_metadata(json!({
                             "node_id": node.id,
                             "parent_id": params.parent_id,
+                            "auto_links": links.iter().map(|l| l.parent_id).collect::<Vec<_>>(),
                         }))
                     }
                     Err(e) => ToolResult::error(format!("Database error: {}", e)),
@@ -293,9 +388,14 @@ impl Tool for MindmapManageTool {
                     None => return ToolResult::error("'child_id' is required for 'connect' action"),
                 };
 
-                match db.create_mind_node_connection(parent_id, child_id) {
+                let edge_type = match parse_edge_type(params.edge_type.as_deref()) {
+                    Ok(t) => t.unwrap_or_default(),
+                    Err(e) => return e,
+                };
+
+                match db.create_typed_mind_node_connection(parent_id, child_id, edge_type, None) {
                     Ok(conn) => ToolResult::success(format!(
-                        "Connected #{} → #{} (connection #{})", conn.parent_id, conn.child_id, conn.id
+                        "Connected {} (connection #{})", format_connection(&conn), conn.id
                     )),
                     Err(e) => ToolResult::error(format!("Failed to connect: {}", e)),
                 }
@@ -318,8 +418,64 @@ impl Tool for MindmapManageTool {
                 }
             }
 
+            "search" => {
+                let query = match params.query.as_deref().or(params.body.as_deref()) {
+                    Some(q) if !q.trim().is_empty() => q,
+                    _ => return ToolResult::error("'query' is required for 'search' action"),
+                };
+
+                match db.search_mind_nodes(query, 10) {
+                    Ok(hits) if hits.is_empty() => ToolResult::success(format!("No nodes match '{}'", query)),
+                    Ok(hits) => {
+                        let mut output = format!("{} node(s) matching '{}':\n", hits.len(), query);
+                        for hit in &hits {
+                            output.push_str(&format!("\n#{} — {}", hit.node.id, hit.snippet));
+                        }
+                        ToolResult::success(output).with_metadata(json!({
+                            "node_ids": hits.iter().map(|h| h.node.id).collect::<Vec<_>>(),
+                        }))
+                    }
+                    Err(e) => ToolResult::error(format!("Search failed: {}", e)),
+                }
+            }
+
+            "subgraph" => {
+                let node_id = match params.node_id {
+                    Some(id) => id,
+                    None => return ToolResult::error("'node_id' is required for 'subgraph' action"),
+                };
+                let edge_types = match parse_edge_type(params.edge_type.as_deref()) {
+                    Ok(t) => t.map(|t| vec![t]),
+                    Err(e) => return e,
+                };
+
+                match db.get_mind_subgraph(node_id, params.hops.unwrap_or(1), edge_types.as_deref()) {
+                    Ok(Some(graph)) => {
+                        let mut output = format!(
+                            "Subgraph around #{} — {} nodes within {} hop(s){}\n\n",
+                            graph.center_id,
+                            graph.nodes.len(),
+                            graph.hops,
+                            if graph.truncated { " (truncated)" } else { "" }
+                        );
+                        for n in &graph.nodes {
+                            output.push_str(&format!("[{}] {}\n", n.distance, format_node(&n.node)));
+                        }
+                        if !graph.connections.is_empty() {
+                            output.push_str("\nConnections:\n");
+                            for conn in &graph.connections {
+                                output.push_str(&format!("  {}\n", format_connection(conn)));
+                            }
+                        }
+                        ToolResult::success(output).with_metadata(json!(graph))
+                    }
+                    Ok(None) => ToolResult::error(format!("Node #{} not found", node_id)),
+                    Err(e) => ToolResult::error(format!("Database error: {}", e)),
+                }
+            }
+
             _ => ToolResult::error(format!(
-                "Unknown action: '{}'. Valid actions: list, get, create, update, delete, connect, disconnect",
+                "Unknown action: '{}'. Valid actions: list, get, create, update, delete, connect, disconnect, search, subgraph",
                 params.action
             )),
         }
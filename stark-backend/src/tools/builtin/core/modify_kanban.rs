@@ -8,7 +8,9 @@
 //! - Create new items (auto-executed by scheduler when "ready")
 //! - Schedule one-time or recurring cron jobs
 
-use crate::db::tables::kanban::{CreateKanbanItemRequest, UpdateKanbanItemRequest};
+use crate::db::tables::kanban::{
+    validate_kanban_options, CreateKanbanItemRequest, UpdateKanbanItemRequest, KANBAN_STATUSES,
+};
 use crate::tools::registry::Tool;
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
@@ -48,13 +50,14 @@ impl WorkstreamTool {
             "status".to_string(),
             PropertySchema {
                 schema_type: "string".to_string(),
-                description: "Filter for 'list' action, or new status for 'update_status'. Values: 'ready', 'in_progress', 'complete'".to_string(),
+                description: "Filter for 'list' action, or new status for 'update_status'. Values: 'ready', 'in_progress', 'complete', 'failed'".to_string(),
                 default: None,
                 items: None,
                 enum_values: Some(vec![
                     "ready".to_string(),
                     "in_progress".to_string(),
                     "complete".to_string(),
+                    "failed".to_string(),
                 ]),
             },
         );
@@ -103,6 +106,34 @@ impl WorkstreamTool {
             },
         );
 
+        properties.insert(
+            "blocked_by".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "For 'create': IDs of items that must be complete before this one is picked".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "integer".to_string(),
+                    description: "Kanban item ID".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "max_retries".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "For 'create': how many times to retry the task (with backoff) if auto-execution fails (0-10, default 0)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
         properties.insert(
             "message".to_string(),
             PropertySchema {
@@ -181,6 +212,9 @@ struct WorkstreamParams {
     title: Option<String>,
     description: Option<String>,
     priority: Option<i32>,
+    #[serde(default)]
+    blocked_by: Vec<i64>,
+    max_retries: Option<i32>,
     message: Option<String>,
     schedule_type: Option<String>,
     schedule_value: Option<String>,
@@ -237,6 +271,10 @@ impl Tool for WorkstreamTool {
                     if !item.description.is_empty() {
                         output.push_str(&format!("  Description: {}\n", item.description));
                     }
+                    if !item.blocked_by.is_empty() {
+                        let ids: Vec<String> = item.blocked_by.iter().map(|id| format!("#{}", id)).collect();
+                        output.push_str(&format!("  Blocked by: {}\n", ids.join(", ")));
+                    }
                     if let Some(ref result) = item.result {
                         output.push_str(&format!("  Notes: {}\n", result));
                     }
@@ -272,7 +310,7 @@ impl Tool for WorkstreamTool {
                             "priority": item.priority,
                         }))
                     }
-                    Ok(None) => ToolResult::success("No ready tasks on the kanban board. All tasks are either in progress or complete."),
+                    Ok(None) => ToolResult::success("No ready tasks on the kanban board. All tasks are in progress, complete, waiting on blockers or waiting to retry."),
                     Err(e) => ToolResult::error(format!("Database error: {}", e)),
                 }
             }
@@ -287,8 +325,8 @@ impl Tool for WorkstreamTool {
                     None => return ToolResult::error("'status' is required for 'update_status' action"),
                 };
 
-                if !["ready", "in_progress", "complete"].contains(&status.as_str()) {
-                    return ToolResult::error("Invalid status. Must be 'ready', 'in_progress', or 'complete'");
+                if !KANBAN_STATUSES.contains(&status.as_str()) {
+                    return ToolResult::error("Invalid status. Must be 'ready', 'in_progress', 'complete' or 'failed'");
                 }
 
                 match db.update_kanban_item(item_id, &UpdateKanbanItemRequest {
@@ -343,10 +381,19 @@ impl Tool for WorkstreamTool {
                     None => return ToolResult::error("'title' is required for 'create' action"),
                 };
 
+                if let Err(e) = validate_kanban_options(params.max_retries, None, None)
+                    .and_then(|_| db.check_kanban_dependencies(None, &params.blocked_by))
+                {
+                    return ToolResult::error(e);
+                }
+
                 let request = CreateKanbanItemRequest {
                     title,
                     description: params.description,
                     priority: params.priority,
+                    blocked_by: params.blocked_by,
+                    max_retries: params.max_retries,
+                    ..Default::default()
                 };
 
                 match db.create_kanban_item(&request) {
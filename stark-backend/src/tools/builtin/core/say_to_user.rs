@@ -6,7 +6,11 @@
 //!
 //! When `finished_task` is true, this also terminates the orchestrator loop,
 //! acting as both a communication and completion signal.
+//!
+//! `files` attaches workspace files (charts, CSVs, exports); they are queued on
+//! the tool context and uploaded by the channel adapter with the final response.
 
+use crate::channels::attachments::Attachment;
 use crate::tools::registry::Tool;
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
@@ -48,6 +52,23 @@ impl SayToUserTool {
             },
         );
 
+        properties.insert(
+            "files".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "Optional workspace file paths to send along with the message (images, CSVs, exports). Channels upload them as native files.".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "Path relative to the workspace directory".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
         SayToUserTool {
             definition: ToolDefinition {
                 name: "say_to_user".to_string(),
@@ -75,6 +96,8 @@ struct SayToUserParams {
     message: String,
     #[serde(default)]
     finished_task: bool,
+    #[serde(default)]
+    files: Vec<String>,
 }
 
 #[async_trait]
@@ -83,18 +106,46 @@ impl Tool for SayToUserTool {
         self.definition.clone()
     }
 
-    async fn execute(&self, params: Value, _context: &ToolContext) -> ToolResult {
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
         let params: SayToUserParams = match serde_json::from_value(params) {
             Ok(p) => p,
             Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
         };
 
+        // Safe mode users must not be able to pull arbitrary workspace files out
+        let safe_mode = context.extra.get("safe_mode").and_then(|v| v.as_bool()).unwrap_or(false);
+        if safe_mode && !params.files.is_empty() {
+            return ToolResult::error("Sending files is not available in safe mode.");
+        }
+
+        // Validate every file before queueing any, so a bad path doesn't send a partial set
+        let workspace = context
+            .workspace_dir
+            .clone()
+            .unwrap_or_else(crate::config::workspace_dir);
+        let mut files = Vec::new();
+        for path in &params.files {
+            match Attachment::from_workspace_file(&workspace, path) {
+                Ok(file) => files.push(file),
+                Err(e) => return ToolResult::error(format!("Cannot attach '{}': {}", path, e)),
+            }
+        }
+        let file_paths: Vec<String> = files.iter().filter_map(|f| f.local_path.clone()).collect();
+        for file in files {
+            context.outbound_files.push(file);
+        }
+
         let mut result = ToolResult::success(params.message);
 
+        let mut metadata = serde_json::Map::new();
         // Signal to the orchestrator that this completes the task
         if params.finished_task {
-            let mut metadata = serde_json::Map::new();
             metadata.insert("finished_task".to_string(), serde_json::Value::Bool(true));
+        }
+        if !file_paths.is_empty() {
+            metadata.insert("files".to_string(), serde_json::json!(file_paths));
+        }
+        if !metadata.is_empty() {
             result.metadata = Some(serde_json::Value::Object(metadata));
         }
 
@@ -10,7 +10,7 @@ use serde::Deserialize;
 use serde_json::{json, Value};
 use std::collections::HashMap;
 
-/// Tool to switch between agent subtypes (Finance, CodeEngineer, Secretary)
+/// Tool to switch between agent subtypes (Finance, CodeEngineer, Secretary, plus any from config)
 /// This controls which tools and skills are available to the agent.
 /// Think of subtypes as "toolboxes" - each one unlocks different capabilities.
 ///
@@ -22,35 +22,36 @@ pub struct SetAgentSubtypeTool {
 
 impl SetAgentSubtypeTool {
     pub fn new() -> Self {
+        let subtypes = AgentSubtype::all();
+        let options = subtypes
+            .iter()
+            .map(|s| format!("• '{}' - {}", s.as_str(), Self::selection_hint(*s)))
+            .collect::<Vec<_>>()
+            .join("\n");
+
         let mut properties = HashMap::new();
         properties.insert(
             "subtype".to_string(),
             PropertySchema {
                 schema_type: "string".to_string(),
-                description: "The agent subtype/toolbox to activate:\n\
-                    • 'finance' - DeFi/crypto operations (swaps, transfers, web3)\n\
-                    • 'code_engineer' - Software development (code editing, git, testing)\n\
-                    • 'secretary' - Social media, messaging, scheduling, marketing".to_string(),
+                description: format!("The agent subtype/toolbox to activate:\n{}", options),
                 default: None,
                 items: None,
-                enum_values: Some(vec![
-                    "finance".to_string(),
-                    "code_engineer".to_string(),
-                    "secretary".to_string(),
-                ]),
+                enum_values: Some(subtypes.iter().map(|s| s.as_str().to_string()).collect()),
             },
         );
 
         SetAgentSubtypeTool {
             definition: ToolDefinition {
                 name: "set_agent_subtype".to_string(),
-                description: "⚡ REQUIRED FIRST TOOL: Select your toolbox before doing anything else!\n\n\
+                description: format!(
+                    "⚡ REQUIRED FIRST TOOL: Select your toolbox before doing anything else!\n\n\
                     You MUST call this tool FIRST based on what the user wants:\n\
-                    • 'finance' - For crypto/DeFi: swaps, transfers, balances, token lookups\n\
-                    • 'code_engineer' - For coding: edit files, git, grep/glob, run commands\n\
-                    • 'secretary' - For social: MoltX, messaging, scheduling, marketing\n\n\
+                    {}\n\n\
                     Choose based on the user's request, then proceed with the appropriate tools.\n\n\
-                    Note: Agent identity/registration (EIP-8004) skills are available in ALL subtypes.".to_string(),
+                    Note: Agent identity/registration (EIP-8004) skills are available in ALL subtypes.",
+                    options
+                ),
                 input_schema: ToolInputSchema {
                     schema_type: "object".to_string(),
                     properties,
@@ -62,12 +63,45 @@ impl SetAgentSubtypeTool {
         }
     }
 
+    /// One-line hint shown next to each subtype in the tool definition
+    fn selection_hint(subtype: AgentSubtype) -> String {
+        match subtype {
+            AgentSubtype::Finance => "For crypto/DeFi: swaps, transfers, balances, token lookups".to_string(),
+            AgentSubtype::CodeEngineer => "For coding: edit files, git, grep/glob, run commands".to_string(),
+            AgentSubtype::Secretary => "For social: MoltX, messaging, scheduling, marketing".to_string(),
+            _ => subtype.description().to_string(),
+        }
+    }
+
     /// Get a description of available tools for a subtype
     fn describe_subtype(subtype: AgentSubtype) -> String {
+        let mut description = Self::describe_toolbox(subtype);
+        if let Some(def) = subtype.definition() {
+            if !def.default_skills.is_empty() {
+                description.push_str(&format!(
+                    "\n\n## Recommended skills\n{}",
+                    def.default_skills.iter().map(|s| format!("• {}", s)).collect::<Vec<_>>().join("\n")
+                ));
+            }
+            if !def.required_tools.is_empty() {
+                description.push_str(&format!("\n\n## Always-available tools\n{}", def.required_tools.join(", ")));
+            }
+        }
+        description
+    }
+
+    fn describe_toolbox(subtype: AgentSubtype) -> String {
         match subtype {
             AgentSubtype::None => {
                 "❓ No toolbox selected. Call set_agent_subtype first!".to_string()
             }
+            AgentSubtype::Custom(_) => format!(
+                "{} {} toolbox activated.\n\n{}\n\nTool groups: {}",
+                subtype.emoji(),
+                subtype.label(),
+                subtype.description(),
+                subtype.allowed_tool_groups().iter().map(|g| g.as_str()).collect::<Vec<_>>().join(", ")
+            ),
             AgentSubtype::Finance => {
                 "💰 Finance toolbox activated.\n\n\
                  ## Skills (use set_agent_subtype then pick a skill with use_skill)\n\
@@ -184,10 +218,15 @@ impl Tool for SetAgentSubtypeTool {
         let subtype = match AgentSubtype::from_str(&params.subtype) {
             Some(s) => s,
             None => {
+                let valid = AgentSubtype::all()
+                    .iter()
+                    .map(|s| format!("'{}'", s.as_str()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
                 return ToolResult::error(format!(
-                    "Invalid subtype '{}'. Valid options: 'finance', 'code_engineer', 'secretary'",
-                    params.subtype
-                ))
+                    "Invalid subtype '{}'. Valid options: {}",
+                    params.subtype, valid
+                ));
             }
         };
 
@@ -274,4 +313,16 @@ mod tests {
         assert!(!result.success);
         assert!(result.error.unwrap().contains("Invalid subtype"));
     }
+
+    #[test]
+    fn test_definition_lists_subtypes() {
+        let tool = SetAgentSubtypeTool::new();
+        let schema = &tool.definition().input_schema.properties["subtype"];
+        let values = schema.enum_values.as_ref().unwrap();
+
+        for key in ["finance", "code_engineer", "secretary"] {
+            assert!(values.iter().any(|v| v == key));
+            assert!(tool.definition().description.contains(&format!("'{}'", key)));
+        }
+    }
 }
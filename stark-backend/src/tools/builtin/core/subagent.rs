@@ -1,9 +1,12 @@
 //! Sub-agent tools for spawning and monitoring background agent instances
 //!
-//! This module provides two tools:
+//! This module provides four tools:
 //! - `subagent`: Spawn a new sub-agent to work on a task
+//! - `subagent_fanout`: Spawn several sub-agents with a shared goal and different inputs
+//! - `subagent_wait`: Wait for sub-agents (or a fan-out group) and collect their results
 //! - `subagent_status`: Check the status of sub-agents
 
+use crate::ai::multi_agent::subagent_manager::SubAgentWaitResult;
 use crate::ai::multi_agent::{SubAgentContext, SubAgentManager, SubAgentStatus};
 use crate::tools::registry::Tool;
 use crate::tools::types::{
@@ -144,6 +147,28 @@ impl SubagentTool {
             },
         );
 
+        properties.insert(
+            "max_iterations".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Optional cap on AI iterations for the subagent (default: 15).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "max_cost_usdc".to_string(),
+            PropertySchema {
+                schema_type: "number".to_string(),
+                description: "Optional cap on x402 spend in USDC; the subagent fails once it is exceeded.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
         SubagentTool {
             definition: ToolDefinition {
                 name: "subagent".to_string(),
@@ -209,6 +234,8 @@ struct SubagentParams {
     context: Option<String>,
     #[serde(default)]
     read_only: Option<bool>,
+    max_iterations: Option<u32>,
+    max_cost_usdc: Option<f64>,
 }
 
 #[async_trait]
@@ -269,7 +296,9 @@ impl Tool for SubagentTool {
                 .with_model(params.model.clone())
                 .with_context(params.context.clone())
                 .with_thinking(params.thinking.clone())
-                .with_read_only(read_only);
+                .with_read_only(read_only)
+                .with_caps(params.max_iterations, params.max_cost_usdc)
+                .with_caller_limits(context);
 
                 // Spawn the sub-agent
                 match manager.spawn(subagent_context).await {
@@ -535,6 +564,449 @@ impl Tool for SubagentTool {
     }
 }
 
+/// Structured view of one sub-agent for fan-in results
+fn subagent_json(agent: &SubAgentContext) -> Value {
+    json!({
+        "id": agent.id,
+        "label": agent.label,
+        "status": agent.status.to_string(),
+        "result": agent.result,
+        "error": agent.error,
+        "duration_secs": agent.completed_at.map(|_| agent.duration().num_seconds()),
+        "iterations": agent.iterations,
+        "cost_usdc": agent.cost_usdc,
+    })
+}
+
+/// Render the outcome of a wait as text plus structured metadata
+fn wait_result(wait: &SubAgentWaitResult, group_id: Option<&str>) -> ToolResult {
+    let count = |status: SubAgentStatus| wait.agents.iter().filter(|a| a.status == status).count();
+    let completed = count(SubAgentStatus::Completed);
+    let failed = count(SubAgentStatus::Failed) + count(SubAgentStatus::TimedOut);
+    let cancelled = count(SubAgentStatus::Cancelled);
+    let pending = wait.agents.iter().filter(|a| !a.status.is_terminal()).count();
+    let total_cost: f64 = wait.agents.iter().map(|a| a.cost_usdc).sum();
+
+    let mut text = format!(
+        "## Subagent results ({} completed, {} failed, {} cancelled, {} still running{})\n",
+        completed,
+        failed,
+        cancelled,
+        pending,
+        if wait.timed_out { " — wait timed out" } else { "" }
+    );
+    for agent in &wait.agents {
+        text.push_str(&format!("\n### {} ({}) — {}\n", agent.label, agent.id, agent.status));
+        if let Some(ref result) = agent.result {
+            text.push_str(result);
+            text.push('\n');
+        }
+        if let Some(ref error) = agent.error {
+            text.push_str(&format!("Error: {}\n", error));
+        }
+    }
+
+    ToolResult::success(text).with_metadata(json!({
+        "group_id": group_id,
+        "timed_out": wait.timed_out,
+        "completed": completed,
+        "failed": failed,
+        "cancelled": cancelled,
+        "pending": pending,
+        "total_cost_usdc": total_cost,
+        "results": wait.agents.iter().map(subagent_json).collect::<Vec<_>>(),
+    }))
+}
+
+/// Tool for spawning several subagents that share a goal (fan-out)
+pub struct SubagentFanoutTool {
+    definition: ToolDefinition,
+}
+
+impl SubagentFanoutTool {
+    /// Most subagents a single fan-out may spawn
+    const MAX_FANOUT: usize = 10;
+
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "goal".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "The shared task every subagent works on (e.g., 'Summarize liquidity, holders and recent price action for this token').".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "inputs".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: format!(
+                    "One input per subagent (e.g., a token address or repo URL). Each subagent gets the goal plus its input. Max {}.",
+                    Self::MAX_FANOUT
+                ),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "Input for one subagent".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "label".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Label prefix for the subagents (numbered per input).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "timeout".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Timeout in seconds for each subagent (default: 300, max: 3600).".to_string(),
+                default: Some(json!(300)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "max_iterations".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Optional cap on AI iterations per subagent (default: 15).".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "max_cost_usdc".to_string(),
+            PropertySchema {
+                schema_type: "number".to_string(),
+                description: "Optional cap on x402 spend in USDC per subagent.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "cancel_on_failure".to_string(),
+            PropertySchema {
+                schema_type: "boolean".to_string(),
+                description: "If true (default), a failing subagent cancels its siblings that are still running.".to_string(),
+                default: Some(json!(true)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "read_only".to_string(),
+            PropertySchema {
+                schema_type: "boolean".to_string(),
+                description: "If true, restrict every subagent to read-only tools. Recommended for research.".to_string(),
+                default: Some(json!(false)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "wait".to_string(),
+            PropertySchema {
+                schema_type: "boolean".to_string(),
+                description: "If true, wait for all subagents and return their results. If false (default), return the group id for `subagent_wait`.".to_string(),
+                default: Some(json!(false)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        SubagentFanoutTool {
+            definition: ToolDefinition {
+                name: "subagent_fanout".to_string(),
+                description: "Spawn several subagents in parallel that work on the same goal with different inputs (e.g., compare several tokens or repos). Returns a group id; collect the results with `subagent_wait`.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["goal".to_string(), "inputs".to_string()],
+                },
+                group: ToolGroup::System,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for SubagentFanoutTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SubagentFanoutParams {
+    goal: String,
+    inputs: Vec<String>,
+    label: Option<String>,
+    timeout: Option<u64>,
+    max_iterations: Option<u32>,
+    max_cost_usdc: Option<f64>,
+    cancel_on_failure: Option<bool>,
+    read_only: Option<bool>,
+    wait: Option<bool>,
+}
+
+#[async_trait]
+impl Tool for SubagentFanoutTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: SubagentFanoutParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        if params.goal.trim().is_empty() {
+            return ToolResult::error("goal is required");
+        }
+        if params.inputs.is_empty() {
+            return ToolResult::error("inputs must contain at least one entry");
+        }
+        if params.inputs.len() > Self::MAX_FANOUT {
+            return ToolResult::error(format!(
+                "Too many inputs ({}); a fan-out is limited to {} subagents",
+                params.inputs.len(),
+                Self::MAX_FANOUT
+            ));
+        }
+
+        let manager = match &context.subagent_manager {
+            Some(m) => m,
+            None => return ToolResult::error("Subagent fan-out is not available in this context"),
+        };
+        let (session_id, channel_id) = match (context.session_id, context.channel_id) {
+            (Some(s), Some(c)) if s > 0 && c > 0 => (s, c),
+            _ => return ToolResult::error("Subagent fan-out requires a session and channel"),
+        };
+
+        let timeout_secs = params.timeout.unwrap_or(300).min(3600);
+        let prefix = params.label.clone().unwrap_or_else(|| "fanout".to_string());
+        let contexts = params
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| {
+                let label = format!("{}-{}", prefix, i + 1);
+                SubAgentContext::new(
+                    SubAgentManager::generate_id(&label),
+                    session_id,
+                    channel_id,
+                    label,
+                    format!("{}\n\n## Input:\n{}", params.goal, input),
+                    timeout_secs,
+                )
+                .with_read_only(params.read_only.unwrap_or(false))
+                .with_caps(params.max_iterations, params.max_cost_usdc)
+                .with_caller_limits(context)
+            })
+            .collect();
+
+        let (group_id, ids) = match manager
+            .spawn_group(contexts, params.cancel_on_failure.unwrap_or(true))
+            .await
+        {
+            Ok(spawned) => spawned,
+            Err(e) => return ToolResult::error(format!("Failed to spawn subagents: {}", e)),
+        };
+
+        if params.wait.unwrap_or(false) {
+            return match manager
+                .wait_for(&ids, std::time::Duration::from_secs(timeout_secs), false)
+                .await
+            {
+                Ok(wait) => wait_result(&wait, Some(&group_id)),
+                Err(e) => ToolResult::error(format!("Failed to wait for subagents: {}", e)),
+            };
+        }
+
+        ToolResult::success(format!(
+            "Spawned {} subagents in group '{}'.\n\nUse `subagent_wait` with group_id '{}' to collect their results.",
+            ids.len(),
+            group_id,
+            group_id
+        ))
+        .with_metadata(json!({
+            "group_id": group_id,
+            "subagent_ids": ids,
+            "timeout": timeout_secs,
+        }))
+    }
+}
+
+/// Tool for waiting on subagents and collecting their results (fan-in)
+pub struct SubagentWaitTool {
+    definition: ToolDefinition,
+}
+
+impl SubagentWaitTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "ids".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "Subagent IDs to wait for. Use this or group_id.".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "Subagent ID".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "group_id".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Fan-out group ID returned by `subagent_fanout`.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "timeout".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Seconds to wait before returning whatever has finished (default: 300, max: 3600).".to_string(),
+                default: Some(json!(300)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "mode".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'all' (default) waits for every subagent; 'any' returns as soon as one finishes.".to_string(),
+                default: Some(json!("all")),
+                items: None,
+                enum_values: Some(vec!["all".to_string(), "any".to_string()]),
+            },
+        );
+
+        SubagentWaitTool {
+            definition: ToolDefinition {
+                name: "subagent_wait".to_string(),
+                description: "Wait for subagents to finish and return their results as a structured list (status, result, error, iterations and cost per subagent). Returns partial results if the timeout elapses.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec![],
+                },
+                group: ToolGroup::System,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for SubagentWaitTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SubagentWaitParams {
+    #[serde(default)]
+    ids: Vec<String>,
+    group_id: Option<String>,
+    timeout: Option<u64>,
+    mode: Option<String>,
+}
+
+#[async_trait]
+impl Tool for SubagentWaitTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: SubagentWaitParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let any = match params.mode.as_deref().unwrap_or("all") {
+            "all" => false,
+            "any" => true,
+            other => return ToolResult::error(format!("Invalid mode '{}'. Use 'all' or 'any'", other)),
+        };
+
+        let manager = match &context.subagent_manager {
+            Some(m) => m,
+            None => return ToolResult::error("Subagent waiting is not available in this context"),
+        };
+
+        let mut ids = params.ids;
+        if let Some(ref group_id) = params.group_id {
+            match manager.list_by_group(group_id) {
+                Ok(members) => {
+                    for member in members {
+                        if !ids.contains(&member.id) {
+                            ids.push(member.id);
+                        }
+                    }
+                }
+                Err(e) => return ToolResult::error(format!("Failed to look up group: {}", e)),
+            }
+        }
+        if ids.is_empty() {
+            return ToolResult::error("No subagents to wait for. Provide ids or a known group_id");
+        }
+
+        let timeout_secs = params.timeout.unwrap_or(300).min(3600);
+        match manager
+            .wait_for(&ids, std::time::Duration::from_secs(timeout_secs), any)
+            .await
+        {
+            Ok(wait) => wait_result(&wait, params.group_id.as_deref()),
+            Err(e) => ToolResult::error(format!("Failed to wait for subagents: {}", e)),
+        }
+    }
+
+    fn safety_level(&self) -> ToolSafetyLevel {
+        ToolSafetyLevel::ReadOnly
+    }
+}
+
 /// Tool for checking subagent status
 pub struct SubagentStatusTool {
     definition: ToolDefinition,
@@ -819,6 +1291,36 @@ impl Tool for SubagentStatusTool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_subagent_inherits_caller_role_limits() {
+        let role_config = crate::tools::ToolConfig {
+            profile: crate::tools::ToolProfile::Finance,
+            denied_groups: vec!["exec".to_string()],
+            ..Default::default()
+        };
+        let mut caller = ToolContext::new();
+        caller.extra.insert("role_tool_config".to_string(), serde_json::to_value(&role_config).unwrap());
+        caller.extra.insert("max_tx_value_wei".to_string(), json!("1000"));
+        caller.extra.insert("max_tx_value_source".to_string(), json!("role 'trader'"));
+
+        let child = SubAgentContext::new("a".into(), 1, 1, "a".into(), "t".into(), 60).with_caller_limits(&caller);
+        let inherited = child.tool_config.expect("role config is inherited");
+        assert_eq!(inherited.profile, crate::tools::ToolProfile::Finance);
+        assert_eq!(inherited.denied_groups, vec!["exec".to_string()]);
+        assert_eq!(child.max_tx_value_wei.as_deref(), Some("1000"));
+        assert_eq!(child.max_tx_value_source.as_deref(), Some("role 'trader'"));
+
+        // No role: the sub-agent falls back to the channel config
+        let unlimited = SubAgentContext::new("b".into(), 1, 1, "b".into(), "t".into(), 60)
+            .with_caller_limits(&ToolContext::new());
+        assert!(unlimited.tool_config.is_none() && unlimited.max_tx_value_wei.is_none());
+
+        // A config that does not parse fails closed
+        caller.extra.insert("role_tool_config".to_string(), json!("garbage"));
+        let closed = SubAgentContext::new("c".into(), 1, 1, "c".into(), "t".into(), 60).with_caller_limits(&caller);
+        assert_eq!(closed.tool_config.unwrap().profile, crate::tools::ToolProfile::SafeMode);
+    }
+
     #[test]
     fn test_subagent_definition() {
         let tool = SubagentTool::new();
@@ -839,6 +1341,37 @@ mod tests {
         assert!(def.input_schema.required.is_empty());
     }
 
+    #[test]
+    fn test_fanout_and_wait_definitions() {
+        let fanout = SubagentFanoutTool::new().definition();
+        assert_eq!(fanout.name, "subagent_fanout");
+        assert!(fanout.input_schema.required.contains(&"inputs".to_string()));
+
+        let wait = SubagentWaitTool::new().definition();
+        assert_eq!(wait.name, "subagent_wait");
+        assert!(wait.input_schema.required.is_empty());
+    }
+
+    #[test]
+    fn test_wait_result_counts() {
+        let mut done = SubAgentContext::new("a".into(), 1, 1, "a".into(), "t".into(), 60);
+        done.mark_completed("ok".into());
+        let mut failed = SubAgentContext::new("b".into(), 1, 1, "b".into(), "t".into(), 60);
+        failed.mark_failed("boom".into());
+        let mut cancelled = SubAgentContext::new("c".into(), 1, 1, "c".into(), "t".into(), 60);
+        cancelled.mark_cancelled_because("Cancelled because sibling 'b' failed".into());
+
+        let result = wait_result(
+            &SubAgentWaitResult { agents: vec![done, failed, cancelled], timed_out: false },
+            Some("group-1"),
+        );
+        let meta = result.metadata.unwrap();
+        assert_eq!(meta["completed"], 1);
+        assert_eq!(meta["failed"], 1);
+        assert_eq!(meta["cancelled"], 1);
+        assert_eq!(meta["results"][1]["error"], "boom");
+    }
+
     #[tokio::test]
     async fn test_spawn_subagent_legacy() {
         let tool = SubagentTool::new();
@@ -0,0 +1,461 @@
+//! A2A agent tool — delegate work to other agents over the A2A protocol
+//!
+//! Peers are found through EIP-8004 discovery (agents advertising an "a2a" service)
+//! or addressed by URL. 402 challenges are paid with x402 like x402_agent_invoke, and
+//! every delegated task is tracked in the a2a_tasks table for later status checks.
+
+use crate::a2a::types::Task;
+use crate::a2a::A2aClient;
+use crate::eip8004::config::Eip8004Config;
+use crate::eip8004::AgentDiscovery;
+use crate::gateway::protocol::GatewayEvent;
+use crate::models::{A2aDirection, A2aTaskState};
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::x402::X402PaymentInfo;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Tool for talking to other agents over A2A
+pub struct A2aAgentTool {
+    definition: ToolDefinition,
+}
+
+impl A2aAgentTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'discover' lists EIP-8004 agents offering A2A, 'card' fetches a peer's agent card, \
+                    'send' delegates a message, 'status' refreshes a delegated task, 'cancel' cancels it, \
+                    'list' shows tracked tasks".to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(vec![
+                    "discover".to_string(),
+                    "card".to_string(),
+                    "send".to_string(),
+                    "status".to_string(),
+                    "cancel".to_string(),
+                    "list".to_string(),
+                ]),
+            },
+        );
+
+        properties.insert(
+            "agent_id".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "EIP-8004 agent ID of the peer (its 'a2a' service endpoint is used). For card/send.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "url".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Peer URL instead of agent_id: site root, agent card URL or JSON-RPC endpoint \
+                    (e.g., http://localhost:8081). For card/send.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "message".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Message for the peer agent (send)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "context_id".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Continue an earlier conversation with the same peer (send). Returned by previous sends.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "task_id".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Remote task ID returned by send (status/cancel)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "wait".to_string(),
+            PropertySchema {
+                schema_type: "boolean".to_string(),
+                description: "Wait for the peer to finish before returning (send). Default true; use false for long jobs and poll with status.".to_string(),
+                default: Some(json!(true)),
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "token".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Optional API token issued by the peer. Token holders are not charged.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        A2aAgentTool {
+            definition: ToolDefinition {
+                name: "a2a_agent".to_string(),
+                description: "Delegate work to other AI agents over the A2A protocol. Discover peers via the \
+                    EIP-8004 registry or use a URL, send them messages (x402 USDC payment is handled automatically \
+                    when the peer charges), and track the remote tasks.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["action".to_string()],
+                },
+                group: ToolGroup::Finance,
+                hidden: false,
+            },
+        }
+    }
+}
+
+impl Default for A2aAgentTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct A2aAgentParams {
+    action: String,
+    agent_id: Option<u64>,
+    url: Option<String>,
+    message: Option<String>,
+    context_id: Option<String>,
+    task_id: Option<String>,
+    #[serde(default = "default_wait")]
+    wait: bool,
+    token: Option<String>,
+}
+
+fn default_wait() -> bool {
+    true
+}
+
+fn discovery(context: &ToolContext) -> Result<AgentDiscovery, String> {
+    let config = Eip8004Config::from_env();
+    if !config.is_identity_deployed() {
+        return Err("EIP-8004 Identity Registry is not deployed; pass a url instead".to_string());
+    }
+    Ok(match context.wallet_provider {
+        Some(ref wp) => AgentDiscovery::new_with_wallet_provider(config, wp.clone()),
+        None => AgentDiscovery::new(config),
+    })
+}
+
+/// Where to reach the peer: its advertised "a2a" endpoint (by agent ID) or the given URL
+async fn peer_endpoint(params: &A2aAgentParams, context: &ToolContext) -> Result<(String, Option<i64>), String> {
+    if let Some(url) = params.url.as_deref().filter(|u| !u.trim().is_empty()) {
+        return Ok((url.trim().to_string(), params.agent_id.map(|id| id as i64)));
+    }
+    let agent_id = params.agent_id.ok_or("Either agent_id or url is required")?;
+    let agent = discovery(context)?.discover_agent(agent_id).await?;
+    let endpoint = agent
+        .registration
+        .as_ref()
+        .and_then(|r| r.services.iter().find(|s| s.name.eq_ignore_ascii_case("a2a")))
+        .map(|s| s.endpoint.clone())
+        .ok_or_else(|| format!("Agent {} does not advertise an 'a2a' service", agent_id))?;
+    Ok((endpoint, Some(agent_id as i64)))
+}
+
+fn task_summary(task: &Task) -> Value {
+    json!({
+        "task_id": task.id,
+        "context_id": task.context_id,
+        "state": task.status.state.as_str(),
+        "output": task.output_text(),
+    })
+}
+
+/// Record a paid call like the other x402 tools do
+fn record_payment(context: &ToolContext, payment: &X402PaymentInfo, rpc_url: &str) {
+    if let Some(ref broadcaster) = context.broadcaster {
+        broadcaster.broadcast(GatewayEvent::x402_payment(
+            context.channel_id.unwrap_or(0),
+            &payment.amount,
+            &payment.amount_formatted,
+            &payment.asset,
+            &payment.pay_to,
+            Some(rpc_url),
+        ));
+    }
+    if let Some(ref db) = context.database {
+        let _ = db.record_x402_payment(
+            context.channel_id,
+            Some("a2a_agent"),
+            Some(rpc_url),
+            &payment.amount,
+            &payment.amount_formatted,
+            &payment.asset,
+            &payment.pay_to,
+            payment.tx_hash.as_deref(),
+            &payment.status.to_string(),
+        );
+    }
+}
+
+impl A2aAgentTool {
+    async fn discover(&self, context: &ToolContext) -> ToolResult {
+        let mut discovery = match discovery(context) {
+            Ok(d) => d,
+            Err(e) => return ToolResult::error(e),
+        };
+        let agents = match discovery.find_by_service("a2a").await {
+            Ok(a) => a,
+            Err(e) => return ToolResult::error(format!("Discovery failed: {}", e)),
+        };
+
+        let peers: Vec<Value> = agents
+            .iter()
+            .filter(|a| a.is_active())
+            .map(|a| {
+                let registration = a.registration.as_ref();
+                json!({
+                    "agent_id": a.identifier.agent_id,
+                    "name": registration.map(|r| r.name.clone()),
+                    "description": registration.map(|r| r.description.clone()),
+                    "endpoint": registration
+                        .and_then(|r| r.services.iter().find(|s| s.name.eq_ignore_ascii_case("a2a")))
+                        .map(|s| s.endpoint.clone()),
+                    "x402": a.is_x402_enabled(),
+                    "reputation": a.reputation,
+                })
+            })
+            .collect();
+
+        ToolResult::success(
+            serde_json::to_string_pretty(&json!({ "agents": peers, "count": peers.len() })).unwrap_or_default(),
+        )
+    }
+
+    async fn card(&self, params: &A2aAgentParams, context: &ToolContext) -> ToolResult {
+        let (endpoint, _) = match peer_endpoint(params, context).await {
+            Ok(e) => e,
+            Err(e) => return ToolResult::error(e),
+        };
+        let client = A2aClient::new(context.http_client(), None);
+        match client.resolve_endpoint(&endpoint).await {
+            Ok((_, Some(card))) => ToolResult::success(serde_json::to_string_pretty(&card).unwrap_or_default()),
+            Ok((rpc_url, None)) => ToolResult::error(format!("No agent card found for {}", rpc_url)),
+            Err(e) => ToolResult::error(e),
+        }
+    }
+
+    async fn send(&self, params: &A2aAgentParams, context: &ToolContext) -> ToolResult {
+        let message = match params.message.as_deref().filter(|m| !m.trim().is_empty()) {
+            Some(m) => m,
+            None => return ToolResult::error("message is required for send"),
+        };
+        let (endpoint, peer_agent_id) = match peer_endpoint(params, context).await {
+            Ok(e) => e,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        let client = A2aClient::new(context.http_client(), context.wallet_provider.clone())
+            .with_bearer_token(params.token.clone());
+        let rpc_url = match client.resolve_endpoint(&endpoint).await {
+            Ok((url, _)) => url,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        log::info!("[a2a_agent] Sending {} chars to {}", message.len(), rpc_url);
+        let result = match client
+            .send_message(&rpc_url, message, params.context_id.clone(), params.wait)
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => return ToolResult::error(e),
+        };
+        let task = result.value;
+
+        if let Some(ref payment) = result.payment {
+            record_payment(context, payment, &rpc_url);
+        }
+        if let Some(ref db) = context.database {
+            let stored = db.create_a2a_task(
+                &task.id,
+                A2aDirection::Outbound,
+                &task.context_id,
+                Some(&rpc_url),
+                peer_agent_id,
+                None,
+                context.channel_id,
+                context.session_id,
+                message,
+                task.status.state,
+            );
+            match stored {
+                Ok(_) => {
+                    let output = task.output_text();
+                    if task.status.state.is_terminal() {
+                        let _ = db.update_a2a_task_state(
+                            A2aDirection::Outbound,
+                            &task.id,
+                            task.status.state,
+                            Some(&output).filter(|o| !o.is_empty()).map(|o| o.as_str()),
+                            None,
+                        );
+                    }
+                    if let Some(ref payment) = result.payment {
+                        let _ = db.set_a2a_task_payment(A2aDirection::Outbound, &task.id, &payment.amount, None, None);
+                    }
+                }
+                Err(e) => log::warn!("[a2a_agent] Failed to track task {}: {}", task.id, e),
+            }
+        }
+
+        let mut summary = task_summary(&task);
+        summary["peer"] = json!(rpc_url);
+        if let Some(ref payment) = result.payment {
+            summary["payment"] = json!({
+                "amount": payment.amount_formatted,
+                "asset": payment.asset,
+                "pay_to": payment.pay_to,
+            });
+        }
+
+        let succeeded = !matches!(task.status.state, A2aTaskState::Failed | A2aTaskState::Rejected);
+        let content = serde_json::to_string_pretty(&summary).unwrap_or_default();
+        if succeeded {
+            ToolResult::success(content).with_metadata(summary)
+        } else {
+            ToolResult::error(content)
+        }
+    }
+
+    async fn refresh(&self, params: &A2aAgentParams, context: &ToolContext, cancel: bool) -> ToolResult {
+        let task_id = match params.task_id.as_deref() {
+            Some(id) if !id.is_empty() => id,
+            _ => return ToolResult::error("task_id is required"),
+        };
+        let db = match context.database {
+            Some(ref db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+        let tracked = match db.get_a2a_task(A2aDirection::Outbound, task_id) {
+            Ok(Some(t)) => t,
+            Ok(None) => return ToolResult::error(format!("No delegated task '{}' is tracked", task_id)),
+            Err(e) => return ToolResult::error(format!("Database error: {}", e)),
+        };
+        let Some(rpc_url) = tracked.peer_url.clone() else {
+            return ToolResult::error("Tracked task has no peer URL");
+        };
+
+        // Polling is free on peers; only message/send is charged
+        let client = A2aClient::new(context.http_client(), context.wallet_provider.clone())
+            .with_bearer_token(params.token.clone());
+        let task = if cancel {
+            client.cancel_task(&rpc_url, task_id).await
+        } else {
+            client.get_task(&rpc_url, task_id).await
+        };
+        let task = match task {
+            Ok(t) => t,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        let output = task.output_text();
+        let error = if matches!(task.status.state, A2aTaskState::Failed | A2aTaskState::Rejected) {
+            Some(output.as_str())
+        } else {
+            None
+        };
+        let result = if error.is_none() && !output.is_empty() { Some(output.as_str()) } else { None };
+        if let Err(e) = db.update_a2a_task_state(A2aDirection::Outbound, task_id, task.status.state, result, error) {
+            log::warn!("[a2a_agent] Failed to update task {}: {}", task_id, e);
+        }
+
+        let mut summary = task_summary(&task);
+        summary["peer"] = json!(rpc_url);
+        ToolResult::success(serde_json::to_string_pretty(&summary).unwrap_or_default())
+    }
+
+    fn list(&self, context: &ToolContext) -> ToolResult {
+        let db = match context.database {
+            Some(ref db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+        let tasks = db.list_a2a_tasks(Some(A2aDirection::Outbound), 20).unwrap_or_default();
+        let rows: Vec<Value> = tasks
+            .iter()
+            .map(|t| {
+                json!({
+                    "task_id": t.task_id,
+                    "context_id": t.context_id,
+                    "peer": t.peer_url,
+                    "peer_agent_id": t.peer_agent_id,
+                    "state": t.state,
+                    "message": t.message.chars().take(200).collect::<String>(),
+                    "result": t.result.as_ref().map(|r| r.chars().take(500).collect::<String>()),
+                    "payment_amount": t.payment_amount,
+                    "created_at": t.created_at,
+                })
+            })
+            .collect();
+        ToolResult::success(serde_json::to_string_pretty(&json!({ "tasks": rows })).unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl Tool for A2aAgentTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: A2aAgentParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        match params.action.as_str() {
+            "discover" => self.discover(context).await,
+            "card" => self.card(&params, context).await,
+            "send" => self.send(&params, context).await,
+            "status" => self.refresh(&params, context, false).await,
+            "cancel" => self.refresh(&params, context, true).await,
+            "list" => self.list(context),
+            other => ToolResult::error(format!(
+                "Unknown action '{}'. Use discover, card, send, status, cancel or list.",
+                other
+            )),
+        }
+    }
+}
@@ -152,10 +152,15 @@ impl Tool for BroadcastWeb3TxTool {
                 )),
             };
 
+            if let Err(e) = context.check_role_tx(&queued_tx) {
+                return ToolResult::error(e);
+            }
+
             // Emit event to open confirmation modal
             if let (Some(broadcaster), Some(ch_id)) = (&context.broadcaster, context.channel_id) {
                 broadcaster.broadcast(GatewayEvent::tx_queue_confirmation_required(
                     ch_id,
+                    context.platform_chat_id.as_deref(),
                     &queued_tx.uuid,
                     &queued_tx.network,
                     &queued_tx.from,
@@ -200,6 +205,10 @@ impl Tool for BroadcastWeb3TxTool {
             )),
         };
 
+        if let Err(e) = context.check_role_tx(&queued_tx) {
+            return ToolResult::error(e);
+        }
+
         // Validate status is Pending
         match queued_tx.status {
             QueuedTxStatus::Pending => {},
@@ -232,8 +241,13 @@ impl Tool for BroadcastWeb3TxTool {
             },
         }
 
-        // Mark as broadcasting
-        tx_queue.mark_broadcasting(&uuid);
+        // Claim it for broadcast; a concurrent approval may have won since the check above
+        if !tx_queue.claim_for_broadcast(&uuid) {
+            return ToolResult::error(format!(
+                "Transaction {} is already being broadcast or is no longer pending.",
+                uuid
+            ));
+        }
 
         // Resolve RPC configuration from context (respects custom RPC settings)
         let rpc_config = resolve_rpc_from_context(&context.extra, &queued_tx.network);
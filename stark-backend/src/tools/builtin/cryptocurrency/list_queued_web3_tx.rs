@@ -308,6 +308,7 @@ impl Tool for ListQueuedWeb3TxTool {
                 if let (Some(broadcaster), Some(ch_id)) = (&context.broadcaster, context.channel_id) {
                     broadcaster.broadcast(GatewayEvent::tx_queue_confirmation_required(
                         ch_id,
+                        context.platform_chat_id.as_deref(),
                         &first_pending.uuid,
                         &first_pending.network,
                         &first_pending.from,
@@ -3,6 +3,7 @@
 //! Tools for interacting with blockchain networks, EVM transactions,
 //! token operations, x402 payment protocol, and prediction markets.
 
+mod a2a_agent;
 mod bridge_usdc;
 mod broadcast_web3_tx;
 pub mod verify_intent;
@@ -28,6 +29,7 @@ mod x402_post;
 mod wallet_monitor;
 mod x402_rpc;
 
+pub use a2a_agent::A2aAgentTool;
 pub use erc8128_fetch::Erc8128FetchTool;
 pub use siwa_auth::SiwaAuthTool;
 pub use bridge_usdc::BridgeUsdcTool;
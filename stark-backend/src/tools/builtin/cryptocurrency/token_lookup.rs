@@ -80,6 +80,15 @@ pub fn get_all_token_symbols() -> Vec<(String, String)> {
     result
 }
 
+/// Decimals of a known token by contract address on a network (None if not in tokens.ron)
+pub fn get_token_decimals(network: &str, address: &str) -> Option<u8> {
+    TOKENS.get()?
+        .get(network)?
+        .values()
+        .find(|t| t.address.eq_ignore_ascii_case(address))
+        .map(|t| t.decimals)
+}
+
 /// Token Lookup tool
 pub struct TokenLookupTool {
     definition: ToolDefinition,
@@ -303,6 +312,11 @@ impl Tool for TokenLookupTool {
     fn safety_level(&self) -> ToolSafetyLevel {
         ToolSafetyLevel::SafeMode
     }
+
+    /// Pure lookup; each call only writes its own `cache_as` register
+    fn parallel_safe(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
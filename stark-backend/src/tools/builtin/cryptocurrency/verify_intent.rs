@@ -101,10 +101,12 @@ async fn run_verification(
         Message {
             role: MessageRole::System,
             content: VERIFICATION_SYSTEM_PROMPT.to_string(),
+            images: Vec::new(),
         },
         Message {
             role: MessageRole::User,
             content: prompt,
+            images: Vec::new(),
         },
     ];
 
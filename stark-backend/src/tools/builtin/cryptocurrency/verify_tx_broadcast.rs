@@ -435,10 +435,12 @@ async fn run_ai_verification(
         Message {
             role: MessageRole::System,
             content: POST_TX_SYSTEM_PROMPT.to_string(),
+            images: Vec::new(),
         },
         Message {
             role: MessageRole::User,
             content: prompt,
+            images: Vec::new(),
         },
     ];
 
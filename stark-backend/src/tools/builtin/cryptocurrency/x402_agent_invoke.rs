@@ -327,6 +327,19 @@ impl Tool for X402AgentInvokeTool {
             return ToolResult::error(e);
         }
 
+        if let Err(e) = crate::x402::payment_limits::check_payment_cap(
+            &payment_option.asset,
+            &payment_option.max_amount_required,
+        ) {
+            return ToolResult::error(e);
+        }
+        if let Err(e) = crate::x402::payment_limits::charge_spend_budget(
+            &payment_option.asset,
+            &payment_option.max_amount_required,
+        ) {
+            return ToolResult::error(e);
+        }
+
         // Get signer
         let signer = match self.get_signer(context) {
             Ok(s) => s,
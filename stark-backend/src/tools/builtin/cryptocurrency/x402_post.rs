@@ -359,6 +359,19 @@ impl Tool for X402PostTool {
 
         let wallet_address = signer.address();
 
+        if let Err(e) = crate::x402::payment_limits::check_payment_cap(
+            &payment_option.asset,
+            &payment_option.max_amount_required,
+        ) {
+            return ToolResult::error(e);
+        }
+        if let Err(e) = crate::x402::payment_limits::charge_spend_budget(
+            &payment_option.asset,
+            &payment_option.max_amount_required,
+        ) {
+            return ToolResult::error(e);
+        }
+
         // Sign payment
         let payment_payload =
             match sign_payment(&signer, &payment_option, payment_info.x402_version).await {
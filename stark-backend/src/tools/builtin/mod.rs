@@ -31,13 +31,14 @@ pub use core::{
     AddTaskTool, DefineTasksTool, AgentSendTool, ApiKeysCheckTool, AskUserTool, HeartbeatConfigTool,
     ImportIdentityTool, InstallApiKeyTool, ManageModulesTool, ManageSkillsTool, MindmapManageTool,
     ReadSkillTool, RegisterNewIdentityTool, WorkstreamTool, ModifySoulTool, SayToUserTool,
-    SetAgentSubtypeTool, SubagentStatusTool, SubagentTool, TaskFullyCompletedTool,
+    SetAgentSubtypeTool, SubagentFanoutTool, SubagentStatusTool, SubagentTool, SubagentWaitTool,
+    TaskFullyCompletedTool,
     // Meta tools (self-management)
     CloudBackupTool, ManageGatewayChannelsTool, ReadOperatingModeTool, ReadRecentTransactionsTool,
     SetThemeAccentTool,
 };
 pub use cryptocurrency::{
-    load_networks, load_tokens, BridgeUsdcTool, BroadcastWeb3TxTool, DecodeCalldataTool,
+    load_networks, load_tokens, A2aAgentTool, BridgeUsdcTool, BroadcastWeb3TxTool, DecodeCalldataTool,
     DexScreenerTool, Erc8128FetchTool, GeckoTerminalTool, ListQueuedWeb3TxTool, PolymarketTradeTool,
     SelectWeb3NetworkTool, SendEthTool, SetAddressTool, SiwaAuthTool, ToRawAmountTool, TokenLookupTool,
     VerifyTxBroadcastTool, Web3PresetFunctionCallTool, X402AgentInvokeTool, X402FetchTool,
@@ -270,6 +270,10 @@ impl Tool for QmdMemoryReadTool {
     fn safety_level(&self) -> ToolSafetyLevel {
         ToolSafetyLevel::SafeMode
     }
+
+    fn parallel_safe(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -164,6 +164,10 @@ impl Tool for QmdMemorySearchTool {
     fn safety_level(&self) -> ToolSafetyLevel {
         ToolSafetyLevel::SafeMode
     }
+
+    fn parallel_safe(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -155,6 +155,10 @@ impl Tool for DiscordLookupTool {
     fn safety_level(&self) -> ToolSafetyLevel {
         ToolSafetyLevel::SafeMode
     }
+
+    fn parallel_safe(&self) -> bool {
+        true
+    }
 }
 
 impl DiscordLookupTool {
@@ -22,6 +22,8 @@ fn register_all_tools(registry: &mut ToolRegistry) {
     // System tools (always available)
     registry.register(Arc::new(builtin::SubagentTool::new()));
     registry.register(Arc::new(builtin::SubagentStatusTool::new()));
+    registry.register(Arc::new(builtin::SubagentFanoutTool::new()));
+    registry.register(Arc::new(builtin::SubagentWaitTool::new()));
     registry.register(Arc::new(builtin::SetAgentSubtypeTool::new()));
     registry.register(Arc::new(builtin::AskUserTool::new()));
     registry.register(Arc::new(builtin::SayToUserTool::new()));
@@ -60,6 +62,8 @@ fn register_all_tools(registry: &mut ToolRegistry) {
     registry.register(Arc::new(builtin::X402FetchTool::new()));
     registry.register(Arc::new(builtin::X402AgentInvokeTool::new()));
     registry.register(Arc::new(builtin::X402PostTool::new()));
+    // A2A protocol client (discovery, x402-paid delegation, remote task tracking)
+    registry.register(Arc::new(builtin::A2aAgentTool::new()));
     // send_eth for simple native ETH transfers (no ABI needed)
     registry.register(Arc::new(builtin::SendEthTool::new()));
     registry.register(Arc::new(builtin::BroadcastWeb3TxTool::new()));
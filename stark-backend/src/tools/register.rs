@@ -280,6 +280,53 @@ impl RegisterStore {
             .unwrap_or_default()
     }
 
+    /// Names of the tools that currently own at least one register entry
+    pub fn source_tools(&self) -> std::collections::HashSet<String> {
+        self.inner
+            .read()
+            .ok()
+            .map(|s| s.values().map(|e| e.source_tool.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Serialize all registers as `{key: {"value": ..., "source_tool": ...}}`
+    ///
+    /// Used to persist registers alongside a session so forks can resume them.
+    pub fn snapshot(&self) -> Value {
+        let store = match self.inner.read() {
+            Ok(s) => s,
+            Err(_) => return json!({}),
+        };
+        let map: serde_json::Map<String, Value> = store
+            .iter()
+            .map(|(key, entry)| {
+                (
+                    key.clone(),
+                    json!({ "value": entry.value, "source_tool": entry.source_tool }),
+                )
+            })
+            .collect();
+        Value::Object(map)
+    }
+
+    /// Restore registers from a `snapshot()` value. Restored entries are
+    /// timestamped now, so staleness checks apply from the time of restore.
+    pub fn restore(&self, snapshot: &Value) {
+        let Some(map) = snapshot.as_object() else {
+            return;
+        };
+        for (key, entry) in map {
+            let Some(value) = entry.get("value") else {
+                continue;
+            };
+            let source_tool = entry
+                .get("source_tool")
+                .and_then(|v| v.as_str())
+                .unwrap_or("restored");
+            self.set(key, value.clone(), source_tool);
+        }
+    }
+
     /// Get age of a register entry in seconds
     pub fn age_secs(&self, key: &str) -> Option<u64> {
         self.get_entry(key)
@@ -381,4 +428,17 @@ mod tests {
         assert_eq!(entry.source_tool, "my_tool");
         assert!(entry.created_at.elapsed().as_secs() < 1);
     }
+
+    #[test]
+    fn test_register_snapshot_restore() {
+        let store = RegisterStore::new();
+        store.set("swap_quote", json!({"to": "0x1234"}), "x402_fetch");
+
+        let restored = RegisterStore::new();
+        restored.restore(&store.snapshot());
+
+        let entry = restored.get_entry("swap_quote").unwrap();
+        assert_eq!(entry.value, json!({"to": "0x1234"}));
+        assert_eq!(entry.source_tool, "x402_fetch");
+    }
 }
@@ -1,5 +1,6 @@
 use crate::ai::multi_agent::types::AgentSubtype;
 use crate::tools::types::{ToolConfig, ToolContext, ToolDefinition, ToolGroup, ToolProfile, ToolResult, ToolSafetyLevel};
+use crate::x402::payment_limits::PaymentCap;
 use async_trait::async_trait;
 use parking_lot::RwLock;
 use serde_json::Value;
@@ -32,6 +33,13 @@ pub trait Tool: Send + Sync {
     fn safety_level(&self) -> ToolSafetyLevel {
         ToolSafetyLevel::Standard
     }
+
+    /// Whether several calls of this tool in one AI response may run concurrently.
+    /// Read-only tools are parallel-safe by default; other tools without side effects
+    /// (lookups that only cache into their own register) can opt in.
+    fn parallel_safe(&self) -> bool {
+        self.safety_level() == ToolSafetyLevel::ReadOnly
+    }
 }
 
 /// Registry that holds all available tools.
@@ -73,6 +81,11 @@ impl ToolRegistry {
         self.tools.read().get(name).cloned()
     }
 
+    /// Whether a registered tool may run concurrently with other calls in the same batch
+    pub fn is_parallel_safe(&self, name: &str) -> bool {
+        self.get(name).is_some_and(|tool| tool.parallel_safe())
+    }
+
     /// List all registered tools
     pub fn list(&self) -> Vec<Arc<dyn Tool>> {
         self.tools.read().values().cloned().collect()
@@ -157,7 +170,7 @@ impl ToolRegistry {
 
     /// Get tool definitions for a specific agent subtype, with additional required tools
     /// that are force-included regardless of config/profile restrictions.
-    /// Used when a skill is activated that requires specific tools.
+    /// Used when the active skill or subtype requires specific tools.
     pub fn get_tool_definitions_for_subtype_with_required(
         &self,
         config: &ToolConfig,
@@ -185,7 +198,7 @@ impl ToolRegistry {
                     };
                     if should_include {
                         log::info!(
-                            "[REGISTRY] Force-including required tool '{}' for active skill",
+                            "[REGISTRY] Force-including required tool '{}' for active skill or subtype",
                             tool_name
                         );
                         tools.push(tool);
@@ -243,8 +256,14 @@ impl ToolRegistry {
             return ToolResult::error(format!("Tool '{}' is not allowed", name));
         }
 
-        // Execute the tool
-        tool.execute(params, context).await
+        // Execute the tool, holding any payment it signs to the caller's spending cap
+        match context.spending_cap() {
+            Some((cap, source)) => {
+                let cap = PaymentCap { cap: cap.to_string(), source: source.to_string() };
+                cap.scope(tool.execute(params, context)).await
+            }
+            None => tool.execute(params, context).await,
+        }
     }
 
     /// Get default configuration
@@ -353,6 +372,36 @@ mod tests {
         assert!(config.is_tool_allowed("safe_tool", ToolGroup::System));
     }
 
+    struct ReadOnlyMockTool(MockTool);
+
+    #[async_trait]
+    impl Tool for ReadOnlyMockTool {
+        fn definition(&self) -> ToolDefinition {
+            self.0.definition()
+        }
+
+        async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+            self.0.execute(params, context).await
+        }
+
+        fn safety_level(&self) -> ToolSafetyLevel {
+            ToolSafetyLevel::ReadOnly
+        }
+    }
+
+    #[test]
+    fn test_parallel_safe_follows_safety_level() {
+        let registry = ToolRegistry::new();
+        registry.register(Arc::new(MockTool::new("exec", ToolGroup::Exec)));
+        registry.register(Arc::new(ReadOnlyMockTool(MockTool::new("read_file", ToolGroup::Filesystem))));
+        registry.register(Arc::new(crate::tools::builtin::AskUserTool::new()));
+
+        assert!(registry.is_parallel_safe("read_file"));
+        assert!(!registry.is_parallel_safe("exec"), "Standard tools run sequentially");
+        assert!(!registry.is_parallel_safe("ask_user"), "ask_user opts out despite being read-only");
+        assert!(!registry.is_parallel_safe("nonexistent"));
+    }
+
     // =========================================================================
     // SAFE MODE ENFORCEMENT TESTS
     //
@@ -7,8 +7,9 @@ use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
 use crate::qmd_memory::MemoryStore;
 use crate::skills::SkillRegistry;
+use crate::tools::builtin::cryptocurrency::token_lookup;
 use crate::tools::register::RegisterStore;
-use crate::tx_queue::TxQueueManager;
+use crate::tx_queue::{QueuedTransaction, TxQueueManager};
 use crate::wallet::WalletProvider;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -26,6 +27,16 @@ pub enum ChannelOutputType {
     RichHtml,
 }
 
+impl ChannelOutputType {
+    /// Output type for a channel type name — only the web UI renders HTML
+    pub fn for_channel(channel_type: &str) -> Self {
+        match channel_type {
+            "web" => Self::RichHtml,
+            _ => Self::TextOnly,
+        }
+    }
+}
+
 /// Safety level for tool access in restricted contexts.
 /// Determines where a tool can be used. Higher levels are available in more contexts.
 /// Defaults to Standard — new tools must explicitly opt in to be available in restricted modes.
@@ -415,6 +426,8 @@ pub struct ToolContext {
     pub tool_http_client: Option<reqwest::Client>,
     /// Disk quota manager for enforcing disk usage limits
     pub disk_quota: Option<Arc<DiskQuotaManager>>,
+    /// Workspace files queued for delivery with the final response (see say_to_user)
+    pub outbound_files: crate::channels::attachments::OutboundFiles,
 }
 
 impl std::fmt::Debug for ToolContext {
@@ -443,6 +456,7 @@ impl std::fmt::Debug for ToolContext {
             .field("proxy_url", &self.proxy_url)
             .field("tool_http_client", &self.tool_http_client.is_some())
             .field("disk_quota", &self.disk_quota.is_some())
+            .field("outbound_files", &self.outbound_files)
             .finish()
     }
 }
@@ -474,6 +488,7 @@ impl Default for ToolContext {
             proxy_url: None,
             tool_http_client: None,
             disk_quota: None,
+            outbound_files: crate::channels::attachments::OutboundFiles::new(),
         }
     }
 }
@@ -485,10 +500,7 @@ impl ToolContext {
 
     pub fn with_channel(mut self, channel_id: i64, channel_type: String) -> Self {
         self.channel_id = Some(channel_id);
-        self.output_type = match channel_type.as_str() {
-            "web" => ChannelOutputType::RichHtml,
-            _ => ChannelOutputType::TextOnly,
-        };
+        self.output_type = ChannelOutputType::for_channel(&channel_type);
         self.channel_type = Some(channel_type);
         self
     }
@@ -700,6 +712,42 @@ impl ToolContext {
         }
     }
 
+    /// The caller's spending cap (role or run) and where it comes from, if any
+    pub fn spending_cap(&self) -> Option<(&str, &str)> {
+        let cap = self.extra.get("max_tx_value_wei").and_then(|v| v.as_str())?;
+        let source = self.extra.get("max_tx_value_source").and_then(|v| v.as_str()).unwrap_or("your role");
+        Some((cap, source))
+    }
+
+    /// Check a transaction against the caller's spending cap (role or run).
+    /// The cap is in 18-decimal units and covers the native `value` and the amount of an
+    /// ERC-20 transfer, transferFrom or approve, scaled from the token's decimals (so 1e18
+    /// allows one ETH or one whole token). Other contract calls (swaps, bridges) are checked
+    /// on their native value; the tokens they pull are bounded by the approval that had to
+    /// pass this check first. Returns Ok(()) when there is no cap.
+    pub fn check_role_tx(&self, tx: &QueuedTransaction) -> Result<(), String> {
+        let Some((cap, source)) = self.spending_cap() else {
+            return Ok(());
+        };
+        // Unparseable values fail closed
+        let Ok(value) = tx.value.parse::<u128>() else {
+            return Err(format!("Could not check transaction value against the spending cap for {}.", source));
+        };
+        check_spending_cap(cap, source, Some(value), "Transaction value")?;
+
+        if let Some(amount) = erc20_amount(&tx.data) {
+            let Some(decimals) = token_lookup::get_token_decimals(&tx.network, &tx.to) else {
+                return Err(format!(
+                    "Token {} on {} is not in the token list, so its amount can't be checked \
+                     against the spending cap for {}.",
+                    tx.to, tx.network, source
+                ));
+            };
+            check_spending_cap(cap, source, amount.and_then(|a| to_cap_units(a, decimals)), "Token amount")?;
+        }
+        Ok(())
+    }
+
     /// Set an HTTP proxy URL for tool requests. Builds a proxy-configured HTTP client.
     /// Does not affect AI model API calls (those use the global shared client directly).
     pub fn with_proxy_url(mut self, url: String) -> Self {
@@ -903,6 +951,50 @@ impl ToolConfig {
     }
 }
 
+/// Scale an amount in an asset's smallest units to the 18-decimal units spending caps
+/// are set in. None when the result doesn't fit (which is over any cap).
+pub fn to_cap_units(amount_raw: u128, decimals: u8) -> Option<u128> {
+    if decimals <= 18 {
+        amount_raw.checked_mul(10u128.pow(18 - decimals as u32))
+    } else {
+        Some(10u128.checked_pow(decimals as u32 - 18).map_or(0, |d| amount_raw / d))
+    }
+}
+
+/// Compare an amount in 18-decimal units (None = too large to represent) with a spending cap
+pub fn check_spending_cap(cap: &str, source: &str, amount: Option<u128>, what: &str) -> Result<(), String> {
+    let Ok(cap_units) = cap.parse::<u128>() else {
+        return Err(format!("Could not read the spending cap for {}.", source));
+    };
+    match amount {
+        Some(amount) if amount <= cap_units => Ok(()),
+        Some(amount) => Err(format!(
+            "{} ({}, in 18-decimal units) exceeds the spending cap for {} ({} per transaction).",
+            what, amount, source, cap_units
+        )),
+        None => Err(format!(
+            "{} exceeds the spending cap for {} ({} per transaction).",
+            what, source, cap_units
+        )),
+    }
+}
+
+/// Amount argument of ERC-20 transfer, approve or transferFrom calldata; None for any other
+/// call. The inner value is None when the amount is missing or doesn't fit in a u128.
+fn erc20_amount(calldata: &str) -> Option<Option<u128>> {
+    let hex = calldata.trim().trim_start_matches("0x");
+    let arg = match hex.get(..8)?.to_ascii_lowercase().as_str() {
+        "a9059cbb" | "095ea7b3" => 1,
+        "23b872dd" => 2,
+        _ => return None,
+    };
+    let word = hex.get(8 + arg * 64..8 + (arg + 1) * 64);
+    Some(word.and_then(|w| {
+        let (high, low) = w.split_at(32);
+        if high.bytes().all(|b| b == b'0') { u128::from_str_radix(low, 16).ok() } else { None }
+    }))
+}
+
 /// Tool execution record for audit logging
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolExecution {
@@ -915,3 +1007,99 @@ pub struct ToolExecution {
     pub duration_ms: Option<i64>,
     pub executed_at: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn capped_context(cap: &str) -> ToolContext {
+        let mut context = ToolContext::new();
+        context.extra.insert("max_tx_value_wei".to_string(), json!(cap));
+        context.extra.insert("max_tx_value_source".to_string(), json!("role 'trader'"));
+        context
+    }
+
+    fn tx(to: &str, value: &str, data: &str) -> QueuedTransaction {
+        QueuedTransaction::new(
+            "uuid".to_string(),
+            "base".to_string(),
+            "0x0000000000000000000000000000000000000001".to_string(),
+            to.to_string(),
+            value.to_string(),
+            data.to_string(),
+            "21000".to_string(),
+            "0".to_string(),
+            "0".to_string(),
+            0,
+            String::new(),
+            None,
+        )
+    }
+
+    /// ERC-20 calldata: selector, then 32-byte words (address args padded, amount in hex)
+    fn erc20_call(selector: &str, args: &[&str]) -> String {
+        let words: String = args.iter().map(|a| format!("{:0>64}", a.trim_start_matches("0x"))).collect();
+        format!("0x{}{}", selector, words)
+    }
+
+    const USDC: &str = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+    const RECIPIENT: &str = "0x00000000000000000000000000000000000000aa";
+
+    #[test]
+    fn test_spending_cap_on_native_value() {
+        // 1000 wei
+        let context = capped_context("1000");
+        assert!(context.check_role_tx(&tx(RECIPIENT, "1000", "0x")).is_ok());
+        assert!(context.check_role_tx(&tx(RECIPIENT, "1001", "")).is_err());
+        assert!(context.check_role_tx(&tx(RECIPIENT, "junk", "0x")).is_err(), "unparseable values fail closed");
+
+        // Other contract calls are held to their native value
+        let swap = "0x3593564c0000000000000000000000000000000000000000000000000000000000000001";
+        assert!(context.check_role_tx(&tx(RECIPIENT, "0", swap)).is_ok());
+        let err = context.check_role_tx(&tx(RECIPIENT, "5000", swap)).unwrap_err();
+        assert!(err.contains("role 'trader'"));
+    }
+
+    #[test]
+    fn test_spending_cap_on_token_amounts() {
+        crate::tools::builtin::cryptocurrency::load_tokens(std::path::Path::new("../config"));
+        // 5 whole tokens in 18-decimal units
+        let context = capped_context("5000000000000000000");
+
+        // USDC has 6 decimals: 5 USDC = 5_000_000 raw
+        let five_usdc = format!("{:x}", 5_000_000u64);
+        let six_usdc = format!("{:x}", 6_000_000u64);
+        let transfer = erc20_call("a9059cbb", &[RECIPIENT, &five_usdc]);
+        assert!(context.check_role_tx(&tx(USDC, "0", &transfer)).is_ok());
+        let approve = erc20_call("095ea7b3", &[RECIPIENT, &six_usdc]);
+        assert!(context.check_role_tx(&tx(USDC, "0", &approve)).is_err());
+        let transfer_from = erc20_call("23b872dd", &[RECIPIENT, RECIPIENT, &six_usdc]);
+        assert!(context.check_role_tx(&tx(USDC, "0", &transfer_from)).is_err());
+
+        // Unlimited approvals and truncated calldata never pass
+        let unlimited = erc20_call("095ea7b3", &[RECIPIENT, &"f".repeat(64)]);
+        assert!(context.check_role_tx(&tx(USDC, "0", &unlimited)).is_err());
+        assert!(context.check_role_tx(&tx(USDC, "0", "0xa9059cbb")).is_err());
+
+        // Tokens with unknown decimals fail closed
+        let unknown = "0x00000000000000000000000000000000000000bb";
+        let err = context.check_role_tx(&tx(unknown, "0", &transfer)).unwrap_err();
+        assert!(err.contains("not in the token list"));
+    }
+
+    #[test]
+    fn test_to_cap_units() {
+        assert_eq!(to_cap_units(1_000_000, 6), Some(1_000_000_000_000_000_000));
+        assert_eq!(to_cap_units(7, 18), Some(7));
+        assert_eq!(to_cap_units(100, 20), Some(1));
+        assert_eq!(to_cap_units(u128::MAX, 6), None);
+    }
+
+    #[test]
+    fn test_no_cap_allows_everything() {
+        let context = ToolContext::new();
+        assert!(context.spending_cap().is_none());
+        assert!(context.check_role_tx(&tx(RECIPIENT, "999999999999999999999", "0xa9059cbb")).is_ok());
+    }
+}
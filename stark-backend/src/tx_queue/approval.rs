@@ -0,0 +1,174 @@
+//! Shared confirm/deny path for partner-mode transactions
+//!
+//! Used by the gateway `tx_queue.confirm` / `tx_queue.deny` RPC methods and by
+//! the in-chat approval buttons on Telegram, Discord and Slack, so every
+//! surface applies the same status checks, expiry and events.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::manager::TxQueueManager;
+use super::types::QueuedTxStatus;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::tools::rpc_config::resolve_rpc_from_network;
+use crate::wallet::WalletProvider;
+use crate::x402::X402EvmRpc;
+
+/// How long a queued transaction can wait for approval before it expires
+pub const TX_APPROVAL_TTL_SECS: i64 = 15 * 60;
+
+/// Result of an approved (broadcast) transaction
+#[derive(Debug, Clone)]
+pub struct TxApproval {
+    pub uuid: String,
+    pub tx_hash: String,
+    pub explorer_url: String,
+}
+
+/// Mark a pending transaction as expired if it outlived the approval window.
+/// Returns true if the transaction is (now) expired.
+pub fn expire_if_stale(tx_queue: &TxQueueManager, uuid: &str) -> bool {
+    match tx_queue.get(uuid) {
+        Some(tx) if tx.status == QueuedTxStatus::Expired => true,
+        Some(tx) if tx.status == QueuedTxStatus::Pending && tx.is_expired(TX_APPROVAL_TTL_SECS) => {
+            tx_queue.mark_expired(uuid)
+        }
+        _ => false,
+    }
+}
+
+/// Approve a pending transaction: broadcast it and emit the result events.
+pub async fn approve_queued_tx(
+    uuid: &str,
+    channel_id: i64,
+    tx_queue: Arc<TxQueueManager>,
+    broadcaster: Arc<EventBroadcaster>,
+    wallet_provider: Option<Arc<dyn WalletProvider>>,
+) -> Result<TxApproval, String> {
+    // Get transaction
+    let tx = tx_queue.get(uuid)
+        .ok_or_else(|| format!("Transaction {} not found", uuid))?;
+
+    // Validate pending status and approval window
+    if expire_if_stale(&tx_queue, uuid) {
+        return Err(format!("Transaction {} has expired", uuid));
+    }
+    if tx.status != QueuedTxStatus::Pending {
+        return Err(format!("Transaction {} is not pending (status: {:?})", uuid, tx.status));
+    }
+
+    // Get wallet provider for x402 payments
+    let wallet_provider = wallet_provider
+        .ok_or_else(|| "Wallet not configured".to_string())?;
+
+    // Claim it; a concurrent approval (e.g. web UI and chat button) may have won already
+    if !tx_queue.claim_for_broadcast(uuid) {
+        return Err(format!("Transaction {} is already being approved or is no longer pending", uuid));
+    }
+
+    // Resolve RPC configuration
+    let rpc_config = resolve_rpc_from_network(&tx.network);
+
+    // Initialize RPC client with WalletProvider (works in both Standard and Flash mode)
+    let rpc = X402EvmRpc::new_with_wallet_provider(
+        wallet_provider,
+        &tx.network,
+        Some(rpc_config.url.clone()),
+        rpc_config.use_x402,
+    ).map_err(|e| {
+        tx_queue.mark_failed(uuid, &e);
+        format!("RPC error: {}", e)
+    })?;
+
+    // Decode signed transaction from hex
+    let signed_tx_bytes = hex::decode(tx.signed_tx_hex.trim_start_matches("0x"))
+        .map_err(|e| {
+            tx_queue.mark_failed(uuid, &format!("Invalid tx hex: {}", e));
+            format!("Invalid tx hex: {}", e)
+        })?;
+
+    // Broadcast the transaction
+    let tx_hash = rpc.send_raw_transaction(&signed_tx_bytes).await
+        .map_err(|e| {
+            tx_queue.mark_failed(uuid, &e);
+            format!("Broadcast failed: {}", e)
+        })?;
+
+    let tx_hash_str = format!("{:?}", tx_hash);
+    let explorer_url = format!("{}/{}", tx.get_explorer_base_url(), tx_hash_str);
+
+    // Mark as broadcast (partner mode - user confirmed)
+    tx_queue.mark_broadcast(uuid, &tx_hash_str, &explorer_url, "partner");
+
+    log::info!("[tx_queue] Transaction {} broadcast as {}", uuid, tx_hash_str);
+
+    // Emit tx.pending event
+    broadcaster.broadcast(GatewayEvent::tx_pending(
+        channel_id, &tx_hash_str, &tx.network, &explorer_url
+    ));
+
+    // Clone values for the spawned task
+    let uuid_clone = uuid.to_string();
+    let network = tx.network.clone();
+    let tx_queue_clone = tx_queue.clone();
+    let broadcaster_clone = broadcaster.clone();
+    let tx_hash_clone = tx_hash_str.clone();
+
+    // Wait for confirmation in a spawned task (don't block the caller)
+    tokio::spawn(async move {
+        match rpc.wait_for_receipt(tx_hash, Duration::from_secs(120)).await {
+            Ok(receipt) => {
+                let status = if receipt.status == Some(ethers::types::U64::from(1)) {
+                    tx_queue_clone.mark_confirmed(&uuid_clone);
+                    "confirmed"
+                } else {
+                    tx_queue_clone.mark_failed(&uuid_clone, "Reverted");
+                    "reverted"
+                };
+                broadcaster_clone.broadcast(GatewayEvent::tx_confirmed(
+                    channel_id, &tx_hash_clone, &network, status
+                ));
+                log::info!("[tx_queue] Transaction {} {}", uuid_clone, status);
+            }
+            Err(e) => {
+                log::warn!("[tx_queue] Receipt wait timeout for {}: {}", uuid_clone, e);
+                // Timeout - tx may still confirm, don't mark as failed
+            }
+        }
+    });
+
+    // Emit tx_queue.confirmed event
+    broadcaster.broadcast(GatewayEvent::tx_queue_confirmed(
+        channel_id, uuid, &tx_hash_str
+    ));
+
+    Ok(TxApproval {
+        uuid: uuid.to_string(),
+        tx_hash: tx_hash_str,
+        explorer_url,
+    })
+}
+
+/// Deny a transaction: remove it from the queue without broadcasting.
+/// Only pending or expired transactions can be denied.
+pub fn deny_queued_tx(
+    uuid: &str,
+    channel_id: i64,
+    tx_queue: &TxQueueManager,
+    broadcaster: &EventBroadcaster,
+) -> Result<(), String> {
+    let tx = tx_queue.get(uuid)
+        .ok_or_else(|| format!("Transaction {} not found", uuid))?;
+    if !matches!(tx.status, QueuedTxStatus::Pending | QueuedTxStatus::Expired) {
+        return Err(format!("Transaction {} can no longer be denied (status: {:?})", uuid, tx.status));
+    }
+
+    tx_queue.remove(uuid);
+
+    // Emit denied event
+    broadcaster.broadcast(GatewayEvent::tx_queue_denied(channel_id, uuid));
+
+    log::info!("[tx_queue] Transaction {} denied and deleted", uuid);
+    Ok(())
+}
@@ -30,18 +30,46 @@ impl TxQueueManager {
         }
     }
 
-    /// Create a new transaction queue manager with database persistence
+    /// Create a new transaction queue manager with database persistence.
+    /// Transactions that were still awaiting approval before a restart are restored.
     pub fn with_db(db: Arc<Database>) -> Self {
+        let transactions = DashMap::new();
+        match db.list_queued_transactions() {
+            Ok(txs) => {
+                if !txs.is_empty() {
+                    log::info!("[TxQueue] Restored {} pending transactions", txs.len());
+                }
+                for tx in txs {
+                    transactions.insert(tx.uuid.clone(), tx);
+                }
+            }
+            Err(e) => log::error!("[TxQueue] Failed to restore pending transactions: {}", e),
+        }
         Self {
-            transactions: DashMap::new(),
+            transactions,
             db: Some(db),
         }
     }
 
+    /// Mirror a transaction's pending state to the database: pending transactions are
+    /// stored, anything else is removed so it can't come back as pending after a restart
+    fn sync_pending(&self, tx: &QueuedTransaction) {
+        let Some(ref db) = self.db else { return };
+        let result = if tx.status == QueuedTxStatus::Pending {
+            db.save_queued_transaction(tx)
+        } else {
+            db.delete_queued_transaction(&tx.uuid).map(|_| ())
+        };
+        if let Err(e) = result {
+            log::error!("[TxQueue] Failed to persist queued transaction {}: {}", tx.uuid, e);
+        }
+    }
+
     /// Queue a new transaction
     pub fn queue(&self, tx: QueuedTransaction) -> String {
         let uuid = tx.uuid.clone();
         log::info!("[TxQueue] Queuing transaction {} to {}", uuid, tx.to);
+        self.sync_pending(&tx);
         self.transactions.insert(uuid.clone(), tx);
         uuid
     }
@@ -93,20 +121,49 @@ impl TxQueueManager {
             .collect()
     }
 
+    /// List transactions queued from a given channel (oldest first)
+    pub fn list_all_for_channel(&self, channel_id: i64) -> Vec<QueuedTxSummary> {
+        let mut txs: Vec<_> = self.transactions
+            .iter()
+            .filter(|r| r.value().channel_id == Some(channel_id))
+            .map(|r| QueuedTxSummary::from(r.value()))
+            .collect();
+        txs.sort_by_key(|t| t.created_at);
+        txs
+    }
+
     /// Update transaction status
     pub fn update_status(&self, uuid: &str, status: QueuedTxStatus) -> bool {
         if let Some(mut tx) = self.transactions.get_mut(uuid) {
             log::info!("[TxQueue] Updating {} status to {:?}", uuid, status);
             tx.status = status;
+            self.sync_pending(&tx);
             true
         } else {
             false
         }
     }
 
-    /// Mark transaction as broadcasting
-    pub fn mark_broadcasting(&self, uuid: &str) -> bool {
-        self.update_status(uuid, QueuedTxStatus::Broadcasting)
+    /// Move a transaction from one status to another only if it is still in `from`.
+    /// The check and the update happen under the same entry lock.
+    fn transition(&self, uuid: &str, from: QueuedTxStatus, to: QueuedTxStatus) -> bool {
+        let Some(mut tx) = self.transactions.get_mut(uuid) else {
+            return false;
+        };
+        if tx.status != from {
+            return false;
+        }
+        log::info!("[TxQueue] Updating {} status from {:?} to {:?}", uuid, from, to);
+        tx.status = to;
+        self.sync_pending(&tx);
+        true
+    }
+
+    /// Claim a pending transaction for broadcast (Pending → Broadcasting).
+    /// Returns false if it is missing or no longer pending, e.g. because a concurrent
+    /// approval already claimed it; the caller must not broadcast in that case.
+    pub fn claim_for_broadcast(&self, uuid: &str) -> bool {
+        self.transition(uuid, QueuedTxStatus::Pending, QueuedTxStatus::Broadcasting)
     }
 
     /// Mark transaction as broadcast with tx_hash
@@ -118,6 +175,7 @@ impl TxQueueManager {
             tx.tx_hash = Some(tx_hash.to_string());
             tx.explorer_url = Some(explorer_url.to_string());
             tx.broadcast_at = Some(Utc::now());
+            self.sync_pending(&tx);
 
             // Persist to database if available
             if let Some(ref db) = self.db {
@@ -172,6 +230,7 @@ impl TxQueueManager {
             log::warn!("[TxQueue] Transaction {} failed: {}", uuid, error);
             tx.status = QueuedTxStatus::Failed;
             tx.error = Some(error.to_string());
+            self.sync_pending(&tx);
 
             // Update database status if available
             if let Some(ref db) = self.db {
@@ -186,15 +245,14 @@ impl TxQueueManager {
         }
     }
 
-    /// Mark transaction as expired
+    /// Mark a pending transaction as expired. Returns false if it is missing or no
+    /// longer pending (a concurrent approval may have claimed it first).
     pub fn mark_expired(&self, uuid: &str) -> bool {
-        if let Some(mut tx) = self.transactions.get_mut(uuid) {
+        let expired = self.transition(uuid, QueuedTxStatus::Pending, QueuedTxStatus::Expired);
+        if expired {
             log::warn!("[TxQueue] Transaction {} expired", uuid);
-            tx.status = QueuedTxStatus::Expired;
-            true
-        } else {
-            false
         }
+        expired
     }
 
     /// Get count of transactions by status
@@ -212,6 +270,11 @@ impl TxQueueManager {
 
     /// Remove a transaction by UUID (for cleanup)
     pub fn remove(&self, uuid: &str) -> Option<QueuedTransaction> {
+        if let Some(ref db) = self.db
+            && let Err(e) = db.delete_queued_transaction(uuid)
+        {
+            log::error!("[TxQueue] Failed to delete queued transaction {}: {}", uuid, e);
+        }
         self.transactions.remove(uuid).map(|(_, tx)| tx)
     }
 
@@ -231,7 +294,7 @@ impl TxQueueManager {
 
         let count = old_uuids.len();
         for uuid in old_uuids {
-            self.transactions.remove(&uuid);
+            self.remove(&uuid);
         }
 
         if count > 0 {
@@ -295,8 +358,8 @@ mod tests {
         let tx = manager.get("test-uuid-2").unwrap();
         assert_eq!(tx.status, QueuedTxStatus::Pending);
 
-        // Mark as broadcasting
-        assert!(manager.mark_broadcasting("test-uuid-2"));
+        // Claim for broadcast
+        assert!(manager.claim_for_broadcast("test-uuid-2"));
         let tx = manager.get("test-uuid-2").unwrap();
         assert_eq!(tx.status, QueuedTxStatus::Broadcasting);
 
@@ -332,4 +395,33 @@ mod tests {
         assert_eq!(pending.len(), 1);
         assert_eq!(pending[0].uuid, "pending-2");
     }
+
+    #[test]
+    fn test_claim_for_broadcast_only_once() {
+        let manager = TxQueueManager::new();
+        manager.queue(create_test_tx("claim-1"));
+
+        assert!(manager.claim_for_broadcast("claim-1"));
+        // A second approval loses and must not touch the winner's status
+        assert!(!manager.claim_for_broadcast("claim-1"));
+        assert!(!manager.mark_expired("claim-1"));
+        assert_eq!(manager.get("claim-1").unwrap().status, QueuedTxStatus::Broadcasting);
+        assert!(!manager.claim_for_broadcast("missing"));
+    }
+
+    #[test]
+    fn test_pending_transactions_survive_restart() {
+        let db = Arc::new(Database::new(":memory:").expect("in-memory db"));
+        let manager = TxQueueManager::with_db(db.clone());
+        manager.queue(create_test_tx("restart-pending"));
+        manager.queue(create_test_tx("restart-expired"));
+        manager.queue(create_test_tx("restart-claimed"));
+        assert!(manager.mark_expired("restart-expired"));
+        assert!(manager.claim_for_broadcast("restart-claimed"));
+
+        let restarted = TxQueueManager::with_db(db);
+        assert_eq!(restarted.get("restart-pending").unwrap().status, QueuedTxStatus::Pending);
+        assert!(restarted.get("restart-expired").is_none());
+        assert!(restarted.get("restart-claimed").is_none());
+    }
 }
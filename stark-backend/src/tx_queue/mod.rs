@@ -8,9 +8,13 @@
 //! 3. `broadcast_web3_tx` broadcasts a transaction by UUID
 //!
 //! This creates a safety layer where transactions can be reviewed before broadcast.
+//! In partner mode the user approves or denies each transaction, either in the
+//! web UI or via in-chat buttons; both go through `approval`.
 
 mod types;
 mod manager;
+pub mod approval;
 
 pub use types::{QueuedTransaction, QueuedTxStatus, QueuedTxSummary};
 pub use manager::{TxQueueManager, create_tx_queue_manager};
+pub use approval::{approve_queued_tx, deny_queued_tx, expire_if_stale, TX_APPROVAL_TTL_SECS};
@@ -123,6 +123,16 @@ impl QueuedTransaction {
         self
     }
 
+    /// When this transaction stops being eligible for approval
+    pub fn expires_at(&self, ttl_secs: i64) -> DateTime<Utc> {
+        self.created_at + chrono::Duration::seconds(ttl_secs)
+    }
+
+    /// Whether the approval window has passed
+    pub fn is_expired(&self, ttl_secs: i64) -> bool {
+        Utc::now() >= self.expires_at(ttl_secs)
+    }
+
     /// Get the explorer URL for this transaction's network
     pub fn get_explorer_base_url(&self) -> &'static str {
         if self.network == "mainnet" {
@@ -0,0 +1,273 @@
+//! Workflow definitions (RON), as declared by a skill
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// Tools a workflow step may never call (they would re-enter the workflow machinery)
+const RESERVED_TOOLS: &[&str] = &["use_skill", "workflow", "define_tasks"];
+
+/// A declarative multi-step workflow attached to a skill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowDefinition {
+    /// Values the agent must supply when starting the workflow
+    #[serde(default)]
+    pub inputs: Vec<WorkflowInput>,
+    pub steps: Vec<WorkflowStep>,
+}
+
+/// A named workflow input, referenced from steps as `{{name}}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowInput {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Used when the agent does not supply a value; inputs without a default are required
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+/// One step of a workflow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowStep {
+    /// Unique step ID; tool and input outputs are stored under it (or `save_as`)
+    pub id: String,
+    pub action: StepAction,
+    /// Step is skipped when the condition is false
+    #[serde(default)]
+    pub when: Option<Condition>,
+    /// Extra attempts for a failing tool call
+    #[serde(default)]
+    pub retries: u32,
+    #[serde(default = "default_retry_delay_secs")]
+    pub retry_delay_secs: u64,
+    /// A failing optional step is logged and the workflow moves on
+    #[serde(default)]
+    pub optional: bool,
+    /// Variable name for the step output (defaults to the step ID)
+    #[serde(default)]
+    pub save_as: Option<String>,
+}
+
+fn default_retry_delay_secs() -> u64 {
+    2
+}
+
+impl WorkflowStep {
+    /// Variable the step output is stored under
+    pub fn output_name(&self) -> &str {
+        self.save_as.as_deref().unwrap_or(&self.id)
+    }
+}
+
+/// What a step does
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum StepAction {
+    /// Call a tool. String arguments are templates; use `cache_as` to bind the result to a register.
+    Tool {
+        tool: String,
+        #[serde(default)]
+        args: Map<String, Value>,
+    },
+    /// Pause until the user approves or rejects in a later message
+    Approval { prompt: String },
+    /// Pause and let the agent supply the named values (the only place the model fills gaps)
+    Input {
+        prompt: String,
+        outputs: Vec<String>,
+    },
+}
+
+/// Step guard. Operands are templates; comparisons of integers work on arbitrarily large values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Condition {
+    /// A variable or register is set
+    Exists(String),
+    /// A variable or register is not set
+    Missing(String),
+    Equals(String, String),
+    NotEquals(String, String),
+    LessThan(String, String),
+    GreaterThan(String, String),
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+impl WorkflowDefinition {
+    /// Parse and validate a RON workflow. `requires_tools` is the owning skill's tool list;
+    /// every tool step must be in it so the workflow gets the same permissions as the skill.
+    pub fn parse(source: &str, requires_tools: &[String]) -> Result<Self, String> {
+        let definition: WorkflowDefinition =
+            ron::from_str(source).map_err(|e| format!("Invalid workflow: {}", e))?;
+        definition.validate(requires_tools)?;
+        Ok(definition)
+    }
+
+    fn validate(&self, requires_tools: &[String]) -> Result<(), String> {
+        if self.steps.is_empty() {
+            return Err("Workflow has no steps".to_string());
+        }
+
+        let mut names = HashSet::new();
+        for input in &self.inputs {
+            if !is_identifier(&input.name) {
+                return Err(format!("Invalid input name '{}'", input.name));
+            }
+            if !names.insert(input.name.as_str()) {
+                return Err(format!("Input '{}' is declared more than once", input.name));
+            }
+        }
+
+        let mut step_ids = HashSet::new();
+        for step in &self.steps {
+            if !is_identifier(&step.id) {
+                return Err(format!("Invalid step id '{}'", step.id));
+            }
+            if !step_ids.insert(step.id.as_str()) {
+                return Err(format!("Step '{}' is declared more than once", step.id));
+            }
+            if let Some(name) = step.save_as.as_deref().filter(|n| !is_identifier(n)) {
+                return Err(format!("Step '{}' has an invalid save_as '{}'", step.id, name));
+            }
+
+            match &step.action {
+                StepAction::Tool { tool, .. } => {
+                    if RESERVED_TOOLS.contains(&tool.as_str()) {
+                        return Err(format!("Step '{}' cannot call '{}'", step.id, tool));
+                    }
+                    if !requires_tools.iter().any(|t| t == tool) {
+                        return Err(format!(
+                            "Step '{}' calls '{}', which is not in the skill's requires_tools",
+                            step.id, tool
+                        ));
+                    }
+                }
+                StepAction::Input { outputs, .. } => {
+                    if outputs.is_empty() {
+                        return Err(format!("Input step '{}' declares no outputs", step.id));
+                    }
+                    if let Some(bad) = outputs.iter().find(|o| !is_identifier(o)) {
+                        return Err(format!("Step '{}' has an invalid output name '{}'", step.id, bad));
+                    }
+                }
+                StepAction::Approval { .. } => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Bind the agent-supplied inputs, applying defaults. Fails listing any missing required input.
+    pub fn bind_inputs(&self, supplied: &Map<String, Value>) -> Result<Map<String, Value>, String> {
+        let mut bound = Map::new();
+        let mut missing = Vec::new();
+
+        for input in &self.inputs {
+            let value = supplied
+                .get(&input.name)
+                .filter(|v| !v.is_null() && v.as_str().is_none_or(|s| !s.trim().is_empty()))
+                .cloned()
+                .or_else(|| input.default.clone().map(Value::String));
+            match value {
+                Some(v) => {
+                    bound.insert(input.name.clone(), v);
+                }
+                None => missing.push(input.name.as_str()),
+            }
+        }
+
+        if missing.is_empty() {
+            Ok(bound)
+        } else {
+            Err(format!("Missing required workflow inputs: {}", missing.join(", ")))
+        }
+    }
+
+    /// Human-readable list of inputs, for the agent
+    pub fn describe_inputs(&self) -> String {
+        self.inputs
+            .iter()
+            .map(|input| {
+                let requirement = match input.default {
+                    Some(ref d) => format!("optional, default \"{}\"", d),
+                    None => "required".to_string(),
+                };
+                format!("- `{}` ({}): {}", input.name, requirement, input.description)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const WORKFLOW: &str = r#"(
+        inputs: [
+            (name: "symbol", description: "Token symbol"),
+            (name: "network", default: Some("base")),
+        ],
+        steps: [
+            (id: "lookup", action: Tool(tool: "token_lookup", args: {"symbol": "{{symbol}}", "cache_as": "token"}), retries: 2),
+            (id: "confirm", action: Approval(prompt: "Continue with {{symbol}}?"), when: Some(Exists("token"))),
+            (id: "note", action: Input(prompt: "Summarize", outputs: ["summary"])),
+        ],
+    )"#;
+
+    fn tools() -> Vec<String> {
+        vec!["token_lookup".to_string()]
+    }
+
+    #[test]
+    fn test_parse_workflow() {
+        let wf = WorkflowDefinition::parse(WORKFLOW, &tools()).unwrap();
+        assert_eq!(wf.steps.len(), 3);
+        assert_eq!(wf.steps[0].retries, 2);
+        assert_eq!(wf.steps[1].retries, 0);
+        match &wf.steps[0].action {
+            StepAction::Tool { tool, args } => {
+                assert_eq!(tool, "token_lookup");
+                assert_eq!(args.get("cache_as"), Some(&json!("token")));
+            }
+            other => panic!("unexpected action {:?}", other),
+        }
+        assert!(matches!(wf.steps[1].when, Some(Condition::Exists(_))));
+        assert_eq!(wf.steps[2].id, "note");
+    }
+
+    #[test]
+    fn test_tool_must_be_required_by_skill() {
+        let err = WorkflowDefinition::parse(WORKFLOW, &[]).unwrap_err();
+        assert!(err.contains("requires_tools"), "{}", err);
+    }
+
+    #[test]
+    fn test_duplicate_step_rejected() {
+        let source = r#"(steps: [
+            (id: "a", action: Approval(prompt: "x")),
+            (id: "a", action: Approval(prompt: "y")),
+        ])"#;
+        assert!(WorkflowDefinition::parse(source, &[]).is_err());
+    }
+
+    #[test]
+    fn test_bind_inputs_applies_defaults() {
+        let wf = WorkflowDefinition::parse(WORKFLOW, &tools()).unwrap();
+        let bound = wf.bind_inputs(json!({"symbol": "USDC"}).as_object().unwrap()).unwrap();
+        assert_eq!(bound.get("network"), Some(&json!("base")));
+
+        let err = wf.bind_inputs(&Map::new()).unwrap_err();
+        assert!(err.contains("symbol"));
+        assert!(!err.contains("network"));
+    }
+}
@@ -0,0 +1,608 @@
+//! Deterministic workflow execution
+//!
+//! `advance` runs steps from the run's current position until the workflow finishes,
+//! fails, or reaches a step that needs someone else: an approval gate (the user) or an
+//! input step (the agent). The run is checkpointed after every step through the
+//! caller-supplied callback, so a crash loses at most the step that was executing.
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::time::Duration;
+
+use super::definition::{Condition, StepAction, WorkflowDefinition, WorkflowStep};
+use crate::models::{WorkflowRun, WorkflowRunStatus, WorkflowStepRecord};
+use crate::tools::{RegisterStore, ToolResult};
+
+static PLACEHOLDER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*([A-Za-z0-9_.]+)\s*\}\}").unwrap());
+
+/// Longest tool output kept in the step log
+const MAX_DETAIL_CHARS: usize = 300;
+
+/// Executes the tool calls of a workflow (the dispatcher in production)
+#[async_trait]
+pub trait StepRunner: Send + Sync {
+    async fn run_tool(&self, tool: &str, args: &Value) -> ToolResult;
+}
+
+/// Where `advance` stopped
+#[derive(Debug, Clone, PartialEq)]
+pub enum Advance {
+    Completed,
+    AwaitingInput { step: String, prompt: String, outputs: Vec<String> },
+    AwaitingApproval { step: String, prompt: String },
+    Failed { step: String, error: String },
+}
+
+/// Run steps until the workflow completes, fails or pauses
+pub async fn advance(
+    definition: &WorkflowDefinition,
+    run: &mut WorkflowRun,
+    registers: &RegisterStore,
+    runner: &dyn StepRunner,
+    checkpoint: &mut (dyn FnMut(&WorkflowRun) + Send),
+) -> Advance {
+    loop {
+        let Some(step) = definition.steps.get(run.current_step) else {
+            run.status = WorkflowRunStatus::Completed;
+            run.paused_at = None;
+            save(run, registers, checkpoint);
+            return Advance::Completed;
+        };
+
+        // Re-entering a step we are already paused at keeps the original pause
+        match (&step.action, run.status) {
+            (StepAction::Approval { prompt }, WorkflowRunStatus::AwaitingApproval) => {
+                return Advance::AwaitingApproval {
+                    step: step.id.clone(),
+                    prompt: render_text(prompt, &run.variables, registers).unwrap_or_else(|_| prompt.clone()),
+                };
+            }
+            (StepAction::Input { prompt, outputs }, WorkflowRunStatus::AwaitingInput) => {
+                return Advance::AwaitingInput {
+                    step: step.id.clone(),
+                    prompt: render_text(prompt, &run.variables, registers).unwrap_or_else(|_| prompt.clone()),
+                    outputs: outputs.clone(),
+                };
+            }
+            _ => {}
+        }
+
+        if let Some(ref condition) = step.when {
+            match evaluate(condition, &run.variables, registers) {
+                Ok(true) => {}
+                Ok(false) => {
+                    log::info!("[WORKFLOW] {} step '{}' skipped (condition false)", run.skill_name, step.id);
+                    record(run, step, "skipped", None, 0, "condition not met".to_string());
+                    run.current_step += 1;
+                    save(run, registers, checkpoint);
+                    continue;
+                }
+                Err(e) => return fail(run, step, registers, checkpoint, format!("Condition error: {}", e)),
+            }
+        }
+
+        match &step.action {
+            StepAction::Tool { tool, args } => {
+                let args = match render_value(&Value::Object(args.clone()), &run.variables, registers) {
+                    Ok(a) => a,
+                    Err(e) => {
+                        if step.optional {
+                            record(run, step, "failed", Some(tool.as_str()), 0, e);
+                            run.current_step += 1;
+                            save(run, registers, checkpoint);
+                            continue;
+                        }
+                        return fail(run, step, registers, checkpoint, e);
+                    }
+                };
+
+                // Checkpoint before the call: a run found "running" after a restart was mid-step
+                run.status = WorkflowRunStatus::Running;
+                save(run, registers, checkpoint);
+
+                let mut attempts = 0;
+                let result = loop {
+                    attempts += 1;
+                    let result = runner.run_tool(tool, &args).await;
+                    if result.success || attempts > step.retries {
+                        break result;
+                    }
+                    let delay = result.retry_after_secs.unwrap_or(0).max(step.retry_delay_secs);
+                    log::warn!(
+                        "[WORKFLOW] {} step '{}' attempt {} failed, retrying in {}s: {}",
+                        run.skill_name, step.id, attempts, delay,
+                        result.error.as_deref().unwrap_or(&result.content)
+                    );
+                    if delay > 0 {
+                        tokio::time::sleep(Duration::from_secs(delay)).await;
+                    }
+                };
+
+                if result.success {
+                    run.variables.insert(step.output_name().to_string(), step_output(&result));
+                    record(run, step, "done", Some(tool.as_str()), attempts, truncate(&result.content));
+                    run.current_step += 1;
+                    save(run, registers, checkpoint);
+                } else {
+                    let error = result.error.clone().unwrap_or_else(|| result.content.clone());
+                    if step.optional {
+                        record(run, step, "failed", Some(tool.as_str()), attempts, truncate(&error));
+                        run.current_step += 1;
+                        save(run, registers, checkpoint);
+                    } else {
+                        let error = format!("{} failed after {} attempt(s): {}", tool, attempts, error);
+                        return fail(run, step, registers, checkpoint, error);
+                    }
+                }
+            }
+            StepAction::Approval { prompt } => {
+                let prompt = match render_text(prompt, &run.variables, registers) {
+                    Ok(p) => p,
+                    Err(e) => return fail(run, step, registers, checkpoint, e),
+                };
+                run.status = WorkflowRunStatus::AwaitingApproval;
+                run.paused_at = Some(chrono::Utc::now().to_rfc3339());
+                save(run, registers, checkpoint);
+                return Advance::AwaitingApproval { step: step.id.clone(), prompt };
+            }
+            StepAction::Input { prompt, outputs } => {
+                let prompt = match render_text(prompt, &run.variables, registers) {
+                    Ok(p) => p,
+                    Err(e) => return fail(run, step, registers, checkpoint, e),
+                };
+                run.status = WorkflowRunStatus::AwaitingInput;
+                run.paused_at = Some(chrono::Utc::now().to_rfc3339());
+                save(run, registers, checkpoint);
+                return Advance::AwaitingInput {
+                    step: step.id.clone(),
+                    prompt,
+                    outputs: outputs.clone(),
+                };
+            }
+        }
+    }
+}
+
+/// Pass the approval gate the run is paused at
+pub fn approve(definition: &WorkflowDefinition, run: &mut WorkflowRun) -> Result<(), String> {
+    let step = paused_step(definition, run, WorkflowRunStatus::AwaitingApproval)?;
+    record(run, step, "approved", None, 0, String::new());
+    run.current_step += 1;
+    run.status = WorkflowRunStatus::Running;
+    run.paused_at = None;
+    Ok(())
+}
+
+/// Stop the run at the approval gate it is paused at
+pub fn reject(definition: &WorkflowDefinition, run: &mut WorkflowRun, reason: &str) -> Result<(), String> {
+    let step = paused_step(definition, run, WorkflowRunStatus::AwaitingApproval)?;
+    record(run, step, "rejected", None, 0, reason.to_string());
+    run.status = WorkflowRunStatus::Canceled;
+    run.error = Some(format!("Rejected at step '{}'", step.id));
+    run.paused_at = None;
+    Ok(())
+}
+
+/// Fill in the values an input step asked for
+pub fn provide(
+    definition: &WorkflowDefinition,
+    run: &mut WorkflowRun,
+    values: &Map<String, Value>,
+) -> Result<(), String> {
+    let step = paused_step(definition, run, WorkflowRunStatus::AwaitingInput)?;
+    let StepAction::Input { ref outputs, .. } = step.action else {
+        return Err(format!("Step '{}' does not take input", step.id));
+    };
+
+    let missing: Vec<&str> = outputs
+        .iter()
+        .filter(|o| values.get(*o).is_none_or(|v| v.is_null()))
+        .map(|o| o.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("Missing values for: {}", missing.join(", ")));
+    }
+
+    for output in outputs {
+        run.variables.insert(output.clone(), values[output].clone());
+    }
+    record(run, step, "done", None, 0, truncate(&Value::Object(values.clone()).to_string()));
+    run.current_step += 1;
+    run.status = WorkflowRunStatus::Running;
+    run.paused_at = None;
+    Ok(())
+}
+
+fn paused_step<'a>(
+    definition: &'a WorkflowDefinition,
+    run: &WorkflowRun,
+    expected: WorkflowRunStatus,
+) -> Result<&'a WorkflowStep, String> {
+    if run.status != expected {
+        return Err(format!(
+            "Workflow is {}, not {}",
+            run.status.as_str(),
+            expected.as_str()
+        ));
+    }
+    definition
+        .steps
+        .get(run.current_step)
+        .ok_or_else(|| "Workflow has no current step".to_string())
+}
+
+fn fail(
+    run: &mut WorkflowRun,
+    step: &WorkflowStep,
+    registers: &RegisterStore,
+    checkpoint: &mut (dyn FnMut(&WorkflowRun) + Send),
+    error: String,
+) -> Advance {
+    log::warn!("[WORKFLOW] {} failed at step '{}': {}", run.skill_name, step.id, error);
+    let tool = match step.action {
+        StepAction::Tool { ref tool, .. } => Some(tool.as_str()),
+        _ => None,
+    };
+    record(run, step, "failed", tool, 0, truncate(&error));
+    run.status = WorkflowRunStatus::Failed;
+    run.error = Some(error.clone());
+    save(run, registers, checkpoint);
+    Advance::Failed { step: step.id.clone(), error }
+}
+
+fn record(run: &mut WorkflowRun, step: &WorkflowStep, outcome: &str, tool: Option<&str>, attempts: u32, detail: String) {
+    run.step_log.push(WorkflowStepRecord {
+        step: step.id.clone(),
+        outcome: outcome.to_string(),
+        tool: tool.map(|t| t.to_string()),
+        attempts,
+        detail,
+        at: chrono::Utc::now().to_rfc3339(),
+    });
+}
+
+fn save(run: &mut WorkflowRun, registers: &RegisterStore, checkpoint: &mut (dyn FnMut(&WorkflowRun) + Send)) {
+    run.registers = registers.snapshot();
+    checkpoint(run);
+}
+
+/// Value stored for a tool step: its metadata fields (plus `content`), else the
+/// output parsed as JSON, else the raw text
+fn step_output(result: &ToolResult) -> Value {
+    let content = serde_json::from_str::<Value>(&result.content)
+        .unwrap_or_else(|_| Value::String(result.content.clone()));
+    match result.metadata {
+        Some(Value::Object(ref metadata)) if !metadata.is_empty() => {
+            let mut output = metadata.clone();
+            output.entry("content").or_insert(content);
+            Value::Object(output)
+        }
+        _ => content,
+    }
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_DETAIL_CHARS {
+        text.to_string()
+    } else {
+        format!("{}…", text.chars().take(MAX_DETAIL_CHARS).collect::<String>())
+    }
+}
+
+/// Resolve `name.field.0` against workflow variables, then registers
+pub fn lookup(path: &str, variables: &Map<String, Value>, registers: &RegisterStore) -> Option<Value> {
+    let mut segments = path.split('.');
+    let root = segments.next()?;
+    let mut value = match variables.get(root) {
+        Some(v) => v.clone(),
+        None => registers.get_entry_or_intrinsic(root)?.value,
+    };
+    for segment in segments {
+        value = match value {
+            Value::Object(mut map) => map.remove(segment)?,
+            Value::Array(mut items) => {
+                let index: usize = segment.parse().ok()?;
+                if index >= items.len() {
+                    return None;
+                }
+                items.swap_remove(index)
+            }
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+fn value_to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Substitute `{{...}}` placeholders in text
+pub fn render_text(template: &str, variables: &Map<String, Value>, registers: &RegisterStore) -> Result<String, String> {
+    let mut unresolved = None;
+    let rendered = PLACEHOLDER.replace_all(template, |caps: &regex::Captures| {
+        match lookup(&caps[1], variables, registers) {
+            Some(value) => value_to_text(&value),
+            None => {
+                unresolved.get_or_insert_with(|| caps[1].to_string());
+                String::new()
+            }
+        }
+    });
+    match unresolved {
+        Some(name) => Err(format!("Unresolved reference '{{{{{}}}}}'", name)),
+        None => Ok(rendered.into_owned()),
+    }
+}
+
+/// Render templates inside a JSON value. A string that is exactly one placeholder
+/// takes the referenced value as-is (numbers and objects keep their type).
+pub fn render_value(value: &Value, variables: &Map<String, Value>, registers: &RegisterStore) -> Result<Value, String> {
+    match value {
+        Value::String(s) => {
+            if let Some(caps) = PLACEHOLDER.captures(s.trim()).filter(|c| c[0].len() == s.trim().len()) {
+                return lookup(&caps[1], variables, registers)
+                    .ok_or_else(|| format!("Unresolved reference '{}'", s.trim()));
+            }
+            render_text(s, variables, registers).map(Value::String)
+        }
+        Value::Array(items) => items
+            .iter()
+            .map(|item| render_value(item, variables, registers))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        Value::Object(map) => {
+            let mut rendered = Map::new();
+            for (key, item) in map {
+                rendered.insert(key.clone(), render_value(item, variables, registers)?);
+            }
+            Ok(Value::Object(rendered))
+        }
+        other => Ok(other.clone()),
+    }
+}
+
+/// Evaluate a step condition
+pub fn evaluate(condition: &Condition, variables: &Map<String, Value>, registers: &RegisterStore) -> Result<bool, String> {
+    let is_set = |name: &str| {
+        let name = name.trim().trim_start_matches("{{").trim_end_matches("}}").trim();
+        match lookup(name, variables, registers) {
+            None | Some(Value::Null) => false,
+            Some(Value::String(s)) => !s.is_empty(),
+            Some(_) => true,
+        }
+    };
+    let operands = |a: &str, b: &str| -> Result<(String, String), String> {
+        Ok((render_text(a, variables, registers)?, render_text(b, variables, registers)?))
+    };
+
+    Ok(match condition {
+        Condition::Exists(name) => is_set(name),
+        Condition::Missing(name) => !is_set(name),
+        Condition::Equals(a, b) => {
+            let (a, b) = operands(a, b)?;
+            compare(&a, &b).map_or(a == b, |o| o == Ordering::Equal)
+        }
+        Condition::NotEquals(a, b) => {
+            let (a, b) = operands(a, b)?;
+            !compare(&a, &b).map_or(a == b, |o| o == Ordering::Equal)
+        }
+        Condition::LessThan(a, b) => {
+            let (a, b) = operands(a, b)?;
+            compare(&a, &b).ok_or_else(|| format!("Cannot compare '{}' and '{}' as numbers", a, b))? == Ordering::Less
+        }
+        Condition::GreaterThan(a, b) => {
+            let (a, b) = operands(a, b)?;
+            compare(&a, &b).ok_or_else(|| format!("Cannot compare '{}' and '{}' as numbers", a, b))? == Ordering::Greater
+        }
+        Condition::All(conditions) => {
+            for c in conditions {
+                if !evaluate(c, variables, registers)? {
+                    return Ok(false);
+                }
+            }
+            true
+        }
+        Condition::Any(conditions) => {
+            for c in conditions {
+                if evaluate(c, variables, registers)? {
+                    return Ok(true);
+                }
+            }
+            false
+        }
+        Condition::Not(inner) => !evaluate(inner, variables, registers)?,
+    })
+}
+
+/// Numeric comparison. Unsigned integers are compared exactly at any size (token amounts
+/// in wei overflow f64); anything else numeric falls back to floating point.
+fn compare(a: &str, b: &str) -> Option<Ordering> {
+    let (a, b) = (a.trim(), b.trim());
+    let is_uint = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    if is_uint(a) && is_uint(b) {
+        let (a, b) = (a.trim_start_matches('0'), b.trim_start_matches('0'));
+        return Some(a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+    }
+    let a: f64 = a.parse().ok()?;
+    let b: f64 = b.parse().ok()?;
+    a.partial_cmp(&b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    /// Replays canned results and records the calls it received
+    struct ScriptedRunner {
+        results: Mutex<Vec<ToolResult>>,
+        calls: Mutex<Vec<(String, Value)>>,
+    }
+
+    impl ScriptedRunner {
+        fn new(results: Vec<ToolResult>) -> Self {
+            Self {
+                results: Mutex::new(results.into_iter().rev().collect()),
+                calls: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl StepRunner for ScriptedRunner {
+        async fn run_tool(&self, tool: &str, args: &Value) -> ToolResult {
+            self.calls.lock().unwrap().push((tool.to_string(), args.clone()));
+            self.results
+                .lock()
+                .unwrap()
+                .pop()
+                .unwrap_or_else(|| ToolResult::error("no scripted result"))
+        }
+    }
+
+    fn new_run(variables: Value) -> WorkflowRun {
+        WorkflowRun {
+            id: 1,
+            run_id: "run-1".to_string(),
+            session_id: 1,
+            channel_id: 1,
+            skill_name: "test".to_string(),
+            definition: String::new(),
+            status: WorkflowRunStatus::Running,
+            current_step: 0,
+            variables: variables.as_object().unwrap().clone(),
+            registers: Value::Null,
+            step_log: Vec::new(),
+            error: None,
+            paused_at: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn parse(source: &str) -> WorkflowDefinition {
+        let tools = vec!["lookup".to_string(), "allowance".to_string(), "approve".to_string()];
+        WorkflowDefinition::parse(source, &tools).unwrap()
+    }
+
+    const FLOW: &str = r#"(steps: [
+        (id: "token", action: Tool(tool: "lookup", args: {"symbol": "{{symbol}}", "cache_as": "token"}), retries: 1, retry_delay_secs: 0),
+        (id: "allowance", action: Tool(tool: "allowance", args: {})),
+        (id: "approve", action: Tool(tool: "approve", args: {"token": "{{token_address}}"}),
+            when: Some(LessThan("{{allowance.result}}", "{{amount}}"))),
+        (id: "confirm", action: Approval(prompt: "Swap {{amount}} {{symbol}}?")),
+        (id: "summary", action: Input(prompt: "Summarize", outputs: ["summary"])),
+    ])"#;
+
+    #[tokio::test]
+    async fn test_runs_until_gate_and_resumes() {
+        let definition = parse(FLOW);
+        let registers = RegisterStore::new();
+        registers.set("token_address", json!("0xabc"), "lookup");
+        let runner = ScriptedRunner::new(vec![
+            ToolResult::error("rate limited"),
+            ToolResult::success("found"),
+            ToolResult::success("\"500\"").with_metadata(json!({"result": "500"})),
+        ]);
+        let mut run = new_run(json!({"symbol": "USDC", "amount": "100"}));
+        let mut checkpoints = 0;
+
+        let outcome = advance(&definition, &mut run, &registers, &runner, &mut |_| checkpoints += 1).await;
+        assert_eq!(
+            outcome,
+            Advance::AwaitingApproval { step: "confirm".to_string(), prompt: "Swap 100 USDC?".to_string() }
+        );
+        assert!(checkpoints >= 4);
+        assert_eq!(run.status, WorkflowRunStatus::AwaitingApproval);
+        assert!(run.paused_at.is_some());
+
+        let calls = runner.calls.lock().unwrap().clone();
+        assert_eq!(calls.len(), 3, "lookup retried once, approve skipped");
+        assert_eq!(calls[0].1, json!({"symbol": "USDC", "cache_as": "token"}));
+        assert_eq!(run.step_log[0].attempts, 2);
+        assert_eq!(run.step_log[2].outcome, "skipped");
+        assert_eq!(run.variables["allowance"]["result"], "500");
+        assert_eq!(run.registers["token_address"]["value"], "0xabc");
+
+        // Advancing again without an approval stays at the gate
+        let again = advance(&definition, &mut run, &registers, &runner, &mut |_| {}).await;
+        assert!(matches!(again, Advance::AwaitingApproval { .. }));
+
+        approve(&definition, &mut run).unwrap();
+        let outcome = advance(&definition, &mut run, &registers, &runner, &mut |_| {}).await;
+        assert!(matches!(outcome, Advance::AwaitingInput { ref outputs, .. } if outputs == &vec!["summary".to_string()]));
+
+        assert!(provide(&definition, &mut run, &Map::new()).is_err());
+        provide(&definition, &mut run, json!({"summary": "done"}).as_object().unwrap()).unwrap();
+        let outcome = advance(&definition, &mut run, &registers, &runner, &mut |_| {}).await;
+        assert_eq!(outcome, Advance::Completed);
+        assert_eq!(run.status, WorkflowRunStatus::Completed);
+        assert_eq!(run.variables["summary"], "done");
+    }
+
+    #[tokio::test]
+    async fn test_failed_step_stops_run() {
+        let definition = parse(r#"(steps: [
+            (id: "a", action: Tool(tool: "lookup", args: {}), optional: true),
+            (id: "b", action: Tool(tool: "lookup", args: {"x": "{{missing}}"})),
+        ])"#);
+        let runner = ScriptedRunner::new(vec![ToolResult::error("boom")]);
+        let mut run = new_run(json!({}));
+
+        let outcome = advance(&definition, &mut run, &RegisterStore::new(), &runner, &mut |_| {}).await;
+        match outcome {
+            Advance::Failed { step, error } => {
+                assert_eq!(step, "b");
+                assert!(error.contains("missing"), "{}", error);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(run.step_log[0].outcome, "failed");
+        assert_eq!(run.status, WorkflowRunStatus::Failed);
+    }
+
+    #[test]
+    fn test_reject_cancels_run() {
+        let definition = parse(FLOW);
+        let mut run = new_run(json!({}));
+        assert!(reject(&definition, &mut run, "no").is_err());
+        run.current_step = 3;
+        run.status = WorkflowRunStatus::AwaitingApproval;
+        reject(&definition, &mut run, "user said no").unwrap();
+        assert_eq!(run.status, WorkflowRunStatus::Canceled);
+    }
+
+    #[test]
+    fn test_render_keeps_types_and_paths() {
+        let registers = RegisterStore::new();
+        registers.set("quote", json!({"amounts": ["1", "2"]}), "x402_fetch");
+        let vars = json!({"n": 5, "name": "eth"}).as_object().unwrap().clone();
+
+        let rendered = render_value(
+            &json!({"n": "{{n}}", "label": "{{name}}-{{quote.amounts.1}}", "list": ["{{ name }}"]}),
+            &vars,
+            &registers,
+        )
+        .unwrap();
+        assert_eq!(rendered, json!({"n": 5, "label": "eth-2", "list": ["eth"]}));
+        assert!(render_text("{{nope}}", &vars, &registers).is_err());
+    }
+
+    #[test]
+    fn test_compare_large_integers() {
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(compare("1000000", max), Some(Ordering::Less));
+        assert_eq!(compare("007", "7"), Some(Ordering::Equal));
+        assert_eq!(compare("1.5", "2"), Some(Ordering::Less));
+        assert_eq!(compare("abc", "2"), None);
+    }
+}
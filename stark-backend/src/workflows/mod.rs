@@ -0,0 +1,156 @@
+//! Declarative skill workflows
+//!
+//! A skill can declare its steps instead of describing them in prose: either inline in
+//! the frontmatter (`workflow: |` followed by an indented RON block) or in a sidecar
+//! file next to the skill (`<skill>.workflow.ron`, or the file named by `workflow:`).
+//! Steps are tool calls (with register bindings through `cache_as`), approval gates and
+//! input steps, each optionally guarded by a condition and retried on failure.
+//!
+//! The dispatcher executes the steps itself when the agent calls the `workflow` tool;
+//! the model only supplies the inputs and answers input steps. Runs are checkpointed to
+//! the `workflow_runs` table after every step, so a run paused at a gate (or cut off by
+//! a restart) continues from where it stopped in the session's next turn.
+
+pub mod definition;
+pub mod engine;
+
+use std::collections::HashMap;
+
+pub use definition::WorkflowDefinition;
+pub use engine::{Advance, StepRunner};
+
+use crate::models::{WorkflowRun, WorkflowRunStatus};
+use crate::tools::{PropertySchema, ToolDefinition, ToolGroup, ToolInputSchema};
+
+/// Name of the pseudo-tool the agent drives workflows with
+pub const TOOL_NAME: &str = "workflow";
+
+/// Tool definition offered while a skill with a workflow is active
+pub fn tool_definition(skill_name: &str, definition: &WorkflowDefinition) -> ToolDefinition {
+    let mut properties = HashMap::new();
+    properties.insert(
+        "action".to_string(),
+        PropertySchema {
+            schema_type: "string".to_string(),
+            description: "start: run the workflow with `inputs`. provide: answer an input step with `values`. \
+                 approve / reject: relay the user's decision at an approval gate. resume: continue a paused or \
+                 interrupted run. status: show progress. cancel: stop the run."
+                .to_string(),
+            default: None,
+            items: None,
+            enum_values: Some(
+                ["start", "provide", "approve", "reject", "resume", "status", "cancel"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+            ),
+        },
+    );
+    properties.insert(
+        "inputs".to_string(),
+        PropertySchema {
+            schema_type: "object".to_string(),
+            description: format!("Workflow inputs (action=start):\n{}", definition.describe_inputs()),
+            default: None,
+            items: None,
+            enum_values: None,
+        },
+    );
+    properties.insert(
+        "values".to_string(),
+        PropertySchema {
+            schema_type: "object".to_string(),
+            description: "Values requested by the current input step (action=provide)".to_string(),
+            default: None,
+            items: None,
+            enum_values: None,
+        },
+    );
+    properties.insert(
+        "reason".to_string(),
+        PropertySchema {
+            schema_type: "string".to_string(),
+            description: "Why the run is rejected or canceled".to_string(),
+            default: None,
+            items: None,
+            enum_values: None,
+        },
+    );
+
+    ToolDefinition {
+        name: TOOL_NAME.to_string(),
+        description: format!(
+            "Run the `{}` skill's workflow. The steps execute automatically; you only supply inputs, \
+             answer input steps and relay the user's approval. Do NOT call the step tools yourself.",
+            skill_name
+        ),
+        input_schema: ToolInputSchema {
+            schema_type: "object".to_string(),
+            properties,
+            required: vec!["action".to_string()],
+        },
+        group: ToolGroup::System,
+        hidden: false,
+    }
+}
+
+/// Instructions appended to `use_skill` output for a skill with a workflow
+pub fn skill_instructions(definition: &WorkflowDefinition) -> String {
+    let steps = definition
+        .steps
+        .iter()
+        .map(|s| s.id.as_str())
+        .collect::<Vec<_>>()
+        .join(" → ");
+    format!(
+        "### Workflow\n\
+         This skill runs as a workflow ({}). Call `workflow` with action \"start\" and these inputs \
+         taken from the user's request:\n{}\n\n\
+         The workflow executes its steps itself. Do NOT call define_tasks or the step tools directly.\n\n",
+        steps,
+        definition.describe_inputs()
+    )
+}
+
+/// Reminder for the agent when a session with an unfinished run gets a new message
+pub fn resume_note(run: &WorkflowRun, definition: &WorkflowDefinition) -> String {
+    let step = definition
+        .steps
+        .get(run.current_step)
+        .map(|s| s.id.as_str())
+        .unwrap_or("end");
+    let next = match run.status {
+        WorkflowRunStatus::AwaitingApproval => {
+            "It is waiting for the user's approval. If their latest message approves, call \
+             `workflow` with action \"approve\"; if it declines, call it with action \"reject\"."
+        }
+        WorkflowRunStatus::AwaitingInput => {
+            "It is waiting for input. Call `workflow` with action \"provide\" and the requested values."
+        }
+        _ => "It was interrupted. Call `workflow` with action \"resume\" to continue, or \"cancel\" to stop it.",
+    };
+    format!(
+        "### Workflow in progress\nThe `{}` workflow is paused at step `{}` ({} of {} done). {}\n\n",
+        run.skill_name,
+        step,
+        run.current_step,
+        definition.steps.len(),
+        next
+    )
+}
+
+/// Human-readable step log
+pub fn progress_summary(run: &WorkflowRun) -> String {
+    run.step_log
+        .iter()
+        .map(|r| {
+            let tool = r.tool.as_deref().map(|t| format!(" ({})", t)).unwrap_or_default();
+            if r.detail.is_empty() {
+                format!("- {}{}: {}", r.step, tool, r.outcome)
+            } else {
+                format!("- {}{}: {} — {}", r.step, tool, r.outcome, r.detail)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
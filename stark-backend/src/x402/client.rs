@@ -143,6 +143,14 @@ impl X402Client {
             &requirements.asset,
             &requirements.max_amount_required,
         )?;
+        super::payment_limits::check_payment_cap(
+            &requirements.asset,
+            &requirements.max_amount_required,
+        )?;
+        super::payment_limits::charge_spend_budget(
+            &requirements.asset,
+            &requirements.max_amount_required,
+        )?;
 
         // Create payment info before signing
         let payment_info = X402PaymentInfo::from_requirements(requirements);
@@ -336,6 +344,14 @@ pub async fn sign_402_payment(
         &requirements.asset,
         &requirements.max_amount_required,
     )?;
+    super::payment_limits::check_payment_cap(
+        &requirements.asset,
+        &requirements.max_amount_required,
+    )?;
+    super::payment_limits::charge_spend_budget(
+        &requirements.asset,
+        &requirements.max_amount_required,
+    )?;
 
     let payment_info = X402PaymentInfo::from_requirements(requirements);
 
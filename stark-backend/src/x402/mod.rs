@@ -15,6 +15,7 @@ mod types;
 mod client;
 mod signer;
 mod evm_rpc;
+mod verify;
 pub mod erc20;
 pub mod payment_limits;
 
@@ -22,3 +23,4 @@ pub use types::*;
 pub use client::{X402Client, X402Response, X402RetryResult, is_x402_endpoint, sign_402_payment, retry_with_x402_payment, check_usdc_balance};
 pub use signer::X402Signer;
 pub use evm_rpc::{TxLog, X402EvmRpc};
+pub use verify::{settle_payment, verify_payment_header, VerifiedPayment};
@@ -5,9 +5,13 @@
 //! at runtime when the user changes limits via the API.
 
 use serde::Deserialize;
+
+use crate::tools::types::{check_spending_cap, to_cap_units};
 use std::collections::HashMap;
+use std::future::Future;
 use std::path::Path;
-use std::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
 
 // ---------------------------------------------------------------------------
 // Types
@@ -185,6 +189,121 @@ pub fn check_payment_limit(asset: &str, amount_raw: &str) -> Result<(), String>
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Per-task spend budget
+// ---------------------------------------------------------------------------
+
+/// Running x402 spend for one task, in USDC raw units (micro-USDC), with an
+/// optional cap. Installed with `SpendBudget::scope`; every payment signed inside
+/// that task (AI calls and tools alike) is charged via `charge_spend_budget`
+/// before it is signed. Work moved to a separately spawned task is not covered.
+#[derive(Debug, Clone, Default)]
+pub struct SpendBudget {
+    spent_micro_usdc: Arc<AtomicU64>,
+    cap_micro_usdc: Option<u64>,
+}
+
+tokio::task_local! {
+    static SPEND_BUDGET: SpendBudget;
+}
+
+impl SpendBudget {
+    pub fn new(cap_micro_usdc: Option<u64>) -> Self {
+        Self { spent_micro_usdc: Arc::new(AtomicU64::new(0)), cap_micro_usdc }
+    }
+
+    /// Total charged so far
+    pub fn spent_micro_usdc(&self) -> u64 {
+        self.spent_micro_usdc.load(Ordering::SeqCst)
+    }
+
+    /// Run `future` with this budget charged for its payments
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        SPEND_BUDGET.scope(self, future).await
+    }
+
+    fn charge(&self, asset: &str, amount_raw: &str) -> Result<(), String> {
+        let Some(cap) = self.cap_micro_usdc else {
+            // Uncapped: just keep the tally (non-USDC amounts can't be summed, skip them)
+            if is_usdc(asset) {
+                self.spent_micro_usdc.fetch_add(amount_raw.parse().unwrap_or(0), Ordering::SeqCst);
+            }
+            return Ok(());
+        };
+        if !is_usdc(asset) {
+            return Err(format!(
+                "x402 payment in {} rejected: the spend cap is in USDC and can't cover other tokens",
+                asset
+            ));
+        }
+        let amount: u64 = amount_raw
+            .parse()
+            .map_err(|_| format!("Cannot parse payment amount '{}' as integer", amount_raw))?;
+        self.spent_micro_usdc
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |spent| {
+                spent.checked_add(amount).filter(|total| *total <= cap)
+            })
+            .map(|_| ())
+            .map_err(|spent| {
+                format!(
+                    "x402 payment of {} USDC rejected: it would exceed the spend cap ({} of {} USDC used)",
+                    format_amount(amount as u128, 6),
+                    format_amount(spent as u128, 6),
+                    format_amount(cap as u128, 6)
+                )
+            })
+    }
+}
+
+/// Charge a payment against the current task's spend budget, if one is installed.
+/// Call right before signing; nothing is charged when the payment is refused.
+pub fn charge_spend_budget(asset: &str, amount_raw: &str) -> Result<(), String> {
+    SPEND_BUDGET.try_with(|budget| budget.charge(asset, amount_raw)).unwrap_or(Ok(()))
+}
+
+// ---------------------------------------------------------------------------
+// Per-payment spending cap
+// ---------------------------------------------------------------------------
+
+/// The caller's role or run spending cap (see `ToolContext::check_role_tx`), installed
+/// by `ToolRegistry::execute` around a tool call. Every payment signed inside that call
+/// is checked via `check_payment_cap` before it is signed.
+#[derive(Debug, Clone)]
+pub struct PaymentCap {
+    pub cap: String,
+    pub source: String,
+}
+
+tokio::task_local! {
+    static PAYMENT_CAP: PaymentCap;
+}
+
+impl PaymentCap {
+    /// Run `future` with this cap applied to its payments
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        PAYMENT_CAP.scope(self, future).await
+    }
+}
+
+/// Check a payment against the spending cap in scope, if any. The amount is scaled
+/// from the asset's decimals (taken from its payment limit) to the cap's 18-decimal units.
+pub fn check_payment_cap(asset: &str, amount_raw: &str) -> Result<(), String> {
+    PAYMENT_CAP
+        .try_with(|cap| {
+            let decimals = get_limit(asset).map(|l| l.decimals).ok_or_else(|| {
+                format!("x402 payment in {} can't be checked against the spending cap for {}", asset, cap.source)
+            })?;
+            let amount = amount_raw.parse::<u128>().ok().and_then(|a| to_cap_units(a, decimals));
+            check_spending_cap(&cap.cap, &cap.source, amount, "x402 payment")
+        })
+        .unwrap_or(Ok(()))
+}
+
+fn is_usdc(asset: &str) -> bool {
+    asset.eq_ignore_ascii_case("USDC")
+        || get_limit(asset).map(|l| l.display_name.eq_ignore_ascii_case("USDC")).unwrap_or(false)
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
@@ -225,3 +344,45 @@ fn builtin_defaults() -> HashMap<String, PaymentLimit> {
     );
     map
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_spend_budget_caps_payments_in_scope() {
+        let budget = SpendBudget::new(Some(25_000));
+        budget
+            .clone()
+            .scope(async {
+                assert!(charge_spend_budget("USDC", "10000").is_ok());
+                assert!(charge_spend_budget("USDC", "10000").is_ok());
+                // The third call would cross the cap and is refused before signing
+                assert!(charge_spend_budget("USDC", "10000").is_err());
+                assert!(charge_spend_budget("STARKBOT", "1").is_err());
+            })
+            .await;
+        assert_eq!(budget.spent_micro_usdc(), 20_000);
+
+        // Outside any scope nothing is charged
+        assert!(charge_spend_budget("USDC", "99999999").is_ok());
+        assert_eq!(budget.spent_micro_usdc(), 20_000);
+    }
+
+    #[tokio::test]
+    async fn test_payment_cap_checks_amount_in_scope() {
+        load_defaults(Path::new("../config"));
+        // One whole token in 18-decimal units
+        let cap = PaymentCap { cap: "1000000000000000000".to_string(), source: "role 'trader'".to_string() };
+        cap.scope(async {
+            assert!(check_payment_cap("USDC", "1000000").is_ok());
+            let err = check_payment_cap("USDC", "1000001").unwrap_err();
+            assert!(err.contains("role 'trader'"));
+            assert!(check_payment_cap("UNKNOWN", "1").is_err());
+        })
+        .await;
+
+        // Outside any scope nothing is checked
+        assert!(check_payment_cap("USDC", "99999999").is_ok());
+    }
+}
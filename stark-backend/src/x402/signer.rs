@@ -564,7 +564,7 @@ impl X402Signer {
 }
 
 /// EIP-712 domain for token signatures
-struct Eip712Domain {
+pub(super) struct Eip712Domain {
     name: String,
     version: String,
     chain_id: u64,
@@ -573,7 +573,7 @@ struct Eip712Domain {
 
 impl Eip712Domain {
     /// Create domain from token metadata (dynamic, not hardcoded)
-    fn from_token_metadata(metadata: &TokenMetadata) -> Result<Self, String> {
+    pub(super) fn from_token_metadata(metadata: &TokenMetadata) -> Result<Self, String> {
         Ok(Eip712Domain {
             name: metadata.name.clone(),
             version: metadata.version.clone(),
@@ -583,7 +583,7 @@ impl Eip712Domain {
         })
     }
 
-    fn separator(&self) -> H256 {
+    pub(super) fn separator(&self) -> H256 {
         let type_hash = keccak256(
             b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
         );
@@ -635,17 +635,18 @@ impl PermitMessage {
 }
 
 /// TransferWithAuthorization message for EIP-3009
-struct TransferWithAuthorizationMessage {
-    from: ethers::types::Address,
-    to: ethers::types::Address,
-    value: U256,
-    valid_after: U256,
-    valid_before: U256,
-    nonce: H256,
+#[derive(Debug, Clone)]
+pub(super) struct TransferWithAuthorizationMessage {
+    pub(super) from: ethers::types::Address,
+    pub(super) to: ethers::types::Address,
+    pub(super) value: U256,
+    pub(super) valid_after: U256,
+    pub(super) valid_before: U256,
+    pub(super) nonce: H256,
 }
 
 impl TransferWithAuthorizationMessage {
-    fn struct_hash(&self) -> H256 {
+    pub(super) fn struct_hash(&self) -> H256 {
         let type_hash = keccak256(
             b"TransferWithAuthorization(address from,address to,uint256 value,uint256 validAfter,uint256 validBefore,bytes32 nonce)"
         );
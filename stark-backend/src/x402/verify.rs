@@ -0,0 +1,339 @@
+//! Receiving side of x402: check an X-PAYMENT header against what we asked for
+//!
+//! Only the "exact" (EIP-3009 TransferWithAuthorization) scheme is accepted — it names
+//! us as the recipient, so the authorization is worth something without a facilitator.
+//! Verification is offline (signature, recipient, amount, validity window); `settle_payment`
+//! then submits the authorization on-chain from the receiving wallet, so the payer is charged
+//! before any work is done.
+
+use ethers::abi::Token;
+use ethers::types::{Address, Signature, H256, U256, U64};
+use ethers::utils::keccak256;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::erc20;
+use super::evm_rpc::X402EvmRpc;
+use super::signer::{Eip712Domain, TransferWithAuthorizationMessage};
+use super::types::{PaymentRequirements, TokenMetadata};
+use crate::wallet::WalletProvider;
+
+/// How long to wait for the settlement transaction to be mined
+const SETTLE_TIMEOUT_SECS: u64 = 90;
+
+/// A payment authorization that passed verification
+#[derive(Debug, Clone)]
+pub struct VerifiedPayment {
+    /// Address that signed (and pays)
+    pub payer: String,
+    /// Authorized amount in the asset's smallest unit
+    pub amount: String,
+    /// EIP-3009 nonce — each authorization can only pay once
+    pub nonce: String,
+    /// The signed authorization, kept for settlement
+    authorization: SignedAuthorization,
+}
+
+#[derive(Debug, Clone)]
+struct SignedAuthorization {
+    network: String,
+    asset: Address,
+    message: TransferWithAuthorizationMessage,
+    signature: Signature,
+}
+
+#[cfg(test)]
+impl VerifiedPayment {
+    /// A payment with a blank authorization, for tests that never settle
+    pub(crate) fn unsettled(payer: &str, amount: &str, nonce: &str) -> Self {
+        VerifiedPayment {
+            payer: payer.to_string(),
+            amount: amount.to_string(),
+            nonce: nonce.to_string(),
+            authorization: SignedAuthorization {
+                network: "base".to_string(),
+                asset: Address::zero(),
+                message: TransferWithAuthorizationMessage {
+                    from: Address::zero(),
+                    to: Address::zero(),
+                    value: U256::zero(),
+                    valid_after: U256::zero(),
+                    valid_before: U256::zero(),
+                    nonce: H256::zero(),
+                },
+                signature: Signature { r: U256::zero(), s: U256::zero(), v: 0 },
+            },
+        }
+    }
+}
+
+/// Verify a base64 X-PAYMENT header (V1 or V2 payload) against the requirements we issued
+pub fn verify_payment_header(
+    header: &str,
+    requirements: &PaymentRequirements,
+) -> Result<VerifiedPayment, String> {
+    let decoded = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, header.trim())
+        .map_err(|e| format!("X-PAYMENT is not valid base64: {}", e))?;
+    let payload: serde_json::Value = serde_json::from_slice(&decoded)
+        .map_err(|e| format!("X-PAYMENT is not valid JSON: {}", e))?;
+
+    // V2 carries scheme/network in "accepted", V1 at the top level
+    let scheme = payload
+        .pointer("/accepted/scheme")
+        .or_else(|| payload.get("scheme"))
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    if scheme != "exact" && scheme != "eip3009" {
+        return Err(format!("Unsupported payment scheme '{}', expected 'exact'", scheme));
+    }
+    let network = payload
+        .pointer("/accepted/network")
+        .or_else(|| payload.get("network"))
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    if network != requirements.network {
+        return Err(format!("Payment is for network '{}', expected '{}'", network, requirements.network));
+    }
+
+    let auth = payload
+        .pointer("/payload/authorization")
+        .ok_or("X-PAYMENT is missing payload.authorization")?;
+    let field = |name: &str| -> Result<&str, String> {
+        auth.get(name)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| format!("Authorization is missing '{}'", name))
+    };
+    let signature = payload
+        .pointer("/payload/signature")
+        .and_then(|v| v.as_str())
+        .ok_or("X-PAYMENT is missing payload.signature")?;
+
+    let parse_address = |s: &str| Address::from_str(s).map_err(|e| format!("Invalid address '{}': {}", s, e));
+    let parse_uint = |s: &str| U256::from_dec_str(s).map_err(|e| format!("Invalid number '{}': {}", s, e));
+
+    let message = TransferWithAuthorizationMessage {
+        from: parse_address(field("from")?)?,
+        to: parse_address(field("to")?)?,
+        value: parse_uint(field("value")?)?,
+        valid_after: parse_uint(field("validAfter")?)?,
+        valid_before: parse_uint(field("validBefore")?)?,
+        nonce: H256::from_str(field("nonce")?).map_err(|e| format!("Invalid nonce: {}", e))?,
+    };
+
+    if message.to != parse_address(&requirements.pay_to_address)? {
+        return Err("Payment recipient does not match".to_string());
+    }
+    if message.value < parse_uint(&requirements.max_amount_required)? {
+        return Err(format!(
+            "Payment of {} is below the required {}",
+            message.value, requirements.max_amount_required
+        ));
+    }
+    let now = U256::from(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Time error: {}", e))?
+            .as_secs(),
+    );
+    if message.valid_after > now || message.valid_before <= now {
+        return Err("Payment authorization is outside its validity window".to_string());
+    }
+
+    // Recover the signer of the EIP-712 digest and require it to be the payer
+    let domain = Eip712Domain::from_token_metadata(&TokenMetadata::from_requirements(requirements))?;
+    let mut to_sign = Vec::with_capacity(66);
+    to_sign.push(0x19);
+    to_sign.push(0x01);
+    to_sign.extend_from_slice(domain.separator().as_bytes());
+    to_sign.extend_from_slice(message.struct_hash().as_bytes());
+    let digest = H256::from(keccak256(&to_sign));
+
+    let signature = Signature::from_str(signature).map_err(|e| format!("Invalid signature: {}", e))?;
+    let signer = signature
+        .recover(digest)
+        .map_err(|e| format!("Could not recover payment signer: {}", e))?;
+    if signer != message.from {
+        return Err("Payment signature does not match the payer".to_string());
+    }
+
+    Ok(VerifiedPayment {
+        payer: format!("{:?}", message.from),
+        amount: message.value.to_string(),
+        nonce: format!("{:?}", message.nonce),
+        authorization: SignedAuthorization {
+            network: requirements.network.clone(),
+            asset: parse_address(&requirements.asset)?,
+            message,
+            signature,
+        },
+    })
+}
+
+fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+fn u256_bytes(value: U256) -> Vec<u8> {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    bytes.to_vec()
+}
+
+/// Encode authorizationState(address,bytes32) — true once a nonce has been used or canceled
+fn encode_authorization_state(authorizer: Address, nonce: H256) -> Vec<u8> {
+    let mut data = selector("authorizationState(address,bytes32)").to_vec();
+    data.extend_from_slice(&ethers::abi::encode(&[
+        Token::Address(authorizer),
+        Token::FixedBytes(nonce.as_bytes().to_vec()),
+    ]));
+    data
+}
+
+/// Encode transferWithAuthorization(from, to, value, validAfter, validBefore, nonce, v, r, s)
+fn encode_transfer_with_authorization(authorization: &SignedAuthorization) -> Vec<u8> {
+    let message = &authorization.message;
+    let signature = &authorization.signature;
+    let mut data = selector(
+        "transferWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)",
+    )
+    .to_vec();
+    data.extend_from_slice(&ethers::abi::encode(&[
+        Token::Address(message.from),
+        Token::Address(message.to),
+        Token::Uint(message.value),
+        Token::Uint(message.valid_after),
+        Token::Uint(message.valid_before),
+        Token::FixedBytes(message.nonce.as_bytes().to_vec()),
+        Token::Uint(U256::from(signature.v)),
+        Token::FixedBytes(u256_bytes(signature.r)),
+        Token::FixedBytes(u256_bytes(signature.s)),
+    ]));
+    data
+}
+
+/// Collect a verified payment: submit its authorization on-chain from our wallet (the recipient)
+/// and wait for it to be mined. Returns the settlement transaction hash.
+pub async fn settle_payment(
+    payment: &VerifiedPayment,
+    wallet_provider: &Arc<dyn WalletProvider>,
+) -> Result<String, String> {
+    let authorization = &payment.authorization;
+    let message = &authorization.message;
+    let network = authorization.network.as_str();
+
+    let rpc_config = crate::tools::rpc_config::resolve_rpc_from_network(network);
+    let rpc = X402EvmRpc::new_with_wallet_provider(
+        wallet_provider.clone(),
+        network,
+        Some(rpc_config.url.clone()),
+        rpc_config.use_x402,
+    )?;
+
+    // Both would make the transfer revert; checking first gives the payer a clear error
+    let state = rpc
+        .call(authorization.asset, &encode_authorization_state(message.from, message.nonce))
+        .await?;
+    if state.len() >= 32 && state[..32].iter().any(|b| *b != 0) {
+        return Err("Payment authorization has already been used".to_string());
+    }
+    let balance = erc20::decode_balance(&rpc.call(authorization.asset, &erc20::encode_balance_of(message.from)).await?)?;
+    if balance < message.value {
+        return Err(format!("Payer balance {} is below the authorized {}", balance, message.value));
+    }
+
+    let signed = crate::web3::sign_transaction_for_queue(
+        network,
+        authorization.asset,
+        encode_transfer_with_authorization(authorization),
+        U256::zero(),
+        &rpc_config,
+        wallet_provider,
+    )
+    .await?;
+    let raw = hex::decode(signed.signed_tx_hex.trim_start_matches("0x"))
+        .map_err(|e| format!("Invalid signed tx: {}", e))?;
+    let tx_hash = rpc.send_raw_transaction(&raw).await?;
+    let receipt = rpc
+        .wait_for_receipt(tx_hash, Duration::from_secs(SETTLE_TIMEOUT_SECS))
+        .await?;
+    if receipt.status != Some(U64::from(1)) {
+        return Err(format!("Payment settlement {:?} reverted", tx_hash));
+    }
+    Ok(format!("{:?}", tx_hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::x402::{X402Signer, USDC_ADDRESS};
+
+    fn requirements(amount: &str) -> PaymentRequirements {
+        PaymentRequirements {
+            scheme: "exact".to_string(),
+            network: "base".to_string(),
+            max_amount_required: amount.to_string(),
+            pay_to_address: "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".to_string(),
+            asset: USDC_ADDRESS.to_string(),
+            max_timeout_seconds: 300,
+            resource: None,
+            description: None,
+            extra: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_signed_payment() {
+        // Hardhat's first default account pays the second
+        let signer = X402Signer::from_private_key(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        let header = signer
+            .sign_payment_v2(&requirements("10000"))
+            .await
+            .unwrap()
+            .to_base64()
+            .unwrap();
+
+        let verified = verify_payment_header(&header, &requirements("10000")).unwrap();
+        assert_eq!(verified.payer, "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+        assert_eq!(verified.amount, "10000");
+
+        // Underpaying for a higher price is rejected
+        assert!(verify_payment_header(&header, &requirements("20000")).is_err());
+        // So is a payment addressed to someone else
+        let mut other = requirements("10000");
+        other.pay_to_address = "0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC".to_string();
+        assert!(verify_payment_header(&header, &other).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_settlement_calldata() {
+        let signer = X402Signer::from_private_key(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        let header = signer
+            .sign_payment_v2(&requirements("10000"))
+            .await
+            .unwrap()
+            .to_base64()
+            .unwrap();
+        let verified = verify_payment_header(&header, &requirements("10000")).unwrap();
+        let authorization = &verified.authorization;
+
+        let data = encode_transfer_with_authorization(authorization);
+        assert_eq!(&data[..4], &[0xe3, 0xee, 0x16, 0x0e]);
+        assert_eq!(data.len(), 4 + 9 * 32);
+        assert_eq!(&data[4 + 12..4 + 32], authorization.message.from.as_bytes());
+        assert_eq!(U256::from_big_endian(&data[4 + 64..4 + 96]), U256::from(10000));
+        assert_eq!(&data[4 + 160..4 + 192], authorization.message.nonce.as_bytes());
+        assert_eq!(U256::from_big_endian(&data[4 + 192..4 + 224]), U256::from(authorization.signature.v));
+
+        let state = encode_authorization_state(authorization.message.from, authorization.message.nonce);
+        assert_eq!(&state[..4], &[0xe9, 0x4a, 0x01, 0x02]);
+        assert_eq!(state.len(), 4 + 2 * 32);
+    }
+}
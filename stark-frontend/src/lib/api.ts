@@ -963,6 +963,18 @@ export async function previewCloudBackup(): Promise<CloudBackupPreview> {
 export const previewKeysFromCloud = previewCloudBackup;
 
 // Cron Jobs API
+
+/** Per-run settings for scheduled (cron/heartbeat) runs */
+export interface RunOverrides {
+  endpoint?: string;
+  thinking_level?: string;
+  tool_profile?: string;
+  agent_subtype?: string;
+  max_tool_iterations?: number;
+  /** Limit on each transaction in the run, not a total for the run */
+  max_value_per_tx_wei?: string;
+}
+
 export interface CronJobInfo {
   id: number;
   job_id: string;
@@ -979,6 +991,7 @@ export interface CronJobInfo {
   deliver: boolean;
   model_override?: string;
   thinking_level?: string;
+  run_overrides: RunOverrides;
   timeout_seconds?: number;
   delete_after_run: boolean;
   status: string;
@@ -987,6 +1000,8 @@ export interface CronJobInfo {
   run_count: number;
   error_count: number;
   last_error?: string;
+  consecutive_failures: number;
+  alert_after_failures?: number;
   created_at: string;
   updated_at: string;
 }
@@ -1022,8 +1037,10 @@ export async function createCronJob(data: {
   deliver?: boolean;
   model_override?: string;
   thinking_level?: string;
+  run_overrides?: RunOverrides;
   timeout_seconds?: number;
   delete_after_run?: boolean;
+  alert_after_failures?: number;
 }): Promise<CronJobInfo> {
   const response = await apiFetch<CronJobResponse>('/cron/jobs', {
     method: 'POST',
@@ -1049,9 +1066,12 @@ export async function updateCronJob(id: number, data: Partial<{
   deliver: boolean;
   model_override: string;
   thinking_level: string;
+  run_overrides: RunOverrides;
   timeout_seconds: number;
   delete_after_run: boolean;
   status: string;
+  /** 0 turns failure alerts off */
+  alert_after_failures: number;
 }>): Promise<CronJobInfo> {
   const response = await apiFetch<CronJobResponse>(`/cron/jobs/${id}`, {
     method: 'PUT',
@@ -1110,6 +1130,14 @@ export interface CronJobRunInfo {
   response?: string;
   error?: string;
   duration_ms?: number;
+  delivery_status?: 'delivered' | 'failed' | 'skipped';
+  delivery_error?: string;
+  session_id?: number;
+  tool_calls?: number;
+  tokens_used?: number;
+  x402_spend_usdc?: number;
+  input?: { message: string; run_overrides: RunOverrides };
+  replay_of?: number;
 }
 
 export async function getCronJobRuns(id: number, limit?: number): Promise<CronJobRunInfo[]> {
@@ -1118,6 +1146,121 @@ export async function getCronJobRuns(id: number, limit?: number): Promise<CronJo
   return response.runs || [];
 }
 
+export async function getCronRun(runId: number): Promise<CronJobRunInfo | null> {
+  const response = await apiFetch<{ success: boolean; run?: CronJobRunInfo }>(`/cron/runs/${runId}`);
+  return response.run || null;
+}
+
+export async function replayCronRun(runId: number): Promise<CronJobRunInfo | null> {
+  const response = await apiFetch<{ success: boolean; run?: CronJobRunInfo; error?: string }>(`/cron/runs/${runId}/replay`, {
+    method: 'POST',
+  });
+  if (!response.success) {
+    throw new Error(response.error || 'Failed to replay run');
+  }
+  return response.run || null;
+}
+
+// Scheduler Trigger API
+export type TriggerCondition =
+  | { type: 'price_cross'; source?: 'dexscreener' | 'geckoterminal'; chain: string; token_address: string; direction: 'above' | 'below'; threshold_usd: number }
+  | { type: 'large_trade'; min_usd?: number; address?: string; chain?: string }
+  | { type: 'keyword_spike'; keyword: string; window_minutes?: number; min_mentions: number }
+  | { type: 'tx_confirmed'; tx_uuid?: string; network?: string };
+
+export interface SchedulerTriggerInfo {
+  id: number;
+  name: string;
+  job_id: number;
+  condition: TriggerCondition;
+  check_interval_secs: number;
+  cooldown_secs: number;
+  confirm_checks: number;
+  enabled: boolean;
+  state: { last_value?: number; streak: number; fired: boolean; cursor?: string };
+  last_checked_at?: string;
+  last_fired_at?: string;
+  fire_count: number;
+  last_error?: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface TriggerRunInfo {
+  id: number;
+  trigger_id: number;
+  fired_at: string;
+  detail: string;
+  value?: number;
+  cron_run_id?: number;
+  success?: boolean;
+  error?: string;
+}
+
+interface TriggerResponse {
+  success: boolean;
+  trigger?: SchedulerTriggerInfo;
+  triggers?: SchedulerTriggerInfo[];
+  runs?: TriggerRunInfo[];
+  error?: string;
+}
+
+export interface TriggerInput {
+  name: string;
+  job_id: number;
+  condition: TriggerCondition;
+  check_interval_secs?: number;
+  cooldown_secs?: number;
+  confirm_checks?: number;
+  enabled?: boolean;
+}
+
+export async function getTriggers(): Promise<SchedulerTriggerInfo[]> {
+  const response = await apiFetch<TriggerResponse>('/triggers');
+  return response.triggers || [];
+}
+
+export async function createTrigger(data: TriggerInput): Promise<SchedulerTriggerInfo> {
+  const response = await apiFetch<TriggerResponse>('/triggers', {
+    method: 'POST',
+    body: JSON.stringify(data),
+  });
+  if (!response.success || !response.trigger) {
+    throw new Error(response.error || 'Failed to create trigger');
+  }
+  return response.trigger;
+}
+
+export async function updateTrigger(id: number, data: Partial<TriggerInput>): Promise<SchedulerTriggerInfo> {
+  const response = await apiFetch<TriggerResponse>(`/triggers/${id}`, {
+    method: 'PUT',
+    body: JSON.stringify(data),
+  });
+  if (!response.success || !response.trigger) {
+    throw new Error(response.error || 'Failed to update trigger');
+  }
+  return response.trigger;
+}
+
+export async function deleteTrigger(id: number): Promise<void> {
+  const response = await apiFetch<TriggerResponse>(`/triggers/${id}`, {
+    method: 'DELETE',
+  });
+  if (!response.success) {
+    throw new Error(response.error || 'Failed to delete trigger');
+  }
+}
+
+export async function getTriggerRuns(id: number, limit?: number): Promise<TriggerRunInfo[]> {
+  const query = limit ? `?limit=${limit}` : '';
+  const response = await apiFetch<TriggerResponse>(`/triggers/${id}/runs${query}`);
+  return response.runs || [];
+}
+
+export async function testTrigger(id: number): Promise<{ success: boolean; met?: boolean; would_fire?: boolean; value?: number; detail?: string; error?: string }> {
+  return apiFetch(`/triggers/${id}/test`, { method: 'POST' });
+}
+
 // Heartbeat Config API
 export interface HeartbeatConfigInfo {
   id: number;
@@ -1128,6 +1271,7 @@ export interface HeartbeatConfigInfo {
   active_hours_end?: string;
   active_days?: string;
   enabled: boolean;
+  run_overrides: RunOverrides;
   last_beat_at?: string;
   next_beat_at?: string;
   created_at: string;
@@ -1152,6 +1296,7 @@ export async function updateHeartbeatConfig(data: {
   active_hours_end?: string;
   active_days?: string;
   enabled?: boolean;
+  run_overrides?: RunOverrides;
 }): Promise<HeartbeatConfigInfo> {
   const response = await apiFetch<HeartbeatConfigResponse>('/heartbeat/config', {
     method: 'PUT',
@@ -1190,6 +1335,7 @@ export interface BotSettings {
   theme_accent?: string;
   proxy_url?: string;
   kanban_auto_execute: boolean;
+  kanban_max_concurrent: number;
   created_at: string;
   updated_at: string;
 }
@@ -1213,6 +1359,7 @@ export async function updateBotSettings(data: {
   theme_accent?: string;
   proxy_url?: string;
   kanban_auto_execute?: boolean;
+  kanban_max_concurrent?: number;
 }): Promise<BotSettings> {
   return apiFetch('/bot-settings', {
     method: 'PUT',
@@ -1753,12 +1900,18 @@ export interface KanbanItem {
   id: number;
   title: string;
   description: string;
-  status: 'ready' | 'in_progress' | 'complete';
+  status: 'ready' | 'in_progress' | 'complete' | 'failed';
   priority: number;
   session_id: number | null;
   result: string | null;
   created_at: string;
   updated_at: string;
+  blocked_by: number[];
+  max_retries: number;
+  attempts: number;
+  next_attempt_at: string | null;
+  timeout_seconds: number | null;
+  agent_subtype: string | null;
 }
 
 export async function getKanbanItems(status?: string): Promise<KanbanItem[]> {
@@ -1770,6 +1923,10 @@ export async function createKanbanItem(data: {
   title: string;
   description?: string;
   priority?: number;
+  blocked_by?: number[];
+  max_retries?: number;
+  timeout_seconds?: number;
+  agent_subtype?: string;
 }): Promise<KanbanItem> {
   return apiFetch('/kanban/items', {
     method: 'POST',
@@ -1784,6 +1941,10 @@ export async function updateKanbanItem(id: number, data: {
   priority?: number;
   session_id?: number;
   result?: string;
+  blocked_by?: number[];
+  max_retries?: number;
+  timeout_seconds?: number;
+  agent_subtype?: string;
 }): Promise<KanbanItem> {
   return apiFetch(`/kanban/items/${id}`, {
     method: 'PUT',
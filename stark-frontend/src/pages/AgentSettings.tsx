@@ -6,6 +6,7 @@ import Input from '@/components/ui/Input';
 import { getAgentSettings, updateAgentSettings, getBotSettings, updateBotSettings, getAiEndpointPresets, AiEndpointPreset } from '@/lib/api';
 
 type ModelArchetype = 'kimi' | 'llama' | 'claude' | 'openai' | 'minimax';
+type VisionOption = 'auto' | 'on' | 'off';
 
 interface Settings {
   endpoint?: string;
@@ -13,6 +14,7 @@ interface Settings {
   max_response_tokens?: number;
   max_context_tokens?: number;
   has_secret_key?: boolean;
+  supports_vision?: boolean | null;
 }
 
 export default function AgentSettings() {
@@ -22,6 +24,7 @@ export default function AgentSettings() {
   const [modelArchetype, setModelArchetype] = useState<ModelArchetype>('kimi');
   const [maxResponseTokens, setMaxResponseTokens] = useState(40000);
   const [maxContextTokens, setMaxContextTokens] = useState(100000);
+  const [vision, setVision] = useState<VisionOption>('auto');
   const [secretKey, setSecretKey] = useState('');
   const [hasExistingSecretKey, setHasExistingSecretKey] = useState(false);
   const [maxToolIterations, setMaxToolIterations] = useState(50);
@@ -89,6 +92,7 @@ export default function AgentSettings() {
       if (data.max_context_tokens && data.max_context_tokens > 0) {
         setMaxContextTokens(data.max_context_tokens);
       }
+      setVision(data.supports_vision == null ? 'auto' : data.supports_vision ? 'on' : 'off');
     } catch (err) {
       setMessage({ type: 'error', text: 'Failed to load settings' });
     } finally {
@@ -154,11 +158,13 @@ export default function AgentSettings() {
         max_response_tokens: number;
         max_context_tokens: number;
         secret_key?: string;
+        supports_vision: boolean | null;
       } = {
         endpoint,
         model_archetype: archetype,
         max_response_tokens: maxResponseTokens,
         max_context_tokens: contextTokens,
+        supports_vision: vision === 'auto' ? null : vision === 'on',
       };
 
       if (endpointOption === 'custom' && secretKey.trim()) {
@@ -287,6 +293,24 @@ export default function AgentSettings() {
                 </p>
               </div>
 
+              <div>
+                <label className="block text-sm font-medium text-slate-300 mb-2">
+                  Image Input
+                </label>
+                <select
+                  value={vision}
+                  onChange={(e) => setVision(e.target.value as VisionOption)}
+                  className="w-full px-4 py-3 bg-slate-900/50 border border-slate-600 rounded-lg text-white focus:outline-none focus:ring-2 focus:ring-stark-500 focus:border-transparent"
+                >
+                  <option value="auto">Automatic</option>
+                  <option value="on">Send images</option>
+                  <option value="off">Text only</option>
+                </select>
+                <p className="text-xs text-slate-500 mt-1">
+                  Whether image attachments are sent to the model. Automatic sends them only to endpoints known to accept images; otherwise attachments are described in text.
+                </p>
+              </div>
+
               <div>
                 <label className="block text-sm font-medium text-slate-300 mb-2">
                   Max Response Tokens
@@ -1,5 +1,5 @@
 import { useState, useEffect } from 'react';
-import { MessageSquare, Hash, Plus, Play, Square, Trash2, Save, Pencil, Twitter, AlertTriangle, Terminal, Dices, Copy, Check } from 'lucide-react';
+import { MessageSquare, Hash, Plus, Play, Square, Trash2, Save, Pencil, Twitter, AlertTriangle, Terminal, Dices, Copy, Check, Mail } from 'lucide-react';
 import Card, { CardContent, CardHeader, CardTitle } from '@/components/ui/Card';
 import Button from '@/components/ui/Button';
 import Input from '@/components/ui/Input';
@@ -26,6 +26,8 @@ const CHANNEL_TYPES = [
   { value: 'discord', label: 'Discord', icon: MessageSquare, color: 'indigo' },
   { value: 'twitter', label: 'Twitter / X', icon: Twitter, color: 'sky' },
   { value: 'external_channel', label: 'External Channel', icon: Terminal, color: 'emerald' },
+  { value: 'email', label: 'Email (IMAP/SMTP)', icon: Mail, color: 'amber' },
+  { value: 'matrix', label: 'Matrix', icon: Hash, color: 'teal' },
 ];
 
 function getChannelHints(channelType: string): string[] {
@@ -33,6 +35,7 @@ function getChannelHints(channelType: string): string[] {
     case 'discord':
       return [
         'In the Discord Developer Portal, enable Presence Intent, Server Members Intent, and Message Content Intent under Bot settings.',
+        'Invite the bot with the <strong>bot</strong> and <strong>applications.commands</strong> scopes to enable slash commands (/ask, /reset, /thinking, /tx list, /status).',
         'Warning: Only install Starkbot in your own Discord server. The admin will have full control over the Agentic Loop and Tools.',
       ];
     case 'twitter':
@@ -51,6 +54,18 @@ function getChannelHints(channelType: string): string[] {
         'Generate a secure API Token in settings after creation. The token authenticates external clients.',
         'Safe mode is off by default — enable it in settings to restrict tool access for untrusted input.',
       ];
+    case 'email':
+      return [
+        'Works with any IMAP/SMTP mailbox (use an app password for Gmail/Outlook). IMAP IDLE is used when the server supports it, otherwise the inbox is polled.',
+        'Only senders listed under <strong>Admin Addresses</strong> get full agent access; everyone else is restricted to safe mode. Set <strong>Allowed Senders</strong> to ignore mail from anyone else.',
+        'Use a dedicated mailbox — every unread message in the watched folder is answered.',
+      ];
+    case 'matrix':
+      return [
+        'Create a bot account on your homeserver, then set the <strong>Homeserver URL</strong> and the account\'s <strong>Access Token</strong> in channel settings after creation.',
+        'Only users listed under <strong>Admin User IDs</strong> (e.g. @you:example.org) get full agent access; everyone else is restricted to safe mode. By default only admins can invite the bot into rooms.',
+        'The bot answers when mentioned, when replied to, and in direct chats. Encrypted rooms are not supported.',
+      ];
     default:
       return [];
   }
@@ -13,12 +13,13 @@ import Modal from '@/components/ui/Modal';
 import Button from '@/components/ui/Button';
 import Input from '@/components/ui/Input';
 
-type KanbanStatus = 'ready' | 'in_progress' | 'complete';
+type KanbanStatus = 'ready' | 'in_progress' | 'complete' | 'failed';
 
 const COLUMNS: { status: KanbanStatus; label: string; color: string; bg: string; border: string }[] = [
   { status: 'ready', label: 'Ready', color: 'text-blue-400', bg: 'bg-blue-500/10', border: 'border-blue-500/30' },
   { status: 'in_progress', label: 'In Progress', color: 'text-yellow-400', bg: 'bg-yellow-500/10', border: 'border-yellow-500/30' },
   { status: 'complete', label: 'Complete', color: 'text-green-400', bg: 'bg-green-500/10', border: 'border-green-500/30' },
+  { status: 'failed', label: 'Failed', color: 'text-red-400', bg: 'bg-red-500/10', border: 'border-red-500/30' },
 ];
 
 const PRIORITY_LABELS: Record<number, { label: string; class: string }> = {
@@ -173,8 +174,8 @@ export default function KanbanBoard() {
         </div>
       )}
 
-      {/* Three-column board */}
-      <div className="flex-1 grid grid-cols-1 md:grid-cols-3 gap-4 min-h-0">
+      {/* Four-column board */}
+      <div className="flex-1 grid grid-cols-1 md:grid-cols-4 gap-4 min-h-0">
         {COLUMNS.map((col) => (
           <div
             key={col.status}
@@ -219,6 +220,20 @@ export default function KanbanBoard() {
                       {item.description && (
                         <p className="text-xs text-slate-400 line-clamp-2">{item.description}</p>
                       )}
+                      {(item.blocked_by.length > 0 || item.attempts > 0) && (
+                        <div className="mt-1.5 flex flex-wrap gap-1 text-[10px]">
+                          {item.blocked_by.length > 0 && (
+                            <span className="px-1.5 py-0.5 rounded bg-slate-700 text-slate-300">
+                              Blocked by {item.blocked_by.map((id) => `#${id}`).join(', ')}
+                            </span>
+                          )}
+                          {item.attempts > 0 && (
+                            <span className="px-1.5 py-0.5 rounded bg-red-600/30 text-red-300">
+                              Attempt {item.attempts}/{item.max_retries + 1}
+                            </span>
+                          )}
+                        </div>
+                      )}
                       {item.session_id && (
                         <div className="mt-1.5 flex items-center gap-1 text-[10px] text-slate-500">
                           <ExternalLink className="w-3 h-3" />
@@ -304,6 +319,7 @@ export default function KanbanBoard() {
               <span className={`text-xs px-2 py-1 rounded-full font-medium ${
                 detailItem.status === 'ready' ? 'bg-blue-500/20 text-blue-400' :
                 detailItem.status === 'in_progress' ? 'bg-yellow-500/20 text-yellow-400' :
+                detailItem.status === 'failed' ? 'bg-red-500/20 text-red-400' :
                 'bg-green-500/20 text-green-400'
               }`}>
                 {detailItem.status === 'in_progress' ? 'In Progress' :
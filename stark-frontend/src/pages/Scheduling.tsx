@@ -1,4 +1,5 @@
 import { useState, useEffect, useMemo } from 'react';
+import { Link } from 'react-router-dom';
 import {
   Clock,
   Plus,
@@ -15,6 +16,8 @@ import {
   Activity,
   Calendar,
   Zap,
+  RotateCcw,
+  ExternalLink,
 } from 'lucide-react';
 import Card, { CardContent, CardHeader, CardTitle } from '@/components/ui/Card';
 import Button from '@/components/ui/Button';
@@ -27,6 +30,7 @@ import {
   pauseCronJob,
   resumeCronJob,
   getCronJobRuns,
+  replayCronRun,
   CronJobInfo,
   CronJobRunInfo,
 } from '@/lib/api';
@@ -205,6 +209,7 @@ function CronJobsSection({ jobs, setJobs, showCreateForm, setShowCreateForm, set
     session_mode: 'main',
     message: '',
     timeout_seconds: '',
+    alert_after_failures: '',
     delete_after_run: false,
   });
 
@@ -221,6 +226,7 @@ function CronJobsSection({ jobs, setJobs, showCreateForm, setShowCreateForm, set
       session_mode: 'main',
       message: '',
       timeout_seconds: '',
+      alert_after_failures: '',
       delete_after_run: false,
     });
     setIntervalValue(1);
@@ -251,6 +257,7 @@ function CronJobsSection({ jobs, setJobs, showCreateForm, setShowCreateForm, set
         delete_after_run: formData.delete_after_run,
       };
       if (formData.timeout_seconds) payload.timeout_seconds = parseInt(formData.timeout_seconds);
+      if (formData.alert_after_failures) payload.alert_after_failures = parseInt(formData.alert_after_failures);
 
       const newJob = await createCronJob(payload as Parameters<typeof createCronJob>[0]);
       setJobs((prev) => [...prev, newJob]);
@@ -283,6 +290,17 @@ function CronJobsSection({ jobs, setJobs, showCreateForm, setShowCreateForm, set
     }
   };
 
+  const handleReplay = async (jobId: number, runId: number) => {
+    try {
+      await replayCronRun(runId);
+      const [updatedJobs, runs] = await Promise.all([getCronJobs(), getCronJobRuns(jobId, 10)]);
+      setJobs(updatedJobs);
+      setJobRuns((prev) => ({ ...prev, [jobId]: runs }));
+    } catch (err) {
+      setError('Failed to replay run');
+    }
+  };
+
   const handleTogglePause = async (job: CronJobInfo) => {
     try {
       const updatedJob = job.status === 'paused'
@@ -473,6 +491,16 @@ function CronJobsSection({ jobs, setJobs, showCreateForm, setShowCreateForm, set
                       placeholder="600"
                     />
                   </div>
+                  <div>
+                    <Input
+                      label="Alert after N failures in a row"
+                      type="number"
+                      min={1}
+                      value={formData.alert_after_failures}
+                      onChange={(e) => setFormData({ ...formData, alert_after_failures: e.target.value })}
+                      placeholder="Off"
+                    />
+                  </div>
                   <div className="md:col-span-2">
                     <label className="flex items-center gap-2 text-sm text-slate-300 cursor-pointer">
                       <input
@@ -514,6 +542,7 @@ function CronJobsSection({ jobs, setJobs, showCreateForm, setShowCreateForm, set
               getScheduleIcon={getScheduleIcon}
               onExpand={() => handleExpand(job.id)}
               onRunNow={() => handleRunNow(job.id)}
+              onReplay={(runId) => handleReplay(job.id, runId)}
               onTogglePause={() => handleTogglePause(job)}
               onDelete={() => handleDelete(job.id, job.name)}
             />
@@ -546,11 +575,12 @@ interface JobCardProps {
   getScheduleIcon: (type: string) => React.ReactNode;
   onExpand: () => void;
   onRunNow: () => void;
+  onReplay: (runId: number) => void;
   onTogglePause: () => void;
   onDelete: () => void;
 }
 
-function JobCard({ job, expanded, runs, getScheduleDisplay, getScheduleIcon, onExpand, onRunNow, onTogglePause, onDelete }: JobCardProps) {
+function JobCard({ job, expanded, runs, getScheduleDisplay, getScheduleIcon, onExpand, onRunNow, onReplay, onTogglePause, onDelete }: JobCardProps) {
   const hasErrors = (job.error_count || 0) > 0;
   const isInBackoff = hasErrors && job.status === 'active' && job.last_error;
 
@@ -630,6 +660,11 @@ function JobCard({ job, expanded, runs, getScheduleDisplay, getScheduleIcon, onE
                     {job.error_count} error{job.error_count !== 1 ? 's' : ''}
                   </span>
                 )}
+                {job.consecutive_failures > 0 && job.alert_after_failures && (
+                  <span className="text-yellow-400/80" title="Consecutive failures / alert threshold">
+                    {job.consecutive_failures}/{job.alert_after_failures} failures in a row
+                  </span>
+                )}
                 {job.session_mode === 'isolated' && (
                   <span className="text-slate-600">isolated</span>
                 )}
@@ -732,6 +767,53 @@ function JobCard({ job, expanded, runs, getScheduleDisplay, getScheduleIcon, onE
                       {run.success && !run.error && (
                         <span className="text-xs text-green-400/60 flex-1">Success</span>
                       )}
+                      {run.delivery_status && (
+                        <span
+                          className={`text-xs shrink-0 ${
+                            run.delivery_status === 'delivered'
+                              ? 'text-green-400/60'
+                              : run.delivery_status === 'failed'
+                                ? 'text-red-400/80'
+                                : 'text-slate-500'
+                          }`}
+                          title={run.delivery_error}
+                        >
+                          {run.delivery_status === 'delivered'
+                            ? 'Delivered'
+                            : run.delivery_status === 'failed'
+                              ? 'Delivery failed'
+                              : 'Not delivered'}
+                        </span>
+                      )}
+                      {(run.tool_calls != null || run.tokens_used != null) && (
+                        <span className="text-xs text-slate-500 shrink-0" title="Tool calls · estimated tokens">
+                          {run.tool_calls ?? 0} tools · {(run.tokens_used ?? 0).toLocaleString()} tok
+                        </span>
+                      )}
+                      {!!run.x402_spend_usdc && (
+                        <span className="text-xs text-amber-400/80 shrink-0" title="x402 spend">
+                          ${run.x402_spend_usdc.toFixed(4)}
+                        </span>
+                      )}
+                      {run.replay_of != null && (
+                        <span className="text-[10px] text-slate-500 bg-slate-800 px-1.5 py-0.5 rounded shrink-0">replay</span>
+                      )}
+                      {run.session_id != null && (
+                        <Link
+                          to={`/sessions/${run.session_id}`}
+                          title="Open session"
+                          className="p-1 text-slate-500 hover:text-white rounded transition-colors shrink-0"
+                        >
+                          <ExternalLink className="w-3.5 h-3.5" />
+                        </Link>
+                      )}
+                      <button
+                        onClick={() => onReplay(run.id)}
+                        title="Re-run with the same inputs"
+                        className="p-1 text-slate-500 hover:text-green-400 rounded transition-colors shrink-0"
+                      >
+                        <RotateCcw className="w-3.5 h-3.5" />
+                      </button>
                     </div>
                   ))}
                 </div>
@@ -33,12 +33,13 @@ import Input from '@/components/ui/Input';
 
 // ── Kanban Types ──────────────────────────────────────────────────────────────
 
-type KanbanStatus = 'ready' | 'in_progress' | 'complete';
+type KanbanStatus = 'ready' | 'in_progress' | 'complete' | 'failed';
 
 const COLUMNS: { status: KanbanStatus; label: string; color: string; accent: string }[] = [
   { status: 'ready', label: 'To-do', color: 'text-slate-400', accent: 'bg-slate-500' },
   { status: 'in_progress', label: 'In Progress', color: 'text-amber-400', accent: 'bg-amber-500' },
   { status: 'complete', label: 'Completed', color: 'text-emerald-400', accent: 'bg-emerald-500' },
+  { status: 'failed', label: 'Failed', color: 'text-red-400', accent: 'bg-red-500' },
 ];
 
 const PRIORITY_LABELS: Record<number, { label: string; class: string }> = {
@@ -507,7 +508,7 @@ export default function Workstream() {
 
       {/* ─── Kanban Board (bottom ~45%) ──────────────────────────────────── */}
       <div className="flex-[2] min-h-0 flex flex-col">
-        <div className="grid grid-cols-1 md:grid-cols-3 gap-3 flex-1 min-h-0">
+        <div className="grid grid-cols-1 md:grid-cols-4 gap-3 flex-1 min-h-0">
           {COLUMNS.map((col) => (
             <div
               key={col.status}
@@ -542,6 +543,7 @@ export default function Workstream() {
                     item.priority === 1 ? 'border-l-amber-500' :
                     col.status === 'complete' ? 'border-l-emerald-500/50' :
                     col.status === 'in_progress' ? 'border-l-amber-500/50' :
+                    col.status === 'failed' ? 'border-l-red-500/50' :
                     'border-l-slate-600';
                   return (
                     <div
@@ -569,6 +571,11 @@ export default function Workstream() {
                           {item.description && (
                             <p className="text-xs text-slate-500 line-clamp-2">{item.description}</p>
                           )}
+                          {item.blocked_by.length > 0 && (
+                            <div className="mt-1 text-[10px] text-slate-500">
+                              Blocked by {item.blocked_by.map((id) => `#${id}`).join(', ')}
+                            </div>
+                          )}
                           {item.session_id && (
                             <div className="mt-1.5 flex items-center gap-1 text-[10px] text-slate-600">
                               <ExternalLink className="w-3 h-3" />
@@ -656,6 +663,7 @@ export default function Workstream() {
               <span className={`text-xs px-2 py-1 rounded-full font-medium ${
                 detailItem.status === 'ready' ? 'bg-blue-500/20 text-blue-400' :
                 detailItem.status === 'in_progress' ? 'bg-yellow-500/20 text-yellow-400' :
+                detailItem.status === 'failed' ? 'bg-red-500/20 text-red-400' :
                 'bg-green-500/20 text-green-400'
               }`}>
                 {detailItem.status === 'in_progress' ? 'In Progress' :